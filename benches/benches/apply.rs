@@ -0,0 +1,37 @@
+//! Apply time for the synthetic workspace's plan: precondition checks, op
+//! execution, and patch rendering in the edit engine. Runs as a dry run so
+//! every iteration starts from the same files.
+
+use buildfix_benches::{DEPS_PER_MEMBER, MEMBERS, NoGit, SyntheticWorkspace, tool_info};
+use buildfix_core::adapters::FsReceiptSource;
+use buildfix_core::pipeline::run_plan;
+use buildfix_edit::{ApplyOptions, apply_plan};
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::time::Duration;
+
+fn apply(c: &mut Criterion) {
+    let ws = SyntheticWorkspace::generate(MEMBERS, DEPS_PER_MEMBER).expect("generate workspace");
+    let receipts = FsReceiptSource::new(ws.artifacts_dir());
+    let plan = run_plan(&ws.plan_settings(), &receipts, &NoGit, tool_info())
+        .expect("plan")
+        .plan;
+
+    let mut group = c.benchmark_group("apply");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(12));
+    for jobs in [1, 0] {
+        let opts = ApplyOptions {
+            dry_run: true,
+            jobs,
+            ..ApplyOptions::default()
+        };
+        let label = if jobs == 0 { "all_cores" } else { "one_job" };
+        group.bench_function(format!("{}_ops/{}", plan.ops.len(), label), |b| {
+            b.iter(|| apply_plan(ws.root(), &plan, tool_info(), &opts).expect("apply"))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, apply);
+criterion_main!(benches);
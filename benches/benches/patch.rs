@@ -0,0 +1,53 @@
+//! Patch rendering alone: one modified manifest per synthetic member, with
+//! a version added to each path dependency.
+
+use buildfix_benches::{DEPS_PER_MEMBER, MEMBERS};
+use buildfix_edit::{FileChange, PatchOptions, render_file_changes};
+use camino::Utf8PathBuf;
+use criterion::{Criterion, Throughput, criterion_group, criterion_main};
+
+fn manifests() -> Vec<(Utf8PathBuf, String, String)> {
+    (0..MEMBERS)
+        .map(|m| {
+            let mut old = format!(
+                "[package]\nname = \"c{m:05}\"\nversion = \"0.1.0\"\nedition = \"2021\"\n\n[dependencies]\n"
+            );
+            let mut new = old.clone();
+            for d in 1..=DEPS_PER_MEMBER {
+                let dep = format!("c{:05}", (m + d) % MEMBERS);
+                old.push_str(&format!("{dep} = {{ path = \"../{dep}\" }}\n"));
+                new.push_str(&format!("{dep} = {{ path = \"../{dep}\", version = \"0.1.0\" }}\n"));
+            }
+            (Utf8PathBuf::from(format!("crates/c{m:05}/Cargo.toml")), old, new)
+        })
+        .collect()
+}
+
+fn patch(c: &mut Criterion) {
+    let files = manifests();
+    let changes: Vec<FileChange<'_>> = files
+        .iter()
+        .map(|(path, old, new)| FileChange::Modify {
+            path,
+            old: old.as_bytes(),
+            new: new.as_bytes(),
+        })
+        .collect();
+
+    let mut group = c.benchmark_group("patch");
+    group.throughput(Throughput::Elements(changes.len() as u64));
+    for jobs in [1, 0] {
+        let opts = PatchOptions {
+            binary: false,
+            jobs,
+        };
+        let label = if jobs == 0 { "all_cores" } else { "one_job" };
+        group.bench_function(format!("{}_files/{}", changes.len(), label), |b| {
+            b.iter(|| render_file_changes(&changes, &opts))
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, patch);
+criterion_main!(benches);
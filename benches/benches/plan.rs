@@ -0,0 +1,34 @@
+//! Plan time for the synthetic workspace: receipt loading, fixer planning,
+//! preconditions, and the patch preview, as `buildfix plan` runs them.
+
+use buildfix_benches::{DEPS_PER_MEMBER, MEMBERS, NoGit, SyntheticWorkspace, tool_info};
+use buildfix_core::adapters::FsReceiptSource;
+use buildfix_core::pipeline::run_plan;
+use criterion::{Criterion, criterion_group, criterion_main};
+use std::time::Duration;
+
+fn plan(c: &mut Criterion) {
+    let ws = SyntheticWorkspace::generate(MEMBERS, DEPS_PER_MEMBER).expect("generate workspace");
+    let settings = ws.plan_settings();
+
+    let mut group = c.benchmark_group("plan");
+    group.sample_size(10);
+    group.measurement_time(Duration::from_secs(30));
+    for jobs in [1, 0] {
+        let settings = buildfix_core::settings::PlanSettings {
+            jobs,
+            ..settings.clone()
+        };
+        let label = if jobs == 0 { "all_cores" } else { "one_job" };
+        group.bench_function(format!("{}_findings/{}", ws.findings(), label), |b| {
+            b.iter(|| {
+                let receipts = FsReceiptSource::new(ws.artifacts_dir());
+                run_plan(&settings, &receipts, &NoGit, tool_info()).expect("plan")
+            })
+        });
+    }
+    group.finish();
+}
+
+criterion_group!(benches, plan);
+criterion_main!(benches);
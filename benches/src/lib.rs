@@ -0,0 +1,147 @@
+//! Synthetic workspaces for the buildfix benchmarks.
+//!
+//! [`SyntheticWorkspace::generate`] writes a Cargo workspace of `members`
+//! crates, each with `deps_per_member` path dependencies that lack a
+//! `version`, plus a depguard receipt reporting every one of them. The
+//! default bench size (1k members, 10 deps each) gives 10k findings and 10k
+//! planned ops across 1k manifests.
+
+use anyhow::Context;
+use buildfix_core::ports::GitPort;
+use buildfix_core::settings::PlanSettings;
+use buildfix_types::receipt::ToolInfo;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::fmt::Write as _;
+use std::fs;
+use tempfile::TempDir;
+
+/// Members in the benchmark workspace.
+pub const MEMBERS: usize = 1_000;
+/// Path dependencies (and so findings) per member.
+pub const DEPS_PER_MEMBER: usize = 10;
+
+/// A generated workspace on disk, removed when dropped.
+pub struct SyntheticWorkspace {
+    _dir: TempDir,
+    root: Utf8PathBuf,
+    findings: usize,
+}
+
+impl SyntheticWorkspace {
+    /// Write a workspace of `members` crates, each depending by path on the
+    /// next `deps_per_member` crates (wrapping around), and a depguard
+    /// receipt with one `missing_version` finding per dependency.
+    pub fn generate(members: usize, deps_per_member: usize) -> anyhow::Result<Self> {
+        let dir = tempfile::tempdir().context("create temp dir")?;
+        let root = Utf8PathBuf::from_path_buf(dir.path().to_path_buf())
+            .map_err(|p| anyhow::anyhow!("non-UTF-8 temp dir {}", p.display()))?;
+
+        fs::write(
+            root.join("Cargo.toml"),
+            "[workspace]\nresolver = \"2\"\nmembers = [\"crates/*\"]\n",
+        )?;
+
+        let mut findings = Vec::with_capacity(members * deps_per_member);
+        for m in 0..members {
+            let name = member_name(m);
+            let mut manifest = format!(
+                "[package]\nname = \"{name}\"\nversion = \"0.1.{m}\"\nedition = \"2021\"\n\n[dependencies]\n"
+            );
+            for d in 1..=deps_per_member.min(members.saturating_sub(1)) {
+                let dep = member_name((m + d) % members);
+                // Line of this dependency: six header lines, then one per dep.
+                let line = 6 + d;
+                writeln!(manifest, "{dep} = {{ path = \"../{dep}\" }}")?;
+                findings.push(serde_json::json!({
+                    "severity": "error",
+                    "check_id": "deps.path_requires_version",
+                    "code": "missing_version",
+                    "message": "path dependency missing version",
+                    "location": { "path": format!("crates/{name}/Cargo.toml"), "line": line, "column": 1 },
+                    "data": {
+                        "dep": dep,
+                        "dep_path": format!("../{dep}"),
+                        "toml_path": ["dependencies", dep]
+                    }
+                }));
+            }
+            let dir = root.join("crates").join(&name);
+            fs::create_dir_all(dir.join("src"))?;
+            fs::write(dir.join("Cargo.toml"), manifest)?;
+            fs::write(dir.join("src/lib.rs"), "")?;
+        }
+
+        let count = findings.len();
+        let receipt = serde_json::json!({
+            "schema": "depguard.report.v1",
+            "tool": { "name": "depguard", "version": "0.0.0" },
+            "verdict": {
+                "status": "fail",
+                "counts": { "findings": count, "errors": count, "warnings": 0 }
+            },
+            "findings": findings,
+        });
+        let receipt_dir = root.join("artifacts").join("depguard");
+        fs::create_dir_all(&receipt_dir)?;
+        fs::write(
+            receipt_dir.join("report.json"),
+            serde_json::to_vec(&receipt)?,
+        )?;
+
+        Ok(Self {
+            _dir: dir,
+            root,
+            findings: count,
+        })
+    }
+
+    pub fn root(&self) -> &Utf8Path {
+        &self.root
+    }
+
+    pub fn artifacts_dir(&self) -> Utf8PathBuf {
+        self.root.join("artifacts")
+    }
+
+    /// Findings in the generated receipt.
+    pub fn findings(&self) -> usize {
+        self.findings
+    }
+
+    /// Plan settings for this workspace; everything else is the default.
+    pub fn plan_settings(&self) -> PlanSettings {
+        PlanSettings {
+            repo_root: self.root.clone(),
+            artifacts_dir: self.artifacts_dir(),
+            out_dir: self.root.join("artifacts").join("buildfix"),
+            ..PlanSettings::default()
+        }
+    }
+}
+
+fn member_name(i: usize) -> String {
+    format!("c{i:05}")
+}
+
+/// A git port for a tree that is not a repository, so benchmarks do not
+/// measure `git` process start-up.
+pub struct NoGit;
+
+impl GitPort for NoGit {
+    fn head_sha(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn is_dirty(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        Ok(Some(false))
+    }
+}
+
+pub fn tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "buildfix".to_string(),
+        version: Some("bench".to_string()),
+        repo: None,
+        commit: None,
+    }
+}
@@ -0,0 +1,19 @@
+//! Keeps the benchmark fixtures honest: a small synthetic workspace must plan
+//! one op per finding, or the benches would be timing an empty plan.
+
+use buildfix_benches::{NoGit, SyntheticWorkspace, tool_info};
+use buildfix_core::adapters::FsReceiptSource;
+use buildfix_core::pipeline::run_plan;
+
+#[test]
+fn synthetic_workspace_plans_one_op_per_finding() {
+    let ws = SyntheticWorkspace::generate(20, 3).unwrap();
+    assert_eq!(ws.findings(), 60);
+
+    let receipts = FsReceiptSource::new(ws.artifacts_dir());
+    let outcome = run_plan(&ws.plan_settings(), &receipts, &NoGit, tool_info()).unwrap();
+
+    assert_eq!(outcome.plan.ops.len(), 60);
+    assert!(outcome.plan.ops.iter().all(|op| !op.blocked));
+    assert_eq!(outcome.patch.matches("diff --git").count(), 20);
+}
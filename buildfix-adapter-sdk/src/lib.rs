@@ -104,6 +104,18 @@ pub trait Adapter: Send + Sync {
     fn sensor_id(&self) -> &str;
 
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError>;
+
+    /// Convert sensor output that is already in memory, e.g. an archive entry.
+    ///
+    /// Adapters that only know how to read from a path keep the default, which
+    /// rejects the input.
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        let _ = content;
+        Err(AdapterError::InvalidFormat(format!(
+            "{} adapter cannot parse in-memory output",
+            self.sensor_id()
+        )))
+    }
 }
 
 impl<T: Adapter + ?Sized> Adapter for &T {
@@ -114,6 +126,10 @@ impl<T: Adapter + ?Sized> Adapter for &T {
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError> {
         (*self).load(path)
     }
+
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        (*self).parse(content)
+    }
 }
 
 impl<T: Adapter + ?Sized> Adapter for Box<T> {
@@ -124,6 +140,10 @@ impl<T: Adapter + ?Sized> Adapter for Box<T> {
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError> {
         (**self).load(path)
     }
+
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        (**self).parse(content)
+    }
 }
 
 /// Metadata trait for adapter self-description.
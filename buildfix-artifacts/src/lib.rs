@@ -1,7 +1,9 @@
 //! Artifact serialization and persistence for buildfix outputs.
 
 use anyhow::Context;
-use buildfix_render::{render_apply_md, render_comment_md, render_plan_md};
+use buildfix_render::{
+    render_apply_junit, render_gitlab_code_quality, render_plan_html, render_plan_junit,
+};
 use buildfix_types::apply::BuildfixApply;
 use buildfix_types::plan::BuildfixPlan;
 use buildfix_types::report::BuildfixReport;
@@ -10,11 +12,28 @@ use buildfix_types::wire::{PlanV1, ReportV1};
 use camino::Utf8Path;
 use std::collections::BTreeMap;
 use std::fs;
+use std::io;
+
+pub use buildfix_render::MarkdownTemplates;
 
 /// Filesystem-facing abstraction for artifact emission.
 pub trait ArtifactWriter {
     fn write_file(&self, path: &Utf8Path, contents: &[u8]) -> anyhow::Result<()>;
     fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()>;
+
+    /// Write `path` from `write`, which streams the contents into the sink it
+    /// is handed. `patch.diff` is written this way so it is never copied into
+    /// a second buffer. The default buffers and calls
+    /// [`ArtifactWriter::write_file`].
+    fn write_file_with(
+        &self,
+        path: &Utf8Path,
+        write: &mut dyn FnMut(&mut dyn io::Write) -> io::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        write(&mut buf).with_context(|| format!("write {}", path))?;
+        self.write_file(path, &buf)
+    }
 }
 
 /// Standard filesystem implementation.
@@ -33,12 +52,29 @@ impl ArtifactWriter for FsArtifactWriter {
     fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()> {
         fs::create_dir_all(path).with_context(|| format!("create dir {}", path))
     }
+
+    fn write_file_with(
+        &self,
+        path: &Utf8Path,
+        write: &mut dyn FnMut(&mut dyn io::Write) -> io::Result<()>,
+    ) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            fs::create_dir_all(parent)
+                .with_context(|| format!("create parent dir for {}", path))?;
+        }
+        let file = fs::File::create(path).with_context(|| format!("write {}", path))?;
+        let mut out = io::BufWriter::new(file);
+        write(&mut out)
+            .and_then(|()| io::Write::flush(&mut out))
+            .with_context(|| format!("write {}", path))
+    }
 }
 
 fn render_plan_report(
     plan: &BuildfixPlan,
     report: &BuildfixReport,
     patch: &str,
+    templates: &MarkdownTemplates,
     out_dir: &Utf8Path,
 ) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
     let plan_wire = PlanV1::try_from(plan).context("convert plan to wire")?;
@@ -60,15 +96,23 @@ fn render_plan_report(
     );
     files.insert(
         out_dir.join("plan.md").to_string(),
-        render_plan_md(plan).into_bytes(),
+        templates.render_plan_md(plan)?.into_bytes(),
     );
     files.insert(
         out_dir.join("comment.md").to_string(),
-        render_comment_md(plan).into_bytes(),
+        templates.render_comment_md(plan)?.into_bytes(),
+    );
+    files.insert(
+        out_dir.join("report.html").to_string(),
+        render_plan_html(plan, patch).into_bytes(),
     );
     files.insert(
-        out_dir.join("patch.diff").to_string(),
-        patch.as_bytes().to_vec(),
+        out_dir.join("gl-code-quality-report.json").to_string(),
+        render_gitlab_code_quality(plan).into_bytes(),
+    );
+    files.insert(
+        out_dir.join("plan.junit.xml").to_string(),
+        render_plan_junit(plan).into_bytes(),
     );
     files.insert(
         out_dir.join("report.json").to_string(),
@@ -86,8 +130,10 @@ fn render_plan_report(
 
 fn render_apply_report(
     apply: &BuildfixApply,
+    plan: &BuildfixPlan,
     report: &BuildfixReport,
     patch: &str,
+    templates: &MarkdownTemplates,
     out_dir: &Utf8Path,
 ) -> anyhow::Result<BTreeMap<String, Vec<u8>>> {
     let apply_wire =
@@ -110,11 +156,11 @@ fn render_apply_report(
     );
     files.insert(
         out_dir.join("apply.md").to_string(),
-        render_apply_md(apply).into_bytes(),
+        templates.render_apply_md(apply, plan, patch)?.into_bytes(),
     );
     files.insert(
-        out_dir.join("patch.diff").to_string(),
-        patch.as_bytes().to_vec(),
+        out_dir.join("apply.junit.xml").to_string(),
+        render_apply_junit(apply, plan).into_bytes(),
     );
     files.insert(
         out_dir.join("report.json").to_string(),
@@ -140,32 +186,53 @@ fn write_files<W: ArtifactWriter>(
     Ok(())
 }
 
-/// Emit all plan artifacts (plan.json, plan.md, comment.md, patch, report, extras).
+/// Stream `patch.diff` straight from `patch`; it can be the largest artifact
+/// by far, so it is not copied into the rendered file map.
+fn write_patch<W: ArtifactWriter>(
+    patch: &str,
+    out_dir: &Utf8Path,
+    writer: &W,
+) -> anyhow::Result<()> {
+    writer.write_file_with(&out_dir.join("patch.diff"), &mut |w| {
+        w.write_all(patch.as_bytes())
+    })
+}
+
+/// Emit all plan artifacts (plan.json, plan.md, comment.md, report.html,
+/// gl-code-quality-report.json, plan.junit.xml, patch, report, extras).
+/// `templates` override the markdown artifacts.
 pub fn write_plan_artifacts<W: ArtifactWriter>(
     plan: &BuildfixPlan,
     report: &BuildfixReport,
     patch: &str,
+    templates: &MarkdownTemplates,
     out_dir: &Utf8Path,
     writer: &W,
 ) -> anyhow::Result<()> {
     writer.create_dir_all(out_dir)?;
     writer.create_dir_all(&out_dir.join("extras"))?;
-    let files = render_plan_report(plan, report, patch, out_dir)?;
-    write_files(files, writer)
+    let files = render_plan_report(plan, report, patch, templates, out_dir)?;
+    write_files(files, writer)?;
+    write_patch(patch, out_dir, writer)
 }
 
-/// Emit all apply artifacts (apply.json, apply.md, patch, report, extras).
+/// Emit all apply artifacts (apply.json, apply.md, apply.junit.xml, patch,
+/// report, extras). `plan` is the plan that was applied; it groups the JUnit
+/// cases by fixer and is passed to an `apply.md` template.
 pub fn write_apply_artifacts<W: ArtifactWriter>(
     apply: &BuildfixApply,
+    plan: &BuildfixPlan,
     report: &BuildfixReport,
     patch: &str,
+    templates: &MarkdownTemplates,
     out_dir: &Utf8Path,
     writer: &W,
 ) -> anyhow::Result<()> {
     writer.create_dir_all(out_dir)?;
     writer.create_dir_all(&out_dir.join("extras"))?;
-    let files = render_apply_report(apply, report, patch, out_dir)?;
-    write_files(files, writer)
+    let files = render_apply_report(apply, plan, report, patch, templates, out_dir)?;
+    write_files(files, writer)?;
+    write_patch(patch, out_dir, writer)
 }
 
 #[cfg(test)]
@@ -652,6 +652,37 @@ fn test_fs_writer_with_spaces_in_path() {
     assert!(path.exists());
 }
 
+#[test]
+fn test_fs_writer_streams_contents_and_creates_parents() {
+    let writer = FsArtifactWriter;
+    let temp = tempfile::tempdir().unwrap();
+    let full = temp.path().join("out").join("patch.diff");
+    let path: &Utf8Path = Utf8Path::from_path(full.as_path()).unwrap();
+
+    writer
+        .write_file_with(path, &mut |w| {
+            w.write_all(b"diff --git a/x b/x\n")?;
+            w.write_all(b"+y\n")
+        })
+        .unwrap();
+
+    assert_eq!(std::fs::read(path).unwrap(), b"diff --git a/x b/x\n+y\n");
+}
+
+#[test]
+fn test_default_streamed_write_goes_through_write_file() {
+    let writer = MockArtifactWriter::new();
+
+    writer
+        .write_file_with(Utf8Path::new("patch.diff"), &mut |w| w.write_all(b"patch"))
+        .unwrap();
+
+    assert_eq!(
+        writer.get_file_content("patch.diff"),
+        Some(b"patch".to_vec())
+    );
+}
+
 // =============================================================================
 // Concurrency and State Tests
 // =============================================================================
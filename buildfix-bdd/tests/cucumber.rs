@@ -3351,7 +3351,7 @@ async fn each_catalog_has_explanation(world: &mut BuildfixWorld) {
     assert!(
         missing.is_empty(),
         "catalog entries missing from explain registry: {:?}\n\
-         Add corresponding FixExplanation entries to FIX_REGISTRY in explain.rs",
+         Fill in FixerMeta for the fixer and register it in builtin_fixers()",
         missing
     );
 }
@@ -0,0 +1,36 @@
+//! Embed the git commit the binary is built from as `BUILDFIX_GIT_COMMIT`,
+//! recorded as `tool.commit` in artifacts. A value already set in the
+//! environment wins; builds outside a git checkout (e.g. from crates.io)
+//! leave it unset.
+
+use std::path::Path;
+use std::process::Command;
+
+fn main() {
+    println!("cargo:rerun-if-env-changed=BUILDFIX_GIT_COMMIT");
+    if std::env::var_os("BUILDFIX_GIT_COMMIT").is_some() {
+        return;
+    }
+
+    let git = |args: &[&str]| -> Option<String> {
+        let output = Command::new("git").args(args).output().ok()?;
+        let out = String::from_utf8(output.stdout).ok()?.trim().to_string();
+        (output.status.success() && !out.is_empty()).then_some(out)
+    };
+    let Some(commit) = git(&["rev-parse", "HEAD"]) else {
+        return;
+    };
+    println!("cargo:rustc-env=BUILDFIX_GIT_COMMIT={}", commit);
+
+    // Rebuild when HEAD moves: HEAD itself on a branch switch, the ref it
+    // points at on a commit. `--git-path` resolves both inside worktrees.
+    let mut watched = vec!["HEAD".to_string()];
+    watched.extend(git(&["symbolic-ref", "-q", "HEAD"]));
+    for name in watched {
+        if let Some(path) = git(&["rev-parse", "--git-path", &name])
+            && Path::new(&path).exists()
+        {
+            println!("cargo:rerun-if-changed={}", path);
+        }
+    }
+}
@@ -0,0 +1,35 @@
+//! `cargo buildfix ...` entry point.
+//!
+//! Cargo runs `cargo-buildfix buildfix <args>`. This drops the harness
+//! argument and runs the `buildfix` binary installed alongside, which then
+//! defaults `--repo-root` to the enclosing workspace.
+
+use std::ffi::OsString;
+use std::process::{Command, ExitCode};
+
+fn main() -> ExitCode {
+    let mut args: Vec<OsString> = std::env::args_os().skip(1).collect();
+    if args.first().is_some_and(|a| a == "buildfix") {
+        args.remove(0);
+    }
+
+    let sibling = std::env::current_exe()
+        .ok()
+        .map(|exe| exe.with_file_name(format!("buildfix{}", std::env::consts::EXE_SUFFIX)));
+    let program = match sibling {
+        Some(path) if path.is_file() => path.into_os_string(),
+        _ => OsString::from("buildfix"),
+    };
+
+    match Command::new(&program)
+        .args(args)
+        .env("BUILDFIX_CARGO_SUBCOMMAND", "1")
+        .status()
+    {
+        Ok(status) => ExitCode::from(status.code().unwrap_or(1).clamp(0, 255) as u8),
+        Err(e) => {
+            eprintln!("cargo-buildfix: failed to run {:?}: {}", program, e);
+            ExitCode::from(1)
+        }
+    }
+}
@@ -0,0 +1,63 @@
+//! Argument handling when buildfix runs as `cargo buildfix`.
+//!
+//! Cargo extensions conventionally act on the workspace around the current
+//! directory, so subcommands that take `--repo-root` default it to the
+//! workspace root from `cargo locate-project` instead of `.`.
+
+use camino::Utf8PathBuf;
+use clap::Command;
+use std::ffi::OsString;
+use std::process;
+
+/// Set by `cargo-buildfix` on the `buildfix` process it spawns.
+pub const ENV: &str = "BUILDFIX_CARGO_SUBCOMMAND";
+
+pub fn is_cargo_subcommand() -> bool {
+    std::env::var_os(ENV).is_some()
+}
+
+/// Insert `--repo-root <workspace root>` after the subcommand name when the
+/// subcommand accepts it and the caller did not pass one. Arguments are
+/// returned unchanged when no workspace is found.
+pub fn with_workspace_root(cli: &Command, mut args: Vec<OsString>) -> Vec<OsString> {
+    let Some(pos) = args
+        .iter()
+        .skip(1)
+        .position(|a| !a.to_string_lossy().starts_with('-'))
+        .map(|i| i + 1)
+    else {
+        return args;
+    };
+    let name = args[pos].to_string_lossy().into_owned();
+    let takes_repo_root = cli.find_subcommand(&name).is_some_and(|sub| {
+        sub.get_arguments()
+            .any(|a| a.get_long() == Some("repo-root"))
+    });
+    let given = args
+        .iter()
+        .any(|a| a == "--repo-root" || a.to_string_lossy().starts_with("--repo-root="));
+    if !takes_repo_root || given {
+        return args;
+    }
+    if let Some(root) = locate_workspace_root() {
+        args.splice(
+            pos + 1..pos + 1,
+            [OsString::from("--repo-root"), root.into()],
+        );
+    }
+    args
+}
+
+/// Directory of the workspace `Cargo.toml` enclosing the current directory.
+pub fn locate_workspace_root() -> Option<Utf8PathBuf> {
+    let cargo = std::env::var_os("CARGO").unwrap_or_else(|| "cargo".into());
+    let output = process::Command::new(cargo)
+        .args(["locate-project", "--workspace", "--message-format", "plain"])
+        .output()
+        .ok()?;
+    if !output.status.success() {
+        return None;
+    }
+    let manifest = Utf8PathBuf::from(String::from_utf8(output.stdout).ok()?.trim());
+    manifest.parent().map(Utf8PathBuf::from)
+}
@@ -3,11 +3,20 @@
 //! Discovers and loads `buildfix.toml` from the repository root.
 //! Merges config file settings with CLI arguments (CLI takes precedence).
 
+use crate::extends::{self, ExtendsConfig};
 use anyhow::Context;
+use buildfix_cli::explain::{list_fix_keys, lookup_fix};
+use buildfix_core::settings::FixerSettings;
+use buildfix_receipts::{ReceiptDiscovery, SensorIdRule, TrustMode, TrustPolicy};
+use buildfix_render::MarkdownTemplates;
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{DirtyScope, PathPolicy, PreconditionMode, ScanPolicy};
+use buildfix_types::receipt::Severity;
+use buildfix_types::telemetry;
 use camino::{Utf8Path, Utf8PathBuf};
 use fs_err as fs;
 use serde::Deserialize;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
 use tracing::debug;
 
 /// The config file name to search for.
@@ -17,9 +26,19 @@ pub const CONFIG_FILE_NAME: &str = "buildfix.toml";
 #[derive(Debug, Clone, Default, Deserialize)]
 #[serde(default)]
 pub struct BuildfixConfig {
+    /// Base config merged beneath this one: a directory, a file or an
+    /// `https://` URL.
+    pub extends: Option<ExtendsConfig>,
+
     /// Policy settings (allow/deny lists, safety, caps).
     pub policy: PolicyConfig,
 
+    /// Paths planning never targets.
+    pub scan: ScanPolicy,
+
+    /// Precondition settings.
+    pub preconditions: PreconditionsConfig,
+
     /// Backup settings.
     pub backups: BackupsConfig,
 
@@ -28,6 +47,51 @@ pub struct BuildfixConfig {
 
     /// Parameters for unsafe fixes.
     pub params: HashMap<String, String>,
+
+    /// Receipt loading settings.
+    pub receipts: ReceiptsConfig,
+
+    /// Markdown rendering settings.
+    pub render: RenderConfig,
+
+    /// `buildfix gc` retention settings.
+    pub gc: GcConfig,
+
+    /// `buildfix pr` settings.
+    pub pr: PrConfig,
+
+    /// Which forge `buildfix pr` talks to, and how.
+    pub forge: ForgeConfig,
+
+    /// Per-fixer overrides, keyed by fix key (`msrv`) or fix id
+    /// (`cargo.normalize_rust_version`).
+    pub fixers: BTreeMap<String, FixerConfig>,
+}
+
+impl BuildfixConfig {
+    /// `[fixers.*]` keyed by fix id. Fails on names no enabled fixer answers to.
+    pub fn fixer_settings(&self) -> anyhow::Result<BTreeMap<String, FixerSettings>> {
+        let mut out = BTreeMap::new();
+        for (name, fixer) in &self.fixers {
+            let Some(fix) = lookup_fix(name) else {
+                anyhow::bail!(
+                    "[fixers.{}]: unknown fix; expected one of: {}",
+                    name,
+                    list_fix_keys().join(", ")
+                );
+            };
+            let settings = FixerSettings {
+                enabled: fixer.enabled,
+                safety: fixer.safety,
+                force: fixer.force,
+                params: fixer.params.clone(),
+            };
+            if out.insert(fix.fix_id.to_string(), settings).is_some() {
+                anyhow::bail!("[fixers.{}]: {} is configured twice", name, fix.key);
+            }
+        }
+        Ok(out)
+    }
 }
 
 /// Policy section of the config.
@@ -50,6 +114,10 @@ pub struct PolicyConfig {
     /// Allow applying fixes when working directory is dirty.
     pub allow_dirty: bool,
 
+    /// Which uncommitted changes count as dirty: `repo` (any) or `plan`
+    /// (only files the plan targets).
+    pub dirty_scope: DirtyScope,
+
     /// Maximum number of operations allowed.
     pub max_ops: Option<u64>,
 
@@ -58,6 +126,62 @@ pub struct PolicyConfig {
 
     /// Maximum size of the patch in bytes.
     pub max_patch_bytes: Option<u64>,
+
+    /// Hours a plan stays applicable; apply blocks it with `expired_plan`
+    /// after that. Unset plans never expire.
+    pub plan_ttl_hours: Option<u64>,
+
+    /// Ignore findings below this severity (`info`, `warn` or `error`).
+    pub min_severity: Option<Severity>,
+
+    /// Only plan from findings of these sensors (sensor id or tool name).
+    /// Empty means every sensor.
+    pub sensors: Vec<String>,
+
+    /// Ignore findings with these check ids.
+    pub exclude_check_ids: Vec<String>,
+
+    /// `[[policy.paths]]`: allow/deny and safety limits for ops under a
+    /// target path glob.
+    pub paths: Vec<PathPolicy>,
+}
+
+/// One `[fixers.<fix_key>]` section.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct FixerConfig {
+    /// `false` turns the fixer off.
+    pub enabled: bool,
+
+    /// Safety class for the fixer's ops. Stricter than the fixer's own is
+    /// always accepted; less strict needs `force = true`.
+    pub safety: Option<SafetyClass>,
+
+    /// Allow `safety` to loosen the fixer's class.
+    pub force: bool,
+
+    /// Params for this fixer's ops; override `[params]`.
+    pub params: HashMap<String, String>,
+}
+
+impl Default for FixerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            safety: None,
+            force: false,
+            params: HashMap::new(),
+        }
+    }
+}
+
+/// Preconditions section of the config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PreconditionsConfig {
+    /// `exact` (any byte change blocks) or `semantic` (TOML files whose
+    /// parsed structure is unchanged still apply).
+    pub mode: PreconditionMode,
 }
 
 /// Backups section of the config.
@@ -69,6 +193,9 @@ pub struct BackupsConfig {
 
     /// Suffix for backup files.
     pub suffix: String,
+
+    /// Number of backup runs to retain; older runs are pruned after apply.
+    pub keep_last: Option<usize>,
 }
 
 impl Default for BackupsConfig {
@@ -76,6 +203,7 @@ impl Default for BackupsConfig {
         Self {
             enabled: true,
             suffix: ".buildfix.bak".to_string(),
+            keep_last: None,
         }
     }
 }
@@ -89,6 +217,201 @@ pub struct CommitConfig {
 
     /// Optional commit message override.
     pub message: Option<String>,
+
+    /// Branch template to move applied changes onto (see `--branch`).
+    pub branch: Option<String>,
+
+    /// Remote to push that branch to after committing (see `--push`).
+    pub push: Option<String>,
+}
+
+/// `[pr]` section: defaults for `buildfix pr`.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PrConfig {
+    /// Labels added to every pull request.
+    pub labels: Vec<String>,
+
+    /// Branch to merge into instead of the repository's default branch.
+    pub base: Option<String>,
+}
+
+/// `[forge]` section: the hosting service `buildfix pr` opens pull (merge)
+/// requests on. Unset keys fall back to the provider's CI variables and the
+/// `origin` remote.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ForgeConfig {
+    /// `github`, `gitlab` or `bitbucket`; detected from the origin host if unset.
+    pub provider: Option<ForgeProvider>,
+
+    /// REST API root, for self-hosted instances.
+    pub api_url: Option<String>,
+
+    /// Repository path (`owner/name`, or a GitLab project path).
+    pub repo: Option<String>,
+
+    /// Environment variable holding the API token.
+    pub token_env: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum ForgeProvider {
+    Github,
+    Gitlab,
+    Bitbucket,
+}
+
+#[cfg(feature = "forge")]
+impl From<ForgeProvider> for buildfix_core::adapters::ForgeKind {
+    fn from(provider: ForgeProvider) -> Self {
+        match provider {
+            ForgeProvider::Github => Self::Github,
+            ForgeProvider::Gitlab => Self::Gitlab,
+            ForgeProvider::Bitbucket => Self::Bitbucket,
+        }
+    }
+}
+
+/// `[gc]` section: what `buildfix gc` removes besides backup runs beyond
+/// `[backups] keep_last`.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct GcConfig {
+    /// Remove backup runs older than this many days.
+    pub backup_max_age_days: Option<u64>,
+
+    /// Remove cache files not rewritten for this many days.
+    pub cache_max_age_days: Option<u64>,
+}
+
+impl Default for GcConfig {
+    fn default() -> Self {
+        Self {
+            backup_max_age_days: None,
+            cache_max_age_days: Some(14),
+        }
+    }
+}
+
+/// Receipts section of the config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct ReceiptsConfig {
+    /// Globs for receipt files, relative to the repo root. Replaces the
+    /// default `<artifacts>/<sensor>/report.json` layout when non-empty.
+    pub patterns: Vec<String>,
+
+    /// How to name the sensor of a discovered receipt: `parent_dir`
+    /// (default) or `file_stem`.
+    pub sensor_id: SensorIdRule,
+
+    /// Findings kept per receipt (default 100000). Larger receipts are
+    /// truncated while parsing and marked partial.
+    pub max_findings: Option<usize>,
+
+    /// Verify detached minisign signatures next to receipts.
+    pub trust: Option<TrustConfig>,
+}
+
+impl ReceiptsConfig {
+    /// Discovery settings with patterns resolved against `repo_root`.
+    pub fn discovery(&self, repo_root: &Utf8Path) -> ReceiptDiscovery {
+        let patterns = self
+            .patterns
+            .iter()
+            .map(|pattern| repo_root.join(pattern).into_string())
+            .collect();
+        let discovery = ReceiptDiscovery::new(patterns).with_sensor_id(self.sensor_id);
+        match self.max_findings {
+            Some(max) => discovery.with_max_findings(max),
+            None => discovery,
+        }
+    }
+}
+
+/// `[receipts.trust]`: keys receipts must be signed with.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct TrustConfig {
+    /// `permissive` (default) loads unsigned receipts; `strict` rejects them.
+    pub mode: TrustMode,
+
+    /// Minisign public keys (base64).
+    pub public_keys: Vec<String>,
+
+    /// Minisign public key files, relative to the repo root.
+    pub public_key_files: Vec<Utf8PathBuf>,
+}
+
+impl TrustConfig {
+    /// Build the trust policy, resolving key files against `repo_root`.
+    pub fn to_policy(&self, repo_root: &Utf8Path) -> anyhow::Result<TrustPolicy> {
+        let mut policy = TrustPolicy::new(self.mode);
+        for key in &self.public_keys {
+            policy = policy.with_public_key(key)?;
+        }
+        for file in &self.public_key_files {
+            policy = policy.with_public_key_file(&repo_root.join(file))?;
+        }
+        if policy.key_count() == 0 {
+            anyhow::bail!("[receipts.trust] needs at least one public key");
+        }
+        Ok(policy)
+    }
+}
+
+/// Render section of the config.
+#[derive(Debug, Clone, Default, Deserialize)]
+#[serde(default)]
+pub struct RenderConfig {
+    /// Directory of MiniJinja templates, relative to the repo root. A
+    /// `plan.md`, `apply.md` or `comment.md` in it replaces the builtin
+    /// markdown for that artifact.
+    pub templates_dir: Option<Utf8PathBuf>,
+
+    /// Size limit for the builtin `comment.md` in bytes (default 65536, the
+    /// GitHub comment limit). Longer op listings are truncated.
+    pub comment_max_bytes: Option<usize>,
+}
+
+impl RenderConfig {
+    /// Read the template overrides, resolving `templates_dir` against
+    /// `repo_root`.
+    pub fn templates(&self, repo_root: &Utf8Path) -> anyhow::Result<MarkdownTemplates> {
+        let mut templates = MarkdownTemplates::default();
+        if let Some(max) = self.comment_max_bytes {
+            templates.comment_options.max_bytes = max;
+        }
+        let Some(dir) = &self.templates_dir else {
+            return Ok(templates);
+        };
+        let dir = repo_root.join(dir);
+        if !dir.is_dir() {
+            anyhow::bail!("templates_dir {} is not a directory", dir);
+        }
+        let read = |name: &str| -> anyhow::Result<Option<String>> {
+            let path = dir.join(name);
+            if !path.is_file() {
+                return Ok(None);
+            }
+            debug!(
+                event = telemetry::CONFIG_TEMPLATE,
+                file = %path,
+                "using {} template {}",
+                name,
+                path
+            );
+            fs::read_to_string(&path)
+                .map(Some)
+                .with_context(|| format!("read template {}", path))
+        };
+        templates.plan = read("plan.md")?;
+        templates.apply = read("apply.md")?;
+        templates.comment = read("comment.md")?;
+        Ok(templates)
+    }
 }
 
 /// Discover the buildfix.toml config file.
@@ -98,10 +421,22 @@ pub struct CommitConfig {
 pub fn discover_config(repo_root: &Utf8Path) -> Option<Utf8PathBuf> {
     let config_path = repo_root.join(CONFIG_FILE_NAME);
     if config_path.exists() {
-        debug!("found config file at {}", config_path);
+        debug!(
+            event = telemetry::CONFIG_FILE,
+            file = %config_path,
+            outcome = "found",
+            "found config file at {}",
+            config_path
+        );
         Some(config_path)
     } else {
-        debug!("no config file found at {}", config_path);
+        debug!(
+            event = telemetry::CONFIG_FILE,
+            file = %config_path,
+            outcome = "missing",
+            "no config file found at {}",
+            config_path
+        );
         None
     }
 }
@@ -112,7 +447,18 @@ pub fn discover_config(repo_root: &Utf8Path) -> Option<Utf8PathBuf> {
 pub fn load_config(path: &Utf8Path) -> anyhow::Result<BuildfixConfig> {
     let contents =
         fs::read_to_string(path).with_context(|| format!("read config file {}", path))?;
-    parse_config(&contents).with_context(|| format!("parse config file {}", path))
+    let config = parse_config(&contents).with_context(|| format!("parse config file {}", path))?;
+    if config.extends.is_none() {
+        return Ok(config);
+    }
+
+    let local: toml::Table =
+        toml::from_str(&contents).with_context(|| format!("parse config file {}", path))?;
+    let merged =
+        extends::resolve(path, local).with_context(|| format!("resolve extends of {}", path))?;
+    toml::Value::Table(merged)
+        .try_into()
+        .with_context(|| format!("parse config file {} merged with its bases", path))
 }
 
 /// Parse a config file from a string.
@@ -140,6 +486,9 @@ pub struct MergedConfig {
     /// Deny patterns (from config file, extended by CLI).
     pub deny: Vec<String>,
 
+    /// Path-scoped policy rules (from config).
+    pub path_policies: Vec<PathPolicy>,
+
     /// Whether to allow guarded fixes.
     pub allow_guarded: bool,
 
@@ -149,9 +498,18 @@ pub struct MergedConfig {
     /// Whether to allow applying when dirty.
     pub allow_dirty: bool,
 
+    /// Which uncommitted changes count as dirty (from config).
+    pub dirty_scope: DirtyScope,
+
     /// Whether to require clean hashes for preconditions.
     pub require_clean_hashes: bool,
 
+    /// How file preconditions are compared (from config).
+    pub precondition_mode: PreconditionMode,
+
+    /// Paths planning never targets (from config).
+    pub scan: ScanPolicy,
+
     /// Maximum number of operations (from config).
     pub max_ops: Option<u64>,
 
@@ -161,6 +519,18 @@ pub struct MergedConfig {
     /// Maximum patch size in bytes (from config).
     pub max_patch_bytes: Option<u64>,
 
+    /// Plan lifetime in hours (from config).
+    pub plan_ttl_hours: Option<u64>,
+
+    /// Minimum finding severity (from config).
+    pub min_severity: Option<Severity>,
+
+    /// Sensors whose findings are planned (from config).
+    pub sensors: Vec<String>,
+
+    /// Check ids whose findings are ignored (from config).
+    pub exclude_check_ids: Vec<String>,
+
     /// Backup settings.
     pub backups: BackupsConfig,
 
@@ -170,6 +540,12 @@ pub struct MergedConfig {
     /// Optional auto-commit message override.
     pub commit_message: Option<String>,
 
+    /// Branch template for applied changes.
+    pub branch: Option<String>,
+
+    /// Remote to push the branch to.
+    pub push_remote: Option<String>,
+
     /// Parameters for unsafe fixes.
     pub params: HashMap<String, String>,
 }
@@ -219,16 +595,26 @@ impl ConfigMerger {
         MergedConfig {
             allow,
             deny,
+            path_policies: self.config.policy.paths.clone(),
             allow_guarded: self.config.policy.allow_guarded,
             allow_unsafe: self.config.policy.allow_unsafe,
             allow_dirty: self.config.policy.allow_dirty,
+            dirty_scope: self.config.policy.dirty_scope,
             require_clean_hashes: !no_clean_hashes,
+            precondition_mode: self.config.preconditions.mode,
+            scan: self.config.scan.clone(),
             max_ops: self.config.policy.max_ops,
             max_files: self.config.policy.max_files,
             max_patch_bytes: self.config.policy.max_patch_bytes,
+            plan_ttl_hours: self.config.policy.plan_ttl_hours,
+            min_severity: self.config.policy.min_severity,
+            sensors: self.config.policy.sensors.clone(),
+            exclude_check_ids: self.config.policy.exclude_check_ids.clone(),
             backups: self.config.backups.clone(),
             auto_commit: self.config.commit.enabled,
             commit_message: self.config.commit.message.clone(),
+            branch: self.config.commit.branch.clone(),
+            push_remote: self.config.commit.push.clone(),
             params,
         }
     }
@@ -260,16 +646,26 @@ impl ConfigMerger {
         MergedConfig {
             allow: self.config.policy.allow.clone(),
             deny: self.config.policy.deny.clone(),
+            path_policies: self.config.policy.paths.clone(),
             allow_guarded,
             allow_unsafe,
             allow_dirty: self.config.policy.allow_dirty,
+            dirty_scope: self.config.policy.dirty_scope,
             require_clean_hashes: true,
+            precondition_mode: self.config.preconditions.mode,
+            scan: self.config.scan.clone(),
             max_ops: self.config.policy.max_ops,
             max_files: self.config.policy.max_files,
             max_patch_bytes: self.config.policy.max_patch_bytes,
+            plan_ttl_hours: self.config.policy.plan_ttl_hours,
+            min_severity: self.config.policy.min_severity,
+            sensors: self.config.policy.sensors.clone(),
+            exclude_check_ids: self.config.policy.exclude_check_ids.clone(),
             backups: self.config.backups.clone(),
             auto_commit,
             commit_message,
+            branch: self.config.commit.branch.clone(),
+            push_remote: self.config.commit.push.clone(),
             params,
         }
     }
@@ -300,6 +696,57 @@ mod tests {
     use super::*;
     use tempfile::TempDir;
 
+    #[test]
+    fn test_parse_path_policies() {
+        let config = parse_config(
+            r#"
+[[policy.paths]]
+path = "vendor/**"
+deny = ["*"]
+
+[[policy.paths]]
+path = "crates/experimental/**"
+max_safety = "safe"
+"#,
+        )
+        .unwrap();
+        assert_eq!(config.policy.paths.len(), 2);
+        assert_eq!(config.policy.paths[0].deny, vec!["*".to_string()]);
+        assert_eq!(config.policy.paths[1].max_safety, Some(SafetyClass::Safe));
+
+        let merged = ConfigMerger::new(config).merge_plan_args(&[], &[], false, &HashMap::new());
+        assert_eq!(merged.path_policies.len(), 2);
+
+        assert!(parse_config("[[policy.paths]]\ndeny = [\"*\"]\n").is_err());
+    }
+
+    #[test]
+    fn test_fixer_sections_resolve_to_fix_ids() {
+        let config = parse_config(
+            r#"
+[fixers.msrv]
+safety = "guarded"
+params = { rust_version = "1.75" }
+
+[fixers."cargo.normalize_edition"]
+enabled = false
+"#,
+        )
+        .unwrap();
+        let fixers = config.fixer_settings().unwrap();
+        let msrv = &fixers["cargo.normalize_rust_version"];
+        assert!(msrv.enabled);
+        assert_eq!(msrv.safety, Some(SafetyClass::Guarded));
+        assert_eq!(msrv.params["rust_version"], "1.75");
+        assert!(!fixers["cargo.normalize_edition"].enabled);
+
+        let config = parse_config("[fixers.no-such-fix]\nenabled = false\n").unwrap();
+        let err = config.fixer_settings().unwrap_err().to_string();
+        assert!(err.contains("[fixers.no-such-fix]"), "{}", err);
+
+        assert!(parse_config("[fixers.msrv]\nenable = false\n").is_err());
+    }
+
     #[test]
     fn test_parse_example_config() {
         let contents = r#"
@@ -315,6 +762,7 @@ allow_dirty = false
 max_ops = 50
 max_files = 25
 max_patch_bytes = 250000
+plan_ttl_hours = 24
 
 [backups]
 enabled = true
@@ -331,11 +779,206 @@ suffix = ".buildfix.bak"
         assert_eq!(config.policy.max_ops, Some(50));
         assert_eq!(config.policy.max_files, Some(25));
         assert_eq!(config.policy.max_patch_bytes, Some(250000));
+        assert_eq!(config.policy.plan_ttl_hours, Some(24));
         assert!(config.backups.enabled);
         assert_eq!(config.backups.suffix, ".buildfix.bak");
+        assert!(config.backups.keep_last.is_none());
         assert!(!config.commit.enabled);
     }
 
+    #[test]
+    fn test_parse_backups_keep_last() {
+        let contents = r#"
+[backups]
+keep_last = 5
+"#;
+
+        let config = parse_config(contents).unwrap();
+        assert_eq!(config.backups.keep_last, Some(5));
+        assert!(config.backups.enabled);
+
+        let merged =
+            ConfigMerger::new(config).merge_apply_args(false, false, false, None, &HashMap::new());
+        assert_eq!(merged.backups.keep_last, Some(5));
+    }
+
+    #[test]
+    fn test_parse_gc_retention() {
+        let default = parse_config("").unwrap();
+        assert_eq!(default.gc.cache_max_age_days, Some(14));
+        assert!(default.gc.backup_max_age_days.is_none());
+
+        let config = parse_config("[gc]\nbackup_max_age_days = 30\n").unwrap();
+        assert_eq!(config.gc.backup_max_age_days, Some(30));
+        assert_eq!(config.gc.cache_max_age_days, Some(14));
+
+        assert!(parse_config("[gc]\nmax_age = 1\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_dirty_scope() {
+        assert_eq!(
+            parse_config("").unwrap().policy.dirty_scope,
+            DirtyScope::Repo
+        );
+        let config = parse_config("[policy]\ndirty_scope = \"plan\"\n").unwrap();
+        assert_eq!(config.policy.dirty_scope, DirtyScope::Plan);
+        let merged =
+            ConfigMerger::new(config).merge_apply_args(false, false, false, None, &HashMap::new());
+        assert_eq!(merged.dirty_scope, DirtyScope::Plan);
+
+        assert!(parse_config("[policy]\ndirty_scope = \"files\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_scan() {
+        assert_eq!(parse_config("").unwrap().scan, ScanPolicy::default());
+        let config =
+            parse_config("[scan]\nskip_dirs = [\"target\", \"third_party\"]\ngitignore = false\n")
+                .unwrap();
+        assert_eq!(config.scan.skip_dirs, vec!["target", "third_party"]);
+        assert!(!config.scan.gitignore);
+        assert!(config.scan.vendored);
+        let merged = ConfigMerger::new(config).merge_plan_args(&[], &[], false, &HashMap::new());
+        assert_eq!(merged.scan.skip_dirs, vec!["target", "third_party"]);
+
+        assert!(parse_config("[scan]\nvendor = false\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_preconditions_mode() {
+        assert_eq!(
+            parse_config("").unwrap().preconditions.mode,
+            PreconditionMode::Exact
+        );
+        let config = parse_config("[preconditions]\nmode = \"semantic\"\n").unwrap();
+        assert_eq!(config.preconditions.mode, PreconditionMode::Semantic);
+        let merged = ConfigMerger::new(config).merge_plan_args(&[], &[], false, &HashMap::new());
+        assert_eq!(merged.precondition_mode, PreconditionMode::Semantic);
+
+        assert!(parse_config("[preconditions]\nmode = \"fuzzy\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_commit_branch_and_push() {
+        let config = parse_config(
+            "[commit]\nenabled = true\nbranch = \"fix/{plan_hash}\"\npush = \"origin\"\n",
+        )
+        .unwrap();
+        let merged =
+            ConfigMerger::new(config).merge_apply_args(false, false, false, None, &HashMap::new());
+        assert_eq!(merged.branch.as_deref(), Some("fix/{plan_hash}"));
+        assert_eq!(merged.push_remote.as_deref(), Some("origin"));
+    }
+
+    #[test]
+    fn test_parse_forge() {
+        let config = parse_config(
+            "[forge]\nprovider = \"gitlab\"\napi_url = \"https://git.example.com/api/v4\"\nrepo = \"g/sub/p\"\ntoken_env = \"BOT_TOKEN\"\n",
+        )
+        .unwrap();
+        assert_eq!(config.forge.provider, Some(ForgeProvider::Gitlab));
+        assert_eq!(config.forge.repo.as_deref(), Some("g/sub/p"));
+        assert_eq!(config.forge.token_env.as_deref(), Some("BOT_TOKEN"));
+        assert!(parse_config("[forge]\nprovider = \"gitea\"\n").is_err());
+    }
+
+    #[test]
+    fn test_parse_receipts_trust() {
+        let contents = r#"
+[receipts.trust]
+mode = "strict"
+public_keys = ["RWQf6LRCGA9i53mlYecO4IzT51TGPpvWucNSCh1CBM0QTaLn73Y7GFO3"]
+"#;
+
+        let config = parse_config(contents).unwrap();
+        let trust = config.receipts.trust.expect("trust section");
+        assert_eq!(trust.mode, TrustMode::Strict);
+        let policy = trust.to_policy(Utf8Path::new(".")).unwrap();
+        assert_eq!(policy.key_count(), 1);
+
+        let empty = parse_config("[receipts.trust]\n").unwrap();
+        assert_eq!(
+            empty.receipts.trust.as_ref().unwrap().mode,
+            TrustMode::Permissive
+        );
+        assert!(
+            empty
+                .receipts
+                .trust
+                .unwrap()
+                .to_policy(Utf8Path::new("."))
+                .is_err()
+        );
+    }
+
+    #[test]
+    fn test_parse_receipt_discovery() {
+        let contents = r#"
+[receipts]
+patterns = ["ci-out/**/*.report.json"]
+sensor_id = "file_stem"
+max_findings = 500
+"#;
+
+        let config = parse_config(contents).unwrap();
+        let discovery = config.receipts.discovery(Utf8Path::new("/repo"));
+        assert_eq!(discovery.patterns, vec!["/repo/ci-out/**/*.report.json"]);
+        assert_eq!(discovery.sensor_id, SensorIdRule::FileStem);
+        assert_eq!(discovery.max_findings, 500);
+
+        let default = parse_config("").unwrap();
+        assert_eq!(
+            default.receipts.discovery(Utf8Path::new("/repo")),
+            ReceiptDiscovery::default()
+        );
+    }
+
+    #[test]
+    fn test_render_templates_dir() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        std::fs::create_dir(root.join("templates")).expect("create dir");
+        std::fs::write(root.join("templates/comment.md"), "{{ default }}").expect("write");
+
+        let config = parse_config("[render]\ntemplates_dir = \"templates\"\n").unwrap();
+        let templates = config.render.templates(&root).unwrap();
+        assert_eq!(templates.comment.as_deref(), Some("{{ default }}"));
+        assert!(templates.plan.is_none());
+        assert!(templates.apply.is_none());
+
+        let missing = parse_config("[render]\ntemplates_dir = \"nope\"\n").unwrap();
+        assert!(missing.render.templates(&root).is_err());
+
+        let default = parse_config("").unwrap();
+        assert!(default.render.templates(&root).unwrap().is_builtin());
+
+        let limited = parse_config("[render]\ncomment_max_bytes = 4000\n").unwrap();
+        let templates = limited.render.templates(&root).unwrap();
+        assert!(templates.is_builtin());
+        assert_eq!(templates.comment_options.max_bytes, 4000);
+    }
+
+    #[test]
+    fn test_parse_finding_filters() {
+        let contents = r#"
+[policy]
+min_severity = "warn"
+sensors = ["depguard", "builddiag"]
+exclude_check_ids = ["deps.wildcard"]
+"#;
+
+        let config = parse_config(contents).unwrap();
+        assert_eq!(config.policy.min_severity, Some(Severity::Warn));
+
+        let merged = ConfigMerger::new(config).merge_plan_args(&[], &[], false, &HashMap::new());
+        assert_eq!(merged.min_severity, Some(Severity::Warn));
+        assert_eq!(merged.sensors, vec!["depguard", "builddiag"]);
+        assert_eq!(merged.exclude_check_ids, vec!["deps.wildcard"]);
+
+        assert!(parse_config("[policy]\nmin_severity = \"fatal\"\n").is_err());
+    }
+
     #[test]
     fn test_parse_minimal_config() {
         let contents = r#"
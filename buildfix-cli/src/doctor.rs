@@ -0,0 +1,297 @@
+//! Environment diagnostics for `buildfix doctor`.
+//!
+//! Each check reports ok, warn, or fail; failures carry a remediation line.
+//! Nothing is written except a probe file that is removed immediately.
+
+use crate::config;
+use buildfix_receipts::ReceiptDiscovery;
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use serde::Serialize;
+use std::process::Command;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CheckStatus {
+    Ok,
+    Warn,
+    Fail,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct DoctorCheck {
+    pub name: &'static str,
+    pub status: CheckStatus,
+    pub detail: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub remediation: Option<String>,
+}
+
+impl DoctorCheck {
+    fn ok(name: &'static str, detail: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Ok,
+            detail: detail.into(),
+            remediation: None,
+        }
+    }
+
+    fn warn(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Warn,
+            detail: detail.into(),
+            remediation: Some(fix.into()),
+        }
+    }
+
+    fn fail(name: &'static str, detail: impl Into<String>, fix: impl Into<String>) -> Self {
+        Self {
+            name,
+            status: CheckStatus::Fail,
+            detail: detail.into(),
+            remediation: Some(fix.into()),
+        }
+    }
+}
+
+/// Embedded schema: file name, expected `$id` suffix, and contents.
+pub type SchemaEntry = (&'static str, &'static str, &'static str);
+
+pub fn run_checks(
+    repo_root: &Utf8Path,
+    artifacts_dir: &Utf8Path,
+    out_dir: &Utf8Path,
+    schemas: &[SchemaEntry],
+) -> Vec<DoctorCheck> {
+    let (config_check, discovery) = check_config(repo_root);
+    vec![
+        check_git(repo_root),
+        check_writable(out_dir),
+        check_receipts(artifacts_dir, &discovery),
+        config_check,
+        check_schemas(schemas),
+    ]
+}
+
+fn check_git(repo_root: &Utf8Path) -> DoctorCheck {
+    const NAME: &str = "git";
+    let version = match Command::new("git").arg("--version").output() {
+        Ok(out) if out.status.success() => String::from_utf8_lossy(&out.stdout).trim().to_string(),
+        _ => {
+            // HEAD and dirty-tree checks are read in-process; only
+            // committing and --changed-only diffs need the binary.
+            return DoctorCheck::warn(
+                NAME,
+                "git not found on PATH",
+                "Install git to use --auto-commit and --changed-only",
+            );
+        }
+    };
+    let in_work_tree = Command::new("git")
+        .args(["rev-parse", "--is-inside-work-tree"])
+        .current_dir(repo_root)
+        .output()
+        .is_ok_and(|out| out.status.success());
+    if in_work_tree {
+        DoctorCheck::ok(
+            NAME,
+            format!("{}; {} is a git work tree", version, repo_root),
+        )
+    } else {
+        DoctorCheck::warn(
+            NAME,
+            format!("{}; {} is not a git work tree", version, repo_root),
+            "Run from a git checkout or pass --repo-root; HEAD and dirty-tree checks are skipped otherwise",
+        )
+    }
+}
+
+fn check_writable(out_dir: &Utf8Path) -> DoctorCheck {
+    const NAME: &str = "out dir";
+    // Probe the nearest existing directory; plan creates the rest.
+    let mut dir: Utf8PathBuf = out_dir.to_path_buf();
+    while !dir.exists() {
+        match dir.parent() {
+            Some(parent) if !parent.as_str().is_empty() => dir = parent.to_path_buf(),
+            _ => {
+                dir = Utf8PathBuf::from(".");
+                break;
+            }
+        }
+    }
+    let probe = dir.join(format!(".buildfix-doctor-{}", std::process::id()));
+    match fs::write(&probe, b"") {
+        Ok(()) => {
+            let _ = fs::remove_file(&probe);
+            DoctorCheck::ok(NAME, format!("{} is writable", out_dir))
+        }
+        Err(e) => DoctorCheck::fail(
+            NAME,
+            format!("cannot write to {}: {}", dir, e),
+            format!("Make {} writable or pass --out-dir", dir),
+        ),
+    }
+}
+
+fn check_receipts(artifacts_dir: &Utf8Path, discovery: &ReceiptDiscovery) -> DoctorCheck {
+    const NAME: &str = "receipts";
+    const RUN_SENSORS: &str = "Run your sensors (e.g. builddiag, depguard) so they write <artifacts>/<sensor>/report.json";
+    if !artifacts_dir.exists() {
+        return DoctorCheck::warn(
+            NAME,
+            format!("{} does not exist", artifacts_dir),
+            RUN_SENSORS,
+        );
+    }
+    let receipts = match buildfix_receipts::discover_receipts(artifacts_dir, discovery, None, None)
+    {
+        Ok(receipts) => receipts,
+        Err(e) => {
+            return DoctorCheck::fail(
+                NAME,
+                format!("cannot read {}: {:#}", artifacts_dir, e),
+                "Check the directory's permissions and [receipts] patterns in buildfix.toml",
+            );
+        }
+    };
+    let broken: Vec<String> = receipts
+        .iter()
+        .filter_map(|r| {
+            r.receipt
+                .as_ref()
+                .err()
+                .map(|e| format!("{}: {}", r.path, e))
+        })
+        .collect();
+    if !broken.is_empty() {
+        return DoctorCheck::fail(
+            NAME,
+            format!(
+                "{} of {} receipts failed to load\n{}",
+                broken.len(),
+                receipts.len(),
+                broken.join("\n")
+            ),
+            "Re-run the sensors that wrote them, or fix the JSON by hand",
+        );
+    }
+    if receipts.is_empty() {
+        return DoctorCheck::warn(
+            NAME,
+            format!("no receipts found in {}", artifacts_dir),
+            RUN_SENSORS,
+        );
+    }
+    DoctorCheck::ok(
+        NAME,
+        format!("{} receipts load from {}", receipts.len(), artifacts_dir),
+    )
+}
+
+/// Also returns the receipt discovery the config asks for, so the receipts
+/// check reads the same files `plan` would.
+fn check_config(repo_root: &Utf8Path) -> (DoctorCheck, ReceiptDiscovery) {
+    const NAME: &str = "buildfix.toml";
+    let Some(path) = config::discover_config(repo_root) else {
+        return (
+            DoctorCheck::ok(NAME, "not present; using defaults"),
+            ReceiptDiscovery::default(),
+        );
+    };
+    let cfg = match config::load_config(&path) {
+        Ok(cfg) => cfg,
+        Err(e) => {
+            return (
+                DoctorCheck::fail(
+                    NAME,
+                    format!("{:#}", e),
+                    "Fix the reported line; see docs/reference/config.md for the accepted keys",
+                ),
+                ReceiptDiscovery::default(),
+            );
+        }
+    };
+    let discovery = cfg.receipts.discovery(repo_root);
+
+    let mut problems = Vec::new();
+    if let Err(e) = cfg.fixer_settings() {
+        problems.push(format!("{:#}", e));
+    }
+    if let Err(e) = cfg.render.templates(repo_root) {
+        problems.push(format!("[render]: {:#}", e));
+    }
+    if let Some(trust) = &cfg.receipts.trust
+        && let Err(e) = trust.to_policy(repo_root)
+    {
+        problems.push(format!("[receipts.trust]: {:#}", e));
+    }
+    let check = if problems.is_empty() {
+        DoctorCheck::ok(NAME, format!("{} is valid", path))
+    } else {
+        DoctorCheck::fail(
+            NAME,
+            problems.join("\n"),
+            "Fix the referenced templates or keys; the TOML itself parses",
+        )
+    };
+    (check, discovery)
+}
+
+fn check_schemas(schemas: &[SchemaEntry]) -> DoctorCheck {
+    const NAME: &str = "schemas";
+    let mut problems = Vec::new();
+    for (file, id, contents) in schemas {
+        let json: serde_json::Value = match serde_json::from_str(contents) {
+            Ok(json) => json,
+            Err(e) => {
+                problems.push(format!("{}: not JSON: {}", file, e));
+                continue;
+            }
+        };
+        let declared = json.get("$id").and_then(|v| v.as_str()).unwrap_or("");
+        if !declared.ends_with(id) {
+            problems.push(format!("{}: $id is '{}', expected *{}", file, declared, id));
+        }
+        if let Err(e) = jsonschema::draft202012::new(&json) {
+            problems.push(format!("{}: does not compile: {}", file, e));
+        }
+    }
+    if problems.is_empty() {
+        DoctorCheck::ok(NAME, format!("{} embedded schemas compile", schemas.len()))
+    } else {
+        DoctorCheck::fail(
+            NAME,
+            problems.join("\n"),
+            "Reinstall buildfix; the embedded schema bundle is damaged",
+        )
+    }
+}
+
+/// One line per check, with detail continuation lines and a `fix:` line
+/// under anything that is not ok.
+pub fn render_text(checks: &[DoctorCheck]) -> String {
+    let mut out = String::new();
+    for check in checks {
+        let status = match check.status {
+            CheckStatus::Ok => "ok",
+            CheckStatus::Warn => "warn",
+            CheckStatus::Fail => "FAIL",
+        };
+        let mut lines = check.detail.lines();
+        out.push_str(&format!(
+            "{:<5} {:<14} {}\n",
+            status,
+            check.name,
+            lines.next().unwrap_or("")
+        ));
+        for line in lines {
+            out.push_str(&format!("{:<20} {}\n", "", line));
+        }
+        if let Some(fix) = &check.remediation {
+            out.push_str(&format!("{:<20} fix: {}\n", "", fix));
+        }
+    }
+    out
+}
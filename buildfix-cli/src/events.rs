@@ -0,0 +1,179 @@
+//! NDJSON progress events for `--events`.
+//!
+//! One JSON object per line, each with an `event` name and an RFC 3339 `ts`,
+//! so orchestrators can follow a run without parsing logs.
+
+use anyhow::Context;
+use buildfix_types::apply::{ApplyStatus, BuildfixApply};
+use buildfix_types::plan::BuildfixPlan;
+use buildfix_types::progress::Progress;
+use camino::Utf8Path;
+use chrono::{SecondsFormat, Utc};
+use fs_err as fs;
+use serde_json::{Map, Value, json};
+use std::io::Write;
+
+/// Destination for progress events; a no-op when `--events` is not given.
+pub struct EventSink {
+    out: Option<Box<dyn Write>>,
+}
+
+impl EventSink {
+    /// Open `target`: a file path, or `fd:N` for an inherited file
+    /// descriptor.
+    pub fn open(target: Option<&str>) -> anyhow::Result<Self> {
+        let Some(target) = target else {
+            return Ok(Self { out: None });
+        };
+        let path = match target.strip_prefix("fd:") {
+            Some(fd) => {
+                let fd: u32 = fd
+                    .parse()
+                    .with_context(|| format!("invalid --events file descriptor '{}'", fd))?;
+                format!("/dev/fd/{}", fd)
+            }
+            None => target.to_string(),
+        };
+        let file = fs::OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(&path)
+            .with_context(|| format!("open --events {}", target))?;
+        Ok(Self {
+            out: Some(Box::new(file)),
+        })
+    }
+
+    /// Events on stdout, for commands whose stdout is the event stream.
+    #[cfg(feature = "watch")]
+    pub fn stdout() -> Self {
+        Self {
+            out: Some(Box::new(std::io::stdout())),
+        }
+    }
+
+    /// Write one event line. Write failures are logged, not fatal: losing
+    /// progress events should not fail the run.
+    pub fn emit(&mut self, event: &str, fields: Value) {
+        let Some(out) = self.out.as_mut() else {
+            return;
+        };
+        let mut line = Map::new();
+        line.insert("event".into(), event.into());
+        line.insert(
+            "ts".into(),
+            Utc::now()
+                .to_rfc3339_opts(SecondsFormat::Millis, true)
+                .into(),
+        );
+        if let Value::Object(fields) = fields {
+            line.extend(fields);
+        }
+        let result = serde_json::to_writer(&mut *out, &line)
+            .map_err(std::io::Error::from)
+            .and_then(|()| out.write_all(b"\n"))
+            .and_then(|()| out.flush());
+        if let Err(e) = result {
+            tracing::warn!(
+                event = buildfix_types::telemetry::EVENTS_WRITE_FAILED,
+                "failed to write event: {}",
+                e
+            );
+            self.out = None;
+        }
+    }
+
+    /// `op_planned` for each ready op and `op_blocked` for each blocked
+    /// one, then `plan_finished`.
+    pub fn plan_ops(&mut self, plan: &BuildfixPlan) {
+        for op in &plan.ops {
+            let mut fields = json!({
+                "op_id": op.id,
+                "fix_key": op.rationale.fix_key,
+                "target": op.target.path,
+                "safety": op.safety,
+            });
+            if op.blocked {
+                fields["reason"] = json!(op.blocked_reason);
+                fields["reason_token"] = json!(op.blocked_reason_token);
+            }
+            self.emit(
+                if op.blocked {
+                    "op_blocked"
+                } else {
+                    "op_planned"
+                },
+                fields,
+            );
+        }
+        self.emit(
+            "plan_finished",
+            json!({
+                "ops_total": plan.summary.ops_total,
+                "ops_blocked": plan.summary.ops_blocked,
+                "files_touched": plan.summary.files_touched,
+            }),
+        );
+    }
+
+    /// One `op_applied` / `op_blocked` / `op_failed` / `op_skipped` per
+    /// result, then `apply_finished`.
+    pub fn apply_results(&mut self, apply: &BuildfixApply) {
+        for r in &apply.results {
+            let event = match r.status {
+                ApplyStatus::Applied => "op_applied",
+                ApplyStatus::Blocked => "op_blocked",
+                ApplyStatus::Failed => "op_failed",
+                ApplyStatus::Skipped => "op_skipped",
+            };
+            let files: Vec<&str> = r.files.iter().map(|f| f.path.as_str()).collect();
+            self.emit(
+                event,
+                json!({
+                    "op_id": r.op_id,
+                    "reason": r.blocked_reason.as_deref().or(r.message.as_deref()),
+                    "reason_token": r.blocked_reason_token,
+                    "files": files,
+                }),
+            );
+        }
+        let s = &apply.summary;
+        self.emit(
+            "apply_finished",
+            json!({
+                "attempted": s.attempted,
+                "applied": s.applied,
+                "blocked": s.blocked,
+                "failed": s.failed,
+                "files_modified": s.files_modified,
+            }),
+        );
+    }
+
+    /// `phase_started`, `fixer_finished` or `op_progress`, as the pipeline
+    /// reports them.
+    pub fn progress(&mut self, progress: &Progress<'_>) {
+        match *progress {
+            Progress::Phase(phase) => {
+                self.emit("phase_started", json!({ "phase": phase.as_str() }))
+            }
+            Progress::Fixer {
+                fix_key,
+                ops,
+                done,
+                total,
+            } => self.emit(
+                "fixer_finished",
+                json!({ "fix_key": fix_key, "ops": ops, "done": done, "total": total }),
+            ),
+            Progress::Op { op_id, done, total } => self.emit(
+                "op_progress",
+                json!({ "op_id": op_id, "done": done, "total": total }),
+            ),
+        }
+    }
+
+    pub fn artifacts_written(&mut self, out_dir: &Utf8Path) {
+        self.emit("artifacts_written", json!({ "out_dir": out_dir }));
+    }
+}
@@ -5,26 +5,34 @@
 //! - Safety rationale
 //! - Remediation guidance
 //! - Triggering sensor findings
+//!
+//! Everything here comes from the enabled fixers' [`FixerMeta`], so a new
+//! fixer is explained and listed as soon as it is registered with the planner.
 
-pub use buildfix_fixer_catalog::TriggerPattern;
+pub use buildfix_core::{FixerMeta, TriggerPattern};
+use buildfix_types::messages::{MessageCatalog, codes};
 use buildfix_types::ops::SafetyClass;
-use std::collections::{BTreeSet, HashSet};
-
-fn enabled_fix_ids() -> HashSet<&'static str> {
-    buildfix_fixer_catalog::enabled_fix_ids()
-        .into_iter()
-        .collect()
-}
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use buildfix_types::receipt::ReceiptEnvelope;
+use serde::Serialize;
+use std::collections::BTreeSet;
+use std::sync::OnceLock;
 
+/// Explanations for every enabled fixer, in planner order.
 pub fn enabled_fixes() -> Vec<&'static FixExplanation> {
-    let enabled = enabled_fix_ids();
-    FIX_REGISTRY
+    static FIXES: OnceLock<Vec<FixExplanation>> = OnceLock::new();
+    FIXES
+        .get_or_init(|| {
+            buildfix_core::builtin_fixer_metas()
+                .iter()
+                .map(FixExplanation::from_meta)
+                .collect()
+        })
         .iter()
-        .filter(|fix| enabled.contains(fix.fix_id))
         .collect()
 }
 
-/// Information about a buildfix fix.
+/// Information about a buildfix fix, taken from the fixer's [`FixerMeta`].
 #[derive(Debug, Clone)]
 pub struct FixExplanation {
     /// Short key for the fix (user-facing, e.g., "resolver-v2").
@@ -45,514 +53,20 @@ pub struct FixExplanation {
     pub triggers: &'static [TriggerPattern],
 }
 
-/// Registry of all available fix explanations.
-pub static FIX_REGISTRY: &[FixExplanation] = &[
-    // 1) Workspace resolver v2
-    FixExplanation {
-        key: "resolver-v2",
-        fix_id: "cargo.workspace_resolver_v2",
-        title: "Workspace Resolver V2",
-        safety: SafetyClass::Safe,
-        description: r#"Sets `[workspace].resolver = "2"` in the root Cargo.toml.
-
-Cargo's resolver v2 is the modern feature resolver that provides correct feature
-unification across the dependency graph. It prevents surprising behavior where
-dev-dependencies can enable features in normal dependencies.
-
-This fix ensures your workspace uses the v2 resolver, which is required for:
-- Correct feature handling in workspaces
-- Avoiding feature leakage between different dependency kinds
-- Compatibility with modern Cargo practices"#,
-        safety_rationale: r#"This fix is classified as SAFE because:
-- It only modifies the resolver field in the workspace table
-- The change is deterministic and predictable
-- Resolver v2 is backwards compatible for most projects
-- The edit is trivially reversible
-- It does not affect dependency versions or features directly"#,
-        remediation: r#"To manually apply this fix, add or update your root Cargo.toml:
-
-    [workspace]
-    resolver = "2"
-
-If this fix is blocked because the file is not a workspace, you may need to
-convert your project to a workspace first or use `package.resolver = "2"`
-for single-crate projects."#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "builddiag",
-                check_id: "workspace.resolver_v2",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo",
-                check_id: "cargo.workspace.resolver_v2",
-                code: None,
-            },
-        ],
-    },
-    // 2) Path dependency requires version
-    FixExplanation {
-        key: "path-dep-version",
-        fix_id: "cargo.path_dep_add_version",
-        title: "Path Dependency Version",
-        safety: SafetyClass::Safe,
-        description: r#"Adds a `version` field to path dependencies that are missing one.
-
-Path dependencies without a version field cannot be published to crates.io. This
-fix automatically determines the correct version by reading the target crate's
-Cargo.toml and adds the version field.
-
-Example transformation:
-    foo = { path = "../foo" }
-becomes:
-    foo = { path = "../foo", version = "1.0.0" }"#,
-        safety_rationale: r#"This fix is classified as SAFE when the version can be determined because:
-- The version is read directly from the target crate's Cargo.toml
-- No guesswork or heuristics are involved
-- The edit is additive (only adds a field, doesn't change existing ones)
-- The change is required for publishing and is policy-compliant
-
-If the version cannot be determined (target crate has no version, or multiple
-possible targets exist), the fix is skipped to maintain safety."#,
-        remediation: r#"To manually apply this fix:
-
-1. Find the target crate's Cargo.toml
-2. Read the `[package].version` field
-3. Add `version = "<version>"` to the path dependency
-
-Example:
-    [dependencies]
-    my-crate = { path = "../my-crate", version = "0.1.0" }
-
-If you need a different version constraint (e.g., ">=0.1"), manually edit
-the version field after buildfix applies the exact version."#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "deps.path_requires_version",
-                code: Some("missing_version"),
-            },
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "cargo.path_requires_version",
-                code: Some("missing_version"),
-            },
-        ],
-    },
-    // 3) Workspace dependency inheritance
-    FixExplanation {
-        key: "workspace-inheritance",
-        fix_id: "cargo.use_workspace_dependency",
-        title: "Workspace Dependency Inheritance",
-        safety: SafetyClass::Safe,
-        description: r#"Converts member crate dependencies to use workspace inheritance.
-
-When a dependency is defined in [workspace.dependencies], member crates should
-use `{ workspace = true }` instead of specifying the version directly. This
-ensures version consistency across the workspace.
-
-Example transformation:
-    serde = "1.0"
-becomes:
-    serde = { workspace = true }
-
-The fix preserves important per-crate overrides like:
-- `features` - additional features for this crate
-- `optional` - whether the dependency is optional
-- `default-features` - whether to include default features
-- `package` - renamed dependencies"#,
-        safety_rationale: r#"This fix is classified as SAFE because:
-- It only applies when the dependency exists in [workspace.dependencies]
-- The transformation is deterministic and preserves override keys
-- It enforces the single-source-of-truth pattern for versions
-- The edit is easily reversible
-
-The fix is skipped for:
-- Dependencies already using workspace = true
-- Path or git dependencies (these have different semantics)
-- Dependencies not defined in workspace.dependencies"#,
-        remediation: r#"To manually apply this fix:
-
-1. Ensure the dependency is defined in root Cargo.toml:
-    [workspace.dependencies]
-    serde = { version = "1.0", features = ["derive"] }
-
-2. Update member Cargo.toml to inherit:
-    [dependencies]
-    serde = { workspace = true }
-
-3. Add per-crate overrides if needed:
-    serde = { workspace = true, features = ["rc"] }
-
-Note: The workspace definition controls version and base features.
-Member crates can add features but cannot change the version."#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "deps.workspace_inheritance",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "cargo.workspace_inheritance",
-                code: None,
-            },
-        ],
-    },
-    // 4) Duplicate dependency consolidation
-    FixExplanation {
-        key: "duplicate-deps",
-        fix_id: "cargo.consolidate_duplicate_deps",
-        title: "Duplicate Dependency Consolidation",
-        safety: SafetyClass::Safe,
-        description: r#"Consolidates duplicate dependency versions across member crates into
-[workspace.dependencies].
-
-When depguard reports that a dependency appears at multiple versions across the
-workspace, this fix:
-1. Ensures the selected version exists in [workspace.dependencies]
-2. Rewrites reported member dependencies to `{ workspace = true }`
-3. Preserves per-crate overrides such as features/optional/package
-
-This creates a single source of truth for the dependency version at workspace
-scope and removes per-crate drift."#,
-        safety_rationale: r#"This fix is classified as SAFE because:
-- The target dependency and selected version come from the sensor receipt
-- Edits are deterministic and scoped to explicitly reported entries
-- The transform is mechanical (workspace.dependencies + workspace=true rewrite)
-- Per-crate non-version overrides are preserved"#,
-        remediation: r#"To manually apply this fix:
-
-1. Pick the canonical version for the duplicated dependency
-2. Set it in root Cargo.toml:
-    [workspace.dependencies]
-    serde = "1.0.210"
-
-3. Update member manifests to inherit from the workspace:
-    [dependencies]
-    serde = { workspace = true }
-
-4. Re-run your sensor to confirm duplicate-version findings are cleared."#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "deps.duplicate_dependency_versions",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "cargo.duplicate_dependency_versions",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "deps.duplicate_versions",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "depguard",
-                check_id: "cargo.duplicate_versions",
-                code: None,
-            },
-        ],
-    },
-    // 5) Remove unused dependencies
-    FixExplanation {
-        key: "remove-unused-deps",
-        fix_id: "cargo.remove_unused_deps",
-        title: "Remove Unused Dependencies",
-        safety: SafetyClass::Unsafe,
-        description: r#"Removes dependency entries that sensors report as unused.
-
-When tools such as cargo-udeps or cargo-machete identify dependencies that are
-not used by the crate, this fix removes the exact dependency entry from
-Cargo.toml (for example `dependencies.foo`).
-
-This is implemented as a direct TOML key removal (`toml_remove`) using
-sensor-provided location data (`toml_path`)."#,
-        safety_rationale: r#"This fix is classified as UNSAFE because:
-- A dependency reported as unused can still be needed for optional/runtime flows
-- Build scripts, feature-gated code, and platform-specific paths can hide usage
-- Removing dependencies can change build behavior or break downstream workflows
-
-The edit is deterministic, but human confirmation is required before apply."#,
-        remediation: r#"To manually apply this fix:
-
-1. Confirm the dependency is truly unused in all relevant build modes
-2. Remove it from the appropriate dependency table in Cargo.toml
-3. Run `cargo check --workspace --all-targets --all-features`
-
-To let buildfix apply this class of fix:
-    buildfix apply --apply --allow-unsafe"#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "cargo-udeps",
-                check_id: "deps.unused_dependency",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "udeps",
-                check_id: "deps.unused_dependency",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo-machete",
-                check_id: "deps.unused_dependency",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "machete",
-                check_id: "deps.unused_dependency",
-                code: None,
-            },
-            // Additional triggers from catalog not in main sensor outputs
-            TriggerPattern {
-                sensor: "cargo-udeps",
-                check_id: "deps.unused_dependencies",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo-udeps",
-                check_id: "cargo.unused_dependency",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo-udeps",
-                check_id: "cargo.unused_dependencies",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "udeps",
-                check_id: "udeps.unused_dependency",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "machete",
-                check_id: "machete.unused_dependency",
-                code: None,
-            },
-        ],
-    },
-    // 6) MSRV normalization
-    FixExplanation {
-        key: "msrv",
-        fix_id: "cargo.normalize_rust_version",
-        title: "MSRV Normalization",
-        safety: SafetyClass::Guarded,
-        description: r#"Normalizes per-crate rust-version (MSRV) declarations to match the workspace
-canonical value.
-
-The Minimum Supported Rust Version should be consistent across a workspace to
-avoid confusion and ensure all crates can build with the same toolchain. This
-fix sets member crate `package.rust-version` to match the workspace standard.
-
-The canonical rust-version is determined from (in order):
-1. [workspace.package].rust-version in root Cargo.toml
-2. [package].rust-version in root Cargo.toml"#,
-        safety_rationale: r#"This fix is classified as GUARDED because:
-- Changing MSRV can affect which Rust versions can compile the crate
-- A lower MSRV might hide newer Rust features being used
-- A higher MSRV might break builds for users on older toolchains
-
-The fix requires --allow-guarded because:
-- It changes a semantic version constraint
-- The impact depends on your support policy
-- Manual review is recommended before applying
-
-The fix is skipped entirely (not even planned) when:
-- No canonical workspace rust-version exists
-- The crate already has the correct rust-version"#,
-        remediation: r#"To manually apply this fix:
-
-1. Decide on your workspace's canonical MSRV
-2. Set it in root Cargo.toml:
-    [workspace.package]
-    rust-version = "1.70"
-
-3. Update each member crate:
-    [package]
-    rust-version = "1.70"
-    # Or use workspace inheritance:
-    rust-version.workspace = true
-
-Before changing MSRV, verify your code compiles with the target version:
-    cargo +1.70 check --workspace
-
-Consider using cargo-msrv to verify actual minimum version."#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "builddiag",
-                check_id: "rust.msrv_consistent",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo",
-                check_id: "cargo.msrv_consistent",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo",
-                check_id: "msrv.consistent",
-                code: None,
-            },
-        ],
-    },
-    // 7) Edition normalization
-    FixExplanation {
-        key: "edition",
-        fix_id: "cargo.normalize_edition",
-        title: "Edition Normalization",
-        safety: SafetyClass::Guarded,
-        description: r#"Normalizes per-crate Rust edition declarations to match the workspace
-canonical value.
-
-The Rust edition determines which language features are available and how certain
-syntax is interpreted. Having consistent editions across a workspace ensures:
-- Predictable behavior across all crates
-- Easier upgrades when moving to a new edition
-- Clear documentation of language version requirements
-
-The canonical edition is determined from (in order):
-1. [workspace.package].edition in root Cargo.toml
-2. [package].edition in root Cargo.toml"#,
-        safety_rationale: r#"This fix is classified as GUARDED because:
-- Changing edition can affect code semantics and compilation
-- A higher edition may require code changes (e.g., 2021 keyword changes)
-- A lower edition might disable features your code depends on
-
-The fix requires --allow-guarded because:
-- Edition changes can break builds
-- The impact depends on which language features are used
-- Manual review is recommended before applying
-
-The fix is skipped entirely (not even planned) when:
-- No canonical workspace edition exists
-- The crate already has the correct edition"#,
-        remediation: r#"To manually apply this fix:
-
-1. Decide on your workspace's canonical edition
-2. Set it in root Cargo.toml:
-    [workspace.package]
-    edition = "2021"
-
-3. Update each member crate:
-    [package]
-    edition = "2021"
-    # Or use workspace inheritance:
-    edition.workspace = true
-
-Before changing edition, verify your code compiles:
-    cargo +nightly fix --edition
-
-Consider using cargo fix to automatically migrate code between editions."#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "builddiag",
-                check_id: "rust.edition_consistent",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo",
-                check_id: "cargo.edition_consistent",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo",
-                check_id: "edition.consistent",
-                code: None,
-            },
-        ],
-    },
-    // 8) License normalization
-    FixExplanation {
-        key: "license",
-        fix_id: "cargo.normalize_license",
-        title: "License Normalization",
-        safety: SafetyClass::Guarded,
-        description: r#"Normalizes per-crate `package.license` declarations to match the workspace
-canonical license.
-
-When cargo-deny reports missing or unlicensed crate manifests, this fix sets
-`package.license` on the affected crate's Cargo.toml using the workspace
-standard from the root manifest.
-
-Canonical license lookup order:
-1. [workspace.package].license in root Cargo.toml
-2. [package].license in root Cargo.toml"#,
-        safety_rationale: r#"This fix is classified as GUARDED because:
-- License metadata has legal/compliance impact
-- A wrong license string can violate distribution policy
-- Manual review is recommended before applying to all crates
-
-If no workspace canonical license exists, the fix becomes UNSAFE and requires
-`--param license=<expression>` to provide an explicit value."#,
-        remediation: r#"To manually apply this fix:
-
-1. Set the workspace canonical license in root Cargo.toml:
-    [workspace.package]
-    license = "MIT OR Apache-2.0"
-
-2. Set each affected crate:
-    [package]
-    license = "MIT OR Apache-2.0"
-
-If no canonical value is available and you still want buildfix to apply:
-    buildfix apply --apply --allow-unsafe --param license="MIT OR Apache-2.0""#,
-        triggers: &[
-            TriggerPattern {
-                sensor: "cargo-deny",
-                check_id: "licenses.unlicensed",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo-deny",
-                check_id: "licenses.missing_license",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "deny",
-                check_id: "licenses.unlicensed",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "deny",
-                check_id: "licenses.missing_license",
-                code: None,
-            },
-            // Additional triggers from catalog
-            TriggerPattern {
-                sensor: "cargo-deny",
-                check_id: "licenses.missing",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "deny",
-                check_id: "license.unlicensed",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "deny",
-                check_id: "license.missing",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "deny",
-                check_id: "license.missing_license",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo-deny",
-                check_id: "cargo.licenses.unlicensed",
-                code: None,
-            },
-            TriggerPattern {
-                sensor: "cargo-deny",
-                check_id: "cargo.licenses.missing_license",
-                code: None,
-            },
-        ],
-    },
-];
+impl FixExplanation {
+    pub fn from_meta(meta: &FixerMeta) -> Self {
+        Self {
+            key: meta.key,
+            fix_id: meta.fix_key,
+            title: meta.title,
+            safety: meta.safety,
+            description: meta.details,
+            safety_rationale: meta.safety_rationale,
+            remediation: meta.remediation,
+            triggers: meta.triggers,
+        }
+    }
+}
 
 /// Look up an enabled fix explanation by key or fix_id.
 pub fn lookup_fix(query: &str) -> Option<&'static FixExplanation> {
@@ -586,6 +100,115 @@ pub fn policy_keys(fix: &FixExplanation) -> Vec<String> {
     keys.into_iter().collect()
 }
 
+/// Enabled fixes with a trigger from `sensor` and/or on `check_id`.
+///
+/// A sensor matches the way receipts do: `cargo-deny` selects triggers
+/// declared for `cargo-deny`, and so does a tool name such as
+/// `cargo-deny-advisories` that starts with it.
+pub fn filter_fixes(sensor: Option<&str>, check_id: Option<&str>) -> Vec<&'static FixExplanation> {
+    enabled_fixes()
+        .into_iter()
+        .filter(|fix| {
+            fix.triggers.iter().any(|t| {
+                sensor.is_none_or(|s| s.starts_with(t.sensor))
+                    && check_id.is_none_or(|c| c == t.check_id)
+            })
+        })
+        .collect()
+}
+
+/// Whether a fix could act on the receipts in an artifacts directory.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CoverageStatus {
+    /// Some receipt has a finding the fix triggers on.
+    Triggered,
+    /// A sensor it reads has reported, without a matching finding.
+    Ready,
+    /// None of the sensors it reads has a receipt.
+    Missing,
+}
+
+/// One row of `list-fixes --coverage`.
+#[derive(Debug, Clone)]
+pub struct FixCoverage {
+    pub fix: &'static FixExplanation,
+    pub status: CoverageStatus,
+    /// Findings matching one of the fix's triggers.
+    pub findings: usize,
+    /// Trigger sensors with no receipt.
+    pub missing_sensors: Vec<&'static str>,
+    /// Trigger check ids whose sensor reported but declares, in its
+    /// `capabilities.check_ids`, that it does not run them.
+    pub unchecked_check_ids: Vec<&'static str>,
+}
+
+/// Cross-reference `fix`'s triggers with loaded receipts.
+pub fn fix_coverage(fix: &'static FixExplanation, receipts: &[ReceiptEnvelope]) -> FixCoverage {
+    let mut findings = 0;
+    let mut missing_sensors = BTreeSet::new();
+    let mut unchecked_check_ids = BTreeSet::new();
+    for trigger in fix.triggers {
+        let from_sensor: Vec<&ReceiptEnvelope> = receipts
+            .iter()
+            .filter(|r| r.tool.name.starts_with(trigger.sensor))
+            .collect();
+        if from_sensor.is_empty() {
+            missing_sensors.insert(trigger.sensor);
+            continue;
+        }
+        let declared: Vec<&String> = from_sensor
+            .iter()
+            .filter_map(|r| r.capabilities.as_ref())
+            .flat_map(|c| &c.check_ids)
+            .collect();
+        if !declared.is_empty() && !declared.iter().any(|c| *c == trigger.check_id) {
+            unchecked_check_ids.insert(trigger.check_id);
+        }
+        findings += from_sensor
+            .iter()
+            .flat_map(|r| &r.findings)
+            .filter(|f| {
+                f.check_id.as_deref() == Some(trigger.check_id)
+                    && trigger
+                        .code
+                        .is_none_or(|code| f.code.as_deref() == Some(code))
+            })
+            .count();
+    }
+    let sensors: BTreeSet<&str> = fix.triggers.iter().map(|t| t.sensor).collect();
+    let status = if findings > 0 {
+        CoverageStatus::Triggered
+    } else if missing_sensors.len() < sensors.len() {
+        CoverageStatus::Ready
+    } else {
+        CoverageStatus::Missing
+    };
+    FixCoverage {
+        fix,
+        status,
+        findings,
+        missing_sensors: missing_sensors.into_iter().collect(),
+        unchecked_check_ids: unchecked_check_ids.into_iter().collect(),
+    }
+}
+
+/// The fix that plans ops under `policy_key` (a plan op's
+/// `rationale.fix_key`, e.g. `builddiag/workspace.resolver_v2/not_v2`).
+pub fn fix_for_policy_key(policy_key: &str) -> Option<&'static FixExplanation> {
+    let (prefix, _code) = policy_key.rsplit_once('/')?;
+    enabled_fixes().into_iter().find(|fix| {
+        policy_keys(fix)
+            .iter()
+            .any(|key| key == policy_key || *key == format!("{}/*", prefix))
+    })
+}
+
+const RULE: &str =
+    "================================================================================";
+const UNDERLINE: &str =
+    "--------------------------------------------------------------------------------";
+
 /// Format a safety class for display.
 pub fn format_safety_class(safety: SafetyClass) -> &'static str {
     match safety {
@@ -596,26 +219,251 @@ pub fn format_safety_class(safety: SafetyClass) -> &'static str {
 }
 
 /// Get a description of what a safety class means.
-pub fn safety_class_meaning(safety: SafetyClass) -> &'static str {
-    match safety {
-        SafetyClass::Safe => {
-            "SAFE fixes are fully determined from repo-local truth and have low impact.\n\
-             They are applied automatically with `buildfix apply --apply`."
-        }
-        SafetyClass::Guarded => {
-            "GUARDED fixes are deterministic but have higher impact.\n\
-             They require explicit approval with `buildfix apply --apply --allow-guarded`."
-        }
-        SafetyClass::Unsafe => {
-            "UNSAFE fixes are ambiguous without user-provided inputs.\n\
-             They require explicit `--allow-unsafe` and any required params to apply."
+pub fn safety_class_meaning(safety: SafetyClass, messages: &MessageCatalog) -> &str {
+    messages.get(match safety {
+        SafetyClass::Safe => codes::SAFETY_SAFE_MEANING,
+        SafetyClass::Guarded => codes::SAFETY_GUARDED_MEANING,
+        SafetyClass::Unsafe => codes::SAFETY_UNSAFE_MEANING,
+    })
+}
+
+/// A fix's registry text, unless `messages` overrides
+/// `explain.<key>.<field>` (`title`, `description`, `safety_rationale`,
+/// `remediation`).
+fn fix_text<'a>(
+    messages: &'a MessageCatalog,
+    fix: &'a FixExplanation,
+    field: &str,
+    default: &'a str,
+) -> &'a str {
+    messages
+        .override_for(&format!("explain.{}.{}", fix.key, field))
+        .unwrap_or(default)
+}
+
+/// Render the `buildfix explain` text for a fix.
+pub fn render_explanation(fix: &FixExplanation, messages: &MessageCatalog) -> String {
+    let m = messages;
+    let mut out = String::new();
+    let mut section = |title: &str, body: &str| {
+        out.push_str(&format!("{}\n{}\n{}\n\n", title, UNDERLINE, body));
+    };
+
+    let mut header = String::new();
+    header.push_str(&format!("{}\n", RULE));
+    header.push_str(&format!(
+        "{}: {}\n",
+        m.get(codes::EXPLAIN_FIX),
+        fix_text(m, fix, "title", fix.title)
+    ));
+    header.push_str(&format!("{}\n\n", RULE));
+    // Labels are padded to the widest one so values line up.
+    let labels = [
+        (codes::EXPLAIN_KEY, fix.key.to_string()),
+        (codes::EXPLAIN_FIX_ID, fix.fix_id.to_string()),
+        (codes::EXPLAIN_POLICY, policy_keys(fix).join(", ")),
+        (
+            codes::EXPLAIN_SAFETY,
+            format_safety_class(fix.safety).to_string(),
+        ),
+    ];
+    let width = labels
+        .iter()
+        .map(|(code, _)| m.get(code).chars().count() + 2)
+        .max()
+        .unwrap_or(0);
+    for (code, value) in &labels {
+        let label = format!("{}:", m.get(code));
+        header.push_str(&format!("{:<width$} {}\n", label, value, width = width));
+    }
+    header.push('\n');
+
+    section(
+        m.get(codes::EXPLAIN_DESCRIPTION),
+        fix_text(m, fix, "description", fix.description),
+    );
+
+    let mut triggers = format!("{}\n\n", m.get(codes::EXPLAIN_TRIGGERS_INTRO));
+    for trigger in fix.triggers {
+        let code_part = trigger
+            .code
+            .map(|c| format!(" / {}", c))
+            .unwrap_or_default();
+        triggers.push_str(&format!(
+            "  - {} / {}{}\n",
+            trigger.sensor, trigger.check_id, code_part
+        ));
+    }
+    section(m.get(codes::EXPLAIN_TRIGGERS), triggers.trim_end());
+
+    section(
+        &format!(
+            "{}: {}",
+            m.get(codes::EXPLAIN_SAFETY_CLASS),
+            format_safety_class(fix.safety)
+        ),
+        safety_class_meaning(fix.safety, m),
+    );
+    section(
+        m.get(codes::EXPLAIN_SAFETY_RATIONALE),
+        fix_text(m, fix, "safety_rationale", fix.safety_rationale),
+    );
+    section(
+        m.get(codes::EXPLAIN_REMEDIATION),
+        fix_text(m, fix, "remediation", fix.remediation),
+    );
+
+    format!("{}{}", header, out)
+}
+
+/// JSON form of [`render_explanation`], for `explain --format json`.
+pub fn fix_json(fix: &FixExplanation) -> serde_json::Value {
+    serde_json::json!({
+        "key": fix.key,
+        "fix_id": fix.fix_id,
+        "title": fix.title,
+        "safety": format_safety_class(fix.safety).to_lowercase(),
+        "policy_keys": policy_keys(fix),
+        "description": fix.description,
+        "safety_rationale": fix.safety_rationale,
+        "remediation": fix.remediation,
+        "triggers": fix.triggers,
+    })
+}
+
+/// Find the op `query` names in `plan`: its full id, or a prefix of exactly
+/// one op id. An ambiguous prefix is an error.
+pub fn find_op<'a>(plan: &'a BuildfixPlan, query: &str) -> anyhow::Result<Option<&'a PlanOp>> {
+    if let Some(op) = plan.ops.iter().find(|op| op.id == query) {
+        return Ok(Some(op));
+    }
+    let matches: Vec<&PlanOp> = plan
+        .ops
+        .iter()
+        .filter(|op| !query.is_empty() && op.id.starts_with(query))
+        .collect();
+    match matches.as_slice() {
+        [] => Ok(None),
+        [op] => Ok(Some(op)),
+        many => anyhow::bail!(
+            "op id prefix '{}' is ambiguous: {}",
+            query,
+            many.iter()
+                .map(|op| op.id.as_str())
+                .collect::<Vec<_>>()
+                .join(", ")
+        ),
+    }
+}
+
+/// A planned op (target, status, operation, findings and the diff it would
+/// make), followed by the explanation of its fix when that fix is known.
+///
+/// `preview` is the rendered diff, or why it could not be produced.
+pub fn render_op_explanation(
+    op: &PlanOp,
+    preview: Result<&str, &str>,
+    fix: Option<&FixExplanation>,
+    m: &MessageCatalog,
+) -> String {
+    let mut out = String::new();
+    out.push_str(&format!("{}\n", RULE));
+    out.push_str(&format!("{}: {}\n", m.get(codes::EXPLAIN_OP), op.id));
+    out.push_str(&format!("{}\n\n", RULE));
+
+    let status = if op.blocked {
+        let reason = m.blocked_reason(
+            op.blocked_reason_token,
+            op.blocked_reason.as_deref().unwrap_or_default(),
+        );
+        m.format(codes::EXPLAIN_OP_BLOCKED, &[("reason", &reason)])
+    } else {
+        m.get(codes::EXPLAIN_OP_READY).to_string()
+    };
+    let mut labels = vec![
+        (codes::EXPLAIN_POLICY, op.rationale.fix_key.clone()),
+        (codes::EXPLAIN_OP_TARGET, op.target.path.clone()),
+        (
+            codes::EXPLAIN_SAFETY,
+            format_safety_class(op.safety).to_string(),
+        ),
+        (codes::EXPLAIN_OP_STATUS, status),
+    ];
+    if !op.params_required.is_empty() {
+        labels.push((codes::EXPLAIN_OP_PARAMS, op.params_required.join(", ")));
+    }
+    let width = labels
+        .iter()
+        .map(|(code, _)| m.get(code).chars().count() + 2)
+        .max()
+        .unwrap_or(0);
+    for (code, value) in &labels {
+        let label = format!("{}:", m.get(code));
+        out.push_str(&format!("{:<width$} {}\n", label, value, width = width));
+    }
+    out.push('\n');
+
+    let mut section = |title: &str, body: &str| {
+        out.push_str(&format!("{}\n{}\n{}\n\n", title, UNDERLINE, body));
+    };
+    let kind = serde_json::to_string_pretty(&op.kind).unwrap_or_default();
+    section(m.get(codes::EXPLAIN_OP_KIND), &kind);
+
+    if !op.rationale.findings.is_empty() {
+        let mut findings = String::new();
+        for f in &op.rationale.findings {
+            let check = f.check_id.as_deref().unwrap_or("-");
+            let location = match (&f.path, f.line) {
+                (Some(path), Some(line)) => format!(" at {}:{}", path, line),
+                (Some(path), None) => format!(" at {}", path),
+                _ => String::new(),
+            };
+            findings.push_str(&format!(
+                "  - {} / {} / {}{}\n",
+                f.source, check, f.code, location
+            ));
         }
+        section(m.get(codes::EXPLAIN_OP_FINDINGS), findings.trim_end());
+    }
+
+    let preview = match preview {
+        Ok("") => m.get(codes::EXPLAIN_OP_NO_CHANGES).to_string(),
+        Ok(diff) => diff.trim_end().to_string(),
+        Err(reason) => m.format(codes::EXPLAIN_OP_NO_PREVIEW, &[("reason", &reason)]),
+    };
+    section(m.get(codes::EXPLAIN_OP_PREVIEW), &preview);
+
+    if let Some(fix) = fix {
+        out.push_str(&render_explanation(fix, m));
+    }
+    out
+}
+
+/// JSON form of [`render_op_explanation`].
+pub fn op_json(
+    op: &PlanOp,
+    preview: Result<&str, &str>,
+    fix: Option<&FixExplanation>,
+) -> serde_json::Value {
+    let (preview, preview_error) = match preview {
+        Ok(diff) => (Some(diff), None),
+        Err(reason) => (None, Some(reason)),
+    };
+    let mut value = serde_json::json!({
+        "op": op,
+        "preview": preview,
+        "fix": fix.map(fix_json),
+    });
+    if let Some(reason) = preview_error {
+        value["preview_error"] = reason.into();
     }
+    value
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use std::collections::HashSet;
 
     #[test]
     fn test_lookup_by_key() {
@@ -735,8 +583,160 @@ mod tests {
 
     #[test]
     fn test_safety_class_meaning_messages() {
-        assert!(safety_class_meaning(SafetyClass::Safe).contains("SAFE fixes"));
-        assert!(safety_class_meaning(SafetyClass::Guarded).contains("GUARDED fixes"));
-        assert!(safety_class_meaning(SafetyClass::Unsafe).contains("UNSAFE fixes"));
+        let messages = MessageCatalog::default();
+        assert!(safety_class_meaning(SafetyClass::Safe, &messages).contains("SAFE fixes"));
+        assert!(safety_class_meaning(SafetyClass::Guarded, &messages).contains("GUARDED fixes"));
+        assert!(safety_class_meaning(SafetyClass::Unsafe, &messages).contains("UNSAFE fixes"));
+    }
+
+    #[test]
+    fn test_render_explanation_uses_catalog() {
+        let fix = lookup_fix("resolver-v2").unwrap();
+        let english = render_explanation(fix, &MessageCatalog::default());
+        assert!(english.contains("FIX: Workspace Resolver V2\n"));
+        assert!(english.contains("Key:     resolver-v2\n"));
+        assert!(english.contains("\nDESCRIPTION\n---"));
+
+        let messages = MessageCatalog::with_overrides([
+            ("explain.fix", "CORRECTIF"),
+            ("explain.description", "DESCRIPTION DÉTAILLÉE"),
+            ("explain.resolver-v2.title", "Résolveur v2"),
+        ]);
+        let localized = render_explanation(fix, &messages);
+        assert!(localized.contains("CORRECTIF: Résolveur v2\n"));
+        assert!(localized.contains("\nDESCRIPTION DÉTAILLÉE\n---"));
+        // Codes without an override keep the English text.
+        assert!(localized.contains("\nREMEDIATION GUIDANCE\n"));
+    }
+
+    fn plan_with_ops(ids: &[&str]) -> BuildfixPlan {
+        use buildfix_types::ops::{OpKind, OpTarget};
+        use buildfix_types::plan::{PlanPolicy, Rationale, RepoInfo};
+        use buildfix_types::receipt::ToolInfo;
+
+        let mut plan = BuildfixPlan::new(
+            ToolInfo {
+                name: "buildfix".to_string(),
+                version: None,
+                repo: None,
+                commit: None,
+            },
+            RepoInfo {
+                root: ".".to_string(),
+                head_sha: None,
+                dirty: None,
+                remote_url: None,
+                ci: None,
+            },
+            PlanPolicy::default(),
+        );
+        plan.ops = ids
+            .iter()
+            .map(|id| PlanOp {
+                id: id.to_string(),
+                safety: SafetyClass::Safe,
+                blocked: false,
+                blocked_reason: None,
+                blocked_reason_token: None,
+                target: OpTarget {
+                    path: "Cargo.toml".to_string(),
+                    sha256: None,
+                },
+                kind: OpKind::TomlSet {
+                    toml_path: vec!["workspace".to_string(), "resolver".to_string()],
+                    value: serde_json::json!("2"),
+                    insert: None,
+                },
+                rationale: Rationale {
+                    fix_key: "builddiag/workspace.resolver_v2/not_v2".to_string(),
+                    description: None,
+                    findings: vec![],
+                },
+                params_required: vec![],
+                preview: None,
+                impact: None,
+                workspace: None,
+            })
+            .collect();
+        plan
+    }
+
+    #[test]
+    fn test_find_op_by_id_or_unique_prefix() {
+        let plan = plan_with_ops(&["abc123", "abd456", "abc"]);
+        assert_eq!(find_op(&plan, "abc").unwrap().unwrap().id, "abc");
+        assert_eq!(find_op(&plan, "abd").unwrap().unwrap().id, "abd456");
+        assert!(find_op(&plan, "zzz").unwrap().is_none());
+        let err = find_op(&plan, "ab").unwrap_err().to_string();
+        assert!(err.contains("ambiguous"), "{}", err);
+    }
+
+    #[test]
+    fn test_render_op_explanation() {
+        let plan = plan_with_ops(&["op-1"]);
+        let op = &plan.ops[0];
+        let fix = fix_for_policy_key(&op.rationale.fix_key);
+        assert_eq!(fix.unwrap().key, "resolver-v2");
+        let messages = MessageCatalog::default();
+
+        let text = render_op_explanation(op, Ok("--- a/Cargo.toml\n"), fix, &messages);
+        assert!(text.starts_with(&format!("{}\nOP: op-1\n", RULE)));
+        assert!(text.contains("Target:  Cargo.toml\n"));
+        assert!(text.contains("Status:  ready\n"));
+        assert!(text.contains("\nDIFF PREVIEW\n---"));
+        assert!(text.contains("--- a/Cargo.toml"));
+        assert!(text.contains("FIX: Workspace Resolver V2\n"));
+
+        let text = render_op_explanation(op, Err("target missing"), None, &messages);
+        assert!(text.contains("No preview: target missing"));
+        assert!(!text.contains("FIX:"));
+
+        let json = op_json(op, Ok(""), fix);
+        assert_eq!(json["op"]["id"], "op-1");
+        assert_eq!(json["preview"], "");
+        assert_eq!(json["fix"]["key"], "resolver-v2");
+        assert!(json.get("preview_error").is_none());
+    }
+
+    #[test]
+    fn test_fix_coverage_statuses() {
+        let receipt = |json: &str| -> ReceiptEnvelope { serde_json::from_str(json).unwrap() };
+        let fix = lookup_fix("resolver-v2").unwrap();
+
+        let none = fix_coverage(fix, &[]);
+        assert_eq!(none.status, CoverageStatus::Missing);
+        assert_eq!(none.missing_sensors, vec!["builddiag", "cargo"]);
+
+        let clean = receipt(
+            r#"{"schema": "builddiag.report.v1", "tool": {"name": "builddiag"},
+                "capabilities": {"check_ids": ["rust.msrv_consistent"]}}"#,
+        );
+        let ready = fix_coverage(fix, std::slice::from_ref(&clean));
+        assert_eq!(ready.status, CoverageStatus::Ready);
+        assert_eq!(ready.missing_sensors, vec!["cargo"]);
+        assert_eq!(ready.unchecked_check_ids, vec!["workspace.resolver_v2"]);
+
+        let failing = receipt(
+            r#"{"schema": "builddiag.report.v1", "tool": {"name": "builddiag"},
+                "findings": [{"check_id": "workspace.resolver_v2", "code": "not_v2"}]}"#,
+        );
+        let triggered = fix_coverage(fix, &[failing]);
+        assert_eq!(triggered.status, CoverageStatus::Triggered);
+        assert_eq!(triggered.findings, 1);
+    }
+
+    #[test]
+    fn test_filter_fixes() {
+        let keys = |fixes: Vec<&FixExplanation>| fixes.iter().map(|f| f.key).collect::<Vec<_>>();
+        assert_eq!(keys(filter_fixes(None, None)).len(), enabled_fixes().len());
+        assert!(keys(filter_fixes(Some("builddiag"), None)).contains(&"resolver-v2"));
+        assert!(!keys(filter_fixes(Some("depguard"), None)).contains(&"resolver-v2"));
+        assert_eq!(
+            keys(filter_fixes(
+                Some("builddiag"),
+                Some("workspace.resolver_v2")
+            )),
+            vec!["resolver-v2"]
+        );
     }
 }
@@ -0,0 +1,491 @@
+//! `extends`: layer `buildfix.toml` over a parent-directory or remote base.
+//!
+//! Bases are merged beneath the file that extends them: tables merge key by
+//! key, and any other value (arrays included) set locally replaces the base's.
+
+use crate::config::CONFIG_FILE_NAME;
+use anyhow::Context;
+use buildfix_receipts::{SIGNATURE_SUFFIX, SignatureStatus, TrustMode, TrustPolicy};
+use buildfix_types::telemetry;
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use serde::Deserialize;
+use std::time::{Duration, SystemTime};
+use tracing::{debug, warn};
+
+/// Remote bases, cached beside the local config's default out dir.
+pub const EXTENDS_CACHE_DIR: &str = "artifacts/buildfix/cache/extends";
+
+/// Cached remote bases younger than this are used without refetching.
+const CACHE_TTL: Duration = Duration::from_secs(60 * 60);
+
+const FETCH_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Longest `extends` chain followed before giving up.
+const MAX_DEPTH: usize = 8;
+
+/// The `extends` key: a bare source, or a source with the keys its
+/// detached minisign signature must verify against.
+#[derive(Debug, Clone, Deserialize)]
+#[serde(untagged)]
+pub enum ExtendsConfig {
+    Source(String),
+    Signed(SignedExtends),
+}
+
+#[derive(Debug, Clone, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct SignedExtends {
+    /// Directory, file path or `https://` URL of the base config; an
+    /// `http://` URL is only accepted together with keys.
+    pub source: String,
+
+    /// Minisign public keys (base64) the base must be signed with.
+    #[serde(default)]
+    pub public_keys: Vec<String>,
+
+    /// Files holding minisign public keys, relative to the local config.
+    #[serde(default)]
+    pub public_key_files: Vec<Utf8PathBuf>,
+}
+
+impl ExtendsConfig {
+    pub fn source(&self) -> &str {
+        match self {
+            ExtendsConfig::Source(source) => source,
+            ExtendsConfig::Signed(signed) => &signed.source,
+        }
+    }
+
+    /// Keys the base must be signed with; `None` when the base may be unsigned.
+    fn trust(&self, config_dir: &Utf8Path) -> anyhow::Result<Option<TrustPolicy>> {
+        let ExtendsConfig::Signed(signed) = self else {
+            return Ok(None);
+        };
+        if signed.public_keys.is_empty() && signed.public_key_files.is_empty() {
+            return Ok(None);
+        }
+        let mut trust = TrustPolicy::new(TrustMode::Strict);
+        for key in &signed.public_keys {
+            trust = trust.with_public_key(key)?;
+        }
+        for file in &signed.public_key_files {
+            trust = trust.with_public_key_file(&config_dir.join(file))?;
+        }
+        Ok(Some(trust))
+    }
+}
+
+/// Where a config layer came from.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum Origin {
+    File(Utf8PathBuf),
+    Url(String),
+}
+
+impl std::fmt::Display for Origin {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Origin::File(path) => write!(f, "{}", path),
+            Origin::Url(url) => write!(f, "{}", url),
+        }
+    }
+}
+
+/// The config at `path` (already read into `local`) with its `extends`
+/// chain merged beneath it.
+pub fn resolve(path: &Utf8Path, local: toml::Table) -> anyhow::Result<toml::Table> {
+    let config_dir = match path.parent() {
+        Some(dir) if !dir.as_str().is_empty() => dir.to_path_buf(),
+        _ => Utf8PathBuf::from("."),
+    };
+    let cache_dir = config_dir.join(EXTENDS_CACHE_DIR);
+
+    let mut seen = vec![Origin::File(canonical(path))];
+    let mut layers = vec![local];
+    while let Some(value) = layers.last().and_then(|layer| layer.get("extends")) {
+        let declared_in = seen.last().cloned().expect("origin of every layer");
+        let extends: ExtendsConfig = value
+            .clone()
+            .try_into()
+            .with_context(|| format!("invalid extends in {}", declared_in))?;
+        let origin = locate(&declared_in, extends.source())?;
+        if seen.contains(&origin) {
+            anyhow::bail!("extends cycle: {} is already in the chain", origin);
+        }
+        if seen.len() > MAX_DEPTH {
+            anyhow::bail!("extends chain is deeper than {} bases", MAX_DEPTH);
+        }
+
+        debug!(
+            event = telemetry::CONFIG_EXTENDS,
+            file = %declared_in,
+            origin = %origin,
+            "extending {} with {}",
+            declared_in,
+            origin
+        );
+        let trust = extends.trust(&config_dir)?;
+        if trust.is_none() && matches!(&origin, Origin::Url(url) if url.starts_with("http://")) {
+            anyhow::bail!(
+                "extends '{}' in {}: http:// bases must be signed; use https:// or set public_keys",
+                origin,
+                declared_in
+            );
+        }
+        let bytes = read_base(&origin, &cache_dir)?;
+        if let Some(trust) = trust {
+            let sig = read_signature(&origin, &cache_dir)?;
+            match trust.verify_detached(&bytes, sig.as_deref()) {
+                SignatureStatus::Verified => {}
+                status => anyhow::bail!(
+                    "base config {} signature is {}{}",
+                    origin,
+                    status.as_str(),
+                    status
+                        .reason()
+                        .map(|r| format!(": {}", r))
+                        .unwrap_or_default()
+                ),
+            }
+        }
+        let text = String::from_utf8(bytes)
+            .with_context(|| format!("base config {} is not UTF-8", origin))?;
+        let layer: toml::Table =
+            toml::from_str(&text).with_context(|| format!("parse base config {}", origin))?;
+        layers.push(layer);
+        seen.push(origin);
+    }
+
+    let mut layers = layers.into_iter().rev();
+    let mut merged = layers.next().unwrap_or_default();
+    for layer in layers {
+        merged.remove("extends");
+        merge(&mut merged, layer);
+    }
+    Ok(merged)
+}
+
+/// Resolve `source` as declared in the layer from `declared_in`.
+fn locate(declared_in: &Origin, source: &str) -> anyhow::Result<Origin> {
+    if source.starts_with("https://") || source.starts_with("http://") {
+        return Ok(Origin::Url(source.to_string()));
+    }
+    let Origin::File(file) = declared_in else {
+        anyhow::bail!(
+            "remote base {} can only extend another URL, not '{}'",
+            declared_in,
+            source
+        );
+    };
+    let mut path = file.parent().unwrap_or(Utf8Path::new(".")).join(source);
+    if path.is_dir() {
+        path.push(CONFIG_FILE_NAME);
+    }
+    if !path.is_file() {
+        anyhow::bail!("extends '{}' in {}: {} not found", source, file, path);
+    }
+    Ok(Origin::File(canonical(&path)))
+}
+
+fn canonical(path: &Utf8Path) -> Utf8PathBuf {
+    path.canonicalize_utf8()
+        .unwrap_or_else(|_| path.to_path_buf())
+}
+
+fn read_base(origin: &Origin, cache_dir: &Utf8Path) -> anyhow::Result<Vec<u8>> {
+    match origin {
+        Origin::File(path) => Ok(fs::read(path)?),
+        Origin::Url(url) => {
+            cached_fetch(url, cache_dir)?.with_context(|| format!("GET {} returned HTTP 404", url))
+        }
+    }
+}
+
+/// The detached signature next to `origin`, if there is one.
+fn read_signature(origin: &Origin, cache_dir: &Utf8Path) -> anyhow::Result<Option<String>> {
+    let bytes = match origin {
+        Origin::File(path) => match fs::read(format!("{}{}", path, SIGNATURE_SUFFIX)) {
+            Ok(bytes) => Some(bytes),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => None,
+            Err(e) => return Err(e.into()),
+        },
+        Origin::Url(url) => cached_fetch(&format!("{}{}", url, SIGNATURE_SUFFIX), cache_dir)?,
+    };
+    Ok(bytes.map(|b| String::from_utf8_lossy(&b).into_owned()))
+}
+
+/// GET `url`, reusing a cached copy younger than [`CACHE_TTL`] and falling
+/// back to an older one when the fetch fails. `None` on HTTP 404.
+fn cached_fetch(url: &str, cache_dir: &Utf8Path) -> anyhow::Result<Option<Vec<u8>>> {
+    let cached = cache_dir.join(buildfix_hash::sha256_hex(url.as_bytes()));
+    let age = fs::metadata(&cached)
+        .and_then(|meta| meta.modified())
+        .ok()
+        .and_then(|modified| SystemTime::now().duration_since(modified).ok());
+    if age.is_some_and(|age| age < CACHE_TTL) {
+        debug!(
+            event = telemetry::CONFIG_EXTENDS_CACHED,
+            file = url,
+            outcome = "fresh",
+            "using cached {}",
+            url
+        );
+        return Ok(Some(fs::read(&cached)?));
+    }
+
+    match fetch(url) {
+        Ok(Some(body)) => {
+            if let Err(e) = fs::create_dir_all(cache_dir).and_then(|()| fs::write(&cached, &body)) {
+                warn!(
+                    event = telemetry::CONFIG_EXTENDS_CACHE_FAILED,
+                    file = url,
+                    error = %e,
+                    "failed to cache {}: {}",
+                    url,
+                    e
+                );
+            }
+            Ok(Some(body))
+        }
+        Ok(None) => Ok(None),
+        Err(e) if age.is_some() => {
+            warn!(
+                event = telemetry::CONFIG_EXTENDS_CACHED,
+                file = url,
+                outcome = "stale",
+                error = %e,
+                "{}; using cached copy",
+                e
+            );
+            Ok(Some(fs::read(&cached)?))
+        }
+        Err(e) => Err(anyhow::anyhow!(e)),
+    }
+}
+
+fn fetch(url: &str) -> Result<Option<Vec<u8>>, String> {
+    use std::io::Read;
+
+    let agent = ureq::AgentBuilder::new().timeout(FETCH_TIMEOUT).build();
+    let response = match agent.get(url).call() {
+        Ok(response) => response,
+        Err(ureq::Error::Status(404, _)) => return Ok(None),
+        Err(ureq::Error::Status(code, _)) => {
+            return Err(format!("GET {} returned HTTP {}", url, code));
+        }
+        Err(other) => return Err(format!("GET {}: {}", url, other)),
+    };
+    let mut body = Vec::new();
+    response
+        .into_reader()
+        .read_to_end(&mut body)
+        .map_err(|e| format!("read body of {}: {}", url, e))?;
+    Ok(Some(body))
+}
+
+/// Merge `overlay` into `base`: tables recursively, everything else replaced.
+fn merge(base: &mut toml::Table, overlay: toml::Table) {
+    for (key, value) in overlay {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(overlay_table)) => {
+                merge(base_table, overlay_table)
+            }
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::load_config;
+    use base64::Engine as _;
+    use base64::engine::general_purpose::STANDARD as BASE64;
+    use ed25519_dalek::{Signer, SigningKey};
+
+    fn write(root: &Utf8Path, rel: &str, contents: &str) -> Utf8PathBuf {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(&path, contents).unwrap();
+        path
+    }
+
+    /// Serve `body` once per entry, then stop accepting.
+    fn serve(bodies: Vec<String>) -> String {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        std::thread::spawn(move || {
+            for body in bodies {
+                let (mut stream, _) = listener.accept().unwrap();
+                let mut reader = BufReader::new(stream.try_clone().unwrap());
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).unwrap();
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                }
+                write!(
+                    stream,
+                    "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    body.len(),
+                    body
+                )
+                .unwrap();
+            }
+        });
+        base
+    }
+
+    const KEY_ID: [u8; 8] = [7, 6, 5, 4, 3, 2, 1, 0];
+
+    fn minisign_public_key(key: &SigningKey) -> String {
+        let mut bin = b"Ed".to_vec();
+        bin.extend_from_slice(&KEY_ID);
+        bin.extend_from_slice(key.verifying_key().as_bytes());
+        BASE64.encode(bin)
+    }
+
+    /// Legacy (non-prehashed) minisign signature of `message`.
+    fn minisign_signature(key: &SigningKey, message: &[u8]) -> String {
+        let sig = key.sign(message).to_bytes();
+        let mut bin = b"Ed".to_vec();
+        bin.extend_from_slice(&KEY_ID);
+        bin.extend_from_slice(&sig);
+
+        let trusted = "timestamp:0\tfile:buildfix-org.toml";
+        let mut global = sig.to_vec();
+        global.extend_from_slice(trusted.as_bytes());
+        let global_sig = key.sign(&global).to_bytes();
+
+        format!(
+            "untrusted comment: test\n{}\ntrusted comment: {}\n{}\n",
+            BASE64.encode(bin),
+            trusted,
+            BASE64.encode(global_sig)
+        )
+    }
+
+    #[test]
+    fn parent_directory_base_is_merged_beneath_local() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        write(
+            root,
+            "buildfix.toml",
+            "[policy]\ndeny = [\"cargo.*\"]\nmax_ops = 10\nmax_files = 5\n\n[params]\nrust_version = \"1.70\"\n",
+        );
+        let local = write(
+            root,
+            "repo/buildfix.toml",
+            "extends = \"..\"\n\n[policy]\nmax_ops = 3\n\n[params]\nedition = \"2021\"\n",
+        );
+
+        let config = load_config(&local).unwrap();
+        assert_eq!(config.policy.deny, vec!["cargo.*"]);
+        assert_eq!(config.policy.max_ops, Some(3));
+        assert_eq!(config.policy.max_files, Some(5));
+        assert_eq!(config.params.get("rust_version").unwrap(), "1.70");
+        assert_eq!(config.params.get("edition").unwrap(), "2021");
+    }
+
+    #[test]
+    fn extends_cycles_and_missing_bases_are_errors() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let a = write(root, "a/buildfix.toml", "extends = \"../b\"\n");
+        write(
+            root,
+            "b/buildfix.toml",
+            "extends = \"../a/buildfix.toml\"\n",
+        );
+        let err = format!("{:#}", load_config(&a).unwrap_err());
+        assert!(err.contains("extends cycle"), "{}", err);
+
+        let missing = write(root, "c/buildfix.toml", "extends = \"../nope\"\n");
+        let err = format!("{:#}", load_config(&missing).unwrap_err());
+        assert!(err.contains("not found"), "{}", err);
+    }
+
+    #[test]
+    fn remote_base_is_cached() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let base_contents = "[policy]\nmax_ops = 7\n";
+        let base = serve(vec![
+            base_contents.to_string(),
+            minisign_signature(&key, base_contents.as_bytes()),
+        ]);
+        let local = write(
+            root,
+            "buildfix.toml",
+            &format!(
+                "[extends]\nsource = \"{}/buildfix-org.toml\"\npublic_keys = [\"{}\"]\n",
+                base,
+                minisign_public_key(&key)
+            ),
+        );
+
+        assert_eq!(load_config(&local).unwrap().policy.max_ops, Some(7));
+        // The server answered once per file; the second load reads the cache.
+        assert_eq!(load_config(&local).unwrap().policy.max_ops, Some(7));
+        let cached = std::fs::read_dir(root.join(EXTENDS_CACHE_DIR)).unwrap();
+        assert_eq!(cached.count(), 2);
+    }
+
+    #[test]
+    fn unsigned_http_base_is_rejected() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let local = write(
+            root,
+            "buildfix.toml",
+            "extends = \"http://example.invalid/buildfix-org.toml\"\n",
+        );
+
+        let err = format!("{:#}", load_config(&local).unwrap_err());
+        assert!(err.contains("http:// bases must be signed"), "{}", err);
+        assert!(!root.join(EXTENDS_CACHE_DIR).exists());
+    }
+
+    #[test]
+    fn signed_base_must_verify() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let base_contents = "[policy]\nmax_ops = 2\n";
+        let base = write(root, "org/buildfix.toml", base_contents);
+        let local = write(
+            root,
+            "repo/buildfix.toml",
+            &format!(
+                "[extends]\nsource = \"../org\"\npublic_keys = [\"{}\"]\n",
+                minisign_public_key(&key)
+            ),
+        );
+
+        let err = format!("{:#}", load_config(&local).unwrap_err());
+        assert!(err.contains("signature is unsigned"), "{}", err);
+
+        std::fs::write(
+            format!("{}.minisig", base),
+            minisign_signature(&key, b"[policy]\nmax_ops = 99\n"),
+        )
+        .unwrap();
+        let err = format!("{:#}", load_config(&local).unwrap_err());
+        assert!(err.contains("signature is invalid"), "{}", err);
+
+        std::fs::write(
+            format!("{}.minisig", base),
+            minisign_signature(&key, base_contents.as_bytes()),
+        )
+        .unwrap();
+        assert_eq!(load_config(&local).unwrap().policy.max_ops, Some(2));
+    }
+}
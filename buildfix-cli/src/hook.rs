@@ -0,0 +1,242 @@
+//! `buildfix hook`: git pre-commit / pre-push checks.
+//!
+//! `hook install` writes a small shell script into the repository's hooks
+//! directory that calls `buildfix hook run`. The run plans against the
+//! staged files (pre-commit) or the files changed since the upstream base
+//! (pre-push), prints a compact summary and fails while safe fixes are
+//! left unapplied.
+
+use anyhow::Context;
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use std::fmt::Write as _;
+
+/// Marks a hook script as ours, so reinstalling may overwrite it.
+const MARKER: &str = "# Installed by `buildfix hook install`.";
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum HookKind {
+    PreCommit,
+    PrePush,
+}
+
+impl HookKind {
+    /// The git hook file name.
+    pub fn name(self) -> &'static str {
+        match self {
+            HookKind::PreCommit => "pre-commit",
+            HookKind::PrePush => "pre-push",
+        }
+    }
+}
+
+/// The hook script. `repo_prefix` is the buildfix repo root relative to the
+/// git top level (empty at the top level), where git runs hooks from.
+pub fn script(kind: HookKind, repo_prefix: &str) -> String {
+    let mut run = format!(
+        "\"${{BUILDFIX:-buildfix}}\" hook run --hook {}",
+        kind.name()
+    );
+    if !repo_prefix.is_empty() {
+        let _ = write!(
+            run,
+            " --repo-root '{}'",
+            repo_prefix.trim_end_matches('/').replace('\'', r"'\''")
+        );
+    }
+    format!("#!/bin/sh\n{MARKER}\n# Set BUILDFIX to use another binary.\nexec {run}\n")
+}
+
+/// Write the `kind` hook for the repository containing `repo_root` and
+/// return its path. An existing hook that buildfix did not install is only
+/// replaced with `force`.
+pub fn install(repo_root: &Utf8Path, kind: HookKind, force: bool) -> anyhow::Result<Utf8PathBuf> {
+    let hooks_path = git(repo_root, &["rev-parse", "--git-path", "hooks"])?;
+    let prefix = git(repo_root, &["rev-parse", "--show-prefix"])?;
+    let hooks_dir = Utf8PathBuf::from(hooks_path);
+    let hooks_dir = if hooks_dir.is_absolute() {
+        hooks_dir
+    } else {
+        repo_root.join(hooks_dir)
+    };
+    let path = hooks_dir.join(kind.name());
+
+    if let Ok(existing) = fs::read_to_string(&path)
+        && !existing.contains(MARKER)
+        && !force
+    {
+        anyhow::bail!(
+            "{} exists and was not installed by buildfix (use --force to replace it)",
+            path
+        );
+    }
+    fs::create_dir_all(&hooks_dir)?;
+    fs::write(&path, script(kind, &prefix))?;
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(&path, std::fs::Permissions::from_mode(0o755))?;
+    }
+    Ok(path)
+}
+
+fn git(repo_root: &Utf8Path, args: &[&str]) -> anyhow::Result<String> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .with_context(|| format!("git {} in {}", args.join(" "), repo_root))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed in {}: {}",
+            args.join(" "),
+            repo_root,
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Safe, unblocked ops: what `buildfix apply --apply` would write.
+pub fn pending_safe_ops(plan: &BuildfixPlan) -> Vec<&PlanOp> {
+    plan.ops
+        .iter()
+        .filter(|op| op.safety == SafetyClass::Safe && !op.blocked)
+        .collect()
+}
+
+/// A few lines for the terminal: one per pending safe op, a count of ops
+/// that need review, and how to clear the hook.
+pub fn render_summary(plan: &BuildfixPlan, kind: HookKind) -> String {
+    let pending = pending_safe_ops(plan);
+    let review = plan
+        .ops
+        .iter()
+        .filter(|op| op.safety != SafetyClass::Safe && !op.blocked)
+        .count();
+
+    let mut out = String::new();
+    if pending.is_empty() {
+        let _ = writeln!(out, "buildfix {}: no safe fixes pending", kind.name());
+    } else {
+        let _ = writeln!(
+            out,
+            "buildfix {}: {} safe fix{} pending",
+            kind.name(),
+            pending.len(),
+            if pending.len() == 1 { "" } else { "es" }
+        );
+        for op in &pending {
+            let _ = write!(out, "  {}  {}", op.target.path, op.rationale.fix_key);
+            if let Some(description) = &op.rationale.description {
+                let _ = write!(out, "  {}", description);
+            }
+            out.push('\n');
+        }
+    }
+    if review > 0 {
+        let _ = writeln!(
+            out,
+            "  {} guarded/unsafe {} review (buildfix explain)",
+            review,
+            if review == 1 { "op needs" } else { "ops need" }
+        );
+    }
+    if !pending.is_empty() {
+        let restage = match kind {
+            HookKind::PreCommit => ", then stage the changes",
+            HookKind::PrePush => ", then commit the changes",
+        };
+        let _ = writeln!(out, "run `buildfix apply --apply`{}", restage);
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buildfix_types::ops::{OpKind, OpTarget};
+    use buildfix_types::plan::{PlanPolicy, Rationale, RepoInfo};
+    use buildfix_types::receipt::ToolInfo;
+
+    fn op(path: &str, safety: SafetyClass, blocked: bool) -> PlanOp {
+        PlanOp {
+            id: path.into(),
+            safety,
+            blocked,
+            blocked_reason: None,
+            blocked_reason_token: None,
+            target: OpTarget {
+                path: path.into(),
+                sha256: None,
+            },
+            kind: OpKind::TomlTransform {
+                rule_id: "ensure_workspace_resolver_v2".into(),
+                args: None,
+            },
+            rationale: Rationale {
+                fix_key: "builddiag/workspace.resolver_v2/not_v2".into(),
+                description: Some("Set resolver = \"2\"".into()),
+                findings: vec![],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: None,
+            workspace: None,
+        }
+    }
+
+    fn plan(ops: Vec<PlanOp>) -> BuildfixPlan {
+        let mut plan = BuildfixPlan::new(
+            ToolInfo {
+                name: "buildfix".into(),
+                version: None,
+                repo: None,
+                commit: None,
+            },
+            RepoInfo {
+                root: ".".into(),
+                head_sha: None,
+                dirty: None,
+                remote_url: None,
+                ci: None,
+            },
+            PlanPolicy::default(),
+        );
+        plan.ops = ops;
+        plan
+    }
+
+    #[test]
+    fn summary_lists_pending_safe_ops_only() {
+        let plan = plan(vec![
+            op("Cargo.toml", SafetyClass::Safe, false),
+            op("crates/a/Cargo.toml", SafetyClass::Safe, true),
+            op("crates/b/Cargo.toml", SafetyClass::Guarded, false),
+        ]);
+        assert_eq!(pending_safe_ops(&plan).len(), 1);
+        assert_eq!(
+            render_summary(&plan, HookKind::PreCommit),
+            "buildfix pre-commit: 1 safe fix pending\n\
+             \x20 Cargo.toml  builddiag/workspace.resolver_v2/not_v2  Set resolver = \"2\"\n\
+             \x20 1 guarded/unsafe op needs review (buildfix explain)\n\
+             run `buildfix apply --apply`, then stage the changes\n"
+        );
+        assert_eq!(
+            render_summary(&self::plan(vec![]), HookKind::PrePush),
+            "buildfix pre-push: no safe fixes pending\n"
+        );
+    }
+
+    #[test]
+    fn script_runs_from_the_repo_prefix() {
+        let script = script(HookKind::PrePush, "sub/dir/");
+        assert!(script.starts_with("#!/bin/sh\n"));
+        assert!(script.contains(MARKER));
+        assert!(script.ends_with(
+            "exec \"${BUILDFIX:-buildfix}\" hook run --hook pre-push --repo-root 'sub/dir'\n"
+        ));
+    }
+}
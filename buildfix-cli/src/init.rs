@@ -0,0 +1,312 @@
+//! `buildfix init`: scaffold a commented `buildfix.toml`.
+//!
+//! Reads the root manifest (and member manifests when the root declares no
+//! MSRV) so the generated caps and params start from the workspace as it is.
+
+use anyhow::Context;
+use buildfix_cli::explain::{FixExplanation, enabled_fixes, format_safety_class, policy_keys};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use std::collections::BTreeSet;
+use std::fmt::Write as _;
+
+/// Facts read from the workspace manifests.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct WorkspaceFacts {
+    /// The root manifest has a `[workspace]` table.
+    pub is_workspace: bool,
+    /// `workspace.resolver` or `package.resolver`.
+    pub resolver: Option<String>,
+    /// Root `rust-version`, or the one every member that declares it agrees on.
+    pub msrv: Option<String>,
+    /// Distinct `rust-version` values when members disagree.
+    pub mixed_msrvs: Vec<String>,
+    pub edition: Option<String>,
+    /// Member directories with a `Cargo.toml`, after globs and excludes.
+    pub members: usize,
+}
+
+/// Read `<repo_root>/Cargo.toml`. A missing manifest yields empty facts.
+pub fn detect(repo_root: &Utf8Path) -> anyhow::Result<WorkspaceFacts> {
+    let manifest_path = repo_root.join("Cargo.toml");
+    if !manifest_path.is_file() {
+        return Ok(WorkspaceFacts::default());
+    }
+    let root = read_manifest(&manifest_path)?;
+    let workspace = root.get("workspace");
+    let ws_package = workspace.and_then(|w| w.get("package"));
+    let package = root.get("package");
+    let field = |key: &str| {
+        ws_package
+            .and_then(|p| p.get(key))
+            .or_else(|| package.and_then(|p| p.get(key)))
+            .and_then(toml::Value::as_str)
+            .map(str::to_string)
+    };
+
+    let members = workspace
+        .map(|w| member_dirs(repo_root, w))
+        .unwrap_or_default();
+    let mut facts = WorkspaceFacts {
+        is_workspace: workspace.is_some(),
+        resolver: workspace
+            .and_then(|w| w.get("resolver"))
+            .or_else(|| package.and_then(|p| p.get("resolver")))
+            .and_then(toml::Value::as_str)
+            .map(str::to_string),
+        msrv: field("rust-version"),
+        mixed_msrvs: Vec::new(),
+        edition: field("edition"),
+        members: members.len(),
+    };
+
+    if facts.msrv.is_none() {
+        let declared: BTreeSet<String> = members
+            .iter()
+            .filter_map(|dir| read_manifest(&dir.join("Cargo.toml")).ok())
+            .filter_map(|m| {
+                m.get("package")?
+                    .get("rust-version")?
+                    .as_str()
+                    .map(str::to_string)
+            })
+            .collect();
+        if declared.len() == 1 {
+            facts.msrv = declared.into_iter().next();
+        } else {
+            facts.mixed_msrvs = declared.into_iter().collect();
+        }
+    }
+    Ok(facts)
+}
+
+fn read_manifest(path: &Utf8Path) -> anyhow::Result<toml::Value> {
+    let contents = fs::read_to_string(path)?;
+    toml::from_str(&contents).with_context(|| format!("parse {}", path))
+}
+
+fn member_dirs(repo_root: &Utf8Path, workspace: &toml::Value) -> Vec<Utf8PathBuf> {
+    let patterns = |key: &str| -> Vec<String> {
+        workspace
+            .get(key)
+            .and_then(toml::Value::as_array)
+            .map(|a| {
+                a.iter()
+                    .filter_map(|v| v.as_str().map(str::to_string))
+                    .collect()
+            })
+            .unwrap_or_default()
+    };
+    let expand = |pattern: &str| -> Vec<Utf8PathBuf> {
+        let Ok(paths) = glob::glob(repo_root.join(pattern).as_str()) else {
+            return Vec::new();
+        };
+        paths
+            .filter_map(Result::ok)
+            .filter_map(|p| Utf8PathBuf::from_path_buf(p).ok())
+            .collect()
+    };
+
+    let excluded: BTreeSet<Utf8PathBuf> =
+        patterns("exclude").iter().flat_map(|p| expand(p)).collect();
+    let members: BTreeSet<Utf8PathBuf> = patterns("members")
+        .iter()
+        .flat_map(|p| expand(p))
+        .filter(|dir| dir.join("Cargo.toml").is_file() && !excluded.contains(dir))
+        .collect();
+    members.into_iter().collect()
+}
+
+/// The generated file. Every key is present; those without a detected value
+/// are commented out at their default.
+pub fn render(facts: &WorkspaceFacts) -> String {
+    // One manifest per member plus the root, with headroom for lockfile edits.
+    let max_files = (facts.members as u64 + 1).max(25);
+    let max_ops = (facts.members as u64 * 2).max(50);
+
+    let mut out = String::new();
+    out.push_str("# buildfix.toml - generated by `buildfix init`.\n");
+    out.push_str("# Reference: docs/reference/config.md\n#\n");
+    out.push_str("# Detected:\n");
+    if facts.is_workspace {
+        let _ = writeln!(out, "#   workspace members: {}", facts.members);
+    } else {
+        out.push_str("#   single package (no [workspace] table)\n");
+    }
+    let _ = writeln!(
+        out,
+        "#   resolver:          {}",
+        facts.resolver.as_deref().unwrap_or("unset")
+    );
+    let msrv = match (&facts.msrv, facts.mixed_msrvs.as_slice()) {
+        (Some(msrv), _) => msrv.clone(),
+        (None, []) => "unset".to_string(),
+        (None, mixed) => format!("mixed ({})", mixed.join(", ")),
+    };
+    let _ = writeln!(out, "#   rust-version:      {}", msrv);
+    let _ = writeln!(
+        out,
+        "#   edition:           {}",
+        facts.edition.as_deref().unwrap_or("unset")
+    );
+
+    out.push_str(
+        "
+[policy]
+# Policy keys are sensor/check_id/code; `*` matches any trailing segment.
+# Deny wins over allow; a non-empty allow list blocks everything else.
+allow = []
+deny = []
+allow_guarded = false
+allow_unsafe = false
+allow_dirty = false
+",
+    );
+    let _ = writeln!(
+        out,
+        "# Sized for {} member(s): one manifest each plus the root.",
+        facts.members
+    );
+    let _ = writeln!(out, "max_ops = {}", max_ops);
+    let _ = writeln!(out, "max_files = {}", max_files);
+    out.push_str(
+        "max_patch_bytes = 250000
+# min_severity = \"warn\"
+# sensors = [\"builddiag\", \"depguard\"]
+# exclude_check_ids = []
+
+[backups]
+enabled = true
+suffix = \".buildfix.bak\"
+keep_last = 10
+
+[commit]
+enabled = false
+# message = \"buildfix: apply\"
+
+[params]
+# Values for ops that cannot infer them (unsafe until provided).
+",
+    );
+    param(&mut out, "rust_version", facts.msrv.as_deref());
+    param(&mut out, "edition", facts.edition.as_deref());
+    param(&mut out, "license", None);
+    param(&mut out, "version", None);
+
+    out.push_str(
+        "
+[render]
+# templates_dir = \".buildfix/templates\"
+# comment_max_bytes = 65536
+
+# Per-fix toggles: uncomment a fix's keys under [policy].deny to turn it off.
+",
+    );
+    for fix in enabled_fixes() {
+        fix_toggle(&mut out, fix, facts);
+    }
+    out
+}
+
+fn param(out: &mut String, key: &str, value: Option<&str>) {
+    match value {
+        Some(value) => {
+            let _ = writeln!(out, "{} = \"{}\"", key, value);
+        }
+        None => {
+            let _ = writeln!(out, "# {} = \"\"", key);
+        }
+    }
+}
+
+fn fix_toggle(out: &mut String, fix: &FixExplanation, facts: &WorkspaceFacts) {
+    let note = match fix.key {
+        "resolver-v2" if facts.resolver.as_deref().is_some_and(|r| r != "1") => {
+            " - resolver already set"
+        }
+        "msrv" if !facts.mixed_msrvs.is_empty() => " - members disagree on rust-version",
+        _ => "",
+    };
+    let _ = writeln!(
+        out,
+        "#\n# {} ({}){}: {}",
+        fix.key,
+        format_safety_class(fix.safety).to_lowercase(),
+        note,
+        fix.title
+    );
+    for key in policy_keys(fix) {
+        let _ = writeln!(out, "#   \"{}\",", key);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::{CONFIG_FILE_NAME, load_config};
+
+    fn write(root: &Utf8Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn detects_members_resolver_and_shared_member_msrv() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\nexclude = [\"crates/old\"]\nresolver = \"2\"\n\n[workspace.package]\nedition = \"2021\"\n",
+        );
+        for name in ["a", "b", "old"] {
+            write(
+                root,
+                &format!("crates/{}/Cargo.toml", name),
+                &format!(
+                    "[package]\nname = \"{}\"\nversion = \"0.1.0\"\nrust-version = \"1.70\"\n",
+                    name
+                ),
+            );
+        }
+        std::fs::create_dir_all(root.join("crates/not-a-crate")).unwrap();
+
+        let facts = detect(root).unwrap();
+        assert_eq!(
+            facts,
+            WorkspaceFacts {
+                is_workspace: true,
+                resolver: Some("2".into()),
+                msrv: Some("1.70".into()),
+                mixed_msrvs: Vec::new(),
+                edition: Some("2021".into()),
+                members: 2,
+            }
+        );
+
+        let rendered = render(&facts);
+        assert!(rendered.contains("rust_version = \"1.70\""));
+        assert!(rendered.contains("resolver-v2 (safe) - resolver already set"));
+    }
+
+    #[test]
+    fn rendered_config_loads_and_scales_caps() {
+        let facts = WorkspaceFacts {
+            is_workspace: true,
+            mixed_msrvs: vec!["1.65".into(), "1.70".into()],
+            members: 40,
+            ..WorkspaceFacts::default()
+        };
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let path = root.join(CONFIG_FILE_NAME);
+        std::fs::write(&path, render(&facts)).unwrap();
+
+        let config = load_config(&path).unwrap();
+        assert_eq!(config.policy.max_files, Some(41));
+        assert_eq!(config.policy.max_ops, Some(80));
+        assert_eq!(config.backups.keep_last, Some(10));
+        assert!(!config.params.contains_key("rust_version"));
+    }
+}
@@ -1,22 +1,68 @@
+mod cargo_subcommand;
 mod config;
+mod doctor;
+mod events;
+mod extends;
+mod hook;
+mod init;
+mod logging;
+mod metrics;
+mod outputs;
+mod serve;
+#[cfg(feature = "tui")]
+mod tui;
+#[cfg(feature = "watch")]
+mod watch;
 use buildfix_cli::explain;
 
 use anyhow::Context;
-use buildfix_core::pipeline::{run_apply, run_plan, write_apply_artifacts, write_plan_artifacts};
+#[cfg(feature = "object-store")]
+use buildfix_core::adapters::ObjectStoreReceiptSource;
+#[cfg(feature = "forge")]
+use buildfix_core::adapters::{ForgeKind, repo_path_from_remote_url};
+use buildfix_core::adapters::{HttpReceiptSource, RemoteReceipt};
+use buildfix_core::branch::DEFAULT_BRANCH_TEMPLATE;
+use buildfix_core::gc::{GcKind, GcReport, GcSettings, collect_garbage};
+use buildfix_core::pipeline::{
+    ApplyOutcome, PlanOutcome, parse_plan, run_apply_patch, run_apply_with_progress,
+    run_plan_with_progress, run_plan_with_repo, write_apply_artifacts, write_plan_artifacts,
+};
+use buildfix_core::sandbox::run_sandbox_apply;
+use buildfix_core::status::{RunStatus, collect_status};
+use buildfix_core::verify::{VerifyCheck, VerifyCheckKind, VerifyReport, verify_apply};
 use buildfix_core_runtime::{
-    ApplySettings, FsReceiptSource, FsWritePort, PlanSettings, RunMode, ShellGitPort,
+    ApplySettings, FsReceiptSource, FsWritePort, GixGitPort, Metrics, PlanSettings, ReceiptSource,
+    RunMode, WritePort,
+};
+use buildfix_receipts::TrustMode;
+use buildfix_render::{
+    render_apply_pretty, render_plan_checkstyle, render_plan_csv, render_plan_diff,
+    render_plan_diff_json, render_plan_pretty, render_plan_teamcity, render_plan_tsv,
 };
+use buildfix_types::cancel::CancellationToken;
+use buildfix_types::clock::{Clock, Ids};
+use buildfix_types::messages::MessageCatalog;
+use buildfix_types::plan::{BuildfixPlan, CiRun, DirtyScope};
+use buildfix_types::progress::Progress;
 use buildfix_types::receipt::ToolInfo;
+use buildfix_types::telemetry;
+use buildfix_types::wire::{ApplyV1, PlanV1};
 use camino::{Utf8Path, Utf8PathBuf};
-use clap::{Parser, Subcommand};
+use clap::{CommandFactory, Parser, Subcommand};
 use config::{ConfigMerger, parse_cli_params};
+use events::EventSink;
 use fs_err as fs;
+use logging::{JsonLines, LogFormat};
 
+use std::cell::RefCell;
+use std::io::Write as _;
 use std::process::ExitCode;
 use tracing::{debug, error, info};
 use tracing_subscriber::EnvFilter;
+use tracing_subscriber::fmt::writer::BoxMakeWriter;
 
 const PLAN_SCHEMA: &str = include_str!("../schemas/buildfix.plan.v1.json");
+const PLAN_V2_SCHEMA: &str = include_str!("../schemas/buildfix.plan.v2.json");
 const APPLY_SCHEMA: &str = include_str!("../schemas/buildfix.apply.v1.json");
 /// Canonical report is validated against the sensor envelope schema.
 const REPORT_SCHEMA: &str =
@@ -47,29 +93,82 @@ EXIT CODES:
 struct Cli {
     #[command(subcommand)]
     cmd: Command,
+
+    /// Log line format: `text`, or `json` for one object per line with a
+    /// stable `event` id (see docs/reference/telemetry.md).
+    #[arg(
+        long,
+        global = true,
+        value_enum,
+        default_value = "text",
+        env = "BUILDFIX_LOG_FORMAT"
+    )]
+    log_format: LogFormat,
 }
 
 #[derive(Debug, Subcommand)]
 enum Command {
     /// Generate a deterministic fix plan from receipts.
-    Plan(PlanArgs),
+    Plan(Box<PlanArgs>),
     /// Apply an existing plan (default: dry-run).
-    Apply(ApplyArgs),
+    Apply(Box<ApplyArgs>),
+    /// Plan and apply in one step (default: dry-run).
+    Fix(Box<FixArgs>),
+    /// Apply an emitted patch.diff directly, without git (default: dry-run).
+    ApplyPatch(ApplyPatchArgs),
     /// Explain what a fix does, its safety rationale, and remediation guidance.
     Explain(ExplainArgs),
     /// List all available fixes with their safety classifications.
     ListFixes(ListFixesArgs),
     /// Validate receipts and buildfix artifacts against schemas.
     Validate(ValidateArgs),
+    /// Report whether the plan is current, applied, and backed up.
+    Status(StatusArgs),
+    /// Remove old backup runs and stale cache files from the output directory.
+    Gc(GcArgs),
+    /// Open or update a pull (merge) request for applied changes.
+    #[cfg(feature = "forge")]
+    Pr(PrArgs),
+    /// Post or update the buildfix summary comment on the current pull request.
+    #[cfg(feature = "forge")]
+    Comment(CommentArgs),
+    /// Publish the report verdict as a check run or commit status.
+    #[cfg(feature = "forge")]
+    Check(CheckArgs),
+    /// Check that files an apply wrote are unchanged and still parse.
+    Verify(VerifyArgs),
+    /// Show ops added, removed or changed between two plans.
+    Compare(CompareArgs),
+    /// Combine plans made from several artifact sets into one.
+    MergePlans(MergePlansArgs),
+    /// Check git, directories, receipts, buildfix.toml and schemas.
+    Doctor(DoctorArgs),
+    /// Write a commented buildfix.toml seeded from the workspace manifests.
+    Init(InitArgs),
+    /// Install or run the git pre-commit / pre-push check.
+    Hook(HookArgs),
+    /// Answer plan, apply, explain and status requests over JSON-RPC.
+    Serve(ServeArgs),
+    /// Print a shell completion script.
+    Completions(CompletionsArgs),
+    /// Print the man page, or write one page per subcommand to a directory.
+    Man(ManArgs),
+    /// Browse a plan interactively and pick ops for a follow-up apply.
+    #[cfg(feature = "tui")]
+    Tui(TuiArgs),
+    /// Re-plan whenever receipts or manifests change, emitting NDJSON events.
+    #[cfg(feature = "watch")]
+    Watch(Box<WatchArgs>),
 }
 
-#[derive(Debug, Parser)]
+#[derive(Debug, Clone, Parser)]
 struct PlanArgs {
     /// Repository root (default: current directory).
     #[arg(long, default_value = ".")]
     repo_root: Utf8PathBuf,
 
-    /// Artifacts directory containing receipts (default: <repo_root>/artifacts).
+    /// Artifacts directory containing receipts, or a .zip/.tar/.tar.gz bundle of one
+    /// (default: <repo_root>/artifacts).
     #[arg(long)]
     artifacts_dir: Option<Utf8PathBuf>,
 
@@ -77,6 +176,10 @@ struct PlanArgs {
     #[arg(long)]
     out_dir: Option<Utf8PathBuf>,
 
+    /// Write one artifact somewhere else, e.g. `plan.json=ci/plan.json` (repeatable).
+    #[arg(long = "out", value_name = "NAME=PATH")]
+    out: Vec<String>,
+
     /// Allowlist patterns for policy keys (apply-time).
     #[arg(long)]
     allow: Vec<String>,
@@ -85,6 +188,30 @@ struct PlanArgs {
     #[arg(long)]
     deny: Vec<String>,
 
+    /// Only plan from findings in files changed since the merge base with
+    /// BASE_REF (default: origin/HEAD), or in their package's Cargo.toml.
+    #[arg(
+        long,
+        value_name = "BASE_REF",
+        num_args = 0..=1,
+        default_missing_value = "origin/HEAD"
+    )]
+    changed_only: Option<String>,
+
+    /// Only plan from findings in files staged in the git index, or in their
+    /// package's Cargo.toml. Combines with --changed-only.
+    #[arg(long, default_value_t = false)]
+    staged: bool,
+
+    /// Only plan ops in workspace members whose name matches this glob
+    /// (repeatable). Root-only ops are left out.
+    #[arg(long = "package", value_name = "NAME")]
+    package: Vec<String>,
+
+    /// Leave out ops in workspace members whose name matches this glob (repeatable).
+    #[arg(long = "exclude-package", value_name = "NAME")]
+    exclude_package: Vec<String>,
+
     /// Disable sha256 preconditions (not recommended).
     #[arg(long, default_value_t = false)]
     no_clean_hashes: bool,
@@ -101,18 +228,85 @@ struct PlanArgs {
     #[arg(long)]
     max_patch_bytes: Option<u64>,
 
+    /// Hours until the plan expires; apply blocks an expired plan
+    /// (default: [policy] plan_ttl_hours, never).
+    #[arg(long, value_name = "HOURS")]
+    plan_ttl_hours: Option<u64>,
+
     /// Require git HEAD SHA precondition for each fix.
     /// Ensures plan can only be applied to the exact commit it was generated from.
     #[arg(long, default_value_t = false)]
     git_head_precondition: bool,
 
+    /// Record each file's sha256 on its ops so a drifted file blocks only the
+    /// ops that touch it instead of the whole apply.
+    #[arg(long, default_value_t = false)]
+    pin_op_targets: bool,
+
     /// Parameters for unsafe fixes (repeatable: key=value).
     #[arg(long)]
     param: Vec<String>,
 
+    /// Emit patch.diff with full-index and binary sections (like `git diff --binary`).
+    #[arg(long, default_value_t = false)]
+    binary: bool,
+
+    /// `cargo metadata --format-version 1` snapshot exposed to fixers
+    /// (default: <artifacts_dir>/cargo-metadata.json when present).
+    #[arg(long)]
+    cargo_metadata: Option<Utf8PathBuf>,
+
+    /// Re-parse every receipt instead of reusing parses cached under
+    /// <out_dir>/cache/receipts.
+    #[arg(long, default_value_t = false)]
+    no_receipt_cache: bool,
+
+    /// Fixers to plan and files to hash and diff at once (default: one per
+    /// core; 1 does them in turn). The plan is the same for any value.
+    #[arg(long, value_name = "N", default_value_t = 0, hide_default_value = true)]
+    jobs: usize,
+
+    /// Fetch a receipt envelope over HTTP(S) instead of scanning the artifacts
+    /// directory (repeatable). Append `#sha256=<hex>` to pin the body and
+    /// `&sensor=<id>` to override the sensor id.
+    #[arg(long = "receipt-url", value_name = "URL")]
+    receipt_urls: Vec<String>,
+
+    /// Header sent with every receipt request (repeatable: "Name: value").
+    #[arg(long = "receipt-header", value_name = "HEADER")]
+    receipt_headers: Vec<String>,
+
+    /// Load receipts from an object store prefix laid out like an artifacts
+    /// directory (`s3://bucket/run-id/`, `gs://...`, `az://...`). Credentials
+    /// come from the usual AWS_*, GOOGLE_* and AZURE_* environment variables.
+    #[cfg(feature = "object-store")]
+    #[arg(long, value_name = "URL", conflicts_with = "receipt_urls")]
+    receipt_store: Option<String>,
+
     /// Run mode. In cockpit mode, policy blocks (exit 2) are mapped to exit 0.
     #[arg(long, value_enum, default_value = "standalone")]
     mode: CliRunMode,
+
+    /// Stdout output: nothing beyond logs (quiet), a colored summary (pretty),
+    /// or the wire JSON with exit status (json; logs move to stderr).
+    #[arg(long, value_enum, default_value = "quiet")]
+    format: RunOutputFormat,
+
+    /// Append NDJSON progress events to this file, or to an inherited file
+    /// descriptor with `fd:N`.
+    #[arg(long, value_name = "PATH|fd:N")]
+    events: Option<String>,
+
+    /// Write run metrics (ops planned and blocked by fixer, duration, patch
+    /// size) to this file in Prometheus text format.
+    #[arg(long, value_name = "PATH")]
+    metrics: Option<Utf8PathBuf>,
+
+    /// Extra report (repeatable): checkstyle, csv and tsv write
+    /// <out_dir>/checkstyle.xml, plan.csv and plan.tsv; teamcity prints
+    /// service messages to stdout.
+    #[arg(long = "report-format", value_enum, value_name = "FORMAT")]
+    report_formats: Vec<ReportFormat>,
 }
 
 #[derive(Debug, Parser)]
@@ -125,6 +319,14 @@ struct ApplyArgs {
     #[arg(long)]
     out_dir: Option<Utf8PathBuf>,
 
+    /// Plan to apply (default: <out_dir>/plan.json).
+    #[arg(long)]
+    plan: Option<Utf8PathBuf>,
+
+    /// Write one artifact somewhere else, e.g. `apply.json=ci/apply.json` (repeatable).
+    #[arg(long = "out", value_name = "NAME=PATH")]
+    out: Vec<String>,
+
     /// Apply changes to disk. If omitted, runs a dry-run and only emits artifacts.
     #[arg(long, default_value_t = false)]
     apply: bool,
@@ -141,10 +343,139 @@ struct ApplyArgs {
     #[arg(long, default_value_t = false)]
     allow_dirty: bool,
 
+    /// Which uncommitted changes block the apply: any (repo) or only those to
+    /// files the plan targets (plan). Default: [policy] dirty_scope, else repo.
+    #[arg(long, value_enum)]
+    dirty_scope: Option<CliDirtyScope>,
+
     /// Parameters for unsafe fixes (repeatable: key=value).
     #[arg(long)]
     param: Vec<String>,
 
+    /// Apply only this op id (repeatable); other ops are skipped as `user_filtered`.
+    #[arg(long = "op", value_name = "ID")]
+    op_ids: Vec<String>,
+
+    /// Apply only ops whose fix key matches this glob (repeatable).
+    #[arg(long = "only", value_name = "FIX_KEY_GLOB")]
+    only: Vec<String>,
+
+    /// Skip ops whose fix key matches this glob (repeatable).
+    #[arg(long = "skip", value_name = "FIX_KEY_GLOB")]
+    skip: Vec<String>,
+
+    /// Apply only ops in workspace members whose name matches this glob
+    /// (repeatable); other ops are skipped as `user_filtered`.
+    #[arg(long = "package", value_name = "NAME")]
+    package: Vec<String>,
+
+    /// Skip ops in workspace members whose name matches this glob (repeatable).
+    #[arg(long = "exclude-package", value_name = "NAME")]
+    exclude_package: Vec<String>,
+
+    /// Auto-commit after successful apply (maintainer workflow).
+    #[arg(long, default_value_t = false)]
+    auto_commit: bool,
+
+    /// Custom commit message for --auto-commit.
+    #[arg(long)]
+    commit_message: Option<String>,
+
+    /// Move applied changes onto a new branch named by this template
+    /// ({fix_keys}, {date}, {plan_hash}); without a value uses
+    /// buildfix/{fix_keys}-{date}. Default: [commit] branch.
+    #[arg(long, value_name = "TEMPLATE", num_args = 0..=1, default_missing_value = DEFAULT_BRANCH_TEMPLATE)]
+    branch: Option<String>,
+
+    /// Push the new branch to this remote after auto-commit (requires
+    /// --branch and --auto-commit). Default: [commit] push.
+    #[arg(long, value_name = "REMOTE")]
+    push: Option<String>,
+
+    /// Apply and verify in a temporary git worktree of HEAD instead of the
+    /// checkout; only artifacts are written to <out_dir>. Exit 2 when a
+    /// check fails.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["auto_commit", "branch", "push"])]
+    sandbox: bool,
+
+    /// Command to run in the sandbox worktree after applying, e.g.
+    /// `cargo check --workspace` (repeatable; run by the shell).
+    #[arg(long = "sandbox-exec", value_name = "CMD", requires = "sandbox")]
+    sandbox_exec: Vec<String>,
+
+    /// Keep the sandbox worktree for inspection instead of removing it.
+    #[arg(long, default_value_t = false, requires = "sandbox")]
+    keep_sandbox: bool,
+
+    /// Emit patch.diff with full-index and binary sections (like `git diff --binary`).
+    #[arg(long, default_value_t = false)]
+    binary: bool,
+
+    /// fsync written files and their directories before exiting.
+    #[arg(long, default_value_t = false)]
+    durable_writes: bool,
+
+    /// Run mode. In cockpit mode, policy blocks (exit 2) are mapped to exit 0.
+    #[arg(long, value_enum, default_value = "standalone")]
+    mode: CliRunMode,
+
+    /// Stdout output: nothing beyond logs (quiet), a colored summary (pretty),
+    /// or the wire JSON with exit status (json; logs move to stderr).
+    #[arg(long, value_enum, default_value = "quiet")]
+    format: RunOutputFormat,
+
+    /// Append NDJSON progress events to this file, or to an inherited file
+    /// descriptor with `fd:N`.
+    #[arg(long, value_name = "PATH|fd:N")]
+    events: Option<String>,
+
+    /// Write run metrics (ops applied, blocked and failed by fixer,
+    /// duration) to this file in Prometheus text format.
+    #[arg(long, value_name = "PATH")]
+    metrics: Option<Utf8PathBuf>,
+}
+
+/// `plan` arguments plus the apply-only flags; shared ones (`--param`,
+/// `--binary`, `--mode`, `--format`, `--events`, `--metrics`) apply to both
+/// steps.
+#[derive(Debug, Parser)]
+struct FixArgs {
+    #[command(flatten)]
+    plan: PlanArgs,
+
+    /// Apply changes to disk. If omitted, runs a dry-run and only emits artifacts.
+    #[arg(long, default_value_t = false)]
+    apply: bool,
+
+    /// Allow guarded fixes to run.
+    #[arg(long, default_value_t = false)]
+    allow_guarded: bool,
+
+    /// Allow unsafe fixes to run.
+    #[arg(long, default_value_t = false)]
+    allow_unsafe: bool,
+
+    /// Allow applying fixes when the git working tree has uncommitted changes.
+    #[arg(long, default_value_t = false)]
+    allow_dirty: bool,
+
+    /// Which uncommitted changes block the apply: any (repo) or only those to
+    /// files the plan targets (plan). Default: [policy] dirty_scope, else repo.
+    #[arg(long, value_enum)]
+    dirty_scope: Option<CliDirtyScope>,
+
+    /// Apply only this op id (repeatable); other ops are skipped as `user_filtered`.
+    #[arg(long = "op", value_name = "ID")]
+    op_ids: Vec<String>,
+
+    /// Apply only ops whose fix key matches this glob (repeatable).
+    #[arg(long = "only", value_name = "FIX_KEY_GLOB")]
+    only: Vec<String>,
+
+    /// Skip ops whose fix key matches this glob (repeatable).
+    #[arg(long = "skip", value_name = "FIX_KEY_GLOB")]
+    skip: Vec<String>,
+
     /// Auto-commit after successful apply (maintainer workflow).
     #[arg(long, default_value_t = false)]
     auto_commit: bool,
@@ -153,6 +484,66 @@ struct ApplyArgs {
     #[arg(long)]
     commit_message: Option<String>,
 
+    /// Move applied changes onto a new branch named by this template
+    /// ({fix_keys}, {date}, {plan_hash}); without a value uses
+    /// buildfix/{fix_keys}-{date}. Default: [commit] branch.
+    #[arg(long, value_name = "TEMPLATE", num_args = 0..=1, default_missing_value = DEFAULT_BRANCH_TEMPLATE)]
+    branch: Option<String>,
+
+    /// Push the new branch to this remote after auto-commit (requires
+    /// --branch and --auto-commit). Default: [commit] push.
+    #[arg(long, value_name = "REMOTE")]
+    push: Option<String>,
+
+    /// Apply and verify in a temporary git worktree of HEAD instead of the
+    /// checkout; only artifacts are written to <out_dir>.
+    #[arg(long, default_value_t = false, conflicts_with_all = ["auto_commit", "branch", "push"])]
+    sandbox: bool,
+
+    /// Command to run in the sandbox worktree after applying (repeatable).
+    #[arg(long = "sandbox-exec", value_name = "CMD", requires = "sandbox")]
+    sandbox_exec: Vec<String>,
+
+    /// Keep the sandbox worktree for inspection instead of removing it.
+    #[arg(long, default_value_t = false, requires = "sandbox")]
+    keep_sandbox: bool,
+
+    /// fsync written files and their directories before exiting.
+    #[arg(long, default_value_t = false)]
+    durable_writes: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ApplyPatchArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Directory containing plan.json and backups (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+
+    /// Patch to apply (default: <out_dir>/patch.diff).
+    #[arg(long)]
+    patch: Option<Utf8PathBuf>,
+
+    /// Write changes to disk. If omitted, only checks that the patch applies.
+    #[arg(long, default_value_t = false)]
+    apply: bool,
+
+    /// Allow applying the patch when the git working tree has uncommitted changes.
+    #[arg(long, default_value_t = false)]
+    allow_dirty: bool,
+
+    /// Which uncommitted changes block the apply: any (repo) or only those to
+    /// files the patch touches (plan). Default: [policy] dirty_scope, else repo.
+    #[arg(long, value_enum)]
+    dirty_scope: Option<CliDirtyScope>,
+
+    /// fsync written files and their directories before exiting.
+    #[arg(long, default_value_t = false)]
+    durable_writes: bool,
+
     /// Run mode. In cockpit mode, policy blocks (exit 2) are mapped to exit 0.
     #[arg(long, value_enum, default_value = "standalone")]
     mode: CliRunMode,
@@ -160,24 +551,108 @@ struct ApplyArgs {
 
 #[derive(Debug, Parser)]
 struct ExplainArgs {
-    /// Fix key or fix ID to explain (e.g., "resolver-v2", "path-dep-version").
+    /// Fix key or fix ID to explain (e.g., "resolver-v2", "path-dep-version"),
+    /// or the id (or a unique prefix of it) of an op in plan.json.
     fix_key: String,
+
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Plan to look op ids up in: a plan.json file or a directory containing
+    /// one (default: <repo_root>/artifacts/buildfix/plan.json).
+    #[arg(long)]
+    plan: Option<Utf8PathBuf>,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
 }
 
 #[derive(Debug, Parser)]
 struct ListFixesArgs {
+    /// Only fixes triggered by this sensor (e.g., "builddiag", "cargo-deny").
+    #[arg(long)]
+    sensor: Option<String>,
+
+    /// Only fixes triggered by this check id (e.g., "workspace.resolver_v2").
+    #[arg(long)]
+    check_id: Option<String>,
+
+    /// Cross-reference the receipts in this artifacts directory (or bundle):
+    /// which fixes would trigger, which could, and which lack inputs.
+    #[arg(long, value_name = "ARTIFACTS_DIR")]
+    coverage: Option<Utf8PathBuf>,
+
+    /// Repository root whose buildfix.toml [receipts] settings --coverage uses
+    /// (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Parser)]
+struct CompareArgs {
+    /// Old plan: a plan.json file or a directory containing one.
+    old: Utf8PathBuf,
+
+    /// New plan: a plan.json file or a directory containing one.
+    new: Utf8PathBuf,
+
     /// Output format (text, json).
     #[arg(long, value_enum, default_value = "text")]
     format: OutputFormat,
 }
 
+#[derive(Debug, Parser)]
+struct MergePlansArgs {
+    /// Plans to merge: plan.json files or directories containing one.
+    #[arg(required = true)]
+    plans: Vec<Utf8PathBuf>,
+
+    /// Write the merged plan.json here instead of printing it.
+    #[arg(long)]
+    out: Option<Utf8PathBuf>,
+}
+
+#[cfg(feature = "tui")]
+#[derive(Debug, Parser)]
+struct TuiArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Directory containing plan.json and patch.diff (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+}
+
+#[cfg(feature = "watch")]
+#[derive(Debug, Parser)]
+struct WatchArgs {
+    #[command(flatten)]
+    plan: PlanArgs,
+
+    /// Wait this long after the last change before re-planning.
+    #[arg(long, value_name = "MS", default_value_t = 300)]
+    debounce_ms: u64,
+
+    /// Exit after this many plans, counting the initial one.
+    #[arg(long, value_name = "N")]
+    max_runs: Option<usize>,
+}
+
 #[derive(Debug, Parser)]
 struct ValidateArgs {
     /// Repository root (default: current directory).
     #[arg(long, default_value = ".")]
     repo_root: Utf8PathBuf,
 
-    /// Artifacts directory containing receipts (default: <repo_root>/artifacts).
+    /// Artifacts directory containing receipts, or a .zip/.tar/.tar.gz bundle of one
+    /// (default: <repo_root>/artifacts).
     #[arg(long)]
     artifacts_dir: Option<Utf8PathBuf>,
 
@@ -186,357 +661,2516 @@ struct ValidateArgs {
     out_dir: Option<Utf8PathBuf>,
 }
 
-#[derive(Debug, Clone, Copy, clap::ValueEnum)]
-enum OutputFormat {
-    Text,
-    Json,
-}
+#[derive(Debug, Parser)]
+struct StatusArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
 
-#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
-enum CliRunMode {
-    #[default]
-    Standalone,
-    Cockpit,
-}
+    /// Artifacts directory containing receipts (default: <repo_root>/artifacts).
+    #[arg(long)]
+    artifacts_dir: Option<Utf8PathBuf>,
 
-impl From<CliRunMode> for RunMode {
-    fn from(m: CliRunMode) -> Self {
-        match m {
-            CliRunMode::Standalone => RunMode::Standalone,
-            CliRunMode::Cockpit => RunMode::Cockpit,
-        }
-    }
-}
+    /// Output directory for buildfix artifacts (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
 
-fn main() -> ExitCode {
-    match real_main() {
-        Ok(code) => code,
-        Err(e) => {
-            error!("{:?}", e);
-            ExitCode::from(1)
-        }
-    }
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
 }
 
-fn real_main() -> anyhow::Result<ExitCode> {
-    tracing_subscriber::fmt()
-        .with_env_filter(EnvFilter::from_default_env())
-        .init();
+#[derive(Debug, Parser)]
+struct GcArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
 
-    let cli = Cli::parse();
-    match cli.cmd {
-        Command::Plan(args) => cmd_plan(args),
-        Command::Apply(args) => cmd_apply(args),
-        Command::Explain(args) => {
-            cmd_explain(args)?;
-            Ok(ExitCode::from(0))
-        }
-        Command::ListFixes(args) => {
-            cmd_list_fixes(args)?;
-            Ok(ExitCode::from(0))
-        }
-        Command::Validate(args) => cmd_validate(args),
-    }
-}
+    /// Artifacts directory containing receipts (default: <repo_root>/artifacts).
+    #[arg(long)]
+    artifacts_dir: Option<Utf8PathBuf>,
 
-fn cmd_plan(args: PlanArgs) -> anyhow::Result<ExitCode> {
-    let repo_root = args.repo_root;
-    let artifacts_dir = args
-        .artifacts_dir
-        .unwrap_or_else(|| repo_root.join("artifacts"));
-    let out_dir = args
-        .out_dir
-        .unwrap_or_else(|| artifacts_dir.join("buildfix"));
+    /// Output directory for buildfix artifacts (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
 
-    let cli_params = parse_cli_params(&args.param)?;
+    /// Keep at most this many backup runs (default: [backups] keep_last).
+    #[arg(long)]
+    keep_last: Option<usize>,
 
-    // Load config file and merge with CLI arguments.
-    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
-    let merged = ConfigMerger::new(file_config).merge_plan_args(
-        &args.allow,
-        &args.deny,
-        args.no_clean_hashes,
-        &cli_params,
-    );
+    /// Remove backup runs older than this many days (default: [gc] backup_max_age_days).
+    #[arg(long)]
+    backup_max_age_days: Option<u64>,
 
-    debug!(
-        "merged config: allow={:?}, deny={:?}, require_clean_hashes={}, params={:?}",
-        merged.allow, merged.deny, merged.require_clean_hashes, merged.params
-    );
+    /// Remove cache files not rewritten for this many days (default: [gc] cache_max_age_days, 14).
+    #[arg(long)]
+    cache_max_age_days: Option<u64>,
 
-    let mode: RunMode = args.mode.into();
+    /// List what would be removed without removing anything.
+    #[arg(long)]
+    dry_run: bool,
 
-    let settings = PlanSettings {
-        repo_root: repo_root.clone(),
-        artifacts_dir: artifacts_dir.clone(),
-        out_dir: out_dir.clone(),
-        allow: merged.allow.clone(),
-        deny: merged.deny.clone(),
-        allow_guarded: merged.allow_guarded,
-        allow_unsafe: merged.allow_unsafe,
-        allow_dirty: merged.allow_dirty,
-        max_ops: args.max_ops.or(merged.max_ops),
-        max_files: args.max_files.or(merged.max_files),
-        max_patch_bytes: args.max_patch_bytes.or(merged.max_patch_bytes),
-        params: merged.params.clone(),
-        require_clean_hashes: merged.require_clean_hashes,
-        git_head_precondition: args.git_head_precondition,
-        backup_suffix: merged.backups.suffix.clone(),
-        mode,
-    };
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
 
-    let receipts_port = FsReceiptSource::new(artifacts_dir);
-    let git = ShellGitPort;
-    let writer = FsWritePort;
-    let tool = tool_info();
+#[derive(Debug, Parser)]
+struct CompletionsArgs {
+    /// Shell to generate completions for.
+    #[arg(value_enum)]
+    shell: clap_complete::Shell,
+}
 
-    let outcome = match run_plan(&settings, &receipts_port, &git, tool) {
-        Ok(outcome) => outcome,
+#[derive(Debug, Parser)]
+struct ManArgs {
+    /// Write buildfix.1 and buildfix-<command>.1 pages here instead of
+    /// printing buildfix.1.
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct InitArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Overwrite an existing buildfix.toml.
+    #[arg(long, default_value_t = false)]
+    force: bool,
+
+    /// Print the config to stdout instead of writing it.
+    #[arg(long, default_value_t = false)]
+    stdout: bool,
+}
+
+#[derive(Debug, Parser)]
+struct ServeArgs {
+    /// Listen on this Unix socket instead of stdin/stdout.
+    #[cfg(unix)]
+    #[arg(long, value_name = "PATH")]
+    socket: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Parser)]
+struct HookArgs {
+    #[command(subcommand)]
+    cmd: HookCommand,
+}
+
+#[derive(Debug, Subcommand)]
+enum HookCommand {
+    /// Write a git hook that runs `buildfix hook run`.
+    Install(HookInstallArgs),
+    /// Plan against staged (pre-commit) or pushed (pre-push) files and fail
+    /// while safe fixes are left unapplied.
+    Run(Box<HookRunArgs>),
+}
+
+#[derive(Debug, Parser)]
+struct HookInstallArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Which git hook to install.
+    #[arg(long, value_enum, default_value = "pre-commit")]
+    hook: hook::HookKind,
+
+    /// Replace an existing hook that buildfix did not install.
+    #[arg(long, default_value_t = false)]
+    force: bool,
+}
+
+#[derive(Debug, Parser)]
+struct HookRunArgs {
+    #[command(flatten)]
+    plan: PlanArgs,
+
+    /// Hook being run: pre-commit plans from staged files; pre-push from
+    /// files changed since --changed-only's base (default: origin/HEAD).
+    #[arg(long, value_enum, default_value = "pre-commit")]
+    hook: hook::HookKind,
+}
+
+#[derive(Debug, Parser)]
+struct DoctorArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Artifacts directory containing receipts (default: <repo_root>/artifacts).
+    #[arg(long)]
+    artifacts_dir: Option<Utf8PathBuf>,
+
+    /// Output directory for buildfix artifacts (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+/// Which forge and repository `pr` and `comment` talk to.
+#[cfg(feature = "forge")]
+#[derive(Debug, Parser)]
+struct ForgeArgs {
+    /// Use GitHub.
+    #[arg(long, conflicts_with_all = ["gitlab", "bitbucket"])]
+    github: bool,
+
+    /// Use GitLab.
+    #[arg(long, conflicts_with = "bitbucket")]
+    gitlab: bool,
+
+    /// Use Bitbucket Cloud.
+    #[arg(long)]
+    bitbucket: bool,
+
+    /// Repository as OWNER/NAME, or a GitLab project path (default: [forge] repo,
+    /// else the provider's CI variable, else the origin remote).
+    #[arg(long, value_name = "OWNER/NAME")]
+    repo: Option<String>,
+
+    /// REST API root (default: [forge] api_url, else the provider's CI variable,
+    /// else the public service).
+    #[arg(long)]
+    api_url: Option<String>,
+}
+
+#[cfg(feature = "forge")]
+#[derive(Debug, Parser)]
+struct PrArgs {
+    #[command(flatten)]
+    forge: ForgeArgs,
+
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Directory with apply.json and comment.md (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+
+    /// Branch with the applied changes (default: the branch apply created,
+    /// else the current branch).
+    #[arg(long)]
+    head: Option<String>,
+
+    /// Branch to merge into (default: [pr] base, else the repository's default branch).
+    #[arg(long)]
+    base: Option<String>,
+
+    /// Pull request title (default: the auto-commit subject).
+    #[arg(long)]
+    title: Option<String>,
+
+    /// Label to add (repeatable; added to [pr] labels).
+    #[arg(long = "label", value_name = "NAME")]
+    labels: Vec<String>,
+
+    /// Do not create the GitHub check run carrying the plan and apply summaries.
+    #[arg(long)]
+    no_check_run: bool,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[cfg(feature = "forge")]
+#[derive(Debug, Parser)]
+struct CommentArgs {
+    #[command(flatten)]
+    forge: ForgeArgs,
+
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Directory containing plan.json (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+
+    /// Pull (merge) request number (default: from the CI environment).
+    #[arg(long, value_name = "N")]
+    number: Option<u64>,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[cfg(feature = "forge")]
+#[derive(Debug, Parser)]
+struct CheckArgs {
+    #[command(flatten)]
+    forge: ForgeArgs,
+
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Directory containing report.json (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+
+    /// Commit to report on (default: the pull request head on GitHub Actions,
+    /// else HEAD).
+    #[arg(long)]
+    sha: Option<String>,
+
+    /// Check run or status context name.
+    #[arg(long, default_value = "buildfix")]
+    name: String,
+
+    /// Link for the check or status (default: the CI job, when known).
+    #[arg(long, value_name = "URL")]
+    details_url: Option<String>,
+
+    /// Set a commit status even where check runs exist (GitHub).
+    #[arg(long)]
+    commit_status: bool,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Parser)]
+struct VerifyArgs {
+    /// Repository root (default: current directory).
+    #[arg(long, default_value = ".")]
+    repo_root: Utf8PathBuf,
+
+    /// Directory containing apply.json (default: <repo_root>/artifacts/buildfix).
+    #[arg(long)]
+    out_dir: Option<Utf8PathBuf>,
+
+    /// Also run `cargo metadata --no-deps` to confirm the workspace still loads.
+    #[arg(long, default_value_t = false)]
+    cargo_metadata: bool,
+
+    /// Output format (text, json).
+    #[arg(long, value_enum, default_value = "text")]
+    format: OutputFormat,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum OutputFormat {
+    Text,
+    Json,
+}
+
+/// What `plan` and `apply` print to stdout; artifacts are written either way.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum RunOutputFormat {
+    Quiet,
+    Pretty,
+    Json,
+}
+
+/// Additional report formats for `plan --report-format`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+enum ReportFormat {
+    Checkstyle,
+    Teamcity,
+    Csv,
+    Tsv,
+}
+
+#[derive(Debug, Clone, Copy, Default, clap::ValueEnum)]
+enum CliRunMode {
+    #[default]
+    Standalone,
+    Cockpit,
+}
+
+#[derive(Debug, Clone, Copy, clap::ValueEnum)]
+enum CliDirtyScope {
+    Repo,
+    Plan,
+}
+
+impl From<CliDirtyScope> for DirtyScope {
+    fn from(s: CliDirtyScope) -> Self {
+        match s {
+            CliDirtyScope::Repo => DirtyScope::Repo,
+            CliDirtyScope::Plan => DirtyScope::Plan,
+        }
+    }
+}
+
+impl From<CliRunMode> for RunMode {
+    fn from(m: CliRunMode) -> Self {
+        match m {
+            CliRunMode::Standalone => RunMode::Standalone,
+            CliRunMode::Cockpit => RunMode::Cockpit,
+        }
+    }
+}
+
+fn main() -> ExitCode {
+    match real_main() {
+        Ok(code) => code,
+        Err(e) => {
+            error!(event = telemetry::COMMAND_FAILED, "{:?}", e);
+            ExitCode::from(1)
+        }
+    }
+}
+
+fn real_main() -> anyhow::Result<ExitCode> {
+    let cli = if cargo_subcommand::is_cargo_subcommand() {
+        let args = std::env::args_os().collect();
+        Cli::parse_from(cargo_subcommand::with_workspace_root(&Cli::command(), args))
+    } else {
+        Cli::parse()
+    };
+    // Keep stdout a single JSON document for `--format json` pipelines.
+    let json_stdout = match &cli.cmd {
+        Command::Plan(args) => args.format == RunOutputFormat::Json,
+        Command::Apply(args) => args.format == RunOutputFormat::Json,
+        Command::Fix(args) => args.plan.format == RunOutputFormat::Json,
+        #[cfg(unix)]
+        Command::Serve(args) => args.socket.is_none(),
+        #[cfg(not(unix))]
+        Command::Serve(_) => true,
+        // Events go to stdout unless --events sends them elsewhere.
+        #[cfg(feature = "watch")]
+        Command::Watch(args) => args.plan.events.is_none(),
+        Command::Hook(HookArgs {
+            cmd: HookCommand::Run(args),
+        }) => args.plan.format == RunOutputFormat::Json,
+        _ => false,
+    };
+    let writer = if json_stdout {
+        BoxMakeWriter::new(std::io::stderr)
+    } else {
+        BoxMakeWriter::new(std::io::stdout)
+    };
+    let logs = tracing_subscriber::fmt()
+        .with_env_filter(EnvFilter::from_default_env())
+        .with_writer(writer);
+    match cli.log_format {
+        LogFormat::Text => logs.init(),
+        LogFormat::Json => logs.event_format(JsonLines).init(),
+    }
+
+    match cli.cmd {
+        Command::Plan(args) => cmd_plan(*args),
+        Command::Apply(args) => cmd_apply(*args),
+        Command::Fix(args) => cmd_fix(*args),
+        Command::ApplyPatch(args) => cmd_apply_patch(args),
+        Command::Explain(args) => {
+            cmd_explain(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::ListFixes(args) => {
+            cmd_list_fixes(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Validate(args) => cmd_validate(args),
+        Command::Status(args) => {
+            cmd_status(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Gc(args) => {
+            cmd_gc(args)?;
+            Ok(ExitCode::from(0))
+        }
+        #[cfg(feature = "forge")]
+        Command::Pr(args) => {
+            cmd_pr(args)?;
+            Ok(ExitCode::from(0))
+        }
+        #[cfg(feature = "forge")]
+        Command::Comment(args) => {
+            cmd_comment(args)?;
+            Ok(ExitCode::from(0))
+        }
+        #[cfg(feature = "forge")]
+        Command::Check(args) => {
+            cmd_check(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Verify(args) => cmd_verify(args),
+        Command::Doctor(args) => cmd_doctor(args),
+        Command::Init(args) => {
+            cmd_init(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Hook(args) => cmd_hook(args),
+        Command::Serve(args) => {
+            cmd_serve(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Completions(args) => {
+            // Buffered: clap_complete panics if the writer fails mid-script.
+            let mut script = Vec::new();
+            clap_complete::generate(args.shell, &mut Cli::command(), "buildfix", &mut script);
+            std::io::stdout().write_all(&script)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Man(args) => {
+            cmd_man(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::Compare(args) => {
+            cmd_compare(args)?;
+            Ok(ExitCode::from(0))
+        }
+        Command::MergePlans(args) => {
+            cmd_merge_plans(args)?;
+            Ok(ExitCode::from(0))
+        }
+        #[cfg(feature = "tui")]
+        Command::Tui(args) => {
+            cmd_tui(args)?;
+            Ok(ExitCode::from(0))
+        }
+        #[cfg(feature = "watch")]
+        Command::Watch(args) => {
+            cmd_watch(*args)?;
+            Ok(ExitCode::from(0))
+        }
+    }
+}
+
+fn cmd_plan(args: PlanArgs) -> anyhow::Result<ExitCode> {
+    let mut events = EventSink::open(args.events.as_deref())?;
+    plan_with_events(args, &mut events)
+}
+
+fn plan_with_events(args: PlanArgs, events: &mut EventSink) -> anyhow::Result<ExitCode> {
+    if args.format == RunOutputFormat::Json && args.report_formats.contains(&ReportFormat::Teamcity)
+    {
+        anyhow::bail!(
+            "--report-format teamcity prints to stdout and cannot be combined with --format json"
+        );
+    }
+    let format = args.format;
+    let teamcity = args.report_formats.contains(&ReportFormat::Teamcity);
+
+    let run = execute_plan(args, events, None)?;
+    match (format, &run.outcome) {
+        (RunOutputFormat::Json, _) => println!("{}", serde_json::to_string_pretty(&run.json()?)?),
+        (_, None) => {}
+        (format, Some(outcome)) => {
+            if teamcity {
+                print!("{}", render_plan_teamcity(&outcome.plan));
+            }
+            if format == RunOutputFormat::Pretty {
+                print!(
+                    "{}",
+                    render_plan_pretty(&outcome.plan, &outcome.patch, stdout_color())
+                );
+            }
+        }
+    }
+    Ok(ExitCode::from(run.code))
+}
+
+/// What one `plan` run produced; printing is left to the caller.
+struct PlanRun {
+    out_dir: Utf8PathBuf,
+    code: u8,
+    /// `None` when the pipeline stopped on a policy block.
+    outcome: Option<PlanOutcome>,
+}
+
+impl PlanRun {
+    /// The `--format json` document.
+    fn json(&self) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+        let (policy_block, wire) = match &self.outcome {
+            Some(outcome) => {
+                let wire = PlanV1::try_from(&outcome.plan).context("convert plan to wire")?;
+                (outcome.policy_block, Some(serde_json::to_value(wire)?))
+            }
+            None => (true, None),
+        };
+        Ok(run_json(
+            "plan",
+            self.code,
+            policy_block,
+            &self.out_dir,
+            wire,
+        ))
+    }
+}
+
+/// Plan and write the artifacts. `warm` keeps parsed receipts and manifest
+/// contents between runs of a long-lived `serve`.
+fn execute_plan(
+    args: PlanArgs,
+    events: &mut EventSink,
+    warm: Option<&serve::WarmCache>,
+) -> anyhow::Result<PlanRun> {
+    let repo_root = args.repo_root;
+    let artifacts_dir = args
+        .artifacts_dir
+        .unwrap_or_else(|| repo_root.join("artifacts"));
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| default_out_dir(&repo_root, &artifacts_dir));
+
+    let out_overrides = outputs::parse_out_overrides(&args.out)?;
+
+    let cli_params = parse_cli_params(&args.param)?;
+    events.emit(
+        "plan_started",
+        serde_json::json!({
+            "repo_root": repo_root,
+            "artifacts_dir": artifacts_dir,
+            "out_dir": out_dir,
+        }),
+    );
+
+    // Load config file and merge with CLI arguments.
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+    let discovery = file_config.receipts.discovery(&repo_root);
+    let trust = file_config
+        .receipts
+        .trust
+        .as_ref()
+        .map(|t| t.to_policy(&repo_root))
+        .transpose()
+        .context("load [receipts.trust]")?;
+    let templates = file_config
+        .render
+        .templates(&repo_root)
+        .context("load [render] templates")?;
+    let fixers = file_config
+        .fixer_settings()
+        .context("load [fixers] config")?;
+    let merged = ConfigMerger::new(file_config).merge_plan_args(
+        &args.allow,
+        &args.deny,
+        args.no_clean_hashes,
+        &cli_params,
+    );
+
+    debug!(
+        event = telemetry::CONFIG_MERGED,
+        allow = ?merged.allow,
+        deny = ?merged.deny,
+        require_clean_hashes = merged.require_clean_hashes,
+        params = ?merged.params,
+        "merged plan config"
+    );
+
+    let mode: RunMode = args.mode.into();
+
+    let (clock, ids) = clock_and_ids()?;
+    let settings = PlanSettings {
+        repo_root: repo_root.clone(),
+        artifacts_dir: artifacts_dir.clone(),
+        out_dir: out_dir.clone(),
+        allow: merged.allow.clone(),
+        deny: merged.deny.clone(),
+        path_policies: merged.path_policies.clone(),
+        allow_guarded: merged.allow_guarded,
+        allow_unsafe: merged.allow_unsafe,
+        allow_dirty: merged.allow_dirty,
+        max_ops: args.max_ops.or(merged.max_ops),
+        max_files: args.max_files.or(merged.max_files),
+        max_patch_bytes: args.max_patch_bytes.or(merged.max_patch_bytes),
+        params: merged.params.clone(),
+        fixers,
+        min_severity: merged.min_severity,
+        sensors: merged.sensors.clone(),
+        exclude_check_ids: merged.exclude_check_ids.clone(),
+        changed_since: args.changed_only.clone(),
+        staged_only: args.staged,
+        packages: args.package.clone(),
+        exclude_packages: args.exclude_package.clone(),
+        scan: merged.scan.clone(),
+        require_clean_hashes: merged.require_clean_hashes,
+        git_head_precondition: args.git_head_precondition,
+        pin_op_targets: args.pin_op_targets,
+        precondition_mode: merged.precondition_mode,
+        backup_suffix: merged.backups.suffix.clone(),
+        binary_patch: args.binary,
+        plan_ttl: args
+            .plan_ttl_hours
+            .or(merged.plan_ttl_hours)
+            .map(|hours| std::time::Duration::from_secs(hours.saturating_mul(60 * 60))),
+        mode,
+        ci_run: ci_run(),
+        cancel: CancellationToken::new(),
+        clock,
+        ids,
+        metrics: metrics::for_run(args.metrics.as_deref()),
+        jobs: args.jobs,
+    };
+
+    let mut local = FsReceiptSource::new(artifacts_dir.clone()).with_discovery(discovery);
+    if !args.no_receipt_cache {
+        local = local.with_cache_dir(out_dir.join("cache").join("receipts"));
+    }
+    if let Some(path) = args.cargo_metadata {
+        local = local.with_cargo_metadata(path);
+    }
+    if let Some(trust) = trust {
+        local = local.with_trust_policy(trust);
+    }
+    let mut remote: Option<Box<dyn ReceiptSource>> = None;
+    if !args.receipt_urls.is_empty() {
+        remote = Some(Box::new(http_receipt_source(
+            &args.receipt_urls,
+            &args.receipt_headers,
+        )?));
+    }
+    #[cfg(feature = "object-store")]
+    if let Some(url) = args.receipt_store {
+        remote = Some(Box::new(
+            ObjectStoreReceiptSource::new(url).with_env_options(),
+        ));
+    }
+    if remote.is_some()
+        && local
+            .trust
+            .as_ref()
+            .is_some_and(|t| t.mode == TrustMode::Strict)
+    {
+        anyhow::bail!(
+            "[receipts.trust] mode = \"strict\" only covers the local artifacts dir; remote receipt sources are not signature-checked"
+        );
+    }
+    let receipts_port: Box<dyn ReceiptSource + '_> = match (remote, warm) {
+        (Some(remote), _) => Box::new(RemoteReceipts { remote, local }),
+        (None, Some(warm)) => Box::new(warm.receipts(local, &repo_root, &artifacts_dir, &out_dir)),
+        (None, None) => Box::new(local),
+    };
+    let git = GixGitPort;
+    let writer = outputs::RelocatingWritePort::new(&FsWritePort, &out_dir, &out_overrides);
+    let tool = tool_info();
+
+    let result = {
+        let events = RefCell::new(&mut *events);
+        let progress = |p: &Progress<'_>| events.borrow_mut().progress(p);
+        match warm {
+            Some(warm) => run_plan_with_repo(
+                &settings,
+                receipts_port.as_ref(),
+                &git,
+                &warm.repo_view(&repo_root),
+                &progress,
+                tool,
+            ),
+            None => {
+                run_plan_with_progress(&settings, receipts_port.as_ref(), &git, &progress, tool)
+            }
+        }
+    };
+    metrics::write(args.metrics.as_deref());
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        Err(buildfix_core::pipeline::ToolError::PolicyBlock) => {
+            return Ok(PlanRun {
+                out_dir,
+                code: 2,
+                outcome: None,
+            });
+        }
+        Err(buildfix_core::pipeline::ToolError::Internal(e)) => return Err(e),
+    };
+
+    events.plan_ops(&outcome.plan);
+
+    write_plan_artifacts(&outcome, &templates, &out_dir, &writer)?;
+    for format in &args.report_formats {
+        let (name, contents) = match format {
+            ReportFormat::Checkstyle => ("checkstyle.xml", render_plan_checkstyle(&outcome.plan)),
+            ReportFormat::Csv => ("plan.csv", render_plan_csv(&outcome.plan)),
+            ReportFormat::Tsv => ("plan.tsv", render_plan_tsv(&outcome.plan)),
+            ReportFormat::Teamcity => continue,
+        };
+        writer.write_file(&out_dir.join(name), contents.as_bytes())?;
+    }
+    events.artifacts_written(&out_dir);
+
+    info!(
+        event = telemetry::ARTIFACTS_WRITTEN,
+        file = %out_dir,
+        "wrote plan to {}",
+        out_dir
+    );
+    let code = if outcome.policy_block && mode != RunMode::Cockpit {
+        2
+    } else {
+        0
+    };
+    Ok(PlanRun {
+        out_dir,
+        code,
+        outcome: Some(outcome),
+    })
+}
+
+/// `--format json` stdout for `plan` and `apply`: one object with the wire
+/// document under the command's name (`null` when the run stopped before
+/// producing one) and how the run ended.
+fn run_json(
+    command: &str,
+    exit_code: u8,
+    policy_block: bool,
+    out_dir: &Utf8Path,
+    document: Option<serde_json::Value>,
+) -> serde_json::Map<String, serde_json::Value> {
+    let mut out = serde_json::Map::new();
+    out.insert("command".into(), command.into());
+    out.insert("exit_code".into(), exit_code.into());
+    out.insert("policy_block".into(), policy_block.into());
+    out.insert("out_dir".into(), out_dir.as_str().into());
+    out.insert(command.into(), document.unwrap_or(serde_json::Value::Null));
+    out
+}
+
+/// Color stdout when it is a terminal, unless `NO_COLOR` is set;
+/// `CLICOLOR_FORCE` forces color on.
+fn stdout_color() -> bool {
+    use std::io::IsTerminal;
+
+    let set = |var: &str| std::env::var_os(var).is_some_and(|v| !v.is_empty() && v != "0");
+    if set("CLICOLOR_FORCE") {
+        return true;
+    }
+    std::env::var_os("NO_COLOR").is_none_or(|v| v.is_empty()) && std::io::stdout().is_terminal()
+}
+
+/// `<artifacts_dir>/buildfix`, or `<repo_root>/artifacts/buildfix` when the
+/// receipts come from an archive.
+fn default_out_dir(repo_root: &Utf8Path, artifacts_dir: &Utf8Path) -> Utf8PathBuf {
+    if buildfix_receipts::is_archive(artifacts_dir) {
+        repo_root.join("artifacts").join("buildfix")
+    } else {
+        artifacts_dir.join("buildfix")
+    }
+}
+
+/// Receipts from a remote source. A local `cargo metadata` snapshot takes
+/// precedence over one stored next to the remote receipts.
+struct RemoteReceipts {
+    remote: Box<dyn ReceiptSource>,
+    local: FsReceiptSource,
+}
+
+impl ReceiptSource for RemoteReceipts {
+    fn load_receipts(&self) -> anyhow::Result<Vec<buildfix_receipts::LoadedReceipt>> {
+        self.remote.load_receipts()
+    }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        match self.local.load_cargo_metadata()? {
+            Some(json) => Ok(Some(json)),
+            None => self.remote.load_cargo_metadata(),
+        }
+    }
+}
+
+fn http_receipt_source(urls: &[String], headers: &[String]) -> anyhow::Result<HttpReceiptSource> {
+    let receipts = urls
+        .iter()
+        .map(|spec| RemoteReceipt::parse(spec))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let mut source = HttpReceiptSource::new(receipts);
+    for header in headers {
+        let (name, value) = header.split_once(':').with_context(|| {
+            format!(
+                "invalid --receipt-header '{}': expected 'Name: value'",
+                header
+            )
+        })?;
+        source = source.with_header(name.trim(), value.trim());
+    }
+    Ok(source)
+}
+
+fn cmd_apply(args: ApplyArgs) -> anyhow::Result<ExitCode> {
+    let format = args.format;
+    let mut events = EventSink::open(args.events.as_deref())?;
+    let run = execute_apply(args, &mut events)?;
+    match (format, &run.outcome) {
+        (RunOutputFormat::Json, _) => println!("{}", serde_json::to_string_pretty(&run.json()?)?),
+        (_, None) => {}
+        (format, Some(outcome)) => {
+            if format == RunOutputFormat::Pretty {
+                print!(
+                    "{}",
+                    render_apply_pretty(&outcome.apply, &outcome.patch, stdout_color())
+                );
+            }
+            if let Some(verify) = &run.verify {
+                print!("{}", render_verify_text(verify));
+            }
+            if let Some(worktree) = &run.kept_worktree {
+                println!("sandbox: kept worktree at {}", worktree);
+            }
+        }
+    }
+    Ok(ExitCode::from(run.code))
+}
+
+/// What one `apply` run produced; printing is left to the caller.
+struct ApplyRun {
+    out_dir: Utf8PathBuf,
+    code: u8,
+    /// `None` when the pipeline stopped on a policy block.
+    outcome: Option<ApplyOutcome>,
+    /// Sandbox verification, for `--sandbox`.
+    verify: Option<VerifyReport>,
+    kept_worktree: Option<Utf8PathBuf>,
+}
+
+impl ApplyRun {
+    fn blocked(out_dir: Utf8PathBuf) -> Self {
+        Self {
+            out_dir,
+            code: 2,
+            outcome: None,
+            verify: None,
+            kept_worktree: None,
+        }
+    }
+
+    /// The `--format json` document.
+    fn json(&self) -> anyhow::Result<serde_json::Map<String, serde_json::Value>> {
+        let (policy_block, wire) = match &self.outcome {
+            Some(outcome) => {
+                let wire = ApplyV1::try_from(&outcome.apply).context("convert apply to wire")?;
+                (outcome.policy_block, Some(serde_json::to_value(wire)?))
+            }
+            None => (true, None),
+        };
+        let mut out = run_json("apply", self.code, policy_block, &self.out_dir, wire);
+        if let Some(verify) = &self.verify {
+            out.insert(
+                "sandbox".into(),
+                serde_json::json!({
+                    "verify": verify,
+                    "worktree": self.kept_worktree,
+                }),
+            );
+        }
+        Ok(out)
+    }
+}
+
+/// Apply the plan (or sandbox-apply and verify it) and write the artifacts.
+fn execute_apply(args: ApplyArgs, events: &mut EventSink) -> anyhow::Result<ApplyRun> {
+    let repo_root = args.repo_root;
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| repo_root.join("artifacts").join("buildfix"));
+
+    let out_overrides = outputs::parse_out_overrides(&args.out)?;
+    let cli_params = parse_cli_params(&args.param)?;
+    events.emit(
+        "apply_started",
+        serde_json::json!({
+            "repo_root": repo_root,
+            "out_dir": out_dir,
+            "dry_run": !args.apply && !args.sandbox,
+        }),
+    );
+
+    // Load config file and merge with CLI arguments.
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+    let templates = file_config
+        .render
+        .templates(&repo_root)
+        .context("load [render] templates")?;
+    let merged = ConfigMerger::new(file_config).merge_apply_args(
+        args.allow_guarded,
+        args.allow_unsafe,
+        args.auto_commit,
+        args.commit_message.as_deref(),
+        &cli_params,
+    );
+
+    let allow_dirty = args.allow_dirty || merged.allow_dirty;
+    let mode: RunMode = args.mode.into();
+
+    // The sandbox never commits, so [commit] settings do not apply to it.
+    let sandbox_ignores = |requested: bool| requested && !args.sandbox;
+    if args.commit_message.is_some() && !merged.auto_commit {
+        anyhow::bail!(
+            "--commit-message requires auto-commit (set --auto-commit or [commit].enabled = true)"
+        );
+    }
+    if sandbox_ignores(merged.auto_commit) && !args.apply {
+        anyhow::bail!("--auto-commit requires --apply");
+    }
+    if sandbox_ignores(merged.auto_commit) && allow_dirty {
+        anyhow::bail!("--auto-commit requires a clean working tree (do not set --allow-dirty)");
+    }
+    let branch = args.branch.or(merged.branch.clone());
+    let push_remote = args.push.or(merged.push_remote.clone());
+    if sandbox_ignores(push_remote.is_some()) && (branch.is_none() || !merged.auto_commit) {
+        anyhow::bail!(
+            "--push requires --branch and auto-commit (set --auto-commit or [commit].enabled = true)"
+        );
+    }
+
+    debug!(
+        event = telemetry::CONFIG_MERGED,
+        allow_guarded = merged.allow_guarded,
+        allow_unsafe = merged.allow_unsafe,
+        allow_dirty,
+        auto_commit = merged.auto_commit,
+        "merged apply config"
+    );
+
+    let (clock, ids) = clock_and_ids()?;
+    let settings = ApplySettings {
+        repo_root: repo_root.clone(),
+        out_dir: out_dir.clone(),
+        plan_path: args.plan,
+        dry_run: !args.apply,
+        allow_guarded: merged.allow_guarded,
+        allow_unsafe: merged.allow_unsafe,
+        allow_dirty,
+        dirty_scope: args.dirty_scope.map_or(merged.dirty_scope, Into::into),
+        params: merged.params.clone(),
+        auto_commit: merged.auto_commit,
+        commit_message: merged.commit_message.clone(),
+        branch,
+        push_remote,
+        op_ids: args.op_ids,
+        only_fix_keys: args.only,
+        skip_fix_keys: args.skip,
+        packages: args.package,
+        exclude_packages: args.exclude_package,
+        backup_enabled: merged.backups.enabled,
+        backup_suffix: merged.backups.suffix.clone(),
+        backup_keep_last: merged.backups.keep_last,
+        durable_writes: args.durable_writes,
+        binary_patch: args.binary,
+        mode,
+        ci_run: ci_run(),
+        cancel: CancellationToken::new(),
+        clock,
+        ids,
+        metrics: metrics::for_run(args.metrics.as_deref()),
+    };
+
+    let git = GixGitPort;
+    let writer = outputs::RelocatingWritePort::new(&FsWritePort, &out_dir, &out_overrides);
+    let tool = tool_info();
+
+    if args.sandbox {
+        let exec = args.sandbox_exec;
+        let checks = |worktree: &Utf8Path| {
+            let mut checks = vec![cargo_metadata_check(worktree)];
+            checks.extend(exec.iter().map(|cmd| command_check(worktree, cmd)));
+            checks
+        };
+        let result = run_sandbox_apply(&settings, &git, tool, &checks, args.keep_sandbox);
+        metrics::write(args.metrics.as_deref());
+        let sandboxed = match result {
+            Ok(sandboxed) => sandboxed,
+            Err(buildfix_core::pipeline::ToolError::PolicyBlock) => {
+                return Ok(ApplyRun::blocked(out_dir));
+            }
+            Err(buildfix_core::pipeline::ToolError::Internal(e)) => return Err(e),
+        };
+        let outcome = sandboxed.apply;
+        events.apply_results(&outcome.apply);
+        write_apply_artifacts(&outcome, &templates, &out_dir, &writer)?;
+        events.artifacts_written(&out_dir);
+        info!(
+            event = telemetry::ARTIFACTS_WRITTEN,
+            file = %out_dir,
+            "wrote sandbox apply artifacts to {}",
+            out_dir
+        );
+
+        let verify = sandboxed.verify;
+        let code = if (outcome.policy_block && mode != RunMode::Cockpit) || !verify.ok {
+            2
+        } else {
+            0
+        };
+        return Ok(ApplyRun {
+            out_dir,
+            code,
+            outcome: Some(outcome),
+            verify: Some(verify),
+            kept_worktree: sandboxed.kept_worktree,
+        });
+    }
+
+    let result = {
+        let events = RefCell::new(&mut *events);
+        let progress = |p: &Progress<'_>| events.borrow_mut().progress(p);
+        run_apply_with_progress(&settings, &git, &progress, tool)
+    };
+    metrics::write(args.metrics.as_deref());
+    let outcome = match result {
+        Ok(outcome) => outcome,
+        Err(buildfix_core::pipeline::ToolError::PolicyBlock) => {
+            return Ok(ApplyRun::blocked(out_dir));
+        }
+        Err(buildfix_core::pipeline::ToolError::Internal(e)) => return Err(e),
+    };
+
+    events.apply_results(&outcome.apply);
+
+    write_apply_artifacts(&outcome, &templates, &out_dir, &writer)?;
+    events.artifacts_written(&out_dir);
+
+    info!(
+        event = telemetry::ARTIFACTS_WRITTEN,
+        file = %out_dir,
+        "wrote apply artifacts to {}",
+        out_dir
+    );
+    let code = if outcome.policy_block && mode != RunMode::Cockpit {
+        2
+    } else {
+        0
+    };
+    Ok(ApplyRun {
+        out_dir,
+        code,
+        outcome: Some(outcome),
+        verify: None,
+        kept_worktree: None,
+    })
+}
+
+/// Run `plan`, then `apply` against the plan it just wrote. A plan with
+/// blocked ops (exit 2) still proceeds so apply can report what it skipped.
+fn cmd_fix(args: FixArgs) -> anyhow::Result<ExitCode> {
+    let FixArgs {
+        mut plan,
+        apply,
+        allow_guarded,
+        allow_unsafe,
+        allow_dirty,
+        dirty_scope,
+        op_ids,
+        only,
+        skip,
+        auto_commit,
+        commit_message,
+        branch,
+        push,
+        sandbox,
+        sandbox_exec,
+        keep_sandbox,
+        durable_writes,
+    } = args;
+
+    let artifacts_dir = plan
+        .artifacts_dir
+        .clone()
+        .unwrap_or_else(|| plan.repo_root.join("artifacts"));
+    let out_dir = plan
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| default_out_dir(&plan.repo_root, &artifacts_dir));
+    plan.out_dir = Some(out_dir.clone());
+    // Apply reads the plan from wherever `--out plan.json=...` sent it.
+    let plan_path = outputs::parse_out_overrides(&plan.out)?.remove("plan.json");
+
+    let apply_args = ApplyArgs {
+        repo_root: plan.repo_root.clone(),
+        out_dir: Some(out_dir),
+        plan: plan_path,
+        out: plan.out.clone(),
+        apply,
+        allow_guarded,
+        allow_unsafe,
+        allow_dirty,
+        dirty_scope,
+        param: plan.param.clone(),
+        op_ids,
+        only,
+        skip,
+        package: plan.package.clone(),
+        exclude_package: plan.exclude_package.clone(),
+        auto_commit,
+        commit_message,
+        branch,
+        push,
+        sandbox,
+        sandbox_exec,
+        keep_sandbox,
+        binary: plan.binary,
+        durable_writes,
+        mode: plan.mode,
+        format: plan.format,
+        events: plan.events.clone(),
+        metrics: plan.metrics.clone(),
+    };
+
+    let code = cmd_plan(plan)?;
+    if code != ExitCode::SUCCESS && code != ExitCode::from(2) {
+        return Ok(code);
+    }
+    cmd_apply(apply_args)
+}
+
+fn cmd_apply_patch(args: ApplyPatchArgs) -> anyhow::Result<ExitCode> {
+    let repo_root = args.repo_root;
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| repo_root.join("artifacts").join("buildfix"));
+    let patch_path = args.patch.unwrap_or_else(|| out_dir.join("patch.diff"));
+
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+    let merged = ConfigMerger::new(file_config).merge_apply_args(
+        false,
+        false,
+        false,
+        None,
+        &Default::default(),
+    );
+    let mode: RunMode = args.mode.into();
+
+    let (clock, ids) = clock_and_ids()?;
+    let settings = ApplySettings {
+        repo_root: repo_root.clone(),
+        out_dir: out_dir.clone(),
+        plan_path: None,
+        dry_run: !args.apply,
+        allow_guarded: false,
+        allow_unsafe: false,
+        allow_dirty: args.allow_dirty || merged.allow_dirty,
+        dirty_scope: args.dirty_scope.map_or(merged.dirty_scope, Into::into),
+        params: Default::default(),
+        auto_commit: false,
+        commit_message: None,
+        branch: None,
+        push_remote: None,
+        op_ids: Vec::new(),
+        only_fix_keys: Vec::new(),
+        skip_fix_keys: Vec::new(),
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
+        backup_enabled: merged.backups.enabled,
+        backup_suffix: merged.backups.suffix.clone(),
+        backup_keep_last: merged.backups.keep_last,
+        durable_writes: args.durable_writes,
+        binary_patch: false,
+        mode,
+        ci_run: ci_run(),
+        cancel: CancellationToken::new(),
+        clock,
+        ids,
+        metrics: Metrics::default(),
+    };
+
+    let git = GixGitPort;
+    let outcome = match run_apply_patch(&settings, &patch_path, &git) {
+        Ok(outcome) => outcome,
         Err(buildfix_core::pipeline::ToolError::PolicyBlock) => return Ok(ExitCode::from(2)),
         Err(buildfix_core::pipeline::ToolError::Internal(e)) => return Err(e),
     };
 
-    write_plan_artifacts(&outcome, &out_dir, &writer)?;
+    for mismatch in &outcome.result.preconditions.mismatches {
+        error!(
+            event = telemetry::APPLY_PATCH_MISMATCH,
+            file = mismatch.path.as_str(),
+            expected = mismatch.expected.as_str(),
+            actual = mismatch.actual.as_str(),
+            "precondition mismatch: {} (expected {}, actual {})",
+            mismatch.path,
+            mismatch.expected,
+            mismatch.actual
+        );
+    }
+    let verb = if settings.dry_run {
+        "would change"
+    } else {
+        "changed"
+    };
+    for file in &outcome.result.files {
+        info!(
+            event = telemetry::APPLY_PATCH_FILE,
+            file = file.path.as_str(),
+            outcome = verb,
+            "{} {}",
+            verb,
+            file.path
+        );
+    }
+    if let Some(run) = &outcome.result.backup_run {
+        info!(
+            event = telemetry::APPLY_PATCH_BACKUPS,
+            file = %run,
+            "backups written to {}",
+            run
+        );
+    }
+
+    Ok(if outcome.policy_block && mode != RunMode::Cockpit {
+        ExitCode::from(2)
+    } else {
+        ExitCode::from(0)
+    })
+}
+
+fn cmd_validate(args: ValidateArgs) -> anyhow::Result<ExitCode> {
+    let repo_root = args.repo_root;
+    let artifacts_dir = args
+        .artifacts_dir
+        .unwrap_or_else(|| repo_root.join("artifacts"));
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| default_out_dir(&repo_root, &artifacts_dir));
+
+    let receipts = buildfix_receipts::load_receipts(&artifacts_dir)
+        .with_context(|| format!("load receipts from {}", artifacts_dir))?;
+    let mut policy_failures = Vec::new();
+    for r in &receipts {
+        if let Err(e) = &r.receipt {
+            policy_failures.push(format!("{}: {}", r.path, e));
+        }
+    }
+
+    let plan_path = out_dir.join("plan.json");
+    let plan_schema = match plan_schema_id(&plan_path) {
+        Some(id) if id == buildfix_types::schema::BUILDFIX_PLAN_V2 => PLAN_V2_SCHEMA,
+        _ => PLAN_SCHEMA,
+    };
+    for (path, schema) in [
+        (plan_path, plan_schema),
+        (out_dir.join("apply.json"), APPLY_SCHEMA),
+        (out_dir.join("report.json"), REPORT_SCHEMA),
+        (
+            out_dir.join("extras").join("buildfix.report.v1.json"),
+            BUILDFIX_REPORT_SCHEMA,
+        ),
+    ] {
+        match validate_file_if_exists(&path, schema)? {
+            ValidateOutcome::Missing | ValidateOutcome::Ok => {}
+            ValidateOutcome::SchemaErrors(errors) => {
+                for err in errors {
+                    policy_failures.push(format!("{}: {}", path, err));
+                }
+            }
+        }
+    }
+
+    if !policy_failures.is_empty() {
+        for msg in &policy_failures {
+            error!(event = telemetry::VALIDATE_FAILED, "{}", msg);
+        }
+        return Ok(ExitCode::from(2));
+    }
+
+    info!(event = telemetry::VALIDATE_PASSED, "validation successful");
+    Ok(ExitCode::from(0))
+}
+
+/// The `schema` field of the plan at `path`, when it is readable JSON.
+fn plan_schema_id(path: &Utf8Path) -> Option<String> {
+    let contents = fs::read_to_string(path).ok()?;
+    let json: serde_json::Value = serde_json::from_str(&contents).ok()?;
+    json.get("schema")?.as_str().map(str::to_string)
+}
+
+enum ValidateOutcome {
+    Missing,
+    Ok,
+    SchemaErrors(Vec<String>),
+}
+
+fn validate_file_if_exists(path: &Utf8Path, schema_str: &str) -> anyhow::Result<ValidateOutcome> {
+    if !path.exists() {
+        return Ok(ValidateOutcome::Missing);
+    }
+    let contents = fs::read_to_string(path).with_context(|| format!("read {}", path))?;
+    let json: serde_json::Value = serde_json::from_str(&contents).context("parse json")?;
+
+    let schema_json: serde_json::Value =
+        serde_json::from_str(schema_str).context("parse schema")?;
+    let compiled = jsonschema::draft202012::new(&schema_json)
+        .map_err(|e| anyhow::anyhow!("compile schema: {}", e))?;
+    let errors: Vec<String> = compiled.iter_errors(&json).map(|e| e.to_string()).collect();
+    if !errors.is_empty() {
+        return Ok(ValidateOutcome::SchemaErrors(errors));
+    }
+    Ok(ValidateOutcome::Ok)
+}
+
+/// `commit` is the git HEAD the binary was built from, when `build.rs`
+/// could read it.
+fn tool_info() -> ToolInfo {
+    ToolInfo {
+        name: "buildfix".to_string(),
+        version: Some(env!("CARGO_PKG_VERSION").to_string()),
+        repo: Some(env!("CARGO_PKG_REPOSITORY").to_string()),
+        commit: option_env!("BUILDFIX_GIT_COMMIT").map(str::to_string),
+    }
+}
+
+/// The CI run this process is part of, recorded in plan and apply artifacts.
+fn ci_run() -> Option<CiRun> {
+    buildfix_core::provenance::detect_ci_run(&env_nonempty)
+}
+
+/// The clock and id source for plan and apply artifacts; fixed when
+/// `SOURCE_DATE_EPOCH` is set.
+fn clock_and_ids() -> anyhow::Result<(Clock, Ids)> {
+    buildfix_core::provenance::reproducible_sources(&env_nonempty)
+}
+
+/// Explain a fix, or a planned op together with the fix that produced it.
+fn cmd_explain(args: ExplainArgs) -> anyhow::Result<()> {
+    let explained = explain_target(&args)?;
+    match args.format {
+        OutputFormat::Text => print!("{}", explained.text()),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&explained.json())?),
+    }
+    Ok(())
+}
+
+/// What `explain` resolved its argument to.
+enum Explained {
+    Fix(&'static explain::FixExplanation),
+    /// An op in plan.json, its preview diff (or why there is none) and the
+    /// fix behind it.
+    Op {
+        op: Box<buildfix_types::plan::PlanOp>,
+        preview: Result<String, String>,
+        fix: Option<&'static explain::FixExplanation>,
+    },
+}
+
+impl Explained {
+    fn text(&self) -> String {
+        match self {
+            Explained::Fix(fix) => explain::render_explanation(fix, &MessageCatalog::default()),
+            Explained::Op { op, preview, fix } => explain::render_op_explanation(
+                op,
+                preview.as_deref().map_err(String::as_str),
+                *fix,
+                &MessageCatalog::default(),
+            ),
+        }
+    }
+
+    fn json(&self) -> serde_json::Value {
+        match self {
+            Explained::Fix(fix) => explain::fix_json(fix),
+            Explained::Op { op, preview, fix } => {
+                explain::op_json(op, preview.as_deref().map_err(String::as_str), *fix)
+            }
+        }
+    }
+}
+
+/// Look `args.fix_key` up as a fix, then as an op id in the plan.
+fn explain_target(args: &ExplainArgs) -> anyhow::Result<Explained> {
+    use explain::{find_op, fix_for_policy_key, list_fix_keys, lookup_fix};
+
+    if let Some(fix) = lookup_fix(&args.fix_key) {
+        return Ok(Explained::Fix(fix));
+    }
+
+    let repo_root = &args.repo_root;
+    let plan_path = args
+        .plan
+        .clone()
+        .unwrap_or_else(|| repo_root.join("artifacts").join("buildfix"));
+    let plan = if plan_path.is_file() || plan_path.join("plan.json").is_file() {
+        Some(read_plan(&plan_path)?)
+    } else {
+        None
+    };
+    let op = match &plan {
+        Some(plan) => find_op(plan, &args.fix_key)?.cloned(),
+        None => None,
+    };
+    let (Some(op), Some(plan)) = (op, plan) else {
+        let available = list_fix_keys().join(", ");
+        anyhow::bail!(
+            "Unknown fix key or op id: '{}'\n\nAvailable fixes: {}",
+            args.fix_key,
+            available
+        );
+    };
+
+    let file_config = config::load_or_default(repo_root).context("load buildfix.toml config")?;
+    let preview =
+        buildfix_core::pipeline::preview_op(repo_root, &plan, &op.id, &file_config.params)
+            .map_err(|e| format!("{:#}", e));
+    let fix = fix_for_policy_key(&op.rationale.fix_key);
+    Ok(Explained::Op {
+        op: Box::new(op),
+        preview,
+        fix,
+    })
+}
+
+fn cmd_compare(args: CompareArgs) -> anyhow::Result<()> {
+    let old = read_plan(&args.old)?;
+    let new = read_plan(&args.new)?;
+    match args.format {
+        OutputFormat::Text => print!("{}", render_plan_diff(&old, &new)),
+        OutputFormat::Json => print!("{}", render_plan_diff_json(&old, &new)),
+    }
+    Ok(())
+}
+
+fn cmd_merge_plans(args: MergePlansArgs) -> anyhow::Result<()> {
+    let plans = args
+        .plans
+        .iter()
+        .map(|path| read_plan(path))
+        .collect::<anyhow::Result<Vec<_>>>()?;
+    let merged = buildfix_core::merge_plans(plans).context("merge plans")?;
+    let wire = PlanV1::try_from(&merged).context("convert plan to wire")?;
+    let json = serde_json::to_string_pretty(&wire).context("serialize plan")?;
+    match &args.out {
+        Some(out) => {
+            if let Some(parent) = out.parent().filter(|p| !p.as_str().is_empty()) {
+                fs::create_dir_all(parent).with_context(|| format!("create {}", parent))?;
+            }
+            fs::write(out, json).with_context(|| format!("write {}", out))?;
+        }
+        None => println!("{}", json),
+    }
+    Ok(())
+}
+
+/// Browse the plan; on export, write the selection to `<out_dir>/selection.args`
+/// (one argument per line) and print the matching `apply` command.
+#[cfg(feature = "tui")]
+fn cmd_tui(args: TuiArgs) -> anyhow::Result<()> {
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| args.repo_root.join("artifacts").join("buildfix"));
+    let plan = read_plan(&out_dir)?;
+    let patch = fs::read_to_string(out_dir.join("patch.diff")).unwrap_or_default();
+
+    let mut triage = tui::Triage::new(plan, &patch);
+    let Some(apply_args) = tui::run(&mut triage)? else {
+        return Ok(());
+    };
+    // An empty `--op` list would mean "apply everything".
+    if apply_args.is_empty() {
+        info!(
+            event = telemetry::EXPORT_SELECTION,
+            outcome = "empty",
+            "no ops selected; nothing exported"
+        );
+        return Ok(());
+    }
+
+    let args_path = out_dir.join("selection.args");
+    let mut contents = apply_args.join("\n");
+    contents.push('\n');
+    fs::write(&args_path, contents).with_context(|| format!("write {}", args_path))?;
+    info!(
+        event = telemetry::EXPORT_SELECTION,
+        file = %args_path,
+        outcome = "written",
+        "wrote {}",
+        args_path
+    );
+    println!("buildfix apply --apply {}", apply_args.join(" "));
+    Ok(())
+}
+
+fn cmd_status(args: StatusArgs) -> anyhow::Result<()> {
+    let status = run_status(&args)?;
+    match args.format {
+        OutputFormat::Text => print!("{}", render_status_text(&status)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&status)?),
+    }
+    Ok(())
+}
+
+fn run_status(args: &StatusArgs) -> anyhow::Result<RunStatus> {
+    let repo_root = &args.repo_root;
+    let artifacts_dir = args
+        .artifacts_dir
+        .clone()
+        .unwrap_or_else(|| repo_root.join("artifacts"));
+    let out_dir = args
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| default_out_dir(repo_root, &artifacts_dir));
+
+    let file_config = config::load_or_default(repo_root).context("load buildfix.toml config")?;
+    let receipts = FsReceiptSource::new(artifacts_dir)
+        .with_discovery(file_config.receipts.discovery(repo_root));
+    collect_status(repo_root, &out_dir, &receipts, &GixGitPort)
+}
+
+fn cmd_gc(args: GcArgs) -> anyhow::Result<()> {
+    let repo_root = args.repo_root;
+    let artifacts_dir = args
+        .artifacts_dir
+        .unwrap_or_else(|| repo_root.join("artifacts"));
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| default_out_dir(&repo_root, &artifacts_dir));
+
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+    let days = |d: u64| std::time::Duration::from_secs(d * 24 * 60 * 60);
+    let settings = GcSettings {
+        backup_keep_last: args.keep_last.or(file_config.backups.keep_last),
+        backup_max_age: args
+            .backup_max_age_days
+            .or(file_config.gc.backup_max_age_days)
+            .map(days),
+        cache_max_age: args
+            .cache_max_age_days
+            .or(file_config.gc.cache_max_age_days)
+            .map(days),
+        dry_run: args.dry_run,
+    };
+    let report = collect_garbage(&out_dir, &settings)?;
+
+    match args.format {
+        OutputFormat::Text => print!("{}", render_gc_text(&report)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
+    Ok(())
+}
+
+/// Push-side half of an unattended fix: open (or refresh) the pull request
+/// for the branch `apply --branch --push` left behind, with `comment.md` as
+/// its body.
+#[cfg(feature = "forge")]
+fn cmd_pr(args: PrArgs) -> anyhow::Result<()> {
+    use buildfix_core::ports::{ChangeRequest, CheckConclusion, CommitCheck};
+    use buildfix_types::apply::{ApplyStatus, BuildfixApply};
+
+    let repo_root = args.repo_root;
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| default_out_dir(&repo_root, &repo_root.join("artifacts")));
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+
+    let apply_path = out_dir.join("apply.json");
+    let apply: BuildfixApply = serde_json::from_str(
+        &fs::read_to_string(&apply_path).with_context(|| format!("read {}", apply_path))?,
+    )
+    .with_context(|| format!("parse {}", apply_path))?;
+    let applied = apply
+        .results
+        .iter()
+        .filter(|r| r.status == ApplyStatus::Applied)
+        .count();
+    if applied == 0 {
+        anyhow::bail!(
+            "{} records no applied ops; run `buildfix apply --apply` first",
+            apply_path
+        );
+    }
+    let comment_path = out_dir.join("comment.md");
+    let body =
+        fs::read_to_string(&comment_path).with_context(|| format!("read {}", comment_path))?;
+
+    let head = args
+        .head
+        .or_else(|| {
+            apply
+                .branch
+                .as_ref()
+                .filter(|b| b.created)
+                .map(|b| b.name.clone())
+        })
+        .or_else(|| git_stdout(&repo_root, &["branch", "--show-current"]))
+        .context("cannot tell the head branch; pass --head")?;
+
+    let (kind, repo, forge) = connect_forge(args.forge, &file_config.forge, &repo_root)?;
+    let base = match args.base.or(file_config.pr.base.clone()) {
+        Some(base) => base,
+        None => forge.default_branch()?,
+    };
+    let title = args
+        .title
+        .or_else(|| {
+            apply
+                .auto_commit
+                .as_ref()
+                .and_then(|c| c.message.as_deref())
+                .and_then(|m| m.lines().next())
+                .map(str::to_string)
+        })
+        .unwrap_or_else(|| format!("buildfix: apply {} fixes", applied));
+
+    let pr = forge.upsert_change_request(&ChangeRequest {
+        head: head.clone(),
+        base: base.clone(),
+        title,
+        body,
+    })?;
+
+    let mut labels = file_config.pr.labels.clone();
+    for label in args.labels {
+        if !labels.contains(&label) {
+            labels.push(label);
+        }
+    }
+    if !forge.add_labels(pr.number, &labels)? {
+        labels.clear();
+    }
+
+    let check_run_url = if args.no_check_run {
+        None
+    } else {
+        let head_sha = apply
+            .repo
+            .head_sha_after
+            .clone()
+            .or_else(|| git_stdout(&repo_root, &["rev-parse", "HEAD"]))
+            .context("cannot tell the head commit for the check run; pass --no-check-run")?;
+        let conclusion = if apply.summary.failed > 0 {
+            CheckConclusion::Failure
+        } else if apply.summary.blocked > 0 {
+            CheckConclusion::Neutral
+        } else {
+            CheckConclusion::Success
+        };
+        forge.publish_check(&CommitCheck {
+            name: "buildfix".to_string(),
+            head_sha,
+            conclusion,
+            title: format!(
+                "{} ops applied, {} files modified",
+                applied, apply.summary.files_modified
+            ),
+            summary: fs::read_to_string(out_dir.join("apply.md")).unwrap_or_default(),
+            text: fs::read_to_string(out_dir.join("plan.md")).ok(),
+            details_url: None,
+        })?
+    };
+
+    match args.format {
+        OutputFormat::Text => {
+            let verb = if pr.created { "Opened" } else { "Updated" };
+            let noun = if kind == ForgeKind::Gitlab {
+                "merge request !"
+            } else {
+                "pull request #"
+            };
+            println!("{} {}{}: {}", verb, noun, pr.number, pr.url);
+            if !labels.is_empty() {
+                println!("Labels: {}", labels.join(", "));
+            }
+            if let Some(url) = &check_run_url {
+                println!("Check run: {}", url);
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "provider": forge.name(),
+                "repo": repo,
+                "head": head,
+                "base": base,
+                "pull_request": {
+                    "number": pr.number,
+                    "url": pr.url,
+                    "created": pr.created,
+                },
+                "labels": labels,
+                "check_run_url": check_run_url,
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// Sticky summary comment: render `comment.md` from `plan.json` and keep it
+/// in one bot comment on the pull request CI is building, found again by a
+/// hidden marker on later runs.
+#[cfg(feature = "forge")]
+fn cmd_comment(args: CommentArgs) -> anyhow::Result<()> {
+    const MARKER: &str = "<!-- buildfix:comment -->";
+
+    let repo_root = args.repo_root;
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| default_out_dir(&repo_root, &repo_root.join("artifacts")));
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+    let templates = file_config
+        .render
+        .templates(&repo_root)
+        .context("load [render] templates")?;
+
+    let plan = read_plan(&out_dir)?;
+    let body = templates.render_comment_md(&plan)?;
+
+    let (kind, repo, forge) = connect_forge(args.forge, &file_config.forge, &repo_root)?;
+    let number = args
+        .number
+        .or_else(|| ci_change_request_number(kind))
+        .context("cannot tell the pull request from the CI environment; pass --number")?;
+    let comment = forge.upsert_comment(number, MARKER, &body)?;
+
+    let sigil = if kind == ForgeKind::Gitlab { "!" } else { "#" };
+    match args.format {
+        OutputFormat::Text => {
+            let verb = if comment.created { "Posted" } else { "Updated" };
+            print!("{} buildfix comment on {}{}{}", verb, repo, sigil, number);
+            match &comment.url {
+                Some(url) => println!(": {}", url),
+                None => println!(),
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "provider": forge.name(),
+                "repo": repo,
+                "number": number,
+                "comment": {
+                    "id": comment.id,
+                    "url": comment.url,
+                    "created": comment.created,
+                },
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// Merge gate: publish `report.json`'s verdict (pass → success, warn →
+/// neutral, fail → failure, skip → skipped) on a commit, as a check run
+/// carrying the markdown summary where the forge has them, else as a commit
+/// status.
+#[cfg(feature = "forge")]
+fn cmd_check(args: CheckArgs) -> anyhow::Result<()> {
+    use buildfix_core::ports::{CheckConclusion, CommitCheck};
+    use buildfix_types::report::BuildfixReport;
+
+    let repo_root = args.repo_root;
+    let out_dir = args
+        .out_dir
+        .unwrap_or_else(|| default_out_dir(&repo_root, &repo_root.join("artifacts")));
+    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
+
+    let report_path = out_dir.join("report.json");
+    let report: BuildfixReport = serde_json::from_str(
+        &fs::read_to_string(&report_path).with_context(|| format!("read {}", report_path))?,
+    )
+    .with_context(|| format!("parse {}", report_path))?;
+    let status = report.verdict.status;
+    let status_name = serde_json::to_value(status)?
+        .as_str()
+        .unwrap_or_default()
+        .to_string();
+
+    // An apply report leads with apply.md; a plan report with plan.md.
+    let from_apply = report
+        .data
+        .as_ref()
+        .is_some_and(|data| data.pointer("/buildfix/apply").is_some());
+    let (summary, text) = if from_apply {
+        (
+            fs::read_to_string(out_dir.join("apply.md")).unwrap_or_default(),
+            fs::read_to_string(out_dir.join("plan.md")).ok(),
+        )
+    } else {
+        (
+            fs::read_to_string(out_dir.join("plan.md")).unwrap_or_default(),
+            None,
+        )
+    };
+
+    let (kind, repo, forge) = connect_forge(args.forge, &file_config.forge, &repo_root)?;
+    let head_sha = args
+        .sha
+        .or_else(|| ci_head_sha(kind))
+        .or_else(|| git_stdout(&repo_root, &["rev-parse", "HEAD"]))
+        .context("cannot tell the commit to report on; pass --sha")?;
+    let title = if report.verdict.reasons.is_empty() {
+        status_name.clone()
+    } else {
+        format!("{}: {}", status_name, report.verdict.reasons.join(", "))
+    };
+    let check = CommitCheck {
+        name: args.name,
+        head_sha,
+        conclusion: CheckConclusion::from(status),
+        title,
+        summary,
+        text,
+        details_url: args.details_url.or_else(|| ci_job_url(kind)),
+    };
 
-    info!("wrote plan to {}", out_dir);
+    let check_run_url = if args.commit_status {
+        None
+    } else {
+        forge.publish_check(&check)?
+    };
+    let published = match &check_run_url {
+        Some(_) => "check_run",
+        None if forge.publish_status(&check)? => "commit_status",
+        None => anyhow::bail!("{} has neither check runs nor commit statuses", kind.name()),
+    };
 
-    Ok(if outcome.policy_block && mode != RunMode::Cockpit {
-        ExitCode::from(2)
+    match args.format {
+        OutputFormat::Text => {
+            print!(
+                "Published {} {} ({}) on {}@{}",
+                published.replace('_', " "),
+                check.name,
+                check.conclusion.as_str(),
+                repo,
+                check.head_sha
+            );
+            match &check_run_url {
+                Some(url) => println!(": {}", url),
+                None => println!(),
+            }
+        }
+        OutputFormat::Json => println!(
+            "{}",
+            serde_json::to_string_pretty(&serde_json::json!({
+                "provider": forge.name(),
+                "repo": repo,
+                "sha": check.head_sha,
+                "status": status,
+                "conclusion": check.conclusion.as_str(),
+                "published": published,
+                "check_run_url": check_run_url,
+            }))?
+        ),
+    }
+    Ok(())
+}
+
+/// Resolve the forge, repository and token for `pr`, `comment` and `check`: flags
+/// first, then `[forge]`, then the provider's CI variables and the `origin`
+/// remote.
+#[cfg(feature = "forge")]
+fn connect_forge(
+    args: ForgeArgs,
+    forge_config: &config::ForgeConfig,
+    repo_root: &Utf8Path,
+) -> anyhow::Result<(ForgeKind, String, Box<dyn buildfix_core::ports::ForgePort>)> {
+    let origin_url = git_stdout(repo_root, &["remote", "get-url", "origin"]);
+    let kind = if args.github {
+        ForgeKind::Github
+    } else if args.gitlab {
+        ForgeKind::Gitlab
+    } else if args.bitbucket {
+        ForgeKind::Bitbucket
+    } else if let Some(provider) = forge_config.provider {
+        provider.into()
     } else {
-        ExitCode::from(0)
-    })
+        origin_url
+            .as_deref()
+            .and_then(ForgeKind::from_remote_url)
+            .context(
+                "cannot tell the forge; pass --github, --gitlab or --bitbucket, or set [forge] provider",
+            )?
+    };
+
+    let token = match &forge_config.token_env {
+        Some(var) => env_nonempty(var)
+            .with_context(|| format!("[forge] token_env names {}, which is not set", var))?,
+        None => kind
+            .token_env_vars()
+            .iter()
+            .find_map(|var| env_nonempty(var))
+            .with_context(|| {
+                format!(
+                    "set {} to a token that can write pull requests",
+                    kind.token_env_vars().join(" or ")
+                )
+            })?,
+    };
+    let repo = args
+        .repo
+        .or_else(|| forge_config.repo.clone())
+        .or_else(|| env_nonempty(kind.repo_env_var()))
+        .or_else(|| origin_url.as_deref().and_then(repo_path_from_remote_url))
+        .with_context(|| {
+            format!(
+                "cannot tell the {} repository; pass --repo OWNER/NAME",
+                kind.name()
+            )
+        })?;
+    let api_url = args
+        .api_url
+        .or_else(|| forge_config.api_url.clone())
+        .or_else(|| kind.api_url_env_var().and_then(env_nonempty))
+        .unwrap_or_else(|| kind.default_api_url().to_string());
+
+    let forge = kind.connect(&api_url, &repo, &token);
+    Ok((kind, repo, forge))
 }
 
-fn cmd_apply(args: ApplyArgs) -> anyhow::Result<ExitCode> {
+/// The pull (merge) request a CI job is building: the `pull_request` event
+/// payload or `refs/pull/<n>/…` on GitHub Actions, `CI_MERGE_REQUEST_IID` on
+/// GitLab, `BITBUCKET_PR_ID` on Bitbucket Pipelines.
+#[cfg(feature = "forge")]
+fn ci_change_request_number(kind: ForgeKind) -> Option<u64> {
+    match kind {
+        ForgeKind::Github => github_event()
+            .and_then(|event| event.pointer("/pull_request/number")?.as_u64())
+            .or_else(|| {
+                env_nonempty("GITHUB_REF")?
+                    .strip_prefix("refs/pull/")?
+                    .split('/')
+                    .next()?
+                    .parse()
+                    .ok()
+            }),
+        ForgeKind::Gitlab => env_nonempty("CI_MERGE_REQUEST_IID")?.parse().ok(),
+        ForgeKind::Bitbucket => env_nonempty("BITBUCKET_PR_ID")?.parse().ok(),
+    }
+}
+
+/// The pull request's head commit on GitHub Actions, where HEAD is a
+/// merge commit no status would show on. `None` elsewhere.
+#[cfg(feature = "forge")]
+fn ci_head_sha(kind: ForgeKind) -> Option<String> {
+    match kind {
+        ForgeKind::Github => github_event()?
+            .pointer("/pull_request/head/sha")?
+            .as_str()
+            .map(str::to_string),
+        ForgeKind::Gitlab | ForgeKind::Bitbucket => None,
+    }
+}
+
+/// The running CI job's page, for linking a check or status back to it.
+#[cfg(feature = "forge")]
+fn ci_job_url(kind: ForgeKind) -> Option<String> {
+    match kind {
+        ForgeKind::Github => Some(format!(
+            "{}/{}/actions/runs/{}",
+            env_nonempty("GITHUB_SERVER_URL")?,
+            env_nonempty("GITHUB_REPOSITORY")?,
+            env_nonempty("GITHUB_RUN_ID")?
+        )),
+        ForgeKind::Gitlab => env_nonempty("CI_JOB_URL"),
+        ForgeKind::Bitbucket => Some(format!(
+            "https://bitbucket.org/{}/pipelines/results/{}",
+            env_nonempty("BITBUCKET_REPO_FULL_NAME")?,
+            env_nonempty("BITBUCKET_BUILD_NUMBER")?
+        )),
+    }
+}
+
+/// The GitHub Actions event payload.
+#[cfg(feature = "forge")]
+fn github_event() -> Option<serde_json::Value> {
+    let event = std::fs::read_to_string(env_nonempty("GITHUB_EVENT_PATH")?).ok()?;
+    serde_json::from_str(&event).ok()
+}
+
+fn env_nonempty(name: &str) -> Option<String> {
+    std::env::var(name).ok().filter(|v| !v.is_empty())
+}
+
+/// Trimmed stdout of a successful `git <args>`, `None` on failure or empty output.
+#[cfg(feature = "forge")]
+fn git_stdout(repo_root: &Utf8Path, args: &[&str]) -> Option<String> {
+    let out = std::process::Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .ok()?;
+    let stdout = String::from_utf8_lossy(&out.stdout).trim().to_string();
+    (out.status.success() && !stdout.is_empty()).then_some(stdout)
+}
+
+fn render_gc_text(report: &GcReport) -> String {
+    let (verb, summary) = if report.dry_run {
+        ("would remove", "would reclaim")
+    } else {
+        ("removed", "reclaimed")
+    };
+    let mut out = String::new();
+    for entry in &report.removed {
+        let kind = match entry.kind {
+            GcKind::Backup => "backup run",
+            GcKind::Cache => "cache file",
+        };
+        out.push_str(&format!(
+            "{} {} {} ({} bytes)\n",
+            verb, kind, entry.path, entry.bytes
+        ));
+    }
+    out.push_str(&format!(
+        "{} {} bytes ({} backup runs, {} cache files) in {}\n",
+        summary,
+        report.reclaimed_bytes,
+        report.count(GcKind::Backup),
+        report.count(GcKind::Cache),
+        report.out_dir
+    ));
+    out
+}
+
+fn render_status_text(status: &RunStatus) -> String {
+    let mut out = String::new();
+    let Some(plan) = &status.plan else {
+        out.push_str(&format!("plan: none in {}\n", status.out_dir));
+        out.push_str("next: run `buildfix plan`\n");
+        return out;
+    };
+
+    out.push_str(&format!(
+        "plan: {} ({} ops, {} blocked, {} files)\n",
+        plan.path, plan.ops_total, plan.ops_blocked, plan.files_touched
+    ));
+    match (&plan.head_sha, &plan.current_head_sha) {
+        (Some(planned), Some(current)) if plan.head_changed => {
+            out.push_str(&format!("  HEAD: moved ({} -> {})\n", planned, current));
+        }
+        (Some(planned), _) => out.push_str(&format!("  HEAD: unchanged ({})\n", planned)),
+        (None, _) => out.push_str("  HEAD: not recorded\n"),
+    }
+    if plan.receipts_changed.is_empty()
+        && plan.receipts_missing.is_empty()
+        && plan.receipts_added.is_empty()
+    {
+        out.push_str("  receipts: unchanged\n");
+    } else {
+        for (label, paths) in [
+            ("changed", &plan.receipts_changed),
+            ("missing", &plan.receipts_missing),
+            ("new", &plan.receipts_added),
+        ] {
+            for path in paths {
+                out.push_str(&format!("  receipts: {} {}\n", label, path));
+            }
+        }
+    }
+    if plan.drifted_files.is_empty() {
+        out.push_str("  preconditions: no drift\n");
+    } else {
+        out.push_str(&format!(
+            "  preconditions: drifted {}\n",
+            plan.drifted_files.join(", ")
+        ));
+    }
+
+    match &status.apply {
+        None => out.push_str("apply: not run\n"),
+        Some(apply) => {
+            let what = if apply.wrote_changes {
+                format!("applied {} ops", apply.applied)
+            } else {
+                "dry run only".to_string()
+            };
+            let target = if apply.matches_plan {
+                "this plan"
+            } else {
+                "a different plan"
+            };
+            out.push_str(&format!(
+                "apply: {} from {} ({} blocked, {} failed, {} files modified)\n",
+                what, target, apply.blocked, apply.failed, apply.files_modified
+            ));
+            if let Some(sha) = &apply.commit_sha {
+                out.push_str(&format!("  committed: {}\n", sha));
+            }
+        }
+    }
+
+    match &status.backups.latest {
+        Some(latest) => out.push_str(&format!(
+            "backups: {} runs (latest {})\n",
+            status.backups.runs, latest
+        )),
+        None => out.push_str("backups: none\n"),
+    }
+
+    let next = if status.is_stale() {
+        "re-run `buildfix plan`"
+    } else if status
+        .apply
+        .as_ref()
+        .is_some_and(|a| a.matches_plan && a.wrote_changes)
+    {
+        "nothing to do"
+    } else {
+        "review plan.md, then `buildfix apply --apply`"
+    };
+    out.push_str(&format!("next: {}\n", next));
+    out
+}
+
+/// Exit 2 when any check fails, like `validate`.
+fn cmd_verify(args: VerifyArgs) -> anyhow::Result<ExitCode> {
     let repo_root = args.repo_root;
     let out_dir = args
         .out_dir
         .unwrap_or_else(|| repo_root.join("artifacts").join("buildfix"));
 
-    let cli_params = parse_cli_params(&args.param)?;
+    let mut report = verify_apply(&repo_root, &out_dir)?;
+    if args.cargo_metadata {
+        report.push(cargo_metadata_check(&repo_root));
+    }
 
-    // Load config file and merge with CLI arguments.
-    let file_config = config::load_or_default(&repo_root).context("load buildfix.toml config")?;
-    let merged = ConfigMerger::new(file_config).merge_apply_args(
-        args.allow_guarded,
-        args.allow_unsafe,
-        args.auto_commit,
-        args.commit_message.as_deref(),
-        &cli_params,
-    );
+    match args.format {
+        OutputFormat::Text => print!("{}", render_verify_text(&report)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&report)?),
+    }
 
-    let allow_dirty = args.allow_dirty || merged.allow_dirty;
-    let mode: RunMode = args.mode.into();
+    Ok(if report.ok {
+        ExitCode::from(0)
+    } else {
+        ExitCode::from(2)
+    })
+}
 
-    if args.commit_message.is_some() && !merged.auto_commit {
-        anyhow::bail!(
-            "--commit-message requires auto-commit (set --auto-commit or [commit].enabled = true)"
-        );
+/// One line per check, then a summary line.
+fn render_verify_text(report: &VerifyReport) -> String {
+    let mut out = String::new();
+    for check in &report.checks {
+        let kind = match check.kind {
+            VerifyCheckKind::FileHash => "hash",
+            VerifyCheckKind::ManifestParse => "parse",
+            VerifyCheckKind::CargoMetadata => "cargo-metadata",
+            VerifyCheckKind::Command => "command",
+        };
+        let mut line = format!(
+            "{:<5} {:<15} {}",
+            if check.ok { "ok" } else { "FAIL" },
+            kind,
+            check.path.as_deref().unwrap_or("")
+        )
+        .trim_end()
+        .to_string();
+        if let Some(message) = &check.message {
+            line.push_str(&format!(": {}", message));
+        }
+        out.push_str(&line);
+        out.push('\n');
     }
-    if merged.auto_commit && !args.apply {
-        anyhow::bail!("--auto-commit requires --apply");
+    let failed = report.failures().count();
+    if report.checks.is_empty() {
+        out.push_str(&format!(
+            "verify: no applied changes recorded in {}\n",
+            report.apply_path
+        ));
+    } else if failed == 0 {
+        out.push_str(&format!(
+            "verify: all {} checks passed\n",
+            report.checks.len()
+        ));
+    } else {
+        out.push_str(&format!(
+            "verify: {} of {} checks failed\n",
+            failed,
+            report.checks.len()
+        ));
     }
-    if merged.auto_commit && allow_dirty {
-        anyhow::bail!("--auto-commit requires a clean working tree (do not set --allow-dirty)");
+    out
+}
+
+/// Pages carry no date, so repeated builds produce identical output.
+fn cmd_man(args: ManArgs) -> anyhow::Result<()> {
+    let cmd = Cli::command();
+    match args.out_dir {
+        Some(out_dir) => {
+            fs::create_dir_all(&out_dir)?;
+            clap_mangen::generate_to(cmd, &out_dir)
+                .with_context(|| format!("write man pages to {}", out_dir))?;
+        }
+        None => clap_mangen::Man::new(cmd).render(&mut std::io::stdout())?,
     }
+    Ok(())
+}
 
-    debug!(
-        "merged config: allow_guarded={}, allow_unsafe={}, allow_dirty={}, auto_commit={}",
-        merged.allow_guarded, merged.allow_unsafe, allow_dirty, merged.auto_commit
-    );
+fn cmd_init(args: InitArgs) -> anyhow::Result<()> {
+    let facts = init::detect(&args.repo_root)?;
+    let contents = init::render(&facts);
+    if args.stdout {
+        print!("{}", contents);
+        return Ok(());
+    }
 
-    let settings = ApplySettings {
-        repo_root: repo_root.clone(),
-        out_dir: out_dir.clone(),
-        dry_run: !args.apply,
-        allow_guarded: merged.allow_guarded,
-        allow_unsafe: merged.allow_unsafe,
-        allow_dirty,
-        params: merged.params.clone(),
-        auto_commit: merged.auto_commit,
-        commit_message: merged.commit_message.clone(),
-        backup_enabled: merged.backups.enabled,
-        backup_suffix: merged.backups.suffix.clone(),
-        mode,
+    let path = args.repo_root.join(config::CONFIG_FILE_NAME);
+    if path.exists() && !args.force {
+        anyhow::bail!("{} already exists; pass --force to overwrite", path);
+    }
+    fs::write(&path, contents).with_context(|| format!("write {}", path))?;
+    println!("wrote {}", path);
+    Ok(())
+}
+
+/// Exit 1 when any check fails; warnings alone still exit 0.
+/// Plan once, then again after each debounced batch of changes. A failed
+/// plan is reported as `plan_failed` and watching goes on.
+#[cfg(feature = "watch")]
+fn cmd_watch(args: WatchArgs) -> anyhow::Result<()> {
+    let WatchArgs {
+        plan,
+        debounce_ms,
+        max_runs,
+    } = args;
+    let artifacts_dir = plan
+        .artifacts_dir
+        .clone()
+        .unwrap_or_else(|| plan.repo_root.join("artifacts"));
+    let out_dir = plan
+        .out_dir
+        .clone()
+        .unwrap_or_else(|| default_out_dir(&plan.repo_root, &artifacts_dir));
+    let set = watch::WatchSet::discover(&plan.repo_root, &artifacts_dir, &out_dir)?;
+    let mut events = match plan.events.as_deref() {
+        Some(target) => EventSink::open(Some(target))?,
+        None => EventSink::stdout(),
     };
+    events.emit(
+        "watch_started",
+        serde_json::json!({
+            "artifacts_dir": set.artifacts,
+            "manifest_dirs": set.manifest_dirs,
+        }),
+    );
 
-    let git = ShellGitPort;
-    let writer = FsWritePort;
-    let tool = tool_info();
+    let mut runs = 0;
+    watch::run(
+        &set,
+        std::time::Duration::from_millis(debounce_ms),
+        |changed| {
+            if !changed.is_empty() {
+                events.emit("change_detected", serde_json::json!({ "paths": changed }));
+            }
+            if let Err(e) = plan_with_events(plan.clone(), &mut events) {
+                error!(event = telemetry::WATCH_PLAN_FAILED, "plan failed: {:#}", e);
+                events.emit(
+                    "plan_failed",
+                    serde_json::json!({ "error": format!("{:#}", e) }),
+                );
+            }
+            runs += 1;
+            if max_runs.is_some_and(|max| runs >= max) {
+                std::ops::ControlFlow::Break(())
+            } else {
+                std::ops::ControlFlow::Continue(())
+            }
+        },
+    )
+}
 
-    let outcome = match run_apply(&settings, &git, tool) {
-        Ok(outcome) => outcome,
-        Err(buildfix_core::pipeline::ToolError::PolicyBlock) => return Ok(ExitCode::from(2)),
-        Err(buildfix_core::pipeline::ToolError::Internal(e)) => return Err(e),
-    };
+fn cmd_serve(args: ServeArgs) -> anyhow::Result<()> {
+    let warm = serve::WarmCache::default();
+    let mut handler = |method: &str, params: Vec<String>| serve_request(&warm, method, params);
+    #[cfg(unix)]
+    if let Some(path) = &args.socket {
+        return serve::serve_socket(path, &mut handler);
+    }
+    let _ = args;
+    serve::serve_stream(std::io::stdin().lock(), std::io::stdout(), &mut handler)?;
+    Ok(())
+}
+
+/// Run one `serve` method with its CLI arguments. Results match the
+/// command's `--format json` output.
+fn serve_request(
+    warm: &serve::WarmCache,
+    method: &str,
+    params: Vec<String>,
+) -> Result<serde_json::Value, serve::RpcError> {
+    fn parse<T: Parser>(method: &str, params: Vec<String>) -> Result<T, serve::RpcError> {
+        T::try_parse_from(std::iter::once(method.to_string()).chain(params))
+            .map_err(|e| serve::RpcError::new(serve::INVALID_PARAMS, e.to_string()))
+    }
 
-    write_apply_artifacts(&outcome, &out_dir, &writer)?;
+    let result = match method {
+        "plan" => {
+            let args: PlanArgs = parse(method, params)?;
+            let mut events = EventSink::open(args.events.as_deref())?;
+            execute_plan(args, &mut events, Some(warm))
+                .and_then(|run| run.json())
+                .map(serde_json::Value::Object)
+        }
+        "apply" => {
+            let args: ApplyArgs = parse(method, params)?;
+            let mut events = EventSink::open(args.events.as_deref())?;
+            execute_apply(args, &mut events)
+                .and_then(|run| run.json())
+                .map(serde_json::Value::Object)
+        }
+        "explain" => {
+            let args: ExplainArgs = parse(method, params)?;
+            explain_target(&args).map(|explained| explained.json())
+        }
+        "status" => {
+            let args: StatusArgs = parse(method, params)?;
+            run_status(&args).and_then(|status| Ok(serde_json::to_value(status)?))
+        }
+        _ => {
+            return Err(serve::RpcError::new(
+                serve::METHOD_NOT_FOUND,
+                format!(
+                    "unknown method '{}' (plan, apply, explain, status, shutdown)",
+                    method
+                ),
+            ));
+        }
+    };
+    Ok(result?)
+}
 
-    info!("wrote apply artifacts to {}", out_dir);
+fn cmd_hook(args: HookArgs) -> anyhow::Result<ExitCode> {
+    let HookRunArgs { mut plan, hook } = match args.cmd {
+        HookCommand::Install(args) => {
+            let path = hook::install(&args.repo_root, args.hook, args.force)?;
+            println!("installed {} hook at {}", args.hook.name(), path);
+            return Ok(ExitCode::from(0));
+        }
+        HookCommand::Run(args) => *args,
+    };
 
-    Ok(if outcome.policy_block && mode != RunMode::Cockpit {
-        ExitCode::from(2)
-    } else {
+    match hook {
+        hook::HookKind::PreCommit => plan.staged = true,
+        hook::HookKind::PrePush => {
+            plan.changed_only
+                .get_or_insert_with(|| "origin/HEAD".to_string());
+        }
+    }
+    let out_dir = plan.out_dir.clone().unwrap_or_else(|| {
+        let artifacts_dir = plan
+            .artifacts_dir
+            .clone()
+            .unwrap_or_else(|| plan.repo_root.join("artifacts"));
+        default_out_dir(&plan.repo_root, &artifacts_dir)
+    });
+    let plan_path = outputs::parse_out_overrides(&plan.out)?
+        .remove("plan.json")
+        .unwrap_or_else(|| out_dir.join("plan.json"));
+    let quiet = plan.format == RunOutputFormat::Quiet;
+
+    let code = cmd_plan(plan)?;
+    if code != ExitCode::SUCCESS && code != ExitCode::from(2) {
+        return Ok(code);
+    }
+    let plan = read_plan(&plan_path)?;
+    // Other formats already printed the plan itself.
+    if quiet {
+        print!("{}", hook::render_summary(&plan, hook));
+    }
+    Ok(if hook::pending_safe_ops(&plan).is_empty() {
         ExitCode::from(0)
+    } else {
+        ExitCode::from(2)
     })
 }
 
-fn cmd_validate(args: ValidateArgs) -> anyhow::Result<ExitCode> {
+fn cmd_doctor(args: DoctorArgs) -> anyhow::Result<ExitCode> {
     let repo_root = args.repo_root;
     let artifacts_dir = args
         .artifacts_dir
         .unwrap_or_else(|| repo_root.join("artifacts"));
     let out_dir = args
         .out_dir
-        .unwrap_or_else(|| artifacts_dir.join("buildfix"));
-
-    let receipts = buildfix_receipts::load_receipts(&artifacts_dir)
-        .with_context(|| format!("load receipts from {}", artifacts_dir))?;
-    let mut policy_failures = Vec::new();
-    for r in &receipts {
-        if let Err(e) = &r.receipt {
-            policy_failures.push(format!("{}: {}", r.path, e));
-        }
-    }
+        .unwrap_or_else(|| default_out_dir(&repo_root, &artifacts_dir));
 
-    for (path, schema) in [
-        (out_dir.join("plan.json"), PLAN_SCHEMA),
-        (out_dir.join("apply.json"), APPLY_SCHEMA),
-        (out_dir.join("report.json"), REPORT_SCHEMA),
+    let schemas: [doctor::SchemaEntry; 5] = [
+        ("buildfix.plan.v1.json", "buildfix.plan.v1", PLAN_SCHEMA),
+        ("buildfix.plan.v2.json", "buildfix.plan.v2", PLAN_V2_SCHEMA),
+        ("buildfix.apply.v1.json", "buildfix.apply.v1", APPLY_SCHEMA),
         (
-            out_dir.join("extras").join("buildfix.report.v1.json"),
+            "buildfix.report.v1.json",
+            "buildfix.report.v1",
             BUILDFIX_REPORT_SCHEMA,
         ),
-    ] {
-        match validate_file_if_exists(&path, schema)? {
-            ValidateOutcome::Missing | ValidateOutcome::Ok => {}
-            ValidateOutcome::SchemaErrors(errors) => {
-                for err in errors {
-                    policy_failures.push(format!("{}: {}", path, err));
-                }
-            }
-        }
-    }
+        ("sensor.report.v1.json", "sensor.report.v1", REPORT_SCHEMA),
+    ];
+    let checks = doctor::run_checks(&repo_root, &artifacts_dir, &out_dir, &schemas);
 
-    if !policy_failures.is_empty() {
-        for msg in &policy_failures {
-            error!("{}", msg);
-        }
-        return Ok(ExitCode::from(2));
+    match args.format {
+        OutputFormat::Text => print!("{}", doctor::render_text(&checks)),
+        OutputFormat::Json => println!("{}", serde_json::to_string_pretty(&checks)?),
     }
 
-    info!("validation successful");
-    Ok(ExitCode::from(0))
-}
-
-enum ValidateOutcome {
-    Missing,
-    Ok,
-    SchemaErrors(Vec<String>),
+    let failed = checks.iter().any(|c| c.status == doctor::CheckStatus::Fail);
+    Ok(ExitCode::from(if failed { 1 } else { 0 }))
 }
 
-fn validate_file_if_exists(path: &Utf8Path, schema_str: &str) -> anyhow::Result<ValidateOutcome> {
-    if !path.exists() {
-        return Ok(ValidateOutcome::Missing);
-    }
-    let contents = fs::read_to_string(path).with_context(|| format!("read {}", path))?;
-    let json: serde_json::Value = serde_json::from_str(&contents).context("parse json")?;
-
-    let schema_json: serde_json::Value =
-        serde_json::from_str(schema_str).context("parse schema")?;
-    let compiled = jsonschema::draft202012::new(&schema_json)
-        .map_err(|e| anyhow::anyhow!("compile schema: {}", e))?;
-    let errors: Vec<String> = compiled.iter_errors(&json).map(|e| e.to_string()).collect();
-    if !errors.is_empty() {
-        return Ok(ValidateOutcome::SchemaErrors(errors));
+fn cargo_metadata_check(repo_root: &Utf8Path) -> VerifyCheck {
+    let output = std::process::Command::new("cargo")
+        .args(["metadata", "--no-deps", "--format-version", "1"])
+        .current_dir(repo_root)
+        .output();
+    let message = match output {
+        Ok(out) if out.status.success() => None,
+        Ok(out) => Some(
+            String::from_utf8_lossy(&out.stderr)
+                .lines()
+                .find(|l| l.starts_with("error"))
+                .unwrap_or("cargo metadata failed")
+                .to_string(),
+        ),
+        Err(e) => Some(format!("run cargo: {}", e)),
+    };
+    VerifyCheck {
+        kind: VerifyCheckKind::CargoMetadata,
+        path: None,
+        ok: message.is_none(),
+        message,
     }
-    Ok(ValidateOutcome::Ok)
 }
 
-fn tool_info() -> ToolInfo {
-    ToolInfo {
-        name: "buildfix".to_string(),
-        version: Some(env!("CARGO_PKG_VERSION").to_string()),
-        repo: None,
-        commit: None,
+/// Run `command` through the shell in `dir`; the check's path is the
+/// command itself.
+fn command_check(dir: &Utf8Path, command: &str) -> VerifyCheck {
+    let mut shell = if cfg!(windows) {
+        let mut cmd = std::process::Command::new("cmd");
+        cmd.arg("/C");
+        cmd
+    } else {
+        let mut cmd = std::process::Command::new("sh");
+        cmd.arg("-c");
+        cmd
+    };
+    let message = match shell.arg(command).current_dir(dir).output() {
+        Ok(out) if out.status.success() => None,
+        Ok(out) => Some(
+            String::from_utf8_lossy(&out.stderr)
+                .lines()
+                .rev()
+                .find(|l| !l.trim().is_empty())
+                .map_or_else(|| out.status.to_string(), str::to_string),
+        ),
+        Err(e) => Some(format!("run shell: {}", e)),
+    };
+    VerifyCheck {
+        kind: VerifyCheckKind::Command,
+        path: Some(command.to_string()),
+        ok: message.is_none(),
+        message,
     }
 }
 
-fn cmd_explain(args: ExplainArgs) -> anyhow::Result<()> {
-    use explain::{
-        format_safety_class, list_fix_keys, lookup_fix, policy_keys, safety_class_meaning,
-    };
-
-    let Some(fix) = lookup_fix(&args.fix_key) else {
-        let available = list_fix_keys().join(", ");
-        anyhow::bail!(
-            "Unknown fix key: '{}'\n\nAvailable fixes: {}",
-            args.fix_key,
-            available
-        );
+/// Read a plan from a plan.json path or a directory containing plan.json.
+fn read_plan(path: &Utf8Path) -> anyhow::Result<BuildfixPlan> {
+    let path = if path.is_dir() {
+        path.join("plan.json")
+    } else {
+        path.to_path_buf()
     };
-
-    // Title and basic info
-    println!("================================================================================");
-    println!("FIX: {}", fix.title);
-    println!("================================================================================");
-    println!();
-    println!("Key:     {}", fix.key);
-    println!("Fix ID:  {}", fix.fix_id);
-    println!("Policy:  {}", policy_keys(fix).join(", "));
-    println!("Safety:  {}", format_safety_class(fix.safety));
-    println!();
-
-    // Description
-    println!("DESCRIPTION");
-    println!("--------------------------------------------------------------------------------");
-    println!("{}", fix.description);
-    println!();
-
-    // Triggering findings
-    println!("TRIGGERING FINDINGS");
-    println!("--------------------------------------------------------------------------------");
-    println!("This fix is triggered by sensor findings matching:");
-    println!();
-    for trigger in fix.triggers {
-        let code_part = trigger
-            .code
-            .map(|c| format!(" / {}", c))
-            .unwrap_or_default();
-        println!("  - {} / {}{}", trigger.sensor, trigger.check_id, code_part);
-    }
-    println!();
-
-    // Safety class explanation
-    println!("SAFETY CLASS: {}", format_safety_class(fix.safety));
-    println!("--------------------------------------------------------------------------------");
-    println!("{}", safety_class_meaning(fix.safety));
-    println!();
-
-    // Safety rationale
-    println!("SAFETY RATIONALE");
-    println!("--------------------------------------------------------------------------------");
-    println!("{}", fix.safety_rationale);
-    println!();
-
-    // Remediation guidance
-    println!("REMEDIATION GUIDANCE");
-    println!("--------------------------------------------------------------------------------");
-    println!("{}", fix.remediation);
-    println!();
-
-    Ok(())
+    let contents = fs::read_to_string(&path).with_context(|| format!("read {}", path))?;
+    parse_plan(&contents).with_context(|| format!("parse {}", path))
 }
 
 fn cmd_list_fixes(args: ListFixesArgs) -> anyhow::Result<()> {
-    use explain::{enabled_fixes, format_safety_class, policy_keys};
+    use explain::{filter_fixes, format_safety_class, policy_keys};
+
+    let fixes = filter_fixes(args.sensor.as_deref(), args.check_id.as_deref());
+    if let Some(artifacts_dir) = &args.coverage {
+        return list_fix_coverage(&args, artifacts_dir, &fixes);
+    }
 
     match args.format {
         OutputFormat::Text => {
             println!("Available fixes:\n");
             println!("  {:<24} {:<10} TITLE", "KEY", "SAFETY");
             println!("  {:<24} {:<10} -----", "---", "------");
-            for fix in &enabled_fixes() {
+            for fix in &fixes {
                 let policy = policy_keys(fix).join(", ");
                 println!(
                     "  {:<24} {:<10} {}",
@@ -550,7 +3184,7 @@ fn cmd_list_fixes(args: ListFixesArgs) -> anyhow::Result<()> {
             println!("Use 'buildfix explain <key>' for details.");
         }
         OutputFormat::Json => {
-            let fixes: Vec<_> = enabled_fixes()
+            let fixes: Vec<_> = fixes
                 .iter()
                 .map(|f| {
                     serde_json::json!({
@@ -568,6 +3202,106 @@ fn cmd_list_fixes(args: ListFixesArgs) -> anyhow::Result<()> {
     Ok(())
 }
 
+/// `list-fixes --coverage`: which of `fixes` the receipts in `artifacts_dir`
+/// trigger, could trigger, or lack a sensor for.
+fn list_fix_coverage(
+    args: &ListFixesArgs,
+    artifacts_dir: &Utf8Path,
+    fixes: &[&'static explain::FixExplanation],
+) -> anyhow::Result<()> {
+    use explain::{CoverageStatus, fix_coverage, format_safety_class, policy_keys};
+
+    let file_config =
+        config::load_or_default(&args.repo_root).context("load buildfix.toml config")?;
+    let discovery = file_config.receipts.discovery(&args.repo_root);
+    let loaded = buildfix_receipts::discover_receipts(artifacts_dir, &discovery, None, None)
+        .with_context(|| format!("read receipts from {}", artifacts_dir))?;
+    let mut receipts = Vec::new();
+    let mut unreadable = Vec::new();
+    for r in &loaded {
+        match &r.receipt {
+            Ok(envelope) => receipts.push(envelope.clone()),
+            Err(e) => unreadable.push((r.path.clone(), e.to_string())),
+        }
+    }
+    let rows: Vec<_> = fixes.iter().map(|f| fix_coverage(f, &receipts)).collect();
+    let count = |status| rows.iter().filter(|r| r.status == status).count();
+
+    match args.format {
+        OutputFormat::Text => {
+            println!(
+                "Fix coverage for {} ({} receipts):\n",
+                artifacts_dir,
+                receipts.len()
+            );
+            println!("  {:<24} {:<10} DETAIL", "KEY", "STATUS");
+            println!("  {:<24} {:<10} ------", "---", "------");
+            for row in &rows {
+                let (status, detail) = match row.status {
+                    CoverageStatus::Triggered => {
+                        ("triggered", format!("{} matching findings", row.findings))
+                    }
+                    CoverageStatus::Ready => ("ready", "no matching findings".to_string()),
+                    CoverageStatus::Missing => (
+                        "missing",
+                        format!("no receipt from {}", row.missing_sensors.join(", ")),
+                    ),
+                };
+                println!("  {:<24} {:<10} {}", row.fix.key, status, detail);
+                if row.status != CoverageStatus::Missing && !row.missing_sensors.is_empty() {
+                    println!("    no receipt from: {}", row.missing_sensors.join(", "));
+                }
+                if !row.unchecked_check_ids.is_empty() {
+                    println!(
+                        "    not run by its sensor: {}",
+                        row.unchecked_check_ids.join(", ")
+                    );
+                }
+            }
+            for (path, error) in &unreadable {
+                println!("  warning: {}: {}", path, error);
+            }
+            println!();
+            println!(
+                "{} triggered, {} ready, {} missing inputs",
+                count(CoverageStatus::Triggered),
+                count(CoverageStatus::Ready),
+                count(CoverageStatus::Missing)
+            );
+        }
+        OutputFormat::Json => {
+            let fixes: Vec<_> = rows
+                .iter()
+                .map(|row| {
+                    serde_json::json!({
+                        "key": row.fix.key,
+                        "fix_id": row.fix.fix_id,
+                        "title": row.fix.title,
+                        "safety": format_safety_class(row.fix.safety).to_lowercase(),
+                        "policy_keys": policy_keys(row.fix),
+                        "status": row.status,
+                        "findings": row.findings,
+                        "missing_sensors": row.missing_sensors,
+                        "unchecked_check_ids": row.unchecked_check_ids,
+                    })
+                })
+                .collect();
+            let unreadable: Vec<_> = unreadable
+                .iter()
+                .map(|(path, error)| serde_json::json!({ "path": path, "error": error }))
+                .collect();
+            let report = serde_json::json!({
+                "artifacts_dir": artifacts_dir,
+                "receipts": receipts.len(),
+                "unreadable": unreadable,
+                "fixes": fixes,
+            });
+            println!("{}", serde_json::to_string_pretty(&report)?);
+        }
+    }
+    Ok(())
+}
+
 #[cfg(test)]
 mod tests {
     use super::{ValidateOutcome, validate_file_if_exists};
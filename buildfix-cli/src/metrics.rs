@@ -0,0 +1,59 @@
+//! Prometheus metrics for `--metrics`.
+//!
+//! Every run in the process records into one registry, and the whole registry
+//! is rewritten to the file after each run, so `fix` (a plan then an apply)
+//! leaves both runs in it. The file is replaced atomically, as node_exporter's
+//! textfile collector expects.
+
+use buildfix_core::adapters::PrometheusMetrics;
+use buildfix_core::settings::Metrics;
+use buildfix_types::telemetry;
+use camino::Utf8Path;
+use fs_err as fs;
+use std::sync::{Arc, OnceLock};
+use tracing::warn;
+
+static REGISTRY: OnceLock<Arc<PrometheusMetrics>> = OnceLock::new();
+
+fn registry() -> &'static Arc<PrometheusMetrics> {
+    REGISTRY.get_or_init(|| Arc::new(PrometheusMetrics::new()))
+}
+
+/// The metrics handle for a run: the process registry when `--metrics` is
+/// given, else a no-op.
+pub fn for_run(path: Option<&Utf8Path>) -> Metrics {
+    match path {
+        Some(_) => Metrics::new(Arc::clone(registry())),
+        None => Metrics::default(),
+    }
+}
+
+/// Rewrite `path` with everything recorded so far. Failures are logged, not
+/// fatal: losing metrics should not fail the run.
+pub fn write(path: Option<&Utf8Path>) {
+    let Some(path) = path else {
+        return;
+    };
+    if let Err(e) = write_atomic(path, &registry().render()) {
+        warn!(
+            event = telemetry::METRICS_WRITE_FAILED,
+            file = %path,
+            error = format!("{:#}", e),
+            "failed to write metrics to {}: {:#}",
+            path,
+            e
+        );
+    }
+}
+
+fn write_atomic(path: &Utf8Path, contents: &str) -> anyhow::Result<()> {
+    if let Some(parent) = path.parent()
+        && !parent.as_str().is_empty()
+    {
+        fs::create_dir_all(parent)?;
+    }
+    let tmp = path.with_extension("prom.tmp");
+    fs::write(&tmp, contents)?;
+    fs::rename(&tmp, path)?;
+    Ok(())
+}
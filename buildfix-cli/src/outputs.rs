@@ -0,0 +1,108 @@
+//! `--out NAME=PATH`: write individual artifacts outside the out dir.
+
+use buildfix_core::ports::WritePort;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::BTreeMap;
+
+/// Artifact names accepted by `--out`, as laid out under the out dir.
+pub const ARTIFACT_NAMES: &[&str] = &[
+    "plan.json",
+    "plan.md",
+    "comment.md",
+    "report.html",
+    "gl-code-quality-report.json",
+    "plan.junit.xml",
+    "checkstyle.xml",
+    "plan.csv",
+    "plan.tsv",
+    "apply.json",
+    "apply.md",
+    "apply.junit.xml",
+    "patch.diff",
+    "report.json",
+    "extras/buildfix.report.v1.json",
+];
+
+/// Parse repeated `NAME=PATH` values. Later values for the same name win.
+pub fn parse_out_overrides(specs: &[String]) -> anyhow::Result<BTreeMap<String, Utf8PathBuf>> {
+    let mut overrides = BTreeMap::new();
+    for spec in specs {
+        let Some((name, path)) = spec.split_once('=') else {
+            anyhow::bail!("invalid --out '{}': expected NAME=PATH", spec);
+        };
+        if !ARTIFACT_NAMES.contains(&name) {
+            anyhow::bail!(
+                "unknown artifact '{}' in --out; expected one of: {}",
+                name,
+                ARTIFACT_NAMES.join(", ")
+            );
+        }
+        if path.is_empty() {
+            anyhow::bail!("invalid --out '{}': empty path", spec);
+        }
+        overrides.insert(name.to_string(), Utf8PathBuf::from(path));
+    }
+    Ok(overrides)
+}
+
+/// Sends writes for overridden artifacts to their `--out` path and
+/// everything else to the wrapped port unchanged.
+pub struct RelocatingWritePort<'a> {
+    inner: &'a dyn WritePort,
+    out_dir: &'a Utf8Path,
+    overrides: &'a BTreeMap<String, Utf8PathBuf>,
+}
+
+impl<'a> RelocatingWritePort<'a> {
+    pub fn new(
+        inner: &'a dyn WritePort,
+        out_dir: &'a Utf8Path,
+        overrides: &'a BTreeMap<String, Utf8PathBuf>,
+    ) -> Self {
+        Self {
+            inner,
+            out_dir,
+            overrides,
+        }
+    }
+}
+
+impl RelocatingWritePort<'_> {
+    /// Where `path` is written: its override (with the parent created), or
+    /// `path` itself.
+    fn target<'p>(&'p self, path: &'p Utf8Path) -> anyhow::Result<&'p Utf8Path> {
+        let target = path
+            .strip_prefix(self.out_dir)
+            .ok()
+            .and_then(|rel| self.overrides.get(rel.as_str()));
+        match target {
+            Some(target) => {
+                if let Some(parent) = target.parent()
+                    && !parent.as_str().is_empty()
+                {
+                    self.inner.create_dir_all(parent)?;
+                }
+                Ok(target)
+            }
+            None => Ok(path),
+        }
+    }
+}
+
+impl WritePort for RelocatingWritePort<'_> {
+    fn write_file(&self, path: &Utf8Path, contents: &[u8]) -> anyhow::Result<()> {
+        self.inner.write_file(self.target(path)?, contents)
+    }
+
+    fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()> {
+        self.inner.create_dir_all(path)
+    }
+
+    fn write_file_with(
+        &self,
+        path: &Utf8Path,
+        write: &mut dyn FnMut(&mut dyn std::io::Write) -> std::io::Result<()>,
+    ) -> anyhow::Result<()> {
+        self.inner.write_file_with(self.target(path)?, write)
+    }
+}
@@ -0,0 +1,526 @@
+//! `buildfix serve`: JSON-RPC 2.0 over stdio or a Unix socket.
+//!
+//! One request per line, one response per line. Params are the method's
+//! CLI arguments as an array of strings; results are what the command would
+//! print with `--format json`. The process stays up between requests, so
+//! [`WarmCache`] keeps parsed receipts and manifests around until the files
+//! behind them change.
+
+use buildfix_core::{GitignoreMatcher, RepoView};
+use buildfix_core_runtime::ReceiptSource;
+use buildfix_receipts::{CacheStats, LoadedReceipt};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde_json::{Value, json};
+use std::cell::Cell;
+use std::collections::HashMap;
+use std::io::{BufRead, Write};
+use std::sync::{Arc, Mutex};
+use std::time::SystemTime;
+use toml_edit::DocumentMut;
+
+/// JSON-RPC error codes.
+pub const PARSE_ERROR: i64 = -32700;
+pub const INVALID_REQUEST: i64 = -32600;
+pub const METHOD_NOT_FOUND: i64 = -32601;
+pub const INVALID_PARAMS: i64 = -32602;
+/// The command ran and failed (exit code 1 on the CLI).
+pub const TOOL_ERROR: i64 = -32000;
+
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+        }
+    }
+}
+
+impl From<anyhow::Error> for RpcError {
+    fn from(e: anyhow::Error) -> Self {
+        Self::new(TOOL_ERROR, format!("{:#}", e))
+    }
+}
+
+/// Answers one method call with its CLI arguments.
+pub type Handler<'a> = dyn FnMut(&str, Vec<String>) -> Result<Value, RpcError> + 'a;
+
+/// Serve requests from `input` until it ends or a `shutdown` request
+/// arrives; returns whether shutdown was requested. Requests without an
+/// `id` are notifications and get no response.
+pub fn serve_stream(
+    input: impl BufRead,
+    mut output: impl Write,
+    handler: &mut Handler<'_>,
+) -> anyhow::Result<bool> {
+    for line in input.lines() {
+        let line = line?;
+        if line.trim().is_empty() {
+            continue;
+        }
+        let (id, outcome, shutdown) = match parse_request(&line) {
+            Ok(request) => {
+                let shutdown = request.method == "shutdown";
+                let outcome = if shutdown {
+                    Ok(Value::Null)
+                } else {
+                    handler(&request.method, request.params)
+                };
+                match request.id {
+                    Some(id) => (id, outcome, shutdown),
+                    None if shutdown => return Ok(true),
+                    None => continue,
+                }
+            }
+            Err((id, error)) => (id, Err(error), false),
+        };
+        let response = match outcome {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(e) => json!({
+                "jsonrpc": "2.0",
+                "id": id,
+                "error": { "code": e.code, "message": e.message },
+            }),
+        };
+        serde_json::to_writer(&mut output, &response)?;
+        output.write_all(b"\n")?;
+        output.flush()?;
+        if shutdown {
+            return Ok(true);
+        }
+    }
+    Ok(false)
+}
+
+struct Request {
+    id: Option<Value>,
+    method: String,
+    params: Vec<String>,
+}
+
+/// The request in `line`, or the id to answer with and the error.
+fn parse_request(line: &str) -> Result<Request, (Value, RpcError)> {
+    let value: Value = serde_json::from_str(line)
+        .map_err(|e| (Value::Null, RpcError::new(PARSE_ERROR, e.to_string())))?;
+    let Value::Object(mut request) = value else {
+        return Err((
+            Value::Null,
+            RpcError::new(
+                INVALID_REQUEST,
+                "expected a request object (batches are not supported)",
+            ),
+        ));
+    };
+    let id = request.remove("id");
+    let reply_id = id.clone().unwrap_or(Value::Null);
+    let invalid = |message: &str| (reply_id.clone(), RpcError::new(INVALID_REQUEST, message));
+    if request.get("jsonrpc") != Some(&json!("2.0")) {
+        return Err(invalid("\"jsonrpc\" must be \"2.0\""));
+    }
+    let Some(Value::String(method)) = request.remove("method") else {
+        return Err(invalid("\"method\" must be a string"));
+    };
+    let params = match request.remove("params") {
+        None => Vec::new(),
+        Some(Value::Array(items)) => items
+            .into_iter()
+            .map(|item| match item {
+                Value::String(arg) => Ok(arg),
+                _ => Err(()),
+            })
+            .collect::<Result<_, _>>()
+            .map_err(|()| {
+                (
+                    reply_id.clone(),
+                    RpcError::new(INVALID_PARAMS, "params must be an array of CLI arguments"),
+                )
+            })?,
+        Some(_) => {
+            return Err((
+                reply_id,
+                RpcError::new(INVALID_PARAMS, "params must be an array of CLI arguments"),
+            ));
+        }
+    };
+    Ok(Request { id, method, params })
+}
+
+/// Accept connections on a Unix socket at `path`, one at a time, until a
+/// client sends `shutdown`. A stale socket file is replaced; the socket is
+/// removed on shutdown.
+#[cfg(unix)]
+pub fn serve_socket(path: &Utf8Path, handler: &mut Handler<'_>) -> anyhow::Result<()> {
+    use anyhow::Context;
+    use std::os::unix::fs::FileTypeExt;
+    use std::os::unix::net::UnixListener;
+
+    if std::fs::symlink_metadata(path).is_ok_and(|m| m.file_type().is_socket()) {
+        std::fs::remove_file(path).with_context(|| format!("remove stale socket {}", path))?;
+    }
+    let listener = UnixListener::bind(path).with_context(|| format!("bind {}", path))?;
+    tracing::info!(
+        event = buildfix_types::telemetry::SERVE_LISTENING,
+        file = %path,
+        "serving JSON-RPC on {}",
+        path
+    );
+    for stream in listener.incoming() {
+        let stream = stream.context("accept connection")?;
+        let reader = std::io::BufReader::new(stream.try_clone()?);
+        match serve_stream(reader, &stream, handler) {
+            Ok(true) => break,
+            Ok(false) => {}
+            // A client hanging up mid-response should not stop the server.
+            Err(e) => tracing::warn!(
+                event = buildfix_types::telemetry::SERVE_CONNECTION_FAILED,
+                "connection failed: {:#}",
+                e
+            ),
+        }
+    }
+    std::fs::remove_file(path).with_context(|| format!("remove socket {}", path))?;
+    Ok(())
+}
+
+/// Size and modification time; a change in either invalidates a cached
+/// read.
+type Stamp = (u64, Option<SystemTime>);
+
+/// Every input file of a receipt load, with its stamp.
+type Stamps = Vec<(Utf8PathBuf, Stamp)>;
+
+fn stamp(path: &Utf8Path) -> Option<Stamp> {
+    let meta = std::fs::metadata(path).ok()?;
+    Some((meta.len(), meta.modified().ok()))
+}
+
+/// State kept between requests.
+#[derive(Default)]
+pub struct WarmCache {
+    /// Per artifacts dir: the stamps of every input file, and what loading
+    /// them produced.
+    receipts: Mutex<HashMap<Utf8PathBuf, (Stamps, Vec<LoadedReceipt>)>>,
+    /// Manifest contents by absolute path.
+    files: Mutex<HashMap<Utf8PathBuf, (Stamp, String)>>,
+    /// Parsed manifests by absolute path.
+    docs: Mutex<HashMap<Utf8PathBuf, (Stamp, Arc<DocumentMut>)>>,
+}
+
+impl WarmCache {
+    /// `inner` with its loaded receipts reused while nothing under
+    /// `artifacts_dir` (outside `out_dir`) or `buildfix.toml` changed.
+    pub fn receipts<'a, R: ReceiptSource>(
+        &'a self,
+        inner: R,
+        repo_root: &Utf8Path,
+        artifacts_dir: &Utf8Path,
+        out_dir: &Utf8Path,
+    ) -> WarmReceipts<'a, R> {
+        WarmReceipts {
+            cache: self,
+            inner,
+            config: repo_root.join("buildfix.toml"),
+            artifacts_dir: artifacts_dir.to_path_buf(),
+            out_dir: out_dir.to_path_buf(),
+            warm_hits: Cell::new(None),
+        }
+    }
+
+    /// A repo view that re-reads a manifest only when it changed.
+    pub fn repo_view(&self, root: &Utf8Path) -> WarmRepoView<'_> {
+        WarmRepoView {
+            cache: self,
+            root: root.to_path_buf(),
+            gitignore: GitignoreMatcher::new(root),
+        }
+    }
+}
+
+pub struct WarmReceipts<'a, R> {
+    cache: &'a WarmCache,
+    inner: R,
+    config: Utf8PathBuf,
+    artifacts_dir: Utf8PathBuf,
+    out_dir: Utf8PathBuf,
+    /// Receipts served from memory by the last load, if it was warm.
+    warm_hits: Cell<Option<u64>>,
+}
+
+impl<R> WarmReceipts<'_, R> {
+    fn stamps(&self) -> Stamps {
+        let mut stamps = Vec::new();
+        let mut pending = vec![self.artifacts_dir.clone()];
+        while let Some(path) = pending.pop() {
+            if path == self.out_dir {
+                continue;
+            }
+            match std::fs::read_dir(&path) {
+                Ok(entries) => pending.extend(
+                    entries
+                        .flatten()
+                        .filter_map(|e| Utf8PathBuf::from_path_buf(e.path()).ok()),
+                ),
+                Err(_) => {
+                    if let Some(stamp) = stamp(&path) {
+                        stamps.push((path, stamp));
+                    }
+                }
+            }
+        }
+        if let Some(stamp) = stamp(&self.config) {
+            stamps.push((self.config.clone(), stamp));
+        }
+        stamps.sort();
+        stamps
+    }
+}
+
+impl<R: ReceiptSource> ReceiptSource for WarmReceipts<'_, R> {
+    fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        let stamps = self.stamps();
+        let mut cache = self.cache.receipts.lock().expect("receipt cache lock");
+        if let Some((cached_stamps, receipts)) = cache.get(&self.artifacts_dir)
+            && *cached_stamps == stamps
+        {
+            self.warm_hits.set(Some(receipts.len() as u64));
+            return Ok(receipts.clone());
+        }
+        self.warm_hits.set(None);
+        let receipts = self.inner.load_receipts()?;
+        cache.insert(self.artifacts_dir.clone(), (stamps, receipts.clone()));
+        Ok(receipts)
+    }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        self.inner.load_cargo_metadata()
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        match self.warm_hits.get() {
+            Some(hits) => Some(CacheStats { hits, misses: 0 }),
+            None => self.inner.cache_stats(),
+        }
+    }
+}
+
+pub struct WarmRepoView<'a> {
+    cache: &'a WarmCache,
+    root: Utf8PathBuf,
+    gitignore: GitignoreMatcher,
+}
+
+impl RepoView for WarmRepoView<'_> {
+    fn root(&self) -> &Utf8Path {
+        &self.root
+    }
+
+    fn read_to_string(&self, rel: &Utf8Path) -> anyhow::Result<String> {
+        use anyhow::Context;
+
+        let abs = self.root.join(rel);
+        let current = stamp(&abs);
+        let mut files = self.cache.files.lock().expect("file cache lock");
+        if let (Some(current), Some((cached, contents))) = (current, files.get(&abs))
+            && current == *cached
+        {
+            return Ok(contents.clone());
+        }
+        let contents = fs_err::read_to_string(&abs).with_context(|| format!("read {}", abs))?;
+        if let Some(current) = current {
+            files.insert(abs, (current, contents.clone()));
+        }
+        Ok(contents)
+    }
+
+    fn exists(&self, rel: &Utf8Path) -> bool {
+        self.root.join(rel).exists()
+    }
+
+    fn is_gitignored(&self, rel: &Utf8Path) -> bool {
+        self.gitignore.is_ignored(rel)
+    }
+
+    fn read_toml(&self, rel: &Utf8Path) -> anyhow::Result<Arc<DocumentMut>> {
+        use anyhow::Context;
+
+        let abs = self.root.join(rel);
+        let current = stamp(&abs);
+        if let (Some(current), Some((cached, doc))) = (
+            current,
+            self.cache.docs.lock().expect("doc cache lock").get(&abs),
+        ) && current == *cached
+        {
+            return Ok(Arc::clone(doc));
+        }
+        let doc = Arc::new(
+            self.read_to_string(rel)?
+                .parse::<DocumentMut>()
+                .with_context(|| format!("parse {}", abs))?,
+        );
+        if let Some(current) = current {
+            self.cache
+                .docs
+                .lock()
+                .expect("doc cache lock")
+                .insert(abs, (current, Arc::clone(&doc)));
+        }
+        Ok(doc)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Call = (String, Vec<String>);
+
+    fn run(input: &str) -> (Vec<Value>, bool, Vec<Call>) {
+        let mut calls = Vec::new();
+        let mut output = Vec::new();
+        let shutdown = serve_stream(input.as_bytes(), &mut output, &mut |method, params| {
+            calls.push((method.to_string(), params.clone()));
+            match method {
+                "echo" => Ok(json!(params)),
+                _ => Err(RpcError::new(
+                    METHOD_NOT_FOUND,
+                    format!("unknown method {}", method),
+                )),
+            }
+        })
+        .unwrap();
+        let responses = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|l| serde_json::from_str(l).unwrap())
+            .collect();
+        (responses, shutdown, calls)
+    }
+
+    #[test]
+    fn answers_requests_and_skips_notifications() {
+        let (responses, shutdown, calls) = run(concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"echo","params":["--x","y"]}"#,
+            "\n\n",
+            r#"{"jsonrpc":"2.0","method":"echo"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":"b","method":"nope"}"#,
+            "\n",
+        ));
+        assert!(!shutdown);
+        assert_eq!(calls.len(), 3);
+        assert_eq!(
+            responses,
+            vec![
+                json!({"jsonrpc": "2.0", "id": 1, "result": ["--x", "y"]}),
+                json!({"jsonrpc": "2.0", "id": "b", "error": {"code": METHOD_NOT_FOUND, "message": "unknown method nope"}}),
+            ]
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_requests() {
+        let (responses, _, calls) = run(concat!(
+            "{not json\n",
+            "[]\n",
+            r#"{"id":1,"method":"echo"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"echo","params":{"a":1}}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":3,"method":"echo","params":[1]}"#,
+            "\n",
+        ));
+        assert!(calls.is_empty());
+        let codes: Vec<_> = responses
+            .iter()
+            .map(|r| (r["id"].clone(), r["error"]["code"].as_i64().unwrap()))
+            .collect();
+        assert_eq!(
+            codes,
+            vec![
+                (Value::Null, PARSE_ERROR),
+                (Value::Null, INVALID_REQUEST),
+                (json!(1), INVALID_REQUEST),
+                (json!(2), INVALID_PARAMS),
+                (json!(3), INVALID_PARAMS),
+            ]
+        );
+    }
+
+    #[test]
+    fn stops_at_shutdown() {
+        let (responses, shutdown, calls) = run(concat!(
+            r#"{"jsonrpc":"2.0","id":1,"method":"shutdown"}"#,
+            "\n",
+            r#"{"jsonrpc":"2.0","id":2,"method":"echo"}"#,
+            "\n",
+        ));
+        assert!(shutdown);
+        assert!(calls.is_empty());
+        assert_eq!(
+            responses,
+            vec![json!({"jsonrpc": "2.0", "id": 1, "result": null})]
+        );
+    }
+
+    struct CountingSource(Cell<usize>);
+
+    impl ReceiptSource for CountingSource {
+        fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+            self.0.set(self.0.get() + 1);
+            Ok(Vec::new())
+        }
+    }
+
+    #[test]
+    fn reuses_receipts_and_manifests_until_they_change() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let artifacts = root.join("artifacts");
+        std::fs::create_dir_all(artifacts.join("builddiag")).unwrap();
+        std::fs::write(artifacts.join("builddiag/report.json"), "{}").unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\n").unwrap();
+
+        let cache = WarmCache::default();
+        let loads = |n: usize| {
+            let source = cache.receipts(
+                CountingSource(Cell::new(0)),
+                root,
+                &artifacts,
+                &artifacts.join("buildfix"),
+            );
+            for _ in 0..n {
+                source.load_receipts().unwrap();
+            }
+            source.inner.0.get()
+        };
+        assert_eq!(loads(2), 1);
+        // Output files do not invalidate the cache; new receipts do.
+        std::fs::create_dir_all(artifacts.join("buildfix")).unwrap();
+        std::fs::write(artifacts.join("buildfix/plan.json"), "{}").unwrap();
+        assert_eq!(loads(1), 0);
+        std::fs::write(artifacts.join("builddiag/report.json"), "{\"x\":1}").unwrap();
+        assert_eq!(loads(1), 1);
+
+        let view = cache.repo_view(root);
+        assert_eq!(
+            view.read_to_string(Utf8Path::new("Cargo.toml")).unwrap(),
+            "[workspace]\n"
+        );
+        let doc = view.read_toml(Utf8Path::new("Cargo.toml")).unwrap();
+        let again = view.read_toml(Utf8Path::new("Cargo.toml")).unwrap();
+        assert!(Arc::ptr_eq(&doc, &again));
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nresolver = \"2\"\n").unwrap();
+        assert!(
+            view.read_to_string(Utf8Path::new("Cargo.toml"))
+                .unwrap()
+                .contains("resolver")
+        );
+        let doc = view.read_toml(Utf8Path::new("Cargo.toml")).unwrap();
+        assert_eq!(doc["workspace"]["resolver"].as_str(), Some("2"));
+    }
+}
@@ -0,0 +1,434 @@
+//! Interactive plan triage for `buildfix tui` (behind the `tui` feature).
+//!
+//! Browses `plan.json` next to its patch preview, filters ops by safety,
+//! fixer, or file, and toggles ops in and out of a selection. Exporting
+//! turns the selection into `--op` arguments for a follow-up `apply`.
+
+use buildfix_render::patch_sections;
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use ratatui::crossterm::event::{self, Event, KeyCode, KeyEventKind};
+use ratatui::layout::{Constraint, Layout};
+use ratatui::style::{Color, Style, Stylize};
+use ratatui::text::{Line, Span};
+use ratatui::widgets::{Block, List, ListItem, ListState, Paragraph};
+use ratatui::{DefaultTerminal, Frame};
+use std::collections::{BTreeMap, BTreeSet};
+
+const HELP: &str =
+    " ↑↓ move  space toggle  a all  s safety  f fixer  p file  PgUp/PgDn diff  e export  q quit ";
+
+/// Triage state: the plan, per-file diffs, active filters, and the ops
+/// picked so far. Kept free of terminal I/O so it can be tested directly.
+pub struct Triage {
+    plan: BuildfixPlan,
+    diffs: BTreeMap<String, String>,
+    safety: Option<SafetyClass>,
+    fixer: Option<String>,
+    file: Option<String>,
+    selected: BTreeSet<String>,
+    cursor: usize,
+    diff_scroll: u16,
+}
+
+impl Triage {
+    /// Start with every unblocked op selected, matching what a plain
+    /// `apply` would attempt.
+    pub fn new(plan: BuildfixPlan, patch: &str) -> Self {
+        let diffs = patch_sections(patch)
+            .into_iter()
+            .map(|(path, section)| (path.to_string(), section.to_string()))
+            .collect();
+        let selected = plan
+            .ops
+            .iter()
+            .filter(|op| !op.blocked)
+            .map(|op| op.id.clone())
+            .collect();
+        Self {
+            plan,
+            diffs,
+            safety: None,
+            fixer: None,
+            file: None,
+            selected,
+            cursor: 0,
+            diff_scroll: 0,
+        }
+    }
+
+    /// Ops passing the current filters, in plan order.
+    pub fn visible(&self) -> Vec<&PlanOp> {
+        self.plan
+            .ops
+            .iter()
+            .filter(|op| self.safety.is_none_or(|s| op.safety == s))
+            .filter(|op| {
+                self.fixer
+                    .as_deref()
+                    .is_none_or(|f| op.rationale.fix_key == f)
+            })
+            .filter(|op| self.file.as_deref().is_none_or(|f| op.target.path == f))
+            .collect()
+    }
+
+    fn current(&self) -> Option<&PlanOp> {
+        self.visible().get(self.cursor).copied()
+    }
+
+    pub fn is_selected(&self, op: &PlanOp) -> bool {
+        self.selected.contains(&op.id)
+    }
+
+    pub fn move_cursor(&mut self, delta: isize) {
+        let len = self.visible().len();
+        if len == 0 {
+            self.cursor = 0;
+            return;
+        }
+        self.cursor = self.cursor.saturating_add_signed(delta).min(len - 1);
+        self.diff_scroll = 0;
+    }
+
+    pub fn toggle_current(&mut self) {
+        if let Some(id) = self.current().map(|op| op.id.clone())
+            && !self.selected.remove(&id)
+        {
+            self.selected.insert(id);
+        }
+    }
+
+    /// Select every visible op, or clear them all if they already are.
+    pub fn toggle_visible(&mut self) {
+        let ids: Vec<String> = self.visible().iter().map(|op| op.id.clone()).collect();
+        if ids.iter().all(|id| self.selected.contains(id)) {
+            for id in &ids {
+                self.selected.remove(id);
+            }
+        } else {
+            self.selected.extend(ids);
+        }
+    }
+
+    pub fn cycle_safety(&mut self) {
+        self.safety = match self.safety {
+            None => Some(SafetyClass::Safe),
+            Some(SafetyClass::Safe) => Some(SafetyClass::Guarded),
+            Some(SafetyClass::Guarded) => Some(SafetyClass::Unsafe),
+            Some(SafetyClass::Unsafe) => None,
+        };
+        self.cursor = 0;
+    }
+
+    pub fn cycle_fixer(&mut self) {
+        let keys: BTreeSet<&str> = self
+            .plan
+            .ops
+            .iter()
+            .map(|op| op.rationale.fix_key.as_str())
+            .collect();
+        self.fixer = next_value(&keys, self.fixer.as_deref());
+        self.cursor = 0;
+    }
+
+    pub fn cycle_file(&mut self) {
+        let files: BTreeSet<&str> = self
+            .plan
+            .ops
+            .iter()
+            .map(|op| op.target.path.as_str())
+            .collect();
+        self.file = next_value(&files, self.file.as_deref());
+        self.cursor = 0;
+    }
+
+    pub fn scroll_diff(&mut self, delta: i16) {
+        self.diff_scroll = self.diff_scroll.saturating_add_signed(delta);
+    }
+
+    /// `apply` arguments that reproduce the selection, in plan order.
+    pub fn apply_args(&self) -> Vec<String> {
+        self.plan
+            .ops
+            .iter()
+            .filter(|op| self.selected.contains(&op.id))
+            .flat_map(|op| ["--op".to_string(), op.id.clone()])
+            .collect()
+    }
+
+    fn filter_label(&self) -> String {
+        format!(
+            "safety:{} fixer:{} file:{}",
+            self.safety.map(safety_label).unwrap_or("*"),
+            self.fixer.as_deref().unwrap_or("*"),
+            self.file.as_deref().unwrap_or("*")
+        )
+    }
+}
+
+/// The value after `current` in `values`, wrapping back to no filter.
+fn next_value(values: &BTreeSet<&str>, current: Option<&str>) -> Option<String> {
+    let next = match current {
+        None => values.iter().next(),
+        Some(c) => values
+            .range::<&str, _>((std::ops::Bound::Excluded(c), std::ops::Bound::Unbounded))
+            .next(),
+    };
+    next.map(|v| v.to_string())
+}
+
+fn safety_label(s: SafetyClass) -> &'static str {
+    match s {
+        SafetyClass::Safe => "safe",
+        SafetyClass::Guarded => "guarded",
+        SafetyClass::Unsafe => "unsafe",
+    }
+}
+
+fn safety_color(s: SafetyClass) -> Color {
+    match s {
+        SafetyClass::Safe => Color::Green,
+        SafetyClass::Guarded => Color::Yellow,
+        SafetyClass::Unsafe => Color::Red,
+    }
+}
+
+/// Run the browser until the user quits. Returns the `apply` arguments when
+/// the selection was exported, `None` when they quit without exporting.
+pub fn run(triage: &mut Triage) -> anyhow::Result<Option<Vec<String>>> {
+    let mut terminal = ratatui::init();
+    let result = event_loop(&mut terminal, triage);
+    ratatui::restore();
+    result
+}
+
+fn event_loop(
+    terminal: &mut DefaultTerminal,
+    triage: &mut Triage,
+) -> anyhow::Result<Option<Vec<String>>> {
+    loop {
+        terminal.draw(|frame| draw(frame, triage))?;
+        let Event::Key(key) = event::read()? else {
+            continue;
+        };
+        if key.kind != KeyEventKind::Press {
+            continue;
+        }
+        match key.code {
+            KeyCode::Char('q') | KeyCode::Esc => return Ok(None),
+            KeyCode::Char('e') => return Ok(Some(triage.apply_args())),
+            KeyCode::Up | KeyCode::Char('k') => triage.move_cursor(-1),
+            KeyCode::Down | KeyCode::Char('j') => triage.move_cursor(1),
+            KeyCode::Char(' ') => triage.toggle_current(),
+            KeyCode::Char('a') => triage.toggle_visible(),
+            KeyCode::Char('s') => triage.cycle_safety(),
+            KeyCode::Char('f') => triage.cycle_fixer(),
+            KeyCode::Char('p') => triage.cycle_file(),
+            KeyCode::PageUp => triage.scroll_diff(-10),
+            KeyCode::PageDown => triage.scroll_diff(10),
+            _ => {}
+        }
+    }
+}
+
+fn draw(frame: &mut Frame, triage: &Triage) {
+    let [main, status] =
+        Layout::vertical([Constraint::Min(1), Constraint::Length(1)]).areas(frame.area());
+    let [list_area, diff_area] =
+        Layout::horizontal([Constraint::Percentage(45), Constraint::Percentage(55)]).areas(main);
+
+    let visible = triage.visible();
+    let items: Vec<ListItem> = visible
+        .iter()
+        .map(|op| {
+            let mark = if triage.is_selected(op) {
+                "[x] "
+            } else {
+                "[ ] "
+            };
+            let mut spans = vec![
+                Span::raw(mark),
+                Span::styled(
+                    format!("{:<8}", safety_label(op.safety)),
+                    Style::new().fg(safety_color(op.safety)),
+                ),
+                Span::raw(format!("{}  {}", op.rationale.fix_key, op.target.path)),
+            ];
+            if op.blocked {
+                spans.push(" (blocked)".dim());
+            }
+            ListItem::new(Line::from(spans))
+        })
+        .collect();
+    let title = format!(
+        " ops {}/{} · {} selected · {} ",
+        visible.len(),
+        triage.plan.ops.len(),
+        triage.selected.len(),
+        triage.filter_label()
+    );
+    let list = List::new(items)
+        .block(Block::bordered().title(title))
+        .highlight_style(Style::new().reversed());
+    let mut state = ListState::default().with_selected(Some(triage.cursor));
+    frame.render_stateful_widget(list, list_area, &mut state);
+
+    let (diff_title, diff_lines) = match triage.current() {
+        Some(op) => {
+            let lines = match triage.diffs.get(&op.target.path) {
+                Some(section) => section.lines().map(diff_line).collect(),
+                None => vec![Line::from("No diff for this op.".dim())],
+            };
+            (format!(" {} ", op.target.path), lines)
+        }
+        None => (
+            " diff ".to_string(),
+            vec![Line::from("No ops match the filters.".dim())],
+        ),
+    };
+    frame.render_widget(
+        Paragraph::new(diff_lines)
+            .block(Block::bordered().title(diff_title))
+            .scroll((triage.diff_scroll, 0)),
+        diff_area,
+    );
+
+    frame.render_widget(Paragraph::new(HELP.dim()), status);
+}
+
+fn diff_line(line: &str) -> Line<'_> {
+    let style = if line.starts_with("+++") || line.starts_with("---") {
+        Style::new().bold()
+    } else if line.starts_with('+') {
+        Style::new().fg(Color::Green)
+    } else if line.starts_with('-') {
+        Style::new().fg(Color::Red)
+    } else if line.starts_with("@@") {
+        Style::new().fg(Color::Cyan)
+    } else {
+        Style::new()
+    };
+    Line::styled(line, style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buildfix_types::ops::{OpKind, OpTarget};
+    use buildfix_types::plan::{PlanPolicy, Rationale, RepoInfo};
+    use buildfix_types::receipt::ToolInfo;
+
+    fn op(id: &str, fix_key: &str, path: &str, safety: SafetyClass, blocked: bool) -> PlanOp {
+        PlanOp {
+            id: id.to_string(),
+            safety,
+            blocked,
+            blocked_reason: None,
+            blocked_reason_token: None,
+            target: OpTarget {
+                path: path.to_string(),
+                sha256: None,
+            },
+            kind: OpKind::TomlRemove {
+                toml_path: vec!["x".to_string()],
+            },
+            rationale: Rationale {
+                fix_key: fix_key.to_string(),
+                description: None,
+                findings: vec![],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: None,
+            workspace: None,
+        }
+    }
+
+    fn triage() -> Triage {
+        let mut plan = BuildfixPlan::new(
+            ToolInfo {
+                name: "buildfix".to_string(),
+                version: None,
+                repo: None,
+                commit: None,
+            },
+            RepoInfo {
+                root: ".".to_string(),
+                head_sha: None,
+                dirty: None,
+                remote_url: None,
+                ci: None,
+            },
+            PlanPolicy::default(),
+        );
+        plan.ops = vec![
+            op("a", "x/resolver", "Cargo.toml", SafetyClass::Safe, false),
+            op(
+                "b",
+                "y/msrv",
+                "crates/a/Cargo.toml",
+                SafetyClass::Guarded,
+                false,
+            ),
+            op(
+                "c",
+                "y/msrv",
+                "crates/b/Cargo.toml",
+                SafetyClass::Guarded,
+                true,
+            ),
+        ];
+        let patch = "diff --git a/Cargo.toml b/Cargo.toml\n--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1 +1,2 @@\n [workspace]\n+resolver = \"2\"\n";
+        Triage::new(plan, patch)
+    }
+
+    #[test]
+    fn starts_with_unblocked_ops_selected() {
+        let t = triage();
+        assert_eq!(t.apply_args(), vec!["--op", "a", "--op", "b"]);
+        assert!(t.diffs.contains_key("Cargo.toml"));
+    }
+
+    #[test]
+    fn filters_narrow_visible_ops() {
+        let mut t = triage();
+        t.cycle_safety();
+        assert_eq!(t.visible().len(), 1);
+        t.cycle_safety();
+        assert_eq!(t.visible().len(), 2);
+
+        t.cycle_safety();
+        t.cycle_safety();
+        t.cycle_fixer();
+        assert_eq!(t.fixer.as_deref(), Some("x/resolver"));
+        t.cycle_fixer();
+        for _ in 0..3 {
+            t.cycle_file();
+        }
+        let ids: Vec<&str> = t.visible().iter().map(|op| op.id.as_str()).collect();
+        assert_eq!(ids, vec!["c"]);
+        t.cycle_fixer();
+        t.cycle_file();
+        assert!(t.fixer.is_none() && t.file.is_none());
+        assert_eq!(t.visible().len(), 3);
+    }
+
+    #[test]
+    fn toggles_update_the_exported_selection() {
+        let mut t = triage();
+        t.toggle_current();
+        assert_eq!(t.apply_args(), vec!["--op", "b"]);
+
+        t.cycle_fixer();
+        t.cycle_fixer();
+        t.toggle_visible();
+        assert_eq!(t.apply_args(), vec!["--op", "b", "--op", "c"]);
+        t.toggle_visible();
+        assert!(t.apply_args().is_empty());
+
+        t.move_cursor(5);
+        assert_eq!(t.cursor, 1);
+        t.move_cursor(-5);
+        assert_eq!(t.cursor, 0);
+    }
+}
@@ -0,0 +1,196 @@
+//! `buildfix watch`: re-plan when receipts or manifests change.
+//!
+//! Watches the artifacts directory recursively (minus the output directory,
+//! which every plan rewrites) and the directory of each `Cargo.toml` and
+//! `buildfix.toml` found at startup. Bursts of events, such as a sensor
+//! writing several receipts, are debounced into one re-plan.
+
+use anyhow::Context;
+use camino::{Utf8Path, Utf8PathBuf};
+use notify::{EventKind, RecursiveMode, Watcher};
+use std::collections::BTreeSet;
+use std::ops::ControlFlow;
+use std::sync::mpsc;
+use std::time::Duration;
+
+/// Manifest file names that trigger a re-plan.
+const MANIFESTS: [&str; 2] = ["Cargo.toml", "buildfix.toml"];
+
+/// What to watch. All paths are canonical, so they compare equal to the
+/// paths the platform watcher reports.
+#[derive(Debug, Clone)]
+pub struct WatchSet {
+    pub artifacts: Utf8PathBuf,
+    pub out_dir: Utf8PathBuf,
+    /// Directories holding a manifest, watched non-recursively.
+    pub manifest_dirs: BTreeSet<Utf8PathBuf>,
+}
+
+impl WatchSet {
+    /// Find the manifests below `repo_root`, skipping hidden directories,
+    /// `target` and the artifacts directory. Manifests added later are not
+    /// picked up until watch restarts.
+    pub fn discover(
+        repo_root: &Utf8Path,
+        artifacts_dir: &Utf8Path,
+        out_dir: &Utf8Path,
+    ) -> anyhow::Result<Self> {
+        let repo_root = canonical(repo_root)?;
+        let artifacts = canonical(artifacts_dir)
+            .with_context(|| format!("artifacts dir {} must exist to watch it", artifacts_dir))?;
+        // The output directory may not exist before the first plan.
+        let out_dir =
+            canonical(out_dir).unwrap_or_else(|_| match out_dir.strip_prefix(artifacts_dir) {
+                Ok(rel) => artifacts.join(rel),
+                Err(_) => out_dir.to_path_buf(),
+            });
+
+        let mut manifest_dirs = BTreeSet::from([repo_root.clone()]);
+        let mut pending = vec![repo_root];
+        while let Some(dir) = pending.pop() {
+            let entries = std::fs::read_dir(&dir).with_context(|| format!("read dir {}", dir))?;
+            for entry in entries {
+                let entry = entry?;
+                let Ok(path) = Utf8PathBuf::from_path_buf(entry.path()) else {
+                    continue;
+                };
+                let name = path.file_name().unwrap_or_default();
+                if entry.file_type()?.is_dir() {
+                    if !name.starts_with('.') && name != "target" && path != artifacts {
+                        pending.push(path);
+                    }
+                } else if MANIFESTS.contains(&name) {
+                    manifest_dirs.insert(dir.clone());
+                }
+            }
+        }
+        Ok(Self {
+            artifacts,
+            out_dir,
+            manifest_dirs,
+        })
+    }
+
+    /// Whether a change to `path` should trigger a re-plan.
+    pub fn is_relevant(&self, path: &Utf8Path) -> bool {
+        if path.starts_with(&self.artifacts) {
+            return !path.starts_with(&self.out_dir);
+        }
+        path.file_name()
+            .is_some_and(|name| MANIFESTS.contains(&name))
+            && path
+                .parent()
+                .is_some_and(|dir| self.manifest_dirs.contains(dir))
+    }
+}
+
+/// Watch `set`, calling `on_change` once with no paths as soon as the
+/// watches are in place, then with the relevant paths of each batch of
+/// changes once `debounce` has passed without another one. Returns when
+/// `on_change` breaks.
+pub fn run(
+    set: &WatchSet,
+    debounce: Duration,
+    mut on_change: impl FnMut(&[Utf8PathBuf]) -> ControlFlow<()>,
+) -> anyhow::Result<()> {
+    let (tx, rx) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(tx).context("start file watcher")?;
+    watcher
+        .watch(set.artifacts.as_std_path(), RecursiveMode::Recursive)
+        .with_context(|| format!("watch {}", set.artifacts))?;
+    for dir in &set.manifest_dirs {
+        watcher
+            .watch(dir.as_std_path(), RecursiveMode::NonRecursive)
+            .with_context(|| format!("watch {}", dir))?;
+    }
+
+    if on_change(&[]).is_break() {
+        return Ok(());
+    }
+    loop {
+        let mut changed = BTreeSet::new();
+        // Block for the first relevant change, then gather until quiet.
+        while changed.is_empty() {
+            let Ok(event) = rx.recv() else {
+                return Ok(());
+            };
+            collect(set, event, &mut changed)?;
+        }
+        loop {
+            match rx.recv_timeout(debounce) {
+                Ok(event) => collect(set, event, &mut changed)?,
+                Err(mpsc::RecvTimeoutError::Timeout) => break,
+                Err(mpsc::RecvTimeoutError::Disconnected) => return Ok(()),
+            }
+        }
+        let changed: Vec<_> = changed.into_iter().collect();
+        if on_change(&changed).is_break() {
+            return Ok(());
+        }
+    }
+}
+
+fn collect(
+    set: &WatchSet,
+    event: notify::Result<notify::Event>,
+    changed: &mut BTreeSet<Utf8PathBuf>,
+) -> anyhow::Result<()> {
+    let event = event.context("file watcher")?;
+    if matches!(event.kind, EventKind::Access(_)) {
+        return Ok(());
+    }
+    changed.extend(
+        event
+            .paths
+            .into_iter()
+            .filter_map(|p| Utf8PathBuf::from_path_buf(p).ok())
+            .filter(|p| set.is_relevant(p)),
+    );
+    Ok(())
+}
+
+fn canonical(path: &Utf8Path) -> anyhow::Result<Utf8PathBuf> {
+    let path = std::fs::canonicalize(path).with_context(|| format!("resolve {}", path))?;
+    Utf8PathBuf::from_path_buf(path).map_err(|p| anyhow::anyhow!("{} is not UTF-8", p.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn watches_receipts_and_manifests_but_not_outputs() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        for dir in ["crates/a", "artifacts/builddiag", "target/debug", ".git"] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+        }
+        for file in [
+            "Cargo.toml",
+            "crates/a/Cargo.toml",
+            "target/debug/Cargo.toml",
+            ".git/Cargo.toml",
+        ] {
+            std::fs::write(root.join(file), "").unwrap();
+        }
+
+        let set = WatchSet::discover(
+            root,
+            &root.join("artifacts"),
+            &root.join("artifacts/buildfix"),
+        )
+        .unwrap();
+        let root = canonical(root).unwrap();
+        assert_eq!(
+            set.manifest_dirs,
+            BTreeSet::from([root.clone(), root.join("crates/a")])
+        );
+
+        assert!(set.is_relevant(&root.join("artifacts/builddiag/report.json")));
+        assert!(!set.is_relevant(&root.join("artifacts/buildfix/plan.json")));
+        assert!(set.is_relevant(&root.join("crates/a/Cargo.toml")));
+        assert!(set.is_relevant(&root.join("buildfix.toml")));
+        assert!(!set.is_relevant(&root.join("crates/a/lib.rs")));
+        assert!(!set.is_relevant(&root.join("target/debug/Cargo.toml")));
+    }
+}
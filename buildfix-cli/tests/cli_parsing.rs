@@ -226,6 +226,115 @@ fn test_explain_invalid_fix() {
         .stdout(predicate::str::contains("not found").or(predicate::str::contains("Unknown")));
 }
 
+#[test]
+fn test_explain_op_from_plan() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    let plan: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/plan.json")).unwrap(),
+    )
+    .unwrap();
+    let op_id = plan["ops"][0]["id"].as_str().unwrap().to_string();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["explain", &op_id[..12]])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(format!("OP: {}", op_id)))
+        .stdout(predicate::str::contains("Target:  Cargo.toml"))
+        .stdout(predicate::str::contains("+resolver = \"2\""))
+        .stdout(predicate::str::contains("FIX: Workspace Resolver V2"));
+
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["explain", &op_id, "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["op"]["id"], op_id.as_str());
+    assert!(json["preview"].as_str().unwrap().contains("resolver"));
+    assert_eq!(json["fix"]["key"], "resolver-v2");
+
+    let out = buildfix()
+        .args(["explain", "resolver-v2", "--format", "json"])
+        .output()
+        .unwrap();
+    let json: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(json["fix_id"], "cargo.workspace_resolver_v2");
+    assert_eq!(json["safety"], "safe");
+}
+
+#[test]
+fn test_list_fixes_filters_and_coverage() {
+    let out = buildfix()
+        .args([
+            "list-fixes",
+            "--check-id",
+            "workspace.resolver_v2",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    let fixes: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let keys: Vec<&str> = fixes
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|f| f["key"].as_str().unwrap())
+        .collect();
+    assert_eq!(keys, vec!["resolver-v2"]);
+
+    buildfix()
+        .args(["list-fixes", "--sensor", "no-such-sensor"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("resolver-v2").not());
+
+    let temp = create_temp_repo_with_receipt();
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["list-fixes", "--coverage", "artifacts", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["receipts"], 1);
+    let row = |key: &str| {
+        report["fixes"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|f| f["key"] == key)
+            .unwrap()
+            .clone()
+    };
+    assert_eq!(row("resolver-v2")["status"], "triggered");
+    assert_eq!(row("resolver-v2")["findings"], 1);
+    let unused = row("remove-unused-deps");
+    assert_eq!(unused["status"], "missing");
+    assert!(
+        unused["missing_sensors"]
+            .as_array()
+            .unwrap()
+            .contains(&"cargo-machete".into())
+    );
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["list-fixes", "--coverage", "artifacts"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("1 matching findings"))
+        .stdout(predicate::str::contains("missing inputs"));
+}
+
 #[test]
 fn test_explain_case_insensitive() {
     buildfix()
@@ -472,6 +581,107 @@ fn test_validate_round_trip() {
         .success();
 }
 
+#[test]
+fn test_validate_accepts_plan_report_with_ops() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .success();
+
+    let report: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/report.json")).unwrap(),
+    )
+    .unwrap();
+    let op_finding = report["findings"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .find(|f| f["code"] == "fix_available")
+        .expect("a finding for the planned op");
+    assert_eq!(op_finding["check_id"], "resolver_v2");
+    assert_eq!(
+        op_finding["data"]["fix_key"],
+        "builddiag/workspace.resolver_v2/not_v2"
+    );
+
+    // The report must satisfy the vendored sensor.report.v1 patterns.
+    buildfix()
+        .current_dir(temp.path())
+        .arg("validate")
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_apply_from_another_dir_checks_receipts_under_repo_root() {
+    let temp = create_temp_repo_with_receipt();
+    // Planned from the repo itself, so inputs are recorded relative to it.
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .success();
+
+    let plan: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/plan.json")).unwrap(),
+    )
+    .unwrap();
+    let input = &plan["inputs"][0];
+    assert_eq!(input["path"], "artifacts/builddiag/report.json");
+    assert!(input["sha256"].is_string(), "receipt hashed at plan time");
+
+    fs::write(
+        temp.path().join("artifacts/builddiag/report.json"),
+        r#"{"schema": "builddiag.report.v1", "tool": {"name": "builddiag"}, "findings": []}"#,
+    )
+    .unwrap();
+
+    // Applied from elsewhere: the changed receipt must still be found.
+    let elsewhere = TempDir::new().unwrap();
+    buildfix()
+        .current_dir(elsewhere.path())
+        .arg("apply")
+        .arg("--repo-root")
+        .arg(temp.path())
+        .assert()
+        .success();
+
+    let apply: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/apply.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(apply["results"][0]["blocked_reason_token"], "stale_plan");
+}
+
+#[test]
+fn test_validate_and_apply_accept_v2_plan() {
+    let temp = create_temp_repo();
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .success();
+
+    let plan_path = temp.path().join("artifacts/buildfix/plan.json");
+    let v1: buildfix_types::wire::PlanV1 =
+        serde_json::from_str(&fs::read_to_string(&plan_path).unwrap()).unwrap();
+    let v2 = buildfix_types::wire::PlanV2::from(v1);
+    fs::write(&plan_path, serde_json::to_string_pretty(&v2).unwrap()).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .arg("validate")
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .arg("apply")
+        .assert()
+        .success();
+}
+
 #[test]
 fn test_plan_mode_standalone_is_default() {
     let temp = create_temp_repo();
@@ -581,50 +791,1632 @@ fn create_temp_repo_with_receipt() -> TempDir {
     td
 }
 
+#[cfg(feature = "object-store")]
 #[test]
-fn exit_code_1_apply_missing_plan() {
-    let temp = create_temp_repo();
+fn test_plan_reads_receipts_from_store() {
+    let temp = create_temp_repo_with_receipt();
+    let store = tempfile::tempdir().expect("tempdir");
+    fs::rename(
+        temp.path().join("artifacts").join("builddiag"),
+        store.path().join("builddiag"),
+    )
+    .unwrap();
+    let url = url::Url::from_directory_path(store.path()).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--receipt-store", url.as_str()])
+        .assert()
+        .success();
+
+    let plan = fs::read_to_string(temp.path().join("artifacts/buildfix/plan.json")).expect("plan");
+    assert!(plan.contains("workspace.resolver_v2"));
+}
+
+#[test]
+fn test_plan_reads_receipts_from_zip_bundle() {
+    use std::io::Write;
+
+    let temp = create_temp_repo_with_receipt();
+    let receipt =
+        fs::read(temp.path().join("artifacts/builddiag/report.json")).expect("read receipt");
+    fs::remove_dir_all(temp.path().join("artifacts")).unwrap();
+
+    let bundle = temp.path().join("receipts.zip");
+    let mut zip = zip::ZipWriter::new(fs::File::create(&bundle).unwrap());
+    zip.start_file(
+        "artifacts/builddiag/report.json",
+        zip::write::SimpleFileOptions::default(),
+    )
+    .unwrap();
+    zip.write_all(&receipt).unwrap();
+    zip.finish().unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--artifacts-dir", "receipts.zip"])
+        .assert()
+        .success();
+
+    let plan = fs::read_to_string(temp.path().join("artifacts/buildfix/plan.json")).expect("plan");
+    assert!(plan.contains("workspace.resolver_v2"));
+}
+
+#[test]
+fn test_plan_and_apply_pretty_format() {
+    let temp = create_temp_repo_with_receipt();
+
+    buildfix()
+        .current_dir(temp.path())
+        .env_remove("CLICOLOR_FORCE")
+        .args(["plan", "--format", "pretty"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "buildfix plan: 1 ops (0 blocked), 1 files",
+        ))
+        .stdout(predicate::str::contains(
+            "builddiag/workspace.resolver_v2/not_v2",
+        ))
+        .stdout(predicate::str::contains("+resolver = \"2\""))
+        .stdout(predicate::str::contains("\x1b[").not());
+
+    buildfix()
+        .current_dir(temp.path())
+        .env("CLICOLOR_FORCE", "1")
+        .args(["apply", "--format", "pretty"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("buildfix apply:"))
+        .stdout(predicate::str::contains("\x1b[32m"));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success()
+        .stdout(predicate::str::is_empty());
+}
+
+#[test]
+fn test_plan_report_formats() {
+    let temp = create_temp_repo_with_receipt();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "plan",
+            "--report-format",
+            "checkstyle",
+            "--report-format",
+            "teamcity",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "##teamcity[inspection typeId='builddiag/workspace.resolver_v2/not_v2'",
+        ))
+        .stdout(predicate::str::contains(
+            "##teamcity[buildStatisticValue key='buildfix.ops_total' value='1']",
+        ));
+
+    let xml = fs::read_to_string(temp.path().join("artifacts/buildfix/checkstyle.xml")).unwrap();
+    assert!(xml.contains("<file name=\"Cargo.toml\">"));
+    assert!(xml.contains("source=\"buildfix.builddiag/workspace.resolver_v2/not_v2\""));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--report-format", "csv", "--report-format", "tsv"])
+        .assert()
+        .success();
+    let csv = fs::read_to_string(temp.path().join("artifacts/buildfix/plan.csv")).unwrap();
+    assert!(csv.starts_with("op_id,fix_key,file,safety,blocked_reason,finding_fingerprint\n"));
+    assert!(csv.contains(",builddiag/workspace.resolver_v2/not_v2,Cargo.toml,safe,,"));
+    assert!(temp.path().join("artifacts/buildfix/plan.tsv").exists());
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--report-format", "sarif"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_fix_plans_and_applies_in_one_step() {
+    let temp = create_temp_repo_with_receipt();
+    let manifest = temp.path().join("Cargo.toml");
+    let original = fs::read_to_string(&manifest).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["fix"])
+        .assert()
+        .success();
+    let out = temp.path().join("artifacts/buildfix");
+    assert!(out.join("plan.json").exists());
+    let apply: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(out.join("apply.json")).unwrap()).unwrap();
+    assert_eq!(apply["results"][0]["status"], "skipped");
+    assert_eq!(fs::read_to_string(&manifest).unwrap(), original);
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["fix", "--apply"])
+        .assert()
+        .success();
+    assert!(
+        fs::read_to_string(&manifest)
+            .unwrap()
+            .contains("resolver = \"2\"")
+    );
+}
+
+#[test]
+fn test_apply_skip_filters_ops_by_fix_key() {
+    let temp = create_temp_repo_with_receipt();
+    let manifest = temp.path().join("Cargo.toml");
+    let original = fs::read_to_string(&manifest).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply", "--skip", "builddiag/*"])
+        .assert()
+        .success();
+
+    let apply: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/apply.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(apply["results"][0]["status"], "skipped");
+    assert_eq!(apply["results"][0]["blocked_reason_token"], "user_filtered");
+    assert_eq!(fs::read_to_string(&manifest).unwrap(), original);
+}
+
+#[test]
+fn test_status_tracks_plan_apply_and_drift() {
+    let temp = create_temp_repo_with_receipt();
+    let status = |format: &str| {
+        let out = buildfix()
+            .current_dir(temp.path())
+            .args(["status", "--format", format])
+            .output()
+            .unwrap();
+        assert!(out.status.success());
+        String::from_utf8(out.stdout).unwrap()
+    };
+
+    assert!(status("text").contains("next: run `buildfix plan`"));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    let json: serde_json::Value = serde_json::from_str(&status("json")).unwrap();
+    assert_eq!(json["plan"]["ops_total"], 1);
+    assert!(json["apply"].is_null());
+    assert_eq!(json["backups"]["runs"], 0);
 
-    // No plan.json exists, so apply --apply should fail with exit 1 (tool error).
     buildfix()
         .current_dir(temp.path())
         .args(["apply", "--apply"])
         .assert()
-        .code(1);
+        .success();
+    let json: serde_json::Value = serde_json::from_str(&status("json")).unwrap();
+    assert_eq!(json["apply"]["matches_plan"], true);
+    assert_eq!(json["apply"]["wrote_changes"], true);
+    assert_eq!(json["backups"]["runs"], 1);
+    assert_eq!(json["plan"]["drifted_files"], serde_json::json!([]));
+    assert!(status("text").contains("next: nothing to do"));
+
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[workspace]\nmembers = []\n",
+    )
+    .unwrap();
+    let json: serde_json::Value = serde_json::from_str(&status("json")).unwrap();
+    assert_eq!(
+        json["plan"]["drifted_files"],
+        serde_json::json!(["Cargo.toml"])
+    );
+    assert!(status("text").contains("next: re-run `buildfix plan`"));
 }
 
 #[test]
-fn exit_code_2_plan_with_deny_policy() {
+fn test_gc_prunes_old_backups_and_stale_cache() {
     let temp = create_temp_repo_with_receipt();
+    let out_dir = temp.path().join("artifacts").join("buildfix");
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply"])
+        .assert()
+        .success();
+
+    let old_run = out_dir.join("backups").join("20200101T000000Z-old");
+    fs::create_dir_all(&old_run).unwrap();
+    fs::write(
+        old_run.join("manifest.json"),
+        r#"{"run_id":"20200101T000000Z-old","created_at":"2020-01-01T00:00:00Z","files":[]}"#,
+    )
+    .unwrap();
+    let cache_dir = out_dir.join("cache").join("receipts");
+    let month_ago = std::time::SystemTime::now() - std::time::Duration::from_secs(30 * 86400);
+    let cached: Vec<_> = fs::read_dir(&cache_dir)
+        .unwrap()
+        .map(|e| e.unwrap().path())
+        .collect();
+    assert!(!cached.is_empty());
+    for path in &cached {
+        fs::File::options()
+            .write(true)
+            .open(path)
+            .unwrap()
+            .set_modified(month_ago)
+            .unwrap();
+    }
+
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["gc", "--dry-run", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["dry_run"], true);
+    let removed = report["removed"].as_array().unwrap();
+    assert_eq!(removed.len(), cached.len());
+    assert!(removed.iter().all(|e| e["kind"] == "cache"));
+    assert!(report["reclaimed_bytes"].as_u64().unwrap() > 0);
+    assert!(cached.iter().all(|p| p.exists()));
 
-    // The receipt triggers an op, but --deny "*" blocks it -> exit 2.
     buildfix()
         .current_dir(temp.path())
-        .args(["plan", "--deny", "*"])
+        .args(["gc", "--backup-max-age-days", "30"])
         .assert()
-        .code(2);
+        .success()
+        .stdout(predicate::str::contains("removed backup run"))
+        .stdout(predicate::str::contains(format!(
+            "({} backup runs, {} cache files)",
+            1,
+            cached.len()
+        )));
+    assert!(!old_run.exists());
+    assert!(cached.iter().all(|p| !p.exists()));
+    assert_eq!(fs::read_dir(out_dir.join("backups")).unwrap().count(), 1);
+    assert!(out_dir.join("plan.json").exists());
 }
 
 #[test]
-fn exit_code_2_plan_with_max_ops_zero() {
+fn test_verify_detects_changes_after_apply() {
     let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply"])
+        .assert()
+        .success();
 
-    // The receipt triggers an op, but --max-ops 0 blocks it -> exit 2.
     buildfix()
         .current_dir(temp.path())
-        .args(["plan", "--max-ops", "0"])
+        .args(["verify"])
         .assert()
-        .code(2);
+        .success()
+        .stdout(predicate::str::contains("verify: all 2 checks passed"));
+
+    fs::write(temp.path().join("Cargo.toml"), "[workspace\n").unwrap();
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["verify", "--format", "json"])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let report: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(report["ok"], false);
+    let kinds: Vec<&str> = report["checks"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|c| c["ok"] == false)
+        .map(|c| c["kind"].as_str().unwrap())
+        .collect();
+    assert_eq!(kinds, vec!["file_hash", "manifest_parse"]);
 }
 
 #[test]
-fn exit_code_0_plan_with_deny_policy_cockpit_mode() {
+fn test_doctor_reports_config_errors_with_line_numbers() {
     let temp = create_temp_repo_with_receipt();
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["doctor", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let checks: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    let status = |name: &str| {
+        checks
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|c| c["name"] == name)
+            .map(|c| c["status"].as_str().unwrap().to_string())
+            .unwrap()
+    };
+    assert_eq!(status("receipts"), "ok");
+    assert_eq!(status("buildfix.toml"), "ok");
+    assert_eq!(status("schemas"), "ok");
 
-    // In cockpit mode, policy blocks (exit 2) are remapped to exit 0.
+    fs::write(
+        temp.path().join("buildfix.toml"),
+        "[policy]\nallow = [\"a\"\n",
+    )
+    .unwrap();
     buildfix()
         .current_dir(temp.path())
-        .args(["plan", "--deny", "*", "--mode", "cockpit"])
+        .args(["doctor"])
         .assert()
-        .code(0);
+        .code(1)
+        .stdout(predicate::str::contains("FAIL  buildfix.toml"))
+        .stdout(predicate::str::contains("line 2"))
+        .stdout(predicate::str::contains("fix: "));
+}
+
+#[test]
+fn test_init_writes_config_and_refuses_to_overwrite() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["init"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("buildfix.toml"));
+
+    let written = fs::read_to_string(temp.path().join("buildfix.toml")).unwrap();
+    assert!(written.contains("#   workspace members: 1"));
+    assert!(written.contains("#   resolver:          unset"));
+    assert!(written.contains("\"builddiag/workspace.resolver_v2/*\""));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["init"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("--force"));
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+}
+
+#[test]
+fn test_completions_and_man_are_deterministic() {
+    let completions = buildfix().args(["completions", "bash"]).output().unwrap();
+    assert!(completions.status.success());
+    let script = String::from_utf8(completions.stdout).unwrap();
+    assert!(script.contains("_buildfix()"));
+    assert!(script.contains("list-fixes"));
+
+    let man = || buildfix().arg("man").output().unwrap().stdout;
+    let page = man();
+    assert_eq!(page, man());
+    assert!(String::from_utf8(page).unwrap().starts_with(".ie "));
+
+    let temp = tempfile::tempdir().unwrap();
+    buildfix()
+        .args(["man", "--out-dir"])
+        .arg(temp.path())
+        .assert()
+        .success();
+    assert!(temp.path().join("buildfix.1").is_file());
+    assert!(temp.path().join("buildfix-plan.1").is_file());
+}
+
+#[test]
+fn test_plan_and_apply_format_json_print_wire_documents() {
+    let temp = create_temp_repo_with_receipt();
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(doc["command"], "plan");
+    assert_eq!(doc["exit_code"], 0);
+    assert_eq!(doc["policy_block"], false);
+    let written: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/plan.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(doc["plan"], written);
+
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--format", "json"])
+        .output()
+        .unwrap();
+    assert!(out.status.success());
+    let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(doc["command"], "apply");
+    assert_eq!(doc["apply"]["schema"], "buildfix.apply.v1");
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--format", "json", "--report-format", "teamcity"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_out_overrides_relocate_artifacts_and_apply_reads_plan() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "plan",
+            "--out",
+            "plan.json=ci/buildfix/plan.json",
+            "--out",
+            "patch.diff=ci/fix.patch",
+        ])
+        .assert()
+        .success();
+    let out_dir = temp.path().join("artifacts/buildfix");
+    assert!(temp.path().join("ci/buildfix/plan.json").is_file());
+    assert!(temp.path().join("ci/fix.patch").is_file());
+    assert!(!out_dir.join("plan.json").exists());
+    assert!(!out_dir.join("patch.diff").exists());
+    assert!(out_dir.join("report.json").is_file());
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply"])
+        .assert()
+        .code(1);
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "apply",
+            "--apply",
+            "--plan",
+            "ci/buildfix/plan.json",
+            "--out",
+            "apply.json=ci/apply.json",
+        ])
+        .assert()
+        .success();
+    assert!(temp.path().join("ci/apply.json").is_file());
+    assert!(
+        fs::read_to_string(temp.path().join("Cargo.toml"))
+            .unwrap()
+            .contains("resolver = \"2\"")
+    );
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--out", "plan.yaml=x"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("unknown artifact 'plan.yaml'"));
+}
+
+#[test]
+fn test_cargo_subcommand_plans_from_workspace_root() {
+    let temp = create_temp_repo_with_receipt();
+    // `cargo locate-project` loads the workspace, which needs a target.
+    fs::create_dir_all(temp.path().join("crates/a/src")).unwrap();
+    fs::write(temp.path().join("crates/a/src/lib.rs"), "").unwrap();
+
+    Command::cargo_bin("cargo-buildfix")
+        .expect("cargo-buildfix binary")
+        .current_dir(temp.path().join("crates/a"))
+        .args(["buildfix", "plan"])
+        .assert()
+        .success();
+    assert!(temp.path().join("artifacts/buildfix/plan.json").is_file());
+    assert!(!temp.path().join("crates/a/artifacts").exists());
+
+    Command::cargo_bin("cargo-buildfix")
+        .expect("cargo-buildfix binary")
+        .args(["buildfix", "list-fixes"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("resolver-v2"));
+}
+
+#[test]
+fn test_plan_changed_only_restricts_to_changed_files() {
+    let temp = create_temp_repo_with_receipt();
+    let git = |args: &[&str]| {
+        let status = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(temp.path())
+            .status()
+            .unwrap();
+        assert!(status.success(), "git {:?}", args);
+    };
+    fs::write(temp.path().join(".gitignore"), "artifacts/\n").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "-A"]);
+    git(&["commit", "-qm", "init"]);
+
+    let ops = || {
+        buildfix()
+            .current_dir(temp.path())
+            .args(["plan", "--changed-only", "HEAD", "--format", "json"])
+            .output()
+            .map(|out| {
+                let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+                doc["plan"]["ops"].as_array().unwrap().len()
+            })
+            .unwrap()
+    };
+    assert_eq!(ops(), 0);
+
+    fs::write(temp.path().join("crates/a/lib.rs"), "").unwrap();
+    assert_eq!(ops(), 0);
+
+    fs::write(temp.path().join("README.md"), "hi").unwrap();
+    assert_eq!(ops(), 1);
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--changed-only", "no-such-ref"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_fix_branch_and_push() {
+    let temp = create_temp_repo_with_receipt();
+    let remote = tempfile::tempdir().unwrap();
+    let git = |dir: &std::path::Path, args: &[&str]| {
+        let out = std::process::Command::new("git")
+            .args(args)
+            .current_dir(dir)
+            .output()
+            .unwrap();
+        assert!(out.status.success(), "git {:?}", args);
+        String::from_utf8(out.stdout).unwrap().trim().to_string()
+    };
+    git(remote.path(), &["init", "-q", "--bare"]);
+    fs::write(temp.path().join(".gitignore"), "artifacts/\n").unwrap();
+    git(temp.path(), &["init", "-q"]);
+    git(temp.path(), &["config", "user.name", "t"]);
+    git(temp.path(), &["config", "user.email", "t@example.com"]);
+    git(temp.path(), &["add", "-A"]);
+    git(temp.path(), &["commit", "-qm", "init"]);
+    git(
+        temp.path(),
+        &["remote", "add", "origin", remote.path().to_str().unwrap()],
+    );
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--push", "origin"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("--push requires --branch"));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "fix",
+            "--apply",
+            "--auto-commit",
+            "--branch",
+            "--push",
+            "origin",
+        ])
+        .assert()
+        .success();
+
+    let apply: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/apply.json")).unwrap(),
+    )
+    .unwrap();
+    let name = apply["branch"]["name"].as_str().unwrap();
+    assert!(name.starts_with("buildfix/resolver-v2-"), "{}", name);
+    assert_eq!(apply["branch"]["created"], true);
+    assert_eq!(apply["branch"]["pushed"], true);
+    assert_eq!(git(temp.path(), &["branch", "--show-current"]), name);
+    assert_eq!(
+        git(
+            remote.path(),
+            &["rev-parse", &format!("refs/heads/{}", name)]
+        ),
+        apply["auto_commit"]["commit_sha"].as_str().unwrap()
+    );
+}
+
+/// Answer each connection with the next canned body (HTTP 200) and hand
+/// back the request lines with their bodies.
+fn serve_json(bodies: Vec<&'static str>) -> (String, std::thread::JoinHandle<Vec<String>>) {
+    use std::io::{BufRead, BufReader, Read, Write};
+
+    let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+    let base = format!("http://{}", listener.local_addr().unwrap());
+    let handle = std::thread::spawn(move || {
+        let mut requests = Vec::new();
+        for body in bodies {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+            let mut request = String::new();
+            reader.read_line(&mut request).unwrap();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                if line == "\r\n" || line.is_empty() {
+                    break;
+                }
+                if let Some(value) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = value.trim().parse().unwrap();
+                }
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+            request.push_str(&String::from_utf8(request_body).unwrap());
+            requests.push(request);
+            write!(
+                stream,
+                "HTTP/1.1 200 OK\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                body.len(),
+                body
+            )
+            .unwrap();
+        }
+        requests
+    });
+    (base, handle)
+}
+
+/// `git` in `dir` with a throwaway identity, asserting success.
+fn git_in(dir: &std::path::Path, args: &[&str]) {
+    let status = std::process::Command::new("git")
+        .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+        .args(args)
+        .current_dir(dir)
+        .status()
+        .unwrap();
+    assert!(status.success(), "git {:?}", args);
+}
+
+#[test]
+fn test_apply_sandbox_leaves_checkout_untouched() {
+    let temp = create_temp_repo_with_receipt();
+    let git = |args: &[&str]| git_in(temp.path(), args);
+    fs::write(temp.path().join(".gitignore"), "artifacts/\n").unwrap();
+    fs::create_dir_all(temp.path().join("crates/a/src")).unwrap();
+    fs::write(temp.path().join("crates/a/src/lib.rs"), "").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "-A"]);
+    git(&["commit", "-qm", "init"]);
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "apply",
+            "--sandbox",
+            "--sandbox-exec",
+            "grep -q 'resolver = \"2\"' Cargo.toml",
+        ])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("ok    command"))
+        .stdout(predicate::str::contains("verify: all 4 checks passed"));
+
+    assert_eq!(
+        fs::read_to_string(temp.path().join("Cargo.toml")).unwrap(),
+        manifest
+    );
+    let apply: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/apply.json")).unwrap(),
+    )
+    .unwrap();
+    assert_eq!(apply["summary"]["applied"], 1);
+
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args([
+            "apply",
+            "--sandbox",
+            "--sandbox-exec",
+            "false",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert_eq!(out.status.code(), Some(2));
+    let run: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(run["sandbox"]["verify"]["ok"], false);
+    assert!(run["sandbox"]["worktree"].is_null());
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--sandbox", "--auto-commit"])
+        .assert()
+        .failure();
+}
+
+#[test]
+fn test_pr_github_opens_pull_request_for_applied_branch() {
+    let temp = create_temp_repo_with_receipt();
+    let git = |args: &[&str]| git_in(temp.path(), args);
+    fs::write(temp.path().join(".gitignore"), "artifacts/\n").unwrap();
+    fs::write(
+        temp.path().join("buildfix.toml"),
+        "[pr]\nlabels = [\"buildfix\"]\n",
+    )
+    .unwrap();
+    git(&["init", "-q"]);
+    git(&["config", "user.name", "t"]);
+    git(&["config", "user.email", "t@example.com"]);
+    git(&["add", "-A"]);
+    git(&["commit", "-qm", "init"]);
+
+    // A dry run leaves nothing to propose.
+    buildfix()
+        .current_dir(temp.path())
+        .arg("fix")
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["pr", "--github", "--repo", "o/r"])
+        .env("GITHUB_TOKEN", "t")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("records no applied ops"));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "fix",
+            "--apply",
+            "--auto-commit",
+            "--branch",
+            "fix/{fix_keys}",
+        ])
+        .assert()
+        .success();
+
+    let (api, handle) = serve_json(vec![
+        r#"{"default_branch":"main"}"#,
+        "[]",
+        r#"{"number":5,"html_url":"https://github.com/o/r/pull/5"}"#,
+        "[]",
+        r#"{"html_url":"https://github.com/o/r/runs/1"}"#,
+    ]);
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["pr", "--github", "--repo", "o/r", "--api-url", &api])
+        .args(["--label", "deps", "--format", "json"])
+        .env("GITHUB_TOKEN", "t")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+    let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(doc["head"], "fix/resolver-v2");
+    assert_eq!(doc["base"], "main");
+    assert_eq!(doc["pull_request"]["number"], 5);
+    assert_eq!(doc["pull_request"]["created"], true);
+    assert_eq!(doc["labels"], serde_json::json!(["buildfix", "deps"]));
+    assert_eq!(doc["check_run_url"], "https://github.com/o/r/runs/1");
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /repos/o/r HTTP"));
+    assert!(requests[2].starts_with("POST /repos/o/r/pulls HTTP"));
+    assert!(requests[2].contains("\"head\":\"fix/resolver-v2\""));
+    assert!(requests[3].starts_with("POST /repos/o/r/issues/5/labels HTTP"));
+    assert!(requests[4].starts_with("POST /repos/o/r/check-runs HTTP"));
+    assert!(requests[4].contains("\"conclusion\":\"success\""));
+}
+
+#[test]
+fn test_comment_keeps_one_sticky_comment() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .success();
+
+    let (api, handle) = serve_json(vec![
+        r#"[{"id":1,"body":"lgtm"}]"#,
+        r#"{"id":8}"#,
+        r#"[{"id":1,"body":"lgtm"},{"id":8,"body":"<!-- buildfix:comment -->\nold","author":{"id":42}}]"#,
+        r#"{"id":42}"#,
+        r#"{"id":8}"#,
+    ]);
+    let comment = |expected: &str| {
+        buildfix()
+            .current_dir(temp.path())
+            .args(["comment", "--gitlab", "--repo", "g/p", "--api-url", &api])
+            .env("GITLAB_TOKEN", "t")
+            .env("CI_MERGE_REQUEST_IID", "3")
+            .assert()
+            .success()
+            .stdout(predicate::str::contains(expected));
+    };
+    comment("Posted buildfix comment on g/p!3");
+    comment("Updated buildfix comment on g/p!3");
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("GET /projects/g%2Fp/merge_requests/3/notes?"));
+    assert!(requests[1].starts_with("POST /projects/g%2Fp/merge_requests/3/notes HTTP"));
+    assert!(requests[1].contains("<!-- buildfix:comment -->\\n"));
+    assert!(requests[3].starts_with("GET /user HTTP"));
+    assert!(requests[4].starts_with("PUT /projects/g%2Fp/merge_requests/3/notes/8 HTTP"));
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["comment", "--github", "--repo", "o/r"])
+        .env("GITHUB_TOKEN", "t")
+        .env_remove("GITHUB_EVENT_PATH")
+        .env_remove("GITHUB_REF")
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("pass --number"));
+}
+
+#[test]
+fn test_check_publishes_report_verdict() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .success();
+
+    let (api, handle) = serve_json(vec![
+        r#"{"html_url":"https://github.com/o/r/runs/2"}"#,
+        "{}",
+    ]);
+    let check = |extra: &[&str]| {
+        let out = buildfix()
+            .current_dir(temp.path())
+            .args(["check", "--github", "--repo", "o/r", "--sha", "abc"])
+            .args(["--api-url", &api, "--format", "json"])
+            .args(extra)
+            .env("GITHUB_TOKEN", "t")
+            .env_remove("GITHUB_RUN_ID")
+            .output()
+            .unwrap();
+        assert!(
+            out.status.success(),
+            "{}",
+            String::from_utf8_lossy(&out.stdout)
+        );
+        serde_json::from_slice::<serde_json::Value>(&out.stdout).unwrap()
+    };
+    let doc = check(&[]);
+    assert_eq!(doc["status"], "warn");
+    assert_eq!(doc["conclusion"], "neutral");
+    assert_eq!(doc["published"], "check_run");
+    assert_eq!(doc["check_run_url"], "https://github.com/o/r/runs/2");
+    let doc = check(&["--commit-status"]);
+    assert_eq!(doc["published"], "commit_status");
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with("POST /repos/o/r/check-runs HTTP"));
+    assert!(requests[0].contains("\"conclusion\":\"neutral\""));
+    assert!(requests[0].contains("# buildfix plan"));
+    assert!(requests[1].starts_with("POST /repos/o/r/statuses/abc HTTP"));
+    assert!(requests[1].contains("\"state\":\"success\""));
+}
+
+#[test]
+fn test_pr_gitlab_from_forge_config_opens_merge_request() {
+    let temp = create_temp_repo_with_receipt();
+    fs::write(temp.path().join(".gitignore"), "artifacts/\n").unwrap();
+    fs::write(
+        temp.path().join("buildfix.toml"),
+        "[pr]\nbase = \"main\"\nlabels = [\"buildfix\"]\n\n[forge]\nprovider = \"gitlab\"\ntoken_env = \"BOT_TOKEN\"\n",
+    )
+    .unwrap();
+    git_in(temp.path(), &["init", "-q"]);
+    git_in(temp.path(), &["config", "user.name", "t"]);
+    git_in(temp.path(), &["config", "user.email", "t@example.com"]);
+    git_in(
+        temp.path(),
+        &["remote", "add", "origin", "git@gitlab.com:g/sub/p.git"],
+    );
+    git_in(temp.path(), &["add", "-A"]);
+    git_in(temp.path(), &["commit", "-qm", "init"]);
+    buildfix()
+        .current_dir(temp.path())
+        .args(["fix", "--apply", "--auto-commit", "--branch", "fix/x"])
+        .assert()
+        .success();
+
+    let (api, handle) = serve_json(vec![
+        "[]",
+        r#"{"iid":2,"web_url":"https://gitlab.com/g/sub/p/-/merge_requests/2"}"#,
+        r#"{"iid":2}"#,
+    ]);
+    let out = buildfix()
+        .current_dir(temp.path())
+        .args(["pr", "--api-url", &api, "--format", "json"])
+        .env("BOT_TOKEN", "t")
+        .env_remove("CI_PROJECT_PATH")
+        .output()
+        .unwrap();
+    assert!(
+        out.status.success(),
+        "{}",
+        String::from_utf8_lossy(&out.stdout)
+    );
+    let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+    assert_eq!(doc["provider"], "gitlab");
+    assert_eq!(doc["repo"], "g/sub/p");
+    assert_eq!(doc["pull_request"]["number"], 2);
+    assert_eq!(doc["labels"], serde_json::json!(["buildfix"]));
+    assert_eq!(doc["check_run_url"], serde_json::Value::Null);
+
+    let requests = handle.join().unwrap();
+    assert!(requests[0].starts_with(
+        "GET /projects/g%2Fsub%2Fp/merge_requests?state=opened&source_branch=fix%2Fx&target_branch=main HTTP"
+    ));
+    assert!(requests[1].starts_with("POST /projects/g%2Fsub%2Fp/merge_requests HTTP"));
+    assert!(requests[2].contains("\"add_labels\":\"buildfix\""));
+}
+
+#[test]
+fn test_package_filters_scope_plan_and_apply() {
+    let temp = create_temp_repo_with_receipt();
+    let ops = |args: &[&str]| {
+        let out = buildfix()
+            .current_dir(temp.path())
+            .args(["plan", "--format", "json"])
+            .args(args)
+            .output()
+            .unwrap();
+        let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        doc["plan"]["ops"].as_array().unwrap().len()
+    };
+    // The only op edits the root manifest, which no member owns.
+    assert_eq!(ops(&["--package", "a"]), 0);
+    assert_eq!(ops(&["--exclude-package", "a"]), 1);
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply", "--package", "a"])
+        .assert()
+        .success();
+    let apply = fs::read_to_string(temp.path().join("artifacts/buildfix/apply.json")).unwrap();
+    assert!(apply.contains("user_filtered"));
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    assert!(!manifest.contains("resolver"));
+}
+
+#[test]
+fn test_fixers_config_disables_fixer_and_rejects_unforced_downgrade() {
+    let temp = create_temp_repo_with_receipt();
+    let ops = || {
+        let out = buildfix()
+            .current_dir(temp.path())
+            .args(["plan", "--format", "json"])
+            .output()
+            .unwrap();
+        let doc: serde_json::Value = serde_json::from_slice(&out.stdout).unwrap();
+        doc["plan"]["ops"].as_array().unwrap().len()
+    };
+    assert_eq!(ops(), 1);
+
+    fs::write(
+        temp.path().join("buildfix.toml"),
+        "[fixers.resolver-v2]\nenabled = false\n",
+    )
+    .unwrap();
+    assert_eq!(ops(), 0);
+
+    // msrv is guarded; declaring it safe needs `force = true`.
+    fs::write(
+        temp.path().join("buildfix.toml"),
+        "[fixers.msrv]\nsafety = \"safe\"\n",
+    )
+    .unwrap();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("set force = true"));
+}
+
+#[test]
+fn test_plan_uses_render_templates() {
+    let temp = create_temp_repo_with_receipt();
+    fs::create_dir(temp.path().join("templates")).unwrap();
+    fs::write(
+        temp.path().join("templates/comment.md"),
+        "Fixes: {% for op in plan.ops %}{{ op.rationale.fix_key }}{% endfor %}\n",
+    )
+    .unwrap();
+    fs::write(
+        temp.path().join("buildfix.toml"),
+        "[render]\ntemplates_dir = \"templates\"\n",
+    )
+    .unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+
+    let out = temp.path().join("artifacts/buildfix");
+    assert_eq!(
+        fs::read_to_string(out.join("comment.md")).unwrap(),
+        "Fixes: builddiag/workspace.resolver_v2/not_v2\n"
+    );
+    assert!(
+        fs::read_to_string(out.join("plan.md"))
+            .unwrap()
+            .starts_with("# buildfix plan")
+    );
+
+    fs::write(temp.path().join("templates/plan.md"), "{{ plan.nope }}").unwrap();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("plan.md"));
+}
+
+#[test]
+fn test_compare_plans() {
+    let temp = create_temp_repo_with_receipt();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    let out = temp.path().join("artifacts/buildfix");
+    fs::copy(out.join("plan.json"), temp.path().join("old.json")).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["compare", "old.json", "artifacts/buildfix"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("- Unchanged: 1"))
+        .stdout(predicate::str::contains("No differences."));
+
+    // Drop the only op from the old plan: the new plan then adds it.
+    let mut old: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(temp.path().join("old.json")).unwrap()).unwrap();
+    old["ops"] = serde_json::json!([]);
+    fs::write(temp.path().join("old.json"), old.to_string()).unwrap();
+
+    let output = buildfix()
+        .current_dir(temp.path())
+        .args([
+            "compare",
+            "old.json",
+            "artifacts/buildfix",
+            "--format",
+            "json",
+        ])
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let diff: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(diff["added"].as_array().unwrap().len(), 1);
+    assert_eq!(
+        diff["added"][0]["fix_key"],
+        "builddiag/workspace.resolver_v2/not_v2"
+    );
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["compare", "missing.json", "old.json"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_merge_plans() {
+    let temp = create_temp_repo_with_receipt();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan"])
+        .assert()
+        .success();
+    let plan: serde_json::Value = serde_json::from_str(
+        &fs::read_to_string(temp.path().join("artifacts/buildfix/plan.json")).unwrap(),
+    )
+    .unwrap();
+    let mut empty = plan.clone();
+    empty["ops"] = serde_json::json!([]);
+    fs::write(temp.path().join("empty.json"), empty.to_string()).unwrap();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "merge-plans",
+            "empty.json",
+            "artifacts/buildfix",
+            "--out",
+            "merged/plan.json",
+        ])
+        .assert()
+        .success();
+    let merged: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(temp.path().join("merged/plan.json")).unwrap())
+            .unwrap();
+    assert_eq!(merged["ops"], plan["ops"]);
+    assert_eq!(merged["summary"]["ops_total"], 1);
+
+    for (name, head) in [("a.json", "deadbeef"), ("b.json", "cafebabe")] {
+        let mut other = plan.clone();
+        other["repo"]["head_sha"] = serde_json::json!(head);
+        fs::write(temp.path().join(name), other.to_string()).unwrap();
+    }
+    buildfix()
+        .current_dir(temp.path())
+        .args(["merge-plans", "a.json", "b.json"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("different commits"));
+}
+
+#[test]
+fn test_plan_and_apply_emit_ndjson_events() {
+    let temp = create_temp_repo_with_receipt();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--events", "events.ndjson"])
+        .assert()
+        .success();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply", "--events", "events.ndjson"])
+        .assert()
+        .success();
+
+    let all: Vec<serde_json::Value> = fs::read_to_string(temp.path().join("events.ndjson"))
+        .unwrap()
+        .lines()
+        .map(|line| serde_json::from_str(line).expect("one JSON object per line"))
+        .collect();
+    let (progress, events): (Vec<_>, Vec<_>) = all.iter().partition(|e| {
+        ["phase_started", "fixer_finished", "op_progress"].contains(&e["event"].as_str().unwrap())
+    });
+    let phases: Vec<&str> = progress
+        .iter()
+        .filter_map(|e| e["phase"].as_str())
+        .collect();
+    assert_eq!(
+        phases,
+        vec![
+            "load_receipts",
+            "plan",
+            "preconditions",
+            "preview",
+            "report",
+            "apply",
+            "write",
+            "report",
+        ]
+    );
+    let fixers: Vec<_> = progress
+        .iter()
+        .filter(|e| e["event"] == "fixer_finished")
+        .collect();
+    let last = fixers.last().unwrap();
+    assert_eq!(last["done"], last["total"]);
+    assert_eq!(fixers.len() as u64, last["total"].as_u64().unwrap());
+    assert_eq!(
+        fixers
+            .iter()
+            .map(|e| e["ops"].as_u64().unwrap())
+            .sum::<u64>(),
+        1
+    );
+    let ops: Vec<_> = progress
+        .iter()
+        .filter(|e| e["event"] == "op_progress")
+        .collect();
+    assert_eq!(ops.len(), 1);
+    assert_eq!(
+        (ops[0]["done"].as_u64(), ops[0]["total"].as_u64()),
+        (Some(1), Some(1))
+    );
+
+    let names: Vec<&str> = events
+        .iter()
+        .map(|e| e["event"].as_str().unwrap())
+        .collect();
+    assert_eq!(
+        names,
+        vec![
+            "plan_started",
+            "op_planned",
+            "plan_finished",
+            "artifacts_written",
+            "apply_started",
+            "op_applied",
+            "apply_finished",
+            "artifacts_written",
+        ]
+    );
+    assert!(events.iter().all(|e| e["ts"].is_string()));
+    assert_eq!(
+        events[1]["fix_key"],
+        "builddiag/workspace.resolver_v2/not_v2"
+    );
+    assert_eq!(events[1]["op_id"], events[5]["op_id"]);
+    assert_eq!(events[4]["dry_run"], false);
+    assert_eq!(events[5]["files"], serde_json::json!(["Cargo.toml"]));
+    assert_eq!(events[6]["applied"], 1);
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--events", "fd:x"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn test_plan_rejects_invalid_receipt_sources() {
+    let temp = create_temp_repo();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--receipt-url", "ftp://ci.example/report.json"])
+        .assert()
+        .code(1);
+
+    buildfix()
+        .current_dir(temp.path())
+        .args([
+            "plan",
+            "--receipt-url",
+            "https://ci.example/builddiag/report.json",
+            "--receipt-header",
+            "no-colon",
+        ])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn exit_code_1_apply_missing_plan() {
+    let temp = create_temp_repo();
+
+    // No plan.json exists, so apply --apply should fail with exit 1 (tool error).
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply", "--apply"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn exit_code_2_plan_with_deny_policy() {
+    let temp = create_temp_repo_with_receipt();
+
+    // The receipt triggers an op, but --deny "*" blocks it -> exit 2.
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--deny", "*"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn exit_code_2_plan_with_max_ops_zero() {
+    let temp = create_temp_repo_with_receipt();
+
+    // The receipt triggers an op, but --max-ops 0 blocks it -> exit 2.
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--max-ops", "0"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn exit_code_0_plan_with_deny_policy_cockpit_mode() {
+    let temp = create_temp_repo_with_receipt();
+
+    // In cockpit mode, policy blocks (exit 2) are remapped to exit 0.
+    buildfix()
+        .current_dir(temp.path())
+        .args(["plan", "--deny", "*", "--mode", "cockpit"])
+        .assert()
+        .code(0);
+}
+
+#[test]
+fn exit_code_1_apply_patch_missing_patch() {
+    let temp = create_temp_repo();
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply-patch", "--apply"])
+        .assert()
+        .code(1);
+}
+
+#[test]
+fn apply_patch_applies_plan_patch() {
+    let temp = create_temp_repo_with_receipt();
+    buildfix()
+        .current_dir(temp.path())
+        .arg("plan")
+        .assert()
+        .code(0);
+
+    let before = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    let patch = fs::read_to_string(temp.path().join("artifacts/buildfix/patch.diff")).unwrap();
+    assert!(!patch.is_empty());
+
+    // Dry run checks the patch without touching files.
+    buildfix()
+        .current_dir(temp.path())
+        .arg("apply-patch")
+        .assert()
+        .code(0);
+    assert_eq!(
+        fs::read_to_string(temp.path().join("Cargo.toml")).unwrap(),
+        before
+    );
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply-patch", "--apply"])
+        .assert()
+        .code(0);
+    assert_ne!(
+        fs::read_to_string(temp.path().join("Cargo.toml")).unwrap(),
+        before
+    );
+
+    // The plan's sha256 preconditions no longer match, so a second apply is blocked.
+    buildfix()
+        .current_dir(temp.path())
+        .args(["apply-patch", "--apply"])
+        .assert()
+        .code(2);
+}
+
+#[test]
+fn test_hook_install_and_run_on_staged_files() {
+    let temp = create_temp_repo_with_receipt();
+    let git = |args: &[&str]| git_in(temp.path(), args);
+    fs::write(temp.path().join(".gitignore"), "artifacts/\n").unwrap();
+    git(&["init", "-q"]);
+    git(&["add", "-A"]);
+    git(&["commit", "-qm", "init"]);
+
+    buildfix()
+        .current_dir(temp.path())
+        .args(["hook", "install"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains("installed pre-commit hook"));
+    let script = fs::read_to_string(temp.path().join(".git/hooks/pre-commit")).unwrap();
+    assert!(script.contains("hook run --hook pre-commit"));
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(temp.path().join(".git/hooks/pre-commit"))
+            .unwrap()
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o755);
+    }
+    // Reinstalling our own hook is fine; someone else's needs --force.
+    buildfix()
+        .current_dir(temp.path())
+        .args(["hook", "install"])
+        .assert()
+        .success();
+    fs::write(
+        temp.path().join(".git/hooks/pre-push"),
+        "#!/bin/sh\nexit 0\n",
+    )
+    .unwrap();
+    buildfix()
+        .current_dir(temp.path())
+        .args(["hook", "install", "--hook", "pre-push"])
+        .assert()
+        .code(1)
+        .stdout(predicate::str::contains("--force"));
+    buildfix()
+        .current_dir(temp.path())
+        .args(["hook", "install", "--hook", "pre-push", "--force"])
+        .assert()
+        .success();
+
+    // Nothing staged: the root manifest's finding is out of scope.
+    buildfix()
+        .current_dir(temp.path())
+        .args(["hook", "run"])
+        .assert()
+        .success()
+        .stdout(predicate::str::contains(
+            "buildfix pre-commit: no safe fixes pending",
+        ));
+
+    fs::write(temp.path().join("README.md"), "hi\n").unwrap();
+    git(&["add", "README.md"]);
+    buildfix()
+        .current_dir(temp.path())
+        .args(["hook", "run"])
+        .assert()
+        .code(2)
+        .stdout(predicate::str::contains(
+            "buildfix pre-commit: 1 safe fix pending",
+        ))
+        .stdout(predicate::str::contains("  Cargo.toml  "))
+        .stdout(predicate::str::contains("buildfix apply --apply"));
+
+    #[cfg(unix)]
+    {
+        let commit = std::process::Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(["commit", "-qm", "readme"])
+            .env("BUILDFIX", assert_cmd::cargo::cargo_bin("buildfix"))
+            .current_dir(temp.path())
+            .output()
+            .unwrap();
+        assert!(!commit.status.success(), "hook should reject the commit");
+        // Git sends hook output to stderr.
+        assert!(String::from_utf8_lossy(&commit.stderr).contains("1 safe fix pending"));
+    }
+}
+
+#[test]
+fn test_watch_replans_when_a_manifest_changes() {
+    use std::io::BufRead;
+
+    let temp = create_temp_repo_with_receipt();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("buildfix"))
+        .args(["watch", "--debounce-ms", "50", "--max-runs", "2"])
+        .current_dir(temp.path())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let stdout = child.stdout.take().unwrap();
+    let (tx, rx) = std::sync::mpsc::channel();
+    std::thread::spawn(move || {
+        for line in std::io::BufReader::new(stdout).lines() {
+            let event: serde_json::Value = serde_json::from_str(&line.unwrap()).unwrap();
+            if tx.send(event).is_err() {
+                break;
+            }
+        }
+    });
+    let next = |name: &str| loop {
+        let event = rx
+            .recv_timeout(std::time::Duration::from_secs(30))
+            .unwrap_or_else(|_| panic!("no {} event", name));
+        if event["event"] == name {
+            return event;
+        }
+    };
+
+    next("watch_started");
+    assert_eq!(next("plan_finished")["ops_total"], 1);
+
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/a\"]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+    let change = next("change_detected");
+    assert!(change["paths"][0].as_str().unwrap().ends_with("Cargo.toml"));
+    next("plan_finished");
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_serve_answers_requests_until_shutdown() {
+    use std::io::{BufRead, Write};
+
+    let temp = create_temp_repo_with_receipt();
+    let root = temp.path().to_str().unwrap();
+    let mut child = std::process::Command::new(assert_cmd::cargo::cargo_bin("buildfix"))
+        .arg("serve")
+        .stdin(std::process::Stdio::piped())
+        .stdout(std::process::Stdio::piped())
+        .stderr(std::process::Stdio::null())
+        .spawn()
+        .unwrap();
+    let mut stdin = child.stdin.take().unwrap();
+    let mut stdout = std::io::BufReader::new(child.stdout.take().unwrap());
+    let mut call = |id: u64, method: &str, params: serde_json::Value| {
+        let request = serde_json::json!({
+            "jsonrpc": "2.0", "id": id, "method": method, "params": params,
+        });
+        writeln!(stdin, "{}", request).unwrap();
+        let mut line = String::new();
+        stdout.read_line(&mut line).unwrap();
+        let response: serde_json::Value = serde_json::from_str(&line).unwrap();
+        assert_eq!(response["id"], id);
+        response
+    };
+
+    for id in [1, 2] {
+        let plan = call(id, "plan", serde_json::json!(["--repo-root", root]));
+        assert_eq!(plan["result"]["exit_code"], 0, "{}", plan);
+        assert_eq!(plan["result"]["plan"]["summary"]["ops_total"], 1);
+    }
+    assert!(temp.path().join("artifacts/buildfix/plan.json").exists());
+
+    let explain = call(3, "explain", serde_json::json!(["resolver-v2"]));
+    assert_eq!(explain["result"]["key"], "resolver-v2", "{}", explain);
+
+    let status = call(
+        4,
+        "status",
+        serde_json::json!(["--repo-root", root, "--format", "json"]),
+    );
+    assert!(status["result"].is_object(), "{}", status);
+
+    assert_eq!(
+        call(5, "frobnicate", serde_json::json!([]))["error"]["code"],
+        -32601
+    );
+    assert_eq!(
+        call(6, "plan", serde_json::json!(["--no-such-flag"]))["error"]["code"],
+        -32602
+    );
+    assert_eq!(
+        call(7, "shutdown", serde_json::json!([]))["result"],
+        serde_json::Value::Null
+    );
+    assert!(child.wait().unwrap().success());
+}
+
+#[test]
+fn test_bundled_schemas_match_workspace_schemas() {
+    let crate_dir = std::path::Path::new(env!("CARGO_MANIFEST_DIR"));
+    for name in [
+        "buildfix.plan.v1.json",
+        "buildfix.plan.v2.json",
+        "buildfix.apply.v1.json",
+        "buildfix.report.v1.json",
+    ] {
+        assert_eq!(
+            fs::read_to_string(crate_dir.join("schemas").join(name)).unwrap(),
+            fs::read_to_string(crate_dir.join("../schemas").join(name)).unwrap(),
+            "buildfix-cli/schemas/{name} is out of sync with schemas/{name}"
+        );
+    }
 }
@@ -63,7 +63,7 @@ mod registry_alignment {
         assert!(
             missing.is_empty(),
             "catalog entries missing from explain registry: {missing:?}\n\
-             Add corresponding FixExplanation entries to FIX_REGISTRY in explain.rs"
+             Fill in FixerMeta for the fixer and register it in builtin_fixers()"
         );
     }
 
@@ -86,7 +86,7 @@ mod registry_alignment {
         assert!(
             orphaned.is_empty(),
             "explain entries without catalog entries (orphaned): {orphaned:?}\n\
-             Remove FixExplanation entries from FIX_REGISTRY or add fixer to catalog"
+             Add the fixer to the catalog or stop registering it in builtin_fixers()"
         );
     }
 
@@ -1,5 +1,8 @@
 //! Default filesystem and in-memory adapters for pipeline ports.
 
+pub use crate::metrics::{NoopMetrics, PrometheusMetrics};
+pub use buildfix_types::clock::{FixedClock, RandomIds, SequentialIds, SystemClock};
+
 #[cfg(feature = "git")]
 use super::ports::GitPort;
 use super::ports::ReceiptSource;
@@ -7,33 +10,101 @@ use super::ports::ReceiptSource;
 use super::ports::WritePort;
 use anyhow::Context;
 use buildfix_receipts::LoadedReceipt;
+#[cfg(feature = "fs")]
+use buildfix_receipts::{CacheStats, ReceiptCache, ReceiptDiscovery, TrustPolicy};
 use camino::{Utf8Path, Utf8PathBuf};
+#[cfg(feature = "fs")]
+use std::sync::Arc;
 #[cfg(feature = "memory")]
 use tracing::debug;
 
-/// Loads receipts from the filesystem via `buildfix_receipts::load_receipts`.
+/// File name of the optional `cargo metadata` snapshot in the artifacts dir.
+pub const CARGO_METADATA_FILE: &str = "cargo-metadata.json";
+
+/// Loads receipts from the filesystem via `buildfix_receipts::discover_receipts`.
 #[cfg(feature = "fs")]
 #[derive(Debug, Clone)]
 pub struct FsReceiptSource {
     pub artifacts_dir: Utf8PathBuf,
+    /// Explicit `cargo metadata` snapshot. When unset, `<artifacts_dir>/cargo-metadata.json`
+    /// is used if it exists.
+    pub cargo_metadata: Option<Utf8PathBuf>,
+    /// Verify detached receipt signatures against this policy.
+    pub trust: Option<TrustPolicy>,
+    /// Where to look for receipts; defaults to `<artifacts_dir>/<sensor>/...`.
+    pub discovery: ReceiptDiscovery,
+    /// Parsed-receipt cache, shared by clones of this source.
+    pub cache: Option<Arc<ReceiptCache>>,
 }
 
 #[cfg(feature = "fs")]
 impl FsReceiptSource {
     pub fn new(artifacts_dir: Utf8PathBuf) -> Self {
-        Self { artifacts_dir }
+        Self {
+            artifacts_dir,
+            cargo_metadata: None,
+            trust: None,
+            discovery: ReceiptDiscovery::default(),
+            cache: None,
+        }
+    }
+
+    pub fn with_cargo_metadata(mut self, path: Utf8PathBuf) -> Self {
+        self.cargo_metadata = Some(path);
+        self
+    }
+
+    pub fn with_trust_policy(mut self, trust: TrustPolicy) -> Self {
+        self.trust = Some(trust);
+        self
+    }
+
+    pub fn with_discovery(mut self, discovery: ReceiptDiscovery) -> Self {
+        self.discovery = discovery;
+        self
+    }
+
+    /// Cache parsed receipts under `dir`, keyed by file sha256.
+    pub fn with_cache_dir(mut self, dir: Utf8PathBuf) -> Self {
+        self.cache = Some(Arc::new(ReceiptCache::new(dir)));
+        self
     }
 }
 
 #[cfg(feature = "fs")]
 impl ReceiptSource for FsReceiptSource {
     fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
-        buildfix_receipts::load_receipts(&self.artifacts_dir)
-            .with_context(|| format!("load receipts from {}", self.artifacts_dir))
+        buildfix_receipts::discover_receipts(
+            &self.artifacts_dir,
+            &self.discovery,
+            self.trust.as_ref(),
+            self.cache.as_deref(),
+        )
+        .with_context(|| format!("load receipts from {}", self.artifacts_dir))
+    }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        let path = match &self.cargo_metadata {
+            Some(path) => path.clone(),
+            None => {
+                let default = self.artifacts_dir.join(CARGO_METADATA_FILE);
+                if !default.is_file() {
+                    return Ok(None);
+                }
+                default
+            }
+        };
+        let json = std::fs::read_to_string(&path)
+            .with_context(|| format!("read cargo metadata {}", path))?;
+        Ok(Some(json))
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.cache.as_ref().map(|cache| cache.stats())
     }
 }
 
-/// Git operations via `buildfix_edit` shell helpers.
+/// Git operations by running the `git` binary.
 #[cfg(feature = "git")]
 #[derive(Debug, Clone, Default)]
 pub struct ShellGitPort;
@@ -41,19 +112,44 @@ pub struct ShellGitPort;
 #[cfg(feature = "git")]
 impl GitPort for ShellGitPort {
     fn head_sha(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
-        match buildfix_edit::get_head_sha(repo_root) {
+        match buildfix_edit::git_cli_head_sha(repo_root) {
             Ok(sha) => Ok(Some(sha)),
             Err(_) => Ok(None),
         }
     }
 
     fn is_dirty(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
-        match buildfix_edit::is_working_tree_dirty(repo_root) {
+        match buildfix_edit::git_cli_is_dirty(repo_root) {
+            Ok(dirty) => Ok(Some(dirty)),
+            Err(_) => Ok(None),
+        }
+    }
+
+    fn is_dirty_paths(
+        &self,
+        repo_root: &Utf8Path,
+        paths: &[String],
+    ) -> anyhow::Result<Option<bool>> {
+        match buildfix_edit::git_cli_is_dirty_paths(repo_root, paths) {
             Ok(dirty) => Ok(Some(dirty)),
             Err(_) => Ok(None),
         }
     }
 
+    fn remote_url(&self, repo_root: &Utf8Path, remote: &str) -> anyhow::Result<Option<String>> {
+        let output = std::process::Command::new("git")
+            .args(["remote", "get-url", remote])
+            .current_dir(repo_root)
+            .output()
+            .with_context(|| format!("git remote get-url in {}", repo_root))?;
+        let url = String::from_utf8_lossy(&output.stdout).trim().to_string();
+        Ok((output.status.success() && !url.is_empty()).then_some(url))
+    }
+
+    fn dirty_paths(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(buildfix_edit::git_cli_dirty_paths(repo_root).ok())
+    }
+
     fn commit_all(&self, repo_root: &Utf8Path, message: &str) -> anyhow::Result<Option<String>> {
         use std::process::Command;
 
@@ -93,6 +189,158 @@ impl GitPort for ShellGitPort {
             None => anyhow::bail!("git commit succeeded but head sha is unavailable"),
         }
     }
+
+    fn create_branch(&self, repo_root: &Utf8Path, branch: &str) -> anyhow::Result<bool> {
+        git_checked(repo_root, &["checkout", "-b", branch])?;
+        Ok(true)
+    }
+
+    fn push(&self, repo_root: &Utf8Path, remote: &str, branch: &str) -> anyhow::Result<bool> {
+        git_checked(repo_root, &["push", "--set-upstream", remote, branch])?;
+        Ok(true)
+    }
+
+    fn add_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<bool> {
+        git_checked(
+            repo_root,
+            &["worktree", "add", "--detach", path.as_str(), "HEAD"],
+        )?;
+        Ok(true)
+    }
+
+    fn remove_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<()> {
+        git_checked(repo_root, &["worktree", "remove", "--force", path.as_str()])
+    }
+
+    fn changed_files(
+        &self,
+        repo_root: &Utf8Path,
+        base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        buildfix_edit::changed_files_since(repo_root, base_ref).map(Some)
+    }
+
+    fn staged_files(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        buildfix_edit::staged_files(repo_root).map(Some)
+    }
+}
+
+/// Run `git <args>` in `repo_root`, failing with its stderr on a non-zero exit.
+#[cfg(feature = "git")]
+fn git_checked(repo_root: &Utf8Path, args: &[&str]) -> anyhow::Result<()> {
+    let output = std::process::Command::new("git")
+        .args(args)
+        .current_dir(repo_root)
+        .output()
+        .with_context(|| format!("git {} in {}", args.join(" "), repo_root))?;
+    if !output.status.success() {
+        anyhow::bail!(
+            "git {} failed: {}",
+            args.join(" "),
+            String::from_utf8_lossy(&output.stderr).trim()
+        );
+    }
+    Ok(())
+}
+
+/// Git operations read in-process with `gix`, so `head_sha` and `is_dirty`
+/// work without a `git` binary and independent of `PATH` and locale. Falls
+/// back to [`ShellGitPort`] when gix cannot read the repository, and for
+/// committing and merge-base diffs, which still run `git`.
+#[cfg(feature = "gix")]
+#[derive(Debug, Clone, Default)]
+pub struct GixGitPort;
+
+#[cfg(feature = "gix")]
+impl GitPort for GixGitPort {
+    fn head_sha(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        match buildfix_edit::gix_head_sha(repo_root) {
+            Ok(sha) => Ok(Some(sha)),
+            Err(e) => {
+                tracing::debug!(
+                    event = buildfix_types::telemetry::GIT_GIX_FALLBACK,
+                    op = "head_sha",
+                    error = format!("{:#}", e),
+                    "gix head sha failed, falling back to git"
+                );
+                ShellGitPort.head_sha(repo_root)
+            }
+        }
+    }
+
+    fn is_dirty(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        match buildfix_edit::gix_is_dirty(repo_root) {
+            Ok(dirty) => Ok(Some(dirty)),
+            Err(e) => {
+                tracing::debug!(
+                    event = buildfix_types::telemetry::GIT_GIX_FALLBACK,
+                    op = "status",
+                    error = format!("{:#}", e),
+                    "gix status failed, falling back to git"
+                );
+                ShellGitPort.is_dirty(repo_root)
+            }
+        }
+    }
+
+    fn is_dirty_paths(
+        &self,
+        repo_root: &Utf8Path,
+        paths: &[String],
+    ) -> anyhow::Result<Option<bool>> {
+        match buildfix_edit::gix_is_dirty_paths(repo_root, paths) {
+            Ok(dirty) => Ok(Some(dirty)),
+            Err(e) => {
+                tracing::debug!(
+                    event = buildfix_types::telemetry::GIT_GIX_FALLBACK,
+                    op = "status",
+                    error = format!("{:#}", e),
+                    "gix status failed, falling back to git"
+                );
+                ShellGitPort.is_dirty_paths(repo_root, paths)
+            }
+        }
+    }
+
+    fn remote_url(&self, repo_root: &Utf8Path, remote: &str) -> anyhow::Result<Option<String>> {
+        ShellGitPort.remote_url(repo_root, remote)
+    }
+
+    fn dirty_paths(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        ShellGitPort.dirty_paths(repo_root)
+    }
+
+    fn commit_all(&self, repo_root: &Utf8Path, message: &str) -> anyhow::Result<Option<String>> {
+        ShellGitPort.commit_all(repo_root, message)
+    }
+
+    fn create_branch(&self, repo_root: &Utf8Path, branch: &str) -> anyhow::Result<bool> {
+        ShellGitPort.create_branch(repo_root, branch)
+    }
+
+    fn push(&self, repo_root: &Utf8Path, remote: &str, branch: &str) -> anyhow::Result<bool> {
+        ShellGitPort.push(repo_root, remote, branch)
+    }
+
+    fn add_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<bool> {
+        ShellGitPort.add_worktree(repo_root, path)
+    }
+
+    fn remove_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<()> {
+        ShellGitPort.remove_worktree(repo_root, path)
+    }
+
+    fn changed_files(
+        &self,
+        repo_root: &Utf8Path,
+        base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        ShellGitPort.changed_files(repo_root, base_ref)
+    }
+
+    fn staged_files(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        ShellGitPort.staged_files(repo_root)
+    }
 }
 
 /// In-memory receipt source for embedding and testing.
@@ -105,6 +353,7 @@ impl GitPort for ShellGitPort {
 #[derive(Debug, Clone)]
 pub struct InMemoryReceiptSource {
     receipts: Vec<LoadedReceipt>,
+    cargo_metadata: Option<String>,
 }
 
 #[cfg(feature = "memory")]
@@ -124,7 +373,8 @@ impl InMemoryReceiptSource {
 
             if is_buildfix || is_cockpit {
                 debug!(
-                    path = r.path.as_str(),
+                    event = buildfix_types::telemetry::RECEIPT_SKIPPED,
+                    file = r.path.as_str(),
                     sensor_id = r.sensor_id.as_str(),
                     "skipping non-sensor receipt"
                 );
@@ -133,7 +383,16 @@ impl InMemoryReceiptSource {
             true
         });
         receipts.sort_by(|a, b| a.path.cmp(&b.path));
-        Self { receipts }
+        Self {
+            receipts,
+            cargo_metadata: None,
+        }
+    }
+
+    /// Serve `json` as the `cargo metadata` snapshot.
+    pub fn with_cargo_metadata(mut self, json: impl Into<String>) -> Self {
+        self.cargo_metadata = Some(json.into());
+        self
     }
 }
 
@@ -142,6 +401,376 @@ impl ReceiptSource for InMemoryReceiptSource {
     fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
         Ok(self.receipts.clone())
     }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        Ok(self.cargo_metadata.clone())
+    }
+}
+
+/// A receipt fetched over HTTP(S).
+#[cfg(feature = "http")]
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RemoteReceipt {
+    pub url: String,
+    /// Sensor id; defaults to the URL path segment before the file name,
+    /// mirroring the `artifacts/<sensor>/report.json` layout.
+    pub sensor_id: Option<String>,
+    /// Expected SHA256 (hex) of the response body.
+    pub sha256: Option<String>,
+}
+
+#[cfg(feature = "http")]
+impl RemoteReceipt {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            sensor_id: None,
+            sha256: None,
+        }
+    }
+
+    /// Parse `URL[#sha256=<hex>][&sensor=<id>]`.
+    ///
+    /// The fragment is never sent to the server, so it is a convenient place
+    /// for per-receipt options on the command line.
+    pub fn parse(spec: &str) -> anyhow::Result<Self> {
+        let (url, fragment) = match spec.split_once('#') {
+            Some((url, fragment)) => (url, Some(fragment)),
+            None => (spec, None),
+        };
+        if !(url.starts_with("http://") || url.starts_with("https://")) {
+            anyhow::bail!("receipt url must be http(s): {}", url);
+        }
+
+        let mut receipt = Self::new(url);
+        for pair in fragment.into_iter().flat_map(|f| f.split('&')) {
+            match pair.split_once('=') {
+                Some(("sha256", hex)) => receipt.sha256 = Some(hex.to_ascii_lowercase()),
+                Some(("sensor", id)) => receipt.sensor_id = Some(id.to_string()),
+                _ => anyhow::bail!("unknown receipt url option '{}' in {}", pair, spec),
+            }
+        }
+        Ok(receipt)
+    }
+
+    fn resolved_sensor_id(&self) -> String {
+        if let Some(id) = &self.sensor_id {
+            return id.clone();
+        }
+        let path = self
+            .url
+            .split(['?', '#'])
+            .next()
+            .unwrap_or_default()
+            .splitn(4, '/')
+            .nth(3)
+            .unwrap_or_default();
+        let mut segments = path.rsplit('/').filter(|s| !s.is_empty());
+        segments.next();
+        segments.next().unwrap_or("unknown").to_string()
+    }
+}
+
+/// Fetches receipt envelopes from URLs, e.g. a CI artifact store.
+///
+/// Transport and parse failures are recorded per receipt, like unreadable
+/// files in `FsReceiptSource`. A checksum mismatch fails the whole load: a
+/// receipt that does not match what the pipeline pinned is never planned from.
+#[cfg(feature = "http")]
+#[derive(Debug, Clone)]
+pub struct HttpReceiptSource {
+    pub receipts: Vec<RemoteReceipt>,
+    /// Headers sent with every request (e.g. `Authorization`).
+    pub headers: Vec<(String, String)>,
+    pub timeout: std::time::Duration,
+    /// URL of a `cargo metadata` snapshot.
+    pub cargo_metadata: Option<RemoteReceipt>,
+}
+
+#[cfg(feature = "http")]
+impl HttpReceiptSource {
+    pub fn new(receipts: Vec<RemoteReceipt>) -> Self {
+        Self {
+            receipts,
+            headers: Vec::new(),
+            timeout: std::time::Duration::from_secs(30),
+            cargo_metadata: None,
+        }
+    }
+
+    pub fn with_header(mut self, name: impl Into<String>, value: impl Into<String>) -> Self {
+        self.headers.push((name.into(), value.into()));
+        self
+    }
+
+    pub fn with_timeout(mut self, timeout: std::time::Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    pub fn with_cargo_metadata(mut self, remote: RemoteReceipt) -> Self {
+        self.cargo_metadata = Some(remote);
+        self
+    }
+
+    fn agent(&self) -> ureq::Agent {
+        ureq::AgentBuilder::new().timeout(self.timeout).build()
+    }
+
+    fn fetch(&self, agent: &ureq::Agent, url: &str) -> Result<Vec<u8>, String> {
+        use std::io::Read;
+
+        let mut request = agent.get(url);
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        let response = request.call().map_err(|e| match e {
+            ureq::Error::Status(code, _) => format!("GET {} returned HTTP {}", url, code),
+            other => format!("GET {}: {}", url, other),
+        })?;
+
+        let mut body = Vec::new();
+        response
+            .into_reader()
+            .read_to_end(&mut body)
+            .map_err(|e| format!("read body of {}: {}", url, e))?;
+        Ok(body)
+    }
+}
+
+#[cfg(feature = "http")]
+fn verify_sha256(remote: &RemoteReceipt, body: &[u8]) -> anyhow::Result<()> {
+    if let Some(expected) = &remote.sha256 {
+        let actual = buildfix_hash::sha256_hex(body);
+        if !actual.eq_ignore_ascii_case(expected) {
+            anyhow::bail!(
+                "checksum mismatch for {} (expected {}, got {})",
+                remote.url,
+                expected,
+                actual
+            );
+        }
+    }
+    Ok(())
+}
+
+#[cfg(feature = "http")]
+impl ReceiptSource for HttpReceiptSource {
+    fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        use buildfix_receipts::{ReceiptEnvelope, ReceiptLoadError};
+
+        let agent = self.agent();
+        let mut out = Vec::with_capacity(self.receipts.len());
+        for remote in &self.receipts {
+            let sensor_id = remote.resolved_sensor_id();
+            if sensor_id == "buildfix" || sensor_id == "cockpit" {
+                tracing::debug!(
+                    event = buildfix_types::telemetry::RECEIPT_SKIPPED,
+                    file = %remote.url,
+                    %sensor_id,
+                    "skipping non-sensor receipt"
+                );
+                continue;
+            }
+
+            let receipt = match self.fetch(&agent, &remote.url) {
+                Ok(body) => {
+                    verify_sha256(remote, &body)?;
+                    serde_json::from_slice::<ReceiptEnvelope>(&body).map_err(|e| {
+                        ReceiptLoadError::Json {
+                            message: e.to_string(),
+                        }
+                    })
+                }
+                Err(message) => Err(ReceiptLoadError::Io { message }),
+            };
+            out.push(LoadedReceipt {
+                path: Utf8PathBuf::from(remote.url.as_str()),
+                sensor_id,
+                receipt,
+                signature: None,
+            });
+        }
+
+        out.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(out)
+    }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        let Some(remote) = &self.cargo_metadata else {
+            return Ok(None);
+        };
+        let body = self
+            .fetch(&self.agent(), &remote.url)
+            .map_err(|message| anyhow::anyhow!(message))?;
+        verify_sha256(remote, &body)?;
+        let json = String::from_utf8(body)
+            .with_context(|| format!("cargo metadata from {} is not UTF-8", remote.url))?;
+        Ok(Some(json))
+    }
+}
+
+/// Loads receipts from an object store prefix (`s3://`, `gs://`, `az://`,
+/// `file://`), laid out like a local artifacts dir:
+/// `<prefix>/<sensor>/report.json` and an optional `<prefix>/cargo-metadata.json`.
+///
+/// Credentials and region come from builder options; [`with_env_options`]
+/// picks up the usual `AWS_*`, `GOOGLE_*` and `AZURE_*` variables.
+///
+/// [`with_env_options`]: ObjectStoreReceiptSource::with_env_options
+#[cfg(feature = "object-store")]
+#[derive(Debug, Clone)]
+pub struct ObjectStoreReceiptSource {
+    pub url: String,
+    pub options: Vec<(String, String)>,
+}
+
+#[cfg(feature = "object-store")]
+impl ObjectStoreReceiptSource {
+    pub fn new(url: impl Into<String>) -> Self {
+        Self {
+            url: url.into(),
+            options: Vec::new(),
+        }
+    }
+
+    pub fn with_option(mut self, key: impl Into<String>, value: impl Into<String>) -> Self {
+        self.options.push((key.into(), value.into()));
+        self
+    }
+
+    /// Add `AWS_*`, `GOOGLE_*` and `AZURE_*` environment variables as options.
+    pub fn with_env_options(mut self) -> Self {
+        for (key, value) in std::env::vars() {
+            if ["AWS_", "GOOGLE_", "AZURE_"]
+                .iter()
+                .any(|p| key.starts_with(p))
+            {
+                self.options.push((key.to_ascii_lowercase(), value));
+            }
+        }
+        self
+    }
+
+    fn open(
+        &self,
+    ) -> anyhow::Result<(Box<dyn object_store::ObjectStore>, object_store::path::Path)> {
+        let url = url::Url::parse(&self.url)
+            .with_context(|| format!("parse object store url {}", self.url))?;
+        object_store::parse_url_opts(&url, self.options.iter().map(|(k, v)| (k, v.clone())))
+            .with_context(|| format!("open object store {}", self.url))
+    }
+
+    fn block_on<F: std::future::Future>(future: F) -> anyhow::Result<F::Output> {
+        let runtime = tokio::runtime::Builder::new_current_thread()
+            .enable_all()
+            .build()
+            .context("start object store runtime")?;
+        Ok(runtime.block_on(future))
+    }
+
+    fn object_url(&self, relative: &str) -> String {
+        format!("{}/{}", self.url.trim_end_matches('/'), relative)
+    }
+}
+
+#[cfg(feature = "object-store")]
+impl ObjectStoreReceiptSource {
+    async fn fetch_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        use buildfix_receipts::{ReceiptEnvelope, ReceiptLoadError};
+        use futures::TryStreamExt;
+
+        let (store, prefix) = self.open()?;
+        let objects: Vec<object_store::ObjectMeta> = store
+            .list(Some(&prefix))
+            .try_collect()
+            .await
+            .with_context(|| format!("list {}", self.url))?;
+
+        let mut out = Vec::new();
+        for meta in objects {
+            let Some(relative) = meta.location.prefix_match(&prefix) else {
+                continue;
+            };
+            let parts: Vec<_> = relative.map(|p| p.as_ref().to_string()).collect();
+            let [sensor_id, file] = parts.as_slice() else {
+                continue;
+            };
+            if file != "report.json" {
+                continue;
+            }
+            if sensor_id == "buildfix" || sensor_id == "cockpit" {
+                tracing::debug!(
+                    event = buildfix_types::telemetry::RECEIPT_SKIPPED,
+                    file = %meta.location,
+                    %sensor_id,
+                    "skipping non-sensor receipt"
+                );
+                continue;
+            }
+
+            let receipt = match store.get(&meta.location).await {
+                Ok(get) => get.bytes().await,
+                Err(e) => Err(e),
+            }
+            .map_err(|e| ReceiptLoadError::Io {
+                message: e.to_string(),
+            })
+            .and_then(|body| {
+                serde_json::from_slice::<ReceiptEnvelope>(&body).map_err(|e| {
+                    ReceiptLoadError::Json {
+                        message: e.to_string(),
+                    }
+                })
+            });
+            out.push(LoadedReceipt {
+                path: Utf8PathBuf::from(self.object_url(&format!("{}/{}", sensor_id, file))),
+                sensor_id: sensor_id.clone(),
+                receipt,
+                signature: None,
+            });
+        }
+
+        out.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(out)
+    }
+
+    async fn fetch_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        let (store, prefix) = self.open()?;
+        let location = prefix.child(CARGO_METADATA_FILE);
+        let body = match store.get(&location).await {
+            Ok(get) => get.bytes().await,
+            Err(object_store::Error::NotFound { .. }) => return Ok(None),
+            Err(e) => Err(e),
+        }
+        .with_context(|| format!("read {}", self.object_url(CARGO_METADATA_FILE)))?;
+        let json = String::from_utf8(body.to_vec())
+            .with_context(|| format!("{} is not UTF-8", location))?;
+        Ok(Some(json))
+    }
+}
+
+#[cfg(feature = "object-store")]
+impl ReceiptSource for ObjectStoreReceiptSource {
+    fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        Self::block_on(self.fetch_receipts())?
+    }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        Self::block_on(self.fetch_cargo_metadata())?
+    }
+}
+
+/// Native async: no runtime of its own, so it can be used from inside one.
+#[cfg(all(feature = "object-store", feature = "async"))]
+impl super::ports::AsyncReceiptSource for ObjectStoreReceiptSource {
+    async fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        self.fetch_receipts().await
+    }
+
+    async fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        self.fetch_cargo_metadata().await
+    }
 }
 
 /// Filesystem write operations.
@@ -162,6 +791,167 @@ impl WritePort for FsWritePort {
     fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()> {
         std::fs::create_dir_all(path).with_context(|| format!("create_dir_all {}", path))
     }
+
+    fn write_file_with(
+        &self,
+        path: &Utf8Path,
+        write: &mut dyn FnMut(&mut dyn std::io::Write) -> std::io::Result<()>,
+    ) -> anyhow::Result<()> {
+        if let Some(parent) = path.parent() {
+            std::fs::create_dir_all(parent)
+                .with_context(|| format!("create parent dir for {}", path))?;
+        }
+        let file = std::fs::File::create(path).with_context(|| format!("write {}", path))?;
+        let mut out = std::io::BufWriter::new(file);
+        write(&mut out)
+            .and_then(|()| std::io::Write::flush(&mut out))
+            .with_context(|| format!("write {}", path))
+    }
+}
+
+/// A sync port used as an async one (`async` feature): each call runs on
+/// tokio's blocking pool, so adapters such as [`ShellGitPort`] or
+/// [`FsReceiptSource`] can back the async pipeline without stalling the
+/// runtime's workers.
+#[cfg(feature = "async")]
+#[derive(Debug, Default)]
+pub struct Blocking<P>(pub std::sync::Arc<P>);
+
+#[cfg(feature = "async")]
+impl<P> Clone for Blocking<P> {
+    fn clone(&self) -> Self {
+        Self(self.0.clone())
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: Send + Sync + 'static> Blocking<P> {
+    pub fn new(port: P) -> Self {
+        Self(std::sync::Arc::new(port))
+    }
+
+    async fn run<T: Send + 'static>(
+        &self,
+        call: impl FnOnce(&P) -> anyhow::Result<T> + Send + 'static,
+    ) -> anyhow::Result<T> {
+        let port = self.0.clone();
+        match tokio::task::spawn_blocking(move || call(&port)).await {
+            Ok(result) => result,
+            Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+            Err(e) => Err(anyhow::anyhow!("blocking port call: {}", e)),
+        }
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: ReceiptSource + Send + Sync + 'static> super::ports::AsyncReceiptSource for Blocking<P> {
+    async fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        self.run(|source| source.load_receipts()).await
+    }
+
+    async fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        self.run(|source| source.load_cargo_metadata()).await
+    }
+
+    fn cache_stats(&self) -> Option<buildfix_receipts::CacheStats> {
+        self.0.cache_stats()
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: super::ports::GitPort + Send + Sync + 'static> super::ports::AsyncGitPort for Blocking<P> {
+    async fn head_sha(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        let root = repo_root.to_owned();
+        self.run(move |git| git.head_sha(&root)).await
+    }
+
+    async fn is_dirty(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        let root = repo_root.to_owned();
+        self.run(move |git| git.is_dirty(&root)).await
+    }
+
+    async fn is_dirty_paths(
+        &self,
+        repo_root: &Utf8Path,
+        paths: &[String],
+    ) -> anyhow::Result<Option<bool>> {
+        let (root, paths) = (repo_root.to_owned(), paths.to_vec());
+        self.run(move |git| git.is_dirty_paths(&root, &paths)).await
+    }
+
+    async fn remote_url(
+        &self,
+        repo_root: &Utf8Path,
+        remote: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let (root, remote) = (repo_root.to_owned(), remote.to_owned());
+        self.run(move |git| git.remote_url(&root, &remote)).await
+    }
+
+    async fn dirty_paths(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        let root = repo_root.to_owned();
+        self.run(move |git| git.dirty_paths(&root)).await
+    }
+
+    async fn commit_all(
+        &self,
+        repo_root: &Utf8Path,
+        message: &str,
+    ) -> anyhow::Result<Option<String>> {
+        let (root, message) = (repo_root.to_owned(), message.to_owned());
+        self.run(move |git| git.commit_all(&root, &message)).await
+    }
+
+    async fn create_branch(&self, repo_root: &Utf8Path, branch: &str) -> anyhow::Result<bool> {
+        let (root, branch) = (repo_root.to_owned(), branch.to_owned());
+        self.run(move |git| git.create_branch(&root, &branch)).await
+    }
+
+    async fn push(&self, repo_root: &Utf8Path, remote: &str, branch: &str) -> anyhow::Result<bool> {
+        let (root, remote, branch) = (repo_root.to_owned(), remote.to_owned(), branch.to_owned());
+        self.run(move |git| git.push(&root, &remote, &branch)).await
+    }
+
+    async fn add_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<bool> {
+        let (root, path) = (repo_root.to_owned(), path.to_owned());
+        self.run(move |git| git.add_worktree(&root, &path)).await
+    }
+
+    async fn remove_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<()> {
+        let (root, path) = (repo_root.to_owned(), path.to_owned());
+        self.run(move |git| git.remove_worktree(&root, &path)).await
+    }
+
+    async fn changed_files(
+        &self,
+        repo_root: &Utf8Path,
+        base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        let (root, base_ref) = (repo_root.to_owned(), base_ref.to_owned());
+        self.run(move |git| git.changed_files(&root, &base_ref))
+            .await
+    }
+
+    async fn staged_files(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        let root = repo_root.to_owned();
+        self.run(move |git| git.staged_files(&root)).await
+    }
+}
+
+#[cfg(feature = "async")]
+impl<P: super::ports::WritePort + Send + Sync + 'static> super::ports::AsyncWritePort
+    for Blocking<P>
+{
+    async fn write_file(&self, path: &Utf8Path, contents: &[u8]) -> anyhow::Result<()> {
+        let (path, contents) = (path.to_owned(), contents.to_vec());
+        self.run(move |writer| writer.write_file(&path, &contents))
+            .await
+    }
+
+    async fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()> {
+        let path = path.to_owned();
+        self.run(move |writer| writer.create_dir_all(&path)).await
+    }
 }
 
 #[cfg(test)]
@@ -179,6 +969,7 @@ mod tests {
             receipt: Err(ReceiptLoadError::Io {
                 message: "stub".to_string(),
             }),
+            signature: None,
         }
     }
 
@@ -189,6 +980,7 @@ mod tests {
             receipt: Err(ReceiptLoadError::Io {
                 message: "stub".to_string(),
             }),
+            signature: None,
         }
     }
 
@@ -432,4 +1224,237 @@ mod tests {
         assert_eq!(port.is_dirty(&root).expect("dirty after"), Some(false));
         assert_ne!(committed, head_before);
     }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn shell_git_port_creates_and_pushes_branch() {
+        let temp = TempDir::new().expect("temp dir");
+        let base = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        let remote = base.join("remote.git");
+        let root = base.join("work");
+        std::fs::create_dir_all(&root).expect("mkdir");
+        run_git(&base, &["init", "--bare", remote.as_str()]);
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\n").expect("write");
+        run_git(&root, &["init"]);
+        run_git(&root, &["config", "user.email", "test@example.com"]);
+        run_git(&root, &["config", "user.name", "Test User"]);
+        run_git(&root, &["add", "."]);
+        run_git(&root, &["commit", "-m", "init"]);
+        run_git(&root, &["remote", "add", "origin", remote.as_str()]);
+
+        let port = ShellGitPort;
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nresolver = \"2\"\n").expect("write");
+        assert!(port.create_branch(&root, "buildfix/test").expect("branch"));
+        let committed = port.commit_all(&root, "fix").expect("commit");
+        assert!(port.push(&root, "origin", "buildfix/test").expect("push"));
+
+        let pushed = std::process::Command::new("git")
+            .args(["rev-parse", "refs/heads/buildfix/test"])
+            .current_dir(&remote)
+            .output()
+            .expect("rev-parse");
+        assert_eq!(
+            String::from_utf8_lossy(&pushed.stdout).trim(),
+            committed.expect("sha")
+        );
+        assert!(port.create_branch(&root, "buildfix/test").is_err());
+    }
+
+    #[cfg(feature = "git")]
+    #[test]
+    fn shell_git_port_adds_and_removes_worktree() {
+        let temp = TempDir::new().expect("temp dir");
+        let base = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        let root = base.join("work");
+        let sandbox = base.join("sandbox");
+        std::fs::create_dir_all(&root).expect("mkdir");
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\n").expect("write");
+        run_git(&root, &["init"]);
+        run_git(&root, &["config", "user.email", "test@example.com"]);
+        run_git(&root, &["config", "user.name", "Test User"]);
+        run_git(&root, &["add", "."]);
+        run_git(&root, &["commit", "-m", "init"]);
+
+        let port = ShellGitPort;
+        assert!(port.add_worktree(&root, &sandbox).expect("add"));
+        std::fs::write(sandbox.join("Cargo.toml"), "changed").expect("write");
+        assert_eq!(
+            std::fs::read_to_string(root.join("Cargo.toml")).expect("read"),
+            "[workspace]\n"
+        );
+        assert_eq!(port.is_dirty(&root).expect("dirty"), Some(false));
+
+        port.remove_worktree(&root, &sandbox).expect("remove");
+        assert!(!sandbox.exists());
+    }
+
+    /// Serve canned responses on localhost, one per connection, and hand back
+    /// the raw request heads.
+    #[cfg(feature = "http")]
+    fn serve(
+        responses: Vec<(&'static str, String)>,
+    ) -> (String, std::thread::JoinHandle<Vec<String>>) {
+        use std::io::{BufRead, BufReader, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let base = format!("http://{}", listener.local_addr().expect("addr"));
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream.try_clone().expect("clone"));
+                let mut head = String::new();
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read");
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    head.push_str(&line);
+                }
+                requests.push(head);
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .expect("write");
+            }
+            requests
+        });
+        (base, handle)
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn remote_receipt_parses_fragment_options() {
+        let r = RemoteReceipt::parse(
+            "https://ci.example/run/7/builddiag/report.json#sha256=AB12&sensor=bd",
+        )
+        .expect("parse");
+        assert_eq!(r.url, "https://ci.example/run/7/builddiag/report.json");
+        assert_eq!(r.sha256.as_deref(), Some("ab12"));
+        assert_eq!(r.sensor_id.as_deref(), Some("bd"));
+
+        let r = RemoteReceipt::parse("https://ci.example/run/7/builddiag/report.json?x=1").unwrap();
+        assert_eq!(r.resolved_sensor_id(), "builddiag");
+
+        assert!(RemoteReceipt::parse("ftp://ci.example/report.json").is_err());
+        assert!(RemoteReceipt::parse("https://ci.example/report.json#md5=00").is_err());
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_receipt_source_fetches_with_headers() {
+        let (base, server) = serve(vec![
+            ("200 OK", valid_receipt_json().to_string()),
+            ("404 Not Found", String::new()),
+        ]);
+        let source = HttpReceiptSource::new(vec![
+            RemoteReceipt::new(format!("{base}/builddiag/report.json")),
+            RemoteReceipt::new(format!("{base}/depguard/report.json")),
+        ])
+        .with_header("Authorization", "Bearer secret");
+
+        let loaded = source.load_receipts().expect("load");
+        let requests = server.join().expect("server");
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded[0].sensor_id, "builddiag");
+        assert!(loaded[0].receipt.is_ok());
+        assert_eq!(loaded[1].sensor_id, "depguard");
+        let err = loaded[1].receipt.as_ref().expect_err("404");
+        assert!(err.to_string().contains("HTTP 404"));
+        assert!(
+            requests
+                .iter()
+                .all(|r| r.contains("Authorization: Bearer secret"))
+        );
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_receipt_source_verifies_checksum() {
+        let body = valid_receipt_json().to_string();
+        let good = buildfix_hash::sha256_hex(body.as_bytes());
+        let (base, server) = serve(vec![("200 OK", body.clone()), ("200 OK", body)]);
+
+        let mut remote = RemoteReceipt::new(format!("{base}/builddiag/report.json"));
+        remote.sha256 = Some(good);
+        let loaded = HttpReceiptSource::new(vec![remote.clone()])
+            .load_receipts()
+            .expect("matching checksum");
+        assert!(loaded[0].receipt.is_ok());
+
+        remote.sha256 = Some("00".repeat(32));
+        let err = HttpReceiptSource::new(vec![remote])
+            .load_receipts()
+            .expect_err("mismatch");
+        assert!(err.to_string().contains("checksum mismatch"));
+        server.join().expect("server");
+    }
+
+    #[cfg(feature = "http")]
+    #[test]
+    fn http_receipt_source_skips_reserved_sensors() {
+        let source = HttpReceiptSource::new(vec![RemoteReceipt::new(
+            "http://127.0.0.1:9/artifacts/buildfix/report.json",
+        )]);
+        assert!(source.load_receipts().expect("load").is_empty());
+    }
+
+    #[cfg(feature = "object-store")]
+    fn file_url(dir: &Utf8Path) -> String {
+        url::Url::from_directory_path(dir.as_std_path())
+            .expect("file url")
+            .to_string()
+    }
+
+    #[cfg(feature = "object-store")]
+    #[test]
+    fn object_store_receipt_source_lists_sensor_reports() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        for (dir, body) in [
+            ("depguard", valid_receipt_json()),
+            ("builddiag", valid_receipt_json()),
+            ("broken", "{ nope"),
+            ("buildfix", valid_receipt_json()),
+        ] {
+            std::fs::create_dir_all(root.join(dir)).expect("mkdir");
+            std::fs::write(root.join(dir).join("report.json"), body).expect("write");
+        }
+        std::fs::create_dir_all(root.join("builddiag/nested")).expect("mkdir");
+        std::fs::write(root.join("builddiag/nested/report.json"), "{}").expect("write");
+        std::fs::write(root.join("builddiag/notes.json"), "{}").expect("write");
+
+        let url = file_url(&root);
+        let source = ObjectStoreReceiptSource::new(url.clone());
+        let loaded = source.load_receipts().expect("load");
+
+        let sensors: Vec<&str> = loaded.iter().map(|r| r.sensor_id.as_str()).collect();
+        assert_eq!(sensors, vec!["broken", "builddiag", "depguard"]);
+        assert!(loaded[0].receipt.is_err());
+        assert!(loaded[1].receipt.is_ok());
+        assert_eq!(
+            loaded[1].path.as_str(),
+            format!("{}/builddiag/report.json", url.trim_end_matches('/'))
+        );
+        assert!(source.load_cargo_metadata().expect("metadata").is_none());
+
+        std::fs::write(root.join(CARGO_METADATA_FILE), "{}").expect("write");
+        assert_eq!(
+            source.load_cargo_metadata().expect("metadata").as_deref(),
+            Some("{}")
+        );
+    }
+
+    #[cfg(feature = "object-store")]
+    #[test]
+    fn object_store_receipt_source_rejects_bad_url() {
+        let source = ObjectStoreReceiptSource::new("not a url");
+        assert!(source.load_receipts().is_err());
+    }
 }
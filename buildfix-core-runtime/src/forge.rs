@@ -0,0 +1,1154 @@
+//! [`ForgePort`] adapters over the GitHub, GitLab and Bitbucket Cloud REST
+//! APIs.
+//!
+//! Each adapter covers only what proposing applied changes needs: the
+//! default branch, opening or updating the pull (merge) request for a head
+//! branch, labels where the forge has them, and a commit check on GitHub.
+//! Requests are authenticated with a caller-supplied token and never
+//! retried.
+
+use crate::ports::{
+    ChangeRequest, ChangeRequestRef, CheckConclusion, CommentRef, CommitCheck, ForgePort,
+};
+use anyhow::Context;
+use serde_json::{Value, json};
+use std::time::Duration;
+
+/// GitHub caps check-run `summary` and `text` at 65535 characters.
+const CHECK_RUN_TEXT_LIMIT: usize = 65_535;
+
+/// GitHub truncates commit status descriptions past 140 characters.
+const STATUS_DESCRIPTION_LIMIT: usize = 140;
+
+const TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Items requested per page when listing comments.
+const PAGE_SIZE: usize = 100;
+
+/// Supported forges, with the CI conventions used to find their defaults.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ForgeKind {
+    Github,
+    Gitlab,
+    Bitbucket,
+}
+
+impl ForgeKind {
+    pub fn name(self) -> &'static str {
+        match self {
+            Self::Github => "github",
+            Self::Gitlab => "gitlab",
+            Self::Bitbucket => "bitbucket",
+        }
+    }
+
+    pub fn default_api_url(self) -> &'static str {
+        match self {
+            Self::Github => "https://api.github.com",
+            Self::Gitlab => "https://gitlab.com/api/v4",
+            Self::Bitbucket => "https://api.bitbucket.org/2.0",
+        }
+    }
+
+    /// Environment variables holding a token, in lookup order.
+    pub fn token_env_vars(self) -> &'static [&'static str] {
+        match self {
+            Self::Github => &["GITHUB_TOKEN", "GH_TOKEN"],
+            Self::Gitlab => &["GITLAB_TOKEN"],
+            Self::Bitbucket => &["BITBUCKET_TOKEN"],
+        }
+    }
+
+    /// Environment variable CI sets to the repository path.
+    pub fn repo_env_var(self) -> &'static str {
+        match self {
+            Self::Github => "GITHUB_REPOSITORY",
+            Self::Gitlab => "CI_PROJECT_PATH",
+            Self::Bitbucket => "BITBUCKET_REPO_FULL_NAME",
+        }
+    }
+
+    /// Environment variable CI sets to the API root, if the forge has one.
+    pub fn api_url_env_var(self) -> Option<&'static str> {
+        match self {
+            Self::Github => Some("GITHUB_API_URL"),
+            Self::Gitlab => Some("CI_API_V4_URL"),
+            Self::Bitbucket => None,
+        }
+    }
+
+    /// The forge a remote URL points at, judged by its host.
+    pub fn from_remote_url(url: &str) -> Option<Self> {
+        let host = remote_host(url)?.to_ascii_lowercase();
+        if host.contains("github") {
+            Some(Self::Github)
+        } else if host.contains("gitlab") {
+            Some(Self::Gitlab)
+        } else if host.contains("bitbucket") {
+            Some(Self::Bitbucket)
+        } else {
+            None
+        }
+    }
+
+    /// An adapter for `repo` (`owner/name`, or a GitLab project path).
+    pub fn connect(self, api_url: &str, repo: &str, token: &str) -> Box<dyn ForgePort> {
+        match self {
+            Self::Github => Box::new(GithubForge::new(api_url, repo, token)),
+            Self::Gitlab => Box::new(GitlabForge::new(api_url, repo, token)),
+            Self::Bitbucket => Box::new(BitbucketForge::new(api_url, repo, token)),
+        }
+    }
+}
+
+/// Repository path from a remote URL (`https://host/o/n.git`,
+/// `git@host:o/n.git`, `ssh://git@host/group/sub/n`), without `.git`.
+pub fn repo_path_from_remote_url(url: &str) -> Option<String> {
+    let path = match url.split_once("://") {
+        Some((_, rest)) => rest.split_once('/')?.1,
+        None => url.split_once(':')?.1,
+    };
+    let path = path.trim_matches('/').trim_end_matches(".git");
+    let (owner, name) = path.rsplit_once('/')?;
+    (!owner.is_empty() && !name.is_empty()).then(|| path.to_string())
+}
+
+fn remote_host(url: &str) -> Option<&str> {
+    let authority = match url.split_once("://") {
+        Some((_, rest)) => rest.split('/').next()?,
+        None => url.split_once(':')?.0,
+    };
+    let host = authority.rsplit('@').next()?;
+    Some(host.split(':').next().unwrap_or(host))
+}
+
+/// GitHub pull requests, labels and check runs.
+pub struct GithubForge {
+    api: Api,
+    /// `owner/name`.
+    repo: String,
+}
+
+impl GithubForge {
+    pub fn new(api_url: &str, repo: &str, token: &str) -> Self {
+        Self {
+            api: Api::new(
+                api_url,
+                vec![
+                    ("Authorization", format!("Bearer {}", token)),
+                    ("Accept", "application/vnd.github+json".to_string()),
+                    ("X-GitHub-Api-Version", "2022-11-28".to_string()),
+                ],
+            ),
+            repo: repo.to_string(),
+        }
+    }
+}
+
+impl ForgePort for GithubForge {
+    fn name(&self) -> &'static str {
+        ForgeKind::Github.name()
+    }
+
+    fn default_branch(&self) -> anyhow::Result<String> {
+        let repo = self
+            .api
+            .send("GET", &format!("/repos/{}", self.repo), None)?;
+        string_at(&repo, "/default_branch")
+    }
+
+    fn upsert_change_request(&self, request: &ChangeRequest) -> anyhow::Result<ChangeRequestRef> {
+        let owner = self.repo.split('/').next().unwrap_or_default();
+        let open = self.api.send(
+            "GET",
+            &format!(
+                "/repos/{}/pulls?state=open&head={}&base={}",
+                self.repo,
+                encode(&format!("{}:{}", owner, request.head)),
+                encode(&request.base)
+            ),
+            None,
+        )?;
+
+        let (pr, created) = match open.as_array().and_then(|prs| prs.first()) {
+            Some(pr) => {
+                let number = number_at(pr, "/number")?;
+                let updated = self.api.send(
+                    "PATCH",
+                    &format!("/repos/{}/pulls/{}", self.repo, number),
+                    Some(json!({ "title": request.title, "body": request.body })),
+                )?;
+                (updated, false)
+            }
+            None => {
+                let opened = self.api.send(
+                    "POST",
+                    &format!("/repos/{}/pulls", self.repo),
+                    Some(json!({
+                        "title": request.title,
+                        "head": request.head,
+                        "base": request.base,
+                        "body": request.body,
+                    })),
+                )?;
+                (opened, true)
+            }
+        };
+
+        Ok(ChangeRequestRef {
+            number: number_at(&pr, "/number")?,
+            url: pr["html_url"].as_str().unwrap_or_default().to_string(),
+            created,
+        })
+    }
+
+    fn upsert_comment(&self, number: u64, marker: &str, body: &str) -> anyhow::Result<CommentRef> {
+        let existing = find_marked(
+            |page| {
+                self.api.send(
+                    "GET",
+                    &format!(
+                        "/repos/{}/issues/{}/comments?per_page={}&page={}",
+                        self.repo, number, PAGE_SIZE, page
+                    ),
+                    None,
+                )
+            },
+            "",
+            "/body",
+            marker,
+            ("/user/login", || self.api.current_user("/login")),
+        )?;
+        let payload = json!({ "body": marked(marker, body) });
+        let comment = match existing {
+            Some(id) => self.api.send(
+                "PATCH",
+                &format!("/repos/{}/issues/comments/{}", self.repo, id),
+                Some(payload),
+            )?,
+            None => self.api.send(
+                "POST",
+                &format!("/repos/{}/issues/{}/comments", self.repo, number),
+                Some(payload),
+            )?,
+        };
+        Ok(CommentRef {
+            id: number_at(&comment, "/id")?,
+            url: comment["html_url"].as_str().map(str::to_string),
+            created: existing.is_none(),
+        })
+    }
+
+    fn add_labels(&self, number: u64, labels: &[String]) -> anyhow::Result<bool> {
+        if !labels.is_empty() {
+            self.api.send(
+                "POST",
+                &format!("/repos/{}/issues/{}/labels", self.repo, number),
+                Some(json!({ "labels": labels })),
+            )?;
+        }
+        Ok(true)
+    }
+
+    fn publish_check(&self, check: &CommitCheck) -> anyhow::Result<Option<String>> {
+        let mut output = json!({
+            "title": check.title,
+            "summary": truncate(&check.summary, CHECK_RUN_TEXT_LIMIT),
+        });
+        if let Some(text) = &check.text {
+            output["text"] = json!(truncate(text, CHECK_RUN_TEXT_LIMIT));
+        }
+        let mut run = json!({
+            "name": check.name,
+            "head_sha": check.head_sha,
+            "status": "completed",
+            "conclusion": check.conclusion.as_str(),
+            "output": output,
+        });
+        if let Some(url) = &check.details_url {
+            run["details_url"] = json!(url);
+        }
+        let created = self.api.send(
+            "POST",
+            &format!("/repos/{}/check-runs", self.repo),
+            Some(run),
+        )?;
+        Ok(created["html_url"].as_str().map(str::to_string))
+    }
+
+    fn publish_status(&self, check: &CommitCheck) -> anyhow::Result<bool> {
+        let state = match check.conclusion {
+            CheckConclusion::Failure => "failure",
+            _ => "success",
+        };
+        self.api.send(
+            "POST",
+            &format!("/repos/{}/statuses/{}", self.repo, check.head_sha),
+            Some(json!({
+                "state": state,
+                "context": check.name,
+                "description": truncate(&check.title, STATUS_DESCRIPTION_LIMIT),
+                "target_url": check.details_url,
+            })),
+        )?;
+        Ok(true)
+    }
+}
+
+/// GitLab merge requests and labels.
+pub struct GitlabForge {
+    api: Api,
+    /// URL-encoded project path, usable as `:id`.
+    project: String,
+}
+
+impl GitlabForge {
+    pub fn new(api_url: &str, project_path: &str, token: &str) -> Self {
+        Self {
+            api: Api::new(api_url, vec![("PRIVATE-TOKEN", token.to_string())]),
+            project: encode(project_path),
+        }
+    }
+}
+
+impl ForgePort for GitlabForge {
+    fn name(&self) -> &'static str {
+        ForgeKind::Gitlab.name()
+    }
+
+    fn default_branch(&self) -> anyhow::Result<String> {
+        let project = self
+            .api
+            .send("GET", &format!("/projects/{}", self.project), None)?;
+        string_at(&project, "/default_branch")
+    }
+
+    fn upsert_change_request(&self, request: &ChangeRequest) -> anyhow::Result<ChangeRequestRef> {
+        let open = self.api.send(
+            "GET",
+            &format!(
+                "/projects/{}/merge_requests?state=opened&source_branch={}&target_branch={}",
+                self.project,
+                encode(&request.head),
+                encode(&request.base)
+            ),
+            None,
+        )?;
+
+        let (mr, created) = match open.as_array().and_then(|mrs| mrs.first()) {
+            Some(mr) => {
+                let iid = number_at(mr, "/iid")?;
+                let updated = self.api.send(
+                    "PUT",
+                    &format!("/projects/{}/merge_requests/{}", self.project, iid),
+                    Some(json!({ "title": request.title, "description": request.body })),
+                )?;
+                (updated, false)
+            }
+            None => {
+                let opened = self.api.send(
+                    "POST",
+                    &format!("/projects/{}/merge_requests", self.project),
+                    Some(json!({
+                        "source_branch": request.head,
+                        "target_branch": request.base,
+                        "title": request.title,
+                        "description": request.body,
+                    })),
+                )?;
+                (opened, true)
+            }
+        };
+
+        Ok(ChangeRequestRef {
+            number: number_at(&mr, "/iid")?,
+            url: mr["web_url"].as_str().unwrap_or_default().to_string(),
+            created,
+        })
+    }
+
+    fn upsert_comment(&self, number: u64, marker: &str, body: &str) -> anyhow::Result<CommentRef> {
+        let notes = format!("/projects/{}/merge_requests/{}/notes", self.project, number);
+        let existing = find_marked(
+            |page| {
+                self.api.send(
+                    "GET",
+                    &format!("{}?sort=asc&per_page={}&page={}", notes, PAGE_SIZE, page),
+                    None,
+                )
+            },
+            "",
+            "/body",
+            marker,
+            ("/author/id", || self.api.current_user("/id")),
+        )?;
+        let payload = json!({ "body": marked(marker, body) });
+        let note = match existing {
+            Some(id) => self
+                .api
+                .send("PUT", &format!("{}/{}", notes, id), Some(payload))?,
+            None => self.api.send("POST", &notes, Some(payload))?,
+        };
+        Ok(CommentRef {
+            id: number_at(&note, "/id")?,
+            url: None,
+            created: existing.is_none(),
+        })
+    }
+
+    fn add_labels(&self, number: u64, labels: &[String]) -> anyhow::Result<bool> {
+        if !labels.is_empty() {
+            self.api.send(
+                "PUT",
+                &format!("/projects/{}/merge_requests/{}", self.project, number),
+                Some(json!({ "add_labels": labels.join(",") })),
+            )?;
+        }
+        Ok(true)
+    }
+
+    fn publish_status(&self, check: &CommitCheck) -> anyhow::Result<bool> {
+        let state = match check.conclusion {
+            CheckConclusion::Success | CheckConclusion::Neutral => "success",
+            CheckConclusion::Failure => "failed",
+            CheckConclusion::Skipped => "skipped",
+        };
+        self.api.send(
+            "POST",
+            &format!("/projects/{}/statuses/{}", self.project, check.head_sha),
+            Some(json!({
+                "state": state,
+                "name": check.name,
+                "description": truncate(&check.title, STATUS_DESCRIPTION_LIMIT),
+                "target_url": check.details_url,
+            })),
+        )?;
+        Ok(true)
+    }
+}
+
+/// Bitbucket Cloud pull requests. Bitbucket has no labels.
+pub struct BitbucketForge {
+    api: Api,
+    /// `workspace/repo_slug`.
+    repo: String,
+}
+
+impl BitbucketForge {
+    pub fn new(api_url: &str, repo: &str, token: &str) -> Self {
+        Self {
+            api: Api::new(
+                api_url,
+                vec![("Authorization", format!("Bearer {}", token))],
+            ),
+            repo: repo.to_string(),
+        }
+    }
+}
+
+impl ForgePort for BitbucketForge {
+    fn name(&self) -> &'static str {
+        ForgeKind::Bitbucket.name()
+    }
+
+    fn default_branch(&self) -> anyhow::Result<String> {
+        let repo = self
+            .api
+            .send("GET", &format!("/repositories/{}", self.repo), None)?;
+        string_at(&repo, "/mainbranch/name")
+    }
+
+    fn upsert_change_request(&self, request: &ChangeRequest) -> anyhow::Result<ChangeRequestRef> {
+        let query = format!(
+            "source.branch.name=\"{}\" AND destination.branch.name=\"{}\"",
+            request.head, request.base
+        );
+        let open = self.api.send(
+            "GET",
+            &format!(
+                "/repositories/{}/pullrequests?state=OPEN&q={}",
+                self.repo,
+                encode(&query)
+            ),
+            None,
+        )?;
+
+        let (pr, created) = match open["values"].as_array().and_then(|prs| prs.first()) {
+            Some(pr) => {
+                let id = number_at(pr, "/id")?;
+                let updated = self.api.send(
+                    "PUT",
+                    &format!("/repositories/{}/pullrequests/{}", self.repo, id),
+                    Some(json!({ "title": request.title, "description": request.body })),
+                )?;
+                (updated, false)
+            }
+            None => {
+                let opened = self.api.send(
+                    "POST",
+                    &format!("/repositories/{}/pullrequests", self.repo),
+                    Some(json!({
+                        "title": request.title,
+                        "description": request.body,
+                        "source": { "branch": { "name": request.head } },
+                        "destination": { "branch": { "name": request.base } },
+                    })),
+                )?;
+                (opened, true)
+            }
+        };
+
+        Ok(ChangeRequestRef {
+            number: number_at(&pr, "/id")?,
+            url: pr
+                .pointer("/links/html/href")
+                .and_then(Value::as_str)
+                .unwrap_or_default()
+                .to_string(),
+            created,
+        })
+    }
+
+    fn upsert_comment(&self, number: u64, marker: &str, body: &str) -> anyhow::Result<CommentRef> {
+        let comments = format!(
+            "/repositories/{}/pullrequests/{}/comments",
+            self.repo, number
+        );
+        let existing = find_marked(
+            |page| {
+                self.api.send(
+                    "GET",
+                    &format!("{}?pagelen={}&page={}", comments, PAGE_SIZE, page),
+                    None,
+                )
+            },
+            "/values",
+            "/content/raw",
+            marker,
+            ("/user/uuid", || self.api.current_user("/uuid")),
+        )?;
+        let payload = json!({ "content": { "raw": marked(marker, body) } });
+        let comment = match existing {
+            Some(id) => self
+                .api
+                .send("PUT", &format!("{}/{}", comments, id), Some(payload))?,
+            None => self.api.send("POST", &comments, Some(payload))?,
+        };
+        Ok(CommentRef {
+            id: number_at(&comment, "/id")?,
+            url: comment
+                .pointer("/links/html/href")
+                .and_then(Value::as_str)
+                .map(str::to_string),
+            created: existing.is_none(),
+        })
+    }
+
+    /// A build status, keyed by `check.name`. Bitbucket requires a link, so
+    /// without `details_url` it points at the commit.
+    fn publish_status(&self, check: &CommitCheck) -> anyhow::Result<bool> {
+        let state = match check.conclusion {
+            CheckConclusion::Success | CheckConclusion::Neutral => "SUCCESSFUL",
+            CheckConclusion::Failure => "FAILED",
+            CheckConclusion::Skipped => "STOPPED",
+        };
+        let url = check.details_url.clone().unwrap_or_else(|| {
+            format!(
+                "https://bitbucket.org/{}/commits/{}",
+                self.repo, check.head_sha
+            )
+        });
+        self.api.send(
+            "POST",
+            &format!(
+                "/repositories/{}/commit/{}/statuses/build",
+                self.repo, check.head_sha
+            ),
+            Some(json!({
+                "key": check.name,
+                "name": check.name,
+                "state": state,
+                "description": check.title,
+                "url": url,
+            })),
+        )?;
+        Ok(true)
+    }
+}
+
+/// JSON-over-HTTPS with fixed headers.
+struct Api {
+    base: String,
+    headers: Vec<(&'static str, String)>,
+    agent: ureq::Agent,
+}
+
+impl Api {
+    fn new(base: &str, headers: Vec<(&'static str, String)>) -> Self {
+        Self {
+            base: base.trim_end_matches('/').to_string(),
+            headers,
+            agent: ureq::AgentBuilder::new().timeout(TIMEOUT).build(),
+        }
+    }
+
+    fn send(&self, method: &str, path: &str, body: Option<Value>) -> anyhow::Result<Value> {
+        let url = format!("{}{}", self.base, path);
+        let mut request = self.agent.request(method, &url).set(
+            "User-Agent",
+            concat!("buildfix/", env!("CARGO_PKG_VERSION")),
+        );
+        for (name, value) in &self.headers {
+            request = request.set(name, value);
+        }
+        let result = match body {
+            Some(body) => request
+                .set("Content-Type", "application/json")
+                .send_string(&body.to_string()),
+            None => request.call(),
+        };
+        let response = match result {
+            Ok(response) => response,
+            Err(ureq::Error::Status(code, response)) => {
+                let detail = response.into_string().unwrap_or_default();
+                anyhow::bail!(
+                    "{} {} returned HTTP {}: {}",
+                    method,
+                    url,
+                    code,
+                    error_message(&detail)
+                );
+            }
+            Err(err) => anyhow::bail!("{} {}: {}", method, url, err),
+        };
+        let text = response
+            .into_string()
+            .with_context(|| format!("read response of {} {}", method, url))?;
+        if text.trim().is_empty() {
+            return Ok(Value::Null);
+        }
+        serde_json::from_str(&text).with_context(|| format!("parse response of {} {}", method, url))
+    }
+
+    /// `field` of the token's own user (`GET /user`), or `None` when the
+    /// token cannot look itself up.
+    fn current_user(&self, field: &str) -> Option<Value> {
+        let user = self.send("GET", "/user", None).ok()?;
+        user.pointer(field).filter(|v| !v.is_null()).cloned()
+    }
+}
+
+/// The human part of an error body: GitHub and GitLab `message`, Bitbucket
+/// `error.message`, else the body itself.
+fn error_message(body: &str) -> String {
+    let Ok(value) = serde_json::from_str::<Value>(body) else {
+        return body.trim().to_string();
+    };
+    match (&value["message"], &value["error"]) {
+        (Value::String(message), _) => message.clone(),
+        (Value::Null, Value::Object(error)) => error
+            .get("message")
+            .and_then(Value::as_str)
+            .map_or_else(|| value["error"].to_string(), str::to_string),
+        (Value::Null, Value::String(error)) => error.clone(),
+        (Value::Null, _) => body.trim().to_string(),
+        (message, _) => message.to_string(),
+    }
+}
+
+/// Id of the first listed comment whose text (at `text` in each item)
+/// starts with `marker` and that was written by the token's own user.
+/// Fetches `list(1)`, `list(2)`, … until a short page; `items` points at the
+/// array in each page (`""` when the page is one).
+///
+/// `author` pairs the pointer to a comment's author with a lookup of the
+/// token's user, made once a marked comment is seen. When the forge cannot
+/// name that user (app and repository tokens), any author matches.
+fn find_marked(
+    mut list: impl FnMut(usize) -> anyhow::Result<Value>,
+    items: &str,
+    text: &str,
+    marker: &str,
+    author: (&str, impl FnOnce() -> Option<Value>),
+) -> anyhow::Result<Option<u64>> {
+    let (author, current_user) = author;
+    let mut current_user = Some(current_user);
+    let mut me: Option<Value> = None;
+    for page in 1.. {
+        let listed = list(page)?;
+        let listed = listed
+            .pointer(items)
+            .and_then(Value::as_array)
+            .map(Vec::as_slice)
+            .unwrap_or_default();
+        let found = listed.iter().find(|item| {
+            let is_marked = item
+                .pointer(text)
+                .and_then(Value::as_str)
+                .is_some_and(|t| t.starts_with(marker));
+            if !is_marked {
+                return false;
+            }
+            if let Some(lookup) = current_user.take() {
+                me = lookup();
+            }
+            me.as_ref()
+                .is_none_or(|me| item.pointer(author) == Some(me))
+        });
+        if let Some(item) = found {
+            return number_at(item, "/id").map(Some);
+        }
+        if listed.len() < PAGE_SIZE {
+            break;
+        }
+    }
+    Ok(None)
+}
+
+/// Comment text carrying `marker` on its first line.
+fn marked(marker: &str, body: &str) -> String {
+    format!("{}\n{}", marker, body)
+}
+
+fn string_at(value: &Value, pointer: &str) -> anyhow::Result<String> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_str)
+        .map(str::to_string)
+        .with_context(|| format!("response has no {}", pointer))
+}
+
+fn number_at(value: &Value, pointer: &str) -> anyhow::Result<u64> {
+    value
+        .pointer(pointer)
+        .and_then(Value::as_u64)
+        .with_context(|| format!("response has no {}", pointer))
+}
+
+/// At most `limit` characters of `text`, marking the cut.
+fn truncate(text: &str, limit: usize) -> String {
+    const MARK: &str = "\n\n… (truncated)";
+    if text.chars().count() <= limit {
+        return text.to_string();
+    }
+    let keep = limit - MARK.chars().count();
+    let mut out: String = text.chars().take(keep).collect();
+    out.push_str(MARK);
+    out
+}
+
+/// Percent-encode everything but RFC 3986 unreserved characters.
+fn encode(value: &str) -> String {
+    let mut out = String::new();
+    for b in value.bytes() {
+        if b.is_ascii_alphanumeric() || matches!(b, b'-' | b'_' | b'.' | b'~') {
+            out.push(b as char);
+        } else {
+            out.push_str(&format!("%{:02X}", b));
+        }
+    }
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// One recorded request: request line plus body.
+    type Recorded = (String, String);
+
+    /// Answer each connection with the next canned `(status, body)` and
+    /// hand back what was requested.
+    fn serve(
+        responses: Vec<(&'static str, &'static str)>,
+    ) -> (String, std::thread::JoinHandle<Vec<Recorded>>) {
+        use std::io::{BufRead, BufReader, Read, Write};
+
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").expect("bind");
+        let base = format!("http://{}", listener.local_addr().expect("addr"));
+        let handle = std::thread::spawn(move || {
+            let mut requests = Vec::new();
+            for (status, body) in responses {
+                let (mut stream, _) = listener.accept().expect("accept");
+                let mut reader = BufReader::new(stream.try_clone().expect("clone"));
+                let mut request_line = String::new();
+                reader.read_line(&mut request_line).expect("read");
+                let mut content_length = 0;
+                loop {
+                    let mut line = String::new();
+                    reader.read_line(&mut line).expect("read");
+                    if line == "\r\n" || line.is_empty() {
+                        break;
+                    }
+                    if let Some((name, value)) = line.split_once(':')
+                        && name.eq_ignore_ascii_case("content-length")
+                    {
+                        content_length = value.trim().parse().expect("length");
+                    }
+                }
+                let mut request_body = vec![0; content_length];
+                reader.read_exact(&mut request_body).expect("body");
+                requests.push((
+                    request_line.trim().to_string(),
+                    String::from_utf8(request_body).expect("utf8"),
+                ));
+                write!(
+                    stream,
+                    "HTTP/1.1 {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                    status,
+                    body.len(),
+                    body
+                )
+                .expect("write");
+            }
+            requests
+        });
+        (base, handle)
+    }
+
+    fn request(head: &str) -> ChangeRequest {
+        ChangeRequest {
+            head: head.to_string(),
+            base: "main".to_string(),
+            title: "Fix".to_string(),
+            body: "body".to_string(),
+        }
+    }
+
+    #[test]
+    fn github_opens_a_pull_request_when_none_is_open() {
+        let (base, handle) = serve(vec![
+            ("200 OK", "[]"),
+            (
+                "201 Created",
+                r#"{"number":7,"html_url":"https://github.com/o/r/pull/7"}"#,
+            ),
+        ]);
+        let forge = GithubForge::new(&base, "o/r", "t");
+        let pr = forge
+            .upsert_change_request(&request("buildfix/x"))
+            .expect("upsert");
+        assert_eq!(
+            pr,
+            ChangeRequestRef {
+                number: 7,
+                url: "https://github.com/o/r/pull/7".to_string(),
+                created: true,
+            }
+        );
+
+        let requests = handle.join().expect("server");
+        assert_eq!(
+            requests[0].0,
+            "GET /repos/o/r/pulls?state=open&head=o%3Abuildfix%2Fx&base=main HTTP/1.1"
+        );
+        assert_eq!(requests[1].0, "POST /repos/o/r/pulls HTTP/1.1");
+        let body: Value = serde_json::from_str(&requests[1].1).expect("json");
+        assert_eq!(body["head"], "buildfix/x");
+        assert_eq!(body["base"], "main");
+        assert_eq!(body["body"], "body");
+    }
+
+    #[test]
+    fn github_updates_the_open_pull_request() {
+        let (base, handle) = serve(vec![
+            ("200 OK", r#"[{"number":3}]"#),
+            (
+                "200 OK",
+                r#"{"number":3,"html_url":"https://github.com/o/r/pull/3"}"#,
+            ),
+        ]);
+        let forge = GithubForge::new(&base, "o/r", "t");
+        let pr = forge.upsert_change_request(&request("b")).expect("upsert");
+        assert_eq!(pr.number, 3);
+        assert!(!pr.created);
+        let requests = handle.join().expect("server");
+        assert_eq!(requests[1].0, "PATCH /repos/o/r/pulls/3 HTTP/1.1");
+    }
+
+    #[test]
+    fn gitlab_opens_a_merge_request_and_adds_labels() {
+        let (base, handle) = serve(vec![
+            ("200 OK", "[]"),
+            (
+                "201 Created",
+                r#"{"iid":4,"web_url":"https://gitlab.com/g/s/p/-/merge_requests/4"}"#,
+            ),
+            ("200 OK", r#"{"iid":4}"#),
+        ]);
+        let forge = GitlabForge::new(&base, "g/s/p", "t");
+        let mr = forge
+            .upsert_change_request(&request("buildfix/x"))
+            .expect("upsert");
+        assert_eq!(mr.number, 4);
+        assert!(mr.created);
+        assert!(
+            forge
+                .add_labels(4, &["a".to_string(), "b".to_string()])
+                .expect("labels")
+        );
+
+        let requests = handle.join().expect("server");
+        assert_eq!(
+            requests[0].0,
+            "GET /projects/g%2Fs%2Fp/merge_requests?state=opened&source_branch=buildfix%2Fx&target_branch=main HTTP/1.1"
+        );
+        assert_eq!(
+            requests[1].0,
+            "POST /projects/g%2Fs%2Fp/merge_requests HTTP/1.1"
+        );
+        let body: Value = serde_json::from_str(&requests[1].1).expect("json");
+        assert_eq!(body["source_branch"], "buildfix/x");
+        assert_eq!(body["description"], "body");
+        assert_eq!(
+            requests[2].0,
+            "PUT /projects/g%2Fs%2Fp/merge_requests/4 HTTP/1.1"
+        );
+        assert_eq!(requests[2].1, r#"{"add_labels":"a,b"}"#);
+    }
+
+    #[test]
+    fn bitbucket_updates_the_open_pull_request() {
+        let (base, handle) = serve(vec![
+            ("200 OK", r#"{"values":[{"id":9}]}"#),
+            (
+                "200 OK",
+                r#"{"id":9,"links":{"html":{"href":"https://bitbucket.org/w/r/pull-requests/9"}}}"#,
+            ),
+        ]);
+        let forge = BitbucketForge::new(&base, "w/r", "t");
+        let pr = forge.upsert_change_request(&request("b")).expect("upsert");
+        assert_eq!(pr.number, 9);
+        assert_eq!(pr.url, "https://bitbucket.org/w/r/pull-requests/9");
+        assert!(!pr.created);
+        assert!(!forge.add_labels(9, &["a".to_string()]).expect("labels"));
+        assert!(
+            forge
+                .publish_check(&CommitCheck {
+                    name: "buildfix".to_string(),
+                    head_sha: "abc".to_string(),
+                    conclusion: CheckConclusion::Success,
+                    title: String::new(),
+                    summary: String::new(),
+                    text: None,
+                    details_url: None,
+                })
+                .expect("check")
+                .is_none()
+        );
+
+        let requests = handle.join().expect("server");
+        assert!(requests[0].0.starts_with(
+            "GET /repositories/w/r/pullrequests?state=OPEN&q=source.branch.name%3D%22b%22"
+        ));
+        assert_eq!(
+            requests[1].0,
+            "PUT /repositories/w/r/pullrequests/9 HTTP/1.1"
+        );
+    }
+
+    const MARKER: &str = "<!-- buildfix -->";
+
+    #[test]
+    fn github_edits_the_marked_comment() {
+        let (base, handle) = serve(vec![
+            (
+                "200 OK",
+                r#"[{"id":1,"body":"lgtm","user":{"login":"bot"}},
+                    {"id":2,"body":"quoting <!-- buildfix -->","user":{"login":"bot"}},
+                    {"id":3,"body":"<!-- buildfix -->\nforged","user":{"login":"someone"}},
+                    {"id":2,"body":"<!-- buildfix -->\nold","user":{"login":"bot"}}]"#,
+            ),
+            ("200 OK", r#"{"login":"bot"}"#),
+            (
+                "200 OK",
+                r#"{"id":2,"html_url":"https://github.com/o/r/pull/7#issuecomment-2"}"#,
+            ),
+        ]);
+        let forge = GithubForge::new(&base, "o/r", "t");
+        let comment = forge.upsert_comment(7, MARKER, "new").expect("upsert");
+        assert_eq!(
+            comment,
+            CommentRef {
+                id: 2,
+                url: Some("https://github.com/o/r/pull/7#issuecomment-2".to_string()),
+                created: false,
+            }
+        );
+
+        let requests = handle.join().expect("server");
+        assert_eq!(
+            requests[0].0,
+            "GET /repos/o/r/issues/7/comments?per_page=100&page=1 HTTP/1.1"
+        );
+        assert_eq!(requests[1].0, "GET /user HTTP/1.1");
+        assert_eq!(requests[2].0, "PATCH /repos/o/r/issues/comments/2 HTTP/1.1");
+        assert_eq!(requests[2].1, r#"{"body":"<!-- buildfix -->\nnew"}"#);
+    }
+
+    #[test]
+    fn gitlab_and_bitbucket_post_a_comment_when_none_is_marked() {
+        let (base, handle) = serve(vec![
+            ("200 OK", r#"[{"id":1,"body":"lgtm"}]"#),
+            ("201 Created", r#"{"id":5}"#),
+            ("200 OK", r#"{"values":[]}"#),
+            (
+                "201 Created",
+                r#"{"id":6,"links":{"html":{"href":"https://bitbucket.org/w/r/pull-requests/9#comment-6"}}}"#,
+            ),
+        ]);
+        let note = GitlabForge::new(&base, "g/p", "t")
+            .upsert_comment(4, MARKER, "summary")
+            .expect("gitlab");
+        assert_eq!((note.id, note.url, note.created), (5, None, true));
+        let comment = BitbucketForge::new(&base, "w/r", "t")
+            .upsert_comment(9, MARKER, "summary")
+            .expect("bitbucket");
+        assert_eq!(comment.id, 6);
+        assert!(comment.created);
+
+        let requests = handle.join().expect("server");
+        assert_eq!(
+            requests[0].0,
+            "GET /projects/g%2Fp/merge_requests/4/notes?sort=asc&per_page=100&page=1 HTTP/1.1"
+        );
+        assert_eq!(
+            requests[1].0,
+            "POST /projects/g%2Fp/merge_requests/4/notes HTTP/1.1"
+        );
+        assert_eq!(
+            requests[3].0,
+            "POST /repositories/w/r/pullrequests/9/comments HTTP/1.1"
+        );
+        assert_eq!(
+            requests[3].1,
+            r#"{"content":{"raw":"<!-- buildfix -->\nsummary"}}"#
+        );
+    }
+
+    fn check(conclusion: CheckConclusion) -> CommitCheck {
+        CommitCheck {
+            name: "buildfix".to_string(),
+            head_sha: "abc".to_string(),
+            conclusion,
+            title: "warn: 2 ops pending".to_string(),
+            summary: "# Plan".to_string(),
+            text: None,
+            details_url: Some("https://ci.example.com/1".to_string()),
+        }
+    }
+
+    #[test]
+    fn report_verdicts_map_to_checks_and_statuses() {
+        use buildfix_types::report::ReportStatus;
+
+        let (base, handle) = serve(vec![
+            (
+                "201 Created",
+                r#"{"html_url":"https://github.com/o/r/runs/1"}"#,
+            ),
+            ("201 Created", "{}"),
+            ("201 Created", "{}"),
+            ("201 Created", "{}"),
+        ]);
+        let github = GithubForge::new(&base, "o/r", "t");
+        let url = github
+            .publish_check(&check(ReportStatus::Warn.into()))
+            .expect("check run");
+        assert_eq!(url.as_deref(), Some("https://github.com/o/r/runs/1"));
+        assert!(
+            github
+                .publish_status(&check(ReportStatus::Warn.into()))
+                .expect("github status")
+        );
+        assert!(
+            GitlabForge::new(&base, "g/p", "t")
+                .publish_status(&check(ReportStatus::Fail.into()))
+                .expect("gitlab status")
+        );
+        assert!(
+            BitbucketForge::new(&base, "w/r", "t")
+                .publish_status(&check(ReportStatus::Skip.into()))
+                .expect("bitbucket status")
+        );
+
+        let requests = handle.join().expect("server");
+        assert_eq!(requests[0].0, "POST /repos/o/r/check-runs HTTP/1.1");
+        let run: Value = serde_json::from_str(&requests[0].1).expect("json");
+        assert_eq!(run["conclusion"], "neutral");
+        assert_eq!(run["output"]["summary"], "# Plan");
+        assert_eq!(run["details_url"], "https://ci.example.com/1");
+        assert_eq!(requests[1].0, "POST /repos/o/r/statuses/abc HTTP/1.1");
+        let status: Value = serde_json::from_str(&requests[1].1).expect("json");
+        assert_eq!(status["state"], "success");
+        assert_eq!(status["context"], "buildfix");
+        assert_eq!(requests[2].0, "POST /projects/g%2Fp/statuses/abc HTTP/1.1");
+        assert!(requests[2].1.contains(r#""state":"failed""#));
+        assert_eq!(
+            requests[3].0,
+            "POST /repositories/w/r/commit/abc/statuses/build HTTP/1.1"
+        );
+        assert!(requests[3].1.contains(r#""state":"STOPPED""#));
+    }
+
+    #[test]
+    fn http_errors_carry_the_forge_message() {
+        let (base, _handle) = serve(vec![
+            (
+                "422 Unprocessable Entity",
+                r#"{"message":"Validation Failed"}"#,
+            ),
+            ("400 Bad Request", r#"{"error":{"message":"bad branch"}}"#),
+        ]);
+        let err = GithubForge::new(&base, "o/r", "t")
+            .default_branch()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("HTTP 422: Validation Failed"), "{}", err);
+        let err = BitbucketForge::new(&base, "w/r", "t")
+            .default_branch()
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("HTTP 400: bad branch"), "{}", err);
+    }
+
+    #[test]
+    fn parses_remote_urls() {
+        for url in [
+            "https://github.com/o/r.git",
+            "https://github.com/o/r",
+            "git@github.com:o/r.git",
+            "ssh://git@github.com/o/r",
+        ] {
+            assert_eq!(
+                repo_path_from_remote_url(url).as_deref(),
+                Some("o/r"),
+                "{}",
+                url
+            );
+            assert_eq!(ForgeKind::from_remote_url(url), Some(ForgeKind::Github));
+        }
+        assert_eq!(
+            repo_path_from_remote_url("git@gitlab.com:g/sub/p.git").as_deref(),
+            Some("g/sub/p")
+        );
+        assert_eq!(
+            ForgeKind::from_remote_url("git@gitlab.example.com:g/p.git"),
+            Some(ForgeKind::Gitlab)
+        );
+        assert_eq!(
+            ForgeKind::from_remote_url("https://user@bitbucket.org/w/r.git"),
+            Some(ForgeKind::Bitbucket)
+        );
+        assert_eq!(ForgeKind::from_remote_url("https://example.com/o/r"), None);
+        assert_eq!(repo_path_from_remote_url("not a url"), None);
+    }
+
+    #[test]
+    fn truncates_long_check_run_text() {
+        assert_eq!(truncate("short", 10), "short");
+        let cut = truncate(&"x".repeat(100), 50);
+        assert_eq!(cut.chars().count(), 50);
+        assert!(cut.ends_with("(truncated)"));
+    }
+}
@@ -4,14 +4,38 @@
 //! wiring ports/adapters and execution policy configuration that are reused across host binaries.
 
 pub mod adapters;
+#[cfg(feature = "forge")]
+pub mod forge;
+pub mod metrics;
 pub mod ports;
 pub mod settings;
 
+#[cfg(feature = "async")]
+pub use adapters::Blocking;
+pub use adapters::CARGO_METADATA_FILE;
+#[cfg(feature = "gix")]
+pub use adapters::GixGitPort;
 #[cfg(feature = "memory")]
 pub use adapters::InMemoryReceiptSource;
+#[cfg(feature = "object-store")]
+pub use adapters::ObjectStoreReceiptSource;
 #[cfg(feature = "git")]
 pub use adapters::ShellGitPort;
+pub use adapters::{
+    FixedClock, NoopMetrics, PrometheusMetrics, RandomIds, SequentialIds, SystemClock,
+};
 #[cfg(feature = "fs")]
 pub use adapters::{FsReceiptSource, FsWritePort};
-pub use ports::{GitPort, ReceiptSource, WritePort};
-pub use settings::{ApplySettings, PlanSettings, RunMode};
+#[cfg(feature = "http")]
+pub use adapters::{HttpReceiptSource, RemoteReceipt};
+#[cfg(feature = "forge")]
+pub use forge::{BitbucketForge, ForgeKind, GithubForge, GitlabForge, repo_path_from_remote_url};
+#[cfg(feature = "async")]
+pub use ports::{AsyncGitPort, AsyncReceiptSource, AsyncWritePort};
+pub use ports::{
+    ChangeRequest, ChangeRequestRef, CheckConclusion, ClockPort, CommentRef, CommitCheck,
+    ForgePort, GitPort, IdPort, MetricsPort, ProgressPort, ReceiptSource, WritePort,
+};
+pub use settings::{
+    ApplySettings, CancellationToken, Clock, FixerSettings, Ids, Metrics, PlanSettings, RunMode,
+};
@@ -0,0 +1,245 @@
+//! Run metrics: the names buildfix records through a
+//! [`MetricsPort`], the [`Metrics`] handle carried in settings, and the
+//! no-op and Prometheus adapters.
+//!
+//! Names and labels follow Prometheus conventions and are stable: a metric
+//! is never renamed and a label never removed.
+
+use crate::ports::MetricsPort;
+use std::collections::BTreeMap;
+use std::fmt::{self, Write as _};
+use std::sync::{Arc, Mutex};
+
+/// Ops in each plan, by `fix_key`. Includes blocked ops.
+pub const OPS_PLANNED: &str = "buildfix_ops_planned_total";
+/// Blocked ops, by `fix_key` and `stage` (`plan` or `apply`).
+pub const OPS_BLOCKED: &str = "buildfix_ops_blocked_total";
+/// Ops written by an apply, by `fix_key`. Dry runs are not counted.
+pub const OPS_APPLIED: &str = "buildfix_ops_applied_total";
+/// Ops that failed during an apply, by `fix_key`. Dry runs are not counted.
+pub const OPS_FAILED: &str = "buildfix_ops_failed_total";
+/// Finished runs, by `command` (`plan` or `apply`) and `outcome` (the
+/// report verdict).
+pub const RUNS: &str = "buildfix_runs_total";
+/// Wall time of each plan run.
+pub const PLAN_DURATION: &str = "buildfix_plan_duration_seconds";
+/// Wall time of each apply run.
+pub const APPLY_DURATION: &str = "buildfix_apply_duration_seconds";
+/// Size of each plan's preview patch.
+pub const PATCH_BYTES: &str = "buildfix_patch_bytes";
+
+/// `# HELP` text for the metrics above.
+pub fn help(name: &str) -> Option<&'static str> {
+    Some(match name {
+        OPS_PLANNED => "Ops planned, by fixer.",
+        OPS_BLOCKED => "Ops blocked at plan or apply time, by fixer.",
+        OPS_APPLIED => "Ops written to disk, by fixer.",
+        OPS_FAILED => "Ops that failed to apply, by fixer.",
+        RUNS => "Finished plan and apply runs, by verdict.",
+        PLAN_DURATION => "Plan run wall time.",
+        APPLY_DURATION => "Apply run wall time.",
+        PATCH_BYTES => "Preview patch size.",
+        _ => return None,
+    })
+}
+
+/// Shared handle to a [`MetricsPort`], as carried in settings. Defaults to
+/// [`NoopMetrics`].
+#[derive(Clone)]
+pub struct Metrics(Arc<dyn MetricsPort>);
+
+impl Metrics {
+    pub fn new(port: impl MetricsPort + 'static) -> Self {
+        Self(Arc::new(port))
+    }
+
+    pub fn counter(&self, name: &str, labels: &[(&str, &str)], value: u64) {
+        self.0.counter(name, labels, value);
+    }
+
+    pub fn histogram(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        self.0.histogram(name, labels, value);
+    }
+}
+
+impl Default for Metrics {
+    fn default() -> Self {
+        Self::new(NoopMetrics)
+    }
+}
+
+impl fmt::Debug for Metrics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Metrics").finish_non_exhaustive()
+    }
+}
+
+/// Discards every metric.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct NoopMetrics;
+
+impl MetricsPort for NoopMetrics {
+    fn counter(&self, _name: &str, _labels: &[(&str, &str)], _value: u64) {}
+
+    fn histogram(&self, _name: &str, _labels: &[(&str, &str)], _value: f64) {}
+}
+
+/// Upper bounds for `*_seconds` histograms.
+const SECONDS_BUCKETS: [f64; 11] = [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0, 60.0];
+/// Upper bounds for `*_bytes` histograms.
+const BYTES_BUCKETS: [f64; 7] = [256.0, 1024.0, 4096.0, 16384.0, 65536.0, 262144.0, 1048576.0];
+/// Prometheus client defaults, for any other histogram.
+const DEFAULT_BUCKETS: [f64; 11] = [
+    0.005, 0.01, 0.025, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0,
+];
+
+fn buckets(name: &str) -> &'static [f64] {
+    if name.ends_with("_seconds") {
+        &SECONDS_BUCKETS
+    } else if name.ends_with("_bytes") {
+        &BYTES_BUCKETS
+    } else {
+        &DEFAULT_BUCKETS
+    }
+}
+
+type Labels = Vec<(String, String)>;
+
+#[derive(Debug, Default)]
+struct Histogram {
+    /// Observations at or below each of the name's bucket bounds.
+    buckets: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+#[derive(Debug, Default)]
+struct Registry {
+    counters: BTreeMap<String, BTreeMap<Labels, u64>>,
+    histograms: BTreeMap<String, BTreeMap<Labels, Histogram>>,
+}
+
+/// Accumulates metrics in memory and renders them in the Prometheus text
+/// exposition format, e.g. for node_exporter's textfile collector or a
+/// host's `/metrics` endpoint. Series are rendered sorted by name and labels.
+#[derive(Debug, Default)]
+pub struct PrometheusMetrics {
+    registry: Mutex<Registry>,
+}
+
+impl PrometheusMetrics {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Every series recorded so far.
+    pub fn render(&self) -> String {
+        let registry = self.registry.lock().unwrap_or_else(|e| e.into_inner());
+        let mut out = String::new();
+        for (name, series) in &registry.counters {
+            header(&mut out, name, "counter");
+            for (labels, value) in series {
+                let _ = writeln!(out, "{}{} {}", name, render_labels(labels, None), value);
+            }
+        }
+        for (name, series) in &registry.histograms {
+            header(&mut out, name, "histogram");
+            let bounds = buckets(name);
+            for (labels, histogram) in series {
+                for (bound, count) in bounds.iter().zip(&histogram.buckets) {
+                    let le = bound.to_string();
+                    let _ = writeln!(
+                        out,
+                        "{}_bucket{} {}",
+                        name,
+                        render_labels(labels, Some(&le)),
+                        count
+                    );
+                }
+                let _ = writeln!(
+                    out,
+                    "{}_bucket{} {}",
+                    name,
+                    render_labels(labels, Some("+Inf")),
+                    histogram.count
+                );
+                let plain = render_labels(labels, None);
+                let _ = writeln!(out, "{}_sum{} {}", name, plain, histogram.sum);
+                let _ = writeln!(out, "{}_count{} {}", name, plain, histogram.count);
+            }
+        }
+        out
+    }
+}
+
+impl MetricsPort for PrometheusMetrics {
+    fn counter(&self, name: &str, labels: &[(&str, &str)], value: u64) {
+        let mut registry = self.registry.lock().unwrap_or_else(|e| e.into_inner());
+        *registry
+            .counters
+            .entry(name.to_string())
+            .or_default()
+            .entry(owned(labels))
+            .or_default() += value;
+    }
+
+    fn histogram(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        let bounds = buckets(name);
+        let mut registry = self.registry.lock().unwrap_or_else(|e| e.into_inner());
+        let histogram = registry
+            .histograms
+            .entry(name.to_string())
+            .or_default()
+            .entry(owned(labels))
+            .or_insert_with(|| Histogram {
+                buckets: vec![0; bounds.len()],
+                ..Histogram::default()
+            });
+        for (bound, count) in bounds.iter().zip(histogram.buckets.iter_mut()) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        histogram.sum += value;
+        histogram.count += 1;
+    }
+}
+
+fn owned(labels: &[(&str, &str)]) -> Labels {
+    let mut labels: Labels = labels
+        .iter()
+        .map(|(k, v)| (k.to_string(), v.to_string()))
+        .collect();
+    labels.sort();
+    labels
+}
+
+fn header(out: &mut String, name: &str, kind: &str) {
+    if let Some(help) = help(name) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+    }
+    let _ = writeln!(out, "# TYPE {} {}", name, kind);
+}
+
+fn render_labels(labels: &Labels, le: Option<&str>) -> String {
+    let mut parts: Vec<String> = labels
+        .iter()
+        .map(|(k, v)| format!("{}=\"{}\"", k, escape(v)))
+        .collect();
+    if let Some(le) = le {
+        parts.push(format!("le=\"{}\"", le));
+    }
+    if parts.is_empty() {
+        String::new()
+    } else {
+        format!("{{{}}}", parts.join(","))
+    }
+}
+
+/// Escape a label value: backslash, double quote and newline.
+fn escape(value: &str) -> String {
+    value
+        .replace('\\', r"\\")
+        .replace('"', "\\\"")
+        .replace('\n', r"\n")
+}
@@ -1,24 +1,359 @@
 //! Port traits that isolate buildfix core from host I/O.
 
-use buildfix_receipts::LoadedReceipt;
+use anyhow::Context;
+use buildfix_receipts::{CacheStats, LoadedReceipt};
+use buildfix_types::progress::Progress;
+use buildfix_types::report::ReportStatus;
 use camino::Utf8Path;
 
+/// Time and id sources, carried in the settings as
+/// [`Clock`](crate::settings::Clock) and [`Ids`](crate::settings::Ids).
+pub use buildfix_types::clock::{ClockPort, IdPort};
+
 /// Source of sensor receipts.
 pub trait ReceiptSource {
     fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>>;
+
+    /// Raw `cargo metadata --format-version 1` JSON, if a snapshot is available.
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    /// Parsed-receipt cache hits and misses so far, if the source caches.
+    fn cache_stats(&self) -> Option<CacheStats> {
+        None
+    }
 }
 
 /// Git queries (HEAD SHA, dirty status).
 pub trait GitPort {
     fn head_sha(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<String>>;
     fn is_dirty(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<bool>>;
+    /// Whether any of `paths` (relative to `repo_root`) has uncommitted
+    /// changes. Ports that cannot scope fall back to the whole tree.
+    fn is_dirty_paths(
+        &self,
+        repo_root: &Utf8Path,
+        _paths: &[String],
+    ) -> anyhow::Result<Option<bool>> {
+        self.is_dirty(repo_root)
+    }
+    /// URL of the remote named `remote`, as configured. `None` when there is
+    /// no such remote or the port has no git access.
+    fn remote_url(&self, _repo_root: &Utf8Path, _remote: &str) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+    /// Paths with uncommitted changes, including untracked files. `None`
+    /// when the port cannot list them.
+    fn dirty_paths(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(None)
+    }
     fn commit_all(&self, _repo_root: &Utf8Path, _message: &str) -> anyhow::Result<Option<String>> {
         Ok(None)
     }
+    /// Create `branch` at HEAD and switch to it, carrying uncommitted
+    /// changes along. `false` when the port has no git access.
+    fn create_branch(&self, _repo_root: &Utf8Path, _branch: &str) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+    /// Push `branch` to `remote` and set it as upstream. `false` when the
+    /// port has no git access.
+    fn push(&self, _repo_root: &Utf8Path, _remote: &str, _branch: &str) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+    /// Check HEAD out, detached, into a new linked worktree at `path`.
+    /// `false` when the port has no git access.
+    fn add_worktree(&self, _repo_root: &Utf8Path, _path: &Utf8Path) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+    /// Remove the linked worktree at `path`, discarding its changes.
+    fn remove_worktree(&self, _repo_root: &Utf8Path, _path: &Utf8Path) -> anyhow::Result<()> {
+        Ok(())
+    }
+    /// Repo-relative paths changed since the merge base of `base_ref` and
+    /// HEAD, including uncommitted and untracked files. `None` when the
+    /// port has no git access.
+    fn changed_files(
+        &self,
+        _repo_root: &Utf8Path,
+        _base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(None)
+    }
+    /// Repo-relative paths with changes staged in the index. `None` when
+    /// the port has no git access.
+    fn staged_files(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(None)
+    }
+}
+
+/// A pull (merge) request to open, or to update when one is already open
+/// from `head`.
+#[derive(Debug, Clone)]
+pub struct ChangeRequest {
+    pub head: String,
+    pub base: String,
+    pub title: String,
+    pub body: String,
+}
+
+/// A pull (merge) request as the forge reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ChangeRequestRef {
+    /// PR number (GitHub, Bitbucket) or MR iid (GitLab).
+    pub number: u64,
+    pub url: String,
+    /// `false` when an open request for the head branch already existed.
+    pub created: bool,
+}
+
+/// A comment on a change request as the forge reports it.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentRef {
+    pub id: u64,
+    /// Link to the comment, when the forge returns one.
+    pub url: Option<String>,
+    /// `false` when an earlier comment with the same marker was edited.
+    pub created: bool,
+}
+
+/// How a check or commit status ends. Only `Failure` should block a merge.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CheckConclusion {
+    Success,
+    Neutral,
+    Failure,
+    Skipped,
+}
+
+impl CheckConclusion {
+    /// GitHub check-run conclusion name.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Self::Success => "success",
+            Self::Neutral => "neutral",
+            Self::Failure => "failure",
+            Self::Skipped => "skipped",
+        }
+    }
+}
+
+/// A report verdict as a check: `warn` (fixes available, nothing broken)
+/// is neutral rather than failing.
+impl From<ReportStatus> for CheckConclusion {
+    fn from(status: ReportStatus) -> Self {
+        match status {
+            ReportStatus::Pass => Self::Success,
+            ReportStatus::Warn => Self::Neutral,
+            ReportStatus::Fail => Self::Failure,
+            ReportStatus::Skip => Self::Skipped,
+        }
+    }
+}
+
+/// A completed check on a commit with a markdown summary.
+#[derive(Debug, Clone)]
+pub struct CommitCheck {
+    pub name: String,
+    pub head_sha: String,
+    pub conclusion: CheckConclusion,
+    pub title: String,
+    pub summary: String,
+    pub text: Option<String>,
+    /// Page linked from the check or status (e.g. the CI job).
+    pub details_url: Option<String>,
+}
+
+/// Code-hosting API for proposing applied changes: GitHub pull requests,
+/// GitLab merge requests, Bitbucket pull requests.
+pub trait ForgePort {
+    /// Provider name for messages (`github`, `gitlab`, `bitbucket`).
+    fn name(&self) -> &'static str;
+    fn default_branch(&self) -> anyhow::Result<String>;
+    fn upsert_change_request(&self, request: &ChangeRequest) -> anyhow::Result<ChangeRequestRef>;
+    /// Edit the comment on change request `number` that starts with
+    /// `marker` (a hidden HTML comment) to `marker` plus `body`, or post it
+    /// if there is none, so repeated runs keep a single comment.
+    fn upsert_comment(&self, number: u64, marker: &str, body: &str) -> anyhow::Result<CommentRef>;
+    /// Add labels to a change request. `false` when the forge has no labels.
+    fn add_labels(&self, _number: u64, _labels: &[String]) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+    /// Attach `check` to its commit and return the check's URL. `None` when
+    /// the forge has no equivalent.
+    fn publish_check(&self, _check: &CommitCheck) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+    /// Set a commit status named `check.name` on `check.head_sha`. Statuses
+    /// carry only `check.title` as a short description, not the markdown.
+    /// `false` when the forge has no commit statuses.
+    fn publish_status(&self, _check: &CommitCheck) -> anyhow::Result<bool> {
+        Ok(false)
+    }
+}
+
+/// Receives progress while `run_plan` / `run_apply` run, e.g. to drive a
+/// progress bar. Called on the pipeline's thread, so keep it cheap. Any
+/// `Fn(&Progress)` closure is a progress port.
+pub trait ProgressPort {
+    fn report(&self, progress: &Progress<'_>);
+}
+
+impl<F: Fn(&Progress<'_>)> ProgressPort for F {
+    fn report(&self, progress: &Progress<'_>) {
+        self(progress)
+    }
+}
+
+/// Receives counters and histograms as `run_plan` / `run_apply` finish,
+/// carried in the settings as [`Metrics`](crate::settings::Metrics). Names
+/// and labels are listed in [`metrics`](crate::metrics).
+pub trait MetricsPort: Send + Sync {
+    /// Add `value` to the counter `name` for `labels`.
+    fn counter(&self, name: &str, labels: &[(&str, &str)], value: u64);
+    /// Record one observation of `value` in the histogram `name` for `labels`.
+    fn histogram(&self, name: &str, labels: &[(&str, &str)], value: f64);
+}
+
+/// Lets a host keep its own handle on a port it passes to [`Metrics`](crate::settings::Metrics),
+/// e.g. to render a [`PrometheusMetrics`](crate::metrics::PrometheusMetrics) afterwards.
+impl<T: MetricsPort + ?Sized> MetricsPort for std::sync::Arc<T> {
+    fn counter(&self, name: &str, labels: &[(&str, &str)], value: u64) {
+        (**self).counter(name, labels, value);
+    }
+
+    fn histogram(&self, name: &str, labels: &[(&str, &str)], value: f64) {
+        (**self).histogram(name, labels, value);
+    }
 }
 
 /// File-system write operations.
 pub trait WritePort {
     fn write_file(&self, path: &Utf8Path, contents: &[u8]) -> anyhow::Result<()>;
     fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()>;
+
+    /// Write `path` from `write`, which streams the contents into the sink it
+    /// is handed. Large artifacts such as `patch.diff` go through here so
+    /// adapters that can stream never hold a second copy. The default buffers
+    /// the contents and calls [`WritePort::write_file`].
+    fn write_file_with(
+        &self,
+        path: &Utf8Path,
+        write: &mut dyn FnMut(&mut dyn std::io::Write) -> std::io::Result<()>,
+    ) -> anyhow::Result<()> {
+        let mut buf = Vec::new();
+        write(&mut buf).with_context(|| format!("write {}", path))?;
+        self.write_file(path, &buf)
+    }
+}
+
+/// [`ReceiptSource`] for hosts already inside a tokio runtime (`async`
+/// feature).
+#[cfg(feature = "async")]
+pub trait AsyncReceiptSource: Send + Sync {
+    fn load_receipts(&self) -> impl Future<Output = anyhow::Result<Vec<LoadedReceipt>>> + Send;
+
+    /// Raw `cargo metadata --format-version 1` JSON, if a snapshot is available.
+    fn load_cargo_metadata(&self) -> impl Future<Output = anyhow::Result<Option<String>>> + Send {
+        async { Ok(None) }
+    }
+
+    /// Parsed-receipt cache hits and misses so far, if the source caches.
+    fn cache_stats(&self) -> Option<CacheStats> {
+        None
+    }
+}
+
+/// [`GitPort`] for hosts already inside a tokio runtime (`async` feature).
+/// Methods and defaults mirror [`GitPort`].
+#[cfg(feature = "async")]
+pub trait AsyncGitPort: Send + Sync {
+    fn head_sha(
+        &self,
+        repo_root: &Utf8Path,
+    ) -> impl Future<Output = anyhow::Result<Option<String>>> + Send;
+    fn is_dirty(
+        &self,
+        repo_root: &Utf8Path,
+    ) -> impl Future<Output = anyhow::Result<Option<bool>>> + Send;
+    fn is_dirty_paths(
+        &self,
+        repo_root: &Utf8Path,
+        _paths: &[String],
+    ) -> impl Future<Output = anyhow::Result<Option<bool>>> + Send {
+        self.is_dirty(repo_root)
+    }
+    fn remote_url(
+        &self,
+        _repo_root: &Utf8Path,
+        _remote: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<String>>> + Send {
+        async { Ok(None) }
+    }
+    fn dirty_paths(
+        &self,
+        _repo_root: &Utf8Path,
+    ) -> impl Future<Output = anyhow::Result<Option<Vec<String>>>> + Send {
+        async { Ok(None) }
+    }
+    fn commit_all(
+        &self,
+        _repo_root: &Utf8Path,
+        _message: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<String>>> + Send {
+        async { Ok(None) }
+    }
+    fn create_branch(
+        &self,
+        _repo_root: &Utf8Path,
+        _branch: &str,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send {
+        async { Ok(false) }
+    }
+    fn push(
+        &self,
+        _repo_root: &Utf8Path,
+        _remote: &str,
+        _branch: &str,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send {
+        async { Ok(false) }
+    }
+    fn add_worktree(
+        &self,
+        _repo_root: &Utf8Path,
+        _path: &Utf8Path,
+    ) -> impl Future<Output = anyhow::Result<bool>> + Send {
+        async { Ok(false) }
+    }
+    fn remove_worktree(
+        &self,
+        _repo_root: &Utf8Path,
+        _path: &Utf8Path,
+    ) -> impl Future<Output = anyhow::Result<()>> + Send {
+        async { Ok(()) }
+    }
+    fn changed_files(
+        &self,
+        _repo_root: &Utf8Path,
+        _base_ref: &str,
+    ) -> impl Future<Output = anyhow::Result<Option<Vec<String>>>> + Send {
+        async { Ok(None) }
+    }
+    fn staged_files(
+        &self,
+        _repo_root: &Utf8Path,
+    ) -> impl Future<Output = anyhow::Result<Option<Vec<String>>>> + Send {
+        async { Ok(None) }
+    }
+}
+
+/// [`WritePort`] for hosts already inside a tokio runtime (`async` feature).
+#[cfg(feature = "async")]
+pub trait AsyncWritePort: Send + Sync {
+    fn write_file(
+        &self,
+        path: &Utf8Path,
+        contents: &[u8],
+    ) -> impl Future<Output = anyhow::Result<()>> + Send;
+    fn create_dir_all(&self, path: &Utf8Path) -> impl Future<Output = anyhow::Result<()>> + Send;
 }
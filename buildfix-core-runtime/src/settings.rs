@@ -1,7 +1,15 @@
 //! Public configuration models used by the plan and apply pipeline.
 
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{CiRun, DirtyScope, PathPolicy, PreconditionMode, ScanPolicy};
+use buildfix_types::receipt::Severity;
 use camino::Utf8PathBuf;
-use std::collections::HashMap;
+use std::collections::{BTreeMap, HashMap};
+use std::time::Duration;
+
+pub use crate::metrics::Metrics;
+pub use buildfix_types::cancel::CancellationToken;
+pub use buildfix_types::clock::{Clock, Ids};
 
 /// Run mode controls exit-code semantics.
 ///
@@ -24,6 +32,8 @@ pub struct PlanSettings {
     // Policy
     pub allow: Vec<String>,
     pub deny: Vec<String>,
+    /// Allow/deny and safety limits for ops under matching target paths.
+    pub path_policies: Vec<PathPolicy>,
     pub allow_guarded: bool,
     pub allow_unsafe: bool,
     pub allow_dirty: bool,
@@ -31,16 +41,73 @@ pub struct PlanSettings {
     pub max_files: Option<u64>,
     pub max_patch_bytes: Option<u64>,
     pub params: HashMap<String, String>,
+    /// Per-fixer overrides, keyed by fix id (e.g. `cargo.normalize_edition`).
+    pub fixers: BTreeMap<String, FixerSettings>,
+
+    // Finding filters
+    /// Drop findings below this severity before fixers see them.
+    pub min_severity: Option<Severity>,
+    /// Only keep findings from these sensors (empty means all).
+    pub sensors: Vec<String>,
+    /// Drop findings with these check ids.
+    pub exclude_check_ids: Vec<String>,
+    /// Only keep findings in files changed since this git ref, or in the
+    /// manifest of the package containing them.
+    pub changed_since: Option<String>,
+    /// Only keep findings in files staged in the git index, or in the
+    /// manifest of the package containing them. Combines with
+    /// `changed_since` by intersection.
+    pub staged_only: bool,
+    /// Only plan ops in workspace members whose name matches one of these
+    /// globs (empty means all members and the workspace root).
+    pub packages: Vec<String>,
+    /// Never plan ops in workspace members whose name matches one of these globs.
+    pub exclude_packages: Vec<String>,
+    /// Never plan ops on build output, vendored crates or gitignored files.
+    pub scan: ScanPolicy,
 
     // Preconditions
     pub require_clean_hashes: bool,
     pub git_head_precondition: bool,
+    /// Pin each op's target sha256 so drift blocks only the affected ops.
+    pub pin_op_targets: bool,
+    /// `semantic` also records each TOML file's structural hash, so apply
+    /// accepts files that only changed formatting or comments.
+    pub precondition_mode: PreconditionMode,
 
     // Backups
     pub backup_suffix: String,
 
+    // Patch output
+    /// Render `patch.diff` like `git diff --binary --full-index`.
+    pub binary_patch: bool,
+
+    // Expiry
+    /// Stamp the plan with `expires_at` this long after it is made; apply
+    /// blocks an expired plan. `None` plans never expire.
+    pub plan_ttl: Option<Duration>,
+
+    // Provenance
+    /// CI run recorded in the plan's `repo.ci`.
+    pub ci_run: Option<CiRun>,
+
     // Mode
     pub mode: RunMode,
+
+    // Control
+    /// Fixers planned at once, and threads used to hash and diff touched
+    /// files; 0 uses one per available core. Results are merged in input
+    /// order, so the plan and patch are the same for any value.
+    pub jobs: usize,
+    /// Checked between fixers (plan) and between ops (apply); keep a clone
+    /// to cancel the run from another thread.
+    pub cancel: CancellationToken,
+    /// Time source for report timestamps and backup runs.
+    pub clock: Clock,
+    /// Id source for backup runs and temp files.
+    pub ids: Ids,
+    /// Receives op counts, duration and patch size when the run finishes.
+    pub metrics: Metrics,
 }
 
 impl Default for PlanSettings {
@@ -51,6 +118,7 @@ impl Default for PlanSettings {
             out_dir: Utf8PathBuf::from("artifacts/buildfix"),
             allow: Vec::new(),
             deny: Vec::new(),
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -58,10 +126,53 @@ impl Default for PlanSettings {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: BTreeMap::new(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_since: None,
+            staged_only: false,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: ScanPolicy::default(),
             require_clean_hashes: true,
             git_head_precondition: false,
+            pin_op_targets: false,
+            precondition_mode: PreconditionMode::Exact,
             backup_suffix: ".buildfix.bak".to_string(),
+            binary_patch: false,
+            plan_ttl: None,
+            ci_run: None,
             mode: RunMode::default(),
+            jobs: 0,
+            cancel: CancellationToken::default(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            metrics: Metrics::default(),
+        }
+    }
+}
+
+/// `[fixers.<fix_key>]` overrides for one fixer.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixerSettings {
+    /// `false` skips the fixer entirely.
+    pub enabled: bool,
+    /// Safety class for every op the fixer emits.
+    pub safety: Option<SafetyClass>,
+    /// Allow `safety` to be less strict than the fixer's own class.
+    pub force: bool,
+    /// Params for this fixer's ops, taking precedence over the global ones.
+    pub params: HashMap<String, String>,
+}
+
+impl Default for FixerSettings {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            safety: None,
+            force: false,
+            params: HashMap::new(),
         }
     }
 }
@@ -71,22 +182,69 @@ impl Default for PlanSettings {
 pub struct ApplySettings {
     pub repo_root: Utf8PathBuf,
     pub out_dir: Utf8PathBuf,
+    /// Plan to apply; `None` reads `<out_dir>/plan.json`.
+    pub plan_path: Option<Utf8PathBuf>,
 
     // Apply behaviour
     pub dry_run: bool,
     pub allow_guarded: bool,
     pub allow_unsafe: bool,
     pub allow_dirty: bool,
+    /// Which uncommitted changes count as dirty. Auto-commit always
+    /// requires the whole tree to be clean.
+    pub dirty_scope: DirtyScope,
     pub params: HashMap<String, String>,
     pub auto_commit: bool,
     pub commit_message: Option<String>,
+    /// Move the applied changes onto a new branch named by this template
+    /// (`{fix_keys}`, `{date}`, `{plan_hash}`) before committing.
+    pub branch: Option<String>,
+    /// Push the branch to this remote once auto-commit has committed.
+    pub push_remote: Option<String>,
+
+    // Selection
+    /// Apply only these op ids (plus any matched by `only_fix_keys`).
+    pub op_ids: Vec<String>,
+    /// Apply only ops whose fix key matches one of these globs.
+    pub only_fix_keys: Vec<String>,
+    /// Never apply ops whose fix key matches one of these globs.
+    pub skip_fix_keys: Vec<String>,
+    /// Apply only ops in workspace members whose name matches one of these globs.
+    pub packages: Vec<String>,
+    /// Never apply ops in workspace members whose name matches one of these globs.
+    pub exclude_packages: Vec<String>,
 
     // Backups
     pub backup_enabled: bool,
     pub backup_suffix: String,
+    /// Keep only the newest N backup runs; `None` keeps all.
+    pub backup_keep_last: Option<usize>,
+
+    // Durability
+    /// fsync written files and their directories before reporting success.
+    pub durable_writes: bool,
+
+    // Patch output
+    /// Render `patch.diff` like `git diff --binary --full-index`.
+    pub binary_patch: bool,
+
+    // Provenance
+    /// CI run recorded in the apply's `repo.ci`.
+    pub ci_run: Option<CiRun>,
 
     // Mode
     pub mode: RunMode,
+
+    // Control
+    /// Checked between fixers (plan) and between ops (apply); keep a clone
+    /// to cancel the run from another thread.
+    pub cancel: CancellationToken,
+    /// Time source for report timestamps and backup runs.
+    pub clock: Clock,
+    /// Id source for backup runs and temp files.
+    pub ids: Ids,
+    /// Receives op counts, duration and patch size when the run finishes.
+    pub metrics: Metrics,
 }
 
 impl Default for ApplySettings {
@@ -94,16 +252,33 @@ impl Default for ApplySettings {
         Self {
             repo_root: Utf8PathBuf::from("."),
             out_dir: Utf8PathBuf::from("artifacts/buildfix"),
+            plan_path: None,
             dry_run: true,
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
+            dirty_scope: DirtyScope::default(),
             params: HashMap::new(),
             auto_commit: false,
             commit_message: None,
+            branch: None,
+            push_remote: None,
+            op_ids: Vec::new(),
+            only_fix_keys: Vec::new(),
+            skip_fix_keys: Vec::new(),
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
             backup_enabled: true,
             backup_suffix: ".buildfix.bak".to_string(),
+            backup_keep_last: None,
+            durable_writes: false,
+            binary_patch: false,
+            ci_run: None,
             mode: RunMode::default(),
+            cancel: CancellationToken::default(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            metrics: Metrics::default(),
         }
     }
 }
@@ -129,7 +304,10 @@ mod tests {
         assert!(settings.params.is_empty());
         assert!(settings.require_clean_hashes);
         assert!(!settings.git_head_precondition);
+        assert!(!settings.pin_op_targets);
         assert_eq!(settings.backup_suffix, ".buildfix.bak");
+        assert!(!settings.binary_patch);
+        assert!(settings.plan_ttl.is_none());
         assert_eq!(settings.mode, RunMode::Standalone);
     }
 
@@ -147,6 +325,9 @@ mod tests {
         assert!(settings.commit_message.is_none());
         assert!(settings.backup_enabled);
         assert_eq!(settings.backup_suffix, ".buildfix.bak");
+        assert!(settings.backup_keep_last.is_none());
+        assert!(!settings.durable_writes);
+        assert!(!settings.binary_patch);
         assert_eq!(settings.mode, RunMode::Standalone);
     }
 }
@@ -3,7 +3,9 @@
 //! Tests runtime adapters, settings, and ports implementation.
 
 use buildfix_core_runtime::ports::{GitPort, ReceiptSource, WritePort};
-use buildfix_core_runtime::settings::{ApplySettings, PlanSettings, RunMode};
+use buildfix_core_runtime::settings::{
+    ApplySettings, CancellationToken, Clock, Ids, Metrics, PlanSettings, RunMode,
+};
 use buildfix_receipts::{LoadedReceipt, ReceiptLoadError};
 use camino::{Utf8Path, Utf8PathBuf};
 use std::collections::HashMap;
@@ -67,6 +69,7 @@ fn test_plan_settings_custom() {
         out_dir: Utf8PathBuf::from("/custom/out"),
         allow: vec!["fix1".to_string(), "fix2".to_string()],
         deny: vec!["fix3".to_string()],
+        path_policies: Vec::new(),
         allow_guarded: true,
         allow_unsafe: true,
         allow_dirty: true,
@@ -74,10 +77,29 @@ fn test_plan_settings_custom() {
         max_files: Some(50),
         max_patch_bytes: Some(10000),
         params,
+        fixers: Default::default(),
+        min_severity: None,
+        sensors: Vec::new(),
+        exclude_check_ids: Vec::new(),
+        changed_since: None,
+        staged_only: false,
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
         require_clean_hashes: false,
         git_head_precondition: true,
+        pin_op_targets: false,
         backup_suffix: ".bak".to_string(),
+        binary_patch: false,
         mode: RunMode::Cockpit,
+        ci_run: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        metrics: Metrics::default(),
+        jobs: 0,
+        precondition_mode: Default::default(),
+        scan: Default::default(),
+        plan_ttl: None,
     };
 
     assert_eq!(settings.repo_root, Utf8PathBuf::from("/custom/repo"));
@@ -142,16 +164,33 @@ fn test_apply_settings_custom() {
     let settings = ApplySettings {
         repo_root: Utf8PathBuf::from("/custom/repo"),
         out_dir: Utf8PathBuf::from("/custom/out"),
+        plan_path: None,
         dry_run: false,
         allow_guarded: true,
         allow_unsafe: true,
         allow_dirty: true,
+        dirty_scope: Default::default(),
         params,
         auto_commit: true,
         commit_message: Some("Auto-fix commit".to_string()),
+        branch: None,
+        push_remote: None,
+        op_ids: Vec::new(),
+        only_fix_keys: Vec::new(),
+        skip_fix_keys: Vec::new(),
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
         backup_enabled: false,
         backup_suffix: ".backup".to_string(),
+        backup_keep_last: None,
+        durable_writes: false,
+        binary_patch: false,
         mode: RunMode::Cockpit,
+        ci_run: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        metrics: Metrics::default(),
     };
 
     assert_eq!(settings.repo_root, Utf8PathBuf::from("/custom/repo"));
@@ -298,6 +337,7 @@ fn test_mock_receipt_source_with_receipts() {
         receipt: Err(ReceiptLoadError::Io {
             message: "stub".to_string(),
         }),
+        signature: None,
     };
 
     let source = MockReceiptSource::new(vec![receipt]);
@@ -409,6 +449,28 @@ mod fs_tests {
         // Empty directory should return empty receipts
         assert!(receipts.is_empty());
     }
+
+    #[test]
+    fn test_fs_receipt_source_discovers_cargo_metadata() {
+        let temp_dir = TempDir::new().unwrap();
+        let artifacts_dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+
+        let source = FsReceiptSource::new(artifacts_dir.to_owned());
+        assert!(source.load_cargo_metadata().unwrap().is_none());
+
+        std::fs::write(artifacts_dir.join("cargo-metadata.json"), "{}").unwrap();
+        assert_eq!(source.load_cargo_metadata().unwrap().as_deref(), Some("{}"));
+    }
+
+    #[test]
+    fn test_fs_receipt_source_explicit_cargo_metadata_must_exist() {
+        let temp_dir = TempDir::new().unwrap();
+        let artifacts_dir = Utf8Path::from_path(temp_dir.path()).unwrap();
+
+        let source = FsReceiptSource::new(artifacts_dir.to_owned())
+            .with_cargo_metadata(artifacts_dir.join("missing.json"));
+        assert!(source.load_cargo_metadata().is_err());
+    }
 }
 
 // ============================================================================
@@ -464,6 +526,50 @@ mod fs_write_tests {
     }
 }
 
+// ============================================================================
+// PrometheusMetrics Tests
+// ============================================================================
+
+#[test]
+fn test_prometheus_metrics_render_text_format() {
+    use buildfix_core_runtime::metrics::{self, Metrics, PrometheusMetrics};
+    use std::sync::Arc;
+
+    let registry = Arc::new(PrometheusMetrics::new());
+    let handle = Metrics::new(Arc::clone(&registry));
+    let labels = [("fix_key", "cargo.workspace_resolver_v2")];
+    handle.counter(metrics::OPS_PLANNED, &labels, 1);
+    handle.counter(metrics::OPS_PLANNED, &labels, 2);
+    handle.counter(metrics::RUNS, &[("outcome", "say \"hi\"\n")], 1);
+    handle.histogram(metrics::PLAN_DURATION, &[], 0.25);
+    handle.histogram(metrics::PLAN_DURATION, &[], 3.0);
+
+    let text = registry.render();
+    assert!(text.contains("# HELP buildfix_ops_planned_total Ops planned, by fixer.\n"));
+    assert!(text.contains("# TYPE buildfix_ops_planned_total counter\n"));
+    assert!(
+        text.contains("buildfix_ops_planned_total{fix_key=\"cargo.workspace_resolver_v2\"} 3\n")
+    );
+    assert!(text.contains(r#"buildfix_runs_total{outcome="say \"hi\"\n"} 1"#));
+    assert!(text.contains("# TYPE buildfix_plan_duration_seconds histogram\n"));
+    assert!(text.contains("buildfix_plan_duration_seconds_bucket{le=\"0.1\"} 0\n"));
+    assert!(text.contains("buildfix_plan_duration_seconds_bucket{le=\"0.25\"} 1\n"));
+    assert!(text.contains("buildfix_plan_duration_seconds_bucket{le=\"5\"} 2\n"));
+    assert!(text.contains("buildfix_plan_duration_seconds_bucket{le=\"+Inf\"} 2\n"));
+    assert!(text.contains("buildfix_plan_duration_seconds_sum 3.25\n"));
+    assert!(text.contains("buildfix_plan_duration_seconds_count 2\n"));
+}
+
+#[test]
+fn test_metrics_default_is_noop() {
+    use buildfix_core_runtime::metrics::Metrics;
+
+    let metrics = Metrics::default();
+    metrics.counter("buildfix_test_total", &[], 1);
+    metrics.histogram("buildfix_test_seconds", &[], 1.0);
+    assert_eq!(format!("{:?}", metrics), "Metrics(..)");
+}
+
 // ============================================================================
 // InMemoryReceiptSource Tests (when memory feature is enabled)
 // ============================================================================
@@ -480,6 +586,7 @@ mod memory_tests {
             receipt: Err(ReceiptLoadError::Io {
                 message: "stub".to_string(),
             }),
+            signature: None,
         }
     }
 
@@ -570,3 +677,40 @@ mod git_shell_tests {
     // Note: Testing actual git operations requires a real git repository
     // These tests would be integration tests rather than unit tests
 }
+
+#[cfg(feature = "gix")]
+mod gix_git_tests {
+    use super::*;
+    use buildfix_core_runtime::adapters::{GixGitPort, ShellGitPort};
+    use std::process::Command;
+
+    fn git(root: &Utf8Path, args: &[&str]) {
+        let status = Command::new("git")
+            .args(args)
+            .current_dir(root)
+            .status()
+            .expect("run git");
+        assert!(status.success(), "git {:?} failed", args);
+    }
+
+    #[test]
+    fn test_gix_git_port_matches_shell_port() {
+        let temp = TempDir::new().unwrap();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\n").unwrap();
+        git(&root, &["init", "-q"]);
+        git(&root, &["config", "user.email", "test@example.com"]);
+        git(&root, &["config", "user.name", "Test User"]);
+        git(&root, &["add", "."]);
+        git(&root, &["commit", "-qm", "init"]);
+
+        let head = GixGitPort.head_sha(&root).unwrap();
+        assert!(head.is_some());
+        assert_eq!(head, ShellGitPort.head_sha(&root).unwrap());
+        assert_eq!(GixGitPort.is_dirty(&root).unwrap(), Some(false));
+
+        std::fs::write(root.join("notes.txt"), "untracked\n").unwrap();
+        assert_eq!(GixGitPort.is_dirty(&root).unwrap(), Some(true));
+        assert_eq!(ShellGitPort.is_dirty(&root).unwrap(), Some(true));
+    }
+}
@@ -1,3 +1,14 @@
+#[cfg(feature = "async")]
+pub use buildfix_core_runtime::Blocking;
+#[cfg(feature = "object-store")]
+pub use buildfix_core_runtime::ObjectStoreReceiptSource;
+#[cfg(feature = "forge")]
 pub use buildfix_core_runtime::{
-    FsReceiptSource, FsWritePort, InMemoryReceiptSource, ShellGitPort,
+    BitbucketForge, ForgeKind, GithubForge, GitlabForge, repo_path_from_remote_url,
 };
+pub use buildfix_core_runtime::{
+    CARGO_METADATA_FILE, FixedClock, FsReceiptSource, FsWritePort, InMemoryReceiptSource,
+    NoopMetrics, PrometheusMetrics, RandomIds, SequentialIds, ShellGitPort, SystemClock,
+};
+#[cfg(feature = "http")]
+pub use buildfix_core_runtime::{HttpReceiptSource, RemoteReceipt};
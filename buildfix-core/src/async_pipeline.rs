@@ -0,0 +1,225 @@
+//! Async entry points for hosts already inside a tokio runtime (`async`
+//! feature).
+//!
+//! The pipeline itself stays synchronous: it runs on tokio's blocking pool,
+//! and each port call it makes is driven on the caller's runtime through the
+//! async port. Hosts therefore never block a runtime worker, and their ports
+//! can await remote receipts or forge APIs directly. Use
+//! [`Blocking`](crate::adapters::Blocking) to pass the sync adapters.
+
+use crate::pipeline::{
+    ApplyOutcome, MarkdownTemplates, PlanOutcome, ToolError, run_apply, run_plan,
+    write_apply_artifacts, write_plan_artifacts,
+};
+use crate::ports::{
+    AsyncGitPort, AsyncReceiptSource, AsyncWritePort, GitPort, ReceiptSource, WritePort,
+};
+use crate::settings::{ApplySettings, PlanSettings};
+use buildfix_receipts::{CacheStats, LoadedReceipt};
+use buildfix_types::receipt::ToolInfo;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::sync::{Arc, Mutex};
+use tokio::runtime::Handle;
+
+/// [`run_plan`] for async ports. Must be called from within a tokio runtime.
+pub async fn run_plan_async<R, G>(
+    settings: &PlanSettings,
+    receipts_port: Arc<R>,
+    git: Arc<G>,
+    tool: ToolInfo,
+) -> Result<PlanOutcome, ToolError>
+where
+    R: AsyncReceiptSource + 'static,
+    G: AsyncGitPort + 'static,
+{
+    let handle = Handle::current();
+    let receipts = OnRuntime::new(receipts_port, handle.clone());
+    let git = OnRuntime::new(git, handle);
+    let settings = settings.clone();
+    join(tokio::task::spawn_blocking(move || run_plan(&settings, &receipts, &git, tool)).await)
+}
+
+/// [`run_apply`] for an async git port. Must be called from within a tokio
+/// runtime.
+pub async fn run_apply_async<G>(
+    settings: &ApplySettings,
+    git: Arc<G>,
+    tool: ToolInfo,
+) -> Result<ApplyOutcome, ToolError>
+where
+    G: AsyncGitPort + 'static,
+{
+    let git = OnRuntime::new(git, Handle::current());
+    let settings = settings.clone();
+    join(tokio::task::spawn_blocking(move || run_apply(&settings, &git, tool)).await)
+}
+
+/// [`write_plan_artifacts`] through an async writer. The artifacts are
+/// rendered in memory first, then written in order.
+pub async fn write_plan_artifacts_async<W: AsyncWritePort>(
+    outcome: &PlanOutcome,
+    templates: &MarkdownTemplates,
+    out_dir: &Utf8Path,
+    writer: &W,
+) -> anyhow::Result<()> {
+    let recorded = RecordingWritePort::default();
+    write_plan_artifacts(outcome, templates, out_dir, &recorded)?;
+    recorded.replay(writer).await
+}
+
+/// [`write_apply_artifacts`] through an async writer. The artifacts are
+/// rendered in memory first, then written in order.
+pub async fn write_apply_artifacts_async<W: AsyncWritePort>(
+    outcome: &ApplyOutcome,
+    templates: &MarkdownTemplates,
+    out_dir: &Utf8Path,
+    writer: &W,
+) -> anyhow::Result<()> {
+    let recorded = RecordingWritePort::default();
+    write_apply_artifacts(outcome, templates, out_dir, &recorded)?;
+    recorded.replay(writer).await
+}
+
+fn join<T>(result: Result<Result<T, ToolError>, tokio::task::JoinError>) -> Result<T, ToolError> {
+    match result {
+        Ok(result) => result,
+        Err(e) if e.is_panic() => std::panic::resume_unwind(e.into_panic()),
+        Err(e) => Err(anyhow::anyhow!("pipeline task: {}", e).into()),
+    }
+}
+
+/// An async port seen as a sync one from a blocking-pool thread: each call
+/// blocks that thread until the port's future completes on `handle`.
+struct OnRuntime<P> {
+    port: Arc<P>,
+    handle: Handle,
+}
+
+impl<P> OnRuntime<P> {
+    fn new(port: Arc<P>, handle: Handle) -> Self {
+        Self { port, handle }
+    }
+}
+
+impl<P: AsyncReceiptSource> ReceiptSource for OnRuntime<P> {
+    fn load_receipts(&self) -> anyhow::Result<Vec<LoadedReceipt>> {
+        self.handle.block_on(self.port.load_receipts())
+    }
+
+    fn load_cargo_metadata(&self) -> anyhow::Result<Option<String>> {
+        self.handle.block_on(self.port.load_cargo_metadata())
+    }
+
+    fn cache_stats(&self) -> Option<CacheStats> {
+        self.port.cache_stats()
+    }
+}
+
+impl<P: AsyncGitPort> GitPort for OnRuntime<P> {
+    fn head_sha(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        self.handle.block_on(self.port.head_sha(repo_root))
+    }
+
+    fn is_dirty(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        self.handle.block_on(self.port.is_dirty(repo_root))
+    }
+
+    fn is_dirty_paths(
+        &self,
+        repo_root: &Utf8Path,
+        paths: &[String],
+    ) -> anyhow::Result<Option<bool>> {
+        self.handle
+            .block_on(self.port.is_dirty_paths(repo_root, paths))
+    }
+
+    fn remote_url(&self, repo_root: &Utf8Path, remote: &str) -> anyhow::Result<Option<String>> {
+        self.handle
+            .block_on(self.port.remote_url(repo_root, remote))
+    }
+
+    fn dirty_paths(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        self.handle.block_on(self.port.dirty_paths(repo_root))
+    }
+
+    fn commit_all(&self, repo_root: &Utf8Path, message: &str) -> anyhow::Result<Option<String>> {
+        self.handle
+            .block_on(self.port.commit_all(repo_root, message))
+    }
+
+    fn create_branch(&self, repo_root: &Utf8Path, branch: &str) -> anyhow::Result<bool> {
+        self.handle
+            .block_on(self.port.create_branch(repo_root, branch))
+    }
+
+    fn push(&self, repo_root: &Utf8Path, remote: &str, branch: &str) -> anyhow::Result<bool> {
+        self.handle
+            .block_on(self.port.push(repo_root, remote, branch))
+    }
+
+    fn add_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<bool> {
+        self.handle
+            .block_on(self.port.add_worktree(repo_root, path))
+    }
+
+    fn remove_worktree(&self, repo_root: &Utf8Path, path: &Utf8Path) -> anyhow::Result<()> {
+        self.handle
+            .block_on(self.port.remove_worktree(repo_root, path))
+    }
+
+    fn changed_files(
+        &self,
+        repo_root: &Utf8Path,
+        base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        self.handle
+            .block_on(self.port.changed_files(repo_root, base_ref))
+    }
+
+    fn staged_files(&self, repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        self.handle.block_on(self.port.staged_files(repo_root))
+    }
+}
+
+enum WriteOp {
+    CreateDir(Utf8PathBuf),
+    File(Utf8PathBuf, Vec<u8>),
+}
+
+/// Collects the artifact writes so they can be replayed through an async
+/// writer.
+#[derive(Default)]
+struct RecordingWritePort {
+    ops: Mutex<Vec<WriteOp>>,
+}
+
+impl RecordingWritePort {
+    async fn replay<W: AsyncWritePort>(self, writer: &W) -> anyhow::Result<()> {
+        let ops = self.ops.into_inner().unwrap_or_else(|e| e.into_inner());
+        for op in ops {
+            match op {
+                WriteOp::CreateDir(path) => writer.create_dir_all(&path).await?,
+                WriteOp::File(path, contents) => writer.write_file(&path, &contents).await?,
+            }
+        }
+        Ok(())
+    }
+}
+
+impl WritePort for RecordingWritePort {
+    fn write_file(&self, path: &Utf8Path, contents: &[u8]) -> anyhow::Result<()> {
+        self.ops
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(WriteOp::File(path.to_owned(), contents.to_vec()));
+        Ok(())
+    }
+
+    fn create_dir_all(&self, path: &Utf8Path) -> anyhow::Result<()> {
+        self.ops
+            .lock()
+            .unwrap_or_else(|e| e.into_inner())
+            .push(WriteOp::CreateDir(path.to_owned()));
+        Ok(())
+    }
+}
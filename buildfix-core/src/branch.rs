@@ -0,0 +1,298 @@
+//! Branch names for `apply --branch`.
+//!
+//! A template such as `buildfix/{fix_keys}-{date}` expands to a git ref
+//! name from what the apply actually changed:
+//!
+//! - `{fix_keys}` — CLI keys of the fixers with applied ops, sorted and
+//!   joined with `+` (e.g. `path-dep-version+resolver-v2`)
+//! - `{date}` — the UTC date as `YYYYMMDD`
+//! - `{plan_hash}` — the first 12 hex digits of the plan's sha256
+
+use buildfix_domain::builtin_fixer_metas;
+use buildfix_types::apply::{ApplyStatus, BuildfixApply};
+use buildfix_types::plan::BuildfixPlan;
+use chrono::NaiveDate;
+use std::collections::BTreeSet;
+
+/// Template used when `--branch` is given without a value.
+pub const DEFAULT_BRANCH_TEMPLATE: &str = "buildfix/{fix_keys}-{date}";
+
+/// Expand `template` for `apply` of `plan` and make the result a valid ref
+/// name. Fails on an unknown placeholder or when nothing usable is left.
+pub fn render_branch_name(
+    template: &str,
+    plan: &BuildfixPlan,
+    apply: &BuildfixApply,
+    plan_sha: &str,
+    date: NaiveDate,
+) -> anyhow::Result<String> {
+    expand(template, &applied_fix_keys(plan, apply), plan_sha, date)
+}
+
+/// Check `template` for unknown or unclosed placeholders, so a bad
+/// `--branch` fails before any file is written.
+pub fn validate_branch_template(template: &str) -> anyhow::Result<()> {
+    let date = NaiveDate::from_ymd_opt(2000, 1, 1).expect("valid date");
+    expand(template, &["fix".to_string()], "0", date).map(|_| ())
+}
+
+fn expand(
+    template: &str,
+    fix_keys: &[String],
+    plan_sha: &str,
+    date: NaiveDate,
+) -> anyhow::Result<String> {
+    let mut rendered = String::new();
+    let mut rest = template;
+    while let Some(start) = rest.find('{') {
+        rendered.push_str(&rest[..start]);
+        let Some(len) = rest[start..].find('}') else {
+            anyhow::bail!("unclosed '{{' in branch template {:?}", template);
+        };
+        let value = match &rest[start + 1..start + len] {
+            "fix_keys" => fix_keys.join("+"),
+            "date" => date.format("%Y%m%d").to_string(),
+            "plan_hash" => plan_sha.chars().take(12).collect(),
+            other => anyhow::bail!(
+                "unknown placeholder {{{}}} in branch template (expected fix_keys, date or plan_hash)",
+                other
+            ),
+        };
+        rendered.push_str(&value);
+        rest = &rest[start + len + 1..];
+    }
+    rendered.push_str(rest);
+
+    let name = sanitize_ref_name(&rendered);
+    if name.is_empty() {
+        anyhow::bail!("branch template {:?} expands to an empty name", template);
+    }
+    Ok(name)
+}
+
+/// CLI keys of the fixers behind the applied ops. An op whose policy key
+/// matches no built-in fixer contributes its check id (or, lacking one,
+/// the whole key) instead.
+fn applied_fix_keys(plan: &BuildfixPlan, apply: &BuildfixApply) -> Vec<String> {
+    let applied: BTreeSet<&str> = apply
+        .results
+        .iter()
+        .filter(|r| r.status == ApplyStatus::Applied)
+        .map(|r| r.op_id.as_str())
+        .collect();
+    let metas = builtin_fixer_metas();
+
+    let mut keys = BTreeSet::new();
+    for op in plan
+        .ops
+        .iter()
+        .filter(|op| applied.contains(op.id.as_str()))
+    {
+        let mut parts = op.rationale.fix_key.splitn(3, '/');
+        let (sensor, check_id, code) = (
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or_default(),
+            parts.next().unwrap_or("*"),
+        );
+        let key = metas
+            .iter()
+            .find(|meta| {
+                meta.triggers.iter().any(|t| {
+                    t.sensor == sensor
+                        && t.check_id == check_id
+                        && t.code.is_none_or(|c| c == code || code == "*")
+                })
+            })
+            .map_or_else(
+                || {
+                    if check_id.is_empty() {
+                        op.rationale.fix_key.clone()
+                    } else {
+                        check_id.to_string()
+                    }
+                },
+                |meta| meta.key.to_string(),
+            );
+        keys.insert(key);
+    }
+    keys.into_iter().collect()
+}
+
+/// Replace characters git rejects in ref names with `-` and drop empty,
+/// dot-leading and `.lock`-suffixed path components.
+fn sanitize_ref_name(raw: &str) -> String {
+    let replaced: String = raw
+        .chars()
+        .map(|c| {
+            if c.is_ascii_alphanumeric() || matches!(c, '/' | '-' | '_' | '.' | '+') {
+                c
+            } else {
+                '-'
+            }
+        })
+        .collect();
+
+    replaced
+        .split('/')
+        .map(|component| {
+            let mut component = component.trim_start_matches('.').to_string();
+            while component.contains("..") {
+                component = component.replace("..", ".");
+            }
+            while let Some(stripped) = component.strip_suffix(".lock") {
+                component = stripped.to_string();
+            }
+            component.trim_end_matches('.').to_string()
+        })
+        .filter(|component| !component.is_empty())
+        .collect::<Vec<_>>()
+        .join("/")
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buildfix_types::apply::{ApplyRepoInfo, ApplyResult, PlanRef};
+    use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+    use buildfix_types::plan::{PlanOp, PlanPolicy, Rationale, RepoInfo};
+    use buildfix_types::receipt::ToolInfo;
+
+    fn tool() -> ToolInfo {
+        ToolInfo {
+            name: "buildfix".into(),
+            version: None,
+            repo: None,
+            commit: None,
+        }
+    }
+
+    fn op(id: &str, policy_key: &str) -> PlanOp {
+        PlanOp {
+            id: id.into(),
+            safety: SafetyClass::Safe,
+            blocked: false,
+            blocked_reason: None,
+            blocked_reason_token: None,
+            target: OpTarget {
+                path: "Cargo.toml".into(),
+                sha256: None,
+            },
+            kind: OpKind::TomlTransform {
+                rule_id: "ensure_workspace_resolver_v2".into(),
+                args: None,
+            },
+            rationale: Rationale {
+                fix_key: policy_key.into(),
+                description: None,
+                findings: vec![],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: None,
+            workspace: None,
+        }
+    }
+
+    fn fixture(applied: &[&str]) -> (BuildfixPlan, BuildfixApply) {
+        let mut plan = BuildfixPlan::new(
+            tool(),
+            RepoInfo {
+                root: ".".into(),
+                head_sha: None,
+                dirty: None,
+                remote_url: None,
+                ci: None,
+            },
+            PlanPolicy::default(),
+        );
+        plan.ops = vec![
+            op("a", "builddiag/workspace.resolver_v2/not_v2"),
+            op("b", "depguard/deps.path_requires_version/missing_version"),
+            op("c", "custom/some.check/x"),
+        ];
+        let mut apply = BuildfixApply::new(
+            tool(),
+            ApplyRepoInfo {
+                root: ".".into(),
+                head_sha_before: None,
+                head_sha_after: None,
+                dirty_before: None,
+                dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
+            },
+            PlanRef {
+                path: "plan.json".into(),
+                sha256: None,
+                compat: None,
+            },
+        );
+        for o in &plan.ops {
+            apply.results.push(ApplyResult {
+                op_id: o.id.clone(),
+                status: if applied.contains(&o.id.as_str()) {
+                    ApplyStatus::Applied
+                } else {
+                    ApplyStatus::Skipped
+                },
+                message: None,
+                blocked_reason: None,
+                blocked_reason_token: None,
+                files: vec![],
+                duration_ms: None,
+                attempts: None,
+                verified: None,
+            });
+        }
+        (plan, apply)
+    }
+
+    fn date() -> NaiveDate {
+        NaiveDate::from_ymd_opt(2026, 3, 7).expect("date")
+    }
+
+    #[test]
+    fn renders_placeholders_from_applied_ops() {
+        let (plan, apply) = fixture(&["a", "b"]);
+        let name = render_branch_name(
+            DEFAULT_BRANCH_TEMPLATE,
+            &plan,
+            &apply,
+            "0123456789abcdef",
+            date(),
+        )
+        .expect("render");
+        assert_eq!(name, "buildfix/path-dep-version+resolver-v2-20260307");
+
+        let name = render_branch_name("fix/{plan_hash}", &plan, &apply, "0123456789abcdef", date())
+            .expect("render");
+        assert_eq!(name, "fix/0123456789ab");
+    }
+
+    #[test]
+    fn unknown_fix_keys_fall_back_to_check_id() {
+        let (plan, apply) = fixture(&["c"]);
+        let name = render_branch_name("{fix_keys}", &plan, &apply, "", date()).expect("render");
+        assert_eq!(name, "some.check");
+    }
+
+    #[test]
+    fn sanitizes_invalid_ref_characters() {
+        assert_eq!(sanitize_ref_name("a b~c^d:e?f*g[h\\i"), "a-b-c-d-e-f-g-h-i");
+        assert_eq!(sanitize_ref_name("/.hidden//x..y/z.lock/"), "hidden/x.y/z");
+    }
+
+    #[test]
+    fn rejects_bad_templates() {
+        let (plan, apply) = fixture(&[]);
+        let err = render_branch_name("x/{nope}", &plan, &apply, "", date()).unwrap_err();
+        assert!(err.to_string().contains("unknown placeholder {nope}"));
+        let err = render_branch_name("x/{date", &plan, &apply, "", date()).unwrap_err();
+        assert!(err.to_string().contains("unclosed"));
+        let err = render_branch_name("{fix_keys}", &plan, &apply, "", date()).unwrap_err();
+        assert!(err.to_string().contains("empty name"));
+        assert!(validate_branch_template("x/{nope}").is_err());
+        assert!(validate_branch_template(DEFAULT_BRANCH_TEMPLATE).is_ok());
+    }
+}
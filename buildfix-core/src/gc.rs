@@ -0,0 +1,158 @@
+//! Retention for an output directory (`buildfix gc`).
+//!
+//! Removes backup runs beyond the configured count or age and cache files
+//! (parsed receipts, remote `extends` bases) that have not been rewritten
+//! recently. Plans, apply reports and anything outside `backups/` and
+//! `cache/` are never touched.
+
+use anyhow::Context;
+use buildfix_edit::{list_backup_runs, read_backup_manifest};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use std::fs;
+use std::time::{Duration, SystemTime};
+
+/// What to keep. `None` disables that rule.
+#[derive(Debug, Clone, Default)]
+pub struct GcSettings {
+    /// Keep at most this many backup runs, newest first.
+    pub backup_keep_last: Option<usize>,
+    /// Remove backup runs created longer ago than this.
+    pub backup_max_age: Option<Duration>,
+    /// Remove cache files last written longer ago than this.
+    pub cache_max_age: Option<Duration>,
+    /// Report what would be removed without removing it.
+    pub dry_run: bool,
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct GcReport {
+    pub out_dir: Utf8PathBuf,
+    pub dry_run: bool,
+    pub removed: Vec<GcEntry>,
+    pub reclaimed_bytes: u64,
+}
+
+impl GcReport {
+    pub fn count(&self, kind: GcKind) -> usize {
+        self.removed.iter().filter(|e| e.kind == kind).count()
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum GcKind {
+    Backup,
+    Cache,
+}
+
+/// One removed backup run directory or cache file.
+#[derive(Debug, Clone, Serialize)]
+pub struct GcEntry {
+    pub kind: GcKind,
+    pub path: Utf8PathBuf,
+    pub bytes: u64,
+}
+
+/// Apply `settings` to `out_dir`, returning what was (or, with `dry_run`,
+/// would be) removed.
+pub fn collect_garbage(out_dir: &Utf8Path, settings: &GcSettings) -> anyhow::Result<GcReport> {
+    let now = SystemTime::now();
+    let mut removed = Vec::new();
+
+    let runs = list_backup_runs(&out_dir.join("backups"))?;
+    let excess = settings
+        .backup_keep_last
+        .map_or(0, |keep| runs.len().saturating_sub(keep));
+    for (i, run) in runs.iter().enumerate() {
+        let expired = settings
+            .backup_max_age
+            .is_some_and(|max_age| backup_age(&run.dir, now).is_some_and(|age| age > max_age));
+        if i < excess || expired {
+            removed.push(GcEntry {
+                kind: GcKind::Backup,
+                bytes: dir_size(&run.dir)?,
+                path: run.dir.clone(),
+            });
+        }
+    }
+
+    if let Some(max_age) = settings.cache_max_age {
+        let cache_dir = out_dir.join("cache");
+        for path in files_under(&cache_dir)? {
+            let meta = fs::metadata(&path).with_context(|| format!("stat {}", path))?;
+            let age = meta
+                .modified()
+                .ok()
+                .and_then(|modified| now.duration_since(modified).ok());
+            if age.is_some_and(|age| age > max_age) {
+                removed.push(GcEntry {
+                    kind: GcKind::Cache,
+                    path,
+                    bytes: meta.len(),
+                });
+            }
+        }
+    }
+
+    if !settings.dry_run {
+        for entry in &removed {
+            match entry.kind {
+                GcKind::Backup => fs::remove_dir_all(&entry.path)
+                    .with_context(|| format!("remove backup run {}", entry.path))?,
+                GcKind::Cache => fs::remove_file(&entry.path)
+                    .with_context(|| format!("remove cache file {}", entry.path))?,
+            }
+        }
+    }
+
+    Ok(GcReport {
+        out_dir: out_dir.to_path_buf(),
+        dry_run: settings.dry_run,
+        reclaimed_bytes: removed.iter().map(|e| e.bytes).sum(),
+        removed,
+    })
+}
+
+/// Age of a backup run from its manifest's `created_at`; `None` when the
+/// manifest is unreadable, so such runs are only removed by count.
+fn backup_age(run_dir: &Utf8Path, now: SystemTime) -> Option<Duration> {
+    let manifest = read_backup_manifest(run_dir).ok()?;
+    let created = chrono::DateTime::parse_from_rfc3339(&manifest.created_at).ok()?;
+    now.duration_since(SystemTime::from(created)).ok()
+}
+
+fn dir_size(dir: &Utf8Path) -> anyhow::Result<u64> {
+    let mut total = 0;
+    for path in files_under(dir)? {
+        total += fs::metadata(&path)
+            .with_context(|| format!("stat {}", path))?
+            .len();
+    }
+    Ok(total)
+}
+
+/// Every regular file below `dir`, sorted; empty when `dir` does not exist.
+fn files_under(dir: &Utf8Path) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let mut files = Vec::new();
+    if !dir.is_dir() {
+        return Ok(files);
+    }
+    let mut pending = vec![dir.to_path_buf()];
+    while let Some(current) = pending.pop() {
+        for entry in fs::read_dir(&current).with_context(|| format!("read dir {}", current))? {
+            let entry = entry?;
+            let Ok(path) = Utf8PathBuf::from_path_buf(entry.path()) else {
+                continue;
+            };
+            let file_type = entry.file_type()?;
+            if file_type.is_dir() {
+                pending.push(path);
+            } else if file_type.is_file() {
+                files.push(path);
+            }
+        }
+    }
+    files.sort();
+    Ok(files)
+}
@@ -9,22 +9,59 @@
 //! - [`ReceiptSource`](ports::ReceiptSource) — load sensor receipts
 //! - [`GitPort`](ports::GitPort) — query git state
 //! - [`WritePort`](ports::WritePort) — write files and create directories
+//! - [`ProgressPort`](ports::ProgressPort) — follow phases, fixers and ops as a run goes
+//! - [`ClockPort`](ports::ClockPort), [`IdPort`](ports::IdPort) — time and ids stamped into artifacts
+//! - [`MetricsPort`](ports::MetricsPort) — op counts, run durations and patch sizes; see [`metrics`]
+//! - [`ForgePort`](ports::ForgePort) — open pull/merge requests on GitHub, GitLab or Bitbucket
 //!
 //! The [`adapters`] module provides default filesystem-backed implementations.
 //!
 //! # Entry points
 //!
 //! - [`run_plan`](pipeline::run_plan) — generate a plan + report
+//! - [`run_plan_with_repo`](pipeline::run_plan_with_repo) — the same, reading manifests through a caller's [`RepoView`]
 //! - [`run_apply`](pipeline::run_apply) — apply an existing plan + report
+//! - `async_pipeline::{run_plan_async, run_apply_async}` — the same over async ports (`async` feature)
+//! - [`run_plan_with_progress`](pipeline::run_plan_with_progress), [`run_apply_with_progress`](pipeline::run_apply_with_progress) — plan or apply, reporting phases, fixers and ops
+//! - [`run_apply_patch`](pipeline::run_apply_patch) — apply an emitted `patch.diff` directly
+//! - [`run_sandbox_apply`](sandbox::run_sandbox_apply) — apply and verify in a throwaway git worktree
+//! - [`merge_plans`] — combine plans made from several artifact sets into one
+//! - [`collect_status`](status::collect_status) — inspect an output directory without changing it
+//! - [`verify_apply`](verify::verify_apply) — re-check files an apply wrote
+//! - [`render_branch_name`](branch::render_branch_name) — name the branch `apply --branch` creates
+//! - [`collect_garbage`](gc::collect_garbage) — prune old backups and caches from an output directory
+//! - [`detect_ci_run`](provenance::detect_ci_run) — identify the CI run recorded in plan and apply artifacts
+//! - [`reproducible_sources`](provenance::reproducible_sources) — fix time and ids from `SOURCE_DATE_EPOCH`
+//!
+//! # Cancellation
+//!
+//! Both settings carry a [`CancellationToken`](settings::CancellationToken).
+//! Cancelling it stops a plan before its next fixer and an apply before its
+//! next op; the report records a `cancelled` verdict reason.
 
 pub mod adapters;
+#[cfg(feature = "async")]
+pub mod async_pipeline;
+pub mod branch;
+pub mod gc;
 pub mod pipeline;
 pub mod ports;
+pub mod provenance;
+pub mod sandbox;
 pub mod settings;
+pub mod status;
+mod timing;
+pub mod verify;
+
+// Metric names and the Prometheus text exporter.
+pub use buildfix_core_runtime::metrics;
 
 // Re-export the domain's RepoView so callers don't need buildfix-domain directly.
-pub use buildfix_domain::RepoView;
-pub use buildfix_domain::builtin_fixer_metas;
+pub use buildfix_domain::{FixerMeta, TriggerPattern, builtin_fixer_metas};
+pub use buildfix_domain::{GitignoreMatcher, RepoView};
+
+// Re-export plan merging for `buildfix merge-plans` and embedders.
+pub use buildfix_domain::merge_plans;
 
 // Re-export receipt types so embedders don't need buildfix-receipts directly.
 pub use buildfix_receipts::{LoadedReceipt, ReceiptEnvelope, ReceiptLoadError};
@@ -1 +1,6 @@
-pub use buildfix_core_runtime::{GitPort, ReceiptSource, WritePort};
+#[cfg(feature = "async")]
+pub use buildfix_core_runtime::{AsyncGitPort, AsyncReceiptSource, AsyncWritePort};
+pub use buildfix_core_runtime::{
+    ChangeRequest, ChangeRequestRef, CheckConclusion, ClockPort, CommentRef, CommitCheck,
+    ForgePort, GitPort, IdPort, MetricsPort, ProgressPort, ReceiptSource, WritePort,
+};
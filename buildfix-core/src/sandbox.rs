@@ -0,0 +1,249 @@
+//! Sandbox apply (`apply --sandbox`).
+//!
+//! Checks HEAD out into a throwaway linked worktree, applies the plan there
+//! and verifies the result, so CI can prove a fix holds up without writing
+//! to the primary checkout. Uncommitted changes in the primary checkout are
+//! not carried over: plan preconditions are checked against HEAD.
+
+use crate::pipeline::{ApplyOutcome, ToolError, run_apply};
+use crate::ports::GitPort;
+use crate::settings::ApplySettings;
+use crate::verify::{VerifyCheck, VerifyReport, verify_applied};
+use anyhow::Context;
+use buildfix_types::receipt::ToolInfo;
+use buildfix_types::telemetry;
+use camino::{Utf8Path, Utf8PathBuf};
+use tracing::{debug, warn};
+
+pub struct SandboxOutcome {
+    pub apply: ApplyOutcome,
+    pub verify: VerifyReport,
+    /// The worktree, when it was kept for inspection.
+    pub kept_worktree: Option<Utf8PathBuf>,
+}
+
+/// Apply `settings`' plan in a fresh worktree of HEAD, verify the written
+/// files, then run `extra_checks` (e.g. `cargo check`) against the
+/// worktree root.
+///
+/// Commit, branch, push and backup settings are ignored; the worktree is
+/// removed afterwards unless `keep` is set.
+pub fn run_sandbox_apply(
+    settings: &ApplySettings,
+    git: &dyn GitPort,
+    tool: ToolInfo,
+    extra_checks: &dyn Fn(&Utf8Path) -> Vec<VerifyCheck>,
+    keep: bool,
+) -> Result<SandboxOutcome, ToolError> {
+    let repo_root = absolute(&settings.repo_root)?;
+    let out_dir = absolute(&settings.out_dir)?;
+    let plan_path = absolute(
+        &settings
+            .plan_path
+            .clone()
+            .unwrap_or_else(|| settings.out_dir.join("plan.json")),
+    )?;
+
+    let dir = Utf8PathBuf::from_path_buf(std::env::temp_dir())
+        .map_err(|p| anyhow::anyhow!("temp dir {} is not UTF-8", p.display()))?
+        .join(format!(
+            "buildfix-sandbox-{}",
+            uuid::Uuid::new_v4().simple()
+        ));
+    let worktree = dir.join("worktree");
+    std::fs::create_dir_all(&dir).with_context(|| format!("create {}", dir))?;
+    if !git.add_worktree(&repo_root, &worktree)? {
+        let _ = std::fs::remove_dir_all(&dir);
+        return Err(anyhow::anyhow!("--sandbox needs git to create a worktree").into());
+    }
+    debug!(
+        event = telemetry::SANDBOX_CREATED,
+        file = %worktree,
+        "sandbox worktree created"
+    );
+
+    let sandbox_settings = ApplySettings {
+        repo_root: worktree.clone(),
+        out_dir: dir.join("out"),
+        plan_path: Some(plan_path),
+        dry_run: false,
+        allow_dirty: false,
+        auto_commit: false,
+        commit_message: None,
+        branch: None,
+        push_remote: None,
+        backup_enabled: false,
+        ..settings.clone()
+    };
+    let result = run_apply(&sandbox_settings, git, tool).map(|apply| {
+        let mut verify = verify_applied(&worktree, out_dir.join("apply.json"), &apply.apply);
+        for check in extra_checks(&worktree) {
+            verify.push(check);
+        }
+        (apply, verify)
+    });
+
+    let kept_worktree = if keep {
+        Some(worktree)
+    } else {
+        if let Err(e) = git.remove_worktree(&repo_root, &worktree) {
+            warn!(
+                event = telemetry::SANDBOX_CLEANUP_FAILED,
+                file = %worktree,
+                error = format!("{:#}", e),
+                "failed to remove sandbox worktree"
+            );
+        }
+        if let Err(e) = std::fs::remove_dir_all(&dir) {
+            warn!(
+                event = telemetry::SANDBOX_CLEANUP_FAILED,
+                file = %dir,
+                error = %e,
+                "failed to remove sandbox directory"
+            );
+        }
+        None
+    };
+
+    let (apply, verify) = result?;
+    Ok(SandboxOutcome {
+        apply,
+        verify,
+        kept_worktree,
+    })
+}
+
+fn absolute(path: &Utf8Path) -> anyhow::Result<Utf8PathBuf> {
+    if path.is_absolute() {
+        return Ok(path.to_path_buf());
+    }
+    let cwd = std::env::current_dir().context("current dir")?;
+    let cwd = Utf8PathBuf::from_path_buf(cwd)
+        .map_err(|p| anyhow::anyhow!("current dir {} is not UTF-8", p.display()))?;
+    Ok(cwd.join(path))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::adapters::ShellGitPort;
+    use crate::verify::VerifyCheckKind;
+    use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+    use buildfix_types::plan::{BuildfixPlan, PlanOp, PlanPolicy, Rationale, RepoInfo};
+    use buildfix_types::wire::PlanV1;
+    use std::process::Command;
+
+    fn tool() -> ToolInfo {
+        ToolInfo {
+            name: "buildfix".into(),
+            version: Some("0.0.0".into()),
+            repo: None,
+            commit: None,
+        }
+    }
+
+    fn git(root: &Utf8Path, args: &[&str]) -> String {
+        let out = Command::new("git")
+            .args(["-c", "user.name=t", "-c", "user.email=t@example.com"])
+            .args(args)
+            .current_dir(root)
+            .output()
+            .expect("git");
+        assert!(out.status.success(), "git {:?}", args);
+        String::from_utf8_lossy(&out.stdout).into_owned()
+    }
+
+    fn resolver_plan() -> String {
+        let mut plan = BuildfixPlan::new(
+            tool(),
+            RepoInfo {
+                root: ".".into(),
+                head_sha: None,
+                dirty: None,
+                remote_url: None,
+                ci: None,
+            },
+            PlanPolicy::default(),
+        );
+        plan.ops = vec![PlanOp {
+            id: "resolver".into(),
+            safety: SafetyClass::Safe,
+            blocked: false,
+            blocked_reason: None,
+            blocked_reason_token: None,
+            target: OpTarget {
+                path: "Cargo.toml".into(),
+                sha256: None,
+            },
+            kind: OpKind::TomlSet {
+                toml_path: vec!["workspace".into(), "resolver".into()],
+                value: serde_json::json!("2"),
+                insert: None,
+            },
+            rationale: Rationale {
+                fix_key: "test".into(),
+                description: None,
+                findings: vec![],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: None,
+            workspace: None,
+        }];
+        serde_json::to_string(&PlanV1::try_from(&plan).expect("wire")).expect("json")
+    }
+
+    #[test]
+    fn applies_and_verifies_without_touching_the_checkout() {
+        let temp = tempfile::tempdir().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        std::fs::write(root.join("Cargo.toml"), "[workspace]\nresolver = \"1\"\n").expect("write");
+        std::fs::write(root.join(".gitignore"), "artifacts/\n").expect("write");
+        git(&root, &["init", "-q"]);
+        git(&root, &["add", "-A"]);
+        git(&root, &["commit", "-qm", "init"]);
+        let out_dir = root.join("artifacts/buildfix");
+        std::fs::create_dir_all(&out_dir).expect("mkdir");
+        std::fs::write(out_dir.join("plan.json"), resolver_plan()).expect("write plan");
+
+        let settings = ApplySettings {
+            repo_root: root.clone(),
+            out_dir: out_dir.clone(),
+            backup_enabled: true,
+            ..ApplySettings::default()
+        };
+        let seen = std::cell::RefCell::new(String::new());
+        let outcome = run_sandbox_apply(
+            &settings,
+            &ShellGitPort,
+            tool(),
+            &|worktree| {
+                *seen.borrow_mut() =
+                    std::fs::read_to_string(worktree.join("Cargo.toml")).expect("read");
+                vec![VerifyCheck {
+                    kind: VerifyCheckKind::Command,
+                    path: None,
+                    ok: true,
+                    message: None,
+                }]
+            },
+            false,
+        )
+        .expect("sandbox apply");
+
+        assert_eq!(outcome.apply.apply.summary.applied, 1);
+        assert!(outcome.verify.ok);
+        assert_eq!(outcome.verify.checks.len(), 3);
+        assert_eq!(outcome.verify.apply_path, out_dir.join("apply.json"));
+        assert!(seen.borrow().contains("resolver = \"2\""));
+        assert!(outcome.kept_worktree.is_none());
+
+        assert_eq!(
+            std::fs::read_to_string(root.join("Cargo.toml")).expect("read"),
+            "[workspace]\nresolver = \"1\"\n"
+        );
+        assert!(git(&root, &["status", "--porcelain"]).is_empty());
+        assert_eq!(git(&root, &["worktree", "list"]).lines().count(), 1);
+        assert!(!out_dir.join("backups").exists());
+    }
+}
@@ -1 +1,3 @@
-pub use buildfix_core_runtime::{ApplySettings, PlanSettings, RunMode};
+pub use buildfix_core_runtime::{
+    ApplySettings, CancellationToken, Clock, FixerSettings, Ids, Metrics, PlanSettings, RunMode,
+};
@@ -0,0 +1,227 @@
+//! Read-only inspection of an output directory (`buildfix status`).
+//!
+//! Answers whether a plan exists, whether it is stale against HEAD and the
+//! receipts it was built from, whether it was applied, and whether the files
+//! it pinned have drifted since.
+
+use crate::pipeline::{parse_plan, same_commit};
+use crate::ports::{GitPort, ReceiptSource};
+use anyhow::Context;
+use buildfix_edit::list_backup_runs;
+use buildfix_hash::sha256_hex;
+use buildfix_types::apply::BuildfixApply;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use std::collections::{BTreeMap, BTreeSet};
+use std::time::SystemTime;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct RunStatus {
+    pub out_dir: Utf8PathBuf,
+    /// `None` when there is no `plan.json`.
+    pub plan: Option<PlanState>,
+    /// `None` when there is no `apply.json`.
+    pub apply: Option<ApplyState>,
+    pub backups: BackupState,
+}
+
+impl RunStatus {
+    /// A plan exists but HEAD, its receipts, or its pinned files moved on.
+    pub fn is_stale(&self) -> bool {
+        self.plan.as_ref().is_some_and(PlanState::is_stale)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct PlanState {
+    pub path: Utf8PathBuf,
+    pub sha256: String,
+    pub ops_total: u64,
+    pub ops_blocked: u64,
+    pub files_touched: u64,
+    /// HEAD recorded in the plan.
+    pub head_sha: Option<String>,
+    /// HEAD now, if the repo is a git checkout.
+    pub current_head_sha: Option<String>,
+    pub head_changed: bool,
+    /// Inputs modified after plan.json was written.
+    pub receipts_changed: Vec<String>,
+    /// Inputs that no longer exist.
+    pub receipts_missing: Vec<String>,
+    /// Receipts present now that the plan did not read.
+    pub receipts_added: Vec<String>,
+    /// Precondition files whose contents differ from both the planned hash
+    /// and what the last apply wrote.
+    pub drifted_files: Vec<String>,
+}
+
+impl PlanState {
+    pub fn is_stale(&self) -> bool {
+        self.head_changed
+            || !self.receipts_changed.is_empty()
+            || !self.receipts_missing.is_empty()
+            || !self.receipts_added.is_empty()
+            || !self.drifted_files.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct ApplyState {
+    pub path: Utf8PathBuf,
+    /// apply.json references the current plan.json (by sha256).
+    pub matches_plan: bool,
+    /// At least one op was written; otherwise the apply was a dry run or
+    /// everything was blocked.
+    pub wrote_changes: bool,
+    pub applied: u64,
+    pub blocked: u64,
+    pub failed: u64,
+    pub files_modified: u64,
+    pub commit_sha: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct BackupState {
+    pub runs: usize,
+    pub latest: Option<String>,
+}
+
+/// Inspect `out_dir` and the repo. Missing artifacts are reported as absent,
+/// not as errors; unreadable or malformed ones are errors.
+pub fn collect_status(
+    repo_root: &Utf8Path,
+    out_dir: &Utf8Path,
+    receipts: &dyn ReceiptSource,
+    git: &dyn GitPort,
+) -> anyhow::Result<RunStatus> {
+    let apply_path = out_dir.join("apply.json");
+    let apply = if apply_path.is_file() {
+        let contents =
+            std::fs::read_to_string(&apply_path).with_context(|| format!("read {}", apply_path))?;
+        let apply: BuildfixApply =
+            serde_json::from_str(&contents).with_context(|| format!("parse {}", apply_path))?;
+        Some(apply)
+    } else {
+        None
+    };
+
+    let plan_path = out_dir.join("plan.json");
+    let plan = if plan_path.is_file() {
+        Some(plan_state(
+            repo_root,
+            &plan_path,
+            apply.as_ref(),
+            receipts,
+            git,
+        )?)
+    } else {
+        None
+    };
+
+    let apply = apply.map(|a| ApplyState {
+        path: apply_path,
+        matches_plan: plan
+            .as_ref()
+            .is_some_and(|p| a.plan_ref.sha256.as_deref() == Some(p.sha256.as_str())),
+        wrote_changes: a.summary.applied > 0,
+        applied: a.summary.applied,
+        blocked: a.summary.blocked,
+        failed: a.summary.failed,
+        files_modified: a.summary.files_modified,
+        commit_sha: a.auto_commit.and_then(|c| c.commit_sha),
+    });
+
+    let runs = list_backup_runs(&out_dir.join("backups"))?;
+    let backups = BackupState {
+        runs: runs.len(),
+        latest: runs.last().map(|r| r.id.clone()),
+    };
+
+    Ok(RunStatus {
+        out_dir: out_dir.to_path_buf(),
+        plan,
+        apply,
+        backups,
+    })
+}
+
+fn plan_state(
+    repo_root: &Utf8Path,
+    plan_path: &Utf8Path,
+    apply: Option<&BuildfixApply>,
+    receipts: &dyn ReceiptSource,
+    git: &dyn GitPort,
+) -> anyhow::Result<PlanState> {
+    let contents =
+        std::fs::read_to_string(plan_path).with_context(|| format!("read {}", plan_path))?;
+    let plan = parse_plan(&contents).with_context(|| format!("parse {}", plan_path))?;
+    let sha256 = sha256_hex(contents.as_bytes());
+    let planned_at = modified(plan_path);
+
+    let current_head_sha = git.head_sha(repo_root).ok().flatten();
+    let head_changed = match (&plan.repo.head_sha, &current_head_sha) {
+        (Some(planned), Some(current)) => !same_commit(planned, current),
+        _ => false,
+    };
+
+    let mut receipts_changed = Vec::new();
+    let mut receipts_missing = Vec::new();
+    for input in &plan.inputs {
+        let path = Utf8Path::new(&input.path);
+        if !path.exists() {
+            receipts_missing.push(input.path.clone());
+        } else if let (Some(receipt), Some(plan)) = (modified(path), planned_at)
+            && receipt > plan
+        {
+            receipts_changed.push(input.path.clone());
+        }
+    }
+
+    let known: BTreeSet<&str> = plan.inputs.iter().map(|i| i.path.as_str()).collect();
+    let receipts_added = receipts
+        .load_receipts()?
+        .into_iter()
+        .map(|r| r.path.to_string())
+        .filter(|p| !known.contains(p.as_str()))
+        .collect();
+
+    // A file the last apply wrote is expected to differ from the plan.
+    let written: BTreeMap<&str, &str> = apply
+        .filter(|a| a.plan_ref.sha256.as_deref() == Some(sha256.as_str()))
+        .into_iter()
+        .flat_map(|a| &a.results)
+        .flat_map(|r| &r.files)
+        .filter_map(|f| Some((f.path.as_str(), f.sha256_after.as_deref()?)))
+        .collect();
+    let drifted_files = plan
+        .preconditions
+        .files
+        .iter()
+        .filter(|pre| {
+            let actual = std::fs::read(repo_root.join(&pre.path))
+                .map(|bytes| sha256_hex(&bytes))
+                .unwrap_or_default();
+            actual != pre.sha256 && written.get(pre.path.as_str()) != Some(&actual.as_str())
+        })
+        .map(|pre| pre.path.clone())
+        .collect();
+
+    Ok(PlanState {
+        path: plan_path.to_path_buf(),
+        sha256,
+        ops_total: plan.summary.ops_total,
+        ops_blocked: plan.summary.ops_blocked,
+        files_touched: plan.summary.files_touched,
+        head_sha: plan.repo.head_sha,
+        current_head_sha,
+        head_changed,
+        receipts_changed,
+        receipts_missing,
+        receipts_added,
+        drifted_files,
+    })
+}
+
+fn modified(path: &Utf8Path) -> Option<SystemTime> {
+    std::fs::metadata(path).and_then(|m| m.modified()).ok()
+}
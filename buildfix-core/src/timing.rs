@@ -0,0 +1,75 @@
+//! Run and per-phase timings stamped into reports.
+
+use crate::ports::ProgressPort;
+use buildfix_types::clock::Clock;
+use buildfix_types::progress::{Phase, Progress};
+use buildfix_types::report::BuildfixReport;
+use chrono::{DateTime, Utc};
+use std::cell::RefCell;
+use std::collections::BTreeMap;
+
+/// Forwards progress to the host's port while timing each phase against
+/// `clock`. A phase runs from its [`Progress::Phase`] event until the next
+/// one, or until [`finish`](Self::finish).
+pub(crate) struct PhaseTimer<'a> {
+    clock: &'a Clock,
+    inner: &'a dyn ProgressPort,
+    started_at: DateTime<Utc>,
+    current: RefCell<Option<(Phase, DateTime<Utc>)>>,
+    phases_ms: RefCell<BTreeMap<&'static str, u64>>,
+}
+
+impl<'a> PhaseTimer<'a> {
+    /// Start timing the run now.
+    pub(crate) fn start(clock: &'a Clock, inner: &'a dyn ProgressPort) -> Self {
+        Self {
+            clock,
+            inner,
+            started_at: clock.now(),
+            current: RefCell::new(None),
+            phases_ms: RefCell::new(BTreeMap::new()),
+        }
+    }
+
+    /// End the run now: close the current phase, stamp `report.run` with the
+    /// real start, end and duration, and record the phases under
+    /// `data.buildfix.phases_ms`. Call last, so building the report is timed
+    /// too.
+    pub(crate) fn finish(self, report: &mut BuildfixReport) {
+        let ended_at = self.clock.now();
+        self.close(ended_at);
+        report.run.started_at = self.started_at.to_rfc3339();
+        report.run.ended_at = Some(ended_at.to_rfc3339());
+        report.run.duration_ms = Some(millis(self.started_at, ended_at));
+        let data = report
+            .data
+            .get_or_insert_with(|| serde_json::json!({ "buildfix": {} }));
+        data["buildfix"]["phases_ms"] = serde_json::json!(self.phases_ms.into_inner());
+    }
+
+    fn close(&self, at: DateTime<Utc>) {
+        if let Some((phase, since)) = self.current.borrow_mut().take() {
+            *self
+                .phases_ms
+                .borrow_mut()
+                .entry(phase.as_str())
+                .or_default() += millis(since, at);
+        }
+    }
+}
+
+impl ProgressPort for PhaseTimer<'_> {
+    fn report(&self, progress: &Progress<'_>) {
+        if let Progress::Phase(phase) = progress {
+            let now = self.clock.now();
+            self.close(now);
+            *self.current.borrow_mut() = Some((*phase, now));
+        }
+        self.inner.report(progress);
+    }
+}
+
+/// Whole milliseconds from `from` to `to`; zero if the clock went backwards.
+fn millis(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    u64::try_from((to - from).num_milliseconds()).unwrap_or(0)
+}
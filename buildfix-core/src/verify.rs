@@ -0,0 +1,134 @@
+//! Post-apply health check (`buildfix verify`).
+//!
+//! Re-hashes every file an apply wrote against the `sha256_after` recorded in
+//! `apply.json`, and confirms the TOML manifests among them still parse.
+//! Hosts can [`push`](VerifyReport::push) further checks, such as running
+//! `cargo metadata`.
+
+use anyhow::Context;
+use buildfix_hash::sha256_hex;
+use buildfix_types::apply::{ApplyStatus, BuildfixApply};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Serialize;
+use std::collections::BTreeMap;
+use toml_edit::DocumentMut;
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyReport {
+    pub apply_path: Utf8PathBuf,
+    /// False once any check has failed.
+    pub ok: bool,
+    pub checks: Vec<VerifyCheck>,
+}
+
+impl VerifyReport {
+    pub fn push(&mut self, check: VerifyCheck) {
+        self.ok &= check.ok;
+        self.checks.push(check);
+    }
+
+    pub fn failures(&self) -> impl Iterator<Item = &VerifyCheck> {
+        self.checks.iter().filter(|c| !c.ok)
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct VerifyCheck {
+    pub kind: VerifyCheckKind,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+    pub ok: bool,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum VerifyCheckKind {
+    /// File contents match the hash the apply recorded.
+    FileHash,
+    /// A written TOML file still parses.
+    ManifestParse,
+    /// `cargo metadata` succeeds for the workspace.
+    CargoMetadata,
+    /// A host-supplied command (e.g. `cargo check`) exits successfully.
+    Command,
+}
+
+/// Check the files recorded in `<out_dir>/apply.json`. Only ops with status
+/// `applied` are considered, so a dry-run apply yields no checks.
+pub fn verify_apply(repo_root: &Utf8Path, out_dir: &Utf8Path) -> anyhow::Result<VerifyReport> {
+    let apply_path = out_dir.join("apply.json");
+    let contents =
+        std::fs::read_to_string(&apply_path).with_context(|| format!("read {}", apply_path))?;
+    let apply: BuildfixApply =
+        serde_json::from_str(&contents).with_context(|| format!("parse {}", apply_path))?;
+    Ok(verify_applied(repo_root, apply_path, &apply))
+}
+
+/// Check the files `apply` wrote under `repo_root`, reporting them as
+/// coming from `apply_path`.
+pub fn verify_applied(
+    repo_root: &Utf8Path,
+    apply_path: Utf8PathBuf,
+    apply: &BuildfixApply,
+) -> VerifyReport {
+    // Later ops on the same file supersede earlier ones.
+    let mut expected: BTreeMap<&str, &str> = BTreeMap::new();
+    for result in apply
+        .results
+        .iter()
+        .filter(|r| r.status == ApplyStatus::Applied)
+    {
+        for file in &result.files {
+            if let Some(sha) = &file.sha256_after {
+                expected.insert(&file.path, sha);
+            }
+        }
+    }
+
+    let mut report = VerifyReport {
+        apply_path,
+        ok: true,
+        checks: Vec::new(),
+    };
+    for (path, sha) in expected {
+        let current = std::fs::read(repo_root.join(path)).ok();
+        report.push(hash_check(path, sha, current.as_deref()));
+        if path.ends_with(".toml")
+            && let Some(current) = &current
+        {
+            report.push(parse_check(path, &String::from_utf8_lossy(current)));
+        }
+    }
+    report
+}
+
+fn hash_check(path: &str, expected: &str, current: Option<&[u8]>) -> VerifyCheck {
+    let message = match current {
+        None => Some("file is missing".to_string()),
+        Some(contents) => {
+            let actual = sha256_hex(contents);
+            (actual != expected).then(|| format!("expected sha256 {}, found {}", expected, actual))
+        }
+    };
+    VerifyCheck {
+        kind: VerifyCheckKind::FileHash,
+        path: Some(path.to_string()),
+        ok: message.is_none(),
+        message,
+    }
+}
+
+fn parse_check(path: &str, contents: &str) -> VerifyCheck {
+    let message = contents
+        .parse::<DocumentMut>()
+        .err()
+        .map(|e| e.message().to_string());
+    VerifyCheck {
+        kind: VerifyCheckKind::ManifestParse,
+        path: Some(path.to_string()),
+        ok: message.is_none(),
+        message,
+    }
+}
@@ -5,7 +5,9 @@
 
 use buildfix_core::RepoView;
 use buildfix_core::ports::{GitPort, ReceiptSource, WritePort};
-use buildfix_core::settings::{ApplySettings, PlanSettings, RunMode};
+use buildfix_core::settings::{
+    ApplySettings, CancellationToken, Clock, Ids, Metrics, PlanSettings, RunMode,
+};
 use buildfix_receipts::{LoadedReceipt, ReceiptLoadError};
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
 use buildfix_types::plan::{PlanOp, PlanPolicy, Rationale, RepoInfo};
@@ -74,6 +76,7 @@ mod settings_tests {
             out_dir: Utf8PathBuf::from("/custom/out"),
             allow: vec!["fix1".to_string(), "fix2".to_string()],
             deny: vec!["fix3".to_string()],
+            path_policies: Vec::new(),
             allow_guarded: true,
             allow_unsafe: true,
             allow_dirty: true,
@@ -81,10 +84,29 @@ mod settings_tests {
             max_files: Some(10),
             max_patch_bytes: Some(1024),
             params,
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_since: None,
+            staged_only: false,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
             require_clean_hashes: false,
             git_head_precondition: true,
+            pin_op_targets: false,
             backup_suffix: ".bak".to_string(),
+            binary_patch: false,
             mode: RunMode::Cockpit,
+            ci_run: None,
+            cancel: CancellationToken::new(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            metrics: Metrics::default(),
+            jobs: 0,
+            precondition_mode: Default::default(),
+            scan: Default::default(),
+            plan_ttl: None,
         };
 
         assert_eq!(settings.repo_root.as_str(), "/custom/root");
@@ -113,16 +135,33 @@ mod settings_tests {
         let settings = ApplySettings {
             repo_root: Utf8PathBuf::from("/repo"),
             out_dir: Utf8PathBuf::from("/out"),
+            plan_path: None,
             dry_run: false,
             allow_guarded: true,
             allow_unsafe: true,
             allow_dirty: true,
+            dirty_scope: Default::default(),
             params,
             auto_commit: true,
             commit_message: Some("custom message".to_string()),
+            branch: None,
+            push_remote: None,
+            op_ids: Vec::new(),
+            only_fix_keys: Vec::new(),
+            skip_fix_keys: Vec::new(),
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
             backup_enabled: false,
             backup_suffix: ".backup".to_string(),
+            backup_keep_last: None,
+            durable_writes: false,
+            binary_patch: false,
             mode: RunMode::Cockpit,
+            ci_run: None,
+            cancel: CancellationToken::new(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            metrics: Metrics::default(),
         };
 
         assert_eq!(settings.repo_root.as_str(), "/repo");
@@ -466,7 +505,7 @@ mod reexport_tests {
 mod pipeline_tests {
     use super::*;
     use buildfix_core::adapters::InMemoryReceiptSource;
-    use buildfix_core::pipeline::{ToolError, run_apply, run_plan};
+    use buildfix_core::pipeline::{ToolError, run_apply, run_apply_patch, run_plan};
 
     /// Stub GitPort for pipeline tests
     struct StubGitPort {
@@ -560,6 +599,7 @@ mod pipeline_tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }
     }
 
@@ -570,6 +610,7 @@ mod pipeline_tests {
             out_dir: root.join("artifacts/buildfix"),
             allow: Vec::new(),
             deny: Vec::new(),
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -577,10 +618,29 @@ mod pipeline_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_since: None,
+            staged_only: false,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
             require_clean_hashes: true,
             git_head_precondition: false,
+            pin_op_targets: false,
             backup_suffix: ".buildfix.bak".to_string(),
+            binary_patch: false,
             mode: RunMode::Standalone,
+            ci_run: None,
+            cancel: CancellationToken::new(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            metrics: Metrics::default(),
+            jobs: 0,
+            precondition_mode: Default::default(),
+            scan: Default::default(),
+            plan_ttl: None,
         }
     }
 
@@ -588,16 +648,33 @@ mod pipeline_tests {
         ApplySettings {
             repo_root: root.to_path_buf(),
             out_dir: out_dir.to_path_buf(),
+            plan_path: None,
             dry_run: true,
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
+            dirty_scope: Default::default(),
             params: HashMap::new(),
             auto_commit: false,
             commit_message: None,
+            branch: None,
+            push_remote: None,
+            op_ids: Vec::new(),
+            only_fix_keys: Vec::new(),
+            skip_fix_keys: Vec::new(),
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
             backup_enabled: false,
             backup_suffix: ".buildfix.bak".to_string(),
+            backup_keep_last: None,
+            durable_writes: false,
+            binary_patch: false,
             mode: RunMode::Standalone,
+            ci_run: None,
+            cancel: CancellationToken::new(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            metrics: Metrics::default(),
         }
     }
 
@@ -608,6 +685,8 @@ mod pipeline_tests {
                 root: ".".into(),
                 head_sha: None,
                 dirty: None,
+                remote_url: None,
+                ci: None,
             },
             PlanPolicy::default(),
         );
@@ -625,10 +704,12 @@ mod pipeline_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["workspace".into(), "resolver".into()],
                 value: serde_json::json!("2"),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "test".into(),
@@ -637,10 +718,12 @@ mod pipeline_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }
     }
 
-    use buildfix_types::plan::BuildfixPlan;
+    use buildfix_types::plan::{BuildfixPlan, FilePrecondition};
 
     #[test]
     fn plan_outcome_contains_expected_fields() {
@@ -738,7 +821,13 @@ mod pipeline_tests {
 
         let writer = MemWritePort::default();
         let out_dir = Utf8PathBuf::from("out");
-        buildfix_core::pipeline::write_plan_artifacts(&outcome, &out_dir, &writer).unwrap();
+        buildfix_core::pipeline::write_plan_artifacts(
+            &outcome,
+            &buildfix_core::pipeline::MarkdownTemplates::default(),
+            &out_dir,
+            &writer,
+        )
+        .unwrap();
 
         let files = writer.files.lock().unwrap();
         assert!(files.contains_key("out/plan.json"));
@@ -880,14 +969,116 @@ mod pipeline_tests {
 
         let writer = MemWritePort::default();
         let out_dir = Utf8PathBuf::from("out");
-        buildfix_core::pipeline::write_apply_artifacts(&outcome, &out_dir, &writer).unwrap();
+        buildfix_core::pipeline::write_apply_artifacts(
+            &outcome,
+            &buildfix_core::pipeline::MarkdownTemplates::default(),
+            &out_dir,
+            &writer,
+        )
+        .unwrap();
 
         let files = writer.files.lock().unwrap();
         assert!(files.contains_key("out/apply.json"));
         assert!(files.contains_key("out/apply.md"));
+        let junit = String::from_utf8(files["out/apply.junit.xml"].clone()).unwrap();
+        assert!(junit.contains("<testsuites name=\"buildfix apply\" tests=\"1\""));
         assert!(files.contains_key("out/patch.diff"));
         assert!(files.contains_key("out/report.json"));
     }
+
+    const RESOLVER_PATCH: &str = "diff --git a/Cargo.toml b/Cargo.toml\n\
+        --- a/Cargo.toml\n\
+        +++ b/Cargo.toml\n\
+        @@ -1,2 +1,2 @@\n \
+        [workspace]\n\
+        -resolver = \"1\"\n\
+        +resolver = \"2\"\n";
+
+    #[test]
+    fn apply_patch_writes_with_backup() {
+        let (_temp, root) = create_temp_repo("[workspace]\nresolver = \"1\"\n");
+        let out_dir = root.join("artifacts").join("buildfix");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let patch_path = out_dir.join("patch.diff");
+        std::fs::write(&patch_path, RESOLVER_PATCH).unwrap();
+
+        let mut settings = make_apply_settings(&root, &out_dir);
+        settings.dry_run = false;
+        settings.backup_enabled = true;
+        let git = StubGitPort::default();
+
+        let outcome = run_apply_patch(&settings, &patch_path, &git).unwrap();
+
+        assert!(!outcome.policy_block);
+        assert_eq!(outcome.result.files.len(), 1);
+        assert!(outcome.result.backup_run.is_some());
+        assert_eq!(
+            std::fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            "[workspace]\nresolver = \"2\"\n"
+        );
+    }
+
+    #[test]
+    fn apply_patch_honors_plan_preconditions() {
+        let (_temp, root) = create_temp_repo("[workspace]\nresolver = \"1\"\n");
+        let out_dir = root.join("artifacts").join("buildfix");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let patch_path = out_dir.join("patch.diff");
+        std::fs::write(&patch_path, RESOLVER_PATCH).unwrap();
+
+        let mut plan = make_plan(vec![make_op(SafetyClass::Safe, false)]);
+        plan.preconditions.files.push(FilePrecondition {
+            path: "Cargo.toml".into(),
+            sha256: "0".repeat(64),
+            semantic_sha256: None,
+        });
+        let plan_wire = PlanV1::try_from(&plan).unwrap();
+        std::fs::write(
+            out_dir.join("plan.json"),
+            serde_json::to_string_pretty(&plan_wire).unwrap(),
+        )
+        .unwrap();
+
+        let mut settings = make_apply_settings(&root, &out_dir);
+        settings.dry_run = false;
+        let git = StubGitPort::default();
+
+        let outcome = run_apply_patch(&settings, &patch_path, &git).unwrap();
+
+        assert!(outcome.policy_block);
+        assert_eq!(
+            outcome.result.preconditions.mismatches[0].path,
+            "Cargo.toml"
+        );
+        assert_eq!(
+            std::fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            "[workspace]\nresolver = \"1\"\n"
+        );
+    }
+
+    #[test]
+    fn apply_patch_blocks_on_dirty_tree() {
+        let (_temp, root) = create_temp_repo("[workspace]\nresolver = \"1\"\n");
+        let out_dir = root.join("artifacts").join("buildfix");
+        std::fs::create_dir_all(&out_dir).unwrap();
+        let patch_path = out_dir.join("patch.diff");
+        std::fs::write(&patch_path, RESOLVER_PATCH).unwrap();
+
+        let mut settings = make_apply_settings(&root, &out_dir);
+        settings.dry_run = false;
+        let git = StubGitPort {
+            head: Some("abc".to_string()),
+            dirty: Some(true),
+        };
+
+        let outcome = run_apply_patch(&settings, &patch_path, &git).unwrap();
+
+        assert!(outcome.policy_block);
+        assert_eq!(
+            outcome.result.preconditions.mismatches[0].path,
+            "<working_tree>"
+        );
+    }
 }
 
 // =============================================================================
@@ -1015,5 +1206,6 @@ fn create_stub_receipt(path: &str) -> LoadedReceipt {
         receipt: Err(ReceiptLoadError::Io {
             message: "stub receipt".to_string(),
         }),
+        signature: None,
     }
 }
@@ -7,9 +7,13 @@
 //! to verify the pipeline works as it would in production.
 
 use buildfix_core::adapters::FsReceiptSource;
-use buildfix_core::pipeline::{PlanOutcome, run_apply, run_plan, write_plan_artifacts};
+use buildfix_core::pipeline::{
+    MarkdownTemplates, PlanOutcome, run_apply, run_plan, write_plan_artifacts,
+};
 use buildfix_core::ports::{GitPort, WritePort};
-use buildfix_core::settings::{ApplySettings, PlanSettings, RunMode};
+use buildfix_core::settings::{
+    ApplySettings, CancellationToken, Clock, FixerSettings, Ids, Metrics, PlanSettings, RunMode,
+};
 use buildfix_types::ops::SafetyClass;
 use buildfix_types::receipt::ToolInfo;
 use buildfix_types::wire::PlanV1;
@@ -68,6 +72,7 @@ fn default_plan_settings(root: &Utf8Path, artifacts_dir: &Utf8Path) -> PlanSetti
         out_dir: artifacts_dir.join("buildfix"),
         allow: Vec::new(),
         deny: Vec::new(),
+        path_policies: Vec::new(),
         allow_guarded: false,
         allow_unsafe: false,
         allow_dirty: false,
@@ -75,10 +80,29 @@ fn default_plan_settings(root: &Utf8Path, artifacts_dir: &Utf8Path) -> PlanSetti
         max_files: None,
         max_patch_bytes: None,
         params: HashMap::new(),
+        fixers: Default::default(),
+        min_severity: None,
+        sensors: Vec::new(),
+        exclude_check_ids: Vec::new(),
+        changed_since: None,
+        staged_only: false,
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
         require_clean_hashes: true,
         git_head_precondition: false,
+        pin_op_targets: false,
         backup_suffix: ".buildfix.bak".to_string(),
+        binary_patch: false,
         mode: RunMode::Standalone,
+        ci_run: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        metrics: Metrics::default(),
+        jobs: 0,
+        precondition_mode: Default::default(),
+        scan: Default::default(),
+        plan_ttl: None,
     }
 }
 
@@ -86,16 +110,33 @@ fn default_apply_settings(root: &Utf8Path, out_dir: &Utf8Path) -> ApplySettings
     ApplySettings {
         repo_root: root.to_path_buf(),
         out_dir: out_dir.to_path_buf(),
+        plan_path: None,
         dry_run: false,
         allow_guarded: false,
         allow_unsafe: false,
         allow_dirty: true, // no real git repo, so allow dirty
+        dirty_scope: Default::default(),
         params: HashMap::new(),
         auto_commit: false,
         commit_message: None,
+        branch: None,
+        push_remote: None,
+        op_ids: Vec::new(),
+        only_fix_keys: Vec::new(),
+        skip_fix_keys: Vec::new(),
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
         backup_enabled: false,
         backup_suffix: ".buildfix.bak".to_string(),
+        backup_keep_last: None,
+        durable_writes: false,
+        binary_patch: false,
         mode: RunMode::Standalone,
+        ci_run: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        metrics: Metrics::default(),
     }
 }
 
@@ -155,8 +196,13 @@ fn plan_and_write(repo: &TestRepo) -> (PlanOutcome, Utf8PathBuf) {
     let plan_outcome = run_plan(&plan_settings, &receipts_port, &NullGitPort, tool_info())
         .expect("run_plan should succeed");
 
-    write_plan_artifacts(&plan_outcome, &out_dir, &FsWritePort)
-        .expect("write_plan_artifacts should succeed");
+    write_plan_artifacts(
+        &plan_outcome,
+        &MarkdownTemplates::default(),
+        &out_dir,
+        &FsWritePort,
+    )
+    .expect("write_plan_artifacts should succeed");
 
     (plan_outcome, out_dir)
 }
@@ -673,6 +719,266 @@ fn test_multi_fixer_apply_modifies_correct_files() {
     );
 }
 
+// =============================================================================
+// Test: --only/--skip apply a subset of the plan
+// =============================================================================
+
+#[test]
+fn test_apply_only_and_skip_select_ops_by_fix_key() {
+    let repo = setup_multi_fixer_repo();
+    let (_plan_outcome, out_dir) = plan_and_write(&repo);
+
+    let mut apply_settings = default_apply_settings(&repo.root, &out_dir);
+    apply_settings.only_fix_keys = vec!["builddiag/*".to_string()];
+    let apply_outcome = run_apply(&apply_settings, &NullGitPort, tool_info())
+        .expect("run_apply should succeed with --only");
+
+    assert_eq!(apply_outcome.apply.summary.applied, 1);
+    assert_eq!(apply_outcome.apply.summary.blocked, 0);
+    let filtered: Vec<_> = apply_outcome
+        .apply
+        .results
+        .iter()
+        .filter(|r| r.blocked_reason_token.map(|c| c.as_str()) == Some("user_filtered"))
+        .collect();
+    assert!(
+        !filtered.is_empty(),
+        "path_dep_version op should be filtered"
+    );
+    assert!(
+        filtered
+            .iter()
+            .all(|r| r.status == buildfix_types::apply::ApplyStatus::Skipped)
+    );
+    assert!(repo.read_file("Cargo.toml").contains("resolver = \"2\""));
+    assert!(
+        !repo
+            .read_file("crates/a/Cargo.toml")
+            .contains("version = \"0.2.0\" }")
+    );
+
+    // --skip wins over --only.
+    let repo = setup_multi_fixer_repo();
+    let (_plan_outcome, out_dir) = plan_and_write(&repo);
+    let mut apply_settings = default_apply_settings(&repo.root, &out_dir);
+    apply_settings.only_fix_keys = vec!["*".to_string()];
+    apply_settings.skip_fix_keys = vec!["builddiag/*".to_string()];
+    let apply_outcome = run_apply(&apply_settings, &NullGitPort, tool_info())
+        .expect("run_apply should succeed with --skip");
+
+    assert!(!repo.read_file("Cargo.toml").contains("resolver"));
+    assert!(apply_outcome.apply.summary.applied >= 1);
+}
+
+#[test]
+fn test_apply_reads_plan_from_plan_path() {
+    let repo = setup_resolver_v2_repo();
+    let (_plan_outcome, out_dir) = plan_and_write(&repo);
+    let moved = repo.root.join("ci").join("buildfix-plan.json");
+    std::fs::create_dir_all(moved.parent().unwrap()).unwrap();
+    std::fs::rename(out_dir.join("plan.json"), &moved).unwrap();
+
+    let apply_settings = default_apply_settings(&repo.root, &out_dir);
+    assert!(run_apply(&apply_settings, &NullGitPort, tool_info()).is_err());
+
+    let mut apply_settings = default_apply_settings(&repo.root, &out_dir);
+    apply_settings.plan_path = Some(moved);
+    let apply_outcome = run_apply(&apply_settings, &NullGitPort, tool_info())
+        .expect("run_apply should read the relocated plan");
+    assert_eq!(apply_outcome.apply.summary.applied, 1);
+    assert!(repo.read_file("Cargo.toml").contains("resolver = \"2\""));
+}
+
+/// Reports a fixed set of changed files.
+struct ChangedFilesGitPort(Vec<&'static str>);
+
+impl GitPort for ChangedFilesGitPort {
+    fn head_sha(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn is_dirty(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        Ok(Some(false))
+    }
+
+    fn changed_files(
+        &self,
+        _repo_root: &Utf8Path,
+        _base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(Some(self.0.iter().map(|s| s.to_string()).collect()))
+    }
+}
+
+#[test]
+fn test_changed_since_keeps_findings_in_changed_packages() {
+    let repo = setup_multi_fixer_repo();
+    let receipts_port = FsReceiptSource::new(repo.artifacts_dir.clone());
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.changed_since = Some("origin/main".to_string());
+
+    // A source change in crates/a brings its manifest into scope, but not
+    // the root manifest the resolver finding points at.
+    let git = ChangedFilesGitPort(vec!["crates/a/src/lib.rs"]);
+    let outcome = run_plan(&settings, &receipts_port, &git, tool_info()).unwrap();
+    let files: Vec<_> = outcome
+        .plan
+        .ops
+        .iter()
+        .map(|op| op.target.path.as_str())
+        .collect();
+    assert_eq!(files, vec!["crates/a/Cargo.toml"]);
+
+    let git = ChangedFilesGitPort(vec!["README.md"]);
+    let outcome = run_plan(&settings, &receipts_port, &git, tool_info()).unwrap();
+    assert_eq!(outcome.plan.ops.len(), 1);
+    assert_eq!(outcome.plan.ops[0].target.path, "Cargo.toml");
+
+    // Without git there is no changed set to plan from.
+    assert!(run_plan(&settings, &receipts_port, &NullGitPort, tool_info()).is_err());
+}
+
+/// Reports fixed changed and staged file sets.
+struct StagedFilesGitPort {
+    changed: Vec<&'static str>,
+    staged: Vec<&'static str>,
+}
+
+impl GitPort for StagedFilesGitPort {
+    fn head_sha(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        Ok(None)
+    }
+
+    fn is_dirty(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        Ok(Some(false))
+    }
+
+    fn changed_files(
+        &self,
+        _repo_root: &Utf8Path,
+        _base_ref: &str,
+    ) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(Some(self.changed.iter().map(|s| s.to_string()).collect()))
+    }
+
+    fn staged_files(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<Vec<String>>> {
+        Ok(Some(self.staged.iter().map(|s| s.to_string()).collect()))
+    }
+}
+
+#[test]
+fn test_staged_only_keeps_findings_in_staged_packages() {
+    let repo = setup_multi_fixer_repo();
+    let receipts_port = FsReceiptSource::new(repo.artifacts_dir.clone());
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.staged_only = true;
+
+    let git = StagedFilesGitPort {
+        changed: vec![],
+        staged: vec!["crates/a/src/lib.rs"],
+    };
+    let outcome = run_plan(&settings, &receipts_port, &git, tool_info()).unwrap();
+    let files: Vec<_> = outcome
+        .plan
+        .ops
+        .iter()
+        .map(|op| op.target.path.as_str())
+        .collect();
+    assert_eq!(files, vec!["crates/a/Cargo.toml"]);
+
+    // With --changed-only as well, only files in both sets stay in scope.
+    settings.changed_since = Some("origin/main".to_string());
+    let git = StagedFilesGitPort {
+        changed: vec!["README.md"],
+        staged: vec!["crates/a/src/lib.rs"],
+    };
+    let outcome = run_plan(&settings, &receipts_port, &git, tool_info()).unwrap();
+    assert!(outcome.plan.ops.is_empty());
+
+    settings.changed_since = None;
+    assert!(run_plan(&settings, &receipts_port, &NullGitPort, tool_info()).is_err());
+}
+
+// =============================================================================
+// Test: [fixers.<fix_key>] disables fixers and overrides safety
+// =============================================================================
+
+#[test]
+fn test_fixer_settings_disable_and_override_safety() {
+    let repo = setup_resolver_v2_repo();
+    let receipts_port = FsReceiptSource::new(repo.artifacts_dir.clone());
+    let fix_id = "cargo.workspace_resolver_v2".to_string();
+
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.fixers.insert(
+        fix_id.clone(),
+        FixerSettings {
+            enabled: false,
+            ..FixerSettings::default()
+        },
+    );
+    let outcome = run_plan(&settings, &receipts_port, &NullGitPort, tool_info()).unwrap();
+    assert!(outcome.plan.ops.is_empty());
+
+    settings.fixers.insert(
+        fix_id,
+        FixerSettings {
+            safety: Some(SafetyClass::Guarded),
+            ..FixerSettings::default()
+        },
+    );
+    let outcome = run_plan(&settings, &receipts_port, &NullGitPort, tool_info()).unwrap();
+    assert_eq!(outcome.plan.ops.len(), 1);
+    assert_eq!(outcome.plan.ops[0].safety, SafetyClass::Guarded);
+}
+
+// =============================================================================
+// Test: --package / --exclude-package scope plan and apply to members
+// =============================================================================
+
+#[test]
+fn test_package_filters_scope_plan_and_apply() {
+    let repo = setup_multi_fixer_repo();
+    let receipts_port = FsReceiptSource::new(repo.artifacts_dir.clone());
+    let target_paths = |settings: &PlanSettings| -> Vec<String> {
+        run_plan(settings, &receipts_port, &NullGitPort, tool_info())
+            .unwrap()
+            .plan
+            .ops
+            .iter()
+            .map(|op| op.target.path.clone())
+            .collect()
+    };
+
+    // Naming a package leaves out ops only the workspace root owns.
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.packages = vec!["crate-*".to_string()];
+    assert_eq!(target_paths(&settings), vec!["crates/a/Cargo.toml"]);
+
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.exclude_packages = vec!["crate-a".to_string()];
+    assert_eq!(target_paths(&settings), vec!["Cargo.toml"]);
+
+    // Apply narrows an unfiltered plan the same way.
+    let (_plan_outcome, out_dir) = plan_and_write(&repo);
+    let mut apply_settings = default_apply_settings(&repo.root, &out_dir);
+    apply_settings.packages = vec!["crate-a".to_string()];
+    let apply_outcome = run_apply(&apply_settings, &NullGitPort, tool_info()).unwrap();
+    let filtered: Vec<_> = apply_outcome
+        .apply
+        .results
+        .iter()
+        .filter(|r| r.blocked_reason_token.map(|c| c.as_str()) == Some("user_filtered"))
+        .map(|r| r.op_id.as_str())
+        .collect();
+    assert_eq!(filtered.len(), 1);
+    assert!(!repo.read_file("Cargo.toml").contains("resolver"));
+    assert!(
+        repo.read_file("crates/a/Cargo.toml")
+            .contains("version = \"0.2.0\"")
+    );
+}
+
 // =============================================================================
 // Test: empty receipts produce no ops
 // =============================================================================
@@ -892,8 +1198,13 @@ fn test_guarded_ops_blocked_by_default_allowed_with_flag() {
     );
 
     // Write plan artifacts for apply
-    write_plan_artifacts(&plan_outcome, &out_dir, &FsWritePort)
-        .expect("write_plan_artifacts should succeed");
+    write_plan_artifacts(
+        &plan_outcome,
+        &MarkdownTemplates::default(),
+        &out_dir,
+        &FsWritePort,
+    )
+    .expect("write_plan_artifacts should succeed");
 
     // Apply WITHOUT allow_guarded -- guarded ops should be blocked at apply time
     let apply_settings = default_apply_settings(&repo.root, &out_dir);
@@ -1052,3 +1363,406 @@ fn test_all_ops_have_deterministic_ids() {
         ids
     );
 }
+
+// =============================================================================
+// Test: progress reporting
+// =============================================================================
+
+#[test]
+fn test_progress_reports_phases_fixers_and_ops() {
+    use buildfix_core::pipeline::{run_apply_with_progress, run_plan_with_progress};
+    use buildfix_types::progress::{Phase, Progress};
+    use std::cell::RefCell;
+
+    let repo = setup_resolver_v2_repo();
+    let settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    let seen = RefCell::new(Vec::new());
+    let record = |p: &Progress<'_>| {
+        seen.borrow_mut().push(match *p {
+            Progress::Phase(phase) => phase.as_str().to_string(),
+            Progress::Fixer {
+                ops, done, total, ..
+            } => format!("fixer {done}/{total} ops={ops}"),
+            Progress::Op { done, total, .. } => format!("op {done}/{total}"),
+        })
+    };
+
+    let outcome = run_plan_with_progress(
+        &settings,
+        &FsReceiptSource::new(repo.artifacts_dir.clone()),
+        &NullGitPort,
+        &record,
+        tool_info(),
+    )
+    .expect("run_plan_with_progress");
+    write_plan_artifacts(
+        &outcome,
+        &MarkdownTemplates::default(),
+        &settings.out_dir,
+        &FsWritePort,
+    )
+    .expect("write plan artifacts");
+    let plan_events = seen.take();
+    let fixers: Vec<_> = plan_events
+        .iter()
+        .filter(|e| e.starts_with("fixer "))
+        .collect();
+    let total = buildfix_core::builtin_fixer_metas().len();
+    assert_eq!(fixers.len(), total);
+    assert!(
+        fixers
+            .last()
+            .unwrap()
+            .starts_with(&format!("fixer {total}/{total}"))
+    );
+    assert_eq!(fixers.iter().filter(|e| e.ends_with("ops=1")).count(), 1);
+    let phases: Vec<_> = plan_events
+        .iter()
+        .filter(|e| !e.starts_with("fixer "))
+        .map(String::as_str)
+        .collect();
+    assert_eq!(
+        phases,
+        [
+            Phase::LoadReceipts,
+            Phase::Plan,
+            Phase::Preconditions,
+            Phase::Preview,
+            Phase::Report
+        ]
+        .map(Phase::as_str)
+    );
+
+    run_apply_with_progress(
+        &default_apply_settings(&repo.root, &settings.out_dir),
+        &NullGitPort,
+        &record,
+        tool_info(),
+    )
+    .expect("run_apply_with_progress");
+    assert_eq!(seen.take(), ["apply", "op 1/1", "write", "report"]);
+}
+
+// =============================================================================
+// Test: fixed clock and ids
+// =============================================================================
+
+#[test]
+fn test_fixed_clock_and_sequential_ids_make_artifacts_reproducible() {
+    let at = chrono::DateTime::from_timestamp(1_700_000_000, 0).unwrap();
+    let repo = setup_resolver_v2_repo();
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.clock = Clock::fixed(at);
+    settings.ids = Ids::sequential();
+    let plan = || {
+        run_plan(
+            &settings,
+            &FsReceiptSource::new(repo.artifacts_dir.clone()),
+            &NullGitPort,
+            tool_info(),
+        )
+        .expect("run_plan")
+    };
+    let first = plan();
+    assert_eq!(
+        serde_json::to_string(&first.report).unwrap(),
+        serde_json::to_string(&plan().report).unwrap()
+    );
+    assert_eq!(first.report.run.started_at, "2023-11-14T22:13:20+00:00");
+    write_plan_artifacts(
+        &first,
+        &MarkdownTemplates::default(),
+        &settings.out_dir,
+        &FsWritePort,
+    )
+    .expect("write plan artifacts");
+
+    let mut apply_settings = default_apply_settings(&repo.root, &settings.out_dir);
+    apply_settings.backup_enabled = true;
+    apply_settings.clock = Clock::fixed(at);
+    apply_settings.ids = Ids::sequential();
+    let apply = run_apply(&apply_settings, &NullGitPort, tool_info()).expect("run_apply");
+    assert_eq!(apply.report.run.started_at, "2023-11-14T22:13:20+00:00");
+    let backup = apply.apply.results[0].files[0]
+        .backup_path
+        .clone()
+        .expect("backup path");
+    assert_eq!(
+        Utf8PathBuf::from(backup)
+            .strip_prefix(&settings.out_dir)
+            .expect("backup under out dir")
+            .as_str()
+            .replace('\\', "/"),
+        "backups/20231114T221320.000Z-00000001/Cargo.toml.buildfix.bak"
+    );
+}
+
+// =============================================================================
+// Test: run and phase timings
+// =============================================================================
+
+/// A clock that moves one second forward every time it is read.
+struct SteppingClock(std::sync::atomic::AtomicI64);
+
+impl buildfix_core::ports::ClockPort for SteppingClock {
+    fn now(&self) -> chrono::DateTime<chrono::Utc> {
+        let step = self.0.fetch_add(1, std::sync::atomic::Ordering::SeqCst);
+        chrono::DateTime::from_timestamp(1_700_000_000 + step, 0).unwrap()
+    }
+}
+
+fn phases_ms(report: &buildfix_types::report::BuildfixReport) -> HashMap<String, u64> {
+    serde_json::from_value(report.data.as_ref().unwrap()["buildfix"]["phases_ms"].clone())
+        .expect("phases_ms")
+}
+
+#[test]
+fn test_reports_record_run_duration_and_phase_timings() {
+    let repo = setup_resolver_v2_repo();
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.clock = Clock::new(SteppingClock(Default::default()));
+    let outcome = run_plan(
+        &settings,
+        &FsReceiptSource::new(repo.artifacts_dir.clone()),
+        &NullGitPort,
+        tool_info(),
+    )
+    .expect("run_plan");
+
+    let run = &outcome.report.run;
+    assert_eq!(run.started_at, "2023-11-14T22:13:20+00:00");
+    let duration = run.duration_ms.expect("duration");
+    assert!(duration > 0);
+    let ended = chrono::DateTime::parse_from_rfc3339(run.ended_at.as_deref().unwrap()).unwrap();
+    assert_eq!(ended.timestamp() - 1_700_000_000, (duration / 1000) as i64);
+    let phases = phases_ms(&outcome.report);
+    for phase in ["load_receipts", "plan", "preview", "report"] {
+        assert!(phases[phase] >= 1000, "{phase}: {phases:?}");
+    }
+    assert!(phases.values().sum::<u64>() <= duration);
+    write_plan_artifacts(
+        &outcome,
+        &MarkdownTemplates::default(),
+        &settings.out_dir,
+        &FsWritePort,
+    )
+    .expect("write plan artifacts");
+
+    let mut apply_settings = default_apply_settings(&repo.root, &settings.out_dir);
+    apply_settings.clock = Clock::new(SteppingClock(Default::default()));
+    let apply = run_apply(&apply_settings, &NullGitPort, tool_info()).expect("run_apply");
+    let duration = apply.report.run.duration_ms.expect("duration");
+    let phases = phases_ms(&apply.report);
+    for phase in ["apply", "write", "report"] {
+        assert!(phases[phase] >= 1000, "{phase}: {phases:?}");
+    }
+    assert!(!phases.contains_key("git"));
+    assert!(phases.values().sum::<u64>() <= duration);
+}
+
+#[test]
+fn test_metrics_record_ops_by_fixer_duration_and_patch_size() {
+    use buildfix_core::adapters::PrometheusMetrics;
+    use std::sync::Arc;
+
+    let registry = Arc::new(PrometheusMetrics::new());
+    let repo = setup_resolver_v2_repo();
+    let mut settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    settings.metrics = Metrics::new(Arc::clone(&registry));
+    let outcome = run_plan(
+        &settings,
+        &FsReceiptSource::new(repo.artifacts_dir.clone()),
+        &NullGitPort,
+        tool_info(),
+    )
+    .expect("run_plan");
+    write_plan_artifacts(
+        &outcome,
+        &MarkdownTemplates::default(),
+        &settings.out_dir,
+        &FsWritePort,
+    )
+    .expect("write plan artifacts");
+
+    let text = registry.render();
+    let fix_key = &outcome.plan.ops[0].rationale.fix_key;
+    let planned = format!("buildfix_ops_planned_total{{fix_key=\"{fix_key}\"}} 1\n");
+    assert!(text.contains(&planned), "{text}");
+    assert!(!text.contains("buildfix_ops_blocked_total"), "{text}");
+    let status = outcome.report.verdict.status.as_str();
+    assert!(text.contains(&format!(
+        "buildfix_runs_total{{command=\"plan\",outcome=\"{status}\"}} 1\n"
+    )));
+    assert!(text.contains("buildfix_plan_duration_seconds_count 1\n"));
+    let patch_bytes = outcome.plan.summary.patch_bytes.unwrap();
+    assert!(patch_bytes > 0);
+    assert!(text.contains(&format!("buildfix_patch_bytes_sum {}\n", patch_bytes)));
+
+    let mut apply_settings = default_apply_settings(&repo.root, &settings.out_dir);
+    apply_settings.metrics = Metrics::new(Arc::clone(&registry));
+    let apply = run_apply(&apply_settings, &NullGitPort, tool_info()).expect("run_apply");
+
+    let text = registry.render();
+    assert!(text.contains(&planned), "{text}");
+    assert!(text.contains(&format!(
+        "buildfix_ops_applied_total{{fix_key=\"{fix_key}\"}} 1\n"
+    )));
+    let status = apply.report.verdict.status.as_str();
+    assert!(text.contains(&format!(
+        "buildfix_runs_total{{command=\"apply\",outcome=\"{status}\"}} 1\n"
+    )));
+    assert!(text.contains("buildfix_apply_duration_seconds_count 1\n"));
+}
+
+// =============================================================================
+// Test: cooperative cancellation
+// =============================================================================
+
+#[test]
+fn test_cancellation_stops_between_fixers_and_between_ops() {
+    use buildfix_core::pipeline::{run_apply_with_progress, run_plan_with_progress};
+    use buildfix_types::apply::ApplyStatus;
+    use buildfix_types::progress::Progress;
+
+    // Plan: cancelled after the first fixer, so no other fixer runs.
+    let repo = setup_multi_fixer_repo();
+    let settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    let fixers = std::cell::Cell::new(0);
+    let outcome = run_plan_with_progress(
+        &settings,
+        &FsReceiptSource::new(repo.artifacts_dir.clone()),
+        &NullGitPort,
+        &|p: &Progress<'_>| {
+            if let Progress::Fixer { .. } = p {
+                fixers.set(fixers.get() + 1);
+                settings.cancel.cancel();
+            }
+        },
+        tool_info(),
+    )
+    .expect("cancelled plan still returns");
+    assert_eq!(fixers.get(), 1);
+    assert!(outcome.plan.ops.len() < 2);
+    assert!(
+        outcome
+            .report
+            .verdict
+            .reasons
+            .contains(&"cancelled".to_string())
+    );
+    assert_eq!(
+        outcome.report.data.as_ref().unwrap()["buildfix"]["plan"]["cancelled"],
+        true
+    );
+
+    // Apply: cancelled after the first op, which is written; the second is
+    // skipped and its file left alone.
+    let (_plan_outcome, out_dir) = plan_and_write(&repo);
+    let root_before = repo.read_file("Cargo.toml");
+    let crate_a_before = repo.read_file("crates/a/Cargo.toml");
+    let apply_settings = default_apply_settings(&repo.root, &out_dir);
+    let apply_outcome = run_apply_with_progress(
+        &apply_settings,
+        &NullGitPort,
+        &|p: &Progress<'_>| {
+            if let Progress::Op { .. } = p {
+                apply_settings.cancel.cancel();
+            }
+        },
+        tool_info(),
+    )
+    .expect("cancelled apply still returns");
+
+    let apply = &apply_outcome.apply;
+    assert_eq!(apply.results.len(), 2);
+    assert_eq!(apply.results[0].status, ApplyStatus::Applied);
+    assert_eq!(apply.results[1].status, ApplyStatus::Skipped);
+    assert_eq!(
+        apply.results[1].blocked_reason_token.map(|c| c.as_str()),
+        Some("cancelled")
+    );
+    assert_eq!((apply.summary.applied, apply.summary.blocked), (1, 0));
+    let changed = [
+        repo.read_file("Cargo.toml") != root_before,
+        repo.read_file("crates/a/Cargo.toml") != crate_a_before,
+    ];
+    assert_eq!(changed.iter().filter(|c| **c).count(), 1);
+    assert!(
+        apply_outcome
+            .report
+            .verdict
+            .reasons
+            .contains(&"cancelled".to_string())
+    );
+    assert_eq!(
+        apply_outcome.report.data.as_ref().unwrap()["buildfix"]["apply"]["cancelled"],
+        true
+    );
+}
+
+// =============================================================================
+// Test: async pipeline (`async` feature)
+// =============================================================================
+
+/// An async git port that yields before answering, like a remote one would.
+#[cfg(feature = "async")]
+struct AsyncNullGitPort;
+
+#[cfg(feature = "async")]
+impl buildfix_core::ports::AsyncGitPort for AsyncNullGitPort {
+    async fn head_sha(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<String>> {
+        tokio::task::yield_now().await;
+        Ok(None)
+    }
+
+    async fn is_dirty(&self, _repo_root: &Utf8Path) -> anyhow::Result<Option<bool>> {
+        tokio::task::yield_now().await;
+        Ok(Some(false))
+    }
+}
+
+#[cfg(feature = "async")]
+#[tokio::test]
+async fn test_async_plan_write_and_apply_on_a_current_thread_runtime() {
+    use buildfix_core::adapters::Blocking;
+    use buildfix_core::async_pipeline::{
+        run_apply_async, run_plan_async, write_plan_artifacts_async,
+    };
+    use std::sync::Arc;
+
+    let repo = setup_resolver_v2_repo();
+    let settings = default_plan_settings(&repo.root, &repo.artifacts_dir);
+    let git = Arc::new(AsyncNullGitPort);
+
+    let plan = run_plan_async(
+        &settings,
+        Arc::new(Blocking::new(FsReceiptSource::new(
+            repo.artifacts_dir.clone(),
+        ))),
+        git.clone(),
+        tool_info(),
+    )
+    .await
+    .expect("run_plan_async");
+    assert_eq!(plan.plan.ops.len(), 1);
+
+    write_plan_artifacts_async(
+        &plan,
+        &MarkdownTemplates::default(),
+        &settings.out_dir,
+        &Blocking::new(FsWritePort),
+    )
+    .await
+    .expect("write plan artifacts");
+    assert!(settings.out_dir.join("plan.json").exists());
+
+    let apply = run_apply_async(
+        &default_apply_settings(&repo.root, &settings.out_dir),
+        git,
+        tool_info(),
+    )
+    .await
+    .expect("run_apply_async");
+    assert_eq!(apply.apply.summary.applied, 1);
+    assert!(normalize_line_endings(&repo.read_file("Cargo.toml")).contains("resolver = \"2\""));
+}
@@ -1,11 +1,14 @@
 //! Reusable domain policy helpers and deterministic plan-op utilities.
 
-use std::collections::{BTreeSet, HashMap};
+use std::collections::hash_map::Entry;
+use std::collections::{BTreeMap, BTreeSet, HashMap};
 
 use anyhow::Result;
-use buildfix_fixer_api::PlannerConfig;
-use buildfix_types::ops::OpKind;
-use buildfix_types::plan::{PlanOp, blocked_tokens};
+use buildfix_fixer_api::{FixerConfig, PlannerConfig};
+use buildfix_types::error_code::BuildfixErrorCode;
+use buildfix_types::messages::{self, codes};
+use buildfix_types::ops::{OpKind, SafetyClass};
+use buildfix_types::plan::{FindingRef, PathPolicy, PlanOp};
 use sha2::{Digest, Sha256};
 use uuid::Uuid;
 
@@ -13,8 +16,9 @@ use uuid::Uuid;
 ///
 /// This is the preferred crate-level entrypoint for `buildfix-domain` policy
 /// application, combining ordering, op-id generation, parameter filling,
-/// allow/deny filtering, and cap enforcement in a single call.
-pub fn apply_plan_policy(cfg: &PlannerConfig, ops: &mut [PlanOp]) -> Result<()> {
+/// allow/deny filtering, conflict resolution, and cap enforcement in a single
+/// call.
+pub fn apply_plan_policy(cfg: &PlannerConfig, ops: &mut Vec<PlanOp>) -> Result<()> {
     // Deterministic ordering.
     ops.sort_by_key(stable_op_sort_key);
 
@@ -27,7 +31,10 @@ pub fn apply_plan_policy(cfg: &PlannerConfig, ops: &mut [PlanOp]) -> Result<()>
 
     // Resolve params and apply policy gates.
     apply_params(&cfg.params, ops);
-    apply_allow_deny(&cfg.allow, &cfg.deny, ops);
+    apply_allow_deny(&cfg.allow, &cfg.deny, &cfg.path_policies, ops);
+
+    // Merge duplicates and block ops that would overwrite each other.
+    resolve_conflicts(ops);
 
     // Enforce caps by blocking all ops when exceeded.
     enforce_caps(cfg, ops)?;
@@ -58,12 +65,61 @@ pub fn apply_params(params: &HashMap<String, String>, ops: &mut [PlanOp]) {
             op.params_required.clear();
         } else {
             op.blocked = true;
-            op.blocked_reason = Some(format!("missing params: {}", missing.join(", ")));
-            op.blocked_reason_token = Some(blocked_tokens::MISSING_PARAMS.to_string());
+            op.blocked_reason = Some(messages::english(
+                codes::BLOCKED_MISSING_PARAMS,
+                &[("params", &missing.join(", "))],
+            ));
+            op.blocked_reason_token = Some(BuildfixErrorCode::MissingParams);
         }
     }
 }
 
+/// Apply one fixer's `[fixers.<fix_key>]` overrides to the ops it planned.
+///
+/// `fixer_safety` is the fixer's declared class. Overriding it with a less
+/// strict class is an error unless the config sets `force`; without `force`
+/// no op ends up less strict than it was planned.
+pub fn apply_fixer_config(
+    fix_key: &str,
+    fixer_safety: SafetyClass,
+    config: &FixerConfig,
+    global_params: &HashMap<String, String>,
+    ops: &mut [PlanOp],
+) -> Result<()> {
+    if let Some(safety) = config.safety {
+        if safety < fixer_safety && !config.force {
+            anyhow::bail!(
+                "[fixers.{}] safety = \"{}\" is less strict than the fixer's \"{}\"; set force = true to downgrade",
+                fix_key,
+                safety_name(safety),
+                safety_name(fixer_safety)
+            );
+        }
+        for op in ops.iter_mut() {
+            op.safety = if config.force {
+                safety
+            } else {
+                op.safety.max(safety)
+            };
+        }
+    }
+
+    if !config.params.is_empty() {
+        let mut params = global_params.clone();
+        params.extend(config.params.clone());
+        apply_params(&params, ops);
+    }
+    Ok(())
+}
+
+fn safety_name(safety: SafetyClass) -> &'static str {
+    match safety {
+        SafetyClass::Safe => "safe",
+        SafetyClass::Guarded => "guarded",
+        SafetyClass::Unsafe => "unsafe",
+    }
+}
+
 fn fill_op_param(op: &mut PlanOp, key: &str, value: &str) {
     let OpKind::TomlTransform { rule_id, args } = &mut op.kind else {
         return;
@@ -107,31 +163,70 @@ fn fill_op_param(op: &mut PlanOp, key: &str, value: &str) {
 /// Apply allowlist/denylist policy gates on operations.
 ///
 /// Existing blocked operations are preserved.
-pub fn apply_allow_deny(allow: &[String], deny: &[String], ops: &mut [PlanOp]) {
+pub fn apply_allow_deny(
+    allow: &[String],
+    deny: &[String],
+    path_policies: &[PathPolicy],
+    ops: &mut [PlanOp],
+) {
     for op in ops {
         if op.blocked {
             continue;
         }
 
         let trigger_keys = op_fix_keys(op);
-        if deny
-            .iter()
-            .any(|pat| trigger_keys.iter().any(|k| glob_match(pat, k)))
-        {
+        let matches_any = |pats: &[String]| {
+            pats.iter()
+                .any(|pat| trigger_keys.iter().any(|k| glob_match(pat, k)))
+        };
+        if matches_any(deny) {
             op.blocked = true;
-            op.blocked_reason = Some("denied by policy".to_string());
-            op.blocked_reason_token = Some(blocked_tokens::DENYLIST.to_string());
+            op.blocked_reason = Some(messages::english(codes::BLOCKED_DENYLIST, &[]));
+            op.blocked_reason_token = Some(BuildfixErrorCode::Denylist);
             continue;
         }
 
-        if !allow.is_empty()
-            && !allow
-                .iter()
-                .any(|pat| trigger_keys.iter().any(|k| glob_match(pat, k)))
-        {
+        if !allow.is_empty() && !matches_any(allow) {
             op.blocked = true;
-            op.blocked_reason = Some("not in allowlist".to_string());
-            op.blocked_reason_token = Some(blocked_tokens::ALLOWLIST_MISSING.to_string());
+            op.blocked_reason = Some(messages::english(codes::BLOCKED_ALLOWLIST_MISSING, &[]));
+            op.blocked_reason_token = Some(BuildfixErrorCode::AllowlistMissing);
+            continue;
+        }
+
+        // Every rule whose glob matches the target applies; the first one
+        // that blocks decides the reason.
+        let path = op.target.path.strip_prefix("./").unwrap_or(&op.target.path);
+        let block = path_policies
+            .iter()
+            .filter(|rule| glob_match(&rule.path, path))
+            .find_map(|rule| {
+                if matches_any(&rule.deny) {
+                    let reason =
+                        messages::english(codes::BLOCKED_PATH_DENYLIST, &[("path", &rule.path)]);
+                    return Some((reason, BuildfixErrorCode::PathDenylist));
+                }
+                if !rule.allow.is_empty() && !matches_any(&rule.allow) {
+                    let reason = messages::english(
+                        codes::BLOCKED_PATH_ALLOWLIST_MISSING,
+                        &[("path", &rule.path)],
+                    );
+                    return Some((reason, BuildfixErrorCode::PathAllowlistMissing));
+                }
+                match rule.max_safety {
+                    Some(max) if op.safety > max => {
+                        let reason = messages::english(
+                            codes::BLOCKED_PATH_SAFETY_NOT_ALLOWED,
+                            &[("safety", &safety_name(op.safety)), ("path", &rule.path)],
+                        );
+                        Some((reason, BuildfixErrorCode::PathSafetyNotAllowed))
+                    }
+                    _ => None,
+                }
+            });
+        if let Some((reason, token)) = block {
+            op.blocked = true;
+            op.blocked_reason = Some(reason);
+            op.blocked_reason_token = Some(token);
         }
     }
 }
@@ -150,21 +245,245 @@ fn op_fix_keys(op: &PlanOp) -> Vec<String> {
         .collect()
 }
 
+/// Merge identical ops and block ops whose TOML writes collide.
+///
+/// Only ops that are not already blocked take part. Two ops on the same file
+/// with the same kind are merged into the first, which keeps the stricter
+/// safety class and gains the other's findings. Ops that write overlapping
+/// `toml_path`s (one equal to or a prefix of the other) are otherwise all
+/// blocked with `CONFLICTING_OPS` unless they write the same value, since
+/// applying them in order would let the last one silently win.
+pub fn resolve_conflicts(ops: &mut Vec<PlanOp>) {
+    let mut first_of: HashMap<(String, String), usize> = HashMap::new();
+    let mut merged = vec![false; ops.len()];
+    for i in 0..ops.len() {
+        if ops[i].blocked {
+            continue;
+        }
+        let key = (ops[i].target.path.clone(), kind_fingerprint(&ops[i].kind));
+        match first_of.entry(key) {
+            Entry::Vacant(slot) => {
+                slot.insert(i);
+            }
+            Entry::Occupied(slot) => {
+                let findings = std::mem::take(&mut ops[i].rationale.findings);
+                let safety = ops[i].safety;
+                let keep = &mut ops[*slot.get()];
+                keep.safety = keep.safety.max(safety);
+                for finding in findings {
+                    if !keep
+                        .rationale
+                        .findings
+                        .iter()
+                        .any(|f| same_finding(f, &finding))
+                    {
+                        keep.rationale.findings.push(finding);
+                    }
+                }
+                merged[i] = true;
+            }
+        }
+    }
+    let mut i = 0;
+    ops.retain(|_| {
+        i += 1;
+        !merged[i - 1]
+    });
+
+    let conflicts = {
+        let mut by_file: BTreeMap<&str, Vec<(usize, Vec<TomlWrite>)>> = BTreeMap::new();
+        for (i, op) in ops.iter().enumerate() {
+            let writes = toml_writes(&op.kind);
+            if !op.blocked && !writes.is_empty() {
+                by_file
+                    .entry(op.target.path.as_str())
+                    .or_default()
+                    .push((i, writes));
+            }
+        }
+
+        let mut conflicts: Vec<Option<(usize, &[String])>> = vec![None; ops.len()];
+        for file_ops in by_file.values() {
+            for (a, (i, writes_i)) in file_ops.iter().enumerate() {
+                for (j, writes_j) in &file_ops[a + 1..] {
+                    let clash = writes_i.iter().find_map(|wi| {
+                        writes_j
+                            .iter()
+                            .find(|wj| wi.conflicts_with(wj))
+                            .map(|wj| (wi, wj))
+                    });
+                    if let Some((wi, wj)) = clash {
+                        conflicts[*i].get_or_insert((*j, &wi.path));
+                        conflicts[*j].get_or_insert((*i, &wj.path));
+                    }
+                }
+            }
+        }
+        conflicts
+            .into_iter()
+            .map(|c| c.map(|(other, path)| (other, path.join("."))))
+            .collect::<Vec<_>>()
+    };
+
+    for (i, conflict) in conflicts.into_iter().enumerate() {
+        let Some((other, toml_path)) = conflict else {
+            continue;
+        };
+        let reason = messages::english(
+            codes::BLOCKED_CONFLICTING_OPS,
+            &[
+                ("fix_key", &ops[other].rationale.fix_key),
+                ("toml_path", &toml_path),
+                ("path", &ops[i].target.path),
+            ],
+        );
+        let op = &mut ops[i];
+        op.blocked = true;
+        op.blocked_reason = Some(reason);
+        op.blocked_reason_token = Some(BuildfixErrorCode::ConflictingOps);
+    }
+}
+
+fn same_finding(a: &FindingRef, b: &FindingRef) -> bool {
+    a.source == b.source
+        && a.check_id == b.check_id
+        && a.code == b.code
+        && a.path == b.path
+        && a.line == b.line
+}
+
+fn kind_fingerprint(kind: &OpKind) -> String {
+    args_fingerprint(&serde_json::to_value(kind).ok())
+}
+
+/// One key an op writes, and what it writes there.
+struct TomlWrite {
+    path: Vec<String>,
+    value: WriteValue,
+}
+
+enum WriteValue {
+    Set(serde_json::Value),
+    Remove,
+    Append,
+    /// A transform whose effect is only known by its arguments.
+    Opaque(String),
+}
+
+impl TomlWrite {
+    /// Overlapping writes conflict unless they are to the same key and agree.
+    /// Appends to the same array never conflict with each other.
+    fn conflicts_with(&self, other: &TomlWrite) -> bool {
+        let n = self.path.len().min(other.path.len());
+        if self.path[..n] != other.path[..n] {
+            return false;
+        }
+        if self.path.len() != other.path.len() {
+            return true;
+        }
+        !match (&self.value, &other.value) {
+            (WriteValue::Set(a), WriteValue::Set(b)) => a == b,
+            (WriteValue::Remove, WriteValue::Remove) => true,
+            (WriteValue::Append, WriteValue::Append) => true,
+            (WriteValue::Opaque(a), WriteValue::Opaque(b)) => a == b,
+            _ => false,
+        }
+    }
+}
+
+/// The TOML keys `kind` writes. Transforms are described by the paths the
+/// edit engine's rules touch; unknown rules without a `toml_path` argument
+/// write nothing we can see.
+fn toml_writes(kind: &OpKind) -> Vec<TomlWrite> {
+    let write = |path: Vec<String>, value| vec![TomlWrite { path, value }];
+    let key = |parts: &[&str]| parts.iter().map(|p| p.to_string()).collect::<Vec<_>>();
+    match kind {
+        OpKind::TomlSet {
+            toml_path, value, ..
+        } => write(toml_path.clone(), WriteValue::Set(value.clone())),
+        OpKind::TomlRemove { toml_path } => write(toml_path.clone(), WriteValue::Remove),
+        OpKind::TomlArrayAppend { toml_path, .. } => write(toml_path.clone(), WriteValue::Append),
+        OpKind::TomlMerge { toml_path, table } => table
+            .iter()
+            .map(|(k, v)| TomlWrite {
+                path: toml_path.iter().cloned().chain([k.clone()]).collect(),
+                value: WriteValue::Set(v.clone()),
+            })
+            .collect(),
+        OpKind::TomlTransform { rule_id, args } => {
+            let arg = |name: &str| args.as_ref().and_then(|a| a.get(name)).cloned();
+            let set_or_opaque = |name: &str| match arg(name) {
+                Some(v) => WriteValue::Set(v),
+                None => WriteValue::Opaque(kind_fingerprint(kind)),
+            };
+            let arg_path = arg("toml_path").and_then(|p| {
+                p.as_array()?
+                    .iter()
+                    .map(|s| s.as_str().map(str::to_string))
+                    .collect::<Option<Vec<_>>>()
+            });
+            match rule_id.as_str() {
+                "ensure_workspace_resolver_v2" => write(
+                    key(&["workspace", "resolver"]),
+                    WriteValue::Set(serde_json::json!("2")),
+                ),
+                "set_package_rust_version" => write(
+                    key(&["package", "rust-version"]),
+                    set_or_opaque("rust_version"),
+                ),
+                "set_package_edition" => {
+                    write(key(&["package", "edition"]), set_or_opaque("edition"))
+                }
+                "set_package_license" => {
+                    write(key(&["package", "license"]), set_or_opaque("license"))
+                }
+                "ensure_path_dep_has_version" => match arg_path {
+                    Some(mut path) => {
+                        path.push("version".to_string());
+                        write(path, set_or_opaque("version"))
+                    }
+                    None => Vec::new(),
+                },
+                "ensure_workspace_dependency_version" => {
+                    match arg("dep").as_ref().and_then(|d| d.as_str()) {
+                        Some(dep) => write(
+                            key(&["workspace", "dependencies", dep]),
+                            WriteValue::Opaque(kind_fingerprint(kind)),
+                        ),
+                        None => Vec::new(),
+                    }
+                }
+                _ => match arg_path {
+                    Some(path) => write(path, WriteValue::Opaque(kind_fingerprint(kind))),
+                    None => Vec::new(),
+                },
+            }
+        }
+        OpKind::JsonSet { .. }
+        | OpKind::JsonRemove { .. }
+        | OpKind::YamlSet { .. }
+        | OpKind::YamlRemove { .. }
+        | OpKind::TextReplaceAnchored { .. } => Vec::new(),
+    }
+}
+
 /// Enforce planning caps (max ops and max files).
 ///
-/// Caps are blocking all operations when exceeded.
+/// Caps are blocking all operations when exceeded. Ops already blocked keep
+/// their own reason and token, so lifting the cap later (see `merge_plans`)
+/// cannot unblock an op a policy gate denied.
 pub fn enforce_caps(cfg: &PlannerConfig, ops: &mut [PlanOp]) -> Result<()> {
     let mut cap_reason: Option<String> = None;
-    let mut cap_token: Option<&str> = None;
+    let mut cap_token: Option<BuildfixErrorCode> = None;
 
     if let Some(max_ops) = cfg.max_ops {
         let total_ops = ops.len() as u64;
         if total_ops > max_ops {
-            cap_reason = Some(format!(
-                "caps exceeded: max_ops {} > {} allowed",
-                total_ops, max_ops
+            cap_reason = Some(messages::english(
+                codes::BLOCKED_MAX_OPS,
+                &[("actual", &total_ops), ("limit", &max_ops)],
             ));
-            cap_token = Some(blocked_tokens::MAX_OPS);
+            cap_token = Some(BuildfixErrorCode::MaxOps);
         }
     }
 
@@ -177,19 +496,19 @@ pub fn enforce_caps(cfg: &PlannerConfig, ops: &mut [PlanOp]) -> Result<()> {
             .collect::<BTreeSet<_>>();
         let total_files = files.len() as u64;
         if total_files > max_files {
-            cap_reason = Some(format!(
-                "caps exceeded: max_files {} > {} allowed",
-                total_files, max_files
+            cap_reason = Some(messages::english(
+                codes::BLOCKED_MAX_FILES,
+                &[("actual", &total_files), ("limit", &max_files)],
             ));
-            cap_token = Some(blocked_tokens::MAX_FILES);
+            cap_token = Some(BuildfixErrorCode::MaxFiles);
         }
     }
 
     if let Some(reason) = cap_reason {
-        for op in ops.iter_mut() {
+        for op in ops.iter_mut().filter(|op| !op.blocked) {
             op.blocked = true;
             op.blocked_reason = Some(reason.clone());
-            op.blocked_reason_token = cap_token.map(|t| t.to_string());
+            op.blocked_reason_token = cap_token;
         }
     }
 
@@ -209,6 +528,21 @@ fn op_sort_key(op: &PlanOp) -> String {
         }
         OpKind::TomlSet { toml_path, .. } => format!("set|{}", toml_path.join(".")),
         OpKind::TomlRemove { toml_path } => format!("remove|{}", toml_path.join(".")),
+        OpKind::TomlArrayAppend {
+            toml_path,
+            values,
+            unique,
+        } => format!(
+            "array_append|{}|{}|{}",
+            toml_path.join("."),
+            args_fingerprint(&Some(serde_json::Value::Array(values.clone()))),
+            unique
+        ),
+        OpKind::TomlMerge { toml_path, table } => format!(
+            "merge|{}|{}",
+            toml_path.join("."),
+            args_fingerprint(&Some(serde_json::Value::Object(table.clone())))
+        ),
         OpKind::JsonSet { json_path, value } => format!(
             "json_set|{}|{}",
             json_path.join("."),
@@ -252,6 +586,8 @@ pub fn deterministic_op_id(op: &PlanOp) -> Uuid {
         OpKind::TomlTransform { rule_id, .. } => rule_id.as_str(),
         OpKind::TomlSet { .. } => "toml_set",
         OpKind::TomlRemove { .. } => "toml_remove",
+        OpKind::TomlArrayAppend { .. } => "toml_array_append",
+        OpKind::TomlMerge { .. } => "toml_merge",
         OpKind::JsonSet { .. } => "json_set",
         OpKind::JsonRemove { .. } => "json_remove",
         OpKind::YamlSet { .. } => "yaml_set",
@@ -275,6 +611,19 @@ pub fn deterministic_op_id(op: &PlanOp) -> Uuid {
         OpKind::YamlRemove { yaml_path } => args_fingerprint(&Some(serde_json::json!({
             "yaml_path": yaml_path,
         }))),
+        OpKind::TomlArrayAppend {
+            toml_path,
+            values,
+            unique,
+        } => args_fingerprint(&Some(serde_json::json!({
+            "toml_path": toml_path,
+            "values": values,
+            "unique": unique,
+        }))),
+        OpKind::TomlMerge { toml_path, table } => args_fingerprint(&Some(serde_json::json!({
+            "toml_path": toml_path,
+            "table": table,
+        }))),
         OpKind::TextReplaceAnchored {
             find,
             replace,
@@ -372,6 +721,7 @@ mod tests {
             blocked_reason_token: None,
             target: buildfix_types::ops::OpTarget {
                 path: path.to_string(),
+                sha256: None,
             },
             kind: buildfix_types::ops::OpKind::TomlTransform {
                 rule_id: rule_id.to_string(),
@@ -386,9 +736,81 @@ mod tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }
     }
 
+    #[test]
+    fn apply_fixer_config_tightens_and_only_loosens_with_force() {
+        let fix_key = "cargo.normalize_rust_version";
+        let op = || make_toml_plan_op("a/Cargo.toml", "set_package_rust_version", fix_key);
+        let guarded = FixerConfig {
+            safety: Some(SafetyClass::Guarded),
+            ..FixerConfig::default()
+        };
+
+        let mut ops = vec![op()];
+        apply_fixer_config(
+            fix_key,
+            SafetyClass::Safe,
+            &guarded,
+            &HashMap::new(),
+            &mut ops,
+        )
+        .unwrap();
+        assert_eq!(ops[0].safety, SafetyClass::Guarded);
+
+        let safe = FixerConfig {
+            safety: Some(SafetyClass::Safe),
+            ..FixerConfig::default()
+        };
+        let err = apply_fixer_config(
+            fix_key,
+            SafetyClass::Guarded,
+            &safe,
+            &HashMap::new(),
+            &mut ops,
+        )
+        .unwrap_err();
+        assert!(err.to_string().contains("force = true"));
+
+        let forced = FixerConfig {
+            force: true,
+            ..safe
+        };
+        apply_fixer_config(
+            fix_key,
+            SafetyClass::Guarded,
+            &forced,
+            &HashMap::new(),
+            &mut ops,
+        )
+        .unwrap();
+        assert_eq!(ops[0].safety, SafetyClass::Safe);
+    }
+
+    #[test]
+    fn apply_fixer_config_params_override_global_params() {
+        let fix_key = "cargo.normalize_rust_version";
+        let mut op = make_toml_plan_op("a/Cargo.toml", "set_package_rust_version", fix_key);
+        op.params_required = vec!["rust_version".to_string()];
+        let mut ops = vec![op];
+        let config = FixerConfig {
+            params: HashMap::from([("rust_version".to_string(), "1.80".to_string())]),
+            ..FixerConfig::default()
+        };
+        let global = HashMap::from([("rust_version".to_string(), "1.70".to_string())]);
+
+        apply_fixer_config(fix_key, SafetyClass::Unsafe, &config, &global, &mut ops).unwrap();
+
+        assert!(ops[0].params_required.is_empty());
+        let OpKind::TomlTransform { args, .. } = &ops[0].kind else {
+            panic!("expected toml transform");
+        };
+        assert_eq!(args.as_ref().unwrap()["rust_version"], "1.80");
+    }
+
     #[test]
     fn apply_plan_policy_assigns_ids_and_blocks_on_caps() {
         let mut ops = vec![
@@ -407,6 +829,7 @@ mod tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -414,6 +837,14 @@ mod tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         apply_plan_policy(&cfg, &mut ops).expect("apply policy");
@@ -422,10 +853,7 @@ mod tests {
         assert_eq!(ops[0].target.path, "a/Cargo.toml");
         assert_eq!(ops[1].target.path, "b/Cargo.toml");
         assert!(ops.iter().all(|op| op.blocked));
-        assert_eq!(
-            ops[0].blocked_reason_token.as_deref(),
-            Some(blocked_tokens::MAX_OPS)
-        );
+        assert_eq!(ops[0].blocked_reason_token, Some(BuildfixErrorCode::MaxOps));
     }
 
     #[test]
@@ -438,6 +866,7 @@ mod tests {
             blocked_reason_token: None,
             target: buildfix_types::ops::OpTarget {
                 path: "a/Cargo.toml".into(),
+                sha256: None,
             },
             kind: buildfix_types::ops::OpKind::TomlTransform {
                 rule_id: "set_package_license".into(),
@@ -450,12 +879,15 @@ mod tests {
             },
             params_required: vec!["license".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let mut ops = vec![op];
         let cfg = PlannerConfig {
             allow: vec!["cargo.*".into()],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -467,6 +899,14 @@ mod tests {
                 map.insert("license".to_string(), "MIT".to_string());
                 map
             },
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         apply_plan_policy(&cfg, &mut ops).expect("apply policy");
@@ -517,6 +957,7 @@ mod tests {
             blocked_reason_token: None,
             target: buildfix_types::ops::OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: buildfix_types::ops::OpKind::TomlTransform {
                 rule_id: "ensure_workspace_resolver_v2".into(),
@@ -532,6 +973,8 @@ mod tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let mut map1 = serde_json::Map::new();
@@ -572,7 +1015,10 @@ mod tests {
                 blocked: false,
                 blocked_reason: None,
                 blocked_reason_token: None,
-                target: buildfix_types::ops::OpTarget { path: "a".into() },
+                target: buildfix_types::ops::OpTarget {
+                    path: "a".into(),
+                    sha256: None,
+                },
                 kind: buildfix_types::ops::OpKind::TomlTransform {
                     rule_id: "set_package_rust_version".into(),
                     args: None,
@@ -584,6 +1030,8 @@ mod tests {
                 },
                 params_required: vec![],
                 preview: None,
+                impact: None,
+                workspace: None,
             },
             buildfix_types::plan::PlanOp {
                 id: String::new(),
@@ -591,7 +1039,10 @@ mod tests {
                 blocked: false,
                 blocked_reason: None,
                 blocked_reason_token: None,
-                target: buildfix_types::ops::OpTarget { path: "b".into() },
+                target: buildfix_types::ops::OpTarget {
+                    path: "b".into(),
+                    sha256: None,
+                },
                 kind: buildfix_types::ops::OpKind::TomlTransform {
                     rule_id: "set_package_rust_version".into(),
                     args: None,
@@ -603,12 +1054,15 @@ mod tests {
                 },
                 params_required: vec![],
                 preview: None,
+                impact: None,
+                workspace: None,
             },
         ];
 
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -616,6 +1070,14 @@ mod tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).expect("caps");
@@ -672,6 +1134,7 @@ mod tests {
             blocked_reason_token: None,
             target: buildfix_types::ops::OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: buildfix_types::ops::OpKind::TomlTransform {
                 rule_id: "test".into(),
@@ -684,6 +1147,8 @@ mod tests {
             },
             params_required: vec!["version".to_string(), "author".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         }];
 
         let params = HashMap::from([
@@ -706,6 +1171,7 @@ mod tests {
             blocked_reason_token: None,
             target: buildfix_types::ops::OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: buildfix_types::ops::OpKind::TomlTransform {
                 rule_id: "test".into(),
@@ -718,6 +1184,8 @@ mod tests {
             },
             params_required: vec!["version".to_string(), "author".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         }];
 
         let params = HashMap::from([("version".to_string(), "1.0.0".to_string())]);
@@ -10,11 +10,12 @@ use std::collections::HashMap;
 
 use buildfix_domain_policy::{
     apply_allow_deny, apply_params, apply_plan_policy, args_fingerprint, deterministic_op_id,
-    enforce_caps, glob_match, stable_op_sort_key,
+    enforce_caps, glob_match, resolve_conflicts, stable_op_sort_key,
 };
 use buildfix_fixer_api::PlannerConfig;
+use buildfix_types::error_code::BuildfixErrorCode;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
-use buildfix_types::plan::{PlanOp, Rationale, blocked_tokens};
+use buildfix_types::plan::{PathPolicy, PlanOp, Rationale};
 
 /// Helper to create a minimal PlanOp for testing.
 fn make_plan_op(path: &str, rule_id: &str, fix_key: &str) -> PlanOp {
@@ -26,6 +27,7 @@ fn make_plan_op(path: &str, rule_id: &str, fix_key: &str) -> PlanOp {
         blocked_reason_token: None,
         target: OpTarget {
             path: path.to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: rule_id.to_string(),
@@ -38,6 +40,8 @@ fn make_plan_op(path: &str, rule_id: &str, fix_key: &str) -> PlanOp {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     }
 }
 
@@ -57,6 +61,7 @@ fn make_plan_op_with_findings(
         blocked_reason_token: None,
         target: OpTarget {
             path: path.to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: rule_id.to_string(),
@@ -74,11 +79,14 @@ fn make_plan_op_with_findings(
                     path: None,
                     line: None,
                     fingerprint: None,
+                    severity: None,
                 })
                 .collect(),
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     }
 }
 
@@ -104,7 +112,7 @@ mod allow_deny_tests {
             ),
         ];
 
-        apply_allow_deny(&[], &[], &mut ops);
+        apply_allow_deny(&[], &[], &[], &mut ops);
 
         assert!(ops.iter().all(|op| !op.blocked));
     }
@@ -117,12 +125,17 @@ mod allow_deny_tests {
             "cargo.normalize_rust_version",
         )];
 
-        apply_allow_deny(&[], &["cargo.normalize_rust_version".to_string()], &mut ops);
+        apply_allow_deny(
+            &[],
+            &["cargo.normalize_rust_version".to_string()],
+            &[],
+            &mut ops,
+        );
 
         assert!(ops[0].blocked);
         assert_eq!(
             ops[0].blocked_reason_token,
-            Some(blocked_tokens::DENYLIST.to_string())
+            Some(BuildfixErrorCode::Denylist)
         );
     }
 
@@ -134,7 +147,7 @@ mod allow_deny_tests {
             make_plan_op("c/Cargo.toml", "rule", "clippy.lint"),
         ];
 
-        apply_allow_deny(&[], &["cargo.*".to_string()], &mut ops);
+        apply_allow_deny(&[], &["cargo.*".to_string()], &[], &mut ops);
 
         assert!(ops[0].blocked); // cargo.normalize_rust_version
         assert!(ops[1].blocked); // cargo.normalize_license
@@ -149,7 +162,12 @@ mod allow_deny_tests {
             "cargo.normalize_rust_version",
         )];
 
-        apply_allow_deny(&["cargo.normalize_rust_version".to_string()], &[], &mut ops);
+        apply_allow_deny(
+            &["cargo.normalize_rust_version".to_string()],
+            &[],
+            &[],
+            &mut ops,
+        );
 
         assert!(!ops[0].blocked);
     }
@@ -162,14 +180,14 @@ mod allow_deny_tests {
             make_plan_op("c/Cargo.toml", "rule", "clippy.lint"),
         ];
 
-        apply_allow_deny(&["cargo.*".to_string()], &[], &mut ops);
+        apply_allow_deny(&["cargo.*".to_string()], &[], &[], &mut ops);
 
         assert!(!ops[0].blocked); // cargo.normalize_rust_version
         assert!(!ops[1].blocked); // cargo.normalize_license
         assert!(ops[2].blocked); // clippy.lint - not in allowlist
         assert_eq!(
             ops[2].blocked_reason_token,
-            Some(blocked_tokens::ALLOWLIST_MISSING.to_string())
+            Some(BuildfixErrorCode::AllowlistMissing)
         );
     }
 
@@ -185,13 +203,14 @@ mod allow_deny_tests {
         apply_allow_deny(
             &["cargo.*".to_string()],
             &["cargo.normalize_rust_version".to_string()],
+            &[],
             &mut ops,
         );
 
         assert!(ops[0].blocked);
         assert_eq!(
             ops[0].blocked_reason_token,
-            Some(blocked_tokens::DENYLIST.to_string())
+            Some(BuildfixErrorCode::Denylist)
         );
     }
 
@@ -204,10 +223,10 @@ mod allow_deny_tests {
         )];
         ops[0].blocked = true;
         ops[0].blocked_reason = Some("pre-existing block".to_string());
-        ops[0].blocked_reason_token = Some("PRE_EXISTING".to_string());
+        ops[0].blocked_reason_token = Some(BuildfixErrorCode::StaleReceipt);
 
         // Try to deny - should not change existing block
-        apply_allow_deny(&[], &["cargo.*".to_string()], &mut ops);
+        apply_allow_deny(&[], &["cargo.*".to_string()], &[], &mut ops);
 
         assert!(ops[0].blocked);
         assert_eq!(
@@ -227,6 +246,7 @@ mod allow_deny_tests {
         apply_allow_deny(
             &[],
             &["cargo.*".to_string(), "clippy.*".to_string()],
+            &[],
             &mut ops,
         );
 
@@ -246,6 +266,7 @@ mod allow_deny_tests {
         apply_allow_deny(
             &["cargo.*".to_string(), "clippy.*".to_string()],
             &[],
+            &[],
             &mut ops,
         );
 
@@ -269,10 +290,69 @@ mod allow_deny_tests {
         )];
 
         // Should match the constructed finding key format
-        apply_allow_deny(&[], &["cargo-deny/licenses/missing".to_string()], &mut ops);
+        apply_allow_deny(
+            &[],
+            &["cargo-deny/licenses/missing".to_string()],
+            &[],
+            &mut ops,
+        );
 
         assert!(ops[0].blocked);
     }
+
+    #[test]
+    fn path_policies_apply_to_matching_targets_only() {
+        let mut guarded = make_plan_op(
+            "crates/experimental/x/Cargo.toml",
+            "rule",
+            "cargo.normalize_edition",
+        );
+        guarded.safety = SafetyClass::Guarded;
+        let mut ops = vec![
+            make_plan_op("vendor/foo/Cargo.toml", "rule", "cargo.normalize_license"),
+            guarded,
+            make_plan_op(
+                "crates/experimental/y/Cargo.toml",
+                "rule",
+                "cargo.normalize_license",
+            ),
+            make_plan_op("crates/core/Cargo.toml", "rule", "cargo.normalize_license"),
+        ];
+        let rules = vec![
+            PathPolicy {
+                path: "vendor/**".to_string(),
+                deny: vec!["*".to_string()],
+                ..PathPolicy::default()
+            },
+            PathPolicy {
+                path: "crates/experimental/**".to_string(),
+                max_safety: Some(SafetyClass::Safe),
+                ..PathPolicy::default()
+            },
+            PathPolicy {
+                path: "crates/core/*".to_string(),
+                allow: vec!["cargo.normalize_edition".to_string()],
+                ..PathPolicy::default()
+            },
+        ];
+
+        apply_allow_deny(&[], &[], &rules, &mut ops);
+
+        let tokens: Vec<_> = ops.iter().map(|op| op.blocked_reason_token).collect();
+        assert_eq!(
+            tokens,
+            vec![
+                Some(BuildfixErrorCode::PathDenylist),
+                Some(BuildfixErrorCode::PathSafetyNotAllowed),
+                None,
+                Some(BuildfixErrorCode::PathAllowlistMissing),
+            ]
+        );
+        assert_eq!(
+            ops[1].blocked_reason.as_deref(),
+            Some("guarded ops are not allowed under crates/experimental/**")
+        );
+    }
 }
 
 // =============================================================================
@@ -293,6 +373,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -300,6 +381,14 @@ mod caps_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -308,7 +397,7 @@ mod caps_tests {
         assert!(ops.iter().all(|op| op.blocked));
         assert!(
             ops.iter()
-                .all(|op| op.blocked_reason_token.as_deref() == Some(blocked_tokens::MAX_OPS))
+                .all(|op| op.blocked_reason_token == Some(BuildfixErrorCode::MaxOps))
         );
     }
 
@@ -322,6 +411,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -329,6 +419,14 @@ mod caps_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -347,6 +445,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -354,6 +453,14 @@ mod caps_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -373,6 +480,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -380,6 +488,14 @@ mod caps_tests {
             max_files: Some(2),
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -388,7 +504,7 @@ mod caps_tests {
         assert!(ops.iter().all(|op| op.blocked));
         assert!(
             ops.iter()
-                .all(|op| op.blocked_reason_token.as_deref() == Some(blocked_tokens::MAX_FILES))
+                .all(|op| op.blocked_reason_token == Some(BuildfixErrorCode::MaxFiles))
         );
     }
 
@@ -404,6 +520,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -411,6 +528,14 @@ mod caps_tests {
             max_files: Some(2),
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -430,6 +555,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -437,6 +563,14 @@ mod caps_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -455,6 +589,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -462,6 +597,14 @@ mod caps_tests {
             max_files: Some(10), // Would not be exceeded
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         enforce_caps(&cfg, &mut ops).unwrap();
@@ -469,7 +612,7 @@ mod caps_tests {
         assert!(ops.iter().all(|op| op.blocked));
         assert!(
             ops.iter()
-                .all(|op| op.blocked_reason_token.as_deref() == Some(blocked_tokens::MAX_OPS))
+                .all(|op| op.blocked_reason_token == Some(BuildfixErrorCode::MaxOps))
         );
     }
 
@@ -480,6 +623,7 @@ mod caps_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -487,6 +631,14 @@ mod caps_tests {
             max_files: Some(0),
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         let mut ops_mut = ops;
@@ -523,6 +675,7 @@ mod params_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "set_package_license".into(),
@@ -535,6 +688,8 @@ mod params_tests {
             },
             params_required: vec!["license".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         }];
 
         let params = HashMap::from([("license".to_string(), "MIT".to_string())]);
@@ -564,6 +719,7 @@ mod params_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "test".into(),
@@ -576,6 +732,8 @@ mod params_tests {
             },
             params_required: vec!["version".to_string(), "author".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         }];
 
         // Only provide one of two required params
@@ -586,7 +744,7 @@ mod params_tests {
         assert!(ops[0].blocked);
         assert_eq!(
             ops[0].blocked_reason_token,
-            Some(blocked_tokens::MISSING_PARAMS.to_string())
+            Some(BuildfixErrorCode::MissingParams)
         );
         assert!(ops[0].blocked_reason.as_ref().unwrap().contains("author"));
     }
@@ -602,6 +760,7 @@ mod params_tests {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: "a.toml".into(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "set_package_rust_version".into(),
@@ -614,6 +773,8 @@ mod params_tests {
                 },
                 params_required: vec!["rust_version".to_string()],
                 preview: None,
+                impact: None,
+                workspace: None,
             },
             PlanOp {
                 id: String::new(),
@@ -623,6 +784,7 @@ mod params_tests {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: "b.toml".into(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "rule".into(),
@@ -635,6 +797,8 @@ mod params_tests {
                 },
                 params_required: vec![], // No params required
                 preview: None,
+                impact: None,
+                workspace: None,
             },
         ];
 
@@ -886,6 +1050,7 @@ mod integration_tests {
         let cfg = PlannerConfig {
             allow: vec!["cargo.*".to_string()],
             deny: vec!["cargo.fix_b".to_string()],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -893,6 +1058,14 @@ mod integration_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         apply_plan_policy(&cfg, &mut ops).unwrap();
@@ -912,14 +1085,14 @@ mod integration_tests {
         assert!(ops[1].blocked);
         assert_eq!(
             ops[1].blocked_reason_token,
-            Some(blocked_tokens::DENYLIST.to_string())
+            Some(BuildfixErrorCode::Denylist)
         );
 
         // clippy.lint: not in allowlist -> blocked
         assert!(ops[2].blocked);
         assert_eq!(
             ops[2].blocked_reason_token,
-            Some(blocked_tokens::ALLOWLIST_MISSING.to_string())
+            Some(BuildfixErrorCode::AllowlistMissing)
         );
     }
 
@@ -933,6 +1106,7 @@ mod integration_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "set_package_license".into(),
@@ -945,11 +1119,14 @@ mod integration_tests {
             },
             params_required: vec!["license".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         }];
 
         let cfg = PlannerConfig {
             allow: vec!["cargo.*".to_string()],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -957,6 +1134,14 @@ mod integration_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::from([("license".to_string(), "MIT".to_string())]),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         apply_plan_policy(&cfg, &mut ops).unwrap();
@@ -978,6 +1163,7 @@ mod integration_tests {
         let cfg = PlannerConfig {
             allow: vec![],
             deny: vec![],
+            path_policies: Vec::new(),
             allow_guarded: false,
             allow_unsafe: false,
             allow_dirty: false,
@@ -985,6 +1171,14 @@ mod integration_tests {
             max_files: None,
             max_patch_bytes: None,
             params: HashMap::new(),
+            fixers: Default::default(),
+            min_severity: None,
+            sensors: Vec::new(),
+            exclude_check_ids: Vec::new(),
+            changed_paths: None,
+            packages: Vec::new(),
+            exclude_packages: Vec::new(),
+            scan: Default::default(),
         };
 
         apply_plan_policy(&cfg, &mut ops).unwrap();
@@ -1010,10 +1204,12 @@ mod op_kind_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["package".into(), "version".into()],
                 value: "1.0.0".into(),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "test.fix".into(),
@@ -1022,6 +1218,8 @@ mod op_kind_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let key = stable_op_sort_key(&op);
@@ -1039,6 +1237,7 @@ mod op_kind_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlRemove {
                 toml_path: vec!["dependencies".into(), "unused".into()],
@@ -1050,6 +1249,8 @@ mod op_kind_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let key = stable_op_sort_key(&op);
@@ -1067,6 +1268,7 @@ mod op_kind_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "config.json".into(),
+                sha256: None,
             },
             kind: OpKind::JsonSet {
                 json_path: vec!["settings".into(), "debug".into()],
@@ -1079,6 +1281,8 @@ mod op_kind_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let key = stable_op_sort_key(&op);
@@ -1095,6 +1299,7 @@ mod op_kind_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "src/lib.rs".into(),
+                sha256: None,
             },
             kind: OpKind::TextReplaceAnchored {
                 find: "old".into(),
@@ -1110,6 +1315,8 @@ mod op_kind_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let key = stable_op_sort_key(&op);
@@ -1128,10 +1335,12 @@ mod op_kind_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["version".into()],
                 value: "1.0".into(),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "test".into(),
@@ -1140,6 +1349,8 @@ mod op_kind_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         let op2 = PlanOp {
@@ -1150,6 +1361,7 @@ mod op_kind_tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlRemove {
                 toml_path: vec!["version".into()],
@@ -1161,9 +1373,223 @@ mod op_kind_tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         };
 
         // Different op kinds should produce different IDs
         assert_ne!(deterministic_op_id(&op1), deterministic_op_id(&op2));
     }
 }
+
+// =============================================================================
+// CONFLICT RESOLUTION TESTS
+// =============================================================================
+
+mod conflict_tests {
+    use super::*;
+
+    fn op(fix_key: &str, kind: OpKind) -> PlanOp {
+        let mut op = make_plan_op_with_findings(
+            "crates/a/Cargo.toml",
+            "unused",
+            fix_key,
+            vec![(fix_key.to_string(), None, "finding".to_string())],
+        );
+        op.kind = kind;
+        op
+    }
+
+    fn set(path: &[&str], value: serde_json::Value) -> OpKind {
+        OpKind::TomlSet {
+            toml_path: path.iter().map(|s| s.to_string()).collect(),
+            value,
+            insert: None,
+        }
+    }
+
+    #[test]
+    fn identical_ops_are_merged_into_one() {
+        let mut ops = vec![
+            op("cargo.a", set(&["package", "edition"], "2021".into())),
+            op("cargo.b", set(&["package", "edition"], "2021".into())),
+        ];
+        ops[1].safety = SafetyClass::Guarded;
+
+        resolve_conflicts(&mut ops);
+
+        assert_eq!(ops.len(), 1);
+        assert!(!ops[0].blocked);
+        assert_eq!(ops[0].rationale.fix_key, "cargo.a");
+        assert_eq!(ops[0].safety, SafetyClass::Guarded);
+        let sources: Vec<_> = ops[0]
+            .rationale
+            .findings
+            .iter()
+            .map(|f| f.source.as_str())
+            .collect();
+        assert_eq!(sources, ["cargo.a", "cargo.b"]);
+    }
+
+    #[test]
+    fn disagreeing_writes_block_both_ops() {
+        let mut ops = vec![
+            op("cargo.a", set(&["package", "edition"], "2021".into())),
+            op("cargo.b", set(&["package", "edition"], "2024".into())),
+        ];
+
+        resolve_conflicts(&mut ops);
+
+        assert_eq!(ops.len(), 2);
+        for (op, other) in ops.iter().zip(["cargo.b", "cargo.a"]) {
+            assert!(op.blocked);
+            assert_eq!(
+                op.blocked_reason_token,
+                Some(BuildfixErrorCode::ConflictingOps)
+            );
+            let reason = op.blocked_reason.as_deref().unwrap();
+            assert!(reason.contains(other), "{reason}");
+            assert!(reason.contains("package.edition"), "{reason}");
+        }
+    }
+
+    #[test]
+    fn prefix_overlap_conflicts() {
+        let mut ops = vec![
+            op(
+                "cargo.a",
+                OpKind::TomlRemove {
+                    toml_path: vec!["dependencies".into(), "serde".into()],
+                },
+            ),
+            op(
+                "cargo.b",
+                set(&["dependencies", "serde", "version"], "1".into()),
+            ),
+            op("cargo.c", set(&["package", "edition"], "2021".into())),
+        ];
+
+        resolve_conflicts(&mut ops);
+
+        assert!(ops[0].blocked);
+        assert!(ops[1].blocked);
+        assert!(!ops[2].blocked);
+    }
+
+    #[test]
+    fn agreeing_writes_and_appends_do_not_conflict() {
+        let mut table = serde_json::Map::new();
+        table.insert("version".into(), "1".into());
+        let mut ops = vec![
+            op(
+                "cargo.a",
+                OpKind::TomlMerge {
+                    toml_path: vec!["dependencies".into(), "serde".into()],
+                    table,
+                },
+            ),
+            op(
+                "cargo.b",
+                set(&["dependencies", "serde", "version"], "1".into()),
+            ),
+            op(
+                "cargo.c",
+                OpKind::TomlArrayAppend {
+                    toml_path: vec!["workspace".into(), "members".into()],
+                    values: vec!["a".into()],
+                    unique: true,
+                },
+            ),
+            op(
+                "cargo.d",
+                OpKind::TomlArrayAppend {
+                    toml_path: vec!["workspace".into(), "members".into()],
+                    values: vec!["b".into()],
+                    unique: true,
+                },
+            ),
+        ];
+
+        resolve_conflicts(&mut ops);
+
+        assert_eq!(ops.len(), 4);
+        assert!(ops.iter().all(|op| !op.blocked));
+    }
+
+    #[test]
+    fn transforms_conflict_with_sets_of_the_same_key() {
+        let mut ops = vec![
+            op(
+                "cargo.a",
+                OpKind::TomlTransform {
+                    rule_id: "set_package_edition".into(),
+                    args: Some(serde_json::json!({ "edition": "2021" })),
+                },
+            ),
+            op("cargo.b", set(&["package", "edition"], "2024".into())),
+            op(
+                "cargo.c",
+                OpKind::TomlTransform {
+                    rule_id: "ensure_path_dep_has_version".into(),
+                    args: Some(serde_json::json!({
+                        "toml_path": ["dependencies", "b"],
+                        "dep_path": "../b",
+                        "version": "0.1.0",
+                    })),
+                },
+            ),
+            op(
+                "cargo.d",
+                OpKind::TomlTransform {
+                    rule_id: "use_workspace_dependency".into(),
+                    args: Some(serde_json::json!({ "toml_path": ["dependencies", "b"] })),
+                },
+            ),
+        ];
+
+        resolve_conflicts(&mut ops);
+
+        assert!(ops.iter().all(|op| op.blocked));
+    }
+
+    #[test]
+    fn other_files_and_blocked_ops_are_ignored() {
+        let mut ops = vec![
+            op("cargo.a", set(&["package", "edition"], "2021".into())),
+            op("cargo.b", set(&["package", "edition"], "2024".into())),
+            op("cargo.c", set(&["package", "edition"], "2018".into())),
+        ];
+        ops[1].target.path = "crates/b/Cargo.toml".into();
+        ops[2].blocked = true;
+        ops[2].blocked_reason_token = Some(BuildfixErrorCode::Denylist);
+
+        resolve_conflicts(&mut ops);
+
+        assert!(!ops[0].blocked);
+        assert!(!ops[1].blocked);
+        assert_eq!(
+            ops[2].blocked_reason_token,
+            Some(BuildfixErrorCode::Denylist)
+        );
+    }
+
+    #[test]
+    fn apply_plan_policy_resolves_conflicts_after_deny() {
+        let mut ops = vec![
+            op("cargo.a", set(&["package", "edition"], "2021".into())),
+            op("cargo.b", set(&["package", "edition"], "2024".into())),
+        ];
+        let cfg = PlannerConfig {
+            deny: vec!["cargo.b/*".to_string()],
+            ..PlannerConfig::default()
+        };
+
+        apply_plan_policy(&cfg, &mut ops).unwrap();
+
+        assert!(!ops[0].blocked);
+        assert_eq!(
+            ops[1].blocked_reason_token,
+            Some(BuildfixErrorCode::Denylist)
+        );
+    }
+}
@@ -98,6 +98,7 @@ impl DuplicateDepsConsolidationFixer {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "ensure_workspace_dependency_version".to_string(),
@@ -141,6 +142,7 @@ impl DuplicateDepsConsolidationFixer {
             blocked_reason_token: None,
             target: OpTarget {
                 path: cand.manifest.to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "use_workspace_dependency".to_string(),
@@ -518,6 +520,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/depguard/report.json"),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -552,6 +555,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         }
     }
 
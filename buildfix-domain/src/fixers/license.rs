@@ -242,6 +242,7 @@ impl Fixer for LicenseNormalizeFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "set_package_license".to_string(),
@@ -368,6 +369,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo-deny/report.json"),
             sensor_id: "cargo-deny".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
 
         ReceiptSet::from_loaded(&loaded)
@@ -378,6 +380,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         }
     }
 
@@ -1,5 +1,7 @@
 use buildfix_fixer_api::{Fixer, FixerMeta};
 
+#[cfg(feature = "fixer-clippy-suggestions")]
+use buildfix_fixer_clippy_suggestions as clippy_suggestions;
 #[cfg(feature = "fixer-duplicate-deps")]
 use buildfix_fixer_duplicate_deps as duplicate_deps;
 #[cfg(feature = "fixer-edition")]
@@ -37,6 +39,8 @@ pub fn builtin_fixers() -> Vec<Box<dyn Fixer>> {
     fixers.push(Box::new(edition::EditionUpgradeFixer));
     #[cfg(feature = "fixer-license")]
     fixers.push(Box::new(license::LicenseNormalizeFixer));
+    #[cfg(feature = "fixer-clippy-suggestions")]
+    fixers.push(Box::new(clippy_suggestions::ClippySuggestionsFixer));
 
     fixers
 }
@@ -68,6 +72,11 @@ mod tests {
             let meta = fixer.meta();
             assert!(!meta.fix_key.is_empty());
             assert!(!meta.description.is_empty());
+            assert!(!meta.title.is_empty());
+            assert!(!meta.details.is_empty());
+            assert!(!meta.safety_rationale.is_empty());
+            assert!(!meta.remediation.is_empty());
+            assert!(!meta.triggers.is_empty());
             keys.insert(meta.fix_key);
         }
 
@@ -106,6 +115,12 @@ mod tests {
                 "safety mismatch for {}",
                 meta.fix_key
             );
+            assert_eq!(meta.key, entry.key, "key mismatch for {}", meta.fix_key);
+            assert_eq!(
+                meta.triggers, entry.triggers,
+                "trigger mismatch for {}",
+                meta.fix_key
+            );
 
             let meta_sensors: BTreeSet<&'static str> =
                 meta.consumes_sensors.iter().copied().collect();
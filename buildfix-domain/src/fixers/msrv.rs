@@ -158,6 +158,7 @@ impl Fixer for MsrvNormalizeFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "set_package_rust_version".to_string(),
@@ -269,6 +270,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -338,6 +340,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/a/Cargo.toml");
@@ -371,6 +374,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/a/Cargo.toml");
@@ -291,6 +291,7 @@ impl Fixer for PathDepVersionFixer {
                     blocked_reason_token: None,
                     target: OpTarget {
                         path: manifest_path,
+                        sha256: None,
                     },
                     kind: OpKind::TomlTransform {
                         rule_id: "ensure_path_dep_has_version".to_string(),
@@ -408,6 +409,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/depguard/report.json"),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -488,6 +490,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/app/Cargo.toml");
@@ -515,6 +518,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/app/Cargo.toml");
@@ -567,6 +571,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let repo_missing = TestRepo::new(&[]);
@@ -163,6 +163,7 @@ impl Fixer for RemoveUnusedDepsFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: group.manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlRemove {
                     toml_path: group.toml_path,
@@ -333,6 +334,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo-machete/report.json"),
             sensor_id: "cargo-machete".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -358,6 +360,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         }
     }
 
@@ -81,6 +81,7 @@ impl Fixer for ResolverV2Fixer {
             blocked_reason_token: None,
             target: OpTarget {
                 path: manifest.to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -189,6 +190,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo/report.json"),
             sensor_id: "cargo".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -239,6 +241,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
         let fixes = ResolverV2Fixer
             .plan(&ctx, &repo, &receipt_set())
@@ -434,6 +434,7 @@ impl Fixer for WorkspaceInheritanceFixer {
                     blocked_reason_token: None,
                     target: OpTarget {
                         path: manifest.to_string(),
+                        sha256: None,
                     },
                     kind: OpKind::TomlTransform {
                         rule_id: "use_workspace_dependency".to_string(),
@@ -546,6 +547,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/depguard/report.json"),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -597,6 +599,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/member/Cargo.toml");
@@ -768,6 +771,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/member/Cargo.toml");
@@ -787,6 +791,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/member/Cargo.toml");
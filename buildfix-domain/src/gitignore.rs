@@ -0,0 +1,112 @@
+//! `.gitignore` matching for [`RepoView::is_gitignored`](crate::RepoView::is_gitignored).
+//!
+//! Each directory's `.gitignore` is parsed the first time a path below it is
+//! checked. Rules in deeper files win over shallower ones, as in git, and the
+//! repo root also reads `.git/info/exclude`. Whether a path is tracked is not
+//! consulted: a committed file matching an ignore rule still counts as ignored.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use ignore::Match;
+use ignore::gitignore::{Gitignore, GitignoreBuilder};
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Lazily loaded `.gitignore` rules of the repo at `root`; clones share them.
+#[derive(Debug, Clone)]
+pub struct GitignoreMatcher {
+    root: Utf8PathBuf,
+    dirs: Arc<Mutex<HashMap<Utf8PathBuf, Arc<Gitignore>>>>,
+}
+
+impl GitignoreMatcher {
+    pub fn new(root: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            dirs: Arc::default(),
+        }
+    }
+
+    /// Whether `rel` (repo-relative, a file) is ignored.
+    pub fn is_ignored(&self, rel: &Utf8Path) -> bool {
+        let rel = rel.strip_prefix("./").unwrap_or(rel);
+        // Absolute paths outside the repo have no rules to match.
+        let rel = match rel.strip_prefix(&self.root) {
+            Ok(inner) => inner,
+            Err(_) if rel.is_absolute() => return false,
+            Err(_) => rel,
+        };
+        let abs = self.root.join(rel);
+        let mut dirs: Vec<&Utf8Path> = rel.ancestors().skip(1).collect();
+        dirs.reverse();
+
+        let mut ignored = false;
+        for dir in dirs {
+            match self.rules(dir).matched_path_or_any_parents(&abs, false) {
+                Match::None => {}
+                Match::Ignore(_) => ignored = true,
+                Match::Whitelist(_) => ignored = false,
+            }
+        }
+        ignored
+    }
+
+    fn rules(&self, dir: &Utf8Path) -> Arc<Gitignore> {
+        let mut dirs = self.dirs.lock().unwrap_or_else(|e| e.into_inner());
+        let rules = dirs.entry(dir.to_path_buf()).or_insert_with(|| {
+            let abs = self.root.join(dir);
+            let mut builder = GitignoreBuilder::new(&abs);
+            // Unreadable or malformed files contribute whatever rules parsed.
+            let _ = builder.add(abs.join(".gitignore"));
+            if dir.as_str().is_empty() {
+                let _ = builder.add(abs.join(".git/info/exclude"));
+            }
+            Arc::new(builder.build().unwrap_or_else(|_| Gitignore::empty()))
+        });
+        Arc::clone(rules)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn write(root: &Utf8Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn nested_gitignores_apply_below_their_directory() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        write(root, ".gitignore", "/scratch/\n*.generated.toml\n");
+        write(
+            root,
+            "crates/a/.gitignore",
+            "fixtures/\n!keep.generated.toml\n",
+        );
+        write(root, ".git/info/exclude", "local/\n");
+        let matcher = GitignoreMatcher::new(root);
+        let ignored = |p: &str| matcher.is_ignored(Utf8Path::new(p));
+
+        assert!(ignored("scratch/Cargo.toml"));
+        assert!(ignored("crates/b/config.generated.toml"));
+        assert!(ignored("crates/a/fixtures/bad/Cargo.toml"));
+        assert!(ignored("local/Cargo.toml"));
+
+        assert!(!ignored("Cargo.toml"));
+        assert!(!ignored("crates/a/Cargo.toml"));
+        assert!(!ignored("crates/a/scratch/Cargo.toml"));
+        assert!(!ignored("crates/b/fixtures/Cargo.toml"));
+        assert!(!ignored("crates/a/keep.generated.toml"));
+    }
+
+    #[test]
+    fn repo_without_gitignore_ignores_nothing() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        let matcher = GitignoreMatcher::new(root);
+        assert!(!matcher.is_ignored(Utf8Path::new("target/Cargo.toml")));
+    }
+}
@@ -0,0 +1,139 @@
+//! [`OpImpact`] estimates attached to each planned op.
+//!
+//! Everything here is derived from the op itself and the workspace layout;
+//! nothing is built or resolved. `affected_crates` is the package owning the
+//! target file, or every workspace member for files only the workspace root
+//! owns (when a workspace graph was loaded).
+
+use crate::packages::owning_package;
+use crate::ports::RepoView;
+use buildfix_fixer_api::PlanContext;
+use buildfix_types::ops::OpKind;
+use buildfix_types::plan::{OpImpact, PlanOp};
+use camino::Utf8Path;
+use std::collections::BTreeMap;
+
+const DEPENDENCY_TABLES: &[&str] = &["dependencies", "dev-dependencies", "build-dependencies"];
+
+/// Set `impact` on every op.
+pub(crate) fn annotate_impact(ctx: &PlanContext, repo: &dyn RepoView, ops: &mut [PlanOp]) {
+    let graph = ctx.workspace_graph.as_ref();
+    let members: Vec<String> = graph
+        .map(|g| {
+            let mut names: Vec<String> = g.member_packages().map(|p| p.name.clone()).collect();
+            names.sort();
+            names.dedup();
+            names
+        })
+        .unwrap_or_default();
+    let mut owners: BTreeMap<String, Option<String>> = BTreeMap::new();
+
+    for op in ops {
+        let owner = owners
+            .entry(op.target.path.clone())
+            .or_insert_with(|| owning_package(repo, graph, &op.target.path));
+        let affected_crates = match owner {
+            Some(name) => vec![name.clone()],
+            None => members.clone(),
+        };
+        let manifest = is_manifest(&op.target.path);
+        op.impact = Some(OpImpact {
+            affected_crates,
+            lockfile_update: manifest && lockfile_update(&op.kind),
+            semver_visible: manifest && semver_visible(&op.kind),
+            ..OpImpact::estimate(&op.kind)
+        });
+    }
+}
+
+fn is_manifest(path: &str) -> bool {
+    Utf8Path::new(path).file_name() == Some("Cargo.toml")
+}
+
+/// Changes to dependency requirements, `[patch]` or `[replace]` can move
+/// resolved versions in `Cargo.lock`.
+fn lockfile_update(kind: &OpKind) -> bool {
+    match kind {
+        OpKind::TomlTransform { rule_id, .. } => matches!(
+            rule_id.as_str(),
+            "ensure_workspace_dependency_version" | "use_workspace_dependency"
+        ),
+        _ => kind.toml_path().is_some_and(|path| {
+            path.iter()
+                .any(|seg| DEPENDENCY_TABLES.contains(&seg.as_str()))
+                || matches!(path.first().map(String::as_str), Some("patch" | "replace"))
+        }),
+    }
+}
+
+/// Changes dependents can observe: the MSRV, the package version, features
+/// and normal (possibly public) dependencies.
+fn semver_visible(kind: &OpKind) -> bool {
+    match kind {
+        OpKind::TomlTransform { rule_id, .. } => rule_id == "set_package_rust_version",
+        _ => {
+            let Some(path) = kind.toml_path() else {
+                return false;
+            };
+            let path: Vec<&str> = path.iter().map(String::as_str).collect();
+            matches!(
+                path.as_slice(),
+                ["package", "rust-version" | "version", ..]
+                    | ["workspace", "package", "rust-version" | "version", ..]
+                    | ["features", ..]
+                    | ["dependencies", ..]
+                    | ["target", _, "dependencies", ..]
+            )
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn set(path: &[&str]) -> OpKind {
+        OpKind::TomlSet {
+            toml_path: path.iter().map(|s| s.to_string()).collect(),
+            value: serde_json::json!("x"),
+            insert: None,
+        }
+    }
+
+    fn transform(rule_id: &str) -> OpKind {
+        OpKind::TomlTransform {
+            rule_id: rule_id.to_string(),
+            args: None,
+        }
+    }
+
+    #[test]
+    fn dependency_changes_update_the_lockfile() {
+        assert!(lockfile_update(&set(&["dependencies", "serde"])));
+        assert!(lockfile_update(&set(&[
+            "workspace",
+            "dependencies",
+            "serde"
+        ])));
+        assert!(lockfile_update(&set(&["patch", "crates-io", "serde"])));
+        assert!(lockfile_update(&transform("use_workspace_dependency")));
+        assert!(!lockfile_update(&set(&["package", "edition"])));
+        assert!(!lockfile_update(&transform("set_package_license")));
+    }
+
+    #[test]
+    fn msrv_features_and_normal_deps_are_semver_visible() {
+        assert!(semver_visible(&set(&["package", "rust-version"])));
+        assert!(semver_visible(&set(&[
+            "workspace",
+            "package",
+            "rust-version"
+        ])));
+        assert!(semver_visible(&set(&["features", "default"])));
+        assert!(semver_visible(&set(&["dependencies", "serde"])));
+        assert!(semver_visible(&transform("set_package_rust_version")));
+        assert!(!semver_visible(&set(&["dev-dependencies", "serde"])));
+        assert!(!semver_visible(&set(&["package", "license"])));
+        assert!(!semver_visible(&transform("set_package_edition")));
+    }
+}
@@ -4,10 +4,22 @@
 //! the `buildfix-edit` crate.
 
 mod fixers;
+mod gitignore;
+mod impact;
+mod merge;
+mod packages;
 mod planner;
 mod ports;
+mod workspaces;
 
-pub use buildfix_fixer_api::{FixerMeta, MatchedFinding, PlanContext, PlannerConfig, ReceiptSet};
+pub use buildfix_fixer_api::{
+    FixerConfig, FixerMeta, MatchedFinding, PlanContext, PlannerConfig, ReceiptSet, TriggerPattern,
+    WorkspaceGraph, skipped_by_scan,
+};
 pub use fixers::builtin_fixer_metas;
+pub use gitignore::GitignoreMatcher;
+pub use merge::merge_plans;
+pub use packages::{owning_package, package_selected, unmatched_package_patterns};
 pub use planner::Planner;
 pub use ports::{FsRepoView, RepoView};
+pub use workspaces::{discover_workspace_roots, owning_workspace, workspace_label};
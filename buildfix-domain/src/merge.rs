@@ -0,0 +1,387 @@
+//! [`merge_plans`]: one plan from several partial ones.
+//!
+//! Plans made from different artifact sets of the same checkout (one per
+//! sensor, say) are combined into the plan a single run over every receipt
+//! would have produced: ops are deduplicated by their deterministic id and
+//! the first plan's policy and caps are applied again to the union.
+
+use anyhow::bail;
+use buildfix_domain_policy::apply_plan_policy;
+use buildfix_fixer_api::PlannerConfig;
+use buildfix_types::error_code::BuildfixErrorCode;
+use buildfix_types::plan::{BuildfixPlan, FilePrecondition, PlanCapabilities, PlanOp};
+use chrono::DateTime;
+use std::collections::{BTreeMap, BTreeSet};
+
+use crate::planner::summarize;
+use crate::workspaces::summarize_workspaces;
+
+/// Combine `plans` into one.
+///
+/// Ops sharing an id are kept once, with the findings of every copy. Ops
+/// blocked by `max_ops` or `max_files` in a partial plan are unblocked
+/// before the caps are enforced on the merged ops; other blocks stand.
+/// Tool, repo and policy come from the first plan, inputs and file
+/// preconditions are the union, and the plan expires with the earliest
+/// `expires_at`. Workspace summaries are counted again over the merged ops.
+/// The merged plan has no patch preview, so `patch_bytes` is unset.
+///
+/// Fails when `plans` is empty, or when the plans were made at different
+/// commits or saw different contents for the same file.
+pub fn merge_plans(plans: Vec<BuildfixPlan>) -> anyhow::Result<BuildfixPlan> {
+    let Some(first) = plans.first() else {
+        bail!("no plans to merge");
+    };
+    let heads: BTreeSet<&str> = plans
+        .iter()
+        .filter_map(|p| p.repo.head_sha.as_deref())
+        .collect();
+    if heads.len() > 1 {
+        bail!(
+            "plans were made at different commits: {}",
+            heads.into_iter().collect::<Vec<_>>().join(", ")
+        );
+    }
+
+    let mut merged =
+        BuildfixPlan::new(first.tool.clone(), first.repo.clone(), first.policy.clone());
+    merged.preconditions.mode = first.preconditions.mode;
+    merged.expires_at = earliest_expiry(&plans);
+
+    let mut files: BTreeMap<String, FilePrecondition> = BTreeMap::new();
+    let mut inputs: BTreeSet<String> = BTreeSet::new();
+    let mut fixers: BTreeSet<String> = BTreeSet::new();
+    let mut workspaces: BTreeSet<String> = BTreeSet::new();
+    let mut ops: Vec<PlanOp> = Vec::new();
+    let mut index_of: BTreeMap<String, usize> = BTreeMap::new();
+
+    for plan in plans {
+        for file in plan.preconditions.files {
+            match files.get(&file.path) {
+                Some(seen) if seen.sha256 != file.sha256 => {
+                    bail!("plans saw different contents for {}", file.path)
+                }
+                Some(_) => {}
+                None => {
+                    files.insert(file.path.clone(), file);
+                }
+            }
+        }
+        merged.preconditions.head_sha = merged
+            .preconditions
+            .head_sha
+            .or(plan.preconditions.head_sha);
+        merged.preconditions.dirty = match (merged.preconditions.dirty, plan.preconditions.dirty) {
+            (Some(a), Some(b)) => Some(a || b),
+            (a, b) => a.or(b),
+        };
+        merged.repo.head_sha = merged.repo.head_sha.or(plan.repo.head_sha);
+
+        for input in plan.inputs {
+            if inputs.insert(input.path.clone()) {
+                merged.inputs.push(input);
+            }
+        }
+        if let Some(caps) = plan.capabilities {
+            fixers.extend(caps.fixers);
+        }
+        workspaces.extend(plan.workspaces.into_iter().map(|w| w.root));
+
+        for mut op in plan.ops {
+            if let Some(&i) = index_of.get(&op.id) {
+                let keep = &mut ops[i];
+                for finding in op.rationale.findings {
+                    let fingerprint = finding.stable_fingerprint();
+                    if !keep
+                        .rationale
+                        .findings
+                        .iter()
+                        .any(|f| f.stable_fingerprint() == fingerprint)
+                    {
+                        keep.rationale.findings.push(finding);
+                    }
+                }
+                continue;
+            }
+            if matches!(
+                op.blocked_reason_token,
+                Some(BuildfixErrorCode::MaxOps | BuildfixErrorCode::MaxFiles)
+            ) {
+                op.blocked = false;
+                op.blocked_reason = None;
+                op.blocked_reason_token = None;
+            }
+            index_of.insert(op.id.clone(), ops.len());
+            ops.push(op);
+        }
+    }
+    merged.preconditions.files = files.into_values().collect();
+
+    let config = PlannerConfig {
+        allow: merged.policy.allow.clone(),
+        deny: merged.policy.deny.clone(),
+        allow_guarded: merged.policy.allow_guarded,
+        allow_unsafe: merged.policy.allow_unsafe,
+        allow_dirty: merged.policy.allow_dirty,
+        max_ops: merged.policy.max_ops,
+        max_files: merged.policy.max_files,
+        max_patch_bytes: merged.policy.max_patch_bytes,
+        ..PlannerConfig::default()
+    };
+    apply_plan_policy(&config, &mut ops)?;
+
+    merged.summary = summarize(&ops);
+    if !workspaces.is_empty() {
+        merged.workspaces = summarize_workspaces(workspaces, &ops);
+    }
+    merged.capabilities = Some(PlanCapabilities::new(fixers, &ops));
+    merged.ops = ops;
+    Ok(merged)
+}
+
+/// The earliest `expires_at` among `plans`. One that does not parse wins,
+/// so apply still refuses the merged plan.
+fn earliest_expiry(plans: &[BuildfixPlan]) -> Option<String> {
+    plans
+        .iter()
+        .filter_map(|p| p.expires_at.as_deref())
+        .min_by_key(|at| DateTime::parse_from_rfc3339(at).ok())
+        .map(str::to_string)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+    use buildfix_types::plan::{
+        FindingRef, PathPolicy, PlanInput, PlanPolicy, PlanWorkspace, Rationale, RepoInfo,
+    };
+    use buildfix_types::receipt::ToolInfo;
+
+    fn plan(sensor: &str, ops: Vec<PlanOp>) -> BuildfixPlan {
+        let tool = ToolInfo {
+            name: "buildfix".to_string(),
+            version: Some("0.0.0".to_string()),
+            repo: None,
+            commit: None,
+        };
+        let repo = RepoInfo {
+            root: ".".to_string(),
+            head_sha: Some("deadbeef".to_string()),
+            dirty: None,
+            remote_url: None,
+            ci: None,
+        };
+        let mut plan = BuildfixPlan::new(tool, repo, PlanPolicy::default());
+        plan.inputs = vec![PlanInput {
+            path: format!("artifacts/{}/report.json", sensor),
+            schema: Some(format!("{}.report.v1", sensor)),
+            tool: Some(sensor.to_string()),
+            git_head_sha: None,
+            generated_at: None,
+            sha256: None,
+        }];
+        plan.preconditions.files = ops
+            .iter()
+            .map(|op| FilePrecondition {
+                path: op.target.path.clone(),
+                sha256: "abc".to_string(),
+                semantic_sha256: None,
+            })
+            .collect();
+        plan.ops = ops;
+        plan
+    }
+
+    fn op(id: &str, path: &str, sensor: &str) -> PlanOp {
+        PlanOp {
+            id: id.to_string(),
+            safety: SafetyClass::Safe,
+            blocked: false,
+            blocked_reason: None,
+            blocked_reason_token: None,
+            target: OpTarget {
+                path: path.to_string(),
+                sha256: None,
+            },
+            kind: OpKind::TomlSet {
+                toml_path: vec!["package".to_string(), "edition".to_string()],
+                value: serde_json::json!("2021"),
+                insert: None,
+            },
+            rationale: Rationale {
+                fix_key: "edition".to_string(),
+                description: None,
+                findings: vec![FindingRef {
+                    source: sensor.to_string(),
+                    check_id: None,
+                    code: "edition".to_string(),
+                    path: Some(path.to_string()),
+                    line: None,
+                    fingerprint: None,
+                    severity: None,
+                }],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: None,
+            workspace: None,
+        }
+    }
+
+    #[test]
+    fn merges_ops_by_id_and_unions_inputs_and_preconditions() {
+        let a = plan(
+            "builddiag",
+            vec![op("op-a", "crates/a/Cargo.toml", "builddiag")],
+        );
+        let b = plan(
+            "depguard",
+            vec![
+                op("op-a", "crates/a/Cargo.toml", "depguard"),
+                op("op-b", "crates/b/Cargo.toml", "depguard"),
+            ],
+        );
+
+        let merged = merge_plans(vec![a, b]).expect("merge");
+
+        let ids: Vec<&str> = merged.ops.iter().map(|o| o.id.as_str()).collect();
+        assert_eq!(ids, ["op-a", "op-b"]);
+        let sources: Vec<&str> = merged.ops[0]
+            .rationale
+            .findings
+            .iter()
+            .map(|f| f.source.as_str())
+            .collect();
+        assert_eq!(sources, ["builddiag", "depguard"]);
+        assert_eq!(merged.inputs.len(), 2);
+        assert_eq!(merged.preconditions.files.len(), 2);
+        assert_eq!(merged.summary.ops_total, 2);
+        assert_eq!(merged.summary.files_touched, 2);
+        assert_eq!(merged.summary.patch_bytes, None);
+        assert_eq!(merged.repo.head_sha.as_deref(), Some("deadbeef"));
+    }
+
+    #[test]
+    fn caps_are_enforced_on_the_union() {
+        let mut a = plan("a", vec![op("op-a", "crates/a/Cargo.toml", "a")]);
+        let mut b = plan("b", vec![op("op-b", "crates/b/Cargo.toml", "b")]);
+        a.policy.max_files = Some(1);
+        b.policy.max_files = Some(1);
+        let merged = merge_plans(vec![a.clone(), b.clone()]).expect("merge");
+        assert!(merged.ops.iter().all(|o| o.blocked));
+        assert_eq!(
+            merged.ops[0].blocked_reason_token,
+            Some(BuildfixErrorCode::MaxFiles)
+        );
+
+        // A cap hit by a partial plan is lifted when the union fits.
+        a.policy.max_files = Some(2);
+        b.ops[0].blocked = true;
+        b.ops[0].blocked_reason_token = Some(BuildfixErrorCode::MaxOps);
+        b.ops[0].blocked_reason = Some("too many ops".to_string());
+        let merged = merge_plans(vec![a, b]).expect("merge");
+        assert!(merged.ops.iter().all(|o| !o.blocked));
+        assert_eq!(merged.summary.ops_blocked, 0);
+    }
+
+    #[test]
+    fn path_denied_ops_stay_blocked_when_a_cap_is_lifted() {
+        let mut a = plan(
+            "a",
+            vec![
+                op("op-a", "crates/a/Cargo.toml", "a"),
+                op("op-vendor", "vendor/x/Cargo.toml", "a"),
+            ],
+        );
+        // The partial plan was denied under vendor/ and went over its cap.
+        let config = PlannerConfig {
+            max_ops: Some(1),
+            path_policies: vec![PathPolicy {
+                path: "vendor/**".to_string(),
+                allow: vec![],
+                deny: vec!["*".to_string()],
+                max_safety: None,
+            }],
+            ..PlannerConfig::default()
+        };
+        apply_plan_policy(&config, &mut a.ops).expect("policy");
+        let vendor = a.ops.iter().find(|o| o.id == "op-vendor").unwrap();
+        assert_eq!(
+            vendor.blocked_reason_token,
+            Some(BuildfixErrorCode::PathDenylist)
+        );
+        a.policy.max_ops = Some(5);
+
+        let merged = merge_plans(vec![a, plan("b", vec![])]).expect("merge");
+        let by_id = |id: &str| merged.ops.iter().find(|o| o.id == id).unwrap();
+        assert!(!by_id("op-a").blocked);
+        assert!(by_id("op-vendor").blocked);
+        assert_eq!(
+            by_id("op-vendor").blocked_reason_token,
+            Some(BuildfixErrorCode::PathDenylist)
+        );
+    }
+
+    #[test]
+    fn workspace_summaries_are_recounted() {
+        let mut api = op("op-api", "services/api/Cargo.toml", "a");
+        api.workspace = Some("services/api".to_string());
+        let mut a = plan("a", vec![api]);
+        a.workspaces = vec![PlanWorkspace {
+            root: "services/api".to_string(),
+            summary: Default::default(),
+        }];
+        let mut cli = op("op-cli", "tools/cli/Cargo.toml", "b");
+        cli.workspace = Some("tools/cli".to_string());
+        let mut b = plan("b", vec![cli.clone(), cli]);
+        b.workspaces = vec![PlanWorkspace {
+            root: "tools/cli".to_string(),
+            summary: Default::default(),
+        }];
+
+        let merged = merge_plans(vec![a, b]).expect("merge");
+
+        let counts: Vec<(&str, u64)> = merged
+            .workspaces
+            .iter()
+            .map(|w| (w.root.as_str(), w.summary.ops_total))
+            .collect();
+        assert_eq!(counts, [("services/api", 1), ("tools/cli", 1)]);
+        assert!(
+            merge_plans(vec![plan("c", vec![])])
+                .unwrap()
+                .workspaces
+                .is_empty()
+        );
+    }
+
+    #[test]
+    fn rejects_plans_from_different_checkouts() {
+        assert!(merge_plans(vec![]).is_err());
+
+        let a = plan("a", vec![op("op-a", "crates/a/Cargo.toml", "a")]);
+        let mut b = plan("b", vec![]);
+        b.repo.head_sha = Some("cafebabe".to_string());
+        let err = merge_plans(vec![a.clone(), b]).expect_err("different commits");
+        assert!(err.to_string().contains("different commits"));
+
+        let mut b = plan("b", vec![op("op-b", "crates/a/Cargo.toml", "b")]);
+        b.preconditions.files[0].sha256 = "def".to_string();
+        let err = merge_plans(vec![a, b]).expect_err("different contents");
+        assert!(err.to_string().contains("crates/a/Cargo.toml"));
+    }
+
+    #[test]
+    fn merged_plan_expires_with_the_earliest_input() {
+        let mut a = plan("a", vec![]);
+        let mut b = plan("b", vec![]);
+        a.expires_at = Some("2025-01-02T00:00:00+00:00".to_string());
+        b.expires_at = Some("2025-01-01T12:00:00+00:00".to_string());
+        let merged = merge_plans(vec![a, b, plan("c", vec![])]).expect("merge");
+        assert_eq!(
+            merged.expires_at.as_deref(),
+            Some("2025-01-01T12:00:00+00:00")
+        );
+    }
+}
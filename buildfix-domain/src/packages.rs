@@ -0,0 +1,127 @@
+//! `--package` / `--exclude-package`: scope ops to workspace members by name.
+
+use crate::ports::RepoView;
+use buildfix_domain_policy::glob_match;
+use buildfix_fixer_api::WorkspaceGraph;
+use camino::{Utf8Path, Utf8PathBuf};
+
+/// Name of the package that owns `path` (repo-relative): the nearest
+/// enclosing manifest that declares a package.
+///
+/// Names come from the workspace graph when one was loaded, otherwise from
+/// the manifests themselves. `None` for files only the workspace root owns.
+pub fn owning_package(
+    repo: &dyn RepoView,
+    graph: Option<&WorkspaceGraph>,
+    path: &str,
+) -> Option<String> {
+    let path = Utf8Path::new(path.strip_prefix("./").unwrap_or(path));
+    let mut dir = path.parent();
+    while let Some(current) = dir {
+        let manifest = if current.as_str().is_empty() {
+            Utf8PathBuf::from("Cargo.toml")
+        } else {
+            current.join("Cargo.toml")
+        };
+        let name = match graph {
+            Some(graph) => graph
+                .member_by_manifest(&manifest)
+                .map(|pkg| pkg.name.clone()),
+            None => manifest_package_name(repo, &manifest),
+        };
+        if name.is_some() {
+            return name;
+        }
+        dir = current.parent();
+    }
+    None
+}
+
+fn manifest_package_name(repo: &dyn RepoView, manifest: &Utf8Path) -> Option<String> {
+    if !repo.exists(manifest) {
+        return None;
+    }
+    let doc = repo.read_toml(manifest).ok()?;
+    doc.get("package")?
+        .get("name")?
+        .as_str()
+        .map(str::to_string)
+}
+
+/// Whether an op owned by `owner` passes the package filters. Ops only the
+/// workspace root owns are kept unless `include` narrows to named packages.
+pub fn package_selected(include: &[String], exclude: &[String], owner: Option<&str>) -> bool {
+    let Some(owner) = owner else {
+        return include.is_empty();
+    };
+    let matches_any = |globs: &[String]| globs.iter().any(|g| glob_match(g, owner));
+    (include.is_empty() || matches_any(include)) && !matches_any(exclude)
+}
+
+/// `include` patterns that match no workspace member, in the order given.
+pub fn unmatched_package_patterns<'a>(
+    graph: &WorkspaceGraph,
+    include: &'a [String],
+) -> Vec<&'a str> {
+    include
+        .iter()
+        .filter(|pat| {
+            !graph
+                .member_packages()
+                .any(|pkg| glob_match(pat, &pkg.name))
+        })
+        .map(String::as_str)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::FsRepoView;
+
+    fn write(root: &Utf8Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn owner_is_nearest_package_manifest() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        write(
+            root,
+            "Cargo.toml",
+            "[workspace]\nmembers = [\"crates/*\"]\n",
+        );
+        write(
+            root,
+            "crates/core-a/Cargo.toml",
+            "[package]\nname = \"core-a\"\nversion = \"0.1.0\"\n",
+        );
+        let repo = FsRepoView::new(root.to_path_buf());
+
+        assert_eq!(
+            owning_package(&repo, None, "crates/core-a/Cargo.toml").as_deref(),
+            Some("core-a")
+        );
+        assert_eq!(
+            owning_package(&repo, None, "./crates/core-a/src/lib.rs").as_deref(),
+            Some("core-a")
+        );
+        assert_eq!(owning_package(&repo, None, "Cargo.toml"), None);
+    }
+
+    #[test]
+    fn include_and_exclude_globs() {
+        let include = vec!["core-*".to_string()];
+        let exclude = vec!["core-legacy".to_string()];
+
+        assert!(package_selected(&include, &exclude, Some("core-a")));
+        assert!(!package_selected(&include, &exclude, Some("core-legacy")));
+        assert!(!package_selected(&include, &exclude, Some("cli")));
+        assert!(!package_selected(&include, &exclude, None));
+        assert!(package_selected(&[], &exclude, None));
+        assert!(!package_selected(&[], &exclude, Some("core-legacy")));
+    }
+}
@@ -1,25 +1,43 @@
 use crate::fixers;
+use crate::impact::annotate_impact;
+use crate::packages::{owning_package, package_selected};
 use crate::ports::RepoView;
+use crate::workspaces::{
+    ScopedRepoView, discover_workspace_roots, owning_workspace, summarize_workspaces,
+    workspace_label,
+};
 use anyhow::Context;
-use buildfix_domain_policy::apply_plan_policy;
 #[cfg(test)]
 use buildfix_domain_policy::{
     apply_allow_deny, apply_params, args_fingerprint, deterministic_op_id, enforce_caps, glob_match,
 };
+use buildfix_domain_policy::{apply_fixer_config, apply_plan_policy};
 #[cfg(test)]
 use buildfix_fixer_api::PlannerConfig;
-use buildfix_fixer_api::{PlanContext, ReceiptSet};
+use buildfix_fixer_api::{PlanContext, ReceiptSet, skipped_by_scan};
 use buildfix_receipts::LoadedReceipt;
+use buildfix_types::cancel::CancellationToken;
 use buildfix_types::plan::{
-    BuildfixPlan, PlanInput, PlanOp, PlanPolicy, PlanSummary, RepoInfo, SafetyCounts,
+    BuildfixPlan, PlanCapabilities, PlanInput, PlanOp, PlanPolicy, PlanSummary, RepoInfo,
+    SafetyCounts,
 };
+use buildfix_types::progress::Progress;
 use buildfix_types::receipt::ToolInfo;
-use std::collections::BTreeSet;
+use buildfix_types::telemetry;
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::{BTreeMap, BTreeSet};
+use std::sync::atomic::{AtomicBool, AtomicUsize, Ordering};
+use std::sync::mpsc;
+use tracing::debug;
 
 pub struct Planner {
     fixers: Vec<Box<dyn buildfix_fixer_api::Fixer>>,
+    jobs: usize,
 }
 
+/// One fixer's ops, or `None` when config disabled it.
+type FixerResult = anyhow::Result<Option<Vec<PlanOp>>>;
+
 impl Default for Planner {
     fn default() -> Self {
         Self::new()
@@ -28,13 +46,27 @@ impl Default for Planner {
 
 impl Planner {
     pub fn new() -> Self {
-        Self {
-            fixers: fixers::builtin_fixers(),
-        }
+        Self::with_fixers(fixers::builtin_fixers())
     }
 
     pub fn with_fixers(fixers: Vec<Box<dyn buildfix_fixer_api::Fixer>>) -> Self {
-        Self { fixers }
+        Self { fixers, jobs: 0 }
+    }
+
+    /// Run up to `jobs` fixers at once; 0 (the default) uses one per
+    /// available core and 1 runs them in turn on the calling thread. The plan
+    /// does not depend on it: ops are merged in fixer order either way.
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    fn worker_count(&self) -> usize {
+        let jobs = match self.jobs {
+            0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+            jobs => jobs,
+        };
+        jobs.min(self.fixers.len()).max(1)
     }
 
     pub fn plan(
@@ -43,6 +75,28 @@ impl Planner {
         repo: &dyn RepoView,
         receipts: &[LoadedReceipt],
         tool: ToolInfo,
+    ) -> anyhow::Result<BuildfixPlan> {
+        self.plan_with_progress(
+            ctx,
+            repo,
+            receipts,
+            tool,
+            &|_| {},
+            &CancellationToken::new(),
+        )
+    }
+
+    /// [`plan`](Self::plan), reporting [`Progress::Fixer`] after each fixer,
+    /// in fixer order. Once `cancel` is cancelled the remaining fixers are
+    /// skipped and the plan holds only the ops reported so far.
+    pub fn plan_with_progress(
+        &self,
+        ctx: &PlanContext,
+        repo: &dyn RepoView,
+        receipts: &[LoadedReceipt],
+        tool: ToolInfo,
+        progress: &dyn Fn(&Progress<'_>),
+        cancel: &CancellationToken,
     ) -> anyhow::Result<BuildfixPlan> {
         let policy = PlanPolicy {
             allow: ctx.config.allow.clone(),
@@ -59,45 +113,329 @@ impl Planner {
             root: ctx.repo_root.to_string(),
             head_sha: None,
             dirty: None,
+            remote_url: None,
+            ci: None,
         };
 
         let mut plan = BuildfixPlan::new(tool, repo_info, policy);
         plan.inputs = receipts.iter().map(to_plan_input).collect();
 
-        let receipt_set = ReceiptSet::from_loaded(receipts);
-
-        let mut ops: Vec<PlanOp> = Vec::new();
-        for fixer in &self.fixers {
-            let mut f = fixer
-                .plan(ctx, repo, &receipt_set)
-                .with_context(|| "fixer.plan")?;
-            ops.append(&mut f);
+        let mut receipt_set = ReceiptSet::from_loaded(receipts);
+        let filtered = receipt_set.retain_findings(&ctx.config);
+        if filtered > 0 {
+            debug!(
+                event = telemetry::PLAN_FINDINGS_FILTERED,
+                filtered, "findings dropped by planner filters"
+            );
         }
 
+        let roots = discover_workspace_roots(repo, &ctx.config.scan);
+        let several = roots.iter().any(|root| !root.as_str().is_empty());
+        let mut ops = if several {
+            debug!(
+                event = telemetry::PLAN_WORKSPACES,
+                count = roots.len(),
+                roots = ?roots.iter().map(|r| workspace_label(r)).collect::<Vec<_>>(),
+                "planning each workspace"
+            );
+            self.plan_workspaces(&roots, ctx, repo, &receipt_set, progress, cancel)?
+        } else {
+            self.plan_ops(ctx, repo, &receipt_set, progress, cancel)?
+        };
+
+        retain_scanned_paths(ctx, repo, &mut ops);
+        retain_selected_packages(ctx, repo, &mut ops);
+        annotate_impact(ctx, repo, &mut ops);
         apply_plan_policy(&ctx.config, &mut ops)?;
 
         plan.summary = summarize(&ops);
+        if several {
+            plan.workspaces =
+                summarize_workspaces(roots.iter().map(|root| workspace_label(root)), &ops);
+        }
+        plan.capabilities = Some(PlanCapabilities::new(
+            self.fixers.iter().map(|f| f.meta().fix_key.to_string()),
+            &ops,
+        ));
         plan.ops = ops;
         Ok(plan)
     }
 }
 
+impl Planner {
+    /// Run every fixer over `repo`, one at a time or on worker threads.
+    fn plan_ops(
+        &self,
+        ctx: &PlanContext,
+        repo: &dyn RepoView,
+        receipts: &ReceiptSet,
+        progress: &dyn Fn(&Progress<'_>),
+        cancel: &CancellationToken,
+    ) -> anyhow::Result<Vec<PlanOp>> {
+        if self.worker_count() == 1 {
+            let mut next = 0;
+            self.collect(progress, cancel, || {
+                let i = next;
+                next += 1;
+                (i < self.fixers.len()).then(|| (i, self.plan_fixer(i, ctx, repo, receipts)))
+            })
+        } else {
+            self.plan_concurrently(ctx, repo, receipts, progress, cancel)
+        }
+    }
+
+    /// Run the fixers once per workspace in `roots`, each seeing the
+    /// workspace directory as the repo root and only the findings located in
+    /// it (or nowhere). Target and finding paths are made repo-relative again
+    /// and ops are tagged with their workspace; ops a workspace's fixers aim
+    /// at another workspace are dropped. Progress restarts for each one.
+    ///
+    /// The workspace graph, taken at the repo root, is only passed to the
+    /// repo root's workspace.
+    fn plan_workspaces(
+        &self,
+        roots: &[Utf8PathBuf],
+        ctx: &PlanContext,
+        repo: &dyn RepoView,
+        receipts: &ReceiptSet,
+        progress: &dyn Fn(&Progress<'_>),
+        cancel: &CancellationToken,
+    ) -> anyhow::Result<Vec<PlanOp>> {
+        let mut ops = Vec::new();
+        for root in roots {
+            if cancel.is_cancelled() {
+                break;
+            }
+            let owned_by_root =
+                |path: &Utf8Path| owning_workspace(roots, path) == Some(root.as_path());
+            let scoped_receipts = receipts.rebased(|path| {
+                owned_by_root(path)
+                    .then(|| path.strip_prefix(root).ok())
+                    .flatten()
+                    .map(Utf8Path::to_path_buf)
+            });
+            let scoped_ctx = PlanContext {
+                repo_root: ctx.repo_root.join(root),
+                artifacts_dir: ctx.artifacts_dir.clone(),
+                config: ctx.config.clone(),
+                workspace_graph: ctx
+                    .workspace_graph
+                    .clone()
+                    .filter(|_| root.as_str().is_empty()),
+            };
+            let scoped_repo = ScopedRepoView::new(repo, root);
+            let label = workspace_label(root);
+            let planned = self.plan_ops(
+                &scoped_ctx,
+                &scoped_repo,
+                &scoped_receipts,
+                progress,
+                cancel,
+            )?;
+            for mut op in planned {
+                let target = root.join(op.target.path.trim_start_matches("./"));
+                if !owned_by_root(&target) {
+                    continue;
+                }
+                op.target.path = target.into_string();
+                for finding in &mut op.rationale.findings {
+                    if let Some(path) = &mut finding.path {
+                        *path = root.join(path.as_str()).into_string();
+                    }
+                }
+                op.workspace = Some(label.clone());
+                ops.push(op);
+            }
+        }
+        Ok(ops)
+    }
+
+    /// Plan each fixer on a pool of scoped worker threads sharing `repo` and
+    /// `receipts`. Workers take fixers in order and stop taking new ones once
+    /// `cancel` fires or the merge gives up.
+    fn plan_concurrently(
+        &self,
+        ctx: &PlanContext,
+        repo: &dyn RepoView,
+        receipts: &ReceiptSet,
+        progress: &dyn Fn(&Progress<'_>),
+        cancel: &CancellationToken,
+    ) -> anyhow::Result<Vec<PlanOp>> {
+        let next = AtomicUsize::new(0);
+        let stop = AtomicBool::new(false);
+        std::thread::scope(|scope| {
+            let (tx, rx) = mpsc::channel();
+            for _ in 0..self.worker_count() {
+                let tx = tx.clone();
+                let (next, stop) = (&next, &stop);
+                scope.spawn(move || {
+                    while !stop.load(Ordering::Relaxed) && !cancel.is_cancelled() {
+                        let i = next.fetch_add(1, Ordering::Relaxed);
+                        if i >= self.fixers.len() {
+                            break;
+                        }
+                        let result = self.plan_fixer(i, ctx, repo, receipts);
+                        if tx.send((i, result)).is_err() {
+                            break;
+                        }
+                    }
+                });
+            }
+            drop(tx);
+            let merged = self.collect(progress, cancel, || rx.recv().ok());
+            stop.store(true, Ordering::Relaxed);
+            merged
+        })
+    }
+
+    /// Merge fixer results from `next` in fixer order, whatever order they
+    /// arrive in, reporting progress as each one is merged. Stops at the first
+    /// error, when `cancel` fires, or when `next` runs dry.
+    fn collect(
+        &self,
+        progress: &dyn Fn(&Progress<'_>),
+        cancel: &CancellationToken,
+        mut next: impl FnMut() -> Option<(usize, FixerResult)>,
+    ) -> anyhow::Result<Vec<PlanOp>> {
+        let total = self.fixers.len();
+        let mut pending: BTreeMap<usize, FixerResult> = BTreeMap::new();
+        let mut ops: Vec<PlanOp> = Vec::new();
+        let mut done = 0;
+        while done < total {
+            if cancel.is_cancelled() {
+                debug!(
+                    event = telemetry::PLAN_CANCELLED,
+                    done, total, "planning cancelled"
+                );
+                break;
+            }
+            let Some(result) = pending.remove(&done) else {
+                match next() {
+                    Some((i, result)) => {
+                        pending.insert(i, result);
+                        continue;
+                    }
+                    None => break,
+                }
+            };
+            let mut planned = result?.unwrap_or_default();
+            done += 1;
+            progress(&Progress::Fixer {
+                fix_key: self.fixers[done - 1].meta().fix_key,
+                ops: planned.len(),
+                done,
+                total,
+            });
+            ops.append(&mut planned);
+        }
+        Ok(ops)
+    }
+
+    /// Run fixer `i` with its `[fixers.<fix_key>]` overrides applied.
+    fn plan_fixer(
+        &self,
+        i: usize,
+        ctx: &PlanContext,
+        repo: &dyn RepoView,
+        receipts: &ReceiptSet,
+    ) -> FixerResult {
+        let meta = self.fixers[i].meta();
+        let overrides = ctx.config.fixers.get(meta.fix_key);
+        if overrides.is_some_and(|o| !o.enabled) {
+            debug!(
+                event = telemetry::PLAN_FIXER,
+                fix_key = meta.fix_key,
+                ops = 0,
+                outcome = "disabled",
+                "fixer disabled by config"
+            );
+            return Ok(None);
+        }
+        let mut ops = self.fixers[i]
+            .plan(ctx, repo, receipts)
+            .with_context(|| "fixer.plan")?;
+        if let Some(overrides) = overrides {
+            apply_fixer_config(
+                meta.fix_key,
+                meta.safety,
+                overrides,
+                &ctx.config.params,
+                &mut ops,
+            )?;
+        }
+        debug!(
+            event = telemetry::PLAN_FIXER,
+            fix_key = meta.fix_key,
+            ops = ops.len(),
+            outcome = "planned",
+            "fixer planned"
+        );
+        Ok(Some(ops))
+    }
+}
+
+/// Drop ops targeting build output, vendored crates or gitignored files.
+fn retain_scanned_paths(ctx: &PlanContext, repo: &dyn RepoView, ops: &mut Vec<PlanOp>) {
+    let before = ops.len();
+    ops.retain(|op| !skipped_by_scan(&ctx.config.scan, repo, op.target.path.as_ref()));
+    if ops.len() < before {
+        debug!(
+            event = telemetry::PLAN_OPS_FILTERED,
+            dropped = before - ops.len(),
+            "ops dropped by scan filters"
+        );
+    }
+}
+
+/// Drop ops outside `--package` / `--exclude-package` before caps count them.
+fn retain_selected_packages(ctx: &PlanContext, repo: &dyn RepoView, ops: &mut Vec<PlanOp>) {
+    let (include, exclude) = (&ctx.config.packages, &ctx.config.exclude_packages);
+    if include.is_empty() && exclude.is_empty() {
+        return;
+    }
+    let mut owners: BTreeMap<String, Option<String>> = BTreeMap::new();
+    let before = ops.len();
+    ops.retain(|op| {
+        let owner = owners
+            .entry(op.target.path.clone())
+            .or_insert_with(|| owning_package(repo, ctx.workspace_graph.as_ref(), &op.target.path));
+        package_selected(include, exclude, owner.as_deref())
+    });
+    if ops.len() < before {
+        debug!(
+            event = telemetry::PLAN_OPS_FILTERED,
+            dropped = before - ops.len(),
+            "ops dropped by package filters"
+        );
+    }
+}
+
 fn to_plan_input(r: &LoadedReceipt) -> PlanInput {
     match &r.receipt {
         Ok(env) => PlanInput {
             path: r.path.to_string(),
             schema: Some(env.schema.clone()),
             tool: Some(env.tool.name.clone()),
+            git_head_sha: env.run.git_head_sha.clone(),
+            generated_at: env
+                .run
+                .ended_at
+                .or(env.run.started_at)
+                .map(|t| t.to_rfc3339()),
+            sha256: None,
         },
         Err(_) => PlanInput {
             path: r.path.to_string(),
             schema: None,
             tool: None,
+            git_head_sha: None,
+            generated_at: None,
+            sha256: None,
         },
     }
 }
 
-fn summarize(ops: &[PlanOp]) -> PlanSummary {
+pub(crate) fn summarize(ops: &[PlanOp]) -> PlanSummary {
     let ops_total = ops.len() as u64;
     let ops_blocked = ops.iter().filter(|o| o.blocked).count() as u64;
     let files_touched = ops
@@ -134,8 +472,9 @@ fn summarize(ops: &[PlanOp]) -> PlanSummary {
 mod tests {
     use super::*;
     use buildfix_receipts::LoadedReceipt;
+    use buildfix_types::error_code::BuildfixErrorCode;
     use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
-    use buildfix_types::plan::{PlanOp, Rationale, blocked_tokens};
+    use buildfix_types::plan::{PlanOp, Rationale};
     use buildfix_types::receipt::{Finding, Location, ReceiptEnvelope, RunInfo, ToolInfo, Verdict};
     use camino::Utf8PathBuf;
     use std::collections::HashMap;
@@ -149,6 +488,7 @@ mod tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: target.to_string(),
+                sha256: None,
             },
             kind,
             rationale: Rationale {
@@ -158,6 +498,8 @@ mod tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }
     }
 
@@ -270,8 +612,8 @@ mod tests {
 
         assert!(ops[0].blocked);
         assert_eq!(
-            ops[0].blocked_reason_token.as_deref(),
-            Some(blocked_tokens::MISSING_PARAMS)
+            ops[0].blocked_reason_token,
+            Some(BuildfixErrorCode::MissingParams)
         );
     }
 
@@ -284,11 +626,11 @@ mod tests {
                 toml_path: vec!["workspace".to_string()],
             },
         )];
-        apply_allow_deny(&[], &["cargo.*".to_string()], &mut ops);
+        apply_allow_deny(&[], &["cargo.*".to_string()], &[], &mut ops);
         assert!(ops[0].blocked);
         assert_eq!(
-            ops[0].blocked_reason_token.as_deref(),
-            Some(blocked_tokens::DENYLIST)
+            ops[0].blocked_reason_token,
+            Some(BuildfixErrorCode::Denylist)
         );
 
         let mut ops = vec![make_op(
@@ -298,11 +640,11 @@ mod tests {
                 toml_path: vec!["workspace".to_string()],
             },
         )];
-        apply_allow_deny(&["depguard.*".to_string()], &[], &mut ops);
+        apply_allow_deny(&["depguard.*".to_string()], &[], &[], &mut ops);
         assert!(ops[0].blocked);
         assert_eq!(
-            ops[0].blocked_reason_token.as_deref(),
-            Some(blocked_tokens::ALLOWLIST_MISSING)
+            ops[0].blocked_reason_token,
+            Some(BuildfixErrorCode::AllowlistMissing)
         );
     }
 
@@ -316,7 +658,7 @@ mod tests {
             },
         )];
 
-        apply_allow_deny(&["cargo.*".to_string()], &[], &mut ops);
+        apply_allow_deny(&["cargo.*".to_string()], &[], &[], &mut ops);
         assert!(!ops[0].blocked);
         assert!(ops[0].blocked_reason.is_none());
         assert!(ops[0].blocked_reason_token.is_none());
@@ -333,13 +675,21 @@ mod tests {
         )];
         ops[0].blocked = true;
         ops[0].blocked_reason = Some("preblocked".to_string());
-        ops[0].blocked_reason_token = Some("custom_token".to_string());
+        ops[0].blocked_reason_token = Some(BuildfixErrorCode::StaleReceipt);
 
-        apply_allow_deny(&["cargo.*".to_string()], &["cargo.*".to_string()], &mut ops);
+        apply_allow_deny(
+            &["cargo.*".to_string()],
+            &["cargo.*".to_string()],
+            &[],
+            &mut ops,
+        );
 
         assert!(ops[0].blocked);
         assert_eq!(ops[0].blocked_reason.as_deref(), Some("preblocked"));
-        assert_eq!(ops[0].blocked_reason_token.as_deref(), Some("custom_token"));
+        assert_eq!(
+            ops[0].blocked_reason_token,
+            Some(BuildfixErrorCode::StaleReceipt)
+        );
     }
 
     #[test]
@@ -367,10 +717,7 @@ mod tests {
         };
         enforce_caps(&cfg, &mut ops).expect("enforce caps");
         assert!(ops.iter().all(|op| op.blocked));
-        assert_eq!(
-            ops[0].blocked_reason_token.as_deref(),
-            Some(blocked_tokens::MAX_OPS)
-        );
+        assert_eq!(ops[0].blocked_reason_token, Some(BuildfixErrorCode::MaxOps));
 
         let mut ops = vec![
             make_op(
@@ -392,11 +739,18 @@ mod tests {
             max_files: Some(1),
             ..Default::default()
         };
+        ops[1].blocked = true;
+        ops[1].blocked_reason_token = Some(BuildfixErrorCode::Denylist);
         enforce_caps(&cfg, &mut ops).expect("enforce caps");
         assert!(ops.iter().all(|op| op.blocked));
         assert_eq!(
-            ops[0].blocked_reason_token.as_deref(),
-            Some(blocked_tokens::MAX_FILES)
+            ops[0].blocked_reason_token,
+            Some(BuildfixErrorCode::MaxFiles)
+        );
+        // An op already blocked keeps its own reason.
+        assert_eq!(
+            ops[1].blocked_reason_token,
+            Some(BuildfixErrorCode::Denylist)
         );
     }
 
@@ -463,11 +817,13 @@ mod tests {
                 path: Utf8PathBuf::from("artifacts/builddiag/report-b.json"),
                 sensor_id: "builddiag".to_string(),
                 receipt: Ok(receipt_a),
+                signature: None,
             },
             LoadedReceipt {
                 path: Utf8PathBuf::from("artifacts/builddiag/report-a.json"),
                 sensor_id: "builddiag".to_string(),
                 receipt: Ok(receipt_b),
+                signature: None,
             },
         ];
 
@@ -512,6 +868,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
 
         let set = ReceiptSet::from_loaded(&loaded);
@@ -528,4 +885,275 @@ mod tests {
         let mismatch = set.matching_findings(&["builddiag"], &[], &["other"]);
         assert!(mismatch.is_empty());
     }
+
+    struct SlowFixer {
+        fix_key: &'static str,
+        delay_ms: u64,
+    }
+
+    impl buildfix_fixer_api::Fixer for SlowFixer {
+        fn meta(&self) -> buildfix_fixer_api::FixerMeta {
+            buildfix_fixer_api::FixerMeta {
+                fix_key: self.fix_key,
+                key: "",
+                title: "",
+                description: "",
+                details: "",
+                safety: SafetyClass::Safe,
+                safety_rationale: "",
+                remediation: "",
+                consumes_sensors: &[],
+                consumes_check_ids: &[],
+                triggers: &[],
+            }
+        }
+
+        fn plan(
+            &self,
+            _ctx: &PlanContext,
+            _repo: &dyn RepoView,
+            _receipts: &ReceiptSet,
+        ) -> anyhow::Result<Vec<PlanOp>> {
+            std::thread::sleep(std::time::Duration::from_millis(self.delay_ms));
+            anyhow::ensure!(self.fix_key != "test.fails", "fixer broke");
+            Ok(vec![make_op(
+                self.fix_key,
+                &format!("crates/{}/Cargo.toml", self.fix_key),
+                OpKind::TomlTransform {
+                    rule_id: "ensure_workspace_resolver_v2".to_string(),
+                    args: None,
+                },
+            )])
+        }
+    }
+
+    struct NoRepo;
+
+    impl RepoView for NoRepo {
+        fn root(&self) -> &camino::Utf8Path {
+            camino::Utf8Path::new(".")
+        }
+
+        fn read_to_string(&self, rel: &camino::Utf8Path) -> anyhow::Result<String> {
+            anyhow::bail!("no file {}", rel)
+        }
+
+        fn exists(&self, _rel: &camino::Utf8Path) -> bool {
+            false
+        }
+    }
+
+    /// Later fixers finish first, so a concurrent run merges out of order.
+    fn slow_fixers(keys: &[&'static str]) -> Vec<Box<dyn buildfix_fixer_api::Fixer>> {
+        keys.iter()
+            .enumerate()
+            .map(|(i, fix_key)| {
+                Box::new(SlowFixer {
+                    fix_key,
+                    delay_ms: 10 * (keys.len() - i) as u64,
+                }) as Box<dyn buildfix_fixer_api::Fixer>
+            })
+            .collect()
+    }
+
+    fn plan_with_jobs(
+        keys: &[&'static str],
+        jobs: usize,
+    ) -> (anyhow::Result<BuildfixPlan>, Vec<(String, usize)>) {
+        let ctx = PlanContext {
+            repo_root: Utf8PathBuf::from("."),
+            artifacts_dir: Utf8PathBuf::from("artifacts"),
+            config: PlannerConfig::default(),
+            workspace_graph: None,
+        };
+        let reported = std::cell::RefCell::new(Vec::new());
+        let plan = Planner::with_fixers(slow_fixers(keys))
+            .with_jobs(jobs)
+            .plan_with_progress(
+                &ctx,
+                &NoRepo,
+                &[],
+                ToolInfo {
+                    name: "buildfix".to_string(),
+                    version: None,
+                    repo: None,
+                    commit: None,
+                },
+                &|p| {
+                    if let Progress::Fixer { fix_key, done, .. } = p {
+                        reported.borrow_mut().push((fix_key.to_string(), *done));
+                    }
+                },
+                &CancellationToken::new(),
+            );
+        (plan, reported.into_inner())
+    }
+
+    #[test]
+    fn ops_on_skipped_paths_are_dropped() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::from_path_buf(td.path().to_path_buf()).unwrap();
+        std::fs::write(root.join(".gitignore"), "/crates/test.ignored/\n").unwrap();
+        std::fs::create_dir_all(root.join("crates/test.vendored")).unwrap();
+        std::fs::write(root.join("crates/test.vendored/.cargo-checksum.json"), "{}").unwrap();
+
+        let mut ctx = PlanContext {
+            repo_root: root.clone(),
+            artifacts_dir: root.join("artifacts"),
+            config: PlannerConfig::default(),
+            workspace_graph: None,
+        };
+        let keys = ["test.kept", "target", "test.vendored", "test.ignored"];
+        let plan_keys = |ctx: &PlanContext| {
+            Planner::with_fixers(slow_fixers(&keys))
+                .with_jobs(1)
+                .plan(
+                    ctx,
+                    &crate::ports::FsRepoView::new(root.clone()),
+                    &[],
+                    ToolInfo {
+                        name: "buildfix".to_string(),
+                        version: None,
+                        repo: None,
+                        commit: None,
+                    },
+                )
+                .unwrap()
+                .ops
+                .into_iter()
+                .map(|op| op.rationale.fix_key)
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(plan_keys(&ctx), vec!["test.kept"]);
+
+        ctx.config.scan = buildfix_types::plan::ScanPolicy {
+            skip_dirs: vec![],
+            gitignore: false,
+            vendored: false,
+        };
+        assert_eq!(plan_keys(&ctx).len(), keys.len());
+    }
+
+    #[test]
+    fn each_workspace_is_planned_on_its_own() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8PathBuf::from_path_buf(td.path().to_path_buf()).unwrap();
+        for (dir, manifest) in [
+            ("services/api", "[workspace]\nmembers = []\n"),
+            ("tools/cli", "[workspace]\nresolver = \"2\"\n"),
+        ] {
+            std::fs::create_dir_all(root.join(dir)).unwrap();
+            std::fs::write(root.join(dir).join("Cargo.toml"), manifest).unwrap();
+        }
+        let finding = |path: &str| Finding {
+            check_id: Some("workspace.resolver_v2".to_string()),
+            location: Some(Location {
+                path: Utf8PathBuf::from(path),
+                line: None,
+                column: None,
+            }),
+            ..Default::default()
+        };
+        let loaded = vec![LoadedReceipt {
+            path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
+            sensor_id: "builddiag".to_string(),
+            receipt: Ok(ReceiptEnvelope {
+                schema: "sensor.report.v1".to_string(),
+                tool: ToolInfo {
+                    name: "builddiag".to_string(),
+                    version: None,
+                    repo: None,
+                    commit: None,
+                },
+                run: RunInfo::default(),
+                verdict: Verdict::default(),
+                findings: vec![
+                    finding("services/api/Cargo.toml"),
+                    finding("tools/cli/Cargo.toml"),
+                ],
+                capabilities: None,
+                data: None,
+            }),
+            signature: None,
+        }];
+        let ctx = PlanContext {
+            repo_root: root.clone(),
+            artifacts_dir: root.join("artifacts"),
+            config: PlannerConfig::default(),
+            workspace_graph: None,
+        };
+
+        let plan = Planner::new()
+            .plan(
+                &ctx,
+                &crate::ports::FsRepoView::new(root.clone()),
+                &loaded,
+                ToolInfo {
+                    name: "buildfix".to_string(),
+                    version: None,
+                    repo: None,
+                    commit: None,
+                },
+            )
+            .unwrap();
+
+        assert_eq!(plan.ops.len(), 1);
+        let op = &plan.ops[0];
+        assert_eq!(op.target.path, "services/api/Cargo.toml");
+        assert_eq!(op.workspace.as_deref(), Some("services/api"));
+        assert_eq!(
+            op.rationale.findings[0].path.as_deref(),
+            Some("services/api/Cargo.toml")
+        );
+        let counts: Vec<(&str, u64)> = plan
+            .workspaces
+            .iter()
+            .map(|w| (w.root.as_str(), w.summary.ops_total))
+            .collect();
+        assert_eq!(counts, [("services/api", 1), ("tools/cli", 0)]);
+    }
+
+    #[test]
+    fn single_workspace_plans_are_untagged() {
+        let (plan, _) = plan_with_jobs(&["test.a"], 1);
+        let plan = plan.unwrap();
+        assert!(plan.workspaces.is_empty());
+        assert!(plan.ops.iter().all(|op| op.workspace.is_none()));
+    }
+
+    #[test]
+    fn concurrent_planning_matches_sequential() {
+        let keys = ["test.a", "test.b", "test.c", "test.d", "test.e"];
+        let (sequential, seq_progress) = plan_with_jobs(&keys, 1);
+        let (concurrent, progress) = plan_with_jobs(&keys, 4);
+        let ids = |plan: BuildfixPlan| {
+            plan.ops
+                .into_iter()
+                .map(|op| (op.id, op.rationale.fix_key))
+                .collect::<Vec<_>>()
+        };
+        let sequential = ids(sequential.unwrap());
+        assert_eq!(sequential.len(), keys.len());
+        assert_eq!(ids(concurrent.unwrap()), sequential);
+
+        let expected: Vec<(String, usize)> = keys
+            .iter()
+            .enumerate()
+            .map(|(i, key)| (key.to_string(), i + 1))
+            .collect();
+        assert_eq!(seq_progress, expected);
+        assert_eq!(progress, expected);
+    }
+
+    #[test]
+    fn concurrent_planning_reports_fixer_errors() {
+        let keys = ["test.a", "test.fails", "test.c"];
+        for jobs in [1, 3] {
+            let (plan, progress) = plan_with_jobs(&keys, jobs);
+            let err = plan.unwrap_err();
+            assert!(format!("{:#}", err).contains("fixer broke"), "{err:#}");
+            assert_eq!(progress, vec![("test.a".to_string(), 1)]);
+        }
+    }
 }
@@ -1,19 +1,37 @@
+use crate::gitignore::GitignoreMatcher;
 use anyhow::Context;
 use camino::{Utf8Path, Utf8PathBuf};
 use fs_err as fs;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use toml_edit::DocumentMut;
 
 // Re-exported for downstream reuse and compatibility with microcrate adapters.
 pub use buildfix_fixer_api::RepoView;
 
+/// One parsed manifest; locked while it is parsed so concurrent readers of
+/// the same file wait for that parse instead of repeating it.
+type TomlSlot = Arc<Mutex<Option<Arc<DocumentMut>>>>;
+
 /// File-system backed `RepoView`.
+///
+/// [`read_toml`](RepoView::read_toml) caches each parsed document for the
+/// life of the view (clones share the cache), so create a view per run:
+/// files changed after their first read are not seen again.
 #[derive(Debug, Clone)]
 pub struct FsRepoView {
     root: Utf8PathBuf,
+    toml: Arc<Mutex<HashMap<Utf8PathBuf, TomlSlot>>>,
+    gitignore: GitignoreMatcher,
 }
 
 impl FsRepoView {
     pub fn new(root: Utf8PathBuf) -> Self {
-        Self { root }
+        Self {
+            gitignore: GitignoreMatcher::new(root.clone()),
+            root,
+            toml: Arc::default(),
+        }
     }
 
     fn abs(&self, rel: &Utf8Path) -> Utf8PathBuf {
@@ -38,6 +56,33 @@ impl RepoView for FsRepoView {
     fn exists(&self, rel: &Utf8Path) -> bool {
         self.abs(rel).exists()
     }
+
+    fn read_toml(&self, rel: &Utf8Path) -> anyhow::Result<Arc<DocumentMut>> {
+        let abs = self.abs(rel);
+        let slot = Arc::clone(
+            self.toml
+                .lock()
+                .unwrap_or_else(|e| e.into_inner())
+                .entry(abs.clone())
+                .or_default(),
+        );
+        let mut slot = slot.lock().unwrap_or_else(|e| e.into_inner());
+        if let Some(doc) = slot.as_ref() {
+            return Ok(Arc::clone(doc));
+        }
+        let contents = fs::read_to_string(&abs).with_context(|| format!("read {}", abs))?;
+        let doc = Arc::new(
+            contents
+                .parse::<DocumentMut>()
+                .with_context(|| format!("parse {}", abs))?,
+        );
+        *slot = Some(Arc::clone(&doc));
+        Ok(doc)
+    }
+
+    fn is_gitignored(&self, rel: &Utf8Path) -> bool {
+        self.gitignore.is_ignored(rel)
+    }
 }
 
 #[cfg(test)]
@@ -77,6 +122,25 @@ mod tests {
         assert!(!repo.exists(Utf8Path::new("missing.toml")));
     }
 
+    #[test]
+    fn fs_repo_view_parses_each_manifest_once() {
+        let temp = TempDir::new().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        let file_path = root.join("Cargo.toml");
+        fs::write(&file_path, "[package]\nname = \"demo\"\n").expect("write");
+        fs::write(root.join("bad.toml"), "[package").expect("write");
+
+        let repo = FsRepoView::new(root);
+        let first = repo.read_toml(Utf8Path::new("Cargo.toml")).expect("parse");
+        assert_eq!(first["package"]["name"].as_str(), Some("demo"));
+        let again = repo.clone().read_toml(&file_path).expect("cached");
+        assert!(Arc::ptr_eq(&first, &again));
+
+        let err = repo.read_toml(Utf8Path::new("bad.toml")).unwrap_err();
+        assert!(err.downcast_ref::<toml_edit::TomlError>().is_some());
+        assert!(repo.read_toml(Utf8Path::new("missing.toml")).is_err());
+    }
+
     #[test]
     fn fs_repo_view_root_is_stable() {
         let temp = TempDir::new().expect("temp dir");
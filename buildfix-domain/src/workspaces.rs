@@ -0,0 +1,225 @@
+//! Repos holding several independent Cargo workspaces.
+//!
+//! Every `Cargo.toml` with a `[workspace]` table roots a workspace, as does a
+//! root manifest without one; a path belongs to the innermost root above it.
+//! When any root lies below the repo root the planner runs the fixers once
+//! per workspace, through a [`ScopedRepoView`] that makes the workspace
+//! directory look like the repo root, and tags each op with its workspace.
+
+use crate::planner::summarize;
+use crate::ports::RepoView;
+use buildfix_fixer_api::skipped_by_scan;
+use buildfix_types::plan::{PlanOp, PlanWorkspace, ScanPolicy};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use std::collections::BTreeMap;
+use std::sync::Arc;
+use toml_edit::DocumentMut;
+
+/// Repo-relative roots of the Cargo workspaces under `repo`, sorted; the
+/// repo root is the empty path.
+///
+/// Directories are listed from the file system below [`RepoView::root`];
+/// ones `scan` skips are not searched, and symlinks are not followed.
+pub fn discover_workspace_roots(repo: &dyn RepoView, scan: &ScanPolicy) -> Vec<Utf8PathBuf> {
+    let mut roots = Vec::new();
+    if repo.exists(Utf8Path::new("Cargo.toml")) {
+        roots.push(Utf8PathBuf::new());
+    }
+    let mut dirs = vec![Utf8PathBuf::new()];
+    while let Some(dir) = dirs.pop() {
+        let Ok(entries) = fs::read_dir(repo.root().join(&dir)) else {
+            continue;
+        };
+        for entry in entries.flatten() {
+            if !entry.file_type().is_ok_and(|t| t.is_dir()) {
+                continue;
+            }
+            let Ok(name) = entry.file_name().into_string() else {
+                continue;
+            };
+            let child = dir.join(name);
+            let manifest = child.join("Cargo.toml");
+            if skipped_by_scan(scan, repo, &manifest) {
+                continue;
+            }
+            if repo.exists(&manifest)
+                && repo
+                    .read_toml(&manifest)
+                    .is_ok_and(|doc| declares_workspace(&doc))
+            {
+                roots.push(child.clone());
+            }
+            dirs.push(child);
+        }
+    }
+    roots.sort();
+    roots
+}
+
+fn declares_workspace(doc: &DocumentMut) -> bool {
+    doc.get("workspace").is_some_and(|w| w.is_table_like())
+}
+
+/// The innermost of `roots` containing `path` (repo-relative).
+pub fn owning_workspace<'a>(roots: &'a [Utf8PathBuf], path: &Utf8Path) -> Option<&'a Utf8Path> {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    roots
+        .iter()
+        .filter(|root| path.starts_with(root))
+        .max_by_key(|root| root.components().count())
+        .map(Utf8PathBuf::as_path)
+}
+
+/// How a workspace root is written in plans: `.` for the repo root.
+pub fn workspace_label(root: &Utf8Path) -> String {
+    if root.as_str().is_empty() {
+        ".".to_string()
+    } else {
+        root.to_string()
+    }
+}
+
+/// One [`PlanWorkspace`] per root in `roots` and per workspace an op is
+/// tagged with, sorted by root.
+pub(crate) fn summarize_workspaces(
+    roots: impl IntoIterator<Item = String>,
+    ops: &[PlanOp],
+) -> Vec<PlanWorkspace> {
+    let mut by_root: BTreeMap<String, Vec<PlanOp>> =
+        roots.into_iter().map(|root| (root, vec![])).collect();
+    for op in ops {
+        if let Some(root) = &op.workspace {
+            by_root.entry(root.clone()).or_default().push(op.clone());
+        }
+    }
+    by_root
+        .into_iter()
+        .map(|(root, ops)| PlanWorkspace {
+            root,
+            summary: summarize(&ops),
+        })
+        .collect()
+}
+
+/// `inner` seen from the workspace at `prefix`: relative paths resolve
+/// against the workspace directory, absolute ones are passed through.
+pub(crate) struct ScopedRepoView<'a> {
+    inner: &'a dyn RepoView,
+    prefix: Utf8PathBuf,
+    root: Utf8PathBuf,
+}
+
+impl<'a> ScopedRepoView<'a> {
+    pub(crate) fn new(inner: &'a dyn RepoView, prefix: &Utf8Path) -> Self {
+        Self {
+            inner,
+            prefix: prefix.to_path_buf(),
+            root: inner.root().join(prefix),
+        }
+    }
+
+    fn inner_path(&self, rel: &Utf8Path) -> Utf8PathBuf {
+        if rel.is_absolute() {
+            rel.to_path_buf()
+        } else {
+            self.prefix.join(rel.strip_prefix("./").unwrap_or(rel))
+        }
+    }
+}
+
+impl RepoView for ScopedRepoView<'_> {
+    fn root(&self) -> &Utf8Path {
+        &self.root
+    }
+
+    fn read_to_string(&self, rel: &Utf8Path) -> anyhow::Result<String> {
+        self.inner.read_to_string(&self.inner_path(rel))
+    }
+
+    fn exists(&self, rel: &Utf8Path) -> bool {
+        self.inner.exists(&self.inner_path(rel))
+    }
+
+    fn read_toml(&self, rel: &Utf8Path) -> anyhow::Result<Arc<DocumentMut>> {
+        self.inner.read_toml(&self.inner_path(rel))
+    }
+
+    fn is_gitignored(&self, rel: &Utf8Path) -> bool {
+        self.inner.is_gitignored(&self.inner_path(rel))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ports::FsRepoView;
+
+    fn write(root: &Utf8Path, rel: &str, contents: &str) {
+        let path = root.join(rel);
+        std::fs::create_dir_all(path.parent().unwrap()).unwrap();
+        std::fs::write(path, contents).unwrap();
+    }
+
+    #[test]
+    fn finds_nested_workspaces_outside_skipped_dirs() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        write(
+            root,
+            "services/api/Cargo.toml",
+            "[workspace]\nmembers = []\n",
+        );
+        write(
+            root,
+            "services/api/crates/core/Cargo.toml",
+            "[package]\nname = \"core\"\n",
+        );
+        write(root, "tools/cli/Cargo.toml", "[workspace]\n");
+        write(root, "tools/cli/target/debug/Cargo.toml", "[workspace]\n");
+        write(root, "third_party/dep/Cargo.toml", "[workspace]\n");
+        write(root, "third_party/dep/.cargo-checksum.json", "{}");
+        let repo = FsRepoView::new(root.to_path_buf());
+
+        let roots = discover_workspace_roots(&repo, &ScanPolicy::default());
+        assert_eq!(roots, vec!["services/api", "tools/cli"]);
+
+        write(root, "Cargo.toml", "[package]\nname = \"top\"\n");
+        let roots = discover_workspace_roots(&repo, &ScanPolicy::default());
+        assert_eq!(roots, vec!["", "services/api", "tools/cli"]);
+    }
+
+    #[test]
+    fn innermost_root_owns_a_path() {
+        let roots: Vec<Utf8PathBuf> = vec!["".into(), "tools".into(), "tools/cli".into()];
+
+        let owner = |path: &str| owning_workspace(&roots, Utf8Path::new(path)).map(workspace_label);
+        assert_eq!(owner("Cargo.toml").as_deref(), Some("."));
+        assert_eq!(owner("./tools/Cargo.toml").as_deref(), Some("tools"));
+        assert_eq!(owner("tools/cli/src/main.rs").as_deref(), Some("tools/cli"));
+        assert_eq!(owner("tools-extra/Cargo.toml").as_deref(), Some("."));
+        assert_eq!(
+            owning_workspace(&roots[1..], Utf8Path::new("Cargo.toml")),
+            None
+        );
+    }
+
+    #[test]
+    fn scoped_view_resolves_against_the_workspace() {
+        let td = tempfile::tempdir().unwrap();
+        let root = Utf8Path::from_path(td.path()).unwrap();
+        write(
+            root,
+            "tools/cli/Cargo.toml",
+            "[workspace]\nresolver = \"2\"\n",
+        );
+        let repo = FsRepoView::new(root.to_path_buf());
+        let scoped = ScopedRepoView::new(&repo, Utf8Path::new("tools/cli"));
+
+        assert_eq!(scoped.root(), root.join("tools/cli"));
+        assert!(scoped.exists(Utf8Path::new("Cargo.toml")));
+        let doc = scoped.read_toml(Utf8Path::new("./Cargo.toml")).unwrap();
+        assert_eq!(doc["workspace"]["resolver"].as_str(), Some("2"));
+        assert!(!scoped.exists(Utf8Path::new("tools/cli/Cargo.toml")));
+    }
+}
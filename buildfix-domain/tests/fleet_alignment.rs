@@ -70,6 +70,7 @@ fn fingerprint_propagates_to_finding_ref() {
         repo_root: root.clone(),
         artifacts_dir,
         config: PlannerConfig::default(),
+        workspace_graph: None,
     };
     let repo = FsRepoView::new(root);
 
@@ -96,6 +97,7 @@ fn safety_counts_computed_in_summary() {
         repo_root: root.clone(),
         artifacts_dir,
         config: PlannerConfig::default(),
+        workspace_graph: None,
     };
     let repo = FsRepoView::new(root);
 
@@ -10,6 +10,8 @@
 
 use buildfix_domain::{FsRepoView, PlanContext, Planner, PlannerConfig};
 use buildfix_render::{render_apply_md, render_plan_md};
+use buildfix_types::cancel::CancellationToken;
+use buildfix_types::clock::{Clock, Ids};
 use buildfix_types::receipt::ToolInfo;
 use buildfix_types::wire::{ApplyV1, PlanV1};
 use camino::Utf8PathBuf;
@@ -131,12 +133,19 @@ fn normalize_apply_json(json: &str) -> serde_json::Value {
             repo.remove("head_sha_after");
             repo.remove("dirty_before");
             repo.remove("dirty_after");
+            repo.remove("dirty_changes");
         }
 
         if let Some(plan_ref) = obj.get_mut("plan_ref").and_then(|r| r.as_object_mut()) {
             plan_ref.insert("path".to_string(), serde_json::json!("<PLAN_PATH>"));
             plan_ref.remove("sha256");
         }
+
+        if let Some(results) = obj.get_mut("results").and_then(|r| r.as_array_mut()) {
+            for result in results.iter_mut().filter_map(|r| r.as_object_mut()) {
+                result.remove("duration_ms");
+            }
+        }
     }
 
     v
@@ -185,6 +194,7 @@ fn run_fixture_test(fixture_name: &str) {
     let planner_config = PlannerConfig {
         allow: fixture_config.policy.allow.clone(),
         deny: fixture_config.policy.deny.clone(),
+        path_policies: Vec::new(),
         allow_guarded: fixture_config.policy.allow_guarded,
         allow_unsafe: fixture_config.policy.allow_unsafe,
         allow_dirty: fixture_config.policy.allow_dirty,
@@ -192,6 +202,14 @@ fn run_fixture_test(fixture_name: &str) {
         max_files: fixture_config.policy.max_files,
         max_patch_bytes: fixture_config.policy.max_patch_bytes,
         params: std::collections::HashMap::new(),
+        fixers: Default::default(),
+        min_severity: None,
+        sensors: Vec::new(),
+        exclude_check_ids: Vec::new(),
+        changed_paths: None,
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
+        scan: Default::default(),
     };
 
     let planner = Planner::new();
@@ -199,6 +217,7 @@ fn run_fixture_test(fixture_name: &str) {
         repo_root: repo_root.clone(),
         artifacts_dir: artifacts_dir_utf8,
         config: planner_config,
+        workspace_graph: None,
     };
     let repo = FsRepoView::new(repo_root.clone());
     let tool = ToolInfo {
@@ -215,6 +234,8 @@ fn run_fixture_test(fixture_name: &str) {
     // Attach preconditions (but we'll strip sha256 for comparison)
     let attach_opts = buildfix_edit::AttachPreconditionsOptions {
         include_git_head: false,
+        pin_op_targets: false,
+        mode: Default::default(),
     };
     buildfix_edit::attach_preconditions(&repo_root, &mut plan, &attach_opts)
         .expect("attach preconditions");
@@ -237,7 +258,16 @@ fn run_fixture_test(fixture_name: &str) {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".buildfix.bak".to_string(),
+        backup_keep_last: None,
         params: std::collections::HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
     let patch =
         buildfix_edit::preview_patch(&repo_root, &plan, &preview_opts).expect("preview patch");
@@ -323,10 +353,19 @@ fn run_fixture_test(fixture_name: &str) {
             backup_enabled: false,
             backup_dir: None,
             backup_suffix: ".buildfix.bak".to_string(),
+            backup_keep_last: None,
             params: std::collections::HashMap::new(),
+            lock_path: None,
+            binary_patch: false,
+            durable_writes: false,
+            selected_ops: None,
+            cancel: CancellationToken::new(),
+            clock: Clock::default(),
+            ids: Ids::default(),
+            jobs: 0,
         };
 
-        let (apply, _patch) =
+        let (apply, apply_patch) =
             buildfix_edit::apply_plan(&repo_root, &plan, tool, &apply_opts).expect("apply plan");
 
         let apply_wire = ApplyV1::try_from(&apply).expect("convert apply to wire");
@@ -349,7 +388,7 @@ fn run_fixture_test(fixture_name: &str) {
             );
         }
 
-        let apply_md = render_apply_md(&apply);
+        let apply_md = render_apply_md(&apply, &apply_patch);
         if bless {
             fs::create_dir_all(&expected_dir).expect("create expected dir");
             fs::write(&expected_apply_md_path, &apply_md).expect("write expected apply.md");
@@ -96,6 +96,7 @@ edition = "2021"
             repo_root: root.clone(),
             artifacts_dir,
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let tool = ToolInfo {
@@ -162,6 +163,7 @@ members = []
             repo_root: root,
             artifacts_dir,
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let tool = ToolInfo {
@@ -0,0 +1,300 @@
+//! Run-scoped backups, retention, and restore.
+//!
+//! Each apply that writes files gets its own directory under the backup root:
+//!
+//! ```text
+//! <backup_dir>/<run_id>/manifest.json
+//! <backup_dir>/<run_id>/<repo-relative path><suffix>
+//! ```
+//!
+//! Run ids start with a UTC timestamp so lexical order is chronological, which
+//! is what retention and "latest run" lookups rely on.
+
+use anyhow::Context;
+use buildfix_hash::sha256_hex;
+use buildfix_types::apply::ApplyResult;
+use buildfix_types::clock::{Clock, Ids};
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// File name of the per-run manifest.
+pub const BACKUP_MANIFEST_FILE: &str = "manifest.json";
+
+/// Manifest written alongside the backed-up files of one apply run.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupManifest {
+    pub run_id: String,
+    pub created_at: String,
+    #[serde(default)]
+    pub files: Vec<BackupEntry>,
+}
+
+/// One backed-up file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BackupEntry {
+    /// Repo-relative path of the original file.
+    pub path: String,
+    /// Path of the backup copy, relative to the run directory.
+    pub backup: String,
+    /// SHA256 of the original contents.
+    pub sha256: String,
+}
+
+/// A backup run directory discovered on disk.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct BackupRun {
+    pub id: String,
+    pub dir: Utf8PathBuf,
+}
+
+/// Write backups of `changed_files` into a fresh run directory and record the
+/// backup paths on matching apply results. Returns the run directory.
+///
+/// Files missing from `before` are being created and have nothing to back up.
+pub(crate) fn create_backups(
+    backup_dir: &Utf8Path,
+    suffix: &str,
+    changed_files: &BTreeSet<Utf8PathBuf>,
+    before: &BTreeMap<Utf8PathBuf, String>,
+    results: &mut [ApplyResult],
+    clock: &Clock,
+    ids: &Ids,
+) -> anyhow::Result<Utf8PathBuf> {
+    // A fixed clock and sequential ids repeat across processes; never
+    // reuse an earlier run's directory.
+    let (run_id, run_dir) = loop {
+        let run_id = new_run_id(clock, ids);
+        let run_dir = backup_dir.join(&run_id);
+        if !run_dir.exists() {
+            break (run_id, run_dir);
+        }
+    };
+    fs::create_dir_all(&run_dir).with_context(|| format!("create backup dir {}", run_dir))?;
+
+    let mut manifest = BackupManifest {
+        run_id,
+        created_at: clock.now().to_rfc3339(),
+        files: Vec::new(),
+    };
+
+    for path in changed_files {
+        let Some(contents) = before.get(path) else {
+            continue;
+        };
+        let backup_rel = format!("{}{}", path, suffix);
+        let backup_path = run_dir.join(&backup_rel);
+
+        if let Some(parent) = backup_path.parent() {
+            fs::create_dir_all(parent).with_context(|| format!("create backup dir {}", parent))?;
+        }
+
+        fs::write(&backup_path, contents)
+            .with_context(|| format!("write backup {}", backup_path))?;
+
+        manifest.files.push(BackupEntry {
+            path: path.to_string(),
+            backup: backup_rel,
+            sha256: sha256_hex(contents.as_bytes()),
+        });
+
+        // Update any result entries that mention this file.
+        for result in results.iter_mut() {
+            for file in &mut result.files {
+                if file.path == *path {
+                    file.backup_path = Some(backup_path.to_string());
+                }
+            }
+        }
+    }
+
+    let manifest_path = run_dir.join(BACKUP_MANIFEST_FILE);
+    let json = serde_json::to_string_pretty(&manifest).context("serialize backup manifest")?;
+    fs::write(&manifest_path, json).with_context(|| format!("write {}", manifest_path))?;
+
+    Ok(run_dir)
+}
+
+/// List backup runs under `backup_dir`, oldest first.
+///
+/// Only directories containing a manifest are considered runs; anything else
+/// (including flat backups from older releases) is ignored.
+pub fn list_backup_runs(backup_dir: &Utf8Path) -> anyhow::Result<Vec<BackupRun>> {
+    if !backup_dir.exists() {
+        return Ok(Vec::new());
+    }
+
+    let mut runs = Vec::new();
+    for entry in fs::read_dir(backup_dir).with_context(|| format!("read dir {}", backup_dir))? {
+        let entry = entry?;
+        let Ok(dir) = Utf8PathBuf::from_path_buf(entry.path()) else {
+            continue;
+        };
+        if !dir.is_dir() || !dir.join(BACKUP_MANIFEST_FILE).is_file() {
+            continue;
+        }
+        let Some(id) = dir.file_name().map(str::to_string) else {
+            continue;
+        };
+        runs.push(BackupRun { id, dir });
+    }
+    runs.sort_by(|a, b| a.id.cmp(&b.id));
+    Ok(runs)
+}
+
+/// The most recent backup run, if any.
+pub fn latest_backup_run(backup_dir: &Utf8Path) -> anyhow::Result<Option<BackupRun>> {
+    Ok(list_backup_runs(backup_dir)?.pop())
+}
+
+/// Delete all but the newest `keep_last` backup runs. Returns the removed runs.
+pub fn prune_backups(backup_dir: &Utf8Path, keep_last: usize) -> anyhow::Result<Vec<BackupRun>> {
+    let runs = list_backup_runs(backup_dir)?;
+    let excess = runs.len().saturating_sub(keep_last);
+    let removed: Vec<BackupRun> = runs.into_iter().take(excess).collect();
+    for run in &removed {
+        fs::remove_dir_all(&run.dir).with_context(|| format!("remove backup run {}", run.dir))?;
+    }
+    Ok(removed)
+}
+
+/// Read the manifest of a backup run.
+pub fn read_backup_manifest(run_dir: &Utf8Path) -> anyhow::Result<BackupManifest> {
+    let path = run_dir.join(BACKUP_MANIFEST_FILE);
+    let contents = fs::read_to_string(&path).with_context(|| format!("read {}", path))?;
+    serde_json::from_str(&contents).with_context(|| format!("parse {}", path))
+}
+
+/// Restore every file recorded in a backup run to `repo_root`.
+///
+/// Each backup copy is checked against the sha256 in the manifest before
+/// anything is written, so a damaged run restores nothing. Restored files are
+/// always fsynced. Returns the repo-relative paths that were restored.
+pub fn restore_from_backup(
+    repo_root: &Utf8Path,
+    run_dir: &Utf8Path,
+) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let manifest = read_backup_manifest(run_dir)?;
+
+    let mut staged = Vec::with_capacity(manifest.files.len());
+    for entry in &manifest.files {
+        let backup_path = run_dir.join(&entry.backup);
+        let contents = fs::read_to_string(&backup_path)
+            .with_context(|| format!("read backup {}", backup_path))?;
+        let actual = sha256_hex(contents.as_bytes());
+        if actual != entry.sha256 {
+            anyhow::bail!(
+                "backup {} does not match manifest sha256 (expected {}, got {})",
+                backup_path,
+                entry.sha256,
+                actual
+            );
+        }
+        staged.push((Utf8PathBuf::from(&entry.path), contents));
+    }
+
+    let mut restored = Vec::with_capacity(staged.len());
+    for (rel, contents) in staged {
+        let abs = super::abs_path(repo_root, &rel);
+        super::write_atomic(&abs, &contents, true, &Ids::default())?;
+        restored.push(rel);
+    }
+    Ok(restored)
+}
+
+fn new_run_id(clock: &Clock, ids: &Ids) -> String {
+    let stamp = clock.now().format("%Y%m%dT%H%M%S%.3fZ");
+    let suffix = ids.new_id().simple().to_string();
+    format!("{}-{}", stamp, &suffix[suffix.len() - 8..])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn temp_root() -> (TempDir, Utf8PathBuf) {
+        let temp = TempDir::new().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        (temp, root)
+    }
+
+    fn backup_one(backup_dir: &Utf8Path, path: &str, contents: &str) -> Utf8PathBuf {
+        let changed: BTreeSet<Utf8PathBuf> = [Utf8PathBuf::from(path)].into();
+        let before: BTreeMap<Utf8PathBuf, String> =
+            [(Utf8PathBuf::from(path), contents.to_string())].into();
+        create_backups(
+            backup_dir,
+            ".bak",
+            &changed,
+            &before,
+            &mut [],
+            &Clock::default(),
+            &Ids::default(),
+        )
+        .expect("backup")
+    }
+
+    #[test]
+    fn run_ids_sort_chronologically() {
+        let (clock, ids) = (Clock::default(), Ids::default());
+        let a = new_run_id(&clock, &ids);
+        std::thread::sleep(std::time::Duration::from_millis(5));
+        let b = new_run_id(&clock, &ids);
+        assert!(a < b, "{a} should sort before {b}");
+    }
+
+    #[test]
+    fn prune_keeps_newest_runs() {
+        let (_temp, root) = temp_root();
+        let backups = root.join("backups");
+        let mut dirs = Vec::new();
+        for i in 0..3 {
+            dirs.push(backup_one(&backups, "Cargo.toml", &format!("v{i}")));
+            std::thread::sleep(std::time::Duration::from_millis(5));
+        }
+        // Legacy flat backups are left alone.
+        fs::write(backups.join("Cargo.toml.bak"), "legacy").expect("write");
+
+        let removed = prune_backups(&backups, 1).expect("prune");
+        assert_eq!(removed.len(), 2);
+        assert!(!dirs[0].exists());
+        assert!(!dirs[1].exists());
+        assert!(dirs[2].exists());
+        assert!(backups.join("Cargo.toml.bak").exists());
+        assert_eq!(latest_backup_run(&backups).unwrap().unwrap().dir, dirs[2]);
+    }
+
+    #[test]
+    fn restore_writes_original_contents() {
+        let (_temp, root) = temp_root();
+        fs::create_dir_all(root.join("crates/a")).expect("mkdir");
+        fs::write(root.join("crates/a/Cargo.toml"), "changed").expect("write");
+
+        let run = backup_one(&root.join("backups"), "crates/a/Cargo.toml", "original");
+        let restored = restore_from_backup(&root, &run).expect("restore");
+
+        assert_eq!(restored, vec![Utf8PathBuf::from("crates/a/Cargo.toml")]);
+        assert_eq!(
+            fs::read_to_string(root.join("crates/a/Cargo.toml")).unwrap(),
+            "original"
+        );
+    }
+
+    #[test]
+    fn restore_rejects_tampered_backup() {
+        let (_temp, root) = temp_root();
+        fs::write(root.join("Cargo.toml"), "changed").expect("write");
+
+        let run = backup_one(&root.join("backups"), "Cargo.toml", "original");
+        fs::write(run.join("Cargo.toml.bak"), "tampered").expect("tamper");
+
+        let err = restore_from_backup(&root, &run).expect_err("tampered");
+        assert!(err.to_string().contains("does not match manifest"));
+        assert_eq!(
+            fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            "changed"
+        );
+    }
+}
@@ -0,0 +1,296 @@
+//! Reading git state for preconditions and scoping.
+//!
+//! HEAD and working-tree status are read in-process with `gix` when the
+//! `gix` feature is on, so they work without a `git` binary and do not
+//! depend on `PATH` or the locale. The `git` subprocess versions remain as
+//! the fallback (and are what `ShellGitPort` uses); `changed_files_since`
+//! always runs `git`.
+
+use anyhow::Context;
+use camino::Utf8Path;
+use std::collections::BTreeSet;
+
+/// Get the current git HEAD SHA for a repository.
+pub fn get_head_sha(repo_root: &Utf8Path) -> anyhow::Result<String> {
+    #[cfg(feature = "gix")]
+    match gix_head_sha(repo_root) {
+        Ok(sha) => return Ok(sha),
+        Err(e) => tracing::debug!(
+            event = buildfix_types::telemetry::GIT_GIX_FALLBACK,
+            op = "head_sha",
+            error = format!("{:#}", e),
+            "gix head sha failed, falling back to git"
+        ),
+    }
+    git_cli_head_sha(repo_root)
+}
+
+/// Check if the git working tree has uncommitted changes (including
+/// untracked files).
+pub fn is_working_tree_dirty(repo_root: &Utf8Path) -> anyhow::Result<bool> {
+    #[cfg(feature = "gix")]
+    match gix_is_dirty(repo_root) {
+        Ok(dirty) => return Ok(dirty),
+        Err(e) => tracing::debug!(
+            event = buildfix_types::telemetry::GIT_GIX_FALLBACK,
+            op = "status",
+            error = format!("{:#}", e),
+            "gix status failed, falling back to git"
+        ),
+    }
+    git_cli_is_dirty(repo_root)
+}
+
+/// Like [`is_working_tree_dirty`], but only for changes to `paths`
+/// (relative to `repo_root`, matched literally). No paths is never dirty.
+pub fn is_working_tree_dirty_paths(repo_root: &Utf8Path, paths: &[String]) -> anyhow::Result<bool> {
+    if paths.is_empty() {
+        return Ok(false);
+    }
+    #[cfg(feature = "gix")]
+    match gix_is_dirty_paths(repo_root, paths) {
+        Ok(dirty) => return Ok(dirty),
+        Err(e) => tracing::debug!(
+            event = buildfix_types::telemetry::GIT_GIX_FALLBACK,
+            op = "status",
+            error = format!("{:#}", e),
+            "gix status failed, falling back to git"
+        ),
+    }
+    git_cli_is_dirty_paths(repo_root, paths)
+}
+
+/// HEAD SHA read in-process.
+#[cfg(feature = "gix")]
+pub fn gix_head_sha(repo_root: &Utf8Path) -> anyhow::Result<String> {
+    let repo = gix::discover(repo_root)
+        .with_context(|| format!("open git repository at {}", repo_root))?;
+    let id = repo.head_id().context("resolve HEAD")?;
+    Ok(id.to_string())
+}
+
+/// Whether `git status --porcelain` would print anything, computed
+/// in-process: staged or unstaged changes, or untracked files.
+#[cfg(feature = "gix")]
+pub fn gix_is_dirty(repo_root: &Utf8Path) -> anyhow::Result<bool> {
+    let repo = gix::discover(repo_root)
+        .with_context(|| format!("open git repository at {}", repo_root))?;
+    gix_status_nonempty(&repo, Vec::new())
+}
+
+/// [`gix_is_dirty`] limited to `paths`, relative to `repo_root`.
+#[cfg(feature = "gix")]
+pub fn gix_is_dirty_paths(repo_root: &Utf8Path, paths: &[String]) -> anyhow::Result<bool> {
+    if paths.is_empty() {
+        return Ok(false);
+    }
+    let repo = gix::discover(repo_root)
+        .with_context(|| format!("open git repository at {}", repo_root))?;
+    // Pathspecs are anchored at the work tree top (`top`), not the process
+    // directory, so prefix repo_root's place in the work tree.
+    let workdir = repo
+        .workdir()
+        .context("repository has no work tree")?
+        .canonicalize()
+        .context("resolve work tree")?;
+    let root = repo_root
+        .as_std_path()
+        .canonicalize()
+        .with_context(|| format!("resolve {}", repo_root))?;
+    let prefix = root
+        .strip_prefix(&workdir)
+        .context("repo root is outside the work tree")?;
+    let prefix = prefix
+        .to_str()
+        .context("non-UTF-8 repo root")?
+        .replace('\\', "/");
+    let specs = paths
+        .iter()
+        .map(|path| {
+            let path = path.trim_start_matches("./");
+            let full = if prefix.is_empty() {
+                path.to_string()
+            } else {
+                format!("{}/{}", prefix, path)
+            };
+            format!(":(top,literal){}", full).into()
+        })
+        .collect();
+    gix_status_nonempty(&repo, specs)
+}
+
+#[cfg(feature = "gix")]
+fn gix_status_nonempty(
+    repo: &gix::Repository,
+    specs: Vec<gix::bstr::BString>,
+) -> anyhow::Result<bool> {
+    let mut changes = repo
+        .status(gix::progress::Discard)
+        .context("prepare status")?
+        .untracked_files(gix::status::UntrackedFiles::Files)
+        .into_iter(specs)
+        .context("compute status")?;
+    match changes.next() {
+        Some(item) => {
+            item.context("compute status")?;
+            Ok(true)
+        }
+        None => Ok(false),
+    }
+}
+
+/// HEAD SHA from `git rev-parse HEAD`.
+pub fn git_cli_head_sha(repo_root: &Utf8Path) -> anyhow::Result<String> {
+    let output = std::process::Command::new("git")
+        .arg("rev-parse")
+        .arg("HEAD")
+        .current_dir(repo_root)
+        .output()
+        .context("failed to run git rev-parse HEAD")?;
+
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git rev-parse HEAD failed: {}", stderr.trim());
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
+}
+
+/// Whether `git status --porcelain` prints anything.
+pub fn git_cli_is_dirty(repo_root: &Utf8Path) -> anyhow::Result<bool> {
+    let status_output = std::process::Command::new("git")
+        .args(["status", "--porcelain"])
+        .current_dir(repo_root)
+        .output()
+        .context("failed to run git status")?;
+
+    if !status_output.status.success() {
+        let stderr = String::from_utf8_lossy(&status_output.stderr);
+        anyhow::bail!("git status failed: {}", stderr.trim());
+    }
+
+    Ok(!status_output.stdout.is_empty())
+}
+
+/// Whether `git status --porcelain -- <paths>` prints anything.
+pub fn git_cli_is_dirty_paths(repo_root: &Utf8Path, paths: &[String]) -> anyhow::Result<bool> {
+    if paths.is_empty() {
+        return Ok(false);
+    }
+    let status_output = std::process::Command::new("git")
+        .args(["status", "--porcelain", "--"])
+        .args(paths.iter().map(|p| format!(":(literal){}", p)))
+        .current_dir(repo_root)
+        .output()
+        .context("failed to run git status")?;
+
+    if !status_output.status.success() {
+        let stderr = String::from_utf8_lossy(&status_output.stderr);
+        anyhow::bail!("git status failed: {}", stderr.trim());
+    }
+
+    Ok(!status_output.stdout.is_empty())
+}
+
+/// Paths under `repo_root` that `git status` reports as modified, staged,
+/// deleted or untracked, relative to `repo_root` and sorted.
+pub fn git_cli_dirty_paths(repo_root: &Utf8Path) -> anyhow::Result<Vec<String>> {
+    let git = |args: &[&str]| -> anyhow::Result<Vec<u8>> {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(repo_root)
+            .output()
+            .with_context(|| format!("failed to run git {}", args[0]))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git {} failed: {}", args[0], stderr.trim());
+        }
+        Ok(output.stdout)
+    };
+
+    // Porcelain paths are relative to the top level, whatever the cwd.
+    let prefix = String::from_utf8_lossy(&git(&["rev-parse", "--show-prefix"])?)
+        .trim()
+        .to_string();
+    let status = git(&[
+        "status",
+        "--porcelain",
+        "-z",
+        "--no-renames",
+        "--untracked-files=all",
+        "--",
+        ".",
+    ])?;
+
+    // Each entry is `XY <path>\0`.
+    let paths: BTreeSet<String> = status
+        .split(|b| *b == 0)
+        .filter(|entry| entry.len() > 3)
+        .filter_map(|entry| {
+            let path = String::from_utf8_lossy(&entry[3..]);
+            path.strip_prefix(prefix.as_str()).map(str::to_string)
+        })
+        .collect();
+    Ok(paths.into_iter().collect())
+}
+
+/// Paths changed between the merge base of `base_ref` and HEAD, plus
+/// uncommitted and untracked files, relative to the repo root and sorted.
+pub fn changed_files_since(repo_root: &Utf8Path, base_ref: &str) -> anyhow::Result<Vec<String>> {
+    let git = |args: &[&str]| -> anyhow::Result<String> {
+        let output = std::process::Command::new("git")
+            .args(args)
+            .current_dir(repo_root)
+            .output()
+            .with_context(|| format!("failed to run git {}", args.join(" ")))?;
+        if !output.status.success() {
+            let stderr = String::from_utf8_lossy(&output.stderr);
+            anyhow::bail!("git {} failed: {}", args.join(" "), stderr.trim());
+        }
+        Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+    };
+
+    let merge_base = git(&["merge-base", base_ref, "HEAD"])?;
+    // Diffing the working tree against the merge base covers both commits
+    // on this branch and uncommitted edits. `--relative` makes paths (and
+    // the untracked listing) relative to a repo root below the git top level.
+    let diff = git(&["diff", "--name-only", "--relative", merge_base.trim()])?;
+    let untracked = git(&["ls-files", "--others", "--exclude-standard"])?;
+
+    let files: BTreeSet<String> = diff
+        .lines()
+        .chain(untracked.lines())
+        .filter(|l| !l.is_empty())
+        .map(str::to_string)
+        .collect();
+    Ok(files.into_iter().collect())
+}
+
+/// Paths with changes staged in the index, relative to the repo root and
+/// sorted. Staged deletions are left out: there is nothing left to fix.
+pub fn staged_files(repo_root: &Utf8Path) -> anyhow::Result<Vec<String>> {
+    let output = std::process::Command::new("git")
+        .args([
+            "diff",
+            "--cached",
+            "--name-only",
+            "--relative",
+            "--diff-filter=d",
+            "-z",
+        ])
+        .current_dir(repo_root)
+        .output()
+        .context("failed to run git diff --cached")?;
+    if !output.status.success() {
+        let stderr = String::from_utf8_lossy(&output.stderr);
+        anyhow::bail!("git diff --cached failed: {}", stderr.trim());
+    }
+
+    let files: BTreeSet<String> = output
+        .stdout
+        .split(|b| *b == 0)
+        .filter(|p| !p.is_empty())
+        .map(|p| String::from_utf8_lossy(p).into_owned())
+        .collect();
+    Ok(files.into_iter().collect())
+}
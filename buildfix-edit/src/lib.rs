@@ -2,12 +2,37 @@
 //!
 //! Responsibilities:
 //! - Attach file preconditions (sha256) to a plan.
+//! - Snapshot touched files so each is read and hashed once per run.
 //! - Apply operations (in-memory or to disk) using `toml_edit`.
 //! - Generate a unified diff preview.
+//! - Serialize concurrent applies with an advisory lock file.
+//! - Keep run-scoped backups with retention and restore.
 
+mod backup;
 mod error;
-
+mod git;
+mod lock;
+mod patch;
+mod patch_apply;
+mod pool;
+mod semantic;
+mod snapshot;
+
+pub use backup::{
+    BACKUP_MANIFEST_FILE, BackupEntry, BackupManifest, BackupRun, latest_backup_run,
+    list_backup_runs, prune_backups, read_backup_manifest, restore_from_backup,
+};
 pub use error::{EditError, EditResult, PolicyBlockError};
+pub use git::{
+    changed_files_since, get_head_sha, git_cli_dirty_paths, git_cli_head_sha, git_cli_is_dirty,
+    git_cli_is_dirty_paths, is_working_tree_dirty, is_working_tree_dirty_paths, staged_files,
+};
+#[cfg(feature = "gix")]
+pub use git::{gix_head_sha, gix_is_dirty, gix_is_dirty_paths};
+pub use lock::{ApplyLock, DEFAULT_LOCK_STALE_AFTER, LockError, LockInfo};
+pub use patch::{FileChange, PatchOptions, render_file_changes};
+pub use patch_apply::{PatchApplyOutcome, apply_patch, patch_paths};
+pub use snapshot::FileSnapshot;
 
 use anyhow::Context;
 use buildfix_hash::sha256_hex;
@@ -15,15 +40,23 @@ use buildfix_types::apply::{
     ApplyFile, ApplyPreconditions, ApplyRepoInfo, ApplyResult, ApplyStatus, ApplySummary,
     BuildfixApply, PlanRef, PreconditionMismatch,
 };
-use buildfix_types::ops::{OpKind, SafetyClass};
-use buildfix_types::plan::{BuildfixPlan, FilePrecondition, PlanOp};
+use buildfix_types::cancel::CancellationToken;
+use buildfix_types::clock::{Clock, Ids};
+use buildfix_types::error_code::BuildfixErrorCode;
+use buildfix_types::messages::{self, codes};
+use buildfix_types::ops::{InsertHint, InsertPosition, OpKind, SafetyClass};
+use buildfix_types::plan::{
+    BuildfixPlan, FilePrecondition, PlanOp, PlanPreconditions, PreconditionMode,
+};
+use buildfix_types::progress::{Phase, Progress};
 use buildfix_types::receipt::ToolInfo;
 use camino::{Utf8Path, Utf8PathBuf};
-use diffy::PatchFormatter;
+use chrono::{DateTime, Utc};
 use fs_err as fs;
 use std::collections::{BTreeMap, BTreeSet, HashMap};
+use std::io::{self, Write};
+use std::time::Duration;
 use toml_edit::{DocumentMut, InlineTable, Item, value};
-use uuid::Uuid;
 
 #[derive(Debug, Clone, Default)]
 pub struct ApplyOptions {
@@ -31,12 +64,37 @@ pub struct ApplyOptions {
     pub allow_guarded: bool,
     pub allow_unsafe: bool,
     pub backup_enabled: bool,
-    /// Directory to store backups.
+    /// Directory to store backups. Each writing apply gets its own run directory beneath it.
     pub backup_dir: Option<Utf8PathBuf>,
     /// Backup file suffix.
     pub backup_suffix: String,
+    /// Retention: keep only the newest N backup runs after a successful write.
+    /// The run just written always counts as one of them.
+    pub backup_keep_last: Option<usize>,
     /// Params to resolve unsafe operations.
     pub params: HashMap<String, String>,
+    /// Lock file held for the duration of a non-dry-run apply.
+    /// When `None`, no lock is taken.
+    pub lock_path: Option<Utf8PathBuf>,
+    /// Render the patch with full-index headers and binary sections (`git diff --binary`).
+    pub binary_patch: bool,
+    /// fsync each written file and its parent directory before returning, so
+    /// an apply survives a crash or power loss right after it reports success.
+    pub durable_writes: bool,
+    /// When set, only ops whose id is listed are applied; the rest are
+    /// reported as skipped with the `user_filtered` token.
+    pub selected_ops: Option<BTreeSet<String>>,
+    /// Checked before each op. Once cancelled, the remaining ops are
+    /// reported as skipped with the `cancelled` token and only the ops that
+    /// already ran are written.
+    pub cancel: CancellationToken,
+    /// Time source for backup run ids and manifests.
+    pub clock: Clock,
+    /// Id source for backup run ids and temp file names.
+    pub ids: Ids,
+    /// Threads used to read, hash, and diff touched files
+    /// (0 = available cores, 1 = inline).
+    pub jobs: usize,
 }
 
 /// Options for attaching preconditions to a plan.
@@ -44,39 +102,12 @@ pub struct ApplyOptions {
 pub struct AttachPreconditionsOptions {
     /// If true, attach a `head_sha` precondition requiring the repo HEAD to match.
     pub include_git_head: bool,
-}
-
-/// Get the current git HEAD SHA for a repository.
-pub fn get_head_sha(repo_root: &Utf8Path) -> anyhow::Result<String> {
-    let output = std::process::Command::new("git")
-        .arg("rev-parse")
-        .arg("HEAD")
-        .current_dir(repo_root)
-        .output()
-        .context("failed to run git rev-parse HEAD")?;
-
-    if !output.status.success() {
-        let stderr = String::from_utf8_lossy(&output.stderr);
-        anyhow::bail!("git rev-parse HEAD failed: {}", stderr.trim());
-    }
-
-    Ok(String::from_utf8_lossy(&output.stdout).trim().to_string())
-}
-
-/// Check if the git working tree has uncommitted changes.
-pub fn is_working_tree_dirty(repo_root: &Utf8Path) -> anyhow::Result<bool> {
-    let status_output = std::process::Command::new("git")
-        .args(["status", "--porcelain"])
-        .current_dir(repo_root)
-        .output()
-        .context("failed to run git status")?;
-
-    if !status_output.status.success() {
-        let stderr = String::from_utf8_lossy(&status_output.stderr);
-        anyhow::bail!("git status failed: {}", stderr.trim());
-    }
-
-    Ok(!status_output.stdout.is_empty())
+    /// If true, also pin each op's `target.sha256` so a drifted file blocks
+    /// only the ops that touch it.
+    pub pin_op_targets: bool,
+    /// With [`PreconditionMode::Semantic`], also record each TOML file's
+    /// structural hash so formatting-only changes do not block apply.
+    pub mode: PreconditionMode,
 }
 
 /// Attach plan-level preconditions (FileSha256) for each file touched by ops.
@@ -86,21 +117,50 @@ pub fn attach_preconditions(
     repo_root: &Utf8Path,
     plan: &mut BuildfixPlan,
     opts: &AttachPreconditionsOptions,
+) -> anyhow::Result<()> {
+    attach_preconditions_with_snapshot(&mut FileSnapshot::new(repo_root), plan, opts)
+}
+
+/// [`attach_preconditions`], hashing files through `snapshot` so a later
+/// [`preview_patch_with_snapshot`] reuses what was read here.
+pub fn attach_preconditions_with_snapshot(
+    snapshot: &mut FileSnapshot,
+    plan: &mut BuildfixPlan,
+    opts: &AttachPreconditionsOptions,
 ) -> anyhow::Result<()> {
     let mut files = BTreeSet::new();
     for op in &plan.ops {
         files.insert(op.target.path.clone());
     }
 
+    snapshot.read(files.iter().map(Utf8Path::new));
     let mut pres = Vec::new();
     for path in files {
-        let abs = abs_path(repo_root, Utf8Path::new(&path));
-        let bytes = fs::read(&abs).with_context(|| format!("read {}", abs))?;
-        let sha = sha256_hex(&bytes);
-        pres.push(FilePrecondition { path, sha256: sha });
+        let file = Utf8Path::new(&path);
+        let sha = snapshot.sha256(file)?.to_string();
+        let semantic_sha256 = match opts.mode {
+            PreconditionMode::Exact => None,
+            PreconditionMode::Semantic => semantic::semantic_sha256(file, snapshot.contents(file)),
+        };
+        pres.push(FilePrecondition {
+            path,
+            sha256: sha,
+            semantic_sha256,
+        });
+    }
+
+    if opts.pin_op_targets {
+        for op in &mut plan.ops {
+            op.target.sha256 = pres
+                .iter()
+                .find(|p| p.path == op.target.path)
+                .map(|p| p.sha256.clone());
+        }
     }
     plan.preconditions.files = pres;
+    plan.preconditions.mode = opts.mode;
 
+    let repo_root = snapshot.root();
     if opts.include_git_head
         && let Ok(sha) = get_head_sha(repo_root)
     {
@@ -119,33 +179,81 @@ pub fn preview_patch(
     plan: &BuildfixPlan,
     opts: &ApplyOptions,
 ) -> anyhow::Result<String> {
-    let outcome = execute_plan(repo_root, plan, opts, false)?;
-    Ok(render_patch(&outcome.before, &outcome.after))
+    let mut snapshot = FileSnapshot::new(repo_root).with_jobs(opts.jobs);
+    preview_patch_with_snapshot(&mut snapshot, plan, opts)
+}
+
+/// [`preview_patch`] against the contents in `snapshot`; files it does not
+/// hold yet are read into it.
+pub fn preview_patch_with_snapshot(
+    snapshot: &mut FileSnapshot,
+    plan: &BuildfixPlan,
+    opts: &ApplyOptions,
+) -> anyhow::Result<String> {
+    let outcome = execute_plan(snapshot, plan, opts, false, &|_| {})?;
+    Ok(render_patch(&outcome.before, &outcome.after, opts))
 }
 
 /// Apply a plan. When `opts.dry_run` is true, no files are written, but results and a patch are still produced.
+///
+/// When writing and `opts.lock_path` is set, the apply lock is held from the
+/// precondition check through the final write; a live holder fails the apply
+/// with a [`LockError`].
 pub fn apply_plan(
     repo_root: &Utf8Path,
     plan: &BuildfixPlan,
     tool: ToolInfo,
     opts: &ApplyOptions,
 ) -> anyhow::Result<(BuildfixApply, String)> {
-    let mut outcome = execute_plan(repo_root, plan, opts, true)?;
-    let patch = render_patch(&outcome.before, &outcome.after);
+    apply_plan_with_progress(repo_root, plan, tool, opts, &|_| {})
+}
+
+/// [`apply_plan`], reporting [`Progress::Op`] after each op and
+/// [`Phase::Write`] before files are written.
+pub fn apply_plan_with_progress(
+    repo_root: &Utf8Path,
+    plan: &BuildfixPlan,
+    tool: ToolInfo,
+    opts: &ApplyOptions,
+    progress: &dyn Fn(&Progress<'_>),
+) -> anyhow::Result<(BuildfixApply, String)> {
+    let _lock = match &opts.lock_path {
+        Some(path) if !opts.dry_run => Some(ApplyLock::acquire(path, DEFAULT_LOCK_STALE_AFTER)?),
+        _ => None,
+    };
+
+    let mut snapshot = FileSnapshot::new(repo_root).with_jobs(opts.jobs);
+    let mut outcome = execute_plan(&mut snapshot, plan, opts, true, progress)?;
+    let patch = render_patch(&outcome.before, &outcome.after, opts);
 
     if !opts.dry_run && outcome.preconditions.verified {
         let changed_files = changed_files(&outcome.before, &outcome.after);
         if !changed_files.is_empty() {
-            if opts.backup_enabled {
-                create_backups(
-                    repo_root,
+            progress(&Progress::Phase(Phase::Write));
+            let backup_dir = opts.backup_dir.as_ref().filter(|_| opts.backup_enabled);
+            if let Some(backup_dir) = backup_dir {
+                backup::create_backups(
+                    backup_dir,
+                    &opts.backup_suffix,
                     &changed_files,
                     &outcome.before,
-                    opts,
                     &mut outcome.results,
+                    &opts.clock,
+                    &opts.ids,
                 )?;
             }
-            write_changed_files(repo_root, &changed_files, &outcome.after)?;
+            let attempts = write_changed_files(
+                repo_root,
+                &changed_files,
+                &outcome.after,
+                opts.durable_writes,
+                &opts.ids,
+            )?;
+            record_writes(repo_root, &outcome.after, &attempts, &mut outcome.results);
+            if let (Some(backup_dir), Some(keep_last)) = (backup_dir, opts.backup_keep_last) {
+                // The run just written is never pruned, even with `keep_last = 0`.
+                prune_backups(backup_dir, keep_last.max(1))?;
+            }
         }
     }
 
@@ -155,11 +263,15 @@ pub fn apply_plan(
         head_sha_after: None,
         dirty_before: None,
         dirty_after: None,
+        dirty_changes: None,
+        remote_url: None,
+        ci: None,
     };
 
     let plan_ref = PlanRef {
         path: "artifacts/buildfix/plan.json".to_string(),
         sha256: None,
+        compat: None,
     };
 
     let mut apply = BuildfixApply::new(tool, repo_info, plan_ref);
@@ -170,19 +282,36 @@ pub fn apply_plan(
     Ok((apply, patch))
 }
 
-struct ExecuteOutcome {
-    before: BTreeMap<Utf8PathBuf, String>,
-    after: BTreeMap<Utf8PathBuf, String>,
-    results: Vec<ApplyResult>,
-    summary: ApplySummary,
-    preconditions: ApplyPreconditions,
+/// Full result of executing a plan: contents before and after, per-op results
+/// (including blocked reasons), and the summary that `apply.json` reports.
+#[derive(Debug, Clone)]
+pub struct ExecuteOutcome {
+    pub before: BTreeMap<Utf8PathBuf, String>,
+    pub after: BTreeMap<Utf8PathBuf, String>,
+    pub results: Vec<ApplyResult>,
+    pub summary: ApplySummary,
+    pub preconditions: ApplyPreconditions,
+}
+
+impl ExecuteOutcome {
+    /// Files whose contents differ between `before` and `after`, with their new contents.
+    pub fn changed(&self) -> BTreeMap<Utf8PathBuf, String> {
+        self.after
+            .iter()
+            .filter(|(path, new)| {
+                self.before.get(*path).map(String::as_str).unwrap_or("") != new.as_str()
+            })
+            .map(|(path, new)| (path.clone(), new.clone()))
+            .collect()
+    }
 }
 
 fn execute_plan(
-    repo_root: &Utf8Path,
+    snapshot: &mut FileSnapshot,
     plan: &BuildfixPlan,
     opts: &ApplyOptions,
     verify_preconditions: bool,
+    progress: &dyn Fn(&Progress<'_>),
 ) -> anyhow::Result<ExecuteOutcome> {
     let mut touched_files = BTreeSet::new();
     let mut resolved_ops: Vec<ResolvedOp> = Vec::new();
@@ -195,20 +324,42 @@ fn execute_plan(
         resolved_ops.push(resolved);
     }
 
-    let mut before: BTreeMap<Utf8PathBuf, String> = BTreeMap::new();
-    for p in &touched_files {
-        let abs = abs_path(repo_root, p);
-        let contents = fs::read_to_string(&abs).unwrap_or_default();
-        before.insert(p.clone(), contents);
-    }
+    snapshot.read(touched_files.iter().map(Utf8PathBuf::as_path));
+    // Files that do not exist yet stay out of `before`; an op that writes
+    // one creates it.
+    let before: BTreeMap<Utf8PathBuf, String> = touched_files
+        .iter()
+        .filter(|p| snapshot.exists(p))
+        .map(|p| (p.clone(), snapshot.contents(p).to_string()))
+        .collect();
 
     let mut preconditions = ApplyPreconditions {
         verified: true,
         mismatches: vec![],
+        soft_matches: vec![],
     };
 
+    // Files whose every op pins its own target hash are checked per op in
+    // `execute_resolved_ops`, so drift there blocks those ops only.
+    let plan_checked: BTreeSet<Utf8PathBuf> = touched_files
+        .iter()
+        .filter(|file| {
+            resolved_ops.iter().any(|r| {
+                r.allowed
+                    && r.op.target.sha256.is_none()
+                    && Utf8Path::new(&r.op.target.path) == *file
+            })
+        })
+        .cloned()
+        .collect();
+
     if verify_preconditions
-        && !check_preconditions(repo_root, plan, &touched_files, &mut preconditions)?
+        && !check_preconditions(
+            snapshot,
+            &plan.preconditions,
+            &plan_checked,
+            &mut preconditions,
+        )?
     {
         // Abort entire apply if any mismatch.
         let mut results = Vec::new();
@@ -222,12 +373,13 @@ fn execute_plan(
             results.push(ApplyResult {
                 op_id: resolved.op.id.clone(),
                 status: ApplyStatus::Blocked,
-                message: Some("precondition mismatch".to_string()),
-                blocked_reason: Some("precondition mismatch".to_string()),
-                blocked_reason_token: Some(
-                    buildfix_types::plan::blocked_tokens::PRECONDITION_MISMATCH.to_string(),
-                ),
+                message: Some(messages::english(codes::BLOCKED_PRECONDITION_MISMATCH, &[])),
+                blocked_reason: Some(messages::english(codes::BLOCKED_PRECONDITION_MISMATCH, &[])),
+                blocked_reason_token: Some(BuildfixErrorCode::PreconditionMismatch),
                 files: vec![],
+                duration_ms: None,
+                attempts: None,
+                verified: None,
             });
         }
 
@@ -240,70 +392,194 @@ fn execute_plan(
         });
     }
 
+    let snapshot = &*snapshot;
+    execute_resolved_ops(
+        before,
+        &|file| snapshot.text_sha256(file),
+        &resolved_ops,
+        opts,
+        preconditions,
+        progress,
+    )
+}
+
+/// Run resolved ops against in-memory contents. Shared by the disk-backed and
+/// content-map entry points so both report identical results and summaries.
+///
+/// `before_sha256` hashes a file's `before` contents, letting the disk-backed
+/// path reuse the snapshot's hashes instead of hashing the same bytes again.
+fn execute_resolved_ops(
+    before: BTreeMap<Utf8PathBuf, String>,
+    before_sha256: &dyn Fn(&Utf8Path) -> String,
+    resolved_ops: &[ResolvedOp<'_>],
+    opts: &ApplyOptions,
+    preconditions: ApplyPreconditions,
+    progress: &dyn Fn(&Progress<'_>),
+) -> anyhow::Result<ExecuteOutcome> {
     let mut current = before.clone();
+    // Hash of each file's current contents, once an op has changed it.
+    let mut current_sha256: BTreeMap<Utf8PathBuf, String> = BTreeMap::new();
+    // Parsed TOML of each file, once a TOML op has touched it.
+    let mut docs: BTreeMap<Utf8PathBuf, DocumentMut> = BTreeMap::new();
     let mut results: Vec<ApplyResult> = Vec::new();
     let mut summary = ApplySummary::default();
 
-    for resolved in &resolved_ops {
+    let total = resolved_ops.len();
+    for (i, resolved) in resolved_ops.iter().enumerate() {
         let op = resolved.op;
 
-        if !resolved.allowed {
-            let mut res = ApplyResult {
+        // Ops run in memory, so stopping here leaves every earlier op
+        // complete and this one untouched.
+        if opts.cancel.is_cancelled() {
+            let reason = messages::english(codes::BLOCKED_CANCELLED, &[]);
+            results.push(ApplyResult {
                 op_id: op.id.clone(),
-                status: ApplyStatus::Blocked,
-                message: None,
-                blocked_reason: resolved.blocked_reason.clone(),
-                blocked_reason_token: resolved.blocked_reason_token.clone(),
+                status: ApplyStatus::Skipped,
+                message: Some(reason.clone()),
+                blocked_reason: Some(reason),
+                blocked_reason_token: Some(BuildfixErrorCode::Cancelled),
                 files: vec![],
-            };
-            if let Some(msg) = &resolved.blocked_message {
-                res.message = Some(msg.clone());
-            }
-            summary.blocked += 1;
-            results.push(res);
+                duration_ms: None,
+                attempts: None,
+                verified: None,
+            });
             continue;
         }
 
-        summary.attempted += 1;
+        'op: {
+            if resolved.is_filtered() {
+                results.push(ApplyResult {
+                    op_id: op.id.clone(),
+                    status: ApplyStatus::Skipped,
+                    message: resolved.blocked_reason.clone(),
+                    blocked_reason: resolved.blocked_reason.clone(),
+                    blocked_reason_token: resolved.blocked_reason_token,
+                    files: vec![],
+                    duration_ms: None,
+                    attempts: None,
+                    verified: None,
+                });
+                break 'op;
+            }
+
+            if !resolved.allowed {
+                let mut res = ApplyResult {
+                    op_id: op.id.clone(),
+                    status: ApplyStatus::Blocked,
+                    message: None,
+                    blocked_reason: resolved.blocked_reason.clone(),
+                    blocked_reason_token: resolved.blocked_reason_token,
+                    files: vec![],
+                    duration_ms: None,
+                    attempts: None,
+                    verified: None,
+                };
+                if let Some(msg) = &resolved.blocked_message {
+                    res.message = Some(msg.clone());
+                }
+                summary.blocked += 1;
+                results.push(res);
+                break 'op;
+            }
 
-        let file = Utf8PathBuf::from(&op.target.path);
-        let old = current.get(&file).cloned().unwrap_or_default();
+            let file = Utf8PathBuf::from(&op.target.path);
+
+            if let Some(expected) = &op.target.sha256 {
+                let actual = before_sha256(&file);
+                if &actual != expected {
+                    summary.blocked += 1;
+                    results.push(ApplyResult {
+                        op_id: op.id.clone(),
+                        status: ApplyStatus::Blocked,
+                        message: Some(messages::english(
+                            codes::APPLY_TARGET_CHANGED,
+                            &[
+                                ("path", &op.target.path),
+                                ("expected", expected),
+                                ("actual", &actual),
+                            ],
+                        )),
+                        blocked_reason: Some(messages::english(codes::BLOCKED_TARGET_DRIFTED, &[])),
+                        blocked_reason_token: Some(BuildfixErrorCode::TargetDrifted),
+                        files: vec![],
+                        duration_ms: None,
+                        attempts: None,
+                        verified: None,
+                    });
+                    break 'op;
+                }
+            }
 
-        let new = apply_op_to_content(&old, &resolved.kind)
-            .with_context(|| format!("apply op {} to {}", op.id, op.target.path))?;
+            summary.attempted += 1;
+            let started = opts.clock.now();
 
-        current.insert(file.clone(), new.clone());
+            let old = current.get(&file).cloned().unwrap_or_default();
 
-        let mut files = Vec::new();
-        if old != new {
-            files.push(ApplyFile {
-                path: op.target.path.clone(),
-                sha256_before: Some(sha256_hex(old.as_bytes())),
-                sha256_after: Some(sha256_hex(new.as_bytes())),
-                backup_path: None,
-            });
-        }
+            let new = if is_toml_op(&resolved.kind) {
+                // TOML ops on a file share one parsed document, so each op
+                // edits the previous op's tree instead of a reparse of it.
+                let doc = docs.entry(file.clone()).or_insert_with(|| {
+                    old.parse::<DocumentMut>()
+                        .unwrap_or_else(|_| DocumentMut::new())
+                });
+                apply_toml_op(doc, &resolved.kind).map(|()| doc.to_string())
+            } else {
+                docs.remove(&file);
+                apply_op_to_content(&old, &resolved.kind)
+            }
+            .with_context(|| format!("apply op {} to {}", op.id, op.target.path))?;
 
-        if opts.dry_run {
-            results.push(ApplyResult {
-                op_id: op.id.clone(),
-                status: ApplyStatus::Skipped,
-                message: Some("dry-run: not written".to_string()),
-                blocked_reason: None,
-                blocked_reason_token: None,
-                files,
-            });
-        } else {
-            summary.applied += 1;
-            results.push(ApplyResult {
-                op_id: op.id.clone(),
-                status: ApplyStatus::Applied,
-                message: None,
-                blocked_reason: None,
-                blocked_reason_token: None,
-                files,
-            });
+            let mut files = Vec::new();
+            if old != new {
+                // A file created by this run has no prior hash.
+                let sha_before = current_sha256
+                    .get(&file)
+                    .cloned()
+                    .or_else(|| before.contains_key(&file).then(|| before_sha256(&file)));
+                let sha_after = sha256_hex(new.as_bytes());
+                current_sha256.insert(file.clone(), sha_after.clone());
+                current.insert(file.clone(), new);
+                files.push(ApplyFile {
+                    path: op.target.path.clone(),
+                    sha256_before: sha_before,
+                    sha256_after: Some(sha_after),
+                    backup_path: None,
+                });
+            }
+
+            let duration_ms = Some(millis(started, opts.clock.now()));
+            if opts.dry_run {
+                results.push(ApplyResult {
+                    op_id: op.id.clone(),
+                    status: ApplyStatus::Skipped,
+                    message: Some(messages::english(codes::APPLY_DRY_RUN, &[])),
+                    blocked_reason: None,
+                    blocked_reason_token: None,
+                    files,
+                    duration_ms,
+                    attempts: None,
+                    verified: None,
+                });
+            } else {
+                summary.applied += 1;
+                results.push(ApplyResult {
+                    op_id: op.id.clone(),
+                    status: ApplyStatus::Applied,
+                    message: None,
+                    blocked_reason: None,
+                    blocked_reason_token: None,
+                    files,
+                    duration_ms,
+                    attempts: None,
+                    verified: None,
+                });
+            }
         }
+        progress(&Progress::Op {
+            op_id: &op.id,
+            done: i + 1,
+            total,
+        });
     }
 
     summary.files_modified = changed_files(&before, &current).len() as u64;
@@ -322,11 +598,31 @@ struct ResolvedOp<'a> {
     kind: OpKind,
     allowed: bool,
     blocked_reason: Option<String>,
-    blocked_reason_token: Option<String>,
+    blocked_reason_token: Option<BuildfixErrorCode>,
     blocked_message: Option<String>,
 }
 
+impl ResolvedOp<'_> {
+    /// Left out by [`ApplyOptions::selected_ops`] rather than blocked.
+    fn is_filtered(&self) -> bool {
+        self.blocked_reason_token == Some(BuildfixErrorCode::UserFiltered)
+    }
+}
+
 fn resolve_op<'a>(op: &'a PlanOp, opts: &ApplyOptions) -> ResolvedOp<'a> {
+    if let Some(selected) = &opts.selected_ops
+        && !selected.contains(&op.id)
+    {
+        return ResolvedOp {
+            op,
+            kind: op.kind.clone(),
+            allowed: false,
+            blocked_reason: Some(messages::english(codes::BLOCKED_USER_FILTERED, &[])),
+            blocked_reason_token: Some(BuildfixErrorCode::UserFiltered),
+            blocked_message: None,
+        };
+    }
+
     if op.blocked {
         if !op.params_required.is_empty() {
             let (kind, missing) = resolve_params(op, &opts.params);
@@ -340,16 +636,18 @@ fn resolve_op<'a>(op: &'a PlanOp, opts: &ApplyOptions) -> ResolvedOp<'a> {
                     blocked_message: None,
                 };
             }
-            let blocked_reason = op
-                .blocked_reason
-                .clone()
-                .or(Some("missing params".to_string()));
+            let blocked_reason = op.blocked_reason.clone().or_else(|| {
+                Some(messages::english(
+                    codes::BLOCKED_MISSING_PARAMS,
+                    &[("params", &missing.join(", "))],
+                ))
+            });
             return ResolvedOp {
                 op,
                 kind: op.kind.clone(),
                 allowed: false,
                 blocked_reason,
-                blocked_reason_token: op.blocked_reason_token.clone(),
+                blocked_reason_token: op.blocked_reason_token,
                 blocked_message: None,
             };
         }
@@ -360,25 +658,32 @@ fn resolve_op<'a>(op: &'a PlanOp, opts: &ApplyOptions) -> ResolvedOp<'a> {
             kind: op.kind.clone(),
             allowed: false,
             blocked_reason,
-            blocked_reason_token: op.blocked_reason_token.clone(),
+            blocked_reason_token: op.blocked_reason_token,
             blocked_message: None,
         };
     }
 
     if !allowed_by_safety(opts, op.safety) {
-        use buildfix_types::plan::blocked_tokens;
-        let token = match op.safety {
-            SafetyClass::Guarded => blocked_tokens::SAFETY_GUARDED_NOT_ALLOWED,
-            SafetyClass::Unsafe => blocked_tokens::SAFETY_UNSAFE_NOT_ALLOWED,
-            _ => blocked_tokens::SAFETY_GUARDED_NOT_ALLOWED,
+        let (token, code) = match op.safety {
+            SafetyClass::Unsafe => (
+                BuildfixErrorCode::SafetyUnsafeNotAllowed,
+                codes::BLOCKED_SAFETY_UNSAFE_NOT_ALLOWED,
+            ),
+            _ => (
+                BuildfixErrorCode::SafetyGuardedNotAllowed,
+                codes::BLOCKED_SAFETY_GUARDED_NOT_ALLOWED,
+            ),
         };
         return ResolvedOp {
             op,
             kind: op.kind.clone(),
             allowed: false,
-            blocked_reason: Some("safety gate".to_string()),
-            blocked_reason_token: Some(token.to_string()),
-            blocked_message: Some("safety class not allowed".to_string()),
+            blocked_reason: Some(messages::english(code, &[])),
+            blocked_reason_token: Some(token),
+            blocked_message: Some(messages::english(
+                codes::APPLY_SAFETY_CLASS_NOT_ALLOWED,
+                &[],
+            )),
         };
     }
 
@@ -388,10 +693,11 @@ fn resolve_op<'a>(op: &'a PlanOp, opts: &ApplyOptions) -> ResolvedOp<'a> {
             op,
             kind,
             allowed: false,
-            blocked_reason: Some(format!("missing params: {}", missing.join(", "))),
-            blocked_reason_token: Some(
-                buildfix_types::plan::blocked_tokens::MISSING_PARAMS.to_string(),
-            ),
+            blocked_reason: Some(messages::english(
+                codes::BLOCKED_MISSING_PARAMS,
+                &[("params", &missing.join(", "))],
+            )),
+            blocked_reason_token: Some(BuildfixErrorCode::MissingParams),
             blocked_message: None,
         };
     }
@@ -466,37 +772,50 @@ fn fill_op_param(kind: &mut OpKind, key: &str, value: &str) {
 }
 
 fn check_preconditions(
-    repo_root: &Utf8Path,
-    plan: &BuildfixPlan,
+    snapshot: &mut FileSnapshot,
+    expected: &PlanPreconditions,
     touched_files: &BTreeSet<Utf8PathBuf>,
     preconditions: &mut ApplyPreconditions,
 ) -> anyhow::Result<bool> {
-    let file_map = plan
-        .preconditions
+    let file_map = expected
         .files
         .iter()
-        .map(|f| (f.path.clone(), f.sha256.clone()))
+        .map(|f| (f.path.as_str(), f))
         .collect::<BTreeMap<_, _>>();
 
     for file in touched_files {
-        let Some(expected) = file_map.get(&file.to_string()) else {
+        let Some(planned) = file_map.get(file.as_str()) else {
             continue;
         };
-        let abs = abs_path(repo_root, file);
-        let bytes = fs::read(&abs).with_context(|| format!("read {}", abs))?;
-        let actual = sha256_hex(&bytes);
-        if &actual != expected {
+        let actual = snapshot.sha256(file)?.to_string();
+        if actual == planned.sha256 {
+            continue;
+        }
+        let mismatch = PreconditionMismatch {
+            path: file.to_string(),
+            expected: planned.sha256.clone(),
+            actual,
+        };
+        // In semantic mode a file whose parsed TOML is unchanged (only
+        // formatting or comments moved) still matches.
+        let soft = expected.mode == PreconditionMode::Semantic
+            && planned.semantic_sha256.is_some()
+            && snapshot
+                .existing(file)
+                .ok()
+                .flatten()
+                .and_then(|text| semantic::semantic_sha256(file, text))
+                == planned.semantic_sha256;
+        if soft {
+            preconditions.soft_matches.push(mismatch);
+        } else {
             preconditions.verified = false;
-            preconditions.mismatches.push(PreconditionMismatch {
-                path: file.to_string(),
-                expected: expected.clone(),
-                actual,
-            });
+            preconditions.mismatches.push(mismatch);
         }
     }
 
-    if let Some(expected) = &plan.preconditions.head_sha
-        && let Ok(actual) = get_head_sha(repo_root)
+    if let Some(expected) = &expected.head_sha
+        && let Ok(actual) = get_head_sha(snapshot.root())
         && &actual != expected
     {
         preconditions.verified = false;
@@ -510,81 +829,173 @@ fn check_preconditions(
     Ok(preconditions.verified)
 }
 
+/// Files that differ between `before` and `after`, including files that
+/// exist only in `after` (created by the run).
 fn changed_files(
     before: &BTreeMap<Utf8PathBuf, String>,
     after: &BTreeMap<Utf8PathBuf, String>,
 ) -> BTreeSet<Utf8PathBuf> {
-    let mut changed = BTreeSet::new();
-    for (path, old) in before {
-        let new = after.get(path).unwrap_or(old);
-        if old != new {
-            changed.insert(path.clone());
-        }
-    }
-    changed
+    before
+        .keys()
+        .chain(after.keys())
+        .filter(|path| before.get(*path) != after.get(*path))
+        .cloned()
+        .collect()
 }
 
-fn create_backups(
-    _repo_root: &Utf8Path,
+/// Write each changed file, returning the attempts each write took.
+fn write_changed_files(
+    repo_root: &Utf8Path,
     changed_files: &BTreeSet<Utf8PathBuf>,
-    before: &BTreeMap<Utf8PathBuf, String>,
-    opts: &ApplyOptions,
-    results: &mut [ApplyResult],
-) -> anyhow::Result<()> {
-    let Some(ref backup_dir) = opts.backup_dir else {
-        return Ok(());
-    };
-
+    after: &BTreeMap<Utf8PathBuf, String>,
+    durable: bool,
+    ids: &Ids,
+) -> anyhow::Result<BTreeMap<Utf8PathBuf, u32>> {
+    let mut attempts = BTreeMap::new();
     for path in changed_files {
-        let contents = before.get(path).cloned().unwrap_or_default();
-        let backup_rel = format!("{}{}", path, opts.backup_suffix);
-        let backup_path = backup_dir.join(backup_rel);
-
-        if let Some(parent) = backup_path.parent() {
-            fs::create_dir_all(parent).with_context(|| format!("create backup dir {}", parent))?;
+        let abs = abs_path(repo_root, path);
+        if let Some(parent) = abs.parent()
+            && !parent.exists()
+        {
+            fs::create_dir_all(parent).with_context(|| format!("create dir {}", parent))?;
         }
-
-        fs::write(&backup_path, &contents)
-            .with_context(|| format!("write backup {}", backup_path))?;
-
-        // Update any result entries that mention this file.
-        for result in results.iter_mut() {
-            for file in &mut result.files {
-                if file.path == *path {
-                    file.backup_path = Some(backup_path.to_string());
+        let new_contents = after.get(path).cloned().unwrap_or_default();
+        let mut attempt = 1;
+        loop {
+            match write_atomic(&abs, &new_contents, durable, ids) {
+                Ok(()) => break,
+                Err(err) if attempt < WRITE_ATTEMPTS && is_transient(&err) => {
+                    std::thread::sleep(WRITE_RETRY_DELAY * attempt);
+                    attempt += 1;
                 }
+                Err(err) => return Err(err),
             }
         }
+        attempts.insert(path.clone(), attempt);
     }
+    Ok(attempts)
+}
 
-    Ok(())
+/// Tries per file before a transient write error fails the apply.
+const WRITE_ATTEMPTS: u32 = 3;
+
+/// Delay before the first retry; later retries wait proportionally longer.
+const WRITE_RETRY_DELAY: Duration = Duration::from_millis(20);
+
+/// Errors worth retrying: interrupted or contended I/O, and on Windows a
+/// file briefly held open by another process (indexers, virus scanners).
+fn is_transient(err: &anyhow::Error) -> bool {
+    err.chain()
+        .filter_map(|e| e.downcast_ref::<io::Error>())
+        .any(|e| match e.kind() {
+            io::ErrorKind::Interrupted
+            | io::ErrorKind::WouldBlock
+            | io::ErrorKind::ResourceBusy => true,
+            io::ErrorKind::PermissionDenied => cfg!(windows),
+            _ => false,
+        })
 }
 
-fn write_changed_files(
+/// Stamp each result that changed files with the attempts its writes took
+/// and whether the files read back with the contents written.
+fn record_writes(
     repo_root: &Utf8Path,
-    changed_files: &BTreeSet<Utf8PathBuf>,
     after: &BTreeMap<Utf8PathBuf, String>,
-) -> anyhow::Result<()> {
-    for path in changed_files {
-        let abs = abs_path(repo_root, path);
-        let new_contents = after.get(path).cloned().unwrap_or_default();
-        write_atomic(&abs, &new_contents)?;
+    attempts: &BTreeMap<Utf8PathBuf, u32>,
+    results: &mut [ApplyResult],
+) {
+    let verified: BTreeMap<&Utf8PathBuf, bool> = attempts
+        .keys()
+        .map(|path| {
+            let on_disk = fs::read_to_string(abs_path(repo_root, path)).ok();
+            (
+                path,
+                on_disk.as_deref() == after.get(path).map(String::as_str),
+            )
+        })
+        .collect();
+    for result in results {
+        let written: Vec<&Utf8PathBuf> = result
+            .files
+            .iter()
+            .filter_map(|f| attempts.get_key_value(Utf8Path::new(&f.path)))
+            .map(|(path, _)| path)
+            .collect();
+        if written.is_empty() {
+            continue;
+        }
+        result.attempts = written.iter().map(|p| attempts[*p]).max();
+        result.verified = Some(written.iter().all(|p| verified[*p]));
     }
-    Ok(())
 }
 
-fn write_atomic(path: &Utf8Path, contents: &str) -> anyhow::Result<()> {
-    let parent = path.parent().unwrap_or_else(|| Utf8Path::new("."));
-    let tmp_name = format!(
-        ".buildfix-tmp-{}",
-        Uuid::new_v4().to_string().replace('-', "")
-    );
+/// Whole milliseconds from `from` to `to`; zero if the clock went backwards.
+fn millis(from: DateTime<Utc>, to: DateTime<Utc>) -> u64 {
+    u64::try_from((to - from).num_milliseconds()).unwrap_or(0)
+}
+
+/// Replace `path` with `contents` atomically.
+///
+/// The temp file lives next to the destination so the final rename never
+/// crosses a filesystem, and it replaces the destination in one step; readers
+/// see either the old or the new contents, never a missing file. An existing
+/// file's permissions carry over. With `durable`, the file is fsynced before
+/// the rename and the parent directory after it.
+fn write_atomic(path: &Utf8Path, contents: &str, durable: bool, ids: &Ids) -> anyhow::Result<()> {
+    let parent = match path.parent() {
+        Some(p) if !p.as_str().is_empty() => p,
+        _ => Utf8Path::new("."),
+    };
+    let tmp_name = format!(".buildfix-tmp-{}", ids.new_id().simple());
     let tmp_path = parent.join(tmp_name);
-    fs::write(&tmp_path, contents).with_context(|| format!("write {}", tmp_path))?;
-    if path.exists() {
-        let _ = fs::remove_file(path);
+    let permissions = fs::metadata(path).ok().map(|m| m.permissions());
+
+    let written = write_temp(&tmp_path, contents, permissions, durable).and_then(|()| {
+        fs::rename(&tmp_path, path).with_context(|| format!("rename {} -> {}", tmp_path, path))
+    });
+    if let Err(err) = written {
+        let _ = fs::remove_file(&tmp_path);
+        return Err(err);
+    }
+
+    if durable {
+        sync_dir(parent)?;
+    }
+    Ok(())
+}
+
+fn write_temp(
+    tmp_path: &Utf8Path,
+    contents: &str,
+    permissions: Option<std::fs::Permissions>,
+    durable: bool,
+) -> anyhow::Result<()> {
+    let mut file = fs::File::create(tmp_path).with_context(|| format!("create {}", tmp_path))?;
+    file.write_all(contents.as_bytes())
+        .with_context(|| format!("write {}", tmp_path))?;
+    if let Some(permissions) = permissions {
+        file.set_permissions(permissions)
+            .with_context(|| format!("set permissions on {}", tmp_path))?;
+    }
+    if durable {
+        file.sync_all()
+            .with_context(|| format!("fsync {}", tmp_path))?;
     }
-    fs::rename(&tmp_path, path).with_context(|| format!("rename {} -> {}", tmp_path, path))?;
+    Ok(())
+}
+
+/// fsync a directory so a rename inside it is persisted.
+#[cfg(unix)]
+fn sync_dir(dir: &Utf8Path) -> anyhow::Result<()> {
+    fs::File::open(dir)
+        .and_then(|d| d.sync_all())
+        .with_context(|| format!("fsync {}", dir))
+}
+
+/// Directories cannot be opened for fsync on Windows; NTFS journals the
+/// rename itself.
+#[cfg(not(unix))]
+fn sync_dir(_dir: &Utf8Path) -> anyhow::Result<()> {
     Ok(())
 }
 
@@ -607,27 +1018,33 @@ fn abs_path(repo_root: &Utf8Path, rel: &Utf8Path) -> Utf8PathBuf {
 fn render_patch(
     before: &BTreeMap<Utf8PathBuf, String>,
     after: &BTreeMap<Utf8PathBuf, String>,
+    opts: &ApplyOptions,
 ) -> String {
-    let mut out = String::new();
-    let formatter = PatchFormatter::new();
-
-    for (path, old) in before {
-        let new = after.get(path).unwrap_or(old);
-        if old == new {
-            continue;
-        }
-
-        out.push_str(&format!("diff --git a/{0} b/{0}\n", path));
-        out.push_str(&format!("--- a/{0}\n+++ b/{0}\n", path));
-
-        let patch = diffy::create_patch(old, new);
-        out.push_str(&formatter.fmt_patch(&patch).to_string());
-        if !out.ends_with('\n') {
-            out.push('\n');
-        }
-    }
+    let changes: Vec<FileChange<'_>> = changed_files(before, after)
+        .into_iter()
+        .filter_map(|path| {
+            let (path, new) = after.get_key_value(&path)?;
+            Some(match before.get(path) {
+                Some(old) => FileChange::Modify {
+                    path,
+                    old: old.as_bytes(),
+                    new: new.as_bytes(),
+                },
+                None => FileChange::Create {
+                    path,
+                    new: new.as_bytes(),
+                },
+            })
+        })
+        .collect();
 
-    out
+    render_file_changes(
+        &changes,
+        &PatchOptions {
+            binary: opts.binary_patch,
+            jobs: opts.jobs,
+        },
+    )
 }
 
 /// Applies a single operation to TOML content, returning the modified content.
@@ -672,12 +1089,45 @@ pub fn apply_op_to_content(contents: &str, kind: &OpKind) -> anyhow::Result<Stri
         .parse::<DocumentMut>()
         .unwrap_or_else(|_| DocumentMut::new());
 
+    apply_toml_op(&mut doc, kind)?;
+    Ok(doc.to_string())
+}
+
+/// Whether `kind` edits a TOML document, and so can run on a parsed
+/// [`DocumentMut`] via [`apply_toml_op`].
+fn is_toml_op(kind: &OpKind) -> bool {
+    matches!(
+        kind,
+        OpKind::TomlSet { .. }
+            | OpKind::TomlRemove { .. }
+            | OpKind::TomlArrayAppend { .. }
+            | OpKind::TomlMerge { .. }
+            | OpKind::TomlTransform { .. }
+    )
+}
+
+/// Apply a TOML operation to an already parsed document in place.
+fn apply_toml_op(doc: &mut DocumentMut, kind: &OpKind) -> anyhow::Result<()> {
     match kind {
-        OpKind::TomlSet { toml_path, value } => {
-            set_toml_path(&mut doc, toml_path, value.clone());
+        OpKind::TomlSet {
+            toml_path,
+            value,
+            insert,
+        } => {
+            set_toml_path(doc, toml_path, value.clone(), insert.as_ref());
         }
         OpKind::TomlRemove { toml_path } => {
-            remove_toml_path(&mut doc, toml_path);
+            remove_toml_path(doc, toml_path);
+        }
+        OpKind::TomlArrayAppend {
+            toml_path,
+            values,
+            unique,
+        } => {
+            append_toml_array(doc, toml_path, values, *unique)?;
+        }
+        OpKind::TomlMerge { toml_path, table } => {
+            merge_toml_table(doc, toml_path, table)?;
         }
         OpKind::JsonSet { .. }
         | OpKind::JsonRemove { .. }
@@ -690,7 +1140,13 @@ pub fn apply_op_to_content(contents: &str, kind: &OpKind) -> anyhow::Result<Stri
         }
         OpKind::TomlTransform { rule_id, args } => match rule_id.as_str() {
             "ensure_workspace_resolver_v2" => {
-                doc["workspace"]["resolver"] = value("2");
+                let hint = insert_hint_arg(args.as_ref())?;
+                set_toml_path(
+                    doc,
+                    &["workspace".to_string(), "resolver".to_string()],
+                    serde_json::Value::String("2".to_string()),
+                    hint.as_ref(),
+                );
             }
             "set_package_rust_version" => {
                 let rust_version = args
@@ -735,13 +1191,13 @@ pub fn apply_op_to_content(contents: &str, kind: &OpKind) -> anyhow::Result<Stri
                     .and_then(|v| v.as_str())
                     .context("missing version param")?;
 
-                let dep_item = get_dep_item_mut(&mut doc, &toml_path)
+                let dep_item = get_dep_item_mut(doc, &toml_path)
                     .context("dependency not found at toml_path")?;
 
                 if let Some(inline) = dep_item.as_inline_table_mut() {
                     let current_path = inline.get("path").and_then(|v| v.as_str());
                     if current_path != Some(dep_path) {
-                        return Ok(doc.to_string());
+                        return Ok(());
                     }
                     if inline.get("version").and_then(|v| v.as_str()).is_none() {
                         inline.insert("version", str_value(version));
@@ -752,7 +1208,7 @@ pub fn apply_op_to_content(contents: &str, kind: &OpKind) -> anyhow::Result<Stri
                         .and_then(|i| i.as_value())
                         .and_then(|v| v.as_str());
                     if current_path != Some(dep_path) {
-                        return Ok(doc.to_string());
+                        return Ok(());
                     }
                     if tbl
                         .get("version")
@@ -826,7 +1282,7 @@ pub fn apply_op_to_content(contents: &str, kind: &OpKind) -> anyhow::Result<Stri
                     }
                 }
 
-                let dep_item = get_dep_item_mut(&mut doc, &toml_path)
+                let dep_item = get_dep_item_mut(doc, &toml_path)
                     .context("dependency not found at toml_path")?;
                 *dep_item = value(inline);
             }
@@ -836,50 +1292,53 @@ pub fn apply_op_to_content(contents: &str, kind: &OpKind) -> anyhow::Result<Stri
         },
     }
 
-    Ok(doc.to_string())
+    Ok(())
 }
 
 /// Execute a plan against pre-loaded file contents (no filesystem access).
 ///
 /// Accepts a `BTreeMap<path, content>` of already-read files and applies each
-/// operation in the plan, returning the modified contents. This lets callers
-/// read files through a `RepoView` and pass them in without giving the edit
-/// engine direct filesystem access.
+/// operation in the plan. This lets callers read files through a `RepoView`
+/// and pass them in without giving the edit engine direct filesystem access.
 ///
-/// The returned map contains only files that were actually changed.
+/// The outcome carries the same per-op results, blocked reasons, and summary
+/// as a disk-based apply; use [`ExecuteOutcome::changed`] for just the modified
+/// files. File preconditions are not checked here, so `preconditions.verified`
+/// is always true.
 pub fn execute_plan_from_contents(
     before: &BTreeMap<Utf8PathBuf, String>,
     plan: &BuildfixPlan,
     opts: &ApplyOptions,
-) -> anyhow::Result<BTreeMap<Utf8PathBuf, String>> {
-    let mut current = before.clone();
-
-    for op in &plan.ops {
-        let resolved = resolve_op(op, opts);
-        if !resolved.allowed {
-            continue;
-        }
-
-        let file = Utf8PathBuf::from(&op.target.path);
-        let old = current.get(&file).cloned().unwrap_or_default();
-        let new = apply_op_to_content(&old, &resolved.kind)
-            .with_context(|| format!("apply op {} to {}", op.id, op.target.path))?;
-        current.insert(file, new);
-    }
-
-    // Return only changed files.
-    let mut changed = BTreeMap::new();
-    for (path, new_content) in &current {
-        let old_content = before.get(path).map(|s| s.as_str()).unwrap_or("");
-        if new_content != old_content {
-            changed.insert(path.clone(), new_content.clone());
-        }
-    }
-
-    Ok(changed)
+) -> anyhow::Result<ExecuteOutcome> {
+    let resolved_ops: Vec<ResolvedOp> = plan.ops.iter().map(|op| resolve_op(op, opts)).collect();
+    execute_resolved_ops(
+        before.clone(),
+        &|file| {
+            sha256_hex(
+                before
+                    .get(file)
+                    .map(String::as_str)
+                    .unwrap_or("")
+                    .as_bytes(),
+            )
+        },
+        &resolved_ops,
+        opts,
+        ApplyPreconditions {
+            verified: true,
+            mismatches: vec![],
+            soft_matches: vec![],
+        },
+        &|_| {},
+    )
 }
 
-fn set_toml_path(doc: &mut DocumentMut, toml_path: &[String], value: serde_json::Value) {
+fn set_toml_path(
+    doc: &mut DocumentMut,
+    toml_path: &[String],
+    value: serde_json::Value,
+    insert: Option<&InsertHint>,
+) {
     if toml_path.is_empty() {
         return;
     }
@@ -895,7 +1354,62 @@ fn set_toml_path(doc: &mut DocumentMut, toml_path: &[String], value: serde_json:
         current = table;
     }
     let last = &toml_path[toml_path.len() - 1];
+    let is_new = !current.contains_key(last);
     current[last] = Item::Value(json_value_to_toml(value));
+    if is_new && let Some(hint) = insert {
+        apply_insert_hint(current, last, hint);
+    }
+}
+
+/// Place a newly inserted key according to `hint` and attach its comment.
+///
+/// An `after` anchor that does not exist in the table leaves the key where
+/// toml_edit put it (at the end); the comment is still attached.
+fn apply_insert_hint(table: &mut toml_edit::Table, key: &str, hint: &InsertHint) {
+    let others: Vec<String> = table
+        .iter()
+        .map(|(k, _)| k.to_string())
+        .filter(|k| k != key)
+        .collect();
+    let target_rank = match &hint.position {
+        Some(InsertPosition::Top) => Some(-1),
+        Some(InsertPosition::After(anchor)) => others
+            .iter()
+            .position(|k| k == anchor)
+            .map(|i| 2 * i as i64 + 1),
+        None => None,
+    };
+    if let Some(target_rank) = target_rank {
+        // Existing keys keep their relative order on even ranks; the new key
+        // slots in on the odd rank just after its anchor.
+        let rank = |k: &str| {
+            if k == key {
+                target_rank
+            } else {
+                others
+                    .iter()
+                    .position(|o| o == k)
+                    .map_or(i64::MAX, |i| 2 * i as i64)
+            }
+        };
+        table.sort_values_by(|k1, _, k2, _| rank(k1.get()).cmp(&rank(k2.get())));
+    }
+
+    if let Some(comment) = &hint.comment
+        && let Some(mut k) = table.key_mut(key)
+    {
+        k.leaf_decor_mut().set_prefix(format!("# {}\n", comment));
+    }
+}
+
+/// Read an optional `insert` hint from transform args.
+fn insert_hint_arg(args: Option<&serde_json::Value>) -> anyhow::Result<Option<InsertHint>> {
+    match args.and_then(|a| a.get("insert")) {
+        Some(v) => Ok(Some(
+            serde_json::from_value(v.clone()).context("invalid insert hint")?,
+        )),
+        None => Ok(None),
+    }
 }
 
 fn remove_toml_path(doc: &mut DocumentMut, toml_path: &[String]) {
@@ -913,6 +1427,165 @@ fn remove_toml_path(doc: &mut DocumentMut, toml_path: &[String]) {
     current.remove(last);
 }
 
+/// Walk to the table-like parent of the last path segment, creating missing
+/// tables. Unlike `set_toml_path`, an existing non-table value is an error
+/// rather than being replaced.
+fn toml_parent_mut<'a>(
+    doc: &'a mut DocumentMut,
+    toml_path: &[String],
+) -> anyhow::Result<&'a mut dyn toml_edit::TableLike> {
+    let mut current: &mut dyn toml_edit::TableLike = doc.as_table_mut();
+    for (i, key) in toml_path[..toml_path.len().saturating_sub(1)]
+        .iter()
+        .enumerate()
+    {
+        if current.get(key).is_none() {
+            current.insert(key, toml_edit::table());
+        }
+        current = current
+            .get_mut(key)
+            .and_then(|item| item.as_table_like_mut())
+            .with_context(|| format!("{} is not a table", toml_path[..=i].join(".")))?;
+    }
+    Ok(current)
+}
+
+fn append_toml_array(
+    doc: &mut DocumentMut,
+    toml_path: &[String],
+    values: &[serde_json::Value],
+    unique: bool,
+) -> anyhow::Result<()> {
+    let Some(last) = toml_path.last() else {
+        anyhow::bail!("toml_array_append requires a non-empty toml_path");
+    };
+    let parent = toml_parent_mut(doc, toml_path)?;
+    if parent.get(last).is_none() {
+        parent.insert(last, Item::Value(toml_edit::Array::new().into()));
+    }
+    let array = parent
+        .get_mut(last)
+        .and_then(|item| item.as_array_mut())
+        .with_context(|| format!("{} is not an array", toml_path.join(".")))?;
+
+    for json in values {
+        let new = json_value_to_toml(json.clone());
+        if unique
+            && array
+                .iter()
+                .any(|existing| toml_values_equal(existing, &new))
+        {
+            continue;
+        }
+        push_array_value(array, new);
+    }
+    Ok(())
+}
+
+/// Push onto an array, matching the layout of the existing elements so that a
+/// one-per-line array stays one-per-line.
+fn push_array_value(array: &mut toml_edit::Array, new: toml_edit::Value) {
+    let Some(last) = array.iter_mut().last() else {
+        array.push(new);
+        return;
+    };
+    // Keep only the indentation: a prefix can also carry the comment that
+    // trailed the previous element.
+    let prefix = last
+        .decor()
+        .prefix()
+        .and_then(|p| p.as_str())
+        .and_then(|p| p.rfind('\n').map(|i| p[i..].to_string()))
+        .unwrap_or_else(|| " ".to_string());
+    // Whitespace before the closing bracket belongs to the last element when
+    // there is no trailing comma; move it to the new last element.
+    let suffix = last
+        .decor()
+        .suffix()
+        .and_then(|s| s.as_str())
+        .unwrap_or("")
+        .to_string();
+    last.decor_mut().set_suffix("");
+    array.push_formatted(new.decorated(prefix, suffix));
+}
+
+fn toml_values_equal(a: &toml_edit::Value, b: &toml_edit::Value) -> bool {
+    use toml_edit::Value as V;
+    match (a, b) {
+        (V::String(a), V::String(b)) => a.value() == b.value(),
+        (V::Integer(a), V::Integer(b)) => a.value() == b.value(),
+        (V::Float(a), V::Float(b)) => a.value() == b.value(),
+        (V::Boolean(a), V::Boolean(b)) => a.value() == b.value(),
+        _ => a.clone().decorated("", "").to_string() == b.clone().decorated("", "").to_string(),
+    }
+}
+
+fn merge_toml_table(
+    doc: &mut DocumentMut,
+    toml_path: &[String],
+    table: &serde_json::Map<String, serde_json::Value>,
+) -> anyhow::Result<()> {
+    let Some(last) = toml_path.last() else {
+        anyhow::bail!("toml_merge requires a non-empty toml_path");
+    };
+    let parent = toml_parent_mut(doc, toml_path)?;
+    if parent.get(last).is_none() {
+        parent.insert(last, Item::Value(InlineTable::new().into()));
+    }
+    let item = parent
+        .get_mut(last)
+        .with_context(|| format!("{} is not a table", toml_path.join(".")))?;
+
+    if let Some(inline) = item.as_inline_table_mut() {
+        // Whitespace before the closing brace hangs off the last value; park it
+        // while keys are added and give it back to whichever value ends up last.
+        let closing = inline
+            .iter_mut()
+            .last()
+            .map(|(_, v)| {
+                let suffix = v.decor().suffix().and_then(|s| s.as_str()).unwrap_or("");
+                let suffix = suffix.to_string();
+                v.decor_mut().set_suffix("");
+                suffix
+            })
+            .unwrap_or_default();
+        let was_empty = inline.is_empty();
+        merge_toml_keys(inline, table);
+        if was_empty {
+            inline.fmt();
+        } else if let Some((_, v)) = inline.iter_mut().last() {
+            v.decor_mut().set_suffix(closing);
+        }
+        return Ok(());
+    }
+
+    let target = item
+        .as_table_like_mut()
+        .with_context(|| format!("{} is not a table", toml_path.join(".")))?;
+    merge_toml_keys(target, table);
+    Ok(())
+}
+
+fn merge_toml_keys(
+    target: &mut dyn toml_edit::TableLike,
+    table: &serde_json::Map<String, serde_json::Value>,
+) {
+    for (key, json) in table {
+        let new = json_value_to_toml(json.clone());
+        match target.get_mut(key) {
+            Some(Item::Value(existing)) => {
+                // Keep the surrounding whitespace/comments of the replaced value.
+                let decor = existing.decor().clone();
+                *existing = new;
+                *existing.decor_mut() = decor;
+            }
+            _ => {
+                target.insert(key, Item::Value(new));
+            }
+        }
+    }
+}
+
 fn apply_text_replace_anchored(
     contents: &str,
     find: &str,
@@ -1329,22 +2002,22 @@ fn json_value_to_toml(json: serde_json::Value) -> toml_edit::Value {
         serde_json::Value::Array(arr) => {
             let mut out = toml_edit::Array::new();
             for v in arr {
-                match v {
-                    serde_json::Value::String(s) => out.push(s.as_str()),
-                    serde_json::Value::Bool(b) => out.push(b),
-                    serde_json::Value::Number(n) => {
-                        if let Some(i) = n.as_i64() {
-                            out.push(i);
-                        } else if let Some(f) = n.as_f64() {
-                            out.push(f);
-                        }
-                    }
-                    _ => {}
+                if !v.is_null() {
+                    out.push(json_value_to_toml(v));
+                }
+            }
+            toml_edit::Value::from(out)
+        }
+        serde_json::Value::Object(map) => {
+            let mut out = InlineTable::new();
+            for (k, v) in map {
+                if !v.is_null() {
+                    out.insert(&k, json_value_to_toml(v));
                 }
             }
             toml_edit::Value::from(out)
         }
-        _ => toml_edit::Value::from(""),
+        serde_json::Value::Null => toml_edit::Value::from(""),
     }
 }
 
@@ -1401,12 +2074,11 @@ pub fn check_policy_block(apply: &BuildfixApply, was_dry_run: bool) -> Option<Po
 
     // Only block if NO ops were applied successfully
     if !blocked.is_empty() && apply.summary.applied == 0 {
-        let reasons: Vec<String> = blocked
+        if blocked
             .iter()
-            .filter_map(|r| r.blocked_reason.clone())
-            .collect();
-
-        if reasons.iter().any(|r| r.contains("safety")) {
+            .filter_map(|r| r.blocked_reason_token)
+            .any(|code| code.is_safety_gate())
+        {
             return Some(PolicyBlockError::SafetyGateDenial {
                 message: format!("{} op(s) blocked by safety gate", blocked.len()),
             });
@@ -0,0 +1,361 @@
+//! Advisory lock that serializes concurrent applies against one workspace.
+//!
+//! The lock is a small JSON file (typically `artifacts/buildfix/.lock`) created
+//! with `create_new`, so only one process can hold it at a time. A lock older
+//! than the stale threshold is assumed to belong to a crashed run and is reclaimed.
+//!
+//! Reclaiming and releasing both move the file aside to a per-holder tombstone
+//! before deciding to delete it, so a process can only ever remove the lock file
+//! it inspected and never one another process created in the meantime.
+
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use serde::{Deserialize, Serialize};
+use std::io::{ErrorKind, Write};
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use thiserror::Error;
+
+/// Locks older than this are considered abandoned and may be reclaimed.
+pub const DEFAULT_LOCK_STALE_AFTER: Duration = Duration::from_secs(15 * 60);
+
+/// Contents written to the lock file, used for diagnostics and stale detection.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LockInfo {
+    pub pid: u32,
+    /// Seconds since the unix epoch when the lock was acquired.
+    pub acquired_at: u64,
+    /// Per-acquisition token, so a holder only ever releases its own lock file.
+    /// Empty for lock files written before the field existed.
+    #[serde(default)]
+    pub nonce: String,
+}
+
+/// Errors returned when the apply lock cannot be acquired.
+#[derive(Debug, Error)]
+pub enum LockError {
+    /// Another live process holds the lock.
+    #[error("apply lock {path} is held by pid {pid} (acquired {age_secs}s ago)")]
+    Held {
+        path: Utf8PathBuf,
+        pid: u32,
+        age_secs: u64,
+    },
+
+    /// The lock file could not be created, read, or removed.
+    #[error("apply lock {path}: {message}")]
+    Io { path: Utf8PathBuf, message: String },
+}
+
+/// Guard for an acquired apply lock. The lock file is removed on drop.
+#[derive(Debug)]
+pub struct ApplyLock {
+    path: Utf8PathBuf,
+    nonce: String,
+}
+
+impl ApplyLock {
+    /// Acquire the lock at `path`, reclaiming it if the existing holder is older
+    /// than `stale_after`.
+    pub fn acquire(path: &Utf8Path, stale_after: Duration) -> Result<Self, LockError> {
+        if let Some(parent) = path.parent()
+            && !parent.as_str().is_empty()
+        {
+            fs::create_dir_all(parent).map_err(|e| io_error(path, e))?;
+        }
+
+        let nonce = new_nonce();
+
+        // One retry after reclaiming a stale lock; a second collision means
+        // another process won the race and now legitimately holds it.
+        for _ in 0..2 {
+            match try_create(path, &nonce) {
+                Ok(()) => {
+                    return Ok(Self {
+                        path: path.to_path_buf(),
+                        nonce,
+                    });
+                }
+                Err(e) if e.kind() == ErrorKind::AlreadyExists => {
+                    let (pid, age) = read_holder(path)?;
+                    if age < stale_after {
+                        return Err(LockError::Held {
+                            path: path.to_path_buf(),
+                            pid,
+                            age_secs: age.as_secs(),
+                        });
+                    }
+                    tracing::warn!(
+                        event = buildfix_types::telemetry::APPLY_LOCK_RECLAIMED,
+                        file = path.as_str(),
+                        pid,
+                        age_secs = age.as_secs(),
+                        "reclaiming stale apply lock"
+                    );
+                    // Staleness is re-checked on the tombstone: if another process
+                    // reclaimed the lock first, the file we moved aside is its fresh
+                    // lock and goes straight back.
+                    take_if(path, &format!("stale-{nonce}"), |tombstone| {
+                        read_holder(tombstone).is_ok_and(|(_, age)| age >= stale_after)
+                    })
+                    .map_err(|e| io_error(path, e))?;
+                }
+                Err(e) => return Err(io_error(path, e)),
+            }
+        }
+
+        let (pid, age) = read_holder(path)?;
+        Err(LockError::Held {
+            path: path.to_path_buf(),
+            pid,
+            age_secs: age.as_secs(),
+        })
+    }
+
+    /// Path of the lock file.
+    pub fn path(&self) -> &Utf8Path {
+        &self.path
+    }
+}
+
+impl Drop for ApplyLock {
+    fn drop(&mut self) {
+        // Only release the file if it is still ours; a stale-lock reclaim by
+        // another process may have replaced it while we were running.
+        let _ = take_if(
+            &self.path,
+            &format!("release-{}", self.nonce),
+            |tombstone| {
+                std::fs::read_to_string(tombstone)
+                    .ok()
+                    .and_then(|c| serde_json::from_str::<LockInfo>(&c).ok())
+                    .is_some_and(|info| info.pid == std::process::id() && info.nonce == self.nonce)
+            },
+        );
+    }
+}
+
+/// Outcome of [`take_if`].
+#[derive(Debug, PartialEq, Eq)]
+enum Taken {
+    /// The file matched and was deleted.
+    Removed,
+    /// The file did not match and was put back.
+    Restored,
+    /// There was no file to take.
+    Gone,
+}
+
+/// Atomically moves `path` to a tombstone named after `tag`, then deletes it if
+/// `matches` accepts the tombstone and otherwise restores it.
+///
+/// The rename guarantees that the file `matches` inspects is the one deleted.
+/// Restoring uses a hard link, which never overwrites a lock created at `path`
+/// in the meantime.
+fn take_if(
+    path: &Utf8Path,
+    tag: &str,
+    matches: impl FnOnce(&Utf8Path) -> bool,
+) -> std::io::Result<Taken> {
+    let tombstone = Utf8PathBuf::from(format!("{path}.{tag}"));
+    match std::fs::rename(path, &tombstone) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok(Taken::Gone),
+        Err(e) => return Err(e),
+    }
+
+    if matches(&tombstone) {
+        std::fs::remove_file(&tombstone)?;
+        return Ok(Taken::Removed);
+    }
+
+    match std::fs::hard_link(&tombstone, path) {
+        Ok(()) => {}
+        Err(e) if e.kind() == ErrorKind::AlreadyExists => {}
+        Err(e) => return Err(e),
+    }
+    std::fs::remove_file(&tombstone)?;
+    Ok(Taken::Restored)
+}
+
+fn try_create(path: &Utf8Path, nonce: &str) -> std::io::Result<()> {
+    let mut file = std::fs::OpenOptions::new()
+        .write(true)
+        .create_new(true)
+        .open(path)?;
+    let info = LockInfo {
+        pid: std::process::id(),
+        acquired_at: unix_now(),
+        nonce: nonce.to_string(),
+    };
+    let json = serde_json::to_vec(&info).map_err(std::io::Error::other)?;
+    file.write_all(&json)?;
+    file.sync_all()
+}
+
+/// Returns the holder pid (0 if unknown) and the lock's age.
+///
+/// Falls back to the file mtime when the contents are unreadable, e.g. when the
+/// holder crashed between creating and writing the file.
+fn read_holder(path: &Utf8Path) -> Result<(u32, Duration), LockError> {
+    let contents = match fs::read_to_string(path) {
+        Ok(c) => c,
+        // Released between our create attempt and this read; treat as stale.
+        Err(e) if e.kind() == ErrorKind::NotFound => return Ok((0, Duration::MAX)),
+        Err(e) => return Err(io_error(path, e)),
+    };
+
+    if let Ok(info) = serde_json::from_str::<LockInfo>(&contents) {
+        let age = unix_now().saturating_sub(info.acquired_at);
+        return Ok((info.pid, Duration::from_secs(age)));
+    }
+
+    let age = fs::metadata(path)
+        .and_then(|m| m.modified())
+        .ok()
+        .and_then(|t| SystemTime::now().duration_since(t).ok())
+        .unwrap_or(Duration::MAX);
+    Ok((0, age))
+}
+
+/// Token unique to one acquisition: pid, wall-clock nanos and an in-process
+/// counter, so two locks taken by the same process never share it.
+fn new_nonce() -> String {
+    static COUNTER: AtomicU64 = AtomicU64::new(0);
+    let nanos = SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_nanos())
+        .unwrap_or(0);
+    format!(
+        "{}-{nanos:x}-{}",
+        std::process::id(),
+        COUNTER.fetch_add(1, Ordering::Relaxed)
+    )
+}
+
+fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map(|d| d.as_secs())
+        .unwrap_or(0)
+}
+
+fn io_error(path: &Utf8Path, e: std::io::Error) -> LockError {
+    LockError::Io {
+        path: path.to_path_buf(),
+        message: e.to_string(),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use tempfile::TempDir;
+
+    fn lock_path(temp: &TempDir) -> Utf8PathBuf {
+        Utf8PathBuf::from_path_buf(temp.path().join("artifacts/buildfix/.lock")).expect("utf8")
+    }
+
+    #[test]
+    fn acquire_creates_and_drop_removes_lock_file() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = lock_path(&temp);
+
+        let lock = ApplyLock::acquire(&path, DEFAULT_LOCK_STALE_AFTER).expect("acquire");
+        assert!(path.exists());
+        assert_eq!(lock.path(), path);
+
+        drop(lock);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn second_acquire_reports_holder() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = lock_path(&temp);
+
+        let _held = ApplyLock::acquire(&path, DEFAULT_LOCK_STALE_AFTER).expect("acquire");
+        let err = ApplyLock::acquire(&path, DEFAULT_LOCK_STALE_AFTER).expect_err("contended");
+        match err {
+            LockError::Held { pid, .. } => assert_eq!(pid, std::process::id()),
+            other => panic!("expected Held, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn stale_lock_is_reclaimed() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = lock_path(&temp);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("mkdir");
+        let stale = LockInfo {
+            pid: 999_999,
+            acquired_at: unix_now() - 3600,
+            nonce: String::new(),
+        };
+        std::fs::write(&path, serde_json::to_vec(&stale).unwrap()).expect("write");
+
+        let lock = ApplyLock::acquire(&path, Duration::from_secs(60)).expect("reclaim");
+        let info: LockInfo =
+            serde_json::from_str(&std::fs::read_to_string(lock.path()).unwrap()).unwrap();
+        assert_eq!(info.pid, std::process::id());
+        assert_eq!(info.nonce, lock.nonce);
+        assert_eq!(lock_dir_entries(&path), vec![".lock".to_string()]);
+    }
+
+    #[test]
+    fn lock_files_without_nonce_still_parse() {
+        let info: LockInfo = serde_json::from_str(r#"{"pid":42,"acquired_at":7}"#).unwrap();
+        assert_eq!(info.pid, 42);
+        assert!(info.nonce.is_empty());
+    }
+
+    #[test]
+    fn drop_leaves_a_lock_now_held_by_someone_else() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = lock_path(&temp);
+
+        let lock = ApplyLock::acquire(&path, DEFAULT_LOCK_STALE_AFTER).expect("acquire");
+        // Simulate another process reclaiming our lock and writing its own.
+        let other = serde_json::to_vec(&LockInfo {
+            pid: std::process::id(),
+            acquired_at: unix_now(),
+            nonce: "someone-else".to_string(),
+        })
+        .unwrap();
+        std::fs::write(&path, &other).expect("overwrite");
+
+        drop(lock);
+        assert_eq!(std::fs::read(&path).expect("still held"), other);
+        assert_eq!(lock_dir_entries(&path), vec![".lock".to_string()]);
+    }
+
+    #[test]
+    fn take_if_restores_a_file_that_does_not_match() {
+        let temp = TempDir::new().expect("temp dir");
+        let path = lock_path(&temp);
+        std::fs::create_dir_all(path.parent().unwrap()).expect("mkdir");
+        std::fs::write(&path, b"fresh").expect("write");
+
+        assert_eq!(take_if(&path, "t", |_| false).unwrap(), Taken::Restored);
+        assert_eq!(std::fs::read(&path).unwrap(), b"fresh");
+        assert_eq!(lock_dir_entries(&path), vec![".lock".to_string()]);
+
+        assert_eq!(take_if(&path, "t", |_| true).unwrap(), Taken::Removed);
+        assert!(!path.exists());
+        assert_eq!(take_if(&path, "t", |_| true).unwrap(), Taken::Gone);
+    }
+
+    #[test]
+    fn nonces_are_unique_within_a_process() {
+        assert_ne!(new_nonce(), new_nonce());
+    }
+
+    fn lock_dir_entries(path: &Utf8Path) -> Vec<String> {
+        let mut names: Vec<String> = std::fs::read_dir(path.parent().unwrap())
+            .unwrap()
+            .map(|e| e.unwrap().file_name().to_string_lossy().into_owned())
+            .collect();
+        names.sort();
+        names
+    }
+}
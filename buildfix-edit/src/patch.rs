@@ -0,0 +1,376 @@
+//! Git-compatible unified diff rendering.
+//!
+//! Output is accepted by `git apply`: every file section starts with a
+//! `diff --git` header, creates/deletes carry `new file mode` / `deleted file mode`
+//! with `/dev/null` on the missing side, and renames carry `rename from` /
+//! `rename to`. With [`PatchOptions::binary`] set, sections also carry a
+//! full-index `index` line and non-text contents are emitted as a
+//! `GIT binary patch`, matching `git diff --binary --full-index`.
+
+use crate::pool;
+use buildfix_hash::git_blob_sha1;
+use camino::Utf8Path;
+use diffy::{DiffOptions, PatchFormatter};
+use flate2::Compression;
+use flate2::write::ZlibEncoder;
+use std::io::Write;
+
+/// File mode used for every header; buildfix only edits regular files.
+const FILE_MODE: &str = "100644";
+
+/// Object id git uses for the missing side of a create or delete.
+const NULL_OID: &str = "0000000000000000000000000000000000000000";
+
+/// Options controlling patch rendering.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct PatchOptions {
+    /// Emit full-index headers and `GIT binary patch` sections for non-text
+    /// contents, like `git diff --binary`. Without it, binary changes are
+    /// reported as `Binary files ... differ` and cannot be applied.
+    pub binary: bool,
+    /// Threads used to render file sections (0 = available cores, 1 = inline).
+    /// Sections are joined in input order either way.
+    pub jobs: usize,
+}
+
+/// One file-level change to render.
+#[derive(Debug, Clone, Copy)]
+pub enum FileChange<'a> {
+    Modify {
+        path: &'a Utf8Path,
+        old: &'a [u8],
+        new: &'a [u8],
+    },
+    Create {
+        path: &'a Utf8Path,
+        new: &'a [u8],
+    },
+    Delete {
+        path: &'a Utf8Path,
+        old: &'a [u8],
+    },
+    Rename {
+        from: &'a Utf8Path,
+        to: &'a Utf8Path,
+        old: &'a [u8],
+        new: &'a [u8],
+    },
+}
+
+/// Render a set of file changes as one git-compatible patch.
+///
+/// Unchanged modifications are skipped; a pure rename (identical contents)
+/// still produces a header-only section.
+pub fn render_file_changes(changes: &[FileChange<'_>], opts: &PatchOptions) -> String {
+    pool::map_init(opts.jobs, changes, Vec::new, |buf, change| {
+        let mut out = String::new();
+        render_change(&mut out, buf, change, opts);
+        out
+    })
+    .concat()
+}
+
+/// Render one section into `out`. `buf` is scratch space for diffy's
+/// formatter, reused across the files a worker renders.
+fn render_change(
+    out: &mut String,
+    buf: &mut Vec<u8>,
+    change: &FileChange<'_>,
+    opts: &PatchOptions,
+) {
+    // (old path, new path, old contents, new contents); `None` means /dev/null.
+    let (old_path, new_path, old, new) = match *change {
+        FileChange::Modify { path, old, new } => {
+            if old == new {
+                return;
+            }
+            (Some(path), Some(path), old, new)
+        }
+        FileChange::Create { path, new } => (None, Some(path), &[][..], new),
+        FileChange::Delete { path, old } => (Some(path), None, old, &[][..]),
+        FileChange::Rename { from, to, old, new } => (Some(from), Some(to), old, new),
+    };
+
+    let a = old_path.or(new_path).expect("change has a path");
+    let b = new_path.or(old_path).expect("change has a path");
+    out.push_str(&format!("diff --git a/{} b/{}\n", a, b));
+
+    match (old_path, new_path) {
+        (None, Some(_)) => out.push_str(&format!("new file mode {}\n", FILE_MODE)),
+        (Some(_), None) => out.push_str(&format!("deleted file mode {}\n", FILE_MODE)),
+        _ => {}
+    }
+    if let FileChange::Rename { from, to, .. } = *change {
+        out.push_str(&format!("rename from {}\nrename to {}\n", from, to));
+    }
+
+    if old == new {
+        // Pure rename: nothing beyond the header.
+        return;
+    }
+
+    if opts.binary {
+        let old_oid = old_path.map_or_else(|| NULL_OID.to_string(), |_| git_blob_sha1(old));
+        let new_oid = new_path.map_or_else(|| NULL_OID.to_string(), |_| git_blob_sha1(new));
+        out.push_str(&format!("index {}..{}", old_oid, new_oid));
+        if old_path.is_some() && new_path.is_some() {
+            out.push_str(&format!(" {}", FILE_MODE));
+        }
+        out.push('\n');
+    }
+
+    let original = old_path.map_or_else(|| "/dev/null".to_string(), |p| format!("a/{}", p));
+    let modified = new_path.map_or_else(|| "/dev/null".to_string(), |p| format!("b/{}", p));
+
+    match (as_text(old), as_text(new)) {
+        (Some(old), Some(new)) => {
+            let patch = DiffOptions::new()
+                .set_original_filename(original)
+                .set_modified_filename(modified)
+                .create_patch(old, new);
+            buf.clear();
+            PatchFormatter::new()
+                .write_patch_into(&patch, &mut *buf)
+                .expect("writing to a Vec cannot fail");
+            out.push_str(std::str::from_utf8(buf).expect("diff of text is text"));
+            if !out.ends_with('\n') {
+                out.push('\n');
+            }
+        }
+        _ if opts.binary => {
+            out.push_str("GIT binary patch\n");
+            push_binary_literal(out, new);
+            push_binary_literal(out, old);
+        }
+        _ => out.push_str(&format!(
+            "Binary files {} and {} differ\n",
+            original, modified
+        )),
+    }
+}
+
+/// Contents are treated as text when they are valid UTF-8 without NUL bytes,
+/// the same heuristic git uses to decide between a text and binary diff.
+fn as_text(bytes: &[u8]) -> Option<&str> {
+    if bytes.contains(&0) {
+        return None;
+    }
+    std::str::from_utf8(bytes).ok()
+}
+
+/// Append a `literal` block: zlib-deflated data in git's base85 line encoding.
+fn push_binary_literal(out: &mut String, data: &[u8]) {
+    let mut encoder = ZlibEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(data)
+        .expect("writing to an in-memory encoder cannot fail");
+    let deflated = encoder
+        .finish()
+        .expect("finishing an in-memory encoder cannot fail");
+
+    out.push_str(&format!("literal {}\n", data.len()));
+    for chunk in deflated.chunks(52) {
+        let len = chunk.len() as u8;
+        out.push(if len <= 26 {
+            (b'A' + len - 1) as char
+        } else {
+            (b'a' + len - 27) as char
+        });
+        encode_base85(out, chunk);
+        out.push('\n');
+    }
+    out.push('\n');
+}
+
+const BASE85_ALPHABET: &[u8; 85] =
+    b"0123456789ABCDEFGHIJKLMNOPQRSTUVWXYZabcdefghijklmnopqrstuvwxyz!#$%&()*+-;<=>?@^_`{|}~";
+
+fn encode_base85(out: &mut String, data: &[u8]) {
+    for group in data.chunks(4) {
+        let mut word = [0u8; 4];
+        word[..group.len()].copy_from_slice(group);
+        let mut acc = u32::from_be_bytes(word);
+        let mut encoded = [0u8; 5];
+        for slot in encoded.iter_mut().rev() {
+            *slot = BASE85_ALPHABET[(acc % 85) as usize];
+            acc /= 85;
+        }
+        out.extend(encoded.iter().map(|&c| c as char));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use camino::Utf8PathBuf;
+    use pretty_assertions::assert_eq;
+
+    fn render(change: FileChange<'_>, binary: bool) -> String {
+        render_file_changes(&[change], &PatchOptions { binary, jobs: 1 })
+    }
+
+    #[test]
+    fn modify_has_single_pair_of_file_headers() {
+        let out = render(
+            FileChange::Modify {
+                path: Utf8Path::new("Cargo.toml"),
+                old: b"a = 1\n",
+                new: b"a = 2\n",
+            },
+            false,
+        );
+        assert_eq!(
+            out,
+            "diff --git a/Cargo.toml b/Cargo.toml\n\
+             --- a/Cargo.toml\n\
+             +++ b/Cargo.toml\n\
+             @@ -1 +1 @@\n\
+             -a = 1\n\
+             +a = 2\n"
+        );
+    }
+
+    #[test]
+    fn unchanged_modify_is_skipped() {
+        let out = render(
+            FileChange::Modify {
+                path: Utf8Path::new("Cargo.toml"),
+                old: b"same\n",
+                new: b"same\n",
+            },
+            false,
+        );
+        assert!(out.is_empty());
+    }
+
+    #[test]
+    fn create_and_delete_use_dev_null() {
+        let created = render(
+            FileChange::Create {
+                path: Utf8Path::new("rust-toolchain.toml"),
+                new: b"[toolchain]\n",
+            },
+            false,
+        );
+        assert!(
+            created.contains("new file mode 100644\n--- /dev/null\n+++ b/rust-toolchain.toml\n")
+        );
+        assert!(created.contains("@@ -0,0 +1 @@\n+[toolchain]\n"));
+
+        let deleted = render(
+            FileChange::Delete {
+                path: Utf8Path::new("old.toml"),
+                old: b"x = 1\n",
+            },
+            false,
+        );
+        assert!(deleted.contains("deleted file mode 100644\n--- a/old.toml\n+++ /dev/null\n"));
+    }
+
+    #[test]
+    fn rename_emits_rename_headers() {
+        let pure = render(
+            FileChange::Rename {
+                from: Utf8Path::new("a.toml"),
+                to: Utf8Path::new("b.toml"),
+                old: b"x\n",
+                new: b"x\n",
+            },
+            false,
+        );
+        assert_eq!(
+            pure,
+            "diff --git a/a.toml b/b.toml\nrename from a.toml\nrename to b.toml\n"
+        );
+
+        let edited = render(
+            FileChange::Rename {
+                from: Utf8Path::new("a.toml"),
+                to: Utf8Path::new("b.toml"),
+                old: b"x\n",
+                new: b"y\n",
+            },
+            false,
+        );
+        assert!(edited.contains("rename to b.toml\n--- a/a.toml\n+++ b/b.toml\n"));
+    }
+
+    #[test]
+    fn binary_option_adds_full_index() {
+        let out = render(
+            FileChange::Modify {
+                path: Utf8Path::new("f.txt"),
+                old: b"",
+                new: b"hello\n",
+            },
+            true,
+        );
+        assert!(out.contains(
+            "index e69de29bb2d1d6434b8b29ae775ad8c2e48c5391..ce013625030ba8dba906f756967f9e9ca394464a 100644\n"
+        ));
+    }
+
+    #[test]
+    fn binary_contents_need_binary_option() {
+        let change = FileChange::Modify {
+            path: Utf8Path::new("blob.bin"),
+            old: b"\x00\x01",
+            new: b"\x00\x02",
+        };
+        assert!(render(change, false).contains("Binary files a/blob.bin and b/blob.bin differ\n"));
+
+        let out = render(change, true);
+        assert!(out.contains("GIT binary patch\nliteral 2\n"));
+        assert!(out.ends_with("\n\n"));
+    }
+
+    #[test]
+    fn parallel_rendering_matches_sequential() {
+        let files: Vec<(Utf8PathBuf, String, String)> = (0..50)
+            .map(|i| {
+                (
+                    Utf8PathBuf::from(format!("crates/c{i}/Cargo.toml")),
+                    format!("[package]\nname = \"c{i}\"\n"),
+                    format!("[package]\nname = \"c{i}\"\nedition = \"2021\"\n"),
+                )
+            })
+            .collect();
+        let changes: Vec<FileChange<'_>> = files
+            .iter()
+            .map(|(path, old, new)| FileChange::Modify {
+                path,
+                old: old.as_bytes(),
+                new: new.as_bytes(),
+            })
+            .collect();
+
+        let sequential = render_file_changes(
+            &changes,
+            &PatchOptions {
+                binary: false,
+                jobs: 1,
+            },
+        );
+        let parallel = render_file_changes(
+            &changes,
+            &PatchOptions {
+                binary: false,
+                jobs: 8,
+            },
+        );
+        assert_eq!(parallel, sequential);
+        assert_eq!(sequential.matches("diff --git").count(), 50);
+    }
+
+    #[test]
+    fn base85_matches_git_encoding() {
+        // Reference values from git's base85.c encoder.
+        let mut out = String::new();
+        encode_base85(&mut out, &[0, 0, 0, 0]);
+        assert_eq!(out, "00000");
+
+        let mut out = String::new();
+        encode_base85(&mut out, &[0xff, 0xff, 0xff, 0xff]);
+        assert_eq!(out, "|NsC0");
+    }
+}
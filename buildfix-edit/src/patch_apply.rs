@@ -0,0 +1,564 @@
+//! Apply a previously emitted `patch.diff` without going through git.
+//!
+//! Only the subset of the git patch format that [`render_file_changes`] emits
+//! is understood: text hunks plus `new file`, `deleted file`, and rename
+//! headers. Binary sections are rejected. The whole patch is applied in memory
+//! first, so a hunk that does not apply leaves the working tree untouched.
+//!
+//! [`render_file_changes`]: crate::render_file_changes
+
+use crate::{
+    ApplyLock, ApplyOptions, DEFAULT_LOCK_STALE_AFTER, FileSnapshot, backup, prune_backups,
+};
+use anyhow::Context;
+use buildfix_hash::sha256_hex;
+use buildfix_types::apply::{ApplyFile, ApplyPreconditions};
+use buildfix_types::plan::PlanPreconditions;
+use camino::{Utf8Component, Utf8Path, Utf8PathBuf};
+use diffy::Patch;
+use fs_err as fs;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Result of applying a patch file.
+#[derive(Debug, Clone)]
+pub struct PatchApplyOutcome {
+    /// One entry per file whose contents changed, created, or removed.
+    pub files: Vec<ApplyFile>,
+    pub preconditions: ApplyPreconditions,
+    /// Backup run directory, when files were written with backups enabled.
+    pub backup_run: Option<Utf8PathBuf>,
+}
+
+/// One `diff --git` section of a patch.
+#[derive(Debug)]
+struct FileSection<'a> {
+    /// `None` for a created file.
+    old_path: Option<Utf8PathBuf>,
+    /// `None` for a deleted file.
+    new_path: Option<Utf8PathBuf>,
+    /// Parsed hunks; `None` for header-only sections (pure rename, empty file).
+    hunks: Option<Patch<'a, str>>,
+}
+
+/// Apply `patch` to the files under `repo_root`.
+///
+/// File sha256 and git HEAD preconditions from `expected` are checked against
+/// the files the patch reads; any mismatch returns an outcome with
+/// `preconditions.verified == false` and nothing written. Backups, retention,
+/// and the apply lock follow the same [`ApplyOptions`] as `apply_plan`. With
+/// `opts.dry_run`, the patch is still fully applied in memory so a patch that
+/// would not apply is reported as an error.
+pub fn apply_patch(
+    repo_root: &Utf8Path,
+    patch: &str,
+    expected: &PlanPreconditions,
+    opts: &ApplyOptions,
+) -> anyhow::Result<PatchApplyOutcome> {
+    let _lock = match &opts.lock_path {
+        Some(path) if !opts.dry_run => Some(ApplyLock::acquire(path, DEFAULT_LOCK_STALE_AFTER)?),
+        _ => None,
+    };
+
+    let sections = parse_sections(patch)?;
+
+    let mut snapshot = FileSnapshot::new(repo_root);
+    snapshot.read(
+        sections
+            .iter()
+            .flat_map(|s| s.old_path.iter().chain(s.new_path.iter()))
+            .map(Utf8PathBuf::as_path),
+    );
+
+    // Current contents per path; `None` means the file does not exist.
+    let mut before: BTreeMap<Utf8PathBuf, Option<String>> = BTreeMap::new();
+    for section in &sections {
+        for path in section.old_path.iter().chain(section.new_path.iter()) {
+            if !before.contains_key(path) {
+                let contents = snapshot.existing(path)?.map(str::to_string);
+                before.insert(path.clone(), contents);
+            }
+        }
+    }
+
+    let mut preconditions = ApplyPreconditions {
+        verified: true,
+        mismatches: vec![],
+        soft_matches: vec![],
+    };
+    let read_files: BTreeSet<Utf8PathBuf> = before
+        .iter()
+        .filter(|(_, contents)| contents.is_some())
+        .map(|(path, _)| path.clone())
+        .collect();
+    if !super::check_preconditions(&mut snapshot, expected, &read_files, &mut preconditions)? {
+        return Ok(PatchApplyOutcome {
+            files: vec![],
+            preconditions,
+            backup_run: None,
+        });
+    }
+
+    let mut after = before.clone();
+    for section in &sections {
+        apply_section(section, &mut after)?;
+    }
+
+    let changed: BTreeSet<Utf8PathBuf> = after
+        .iter()
+        .filter(|(path, new)| before.get(*path) != Some(*new))
+        .map(|(path, _)| path.clone())
+        .collect();
+
+    let mut files: Vec<ApplyFile> = changed
+        .iter()
+        .map(|path| ApplyFile {
+            path: path.to_string(),
+            sha256_before: before[path].as_ref().map(|_| snapshot.text_sha256(path)),
+            sha256_after: after[path].as_ref().map(|c| sha256_hex(c.as_bytes())),
+            backup_path: None,
+        })
+        .collect();
+
+    let mut backup_run = None;
+    if !opts.dry_run && !changed.is_empty() {
+        let backup_dir = opts.backup_dir.as_ref().filter(|_| opts.backup_enabled);
+        if let Some(backup_dir) = backup_dir {
+            // Only files that existed can be backed up.
+            let existing: BTreeSet<Utf8PathBuf> = changed
+                .iter()
+                .filter(|path| before[*path].is_some())
+                .cloned()
+                .collect();
+            if !existing.is_empty() {
+                let originals: BTreeMap<Utf8PathBuf, String> = existing
+                    .iter()
+                    .map(|path| (path.clone(), before[path].clone().unwrap_or_default()))
+                    .collect();
+                let run_dir = backup::create_backups(
+                    backup_dir,
+                    &opts.backup_suffix,
+                    &existing,
+                    &originals,
+                    &mut [],
+                    &opts.clock,
+                    &opts.ids,
+                )?;
+                for file in &mut files {
+                    if existing.contains(Utf8Path::new(&file.path)) {
+                        file.backup_path = Some(
+                            run_dir
+                                .join(format!("{}{}", file.path, opts.backup_suffix))
+                                .to_string(),
+                        );
+                    }
+                }
+                backup_run = Some(run_dir);
+            }
+        }
+
+        for path in &changed {
+            let abs = super::abs_path(repo_root, path);
+            match &after[path] {
+                Some(contents) => {
+                    if let Some(parent) = abs.parent()
+                        && !parent.exists()
+                    {
+                        fs::create_dir_all(parent)
+                            .with_context(|| format!("create dir {}", parent))?;
+                    }
+                    super::write_atomic(&abs, contents, opts.durable_writes, &opts.ids)?;
+                }
+                None => fs::remove_file(&abs).with_context(|| format!("remove {}", abs))?,
+            }
+        }
+
+        if let (Some(backup_dir), Some(keep_last)) = (backup_dir, opts.backup_keep_last) {
+            // The run just written is never pruned, even with `keep_last = 0`.
+            prune_backups(backup_dir, keep_last.max(1))?;
+        }
+    }
+
+    Ok(PatchApplyOutcome {
+        files,
+        preconditions,
+        backup_run,
+    })
+}
+
+fn apply_section(
+    section: &FileSection<'_>,
+    state: &mut BTreeMap<Utf8PathBuf, Option<String>>,
+) -> anyhow::Result<()> {
+    let old = match &section.old_path {
+        Some(path) => Some(
+            state
+                .get(path)
+                .cloned()
+                .flatten()
+                .with_context(|| format!("patch modifies {} which does not exist", path))?,
+        ),
+        None => None,
+    };
+
+    if let Some(path) = &section.new_path
+        && section.old_path.as_ref() != Some(path)
+        && state.get(path).is_some_and(Option::is_some)
+    {
+        anyhow::bail!("patch creates {} which already exists", path);
+    }
+
+    let base = old.as_deref().unwrap_or_default();
+    let new = match &section.hunks {
+        Some(hunks) => {
+            let target = section.new_path.as_ref().or(section.old_path.as_ref());
+            diffy::apply(base, hunks).map_err(|e| {
+                anyhow::anyhow!(
+                    "patch does not apply to {}: {}",
+                    target.map(|p| p.as_str()).unwrap_or_default(),
+                    e
+                )
+            })?
+        }
+        None => base.to_string(),
+    };
+
+    if let Some(path) = &section.old_path {
+        state.insert(path.clone(), None);
+    }
+    match &section.new_path {
+        Some(path) => {
+            state.insert(path.clone(), Some(new));
+        }
+        None if !new.is_empty() => {
+            anyhow::bail!(
+                "patch deletes {} but leaves content behind",
+                section
+                    .old_path
+                    .as_ref()
+                    .map(|p| p.as_str())
+                    .unwrap_or_default()
+            );
+        }
+        None => {}
+    }
+    Ok(())
+}
+
+/// Every path `patch` reads or writes (both sides of renames), sorted.
+pub fn patch_paths(patch: &str) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let paths: BTreeSet<Utf8PathBuf> = parse_sections(patch)?
+        .into_iter()
+        .flat_map(|s| [s.old_path, s.new_path])
+        .flatten()
+        .collect();
+    Ok(paths.into_iter().collect())
+}
+
+fn parse_sections(patch: &str) -> anyhow::Result<Vec<FileSection<'_>>> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in patch.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    if starts.is_empty() {
+        if patch.trim().is_empty() {
+            return Ok(Vec::new());
+        }
+        anyhow::bail!("patch has no `diff --git` sections");
+    }
+
+    let mut sections = Vec::with_capacity(starts.len());
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(patch.len());
+        sections.push(parse_section(&patch[start..end])?);
+    }
+    Ok(sections)
+}
+
+fn parse_section(text: &str) -> anyhow::Result<FileSection<'_>> {
+    let mut lines = text.lines();
+    let header = lines.next().unwrap_or_default();
+    let (mut old_path, mut new_path) = split_git_header(header)?;
+
+    let mut created = false;
+    let mut deleted = false;
+    let mut has_hunks = false;
+
+    for line in lines {
+        if let Some(rest) = line.strip_prefix("rename from ") {
+            old_path = Some(Utf8PathBuf::from(rest));
+        } else if let Some(rest) = line.strip_prefix("rename to ") {
+            new_path = Some(Utf8PathBuf::from(rest));
+        } else if line.starts_with("new file mode ") {
+            created = true;
+        } else if line.starts_with("deleted file mode ") {
+            deleted = true;
+        } else if let Some(rest) = line.strip_prefix("--- ") {
+            old_path = strip_side(rest, "a/");
+        } else if let Some(rest) = line.strip_prefix("+++ ") {
+            new_path = strip_side(rest, "b/");
+        } else if line == "GIT binary patch" || line.starts_with("Binary files ") {
+            anyhow::bail!("binary patches are not supported ({})", header);
+        } else if line.starts_with("@@ ") {
+            has_hunks = true;
+            break;
+        }
+    }
+
+    if created {
+        old_path = None;
+    }
+    if deleted {
+        new_path = None;
+    }
+    if old_path.is_none() && new_path.is_none() {
+        anyhow::bail!("patch section has no file paths: {}", header);
+    }
+    for path in old_path.iter().chain(&new_path) {
+        ensure_repo_relative(path)?;
+    }
+
+    let hunks = if has_hunks {
+        Some(Patch::from_str(text).with_context(|| format!("parse patch section: {}", header))?)
+    } else {
+        None
+    };
+
+    Ok(FileSection {
+        old_path,
+        new_path,
+        hunks,
+    })
+}
+
+/// Split `diff --git a/<old> b/<new>`. Paths containing ` b/` are ambiguous
+/// here; later `---`/`+++`/rename lines override the result when present.
+fn split_git_header(header: &str) -> anyhow::Result<(Option<Utf8PathBuf>, Option<Utf8PathBuf>)> {
+    let rest = header
+        .strip_prefix("diff --git a/")
+        .with_context(|| format!("malformed patch header: {}", header))?;
+    let (old, new) = rest
+        .split_once(" b/")
+        .with_context(|| format!("malformed patch header: {}", header))?;
+    Ok((Some(Utf8PathBuf::from(old)), Some(Utf8PathBuf::from(new))))
+}
+
+/// Patch paths must stay inside the repo root: no absolute paths and no
+/// `..` (or other non-normal) components.
+fn ensure_repo_relative(path: &Utf8Path) -> anyhow::Result<()> {
+    if path.is_absolute()
+        || !path
+            .components()
+            .all(|c| matches!(c, Utf8Component::Normal(_)))
+    {
+        anyhow::bail!("patch path escapes the repo root: {}", path);
+    }
+    Ok(())
+}
+
+fn strip_side(name: &str, prefix: &str) -> Option<Utf8PathBuf> {
+    let name = name.split('\t').next().unwrap_or(name);
+    if name == "/dev/null" {
+        return None;
+    }
+    Some(Utf8PathBuf::from(name.strip_prefix(prefix).unwrap_or(name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{FileChange, PatchOptions, render_file_changes};
+    use tempfile::TempDir;
+
+    fn temp_root() -> (TempDir, Utf8PathBuf) {
+        let temp = TempDir::new().expect("temp dir");
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+        (temp, root)
+    }
+
+    fn write_opts(root: &Utf8Path) -> ApplyOptions {
+        ApplyOptions {
+            backup_enabled: true,
+            backup_dir: Some(root.join("backups")),
+            backup_suffix: ".bak".to_string(),
+            ..ApplyOptions::default()
+        }
+    }
+
+    #[test]
+    fn applies_rendered_modify_create_delete_and_rename() {
+        let (_temp, root) = temp_root();
+        fs::write(root.join("Cargo.toml"), "a = 1\nb = 2\n").unwrap();
+        fs::write(root.join("gone.toml"), "x = 1\n").unwrap();
+        fs::write(root.join("old.toml"), "y = 1\n").unwrap();
+
+        let patch = render_file_changes(
+            &[
+                FileChange::Modify {
+                    path: Utf8Path::new("Cargo.toml"),
+                    old: b"a = 1\nb = 2\n",
+                    new: b"a = 1\nb = 3\n",
+                },
+                FileChange::Create {
+                    path: Utf8Path::new("crates/new/Cargo.toml"),
+                    new: b"[package]\n",
+                },
+                FileChange::Delete {
+                    path: Utf8Path::new("gone.toml"),
+                    old: b"x = 1\n",
+                },
+                FileChange::Rename {
+                    from: Utf8Path::new("old.toml"),
+                    to: Utf8Path::new("new.toml"),
+                    old: b"y = 1\n",
+                    new: b"y = 1\n",
+                },
+            ],
+            &PatchOptions::default(),
+        );
+
+        let outcome = apply_patch(
+            &root,
+            &patch,
+            &PlanPreconditions::default(),
+            &write_opts(&root),
+        )
+        .expect("apply");
+
+        assert!(outcome.preconditions.verified);
+        assert_eq!(outcome.files.len(), 5);
+        assert_eq!(
+            fs::read_to_string(root.join("Cargo.toml")).unwrap(),
+            "a = 1\nb = 3\n"
+        );
+        assert_eq!(
+            fs::read_to_string(root.join("crates/new/Cargo.toml")).unwrap(),
+            "[package]\n"
+        );
+        assert!(!root.join("gone.toml").exists());
+        assert!(!root.join("old.toml").exists());
+        assert_eq!(
+            fs::read_to_string(root.join("new.toml")).unwrap(),
+            "y = 1\n"
+        );
+
+        // Created files have nothing to back up; everything else does.
+        let run = outcome.backup_run.expect("backup run");
+        let manifest = backup::read_backup_manifest(&run).expect("manifest");
+        let backed_up: Vec<_> = manifest.files.iter().map(|f| f.path.as_str()).collect();
+        assert_eq!(backed_up, vec!["Cargo.toml", "gone.toml", "old.toml"]);
+    }
+
+    #[test]
+    fn hunk_mismatch_writes_nothing() {
+        let (_temp, root) = temp_root();
+        fs::write(root.join("a.toml"), "a = 1\n").unwrap();
+        fs::write(root.join("b.toml"), "b = 9\n").unwrap();
+
+        let patch = render_file_changes(
+            &[
+                FileChange::Modify {
+                    path: Utf8Path::new("a.toml"),
+                    old: b"a = 1\n",
+                    new: b"a = 2\n",
+                },
+                FileChange::Modify {
+                    path: Utf8Path::new("b.toml"),
+                    old: b"b = 1\n",
+                    new: b"b = 2\n",
+                },
+            ],
+            &PatchOptions::default(),
+        );
+
+        let err = apply_patch(
+            &root,
+            &patch,
+            &PlanPreconditions::default(),
+            &write_opts(&root),
+        )
+        .expect_err("b.toml drifted");
+        assert!(err.to_string().contains("does not apply to b.toml"));
+        assert_eq!(fs::read_to_string(root.join("a.toml")).unwrap(), "a = 1\n");
+    }
+
+    #[test]
+    fn precondition_mismatch_blocks_write() {
+        let (_temp, root) = temp_root();
+        fs::write(root.join("a.toml"), "a = 1\n").unwrap();
+        let patch = render_file_changes(
+            &[FileChange::Modify {
+                path: Utf8Path::new("a.toml"),
+                old: b"a = 1\n",
+                new: b"a = 2\n",
+            }],
+            &PatchOptions::default(),
+        );
+        let expected = PlanPreconditions {
+            files: vec![buildfix_types::plan::FilePrecondition {
+                path: "a.toml".to_string(),
+                sha256: "0".repeat(64),
+                semantic_sha256: None,
+            }],
+            ..PlanPreconditions::default()
+        };
+
+        let outcome = apply_patch(&root, &patch, &expected, &write_opts(&root)).expect("apply");
+        assert!(!outcome.preconditions.verified);
+        assert_eq!(outcome.preconditions.mismatches[0].path, "a.toml");
+        assert_eq!(fs::read_to_string(root.join("a.toml")).unwrap(), "a = 1\n");
+    }
+
+    #[test]
+    fn dry_run_checks_but_does_not_write() {
+        let (_temp, root) = temp_root();
+        fs::write(root.join("a.toml"), "a = 1\n").unwrap();
+        let patch = render_file_changes(
+            &[FileChange::Modify {
+                path: Utf8Path::new("a.toml"),
+                old: b"a = 1\n",
+                new: b"a = 2\n",
+            }],
+            &PatchOptions::default(),
+        );
+        let opts = ApplyOptions {
+            dry_run: true,
+            ..write_opts(&root)
+        };
+
+        let outcome =
+            apply_patch(&root, &patch, &PlanPreconditions::default(), &opts).expect("apply");
+        assert_eq!(outcome.files.len(), 1);
+        assert!(outcome.backup_run.is_none());
+        assert_eq!(fs::read_to_string(root.join("a.toml")).unwrap(), "a = 1\n");
+    }
+
+    #[test]
+    fn binary_sections_are_rejected() {
+        let patch = "diff --git a/x.bin b/x.bin\nindex 1..2 100644\nGIT binary patch\nliteral 1\n";
+        let err = parse_sections(patch).expect_err("binary");
+        assert!(err.to_string().contains("binary patches are not supported"));
+    }
+
+    #[test]
+    fn absolute_paths_are_rejected() {
+        let patch = "diff --git a/x b/x\n--- a/x\n+++ /etc/x\n@@ -1 +1 @@\n-a\n+b\n";
+        let err = parse_sections(patch).expect_err("absolute");
+        assert!(err.to_string().contains("escapes the repo root"));
+    }
+
+    #[test]
+    fn parent_dir_paths_are_rejected() {
+        let patch = "diff --git a/x b/y\nsimilarity index 100%\nrename from x\nrename to ../../y\n";
+        let err = parse_sections(patch).expect_err("parent dir");
+        assert!(err.to_string().contains("escapes the repo root"));
+
+        let patch = "diff --git a/x b/x\ndeleted file mode 100644\n--- a/../x\n+++ /dev/null\n";
+        let err = parse_sections(patch).expect_err("parent dir");
+        assert!(err.to_string().contains("escapes the repo root"));
+    }
+}
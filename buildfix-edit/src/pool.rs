@@ -0,0 +1,93 @@
+//! Bounded, order-preserving parallel map for per-file work.
+//!
+//! Hashing and diff rendering are independent per file, so a run touching
+//! hundreds of manifests spreads them over a fixed number of scoped threads.
+//! Results come back in input order, keeping output byte-for-byte identical
+//! to a sequential run.
+
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+
+/// Number of workers for `jobs` (0 = available cores) over `items` items.
+pub(crate) fn worker_count(jobs: usize, items: usize) -> usize {
+    let jobs = match jobs {
+        0 => std::thread::available_parallelism().map_or(1, |n| n.get()),
+        jobs => jobs,
+    };
+    jobs.min(items).max(1)
+}
+
+/// Map `f` over `items` on up to `jobs` threads, each with its own `init()`
+/// state for reusing buffers. With one worker everything runs inline.
+pub(crate) fn map_init<T, S, R>(
+    jobs: usize,
+    items: &[T],
+    init: impl Fn() -> S + Sync,
+    f: impl Fn(&mut S, &T) -> R + Sync,
+) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    let workers = worker_count(jobs, items.len());
+    if workers == 1 {
+        let mut state = init();
+        return items.iter().map(|item| f(&mut state, item)).collect();
+    }
+
+    let next = AtomicUsize::new(0);
+    let slots: Vec<Mutex<Option<R>>> = items.iter().map(|_| Mutex::new(None)).collect();
+    std::thread::scope(|scope| {
+        for _ in 0..workers {
+            scope.spawn(|| {
+                let mut state = init();
+                loop {
+                    let i = next.fetch_add(1, Ordering::Relaxed);
+                    let Some(item) = items.get(i) else {
+                        break;
+                    };
+                    let result = f(&mut state, item);
+                    *slots[i].lock().unwrap_or_else(|e| e.into_inner()) = Some(result);
+                }
+            });
+        }
+    });
+    slots
+        .into_iter()
+        .map(|slot| {
+            slot.into_inner()
+                .unwrap_or_else(|e| e.into_inner())
+                .expect("every item is mapped")
+        })
+        .collect()
+}
+
+/// [`map_init`] without per-worker state.
+pub(crate) fn map<T, R>(jobs: usize, items: &[T], f: impl Fn(&T) -> R + Sync) -> Vec<R>
+where
+    T: Sync,
+    R: Send,
+{
+    map_init(jobs, items, || (), |_, item| f(item))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn map_keeps_input_order() {
+        let items: Vec<usize> = (0..200).collect();
+        for jobs in [0, 1, 3, 64] {
+            let out = map(jobs, &items, |i| i * 2);
+            assert_eq!(out, items.iter().map(|i| i * 2).collect::<Vec<_>>());
+        }
+    }
+
+    #[test]
+    fn worker_count_is_bounded_by_items() {
+        assert_eq!(worker_count(8, 3), 3);
+        assert_eq!(worker_count(2, 100), 2);
+        assert_eq!(worker_count(4, 0), 1);
+    }
+}
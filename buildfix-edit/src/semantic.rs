@@ -0,0 +1,105 @@
+//! Formatting-insensitive hashes for `semantic` precondition mode.
+//!
+//! A manifest reformatted by an editor or `taplo fmt`, or with a comment
+//! added, hashes differently byte-for-byte but still holds the same keys and
+//! values. The semantic hash covers only the parsed structure: key order,
+//! whitespace, comments, quoting style and inline-vs-standard tables all
+//! drop out, while every key, value and array order stays significant.
+
+use buildfix_hash::sha256_hex;
+use camino::Utf8Path;
+use std::fmt::Write as _;
+
+/// Semantic hash of `text` when `path` is a TOML file that parses.
+pub(crate) fn semantic_sha256(path: &Utf8Path, text: &str) -> Option<String> {
+    if path.extension() != Some("toml") {
+        return None;
+    }
+    let table = text.parse::<toml::Table>().ok()?;
+    let mut canonical = String::new();
+    write_table(&mut canonical, &table);
+    Some(sha256_hex(canonical.as_bytes()))
+}
+
+fn write_table(out: &mut String, table: &toml::Table) {
+    let mut entries: Vec<_> = table.iter().collect();
+    entries.sort_by(|a, b| a.0.cmp(b.0));
+    out.push('{');
+    for (key, value) in entries {
+        write_string(out, key);
+        out.push(':');
+        write_value(out, value);
+        out.push(',');
+    }
+    out.push('}');
+}
+
+/// Values are tagged with their type, so `"1"` and `1` differ.
+fn write_value(out: &mut String, value: &toml::Value) {
+    match value {
+        toml::Value::String(s) => {
+            out.push('s');
+            write_string(out, s);
+        }
+        toml::Value::Integer(i) => {
+            let _ = write!(out, "i{}", i);
+        }
+        toml::Value::Float(f) => {
+            let _ = write!(out, "f{:?}", f);
+        }
+        toml::Value::Boolean(b) => {
+            let _ = write!(out, "b{}", b);
+        }
+        toml::Value::Datetime(d) => {
+            out.push('d');
+            write_string(out, &d.to_string());
+        }
+        toml::Value::Array(items) => {
+            out.push('[');
+            for item in items {
+                write_value(out, item);
+                out.push(',');
+            }
+            out.push(']');
+        }
+        toml::Value::Table(table) => write_table(out, table),
+    }
+}
+
+fn write_string(out: &mut String, s: &str) {
+    let _ = write!(out, "{:?}", s);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hash(text: &str) -> Option<String> {
+        semantic_sha256(Utf8Path::new("Cargo.toml"), text)
+    }
+
+    #[test]
+    fn formatting_and_comments_do_not_change_the_hash() {
+        let a = "[package]\nname = \"a\"\nversion = \"0.1.0\"\n\n[dependencies]\nserde = { version = \"1\", features = [\"derive\"] }\n";
+        let b = "# the crate\n[package]\nversion = '0.1.0'   # bumped by hand\nname    = \"a\"\n\n[dependencies.serde]\nfeatures = [\n  \"derive\",\n]\nversion = \"1\"\n";
+        assert_eq!(hash(a), hash(b));
+    }
+
+    #[test]
+    fn values_types_and_array_order_change_the_hash() {
+        let base = hash("a = \"1\"\nb = [1, 2]\n");
+        assert_ne!(base, hash("a = \"2\"\nb = [1, 2]\n"));
+        assert_ne!(base, hash("a = 1\nb = [1, 2]\n"));
+        assert_ne!(base, hash("a = \"1\"\nb = [2, 1]\n"));
+        assert_ne!(base, hash("a = \"1\"\nb = [1, 2]\nc = true\n"));
+    }
+
+    #[test]
+    fn only_parseable_toml_files_have_a_hash() {
+        assert_eq!(hash("not = [toml"), None);
+        assert_eq!(
+            semantic_sha256(Utf8Path::new("package.json"), "a = 1"),
+            None
+        );
+    }
+}
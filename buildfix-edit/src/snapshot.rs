@@ -0,0 +1,143 @@
+//! Content snapshot shared by the stages of one plan or apply run.
+//!
+//! Attaching preconditions, checking them, and executing ops all need the
+//! same files. A [`FileSnapshot`] reads and hashes each file once, so every
+//! stage sees the same bytes and the run does no duplicate I/O. Files are
+//! read and hashed on a bounded pool of threads.
+
+use crate::{abs_path, pool};
+use anyhow::Context;
+use buildfix_hash::sha256_hex;
+use camino::{Utf8Path, Utf8PathBuf};
+use fs_err as fs;
+use std::collections::BTreeMap;
+use std::io;
+
+/// Files of a repo as they were when first read during a run.
+#[derive(Debug)]
+pub struct FileSnapshot {
+    root: Utf8PathBuf,
+    jobs: usize,
+    files: BTreeMap<Utf8PathBuf, SnapshotFile>,
+}
+
+#[derive(Debug)]
+enum SnapshotFile {
+    Text {
+        text: String,
+        sha256: String,
+    },
+    /// Readable but not UTF-8: hashable, but ops see it as empty.
+    Binary {
+        sha256: String,
+    },
+    Unreadable(io::Error),
+}
+
+impl SnapshotFile {
+    fn load(abs: &Utf8Path) -> Self {
+        match fs::read(abs) {
+            Ok(bytes) => {
+                let sha256 = sha256_hex(&bytes);
+                match String::from_utf8(bytes) {
+                    Ok(text) => SnapshotFile::Text { text, sha256 },
+                    Err(_) => SnapshotFile::Binary { sha256 },
+                }
+            }
+            Err(e) => SnapshotFile::Unreadable(e),
+        }
+    }
+}
+
+impl FileSnapshot {
+    /// An empty snapshot of the repo at `root`.
+    pub fn new(root: impl Into<Utf8PathBuf>) -> Self {
+        Self {
+            root: root.into(),
+            jobs: 0,
+            files: BTreeMap::new(),
+        }
+    }
+
+    /// Threads used to read and hash files (0 = available cores, 1 = inline).
+    pub fn with_jobs(mut self, jobs: usize) -> Self {
+        self.jobs = jobs;
+        self
+    }
+
+    pub fn root(&self) -> &Utf8Path {
+        &self.root
+    }
+
+    /// Read and hash every path (relative to the root) not already in the
+    /// snapshot. Read failures are kept and surface from [`FileSnapshot::sha256`].
+    pub fn read<'a>(&mut self, paths: impl IntoIterator<Item = &'a Utf8Path>) {
+        let mut missing: Vec<&Utf8Path> = paths
+            .into_iter()
+            .filter(|path| !self.files.contains_key(*path))
+            .collect();
+        missing.sort();
+        missing.dedup();
+
+        let root = &self.root;
+        let loaded = pool::map(self.jobs, &missing, |path| {
+            SnapshotFile::load(&abs_path(root, path))
+        });
+        for (path, file) in missing.into_iter().zip(loaded) {
+            self.files.insert(path.to_owned(), file);
+        }
+    }
+
+    /// Text of `path`; empty when it is missing, unreadable, or not UTF-8.
+    pub fn contents(&self, path: &Utf8Path) -> &str {
+        match self.files.get(path) {
+            Some(SnapshotFile::Text { text, .. }) => text,
+            _ => "",
+        }
+    }
+
+    /// SHA-256 of the bytes of `path`, reading it first if needed.
+    pub fn sha256(&mut self, path: &Utf8Path) -> anyhow::Result<&str> {
+        self.read([path]);
+        match &self.files[path] {
+            SnapshotFile::Text { sha256, .. } | SnapshotFile::Binary { sha256 } => Ok(sha256),
+            SnapshotFile::Unreadable(e) => Err(io::Error::new(e.kind(), e.to_string()))
+                .with_context(|| format!("read {}", abs_path(&self.root, path))),
+        }
+    }
+
+    /// Text of `path`, or `None` when it does not exist. Unlike
+    /// [`FileSnapshot::contents`], unreadable and non-UTF-8 files are errors.
+    pub(crate) fn existing(&self, path: &Utf8Path) -> anyhow::Result<Option<&str>> {
+        let err = match self.files.get(path) {
+            None => return Ok(None),
+            Some(SnapshotFile::Text { text, .. }) => return Ok(Some(text)),
+            Some(SnapshotFile::Unreadable(e)) if e.kind() == io::ErrorKind::NotFound => {
+                return Ok(None);
+            }
+            Some(SnapshotFile::Unreadable(e)) => io::Error::new(e.kind(), e.to_string()),
+            Some(SnapshotFile::Binary { .. }) => io::Error::new(
+                io::ErrorKind::InvalidData,
+                "stream did not contain valid UTF-8",
+            ),
+        };
+        Err(err).with_context(|| format!("read {}", abs_path(&self.root, path)))
+    }
+
+    /// Whether `path` was read and found to exist (even if unreadable).
+    pub(crate) fn exists(&self, path: &Utf8Path) -> bool {
+        match self.files.get(path) {
+            None => false,
+            Some(SnapshotFile::Unreadable(e)) => e.kind() != io::ErrorKind::NotFound,
+            Some(_) => true,
+        }
+    }
+
+    /// SHA-256 of [`FileSnapshot::contents`] for `path`.
+    pub(crate) fn text_sha256(&self, path: &Utf8Path) -> String {
+        match self.files.get(path) {
+            Some(SnapshotFile::Text { sha256, .. }) => sha256.clone(),
+            _ => sha256_hex(b""),
+        }
+    }
+}
@@ -1,12 +1,16 @@
 use buildfix_edit::{
-    ApplyOptions, AttachPreconditionsOptions, apply_op_to_content, apply_plan,
-    attach_preconditions, check_policy_block, get_head_sha, is_working_tree_dirty, preview_patch,
+    ApplyLock, ApplyOptions, AttachPreconditionsOptions, DEFAULT_LOCK_STALE_AFTER, LockError,
+    apply_op_to_content, apply_plan, attach_preconditions, check_policy_block, get_head_sha,
+    is_working_tree_dirty, preview_patch,
 };
 use buildfix_types::apply::{
     ApplyPreconditions, ApplyRepoInfo, ApplyResult, ApplyStatus, ApplySummary, BuildfixApply,
     PlanRef,
 };
-use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+use buildfix_types::cancel::CancellationToken;
+use buildfix_types::clock::{Clock, Ids};
+use buildfix_types::error_code::BuildfixErrorCode;
+use buildfix_types::ops::{InsertHint, InsertPosition, OpKind, OpTarget, SafetyClass};
 use buildfix_types::plan::{BuildfixPlan, PlanOp, PlanPolicy, Rationale, RepoInfo};
 use buildfix_types::receipt::ToolInfo;
 use camino::{Utf8Path, Utf8PathBuf};
@@ -29,6 +33,8 @@ fn repo_info() -> RepoInfo {
         root: ".".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     }
 }
 
@@ -52,6 +58,7 @@ fn make_op(
         blocked_reason_token: None,
         target: OpTarget {
             path: path.to_string(),
+            sha256: None,
         },
         kind,
         rationale: Rationale {
@@ -61,6 +68,8 @@ fn make_op(
         },
         params_required,
         preview: None,
+        impact: None,
+        workspace: None,
     }
 }
 
@@ -104,6 +113,8 @@ fn attach_preconditions_includes_git_head_and_dirty() {
         &mut plan,
         &AttachPreconditionsOptions {
             include_git_head: true,
+            pin_op_targets: false,
+            mode: Default::default(),
         },
     )
     .expect("attach");
@@ -119,12 +130,103 @@ fn attach_preconditions_includes_git_head_and_dirty() {
         &mut plan,
         &AttachPreconditionsOptions {
             include_git_head: true,
+            pin_op_targets: false,
+            mode: Default::default(),
         },
     )
     .expect("attach dirty");
     assert_eq!(plan.preconditions.dirty, Some(true));
 }
 
+#[cfg(feature = "gix")]
+#[test]
+fn gix_git_state_matches_git_cli() {
+    use buildfix_edit::{git_cli_head_sha, git_cli_is_dirty, gix_head_sha, gix_is_dirty};
+
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    fs::write(root.join("Cargo.toml"), "[workspace]\n").expect("write");
+    run_git(&root, &["init"]);
+    run_git(&root, &["config", "user.email", "test@example.com"]);
+    run_git(&root, &["config", "user.name", "Test User"]);
+    run_git(&root, &["add", "."]);
+    run_git(&root, &["commit", "-m", "init"]);
+
+    assert_eq!(
+        gix_head_sha(&root).expect("gix head"),
+        git_cli_head_sha(&root).expect("git head")
+    );
+    // From a subdirectory, both find the enclosing repository.
+    fs::create_dir_all(root.join("crates")).expect("mkdir");
+    assert_eq!(
+        gix_head_sha(&root.join("crates")).expect("gix head"),
+        git_cli_head_sha(&root).expect("git head")
+    );
+
+    assert!(!gix_is_dirty(&root).expect("gix clean"));
+    fs::write(root.join("new.txt"), "untracked\n").expect("write");
+    assert!(gix_is_dirty(&root).expect("gix untracked"));
+    assert!(git_cli_is_dirty(&root).expect("git untracked"));
+    fs::remove_file(root.join("new.txt")).expect("remove");
+
+    fs::write(root.join("Cargo.toml"), "[workspace]\n# edited\n").expect("write");
+    assert!(gix_is_dirty(&root).expect("gix modified"));
+    run_git(&root, &["add", "Cargo.toml"]);
+    assert!(gix_is_dirty(&root).expect("gix staged"));
+    assert!(git_cli_is_dirty(&root).expect("git staged"));
+}
+
+#[test]
+fn scoped_dirty_checks_only_consider_given_paths() {
+    use buildfix_edit::{git_cli_is_dirty_paths, is_working_tree_dirty_paths};
+
+    let temp = TempDir::new().expect("temp dir");
+    let top = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    // The buildfix repo root is a subdirectory of the git work tree.
+    let root = top.join("ws");
+    fs::create_dir_all(root.join("crates/a")).expect("mkdir");
+    fs::write(root.join("Cargo.toml"), "[workspace]\n").expect("write");
+    fs::write(root.join("crates/a/Cargo.toml"), "[package]\n").expect("write");
+    run_git(&top, &["init"]);
+    run_git(&top, &["config", "user.email", "test@example.com"]);
+    run_git(&top, &["config", "user.name", "Test User"]);
+    run_git(&top, &["add", "."]);
+    run_git(&top, &["commit", "-m", "init"]);
+
+    let targets = vec!["Cargo.toml".to_string(), "crates/a/Cargo.toml".to_string()];
+    let check = |expected: bool| {
+        assert_eq!(
+            is_working_tree_dirty_paths(&root, &targets).expect("scoped"),
+            expected
+        );
+        assert_eq!(
+            git_cli_is_dirty_paths(&root, &targets).expect("git scoped"),
+            expected
+        );
+        #[cfg(feature = "gix")]
+        assert_eq!(
+            buildfix_edit::gix_is_dirty_paths(&root, &targets).expect("gix scoped"),
+            expected
+        );
+    };
+
+    check(false);
+    fs::write(root.join("notes.md"), "unrelated\n").expect("write");
+    fs::write(top.join("Cargo.toml"), "[workspace]\n").expect("write");
+    assert!(is_working_tree_dirty(&root).expect("dirty"));
+    check(false);
+
+    fs::write(root.join("crates/a/Cargo.toml"), "[package]\n# edited\n").expect("write");
+    check(true);
+    assert!(!is_working_tree_dirty_paths(&root, &[]).expect("no paths"));
+
+    // Listed relative to the repo root; the top-level edit is outside it.
+    assert_eq!(
+        buildfix_edit::git_cli_dirty_paths(&root).expect("dirty paths"),
+        vec!["crates/a/Cargo.toml".to_string(), "notes.md".to_string()]
+    );
+}
+
 #[test]
 fn preview_patch_emits_diff() {
     let temp = TempDir::new().expect("temp dir");
@@ -151,12 +253,31 @@ fn preview_patch_emits_diff() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let patch = preview_patch(&root, &plan, &opts).expect("preview");
     assert!(patch.contains("diff --git"));
     assert!(patch.contains("workspace"));
+    assert_eq!(patch.matches("--- ").count(), 1, "single header pair");
+    assert!(!patch.contains("\nindex "));
+
+    let binary = ApplyOptions {
+        binary_patch: true,
+        ..opts
+    };
+    let patch = preview_patch(&root, &plan, &binary).expect("preview");
+    assert!(patch.contains("\nindex "));
+    assert!(patch.contains(" 100644\n--- a/Cargo.toml\n+++ b/Cargo.toml\n"));
 }
 
 #[test]
@@ -192,7 +313,16 @@ fn apply_plan_writes_backups() {
         backup_enabled: true,
         backup_dir: Some(backup_dir.clone()),
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
@@ -207,6 +337,309 @@ fn apply_plan_writes_backups() {
     assert!(backup_path.exists());
 }
 
+#[test]
+fn apply_plan_keeps_its_own_backup_run_with_keep_last_zero() {
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    fs::write(root.join("Cargo.toml"), "[package]\nname = \"a\"\n").expect("write");
+
+    let mut plan = base_plan();
+    plan.ops.push(make_op(
+        "op1",
+        "Cargo.toml",
+        SafetyClass::Safe,
+        false,
+        OpKind::TomlTransform {
+            rule_id: "set_package_rust_version".to_string(),
+            args: Some(serde_json::json!({"rust_version": "1.70"})),
+        },
+        vec![],
+    ));
+
+    let backup_dir = Utf8PathBuf::from_path_buf(temp.path().join("backups")).expect("utf8");
+    let opts = ApplyOptions {
+        dry_run: false,
+        allow_guarded: false,
+        allow_unsafe: false,
+        backup_enabled: true,
+        backup_dir: Some(backup_dir.clone()),
+        backup_suffix: ".bak".to_string(),
+        backup_keep_last: Some(0),
+        params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
+    };
+
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
+    let file = &apply.results[0].files[0];
+    let backup_path = Utf8Path::new(file.backup_path.as_ref().expect("backup path"));
+    assert!(backup_path.exists());
+}
+
+#[test]
+fn apply_plan_creates_missing_target_files() {
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+
+    let mut plan = base_plan();
+    plan.ops.push(make_op(
+        "op1",
+        "crates/new/rust-toolchain.toml",
+        SafetyClass::Safe,
+        false,
+        OpKind::TomlSet {
+            toml_path: vec!["toolchain".to_string(), "channel".to_string()],
+            value: serde_json::json!("1.75"),
+            insert: None,
+        },
+        vec![],
+    ));
+
+    let backup_dir = Utf8PathBuf::from_path_buf(temp.path().join("backups")).expect("utf8");
+    let opts = ApplyOptions {
+        dry_run: false,
+        allow_guarded: false,
+        allow_unsafe: false,
+        backup_enabled: true,
+        backup_dir: Some(backup_dir),
+        backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
+        params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
+    };
+
+    let (apply, patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
+    assert!(
+        patch.contains(
+            "new file mode 100644\n--- /dev/null\n+++ b/crates/new/rust-toolchain.toml\n"
+        )
+    );
+    assert!(patch.contains("+channel = \"1.75\"\n"));
+    assert_eq!(apply.summary.files_modified, 1);
+
+    let file = &apply.results[0].files[0];
+    assert_eq!(file.sha256_before, None);
+    assert!(file.sha256_after.is_some());
+    assert_eq!(file.backup_path, None, "nothing to back up for a new file");
+
+    let written = fs::read_to_string(root.join("crates/new/rust-toolchain.toml")).expect("created");
+    assert!(written.contains("channel = \"1.75\""));
+}
+
+#[test]
+fn apply_plan_records_op_timing_attempts_and_verification() {
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    fs::write(
+        root.join("rust-toolchain.toml"),
+        "[toolchain]\nchannel = \"1.70\"\n",
+    )
+    .expect("write toolchain");
+
+    let set_channel = |id: &str| {
+        make_op(
+            id,
+            "rust-toolchain.toml",
+            SafetyClass::Safe,
+            false,
+            OpKind::TomlSet {
+                toml_path: vec!["toolchain".to_string(), "channel".to_string()],
+                value: serde_json::json!("1.75"),
+                insert: None,
+            },
+            vec![],
+        )
+    };
+    let mut plan = base_plan();
+    plan.ops.push(set_channel("op1"));
+    // Same edit again: applied, but changes nothing, so nothing is written for it.
+    plan.ops.push(set_channel("op2"));
+
+    let mut opts = ApplyOptions {
+        dry_run: true,
+        allow_guarded: false,
+        allow_unsafe: false,
+        backup_enabled: false,
+        backup_dir: None,
+        backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
+        params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::fixed(chrono::DateTime::UNIX_EPOCH),
+        ids: Ids::default(),
+        jobs: 0,
+    };
+
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("dry run");
+    let first = &apply.results[0];
+    assert_eq!(first.duration_ms, Some(0), "fixed clock");
+    assert_eq!(
+        (first.attempts, first.verified),
+        (None, None),
+        "nothing written"
+    );
+
+    opts.dry_run = false;
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
+    let (first, second) = (&apply.results[0], &apply.results[1]);
+    assert_eq!(first.status, ApplyStatus::Applied);
+    assert_eq!(first.duration_ms, Some(0));
+    assert_eq!(first.attempts, Some(1));
+    assert_eq!(first.verified, Some(true));
+    assert_eq!(second.status, ApplyStatus::Applied);
+    assert_eq!(second.duration_ms, Some(0));
+    assert_eq!((second.attempts, second.verified), (None, None));
+
+    let json = serde_json::to_value(first).expect("serialize");
+    assert_eq!(json["attempts"], 1);
+    assert_eq!(json["verified"], true);
+    let json = serde_json::to_value(second).expect("serialize");
+    assert!(json.get("attempts").is_none());
+    assert!(json.get("verified").is_none());
+}
+
+#[test]
+fn apply_plan_durable_writes_replace_in_place() {
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    fs::write(root.join("Cargo.toml"), "[package]\nname = \"a\"\n").expect("write");
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        fs::set_permissions(
+            root.join("Cargo.toml"),
+            std::fs::Permissions::from_mode(0o600),
+        )
+        .expect("chmod");
+    }
+
+    let mut plan = base_plan();
+    plan.ops.push(make_op(
+        "op1",
+        "Cargo.toml",
+        SafetyClass::Safe,
+        false,
+        OpKind::TomlTransform {
+            rule_id: "set_package_edition".to_string(),
+            args: Some(serde_json::json!({"edition": "2021"})),
+        },
+        vec![],
+    ));
+
+    let opts = ApplyOptions {
+        dry_run: false,
+        allow_guarded: false,
+        allow_unsafe: false,
+        backup_enabled: false,
+        backup_dir: None,
+        backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
+        params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: true,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
+    };
+
+    apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
+    let contents = fs::read_to_string(root.join("Cargo.toml")).expect("read");
+    assert!(contents.contains("edition = \"2021\""));
+
+    let leftovers: Vec<_> = fs::read_dir(&root)
+        .expect("read dir")
+        .filter_map(|e| e.ok())
+        .filter(|e| {
+            e.file_name()
+                .to_string_lossy()
+                .starts_with(".buildfix-tmp-")
+        })
+        .collect();
+    assert!(
+        leftovers.is_empty(),
+        "temp files left behind: {leftovers:?}"
+    );
+
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = fs::metadata(root.join("Cargo.toml"))
+            .expect("metadata")
+            .permissions()
+            .mode();
+        assert_eq!(mode & 0o777, 0o600);
+    }
+}
+
+#[test]
+fn apply_plan_refuses_when_lock_is_held() {
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    let original = "[package]\nname = \"a\"\n";
+    fs::write(root.join("Cargo.toml"), original).expect("write");
+
+    let mut plan = base_plan();
+    plan.ops.push(make_op(
+        "op1",
+        "Cargo.toml",
+        SafetyClass::Safe,
+        false,
+        OpKind::TomlTransform {
+            rule_id: "set_package_rust_version".to_string(),
+            args: Some(serde_json::json!({"rust_version": "1.70"})),
+        },
+        vec![],
+    ));
+
+    let lock_path = root.join("artifacts").join("buildfix").join(".lock");
+    let mut opts = ApplyOptions {
+        dry_run: false,
+        lock_path: Some(lock_path.clone()),
+        ..ApplyOptions::default()
+    };
+
+    let held = ApplyLock::acquire(&lock_path, DEFAULT_LOCK_STALE_AFTER).expect("acquire");
+    let err = apply_plan(&root, &plan, tool_info(), &opts).expect_err("lock held");
+    assert!(matches!(
+        err.downcast_ref::<LockError>(),
+        Some(LockError::Held { .. })
+    ));
+    assert_eq!(
+        fs::read_to_string(root.join("Cargo.toml")).expect("read"),
+        original
+    );
+
+    // Dry runs never take the lock.
+    opts.dry_run = true;
+    apply_plan(&root, &plan, tool_info(), &opts).expect("dry run");
+
+    drop(held);
+    opts.dry_run = false;
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
+    assert_eq!(apply.summary.applied, 1);
+    assert!(!lock_path.exists());
+}
+
 #[test]
 fn apply_plan_records_block_reasons() {
     let temp = TempDir::new().expect("temp dir");
@@ -222,6 +655,7 @@ fn apply_plan_records_block_reasons() {
         OpKind::TomlSet {
             toml_path: vec!["package".to_string(), "name".to_string()],
             value: serde_json::Value::String("demo".to_string()),
+            insert: None,
         },
         vec![],
     ));
@@ -244,6 +678,7 @@ fn apply_plan_records_block_reasons() {
         OpKind::TomlSet {
             toml_path: vec!["package".to_string(), "name".to_string()],
             value: serde_json::Value::String("demo".to_string()),
+            insert: None,
         },
         vec![],
     ));
@@ -269,7 +704,16 @@ fn apply_plan_records_block_reasons() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params,
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
@@ -308,6 +752,52 @@ fn apply_plan_records_block_reasons() {
     assert_eq!(allowed.status, ApplyStatus::Skipped);
 }
 
+#[test]
+fn apply_plan_skips_ops_outside_selection() {
+    let temp = TempDir::new().expect("temp dir");
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).expect("utf8");
+    fs::write(root.join("Cargo.toml"), "[workspace]\n").expect("write");
+
+    let mut plan = base_plan();
+    for (id, key) in [("picked", "resolver"), ("left_out", "name")] {
+        plan.ops.push(make_op(
+            id,
+            "Cargo.toml",
+            SafetyClass::Safe,
+            false,
+            OpKind::TomlSet {
+                toml_path: vec!["workspace".to_string(), key.to_string()],
+                value: serde_json::Value::String("2".to_string()),
+                insert: None,
+            },
+            vec![],
+        ));
+    }
+
+    let opts = ApplyOptions {
+        selected_ops: Some(["picked".to_string()].into()),
+        ..ApplyOptions::default()
+    };
+
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
+    let left_out = apply
+        .results
+        .iter()
+        .find(|r| r.op_id == "left_out")
+        .unwrap();
+    assert_eq!(left_out.status, ApplyStatus::Skipped);
+    assert_eq!(
+        left_out.blocked_reason_token,
+        Some(BuildfixErrorCode::UserFiltered)
+    );
+    assert_eq!(apply.summary.applied, 1);
+    assert_eq!(apply.summary.blocked, 0);
+
+    let contents = fs::read_to_string(root.join("Cargo.toml")).unwrap();
+    assert!(contents.contains("resolver = \"2\""));
+    assert!(!contents.contains("name"));
+}
+
 #[test]
 fn apply_op_to_content_handles_missing_params_and_unknown_rules() {
     let contents = "[package]\nname = \"demo\"\n";
@@ -430,10 +920,14 @@ fn check_policy_block_classifies_cases() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "artifacts/buildfix/plan.json".to_string(),
             sha256: None,
+            compat: None,
         },
     );
     apply.preconditions.verified = true;
@@ -444,6 +938,7 @@ fn check_policy_block_classifies_cases() {
     preconditions.preconditions = ApplyPreconditions {
         verified: false,
         mismatches: vec![],
+        soft_matches: vec![],
     };
     let err = check_policy_block(&preconditions, false).expect("policy block");
     assert!(format!("{:?}", err).contains("PreconditionMismatch"));
@@ -453,9 +948,12 @@ fn check_policy_block_classifies_cases() {
         op_id: "op1".to_string(),
         status: ApplyStatus::Blocked,
         message: None,
-        blocked_reason: Some("safety gate".to_string()),
-        blocked_reason_token: None,
+        blocked_reason: Some("guarded op not allowed".to_string()),
+        blocked_reason_token: Some(BuildfixErrorCode::SafetyGuardedNotAllowed),
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
     let err = check_policy_block(&safety_block, false).expect("policy block");
     assert!(format!("{:?}", err).contains("SafetyGateDenial"));
@@ -468,6 +966,9 @@ fn check_policy_block_classifies_cases() {
         blocked_reason: Some("policy".to_string()),
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
     let err = check_policy_block(&policy_block, false).expect("policy block");
     assert!(format!("{:?}", err).contains("PolicyDenial"));
@@ -488,6 +989,7 @@ fn apply_op_to_content_set_remove_and_json_values() {
     let set_bool = OpKind::TomlSet {
         toml_path: vec!["package".to_string(), "publish".to_string()],
         value: serde_json::Value::Bool(false),
+        insert: None,
     };
     let out = apply_op_to_content(contents, &set_bool).expect("set bool");
     assert!(out.contains("publish = false"));
@@ -499,6 +1001,7 @@ fn apply_op_to_content_set_remove_and_json_values() {
             "count".to_string(),
         ],
         value: serde_json::Value::Number(1.into()),
+        insert: None,
     };
     let out = apply_op_to_content(&out, &set_int).expect("set int");
     assert!(out.contains("count = 1"));
@@ -510,6 +1013,7 @@ fn apply_op_to_content_set_remove_and_json_values() {
             "ratio".to_string(),
         ],
         value: serde_json::Value::Number(serde_json::Number::from_f64(1.5).unwrap()),
+        insert: None,
     };
     let out = apply_op_to_content(&out, &set_float).expect("set float");
     assert!(out.contains("ratio = 1.5"));
@@ -521,6 +1025,7 @@ fn apply_op_to_content_set_remove_and_json_values() {
             "items".to_string(),
         ],
         value: serde_json::json!(["a", true, 1, 1.5, {"ignored": true}]),
+        insert: None,
     };
     let out = apply_op_to_content(&out, &set_array).expect("set array");
     assert!(out.contains("items ="));
@@ -660,6 +1165,7 @@ fn execute_plan_from_contents_applies_only_allowed_and_fills_params() {
         OpKind::TomlSet {
             toml_path: vec!["package".to_string(), "name".to_string()],
             value: serde_json::Value::String("blocked".to_string()),
+            insert: None,
         },
         vec![],
     ));
@@ -675,6 +1181,7 @@ fn execute_plan_from_contents_applies_only_allowed_and_fills_params() {
                 "flag".to_string(),
             ],
             value: serde_json::Value::Bool(true),
+            insert: None,
         },
         vec!["ignored".to_string()],
     ));
@@ -691,7 +1198,16 @@ fn execute_plan_from_contents_applies_only_allowed_and_fills_params() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params,
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let mut before = BTreeMap::new();
@@ -700,13 +1216,100 @@ fn execute_plan_from_contents_applies_only_allowed_and_fills_params() {
         "[package]\nname = \"demo\"\n\n[dependencies]\ndep = { path = \"../dep\" }\n".to_string(),
     );
 
-    let changed =
+    let outcome =
         buildfix_edit::execute_plan_from_contents(&before, &plan, &opts).expect("execute");
+    let changed = outcome.changed();
     let out = changed.get(Utf8Path::new("Cargo.toml")).expect("changed");
     assert!(out.contains("edition = \"2021\""));
     assert!(out.contains("version = \"1.2.3\""));
     assert!(out.contains("flag = true"));
     assert!(!out.contains("name = \"blocked\""));
+
+    // Per-op results and summary match what a disk-based dry run reports.
+    assert_eq!(outcome.results.len(), 4);
+    assert_eq!(outcome.summary.attempted, 3);
+    assert_eq!(outcome.summary.blocked, 1);
+    assert_eq!(outcome.summary.files_modified, 1);
+    let blocked = outcome
+        .results
+        .iter()
+        .find(|r| r.op_id == "blocked")
+        .expect("blocked result");
+    assert_eq!(blocked.status, ApplyStatus::Blocked);
+    assert_eq!(
+        blocked.blocked_reason_token,
+        Some(BuildfixErrorCode::SafetyGuardedNotAllowed)
+    );
+    assert!(outcome.preconditions.verified);
+}
+
+#[test]
+fn execute_plan_from_contents_matches_op_by_op_edits_on_one_file() {
+    let set = |id: &str, path: &[&str], value: &str| {
+        make_op(
+            id,
+            "Cargo.toml",
+            SafetyClass::Safe,
+            false,
+            OpKind::TomlSet {
+                toml_path: path.iter().map(|s| s.to_string()).collect(),
+                value: serde_json::json!(value),
+                insert: None,
+            },
+            vec![],
+        )
+    };
+    let mut plan = base_plan();
+    plan.ops
+        .push(set("edition", &["package", "edition"], "2021"));
+    plan.ops
+        .push(set("license", &["package", "license"], "MIT"));
+    plan.ops.push(make_op(
+        "rename",
+        "Cargo.toml",
+        SafetyClass::Safe,
+        false,
+        OpKind::TextReplaceAnchored {
+            find: "name = \"demo\" # keep".to_string(),
+            replace: "name = \"renamed\" # keep".to_string(),
+            anchor_before: vec![],
+            anchor_after: vec![],
+            max_replacements: Some(1),
+        },
+        vec![],
+    ));
+    plan.ops
+        .push(set("edition_again", &["package", "edition"], "2021"));
+    plan.ops.push(set("dep", &["dependencies", "serde"], "1"));
+
+    let input = "# demo crate\n[package]\nname = \"demo\" # keep\n\n[dependencies]\n";
+    let mut before = BTreeMap::new();
+    before.insert(Utf8PathBuf::from("Cargo.toml"), input.to_string());
+    let opts = ApplyOptions {
+        dry_run: true,
+        ..ApplyOptions::default()
+    };
+
+    let outcome =
+        buildfix_edit::execute_plan_from_contents(&before, &plan, &opts).expect("execute");
+
+    let mut expected = input.to_string();
+    for op in &plan.ops {
+        expected = apply_op_to_content(&expected, &op.kind).expect("apply op");
+    }
+    assert!(expected.contains("name = \"renamed\" # keep"));
+    let changed = outcome.changed();
+    assert_eq!(changed[Utf8Path::new("Cargo.toml")], expected);
+
+    // Each changing op's hashes chain from the previous op's; the repeated
+    // edition set changes nothing and reports no file.
+    let files: Vec<_> = outcome.results.iter().map(|r| r.files.as_slice()).collect();
+    assert!(files[3].is_empty());
+    let changes: Vec<_> = files.iter().flat_map(|f| f.iter()).collect();
+    assert_eq!(changes.len(), 4);
+    for pair in changes.windows(2) {
+        assert_eq!(pair[1].sha256_before, pair[0].sha256_after);
+    }
 }
 
 #[test]
@@ -742,7 +1345,16 @@ fn apply_plan_handles_head_sha_mismatch() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
@@ -782,7 +1394,16 @@ fn apply_plan_allows_backup_enabled_without_dir() {
         backup_enabled: true,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
@@ -818,7 +1439,16 @@ fn apply_plan_supports_absolute_paths() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (_apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).expect("apply");
@@ -929,3 +1559,145 @@ fn apply_op_to_content_text_replace_anchored_no_match_is_noop() {
     let out = apply_op_to_content(input, &kind).expect("no-op");
     assert_eq!(out, input);
 }
+
+#[test]
+fn apply_op_to_content_toml_array_append_keeps_layout() {
+    let input =
+        "[workspace]\n# members\nmembers = [\n    \"crates/a\", # first\n    \"crates/b\"\n]\n";
+    let kind = OpKind::TomlArrayAppend {
+        toml_path: vec!["workspace".to_string(), "members".to_string()],
+        values: vec![serde_json::json!("crates/b"), serde_json::json!("crates/c")],
+        unique: true,
+    };
+
+    let out = apply_op_to_content(input, &kind).expect("append");
+    assert_eq!(
+        out,
+        "[workspace]\n# members\nmembers = [\n    \"crates/a\", # first\n    \"crates/b\",\n    \"crates/c\"\n]\n"
+    );
+
+    let inline = apply_op_to_content("[package]\nkeywords = [\"a\"]\n", &kind).expect("append");
+    assert!(inline.contains("keywords = [\"a\"]"));
+
+    let single = OpKind::TomlArrayAppend {
+        toml_path: vec!["package".to_string(), "keywords".to_string()],
+        values: vec![serde_json::json!("a"), serde_json::json!("b")],
+        unique: false,
+    };
+    let out = apply_op_to_content("[package]\nkeywords = [\"a\"]\n", &single).expect("append");
+    assert!(out.contains("keywords = [\"a\", \"a\", \"b\"]"));
+
+    let created = apply_op_to_content("[package]\n", &single).expect("create");
+    assert!(created.contains("keywords = [\"a\", \"b\"]"));
+
+    let err = apply_op_to_content("[package]\nkeywords = \"a\"\n", &single).expect_err("not array");
+    assert!(err.to_string().contains("package.keywords is not an array"));
+}
+
+#[test]
+fn apply_op_to_content_toml_merge_preserves_existing_keys() {
+    let input = "[dependencies]\nserde = { version = \"1\", default-features = false } # pinned\n\n[dependencies.tokio]\n# runtime\nversion = \"1\"\n";
+    let mut table = serde_json::Map::new();
+    table.insert("features".to_string(), serde_json::json!(["derive"]));
+    table.insert("version".to_string(), serde_json::json!("1.0.200"));
+    let kind = OpKind::TomlMerge {
+        toml_path: vec!["dependencies".to_string(), "serde".to_string()],
+        table: table.clone(),
+    };
+
+    let out = apply_op_to_content(input, &kind).expect("merge inline");
+    assert!(out.contains(
+        "serde = { version = \"1.0.200\", default-features = false, features = [\"derive\"] } # pinned"
+    ));
+
+    let kind = OpKind::TomlMerge {
+        toml_path: vec!["dependencies".to_string(), "tokio".to_string()],
+        table,
+    };
+    let out = apply_op_to_content(input, &kind).expect("merge table");
+    assert!(out.contains(
+        "[dependencies.tokio]\n# runtime\nversion = \"1.0.200\"\nfeatures = [\"derive\"]\n"
+    ));
+
+    let kind = OpKind::TomlMerge {
+        toml_path: vec!["dependencies".to_string(), "anyhow".to_string()],
+        table: serde_json::Map::from_iter([("version".to_string(), serde_json::json!("1"))]),
+    };
+    let out = apply_op_to_content(input, &kind).expect("merge missing");
+    assert!(out.contains("anyhow = { version = \"1\" }"));
+
+    let kind = OpKind::TomlMerge {
+        toml_path: vec!["package".to_string(), "name".to_string()],
+        table: serde_json::Map::new(),
+    };
+    let err = apply_op_to_content("[package]\nname = \"x\"\n", &kind).expect_err("not a table");
+    assert!(err.to_string().contains("package.name is not a table"));
+}
+
+#[test]
+fn apply_op_to_content_toml_set_honours_insert_hint() {
+    let input = "[workspace]\nmembers = [\"a\"]\nexclude = [\"b\"]\n";
+    let kind = OpKind::TomlSet {
+        toml_path: vec!["workspace".to_string(), "resolver".to_string()],
+        value: serde_json::json!("2"),
+        insert: Some(InsertHint {
+            position: Some(InsertPosition::After("members".to_string())),
+            comment: Some("added by buildfix (cargo.workspace_resolver_v2)".to_string()),
+        }),
+    };
+    let out = apply_op_to_content(input, &kind).expect("after");
+    assert_eq!(
+        out,
+        "[workspace]\nmembers = [\"a\"]\n# added by buildfix (cargo.workspace_resolver_v2)\nresolver = \"2\"\nexclude = [\"b\"]\n"
+    );
+
+    let kind = OpKind::TomlSet {
+        toml_path: vec!["workspace".to_string(), "resolver".to_string()],
+        value: serde_json::json!("2"),
+        insert: Some(InsertHint {
+            position: Some(InsertPosition::Top),
+            comment: None,
+        }),
+    };
+    let out = apply_op_to_content(input, &kind).expect("top");
+    assert!(out.starts_with("[workspace]\nresolver = \"2\"\nmembers"));
+
+    // Existing keys are updated in place and never re-annotated.
+    let out = apply_op_to_content("[workspace]\nresolver = \"1\"\nmembers = []\n", &kind)
+        .expect("existing");
+    assert_eq!(out, "[workspace]\nresolver = \"2\"\nmembers = []\n");
+
+    // A missing anchor leaves the key at the end.
+    let kind = OpKind::TomlSet {
+        toml_path: vec!["workspace".to_string(), "resolver".to_string()],
+        value: serde_json::json!("2"),
+        insert: Some(InsertHint {
+            position: Some(InsertPosition::After("missing".to_string())),
+            comment: None,
+        }),
+    };
+    let out = apply_op_to_content(input, &kind).expect("missing anchor");
+    assert!(out.ends_with("exclude = [\"b\"]\nresolver = \"2\"\n"));
+}
+
+#[test]
+fn apply_op_to_content_resolver_transform_accepts_insert_arg() {
+    let kind = OpKind::TomlTransform {
+        rule_id: "ensure_workspace_resolver_v2".to_string(),
+        args: Some(serde_json::json!({
+            "insert": { "position": "top", "comment": "added by buildfix (cargo.workspace_resolver_v2)" }
+        })),
+    };
+    let out = apply_op_to_content("[workspace]\nmembers = []\n", &kind).expect("transform");
+    assert_eq!(
+        out,
+        "[workspace]\n# added by buildfix (cargo.workspace_resolver_v2)\nresolver = \"2\"\nmembers = []\n"
+    );
+
+    let bad = OpKind::TomlTransform {
+        rule_id: "ensure_workspace_resolver_v2".to_string(),
+        args: Some(serde_json::json!({ "insert": { "position": 3 } })),
+    };
+    let err = apply_op_to_content("[workspace]\n", &bad).expect_err("invalid hint");
+    assert!(err.to_string().contains("invalid insert hint"));
+}
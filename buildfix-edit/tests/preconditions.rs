@@ -1,10 +1,16 @@
 //! Precondition validation tests.
 
-use buildfix_edit::{ApplyOptions, AttachPreconditionsOptions, apply_plan, attach_preconditions};
+use buildfix_edit::{
+    ApplyOptions, AttachPreconditionsOptions, FileSnapshot, apply_plan, attach_preconditions,
+    attach_preconditions_with_snapshot, preview_patch_with_snapshot,
+};
 use buildfix_types::apply::ApplyStatus;
+use buildfix_types::cancel::CancellationToken;
+use buildfix_types::clock::{Clock, Ids};
+use buildfix_types::error_code::BuildfixErrorCode;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
 use buildfix_types::plan::{
-    BuildfixPlan, FilePrecondition, PlanOp, PlanPolicy, Rationale, RepoInfo,
+    BuildfixPlan, FilePrecondition, PlanOp, PlanPolicy, PreconditionMode, Rationale, RepoInfo,
 };
 use buildfix_types::receipt::ToolInfo;
 use camino::Utf8PathBuf;
@@ -49,6 +55,8 @@ fn repo_info() -> RepoInfo {
         root: ".".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     }
 }
 
@@ -57,6 +65,7 @@ fn minimal_plan_with_preconditions(file_path: &str, expected_sha: &str) -> Build
     plan.preconditions.files.push(FilePrecondition {
         path: file_path.to_string(),
         sha256: expected_sha.to_string(),
+        semantic_sha256: None,
     });
     plan.ops.push(PlanOp {
         id: "test-op".to_string(),
@@ -66,6 +75,7 @@ fn minimal_plan_with_preconditions(file_path: &str, expected_sha: &str) -> Build
         blocked_reason_token: None,
         target: OpTarget {
             path: file_path.to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -78,6 +88,8 @@ fn minimal_plan_with_preconditions(file_path: &str, expected_sha: &str) -> Build
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
     plan
 }
@@ -99,7 +111,16 @@ fn test_matching_sha_allows_apply() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -126,7 +147,16 @@ fn test_sha_mismatch_blocks_apply() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -172,7 +202,16 @@ fn test_file_modified_after_plan_blocks_apply() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -198,6 +237,7 @@ fn test_attach_preconditions_computes_correct_sha() {
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -210,6 +250,8 @@ fn test_attach_preconditions_computes_correct_sha() {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
 
     attach_preconditions(&root, &mut plan, &AttachPreconditionsOptions::default()).unwrap();
@@ -219,6 +261,47 @@ fn test_attach_preconditions_computes_correct_sha() {
     assert_eq!(plan.preconditions.files[0].sha256, expected_sha);
 }
 
+#[test]
+fn test_snapshot_shares_one_read_between_attach_and_preview() {
+    let temp = create_temp_repo();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+
+    let contents = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    let expected_sha = sha256_hex(&contents);
+
+    let mut plan = minimal_plan_with_preconditions("Cargo.toml", "");
+    plan.preconditions.files.clear();
+
+    let mut snapshot = FileSnapshot::new(&root);
+    attach_preconditions_with_snapshot(
+        &mut snapshot,
+        &mut plan,
+        &AttachPreconditionsOptions::default(),
+    )
+    .unwrap();
+    assert_eq!(plan.preconditions.files[0].sha256, expected_sha);
+
+    // A change on disk after the snapshot was taken is not seen: the preview
+    // and the hashes describe the same bytes.
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[workspace]\nresolver = \"2\"\n",
+    )
+    .unwrap();
+
+    let opts = ApplyOptions {
+        dry_run: true,
+        ..ApplyOptions::default()
+    };
+    let patch = preview_patch_with_snapshot(&mut snapshot, &plan, &opts).unwrap();
+    assert!(patch.contains("+resolver = \"2\""), "{patch}");
+    assert!(patch.contains(" members = [\"crates/a\"]"), "{patch}");
+    assert_eq!(
+        snapshot.sha256("Cargo.toml".into()).unwrap(),
+        expected_sha.as_str()
+    );
+}
+
 #[test]
 fn test_dry_run_with_valid_preconditions_shows_skipped() {
     let temp = create_temp_repo();
@@ -237,7 +320,16 @@ fn test_dry_run_with_valid_preconditions_shows_skipped() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -276,10 +368,12 @@ version = "0.1.0"
     plan.preconditions.files.push(FilePrecondition {
         path: "Cargo.toml".to_string(),
         sha256: sha1,
+        semantic_sha256: None,
     });
     plan.preconditions.files.push(FilePrecondition {
         path: "crates/a/Cargo.toml".to_string(),
         sha256: sha2,
+        semantic_sha256: None,
     });
     plan.ops.push(PlanOp {
         id: "op1".to_string(),
@@ -289,6 +383,7 @@ version = "0.1.0"
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -301,6 +396,8 @@ version = "0.1.0"
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
     plan.ops.push(PlanOp {
         id: "op2".to_string(),
@@ -310,6 +407,7 @@ version = "0.1.0"
         blocked_reason_token: None,
         target: OpTarget {
             path: "crates/a/Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "set_package_rust_version".to_string(),
@@ -322,6 +420,8 @@ version = "0.1.0"
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
 
     let opts = ApplyOptions {
@@ -331,7 +431,16 @@ version = "0.1.0"
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -367,10 +476,12 @@ version = "0.1.0"
     plan.preconditions.files.push(FilePrecondition {
         path: "Cargo.toml".to_string(),
         sha256: sha1,
+        semantic_sha256: None,
     });
     plan.preconditions.files.push(FilePrecondition {
         path: "crates/a/Cargo.toml".to_string(),
         sha256: wrong_sha2.to_string(),
+        semantic_sha256: None,
     });
     plan.ops.push(PlanOp {
         id: "op1".to_string(),
@@ -380,6 +491,7 @@ version = "0.1.0"
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -392,6 +504,8 @@ version = "0.1.0"
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
     plan.ops.push(PlanOp {
         id: "op2".to_string(),
@@ -401,6 +515,7 @@ version = "0.1.0"
         blocked_reason_token: None,
         target: OpTarget {
             path: "crates/a/Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "set_package_rust_version".to_string(),
@@ -413,6 +528,8 @@ version = "0.1.0"
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
 
     let opts = ApplyOptions {
@@ -422,7 +539,16 @@ version = "0.1.0"
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -451,6 +577,7 @@ fn test_empty_preconditions_allows_apply() {
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -463,6 +590,8 @@ fn test_empty_preconditions_allows_apply() {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
 
     let opts = ApplyOptions {
@@ -472,7 +601,16 @@ fn test_empty_preconditions_allows_apply() {
         backup_enabled: false,
         backup_dir: None,
         backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
         params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
     };
 
     let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
@@ -480,3 +618,154 @@ fn test_empty_preconditions_allows_apply() {
     // Empty preconditions should be verified (nothing to check)
     assert!(apply.preconditions.verified);
 }
+
+#[test]
+fn test_pinned_op_target_drift_blocks_only_that_op() {
+    let temp = create_temp_repo();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    fs::create_dir_all(temp.path().join("crates/a")).unwrap();
+    fs::write(
+        temp.path().join("crates/a/Cargo.toml"),
+        "[package]\nname = \"a\"\n",
+    )
+    .unwrap();
+
+    let mut plan = minimal_plan_with_preconditions("Cargo.toml", "unused");
+    let mut member_op = plan.ops[0].clone();
+    member_op.id = "member-op".to_string();
+    member_op.target.path = "crates/a/Cargo.toml".to_string();
+    member_op.kind = OpKind::TomlTransform {
+        rule_id: "set_package_edition".to_string(),
+        args: Some(serde_json::json!({ "edition": "2021" })),
+    };
+    plan.ops.push(member_op);
+
+    attach_preconditions(
+        &root,
+        &mut plan,
+        &AttachPreconditionsOptions {
+            include_git_head: false,
+            pin_op_targets: true,
+            mode: Default::default(),
+        },
+    )
+    .unwrap();
+    assert!(plan.ops.iter().all(|op| op.target.sha256.is_some()));
+
+    // Drift the member manifest after planning.
+    fs::write(
+        temp.path().join("crates/a/Cargo.toml"),
+        "[package]\nname = \"a\"\nversion = \"0.1.0\"\n",
+    )
+    .unwrap();
+
+    let opts = ApplyOptions {
+        dry_run: false,
+        allow_guarded: false,
+        allow_unsafe: false,
+        backup_enabled: false,
+        backup_dir: None,
+        backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
+        params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
+    };
+
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
+
+    assert!(apply.preconditions.verified);
+    assert_eq!(apply.summary.applied, 1);
+    assert_eq!(apply.summary.blocked, 1);
+    assert_eq!(apply.results[0].status, ApplyStatus::Applied);
+    assert_eq!(apply.results[1].status, ApplyStatus::Blocked);
+    assert_eq!(
+        apply.results[1].blocked_reason_token,
+        Some(BuildfixErrorCode::TargetDrifted)
+    );
+
+    let root_manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    assert!(root_manifest.contains("resolver = \"2\""));
+    let member = fs::read_to_string(temp.path().join("crates/a/Cargo.toml")).unwrap();
+    assert!(!member.contains("edition"));
+}
+
+#[test]
+fn test_semantic_mode_tolerates_formatting_but_not_value_changes() {
+    let semantic_plan = |root: &Utf8PathBuf| {
+        let mut plan = minimal_plan_with_preconditions("Cargo.toml", "");
+        plan.preconditions.files.clear();
+        attach_preconditions(
+            root,
+            &mut plan,
+            &AttachPreconditionsOptions {
+                mode: PreconditionMode::Semantic,
+                ..Default::default()
+            },
+        )
+        .unwrap();
+        plan
+    };
+    let opts = ApplyOptions {
+        dry_run: false,
+        allow_guarded: false,
+        allow_unsafe: false,
+        backup_enabled: false,
+        backup_dir: None,
+        backup_suffix: ".bak".to_string(),
+        backup_keep_last: None,
+        params: HashMap::new(),
+        lock_path: None,
+        binary_patch: false,
+        durable_writes: false,
+        selected_ops: None,
+        cancel: CancellationToken::new(),
+        clock: Clock::default(),
+        ids: Ids::default(),
+        jobs: 0,
+    };
+
+    // Reformatted and commented after planning: a soft match, and the op runs.
+    let temp = create_temp_repo();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    let plan = semantic_plan(&root);
+    assert_eq!(plan.preconditions.mode, PreconditionMode::Semantic);
+    assert!(plan.preconditions.files[0].semantic_sha256.is_some());
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "# workspace root\n[workspace]\nmembers = [\n  \"crates/a\",\n]\n",
+    )
+    .unwrap();
+
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
+    assert!(apply.preconditions.verified);
+    assert!(apply.preconditions.mismatches.is_empty());
+    assert_eq!(apply.preconditions.soft_matches.len(), 1);
+    assert_eq!(apply.preconditions.soft_matches[0].path, "Cargo.toml");
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    assert!(manifest.starts_with("# workspace root\n"));
+    assert!(manifest.contains("resolver = \"2\""));
+
+    // A changed value still blocks.
+    let temp = create_temp_repo();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    let plan = semantic_plan(&root);
+    fs::write(
+        temp.path().join("Cargo.toml"),
+        "[workspace]\nmembers = [\"crates/b\"]\n",
+    )
+    .unwrap();
+
+    let (apply, _patch) = apply_plan(&root, &plan, tool_info(), &opts).unwrap();
+    assert!(!apply.preconditions.verified);
+    assert_eq!(apply.preconditions.mismatches.len(), 1);
+    assert!(apply.preconditions.soft_matches.is_empty());
+    let manifest = fs::read_to_string(temp.path().join("Cargo.toml")).unwrap();
+    assert!(!manifest.contains("resolver"));
+}
@@ -1,31 +1,101 @@
-use anyhow::Result;
+use anyhow::{Context, Result};
 use buildfix_receipts::LoadedReceipt;
 use buildfix_types::ops::SafetyClass;
-use buildfix_types::plan::FindingRef;
+use buildfix_types::plan::{FindingRef, ScanPolicy};
+use buildfix_types::receipt::Severity;
 use serde::Serialize;
 
+mod workspace_graph;
+
+pub use workspace_graph::{WorkspaceDependency, WorkspaceGraph, WorkspacePackage};
+
+/// Trigger pattern for a fixer.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct TriggerPattern {
+    /// Sensor/tool name (e.g., "builddiag", "depguard").
+    pub sensor: &'static str,
+    /// Check ID pattern (e.g., "workspace.resolver_v2").
+    pub check_id: &'static str,
+    /// Optional code pattern (e.g., "missing_version").
+    pub code: Option<&'static str>,
+}
+
 /// Metadata describing a fixer for listing/documentation.
+///
+/// The single source for `buildfix explain` and `list-fixes`: a fixer that
+/// fills this in shows up there without further registration.
 #[derive(Debug, Clone, Serialize)]
 pub struct FixerMeta {
     /// Unique key for this fixer (e.g., "cargo.workspace_resolver_v2").
     pub fix_key: &'static str,
+    /// User-facing CLI key (e.g., "resolver-v2").
+    pub key: &'static str,
+    /// Human-readable title.
+    pub title: &'static str,
     /// Brief human-readable description.
     pub description: &'static str,
+    /// Detailed description of what the fix does.
+    pub details: &'static str,
     /// Safety classification for this fixer's ops.
     pub safety: SafetyClass,
+    /// Why the fix has its safety class.
+    pub safety_rationale: &'static str,
+    /// Guidance for making the change by hand or when the fix is blocked.
+    pub remediation: &'static str,
     /// Tool prefixes consumed by this fixer's checks.
     pub consumes_sensors: &'static [&'static str],
     /// Check IDs consumed by this fixer's checks.
     pub consumes_check_ids: &'static [&'static str],
+    /// Sensor findings that trigger this fixer.
+    pub triggers: &'static [TriggerPattern],
 }
 
-/// Shared repository view used by all fixers.
-pub trait RepoView {
+/// Shared repository view used by all fixers. Fixers run concurrently, so
+/// the view is shared across threads.
+pub trait RepoView: Sync {
     fn root(&self) -> &camino::Utf8Path;
 
     fn read_to_string(&self, rel: &camino::Utf8Path) -> Result<String>;
 
     fn exists(&self, rel: &camino::Utf8Path) -> bool;
+
+    /// `rel` parsed as TOML. Views may cache the document, so fixers sharing
+    /// a manifest parse it once per run; clone it to edit. A parse failure
+    /// keeps the [`toml_edit::TomlError`] as its source.
+    fn read_toml(&self, rel: &camino::Utf8Path) -> Result<std::sync::Arc<toml_edit::DocumentMut>> {
+        let contents = self.read_to_string(rel)?;
+        let doc = contents
+            .parse::<toml_edit::DocumentMut>()
+            .with_context(|| format!("parse {}", rel))?;
+        Ok(std::sync::Arc::new(doc))
+    }
+
+    /// Whether the repo's `.gitignore` files exclude `rel`. Views that do
+    /// not know (the default) report nothing ignored.
+    fn is_gitignored(&self, _rel: &camino::Utf8Path) -> bool {
+        false
+    }
+}
+
+/// Whether `scan` keeps planning away from `path` (repo-relative): it lies
+/// under a skipped directory, inside a vendored crate, or is gitignored.
+///
+/// Fixers that enumerate manifests themselves should skip these paths too.
+pub fn skipped_by_scan(scan: &ScanPolicy, repo: &dyn RepoView, path: &camino::Utf8Path) -> bool {
+    let path = path.strip_prefix("./").unwrap_or(path);
+    // Directories containing `path`, innermost first; the repo root itself
+    // is never skipped.
+    let mut dirs = path.ancestors().skip(1).filter(|d| !d.as_str().is_empty());
+    if dirs.clone().any(|d| {
+        d.file_name()
+            .is_some_and(|name| scan.skip_dirs.iter().any(|s| s == name))
+    }) {
+        return true;
+    }
+    if scan.vendored && dirs.any(|d| repo.exists(&d.join(".cargo-checksum.json"))) {
+        return true;
+    }
+    scan.gitignore && repo.is_gitignored(path)
 }
 
 /// Shared planning input passed into fixers.
@@ -33,6 +103,8 @@ pub trait RepoView {
 pub struct PlannerConfig {
     pub allow: Vec<String>,
     pub deny: Vec<String>,
+    /// Allow/deny and safety limits for ops under matching target paths.
+    pub path_policies: Vec<buildfix_types::plan::PathPolicy>,
     pub allow_guarded: bool,
     pub allow_unsafe: bool,
     pub allow_dirty: bool,
@@ -40,6 +112,50 @@ pub struct PlannerConfig {
     pub max_files: Option<u64>,
     pub max_patch_bytes: Option<u64>,
     pub params: std::collections::HashMap<String, String>,
+    /// `[fixers.<fix_key>]` overrides, keyed by [`FixerMeta::fix_key`].
+    pub fixers: std::collections::BTreeMap<String, FixerConfig>,
+    /// Findings below this severity are dropped before fixers see them.
+    pub min_severity: Option<Severity>,
+    /// Sensors (receipt sensor id or tool name) whose findings are kept;
+    /// empty keeps every sensor.
+    pub sensors: Vec<String>,
+    /// Check ids whose findings are dropped.
+    pub exclude_check_ids: Vec<String>,
+    /// Repo-relative paths in scope for a changed-files-only plan; findings
+    /// located elsewhere, or without a location, are dropped. `None` keeps all.
+    pub changed_paths: Option<std::collections::BTreeSet<String>>,
+    /// Keep only ops in workspace members whose name matches one of these
+    /// globs; empty keeps every member and the workspace root.
+    pub packages: Vec<String>,
+    /// Drop ops in workspace members whose name matches one of these globs.
+    pub exclude_packages: Vec<String>,
+    /// Paths no op may target: build output, VCS metadata, vendored and
+    /// gitignored files.
+    pub scan: ScanPolicy,
+}
+
+/// Per-fixer overrides from `[fixers.<fix_key>]`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FixerConfig {
+    /// `false` skips the fixer entirely.
+    pub enabled: bool,
+    /// Safety class for every op the fixer emits. A class less strict than
+    /// the fixer's own is rejected unless `force` is set.
+    pub safety: Option<SafetyClass>,
+    pub force: bool,
+    /// Params for this fixer's ops, taking precedence over the global ones.
+    pub params: std::collections::HashMap<String, String>,
+}
+
+impl Default for FixerConfig {
+    fn default() -> Self {
+        Self {
+            enabled: true,
+            safety: None,
+            force: false,
+            params: std::collections::HashMap::new(),
+        }
+    }
 }
 
 #[derive(Debug, Clone)]
@@ -47,10 +163,13 @@ pub struct PlanContext {
     pub repo_root: camino::Utf8PathBuf,
     pub artifacts_dir: camino::Utf8PathBuf,
     pub config: PlannerConfig,
+    /// Workspace graph from a `cargo metadata` snapshot, when one was provided.
+    pub workspace_graph: Option<WorkspaceGraph>,
 }
 
-/// Contract each fixer implements.
-pub trait Fixer {
+/// Contract each fixer implements. The planner runs fixers on worker
+/// threads, so implementations must be `Send + Sync`.
+pub trait Fixer: Send + Sync {
     fn meta(&self) -> FixerMeta;
 
     fn plan(
@@ -84,6 +203,9 @@ pub struct ReceiptRecord {
     #[allow(dead_code)]
     pub sensor_id: String,
     pub path: camino::Utf8PathBuf,
+    /// Every receipt merged into this record, in path order. Holds just
+    /// `path` unless several runs of the same tool were found.
+    pub paths: Vec<camino::Utf8PathBuf>,
     pub envelope: buildfix_types::receipt::ReceiptEnvelope,
 }
 
@@ -94,18 +216,93 @@ pub struct ReceiptSet {
 }
 
 impl ReceiptSet {
+    /// Build the set from loaded receipts, skipping ones that failed to load.
+    ///
+    /// Receipts produced by the same tool (e.g. per-shard CI runs) are merged
+    /// into one record so fixers see each finding once.
     pub fn from_loaded(loaded: &[LoadedReceipt]) -> Self {
-        let mut receipts = Vec::new();
-        for r in loaded {
-            if let Ok(env) = &r.receipt {
-                receipts.push(ReceiptRecord {
+        let mut ok: Vec<&LoadedReceipt> = loaded.iter().filter(|r| r.receipt.is_ok()).collect();
+        ok.sort_by(|a, b| a.path.cmp(&b.path));
+
+        let mut receipts: Vec<ReceiptRecord> = Vec::new();
+        for r in ok {
+            let Ok(env) = &r.receipt else { continue };
+            match receipts
+                .iter_mut()
+                .find(|rec| rec.envelope.tool.name == env.tool.name)
+            {
+                Some(rec) => {
+                    rec.paths.push(r.path.clone());
+                    merge_envelope(&mut rec.envelope, env);
+                }
+                None => receipts.push(ReceiptRecord {
                     sensor_id: r.sensor_id.clone(),
                     path: r.path.clone(),
+                    paths: vec![r.path.clone()],
                     envelope: env.clone(),
-                });
+                }),
             }
         }
-        receipts.sort_by(|a, b| a.path.cmp(&b.path));
+        Self { receipts }
+    }
+
+    /// Drop findings filtered out by `min_severity`, `sensors` and
+    /// `exclude_check_ids`. Returns how many were dropped.
+    pub fn retain_findings(&mut self, config: &PlannerConfig) -> usize {
+        let mut dropped = 0;
+        for r in &mut self.receipts {
+            let sensor_ok = config.sensors.is_empty()
+                || config
+                    .sensors
+                    .iter()
+                    .any(|s| *s == r.sensor_id || *s == r.envelope.tool.name);
+            let before = r.envelope.findings.len();
+            r.envelope.findings.retain(|f| {
+                sensor_ok
+                    && config.min_severity.is_none_or(|min| f.severity >= min)
+                    && !f
+                        .check_id
+                        .as_ref()
+                        .is_some_and(|c| config.exclude_check_ids.contains(c))
+                    && config.changed_paths.as_ref().is_none_or(|paths| {
+                        f.location.as_ref().is_some_and(|loc| {
+                            let path = loc.path.as_str().replace('\\', "/");
+                            paths.contains(path.trim_start_matches("./"))
+                        })
+                    })
+            });
+            dropped += before - r.envelope.findings.len();
+        }
+        dropped
+    }
+
+    /// The set as seen from a sub-directory: located findings are kept when
+    /// `rebase` maps their path, and moved to the path it returns. Findings
+    /// without a location are kept. Fingerprints are pinned first, so they
+    /// still match the repo-relative findings.
+    pub fn rebased(
+        &self,
+        rebase: impl Fn(&camino::Utf8Path) -> Option<camino::Utf8PathBuf>,
+    ) -> Self {
+        let mut receipts = self.receipts.clone();
+        for r in &mut receipts {
+            let source = r.envelope.tool.name.clone();
+            r.envelope.findings.retain_mut(|f| {
+                let Some(loc) = &f.location else {
+                    return true;
+                };
+                let path = loc.path.as_str().replace('\\', "/");
+                let Some(path) = rebase(camino::Utf8Path::new(path.trim_start_matches("./")))
+                else {
+                    return false;
+                };
+                f.fingerprint = Some(f.stable_fingerprint(&source));
+                if let Some(loc) = &mut f.location {
+                    loc.path = path;
+                }
+                true
+            });
+        }
         Self { receipts }
     }
 
@@ -165,7 +362,8 @@ impl ReceiptSet {
                         code: f.code.clone().unwrap_or_else(|| "-".to_string()),
                         path: f.location.as_ref().map(|loc| loc.path.to_string()),
                         line: f.location.as_ref().and_then(|loc| loc.line),
-                        fingerprint: f.fingerprint.clone(),
+                        fingerprint: Some(f.stable_fingerprint(tool)),
+                        severity: Some(f.severity),
                     },
                     data: f.data.clone(),
                     confidence: f.confidence,
@@ -180,6 +378,76 @@ impl ReceiptSet {
     }
 }
 
+/// Fold `other` into `into`: findings are appended unless one with the same
+/// fingerprint is already present, verdicts keep the worst status, and
+/// capabilities are unioned.
+fn merge_envelope(
+    into: &mut buildfix_types::receipt::ReceiptEnvelope,
+    other: &buildfix_types::receipt::ReceiptEnvelope,
+) {
+    use buildfix_types::receipt::VerdictStatus;
+
+    let source = into.tool.name.clone();
+    let mut seen: std::collections::BTreeSet<String> = into
+        .findings
+        .iter()
+        .map(|f| f.stable_fingerprint(&source))
+        .collect();
+    for f in &other.findings {
+        if seen.insert(f.stable_fingerprint(&source)) {
+            into.findings.push(f.clone());
+        }
+    }
+
+    let rank = |s: VerdictStatus| match s {
+        VerdictStatus::Unknown => 0,
+        VerdictStatus::Pass => 1,
+        VerdictStatus::Warn => 2,
+        VerdictStatus::Fail => 3,
+    };
+    if rank(other.verdict.status) > rank(into.verdict.status) {
+        into.verdict.status = other.verdict.status;
+    }
+    for reason in &other.verdict.reasons {
+        if !into.verdict.reasons.contains(reason) {
+            into.verdict.reasons.push(reason.clone());
+        }
+    }
+    let counts = &mut into.verdict.counts;
+    counts.findings = into.findings.len() as u64;
+    counts.errors = into
+        .findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count() as u64;
+    counts.warnings = into
+        .findings
+        .iter()
+        .filter(|f| f.severity == Severity::Warn)
+        .count() as u64;
+
+    match (&mut into.capabilities, &other.capabilities) {
+        (Some(caps), Some(more)) => {
+            for id in &more.check_ids {
+                if !caps.check_ids.contains(id) {
+                    caps.check_ids.push(id.clone());
+                }
+            }
+            for scope in &more.scopes {
+                if !caps.scopes.contains(scope) {
+                    caps.scopes.push(scope.clone());
+                }
+            }
+            caps.partial |= more.partial;
+            if caps.reason.is_none() {
+                caps.reason = more.reason.clone();
+            }
+        }
+        (None, Some(more)) => into.capabilities = Some(more.clone()),
+        _ => {}
+    }
+}
+
 fn stable_finding_key(f: &FindingRef) -> String {
     let loc = f
         .path
@@ -247,6 +515,7 @@ mod tests {
             path: "artifacts/cargo-deny/report.json".into(),
             sensor_id: "cargo-deny".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         let set = ReceiptSet::from_loaded(&loaded);
 
@@ -261,6 +530,7 @@ mod tests {
             path: "artifacts/cargo-deny/report.json".into(),
             sensor_id: "cargo-deny".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         let set = ReceiptSet::from_loaded(&loaded);
 
@@ -276,6 +546,7 @@ mod tests {
             path: "artifacts/depguard/report.json".into(),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         let set = ReceiptSet::from_loaded(&loaded);
 
@@ -307,6 +578,7 @@ mod tests {
             path: "artifacts/cargo-deny/report.json".into(),
             sensor_id: "cargo-deny".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         let set = ReceiptSet::from_loaded(&loaded);
 
@@ -324,6 +596,7 @@ mod tests {
                 receipt: Err(buildfix_receipts::ReceiptLoadError::Io {
                     message: "not found".to_string(),
                 }),
+                signature: None,
             },
             buildfix_receipts::LoadedReceipt {
                 path: "artifacts/depguard/report.json".into(),
@@ -332,6 +605,7 @@ mod tests {
                     "depguard",
                     vec![make_finding("deps.path_requires_version", None)],
                 )),
+                signature: None,
             },
         ];
         let set = ReceiptSet::from_loaded(&loaded);
@@ -361,6 +635,7 @@ mod tests {
             path: "artifacts/test-tool/report.json".into(),
             sensor_id: "test-tool".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         let set = ReceiptSet::from_loaded(&loaded);
 
@@ -371,4 +646,60 @@ mod tests {
             "value"
         );
     }
+
+    struct ScanRepo {
+        files: Vec<&'static str>,
+        ignored: Vec<&'static str>,
+    }
+
+    impl RepoView for ScanRepo {
+        fn root(&self) -> &camino::Utf8Path {
+            camino::Utf8Path::new(".")
+        }
+
+        fn read_to_string(&self, rel: &camino::Utf8Path) -> Result<String> {
+            anyhow::bail!("no contents for {}", rel)
+        }
+
+        fn exists(&self, rel: &camino::Utf8Path) -> bool {
+            self.files.contains(&rel.as_str())
+        }
+
+        fn is_gitignored(&self, rel: &camino::Utf8Path) -> bool {
+            self.ignored.contains(&rel.as_str())
+        }
+    }
+
+    #[test]
+    fn scan_skips_build_output_vendored_and_gitignored_paths() {
+        let repo = ScanRepo {
+            files: vec!["vendor/serde/.cargo-checksum.json"],
+            ignored: vec!["scratch/Cargo.toml"],
+        };
+        let scan = ScanPolicy::default();
+        let skipped = |p: &str| skipped_by_scan(&scan, &repo, camino::Utf8Path::new(p));
+
+        assert!(skipped("target/package/demo-0.1.0/Cargo.toml"));
+        assert!(skipped("crates/a/target/Cargo.toml"));
+        assert!(skipped(".git/config"));
+        assert!(skipped("vendor/serde/Cargo.toml"));
+        assert!(skipped("./scratch/Cargo.toml"));
+
+        assert!(!skipped("Cargo.toml"));
+        assert!(!skipped("crates/target-spec/Cargo.toml"));
+        assert!(!skipped("crates/a/target"));
+
+        let off = ScanPolicy {
+            skip_dirs: vec![],
+            gitignore: false,
+            vendored: false,
+        };
+        for path in [
+            "target/Cargo.toml",
+            "vendor/serde/Cargo.toml",
+            "scratch/Cargo.toml",
+        ] {
+            assert!(!skipped_by_scan(&off, &repo, camino::Utf8Path::new(path)));
+        }
+    }
 }
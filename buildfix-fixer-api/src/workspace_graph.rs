@@ -0,0 +1,200 @@
+//! Workspace graph parsed from a `cargo metadata --format-version 1` snapshot.
+//!
+//! The planner accepts an optional snapshot so fixers can query members,
+//! resolved versions, and features without re-walking manifests.
+
+use anyhow::Context;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Deserialize;
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Resolved workspace graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceGraph {
+    /// Absolute workspace root as reported by cargo.
+    pub workspace_root: Utf8PathBuf,
+    /// Package ids of workspace members, in cargo's order.
+    pub members: Vec<String>,
+    /// Every package in the snapshot, keyed by package id.
+    pub packages: BTreeMap<String, WorkspacePackage>,
+}
+
+/// One package in the graph.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspacePackage {
+    pub id: String,
+    pub name: String,
+    pub version: String,
+    /// Manifest path, relative to the workspace root when it lives inside it.
+    pub manifest_path: Utf8PathBuf,
+    /// Registry or git source; `None` for path packages.
+    pub source: Option<String>,
+    /// Feature table declared by the package.
+    pub features: BTreeMap<String, Vec<String>>,
+    /// Features enabled by the resolver. Empty when the snapshot was taken
+    /// with `--no-deps`.
+    pub enabled_features: Vec<String>,
+    /// Dependencies as declared in the manifest.
+    pub dependencies: Vec<WorkspaceDependency>,
+    /// Package ids the resolver picked for this package's dependencies.
+    pub resolved_dependencies: Vec<String>,
+}
+
+/// A dependency declaration.
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct WorkspaceDependency {
+    pub name: String,
+    pub req: String,
+    /// `None` for normal dependencies, otherwise `dev` or `build`.
+    pub kind: Option<String>,
+    pub optional: bool,
+    pub rename: Option<String>,
+    pub path: Option<Utf8PathBuf>,
+}
+
+impl WorkspaceGraph {
+    /// Parse `cargo metadata --format-version 1` JSON.
+    pub fn from_cargo_metadata(json: &str) -> anyhow::Result<Self> {
+        let raw: RawMetadata = serde_json::from_str(json).context("parse cargo metadata JSON")?;
+
+        let mut resolved: BTreeMap<String, RawNode> = raw
+            .resolve
+            .map(|r| r.nodes.into_iter().map(|n| (n.id.clone(), n)).collect())
+            .unwrap_or_default();
+
+        let mut packages = BTreeMap::new();
+        for pkg in raw.packages {
+            let node = resolved.remove(&pkg.id).unwrap_or_default();
+            let manifest_path = pkg
+                .manifest_path
+                .strip_prefix(&raw.workspace_root)
+                .map(Utf8Path::to_path_buf)
+                .unwrap_or(pkg.manifest_path);
+
+            packages.insert(
+                pkg.id.clone(),
+                WorkspacePackage {
+                    id: pkg.id,
+                    name: pkg.name,
+                    version: pkg.version,
+                    manifest_path,
+                    source: pkg.source,
+                    features: pkg.features,
+                    enabled_features: node.features,
+                    dependencies: pkg
+                        .dependencies
+                        .into_iter()
+                        .map(|d| WorkspaceDependency {
+                            name: d.name,
+                            req: d.req,
+                            kind: d.kind,
+                            optional: d.optional,
+                            rename: d.rename,
+                            path: d.path,
+                        })
+                        .collect(),
+                    resolved_dependencies: node.dependencies,
+                },
+            );
+        }
+
+        Ok(Self {
+            workspace_root: raw.workspace_root,
+            members: raw.workspace_members,
+            packages,
+        })
+    }
+
+    /// Workspace member packages, in cargo's member order.
+    pub fn member_packages(&self) -> impl Iterator<Item = &WorkspacePackage> {
+        self.members.iter().filter_map(|id| self.packages.get(id))
+    }
+
+    /// The member whose manifest is at `manifest` (relative to the workspace root).
+    pub fn member_by_manifest(&self, manifest: &Utf8Path) -> Option<&WorkspacePackage> {
+        self.member_packages()
+            .find(|pkg| pkg.manifest_path == manifest)
+    }
+
+    /// Distinct versions of `name` present in the graph, in ascending string order.
+    pub fn resolved_versions(&self, name: &str) -> Vec<&str> {
+        self.packages
+            .values()
+            .filter(|pkg| pkg.name == name)
+            .map(|pkg| pkg.version.as_str())
+            .collect::<BTreeSet<_>>()
+            .into_iter()
+            .collect()
+    }
+
+    /// Package names that resolve to more than one version.
+    pub fn duplicate_versions(&self) -> BTreeMap<&str, Vec<&str>> {
+        let mut by_name: BTreeMap<&str, BTreeSet<&str>> = BTreeMap::new();
+        for pkg in self.packages.values() {
+            by_name
+                .entry(pkg.name.as_str())
+                .or_default()
+                .insert(pkg.version.as_str());
+        }
+        by_name
+            .into_iter()
+            .filter(|(_, versions)| versions.len() > 1)
+            .map(|(name, versions)| (name, versions.into_iter().collect()))
+            .collect()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct RawMetadata {
+    #[serde(default)]
+    packages: Vec<RawPackage>,
+    #[serde(default)]
+    workspace_members: Vec<String>,
+    #[serde(default)]
+    resolve: Option<RawResolve>,
+    workspace_root: Utf8PathBuf,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawPackage {
+    id: String,
+    name: String,
+    version: String,
+    manifest_path: Utf8PathBuf,
+    #[serde(default)]
+    source: Option<String>,
+    #[serde(default)]
+    features: BTreeMap<String, Vec<String>>,
+    #[serde(default)]
+    dependencies: Vec<RawDependency>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawDependency {
+    name: String,
+    #[serde(default)]
+    req: String,
+    #[serde(default)]
+    kind: Option<String>,
+    #[serde(default)]
+    optional: bool,
+    #[serde(default)]
+    rename: Option<String>,
+    #[serde(default)]
+    path: Option<Utf8PathBuf>,
+}
+
+#[derive(Debug, Deserialize)]
+struct RawResolve {
+    #[serde(default)]
+    nodes: Vec<RawNode>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct RawNode {
+    id: String,
+    #[serde(default)]
+    dependencies: Vec<String>,
+    #[serde(default)]
+    features: Vec<String>,
+}
@@ -9,11 +9,14 @@
 //! - MatchedFinding fields and construction
 //! - ReceiptSet edge cases and additional scenarios
 //! - SafetyClass integration
+//! - WorkspaceGraph parsing from cargo metadata
 
 use buildfix_fixer_api::{
     Fixer, FixerMeta, MatchedFinding, PlanContext, PlannerConfig, ReceiptSet, RepoView,
+    WorkspaceGraph,
 };
 use buildfix_receipts::LoadedReceipt;
+use buildfix_types::error_code::BuildfixErrorCode;
 use buildfix_types::ops::{OpKind, OpPreview, OpTarget, SafetyClass};
 use buildfix_types::plan::{FindingRef, PlanOp, Rationale};
 use buildfix_types::receipt::{Finding, Location, ReceiptEnvelope, Severity, ToolInfo};
@@ -72,10 +75,16 @@ impl MockFixer {
         Self {
             meta: FixerMeta {
                 fix_key,
+                key: "",
+                title: "",
                 description: "Mock fixer for testing",
+                details: "",
                 safety,
+                safety_rationale: "",
+                remediation: "",
                 consumes_sensors: &[],
                 consumes_check_ids: &[],
+                triggers: &[],
             },
             ops: vec![],
         }
@@ -151,10 +160,12 @@ fn make_plan_op(id: &str, safety: SafetyClass) -> PlanOp {
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlSet {
             toml_path: vec!["workspace".to_string(), "resolver".to_string()],
             value: serde_json::json!("2"),
+            insert: None,
         },
         rationale: Rationale {
             fix_key: "test.fix".to_string(),
@@ -163,6 +174,8 @@ fn make_plan_op(id: &str, safety: SafetyClass) -> PlanOp {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     }
 }
 
@@ -174,6 +187,7 @@ fn make_finding_ref(source: &str, code: &str) -> FindingRef {
         path: Some("Cargo.toml".to_string()),
         line: Some(1),
         fingerprint: None,
+        severity: None,
     }
 }
 
@@ -185,10 +199,16 @@ fn make_finding_ref(source: &str, code: &str) -> FindingRef {
 fn test_fixer_meta_creation() {
     let meta = FixerMeta {
         fix_key: "cargo.workspace_resolver_v2",
+        key: "",
+        title: "",
         description: "Sets workspace resolver to version 2",
+        details: "",
         safety: SafetyClass::Safe,
+        safety_rationale: "",
+        remediation: "",
         consumes_sensors: &["cargo-deny", "cargo-outdated"],
         consumes_check_ids: &["workspace.resolver"],
+        triggers: &[],
     };
 
     assert_eq!(meta.fix_key, "cargo.workspace_resolver_v2");
@@ -202,10 +222,16 @@ fn test_fixer_meta_creation() {
 fn test_fixer_meta_serialization() {
     let meta = FixerMeta {
         fix_key: "test.fixer",
+        key: "",
+        title: "",
         description: "Test fixer",
+        details: "",
         safety: SafetyClass::Guarded,
+        safety_rationale: "",
+        remediation: "",
         consumes_sensors: &[],
         consumes_check_ids: &[],
+        triggers: &[],
     };
 
     let json = serde_json::to_string(&meta).expect("Should serialize to JSON");
@@ -218,10 +244,16 @@ fn test_fixer_meta_serialization() {
 fn test_fixer_meta_with_all_safety_classes() {
     let safe_meta = FixerMeta {
         fix_key: "safe.op",
+        key: "",
+        title: "",
         description: "Safe operation",
+        details: "",
         safety: SafetyClass::Safe,
+        safety_rationale: "",
+        remediation: "",
         consumes_sensors: &[],
         consumes_check_ids: &[],
+        triggers: &[],
     };
     assert!(safe_meta.safety.is_safe());
     assert!(!safe_meta.safety.is_guarded());
@@ -229,10 +261,16 @@ fn test_fixer_meta_with_all_safety_classes() {
 
     let guarded_meta = FixerMeta {
         fix_key: "guarded.op",
+        key: "",
+        title: "",
         description: "Guarded operation",
+        details: "",
         safety: SafetyClass::Guarded,
+        safety_rationale: "",
+        remediation: "",
         consumes_sensors: &[],
         consumes_check_ids: &[],
+        triggers: &[],
     };
     assert!(!guarded_meta.safety.is_safe());
     assert!(guarded_meta.safety.is_guarded());
@@ -240,10 +278,16 @@ fn test_fixer_meta_with_all_safety_classes() {
 
     let unsafe_meta = FixerMeta {
         fix_key: "unsafe.op",
+        key: "",
+        title: "",
         description: "Unsafe operation",
+        details: "",
         safety: SafetyClass::Unsafe,
+        safety_rationale: "",
+        remediation: "",
         consumes_sensors: &[],
         consumes_check_ids: &[],
+        triggers: &[],
     };
     assert!(!unsafe_meta.safety.is_safe());
     assert!(!unsafe_meta.safety.is_guarded());
@@ -277,6 +321,7 @@ fn test_planner_config_with_values() {
     let config = PlannerConfig {
         allow: vec!["fix1".to_string(), "fix2".to_string()],
         deny: vec!["fix3".to_string()],
+        path_policies: Vec::new(),
         allow_guarded: true,
         allow_unsafe: false,
         allow_dirty: true,
@@ -284,6 +329,14 @@ fn test_planner_config_with_values() {
         max_files: Some(10),
         max_patch_bytes: Some(1024),
         params,
+        fixers: Default::default(),
+        min_severity: None,
+        sensors: Vec::new(),
+        exclude_check_ids: Vec::new(),
+        changed_paths: None,
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
+        scan: Default::default(),
     };
 
     assert_eq!(config.allow.len(), 2);
@@ -302,6 +355,7 @@ fn test_planner_config_clone() {
     let config = PlannerConfig {
         allow: vec!["fix1".to_string()],
         deny: vec![],
+        path_policies: Vec::new(),
         allow_guarded: true,
         allow_unsafe: false,
         allow_dirty: false,
@@ -309,6 +363,14 @@ fn test_planner_config_clone() {
         max_files: None,
         max_patch_bytes: None,
         params: HashMap::new(),
+        fixers: Default::default(),
+        min_severity: None,
+        sensors: Vec::new(),
+        exclude_check_ids: Vec::new(),
+        changed_paths: None,
+        packages: Vec::new(),
+        exclude_packages: Vec::new(),
+        scan: Default::default(),
     };
 
     let cloned = config.clone();
@@ -327,6 +389,7 @@ fn test_plan_context_creation() {
         repo_root: camino::Utf8PathBuf::from("/repo"),
         artifacts_dir: camino::Utf8PathBuf::from("/repo/artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     };
 
     assert_eq!(ctx.repo_root.as_str(), "/repo");
@@ -342,6 +405,7 @@ fn test_plan_context_clone() {
             allow: vec!["test".to_string()],
             ..Default::default()
         },
+        workspace_graph: None,
     };
 
     let cloned = ctx.clone();
@@ -418,6 +482,7 @@ fn test_fixer_trait_plan_returns_ops() {
         repo_root: camino::Utf8PathBuf::from("/repo"),
         artifacts_dir: camino::Utf8PathBuf::from("/repo/artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     };
     let repo = MockRepoView::new("/repo");
     let receipts = ReceiptSet::from_loaded(&[]);
@@ -439,6 +504,7 @@ fn test_fixer_trait_plan_empty_result() {
         repo_root: camino::Utf8PathBuf::from("/repo"),
         artifacts_dir: camino::Utf8PathBuf::from("/repo/artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     };
     let repo = MockRepoView::new("/repo");
     let receipts = ReceiptSet::from_loaded(&[]);
@@ -477,6 +543,7 @@ fn test_matched_finding_creation() {
         path: "artifacts/test-tool/report.json".into(),
         sensor_id: "test-tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -514,6 +581,7 @@ fn test_matched_finding_minimal() {
         path: "artifacts/minimal-tool/report.json".into(),
         sensor_id: "minimal-tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -566,11 +634,13 @@ fn test_receipt_set_multiple_receipts() {
             path: "artifacts/tool-a/report.json".into(),
             sensor_id: "tool-a".to_string(),
             receipt: Ok(receipt1),
+            signature: None,
         },
         LoadedReceipt {
             path: "artifacts/tool-b/report.json".into(),
             sensor_id: "tool-b".to_string(),
             receipt: Ok(receipt2),
+            signature: None,
         },
     ];
     let set = ReceiptSet::from_loaded(&loaded);
@@ -586,6 +656,7 @@ fn test_receipt_set_tool_prefix_matching() {
         path: "artifacts/cargo-deny/report.json".into(),
         sensor_id: "cargo-deny".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -616,6 +687,7 @@ fn test_receipt_set_sorted_output() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -642,6 +714,7 @@ fn test_receipt_set_multiple_findings_single_receipt() {
         path: "artifacts/multi-tool/report.json".into(),
         sensor_id: "multi-tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -662,6 +735,7 @@ fn test_receipt_set_check_id_filtering() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -683,6 +757,7 @@ fn test_receipt_set_code_filtering() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -715,6 +790,7 @@ fn test_receipt_set_location_handling() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -724,6 +800,241 @@ fn test_receipt_set_location_handling() {
     assert_eq!(matches[0].line, Some(100));
 }
 
+fn shard(path: &str, sensor_id: &str, findings: Vec<Finding>) -> LoadedReceipt {
+    LoadedReceipt {
+        path: path.into(),
+        sensor_id: sensor_id.to_string(),
+        receipt: Ok(make_receipt("cargo-deny", findings)),
+        signature: None,
+    }
+}
+
+fn fingerprinted(check_id: &str, path: &str, fingerprint: &str) -> Finding {
+    let mut finding = make_finding(check_id, None);
+    finding.location = Some(Location {
+        path: path.into(),
+        line: Some(1),
+        column: None,
+    });
+    finding.fingerprint = Some(fingerprint.to_string());
+    finding
+}
+
+#[test]
+fn test_receipt_set_merges_shards_by_fingerprint() {
+    let loaded = vec![
+        shard(
+            "artifacts/cargo-deny-shard-2/report.json",
+            "cargo-deny-shard-2",
+            vec![
+                fingerprinted("licenses.unlicensed", "crates/b/Cargo.toml", "fp-b"),
+                fingerprinted("licenses.unlicensed", "crates/c/Cargo.toml", "fp-c"),
+            ],
+        ),
+        shard(
+            "artifacts/cargo-deny-shard-1/report.json",
+            "cargo-deny-shard-1",
+            vec![
+                fingerprinted("licenses.unlicensed", "crates/a/Cargo.toml", "fp-a"),
+                fingerprinted("licenses.unlicensed", "crates/b/Cargo.toml", "fp-b"),
+            ],
+        ),
+    ];
+    let set = ReceiptSet::from_loaded(&loaded);
+
+    let matches = set.matching_findings(&["cargo-deny"], &["licenses.unlicensed"], &[]);
+    let paths: Vec<_> = matches.iter().filter_map(|m| m.path.as_deref()).collect();
+    assert_eq!(
+        paths,
+        vec![
+            "crates/a/Cargo.toml",
+            "crates/b/Cargo.toml",
+            "crates/c/Cargo.toml"
+        ]
+    );
+}
+
+#[test]
+fn test_receipt_set_merges_unfingerprinted_duplicates() {
+    let loaded = vec![
+        shard(
+            "artifacts/cargo-deny-1/report.json",
+            "cargo-deny-1",
+            vec![make_finding("bans.multi", Some("dup"))],
+        ),
+        shard(
+            "artifacts/cargo-deny-2/report.json",
+            "cargo-deny-2",
+            vec![
+                make_finding("bans.multi", Some("dup")),
+                make_finding("bans.multi", Some("other")),
+            ],
+        ),
+    ];
+    let set = ReceiptSet::from_loaded(&loaded);
+
+    let matches = set.matching_findings(&["cargo-deny"], &[], &[]);
+    assert_eq!(matches.len(), 2);
+}
+
+#[test]
+fn test_findings_without_fingerprint_get_a_computed_one() {
+    let loaded = vec![
+        shard(
+            "artifacts/cargo-deny-1/report.json",
+            "cargo-deny-1",
+            vec![make_finding("bans.multi", Some("dup"))],
+        ),
+        shard(
+            "artifacts/cargo-deny-2/report.json",
+            "cargo-deny-2",
+            vec![fingerprinted("bans.multi", "Cargo.toml", "sensor-fp")],
+        ),
+    ];
+    let set = ReceiptSet::from_loaded(&loaded);
+
+    let matches = set.matching_findings(&["cargo-deny"], &[], &[]);
+    let fingerprints: Vec<_> = matches
+        .iter()
+        .map(|m| m.fingerprint.clone().expect("fingerprint"))
+        .collect();
+    assert!(fingerprints.contains(&"sensor-fp".to_string()));
+    assert!(
+        fingerprints
+            .contains(&make_finding("bans.multi", Some("dup")).stable_fingerprint("cargo-deny"))
+    );
+
+    // The same finding planned again gets the same identity.
+    let again = ReceiptSet::from_loaded(&loaded).matching_findings(&["cargo-deny"], &[], &[]);
+    assert_eq!(
+        again
+            .iter()
+            .map(|m| m.fingerprint.clone())
+            .collect::<Vec<_>>(),
+        matches
+            .iter()
+            .map(|m| m.fingerprint.clone())
+            .collect::<Vec<_>>()
+    );
+}
+
+#[test]
+fn test_receipt_set_keeps_distinct_tools_apart() {
+    let loaded = vec![
+        shard(
+            "artifacts/cargo-deny/report.json",
+            "cargo-deny",
+            vec![fingerprinted("bans.multi", "Cargo.toml", "same")],
+        ),
+        LoadedReceipt {
+            path: "artifacts/depguard/report.json".into(),
+            sensor_id: "depguard".to_string(),
+            receipt: Ok(make_receipt(
+                "depguard",
+                vec![fingerprinted("bans.multi", "Cargo.toml", "same")],
+            )),
+            signature: None,
+        },
+    ];
+    let set = ReceiptSet::from_loaded(&loaded);
+
+    let matches = set.matching_findings(&["cargo-deny", "depguard"], &[], &[]);
+    assert_eq!(matches.len(), 2);
+}
+
+#[test]
+fn test_receipt_set_retain_findings_applies_planner_filters() {
+    let mut warn = make_finding("deps.path_requires_version", Some("missing"));
+    warn.severity = Severity::Warn;
+    let loaded = vec![
+        LoadedReceipt {
+            path: "artifacts/depguard/report.json".into(),
+            sensor_id: "depguard".to_string(),
+            receipt: Ok(make_receipt(
+                "depguard",
+                vec![
+                    make_finding("deps.path_requires_version", Some("error")),
+                    warn,
+                    make_finding("deps.wildcard", Some("error")),
+                ],
+            )),
+            signature: None,
+        },
+        LoadedReceipt {
+            path: "artifacts/builddiag/report.json".into(),
+            sensor_id: "builddiag".to_string(),
+            receipt: Ok(make_receipt(
+                "builddiag",
+                vec![make_finding("workspace.resolver_v2", None)],
+            )),
+            signature: None,
+        },
+    ];
+
+    let mut set = ReceiptSet::from_loaded(&loaded);
+    assert_eq!(set.retain_findings(&PlannerConfig::default()), 0);
+
+    let config = PlannerConfig {
+        min_severity: Some(Severity::Error),
+        sensors: vec!["depguard".to_string()],
+        exclude_check_ids: vec!["deps.wildcard".to_string()],
+        ..Default::default()
+    };
+    assert_eq!(set.retain_findings(&config), 3);
+
+    let matches = set.matching_findings(&["depguard", "builddiag"], &[], &[]);
+    assert_eq!(matches.len(), 1);
+    assert_eq!(
+        matches[0].check_id.as_deref(),
+        Some("deps.path_requires_version")
+    );
+    assert_eq!(matches[0].code, "error");
+}
+
+#[test]
+fn test_receipt_set_rebased_keeps_findings_under_the_prefix() {
+    let mut unlocated = make_finding("licenses.unlicensed", None);
+    unlocated.location = None;
+    let inner = make_finding("licenses.unlicensed", Some("inner"));
+    let loaded = vec![shard(
+        "artifacts/cargo-deny/report.json",
+        "cargo-deny",
+        vec![
+            Finding {
+                location: Some(Location {
+                    path: "./tools/cli/Cargo.toml".into(),
+                    line: Some(1),
+                    column: None,
+                }),
+                ..inner.clone()
+            },
+            fingerprinted("licenses.unlicensed", "services/api/Cargo.toml", "fp-api"),
+            unlocated,
+        ],
+    )];
+    let set = ReceiptSet::from_loaded(&loaded);
+    let repo_wide = set.matching_findings(&["cargo-deny"], &[], &[]);
+
+    let scoped = set.rebased(|path| {
+        path.strip_prefix("tools/cli")
+            .ok()
+            .map(camino::Utf8Path::to_path_buf)
+    });
+    let matches = scoped.matching_findings(&["cargo-deny"], &[], &[]);
+
+    assert_eq!(matches.len(), 2);
+    let located = matches.iter().find(|m| m.path.is_some()).unwrap();
+    assert_eq!(located.path.as_deref(), Some("Cargo.toml"));
+    let before = repo_wide
+        .iter()
+        .find(|m| m.code == "inner")
+        .unwrap()
+        .fingerprint
+        .clone();
+    assert_eq!(located.fingerprint, before);
+    assert!(matches.iter().any(|m| m.path.is_none()));
+}
+
 // =============================================================================
 // SafetyClass Integration Tests
 // =============================================================================
@@ -792,13 +1103,13 @@ fn test_plan_op_with_blocked_status() {
     let mut op = make_plan_op("blocked-op", SafetyClass::Guarded);
     op.blocked = true;
     op.blocked_reason = Some("Not in allow list".to_string());
-    op.blocked_reason_token = Some("allowlist_missing".to_string());
+    op.blocked_reason_token = Some(BuildfixErrorCode::AllowlistMissing);
 
     assert!(op.blocked);
     assert_eq!(op.blocked_reason, Some("Not in allow list".to_string()));
     assert_eq!(
         op.blocked_reason_token,
-        Some("allowlist_missing".to_string())
+        Some(BuildfixErrorCode::AllowlistMissing)
     );
 }
 
@@ -856,6 +1167,7 @@ fn test_op_kind_toml_set() {
             "version".to_string(),
         ],
         value: serde_json::json!("1.0.0"),
+        insert: None,
     };
 
     let json = serde_json::to_string(&kind).expect("Should serialize");
@@ -912,6 +1224,7 @@ fn test_finding_ref_complete() {
         path: Some("src/lib.rs".to_string()),
         line: Some(42),
         fingerprint: Some("hash123".to_string()),
+        severity: None,
     };
 
     assert_eq!(finding.source, "cargo-clippy");
@@ -931,6 +1244,7 @@ fn test_finding_ref_minimal() {
         path: None,
         line: None,
         fingerprint: None,
+        severity: None,
     };
 
     assert_eq!(finding.source, "tool");
@@ -949,6 +1263,7 @@ fn test_finding_ref_serialization() {
         path: Some("file.rs".to_string()),
         line: Some(10),
         fingerprint: None,
+        severity: None,
     };
 
     let json = serde_json::to_string(&finding).expect("Should serialize");
@@ -969,6 +1284,7 @@ fn test_empty_tool_prefixes_matches_none() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -986,6 +1302,7 @@ fn test_receipt_set_with_all_error_receipts() {
             receipt: Err(buildfix_receipts::ReceiptLoadError::Io {
                 message: "not found".to_string(),
             }),
+            signature: None,
         },
         LoadedReceipt {
             path: "artifacts/tool2/report.json".into(),
@@ -993,6 +1310,7 @@ fn test_receipt_set_with_all_error_receipts() {
             receipt: Err(buildfix_receipts::ReceiptLoadError::Json {
                 message: "invalid json".to_string(),
             }),
+            signature: None,
         },
     ];
     let set = ReceiptSet::from_loaded(&loaded);
@@ -1022,6 +1340,7 @@ fn test_finding_with_no_code_defaults_to_dash() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -1054,6 +1373,7 @@ fn test_finding_with_special_characters_in_code() {
         path: "artifacts/tool/report.json".into(),
         sensor_id: "tool".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let set = ReceiptSet::from_loaded(&loaded);
 
@@ -1065,3 +1385,139 @@ fn test_finding_with_special_characters_in_code() {
     assert_eq!(matches.len(), 1);
     assert_eq!(matches[0].code, "code-with-special_chars.123");
 }
+
+// =============================================================================
+// WorkspaceGraph Tests
+// =============================================================================
+
+const CARGO_METADATA: &str = r#"{
+  "packages": [
+    {
+      "id": "app 0.1.0 (path+file:///work/repo/crates/app)",
+      "name": "app",
+      "version": "0.1.0",
+      "manifest_path": "/work/repo/crates/app/Cargo.toml",
+      "source": null,
+      "features": {"default": ["fast"], "fast": []},
+      "dependencies": [
+        {"name": "serde", "req": "^1.0", "kind": null, "optional": false, "rename": null},
+        {"name": "lib", "req": "*", "kind": null, "optional": false, "rename": null, "path": "/work/repo/crates/lib"}
+      ]
+    },
+    {
+      "id": "lib 0.1.0 (path+file:///work/repo/crates/lib)",
+      "name": "lib",
+      "version": "0.1.0",
+      "manifest_path": "/work/repo/crates/lib/Cargo.toml",
+      "source": null,
+      "features": {},
+      "dependencies": [
+        {"name": "serde", "req": "^0.9", "kind": "dev", "optional": false, "rename": null}
+      ]
+    },
+    {
+      "id": "serde 1.0.200 (registry+https://github.com/rust-lang/crates.io-index)",
+      "name": "serde",
+      "version": "1.0.200",
+      "manifest_path": "/home/u/.cargo/registry/src/serde-1.0.200/Cargo.toml",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "features": {},
+      "dependencies": []
+    },
+    {
+      "id": "serde 0.9.15 (registry+https://github.com/rust-lang/crates.io-index)",
+      "name": "serde",
+      "version": "0.9.15",
+      "manifest_path": "/home/u/.cargo/registry/src/serde-0.9.15/Cargo.toml",
+      "source": "registry+https://github.com/rust-lang/crates.io-index",
+      "features": {},
+      "dependencies": []
+    }
+  ],
+  "workspace_members": [
+    "app 0.1.0 (path+file:///work/repo/crates/app)",
+    "lib 0.1.0 (path+file:///work/repo/crates/lib)"
+  ],
+  "resolve": {
+    "nodes": [
+      {
+        "id": "app 0.1.0 (path+file:///work/repo/crates/app)",
+        "dependencies": [
+          "lib 0.1.0 (path+file:///work/repo/crates/lib)",
+          "serde 1.0.200 (registry+https://github.com/rust-lang/crates.io-index)"
+        ],
+        "features": ["default", "fast"]
+      }
+    ],
+    "root": null
+  },
+  "target_directory": "/work/repo/target",
+  "version": 1,
+  "workspace_root": "/work/repo"
+}"#;
+
+#[test]
+fn test_workspace_graph_parses_members_and_packages() {
+    let graph = WorkspaceGraph::from_cargo_metadata(CARGO_METADATA).unwrap();
+
+    assert_eq!(graph.workspace_root, "/work/repo");
+    assert_eq!(graph.packages.len(), 4);
+
+    let members: Vec<&str> = graph.member_packages().map(|p| p.name.as_str()).collect();
+    assert_eq!(members, vec!["app", "lib"]);
+
+    let app = graph
+        .member_by_manifest(Utf8Path::new("crates/app/Cargo.toml"))
+        .expect("app member");
+    assert_eq!(app.manifest_path, "crates/app/Cargo.toml");
+    assert_eq!(app.enabled_features, vec!["default", "fast"]);
+    assert_eq!(app.features["default"], vec!["fast"]);
+    assert_eq!(app.resolved_dependencies.len(), 2);
+    assert_eq!(
+        app.dependencies[1].path.as_deref(),
+        Some(Utf8Path::new("/work/repo/crates/lib"))
+    );
+
+    let lib = graph
+        .member_by_manifest(Utf8Path::new("crates/lib/Cargo.toml"))
+        .expect("lib member");
+    assert_eq!(lib.dependencies[0].kind.as_deref(), Some("dev"));
+    assert!(lib.enabled_features.is_empty());
+
+    // Registry packages are not members, and keep their absolute manifest path.
+    assert!(
+        graph
+            .member_by_manifest(Utf8Path::new(
+                "/home/u/.cargo/registry/src/serde-1.0.200/Cargo.toml"
+            ))
+            .is_none()
+    );
+}
+
+#[test]
+fn test_workspace_graph_reports_duplicate_versions() {
+    let graph = WorkspaceGraph::from_cargo_metadata(CARGO_METADATA).unwrap();
+
+    assert_eq!(graph.resolved_versions("serde"), vec!["0.9.15", "1.0.200"]);
+    assert_eq!(graph.resolved_versions("app"), vec!["0.1.0"]);
+    assert!(graph.resolved_versions("missing").is_empty());
+
+    let dups = graph.duplicate_versions();
+    assert_eq!(dups.len(), 1);
+    assert_eq!(dups["serde"], vec!["0.9.15", "1.0.200"]);
+}
+
+#[test]
+fn test_workspace_graph_rejects_invalid_json() {
+    let err = WorkspaceGraph::from_cargo_metadata("{ not json").unwrap_err();
+    assert!(err.to_string().contains("cargo metadata"));
+}
+
+#[test]
+fn test_workspace_graph_without_resolve_section() {
+    let json =
+        r#"{"packages": [], "workspace_members": [], "resolve": null, "workspace_root": "/r"}"#;
+    let graph = WorkspaceGraph::from_cargo_metadata(json).unwrap();
+    assert!(graph.packages.is_empty());
+    assert_eq!(graph.member_packages().count(), 0);
+}
@@ -1,16 +1,6 @@
+pub use buildfix_fixer_api::TriggerPattern;
 use buildfix_types::ops::SafetyClass;
 
-/// Trigger pattern for a fixer.
-#[derive(Debug, Clone, Copy)]
-pub struct TriggerPattern {
-    /// Sensor/tool name (e.g., "builddiag", "depguard").
-    pub sensor: &'static str,
-    /// Check ID pattern (e.g., "workspace.resolver_v2").
-    pub check_id: &'static str,
-    /// Optional code pattern (e.g., "missing_version").
-    pub code: Option<&'static str>,
-}
-
 /// Canonical fix metadata shared between planner and explain surfaces.
 #[derive(Debug, Clone, Copy)]
 pub struct FixerCatalogEntry {
@@ -231,6 +221,13 @@ const LICENSE_TRIGGERS: &[TriggerPattern] = &[
     },
 ];
 
+#[cfg(feature = "fixer-clippy-suggestions")]
+const CLIPPY_SUGGESTIONS_TRIGGERS: &[TriggerPattern] = &[TriggerPattern {
+    sensor: "clippy",
+    check_id: "clippy.*",
+    code: None,
+}];
+
 /// Returns all enabled builtins and their metadata.
 #[allow(clippy::vec_init_then_push)]
 pub fn enabled_fix_catalog() -> Vec<FixerCatalogEntry> {
@@ -300,6 +297,14 @@ pub fn enabled_fix_catalog() -> Vec<FixerCatalogEntry> {
         triggers: LICENSE_TRIGGERS,
     });
 
+    #[cfg(feature = "fixer-clippy-suggestions")]
+    out.push(FixerCatalogEntry {
+        key: "clippy-suggestions",
+        fix_id: "rust.apply_clippy_suggestions",
+        safety: SafetyClass::Guarded,
+        triggers: CLIPPY_SUGGESTIONS_TRIGGERS,
+    });
+
     out
 }
 
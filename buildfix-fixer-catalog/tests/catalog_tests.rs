@@ -482,7 +482,7 @@ fn test_all_default_fixers_enabled() {
 fn test_catalog_count() {
     let catalog = enabled_fix_catalog();
 
-    // With all default features, should have 8 fixers
+    // With all default features, should have 9 fixers
     #[cfg(all(
         feature = "fixer-resolver-v2",
         feature = "fixer-path-dep-version",
@@ -491,9 +491,10 @@ fn test_catalog_count() {
         feature = "fixer-remove-unused-deps",
         feature = "fixer-msrv",
         feature = "fixer-edition",
-        feature = "fixer-license"
+        feature = "fixer-license",
+        feature = "fixer-clippy-suggestions"
     ))]
-    assert_eq!(catalog.len(), 8, "Should have 8 fixers with all features");
+    assert_eq!(catalog.len(), 9, "Should have 9 fixers with all features");
 
     // At minimum, with default features, should have at least one
     assert!(!catalog.is_empty(), "Catalog should not be empty");
@@ -0,0 +1,326 @@
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
+use crate::planner::ReceiptSet;
+use crate::ports::RepoView;
+use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+use buildfix_types::plan::{FindingRef, PlanOp, Rationale};
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::Deserialize;
+use std::collections::BTreeMap;
+
+pub struct ClippySuggestionsFixer;
+
+impl ClippySuggestionsFixer {
+    const FIX_ID: &'static str = "rust.apply_clippy_suggestions";
+    const DESCRIPTION: &'static str =
+        "Applies machine-applicable clippy suggestions as anchored line replacements";
+    const SENSORS: &'static [&'static str] = &["clippy"];
+    const KEY: &'static str = "clippy-suggestions";
+    const TITLE: &'static str = "Clippy Suggestions";
+    const DETAILS: &'static str = r#"Applies machine-applicable suggestions from `cargo clippy --message-format=json`.
+
+Each clippy finding that carries a `MachineApplicable` suggestion becomes a
+`text_replace_anchored` op on the affected source line. Several suggestions on
+the same line are combined into one replacement; neighbouring lines are used as
+anchors so the edit only lands where clippy saw it.
+
+Suggestions spanning several lines, overlapping suggestions, and lines that no
+longer match the text clippy reported are skipped."#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as GUARDED because:
+- It edits Rust source rather than manifest metadata
+- rustc marks the suggestions as compiling, but not as behavior-preserving
+- Many small edits across a codebase deserve a review pass
+
+The edit itself is deterministic: the line is replaced only when it still
+matches what clippy reported."#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Run `cargo clippy --fix --allow-dirty`
+2. Review the diff and run the test suite
+
+To let buildfix apply this class of fix:
+    cargo clippy --message-format=json > artifacts/clippy/clippy.json
+    buildfix plan && buildfix apply --apply --allow-guarded"#;
+    const TRIGGERS: &'static [TriggerPattern] = &[TriggerPattern {
+        sensor: "clippy",
+        check_id: "clippy.*",
+        code: None,
+    }];
+    /// Any clippy lint qualifies as long as its finding carries `data.suggestions`.
+    const CHECK_IDS: &'static [&'static str] = &["clippy.*"];
+    const CHECK_PREFIX: &'static str = "clippy.";
+}
+
+/// One suggestion as recorded by the clippy adapter under `data.suggestions`.
+///
+/// Lines and columns are 1-based character positions; `column_end` is exclusive.
+#[derive(Debug, Clone, Deserialize)]
+struct Suggestion {
+    path: String,
+    line_start: u64,
+    line_end: u64,
+    column_start: u64,
+    column_end: u64,
+    replacement: String,
+    #[serde(default)]
+    line_text: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, PartialOrd, Ord)]
+struct LineEdit {
+    column_start: u64,
+    column_end: u64,
+    replacement: String,
+}
+
+#[derive(Debug, Default)]
+struct LineSuggestions {
+    edits: Vec<LineEdit>,
+    /// Source lines the diagnostics were produced against.
+    expected: Vec<String>,
+    /// Triggering findings keyed for stable order and de-duplication.
+    findings: BTreeMap<String, FindingRef>,
+}
+
+impl Fixer for ClippySuggestionsFixer {
+    fn meta(&self) -> FixerMeta {
+        FixerMeta {
+            fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
+            description: Self::DESCRIPTION,
+            details: Self::DETAILS,
+            safety: SafetyClass::Guarded,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
+            consumes_sensors: Self::SENSORS,
+            consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
+        }
+    }
+
+    fn plan(
+        &self,
+        _ctx: &crate::planner::PlanContext,
+        repo: &dyn RepoView,
+        receipts: &ReceiptSet,
+    ) -> anyhow::Result<Vec<PlanOp>> {
+        let matched = receipts.matching_findings_with_data(Self::SENSORS, &[], &[]);
+
+        // file -> line -> suggestions on that line
+        let mut by_file: BTreeMap<Utf8PathBuf, BTreeMap<u64, LineSuggestions>> = BTreeMap::new();
+        for m in matched {
+            let is_clippy_lint = m
+                .finding
+                .check_id
+                .as_deref()
+                .is_some_and(|c| c.starts_with(Self::CHECK_PREFIX));
+            if !is_clippy_lint {
+                continue;
+            }
+            for s in suggestions_of(m.data.as_ref()) {
+                // Anchored replacement works on whole lines; multi-line
+                // suggestions are left for the developer.
+                if s.line_start != s.line_end || s.line_start == 0 || s.column_start == 0 {
+                    continue;
+                }
+                let path = repo_relative(repo.root(), &s.path);
+                let line = by_file
+                    .entry(path)
+                    .or_default()
+                    .entry(s.line_start)
+                    .or_default();
+                let edit = LineEdit {
+                    column_start: s.column_start,
+                    column_end: s.column_end,
+                    replacement: s.replacement,
+                };
+                if !line.edits.contains(&edit) {
+                    line.edits.push(edit);
+                }
+                if let Some(text) = s.line_text
+                    && !line.expected.contains(&text)
+                {
+                    line.expected.push(text);
+                }
+                line.findings
+                    .entry(finding_key(&m.finding))
+                    .or_insert_with(|| m.finding.clone());
+            }
+        }
+
+        let mut ops = Vec::new();
+        for (path, lines) in by_file {
+            let Ok(contents) = repo.read_to_string(&path) else {
+                continue;
+            };
+            let source: Vec<&str> = contents.lines().collect();
+
+            for (&line_no, line) in &lines {
+                let idx = (line_no - 1) as usize;
+                let Some(current) = source.get(idx) else {
+                    continue;
+                };
+                // The file moved on since clippy ran; the columns are meaningless.
+                if line.expected.iter().any(|text| text != current) {
+                    continue;
+                }
+                let Some(replaced) = apply_line_edits(current, &line.edits) else {
+                    continue;
+                };
+                if replaced == *current {
+                    continue;
+                }
+
+                // Neighbouring lines anchor the match unless they are being
+                // rewritten by another op in this plan.
+                let anchor_before = idx
+                    .checked_sub(1)
+                    .filter(|prev| !lines.contains_key(&(*prev as u64 + 1)))
+                    .and_then(|prev| source.get(prev))
+                    .map(|l| vec![l.to_string()])
+                    .unwrap_or_default();
+                let anchor_after = Some(idx + 1)
+                    .filter(|next| !lines.contains_key(&(*next as u64 + 1)))
+                    .and_then(|next| source.get(next))
+                    .map(|l| vec![l.to_string()])
+                    .unwrap_or_default();
+
+                let findings: Vec<FindingRef> = line.findings.values().cloned().collect();
+                let fix_key = findings
+                    .first()
+                    .map(fix_key_for)
+                    .unwrap_or_else(|| "unknown/-/-".to_string());
+
+                ops.push(PlanOp {
+                    id: String::new(),
+                    safety: SafetyClass::Guarded,
+                    blocked: false,
+                    blocked_reason: None,
+                    blocked_reason_token: None,
+                    target: OpTarget {
+                        path: path.to_string(),
+                        sha256: None,
+                    },
+                    kind: OpKind::TextReplaceAnchored {
+                        find: current.to_string(),
+                        replace: replaced,
+                        anchor_before,
+                        anchor_after,
+                        max_replacements: None,
+                    },
+                    rationale: Rationale {
+                        fix_key,
+                        description: Some(Self::DESCRIPTION.to_string()),
+                        findings,
+                    },
+                    params_required: vec![],
+                    preview: None,
+                    impact: None,
+                    workspace: None,
+                });
+            }
+        }
+
+        Ok(ops)
+    }
+}
+
+fn suggestions_of(data: Option<&serde_json::Value>) -> Vec<Suggestion> {
+    data.and_then(|d| d.get("suggestions"))
+        .and_then(|v| serde_json::from_value(v.clone()).ok())
+        .unwrap_or_default()
+}
+
+/// Clippy reports paths relative to the workspace root, but absolute paths
+/// show up when it runs from elsewhere.
+fn repo_relative(root: &Utf8Path, path: &str) -> Utf8PathBuf {
+    let path = Utf8Path::new(path);
+    path.strip_prefix(root).unwrap_or(path).to_path_buf()
+}
+
+/// Splice `edits` into `line`, right to left so earlier columns stay valid.
+///
+/// Returns `None` when edits overlap or fall outside the line.
+fn apply_line_edits(line: &str, edits: &[LineEdit]) -> Option<String> {
+    let mut edits = edits.to_vec();
+    edits.sort();
+
+    for pair in edits.windows(2) {
+        if pair[1].column_start < pair[0].column_end {
+            return None;
+        }
+    }
+
+    // Byte offset of every char boundary, plus the end of the line.
+    let mut boundaries: Vec<usize> = line.char_indices().map(|(i, _)| i).collect();
+    boundaries.push(line.len());
+
+    let mut out = line.to_string();
+    for edit in edits.iter().rev() {
+        if edit.column_end < edit.column_start {
+            return None;
+        }
+        let start = *boundaries.get((edit.column_start - 1) as usize)?;
+        let end = *boundaries.get((edit.column_end - 1) as usize)?;
+        out.replace_range(start..end, &edit.replacement);
+    }
+    Some(out)
+}
+
+fn fix_key_for(f: &FindingRef) -> String {
+    let check = f.check_id.clone().unwrap_or_else(|| "-".to_string());
+    format!("{}/{}/{}", f.source, check, f.code)
+}
+
+fn finding_key(f: &FindingRef) -> String {
+    format!(
+        "{}|{}:{}",
+        fix_key_for(f),
+        f.path.as_deref().unwrap_or("-"),
+        f.line.unwrap_or(0)
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn edit(start: u64, end: u64, replacement: &str) -> LineEdit {
+        LineEdit {
+            column_start: start,
+            column_end: end,
+            replacement: replacement.to_string(),
+        }
+    }
+
+    #[test]
+    fn apply_line_edits_splices_by_column() {
+        let out = apply_line_edits("    foo(&bar);", &[edit(9, 13, "bar")]);
+        assert_eq!(out.as_deref(), Some("    foo(bar);"));
+    }
+
+    #[test]
+    fn apply_line_edits_handles_multiple_edits_and_unicode() {
+        let out = apply_line_edits("é(&a, &b)", &[edit(7, 9, "b"), edit(3, 5, "a")]);
+        assert_eq!(out.as_deref(), Some("é(a, b)"));
+    }
+
+    #[test]
+    fn apply_line_edits_rejects_overlap_and_out_of_range() {
+        assert!(apply_line_edits("abcdef", &[edit(1, 4, "x"), edit(3, 5, "y")]).is_none());
+        assert!(apply_line_edits("abc", &[edit(2, 9, "x")]).is_none());
+    }
+
+    #[test]
+    fn repo_relative_strips_root() {
+        let root = Utf8Path::new("/work/repo");
+        assert_eq!(
+            repo_relative(root, "/work/repo/src/lib.rs"),
+            Utf8PathBuf::from("src/lib.rs")
+        );
+        assert_eq!(
+            repo_relative(root, "src/lib.rs"),
+            Utf8PathBuf::from("src/lib.rs")
+        );
+    }
+}
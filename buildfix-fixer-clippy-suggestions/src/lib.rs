@@ -0,0 +1,16 @@
+mod planner {
+    pub use buildfix_fixer_api::{PlanContext, ReceiptSet};
+}
+
+mod ports {
+    pub use buildfix_fixer_api::RepoView;
+}
+
+mod fixers {
+    pub use buildfix_fixer_api::{Fixer, FixerMeta, TriggerPattern};
+}
+
+include!(concat!(
+    env!("CARGO_MANIFEST_DIR"),
+    "/src/clippy_suggestions.rs"
+));
@@ -0,0 +1,235 @@
+//! Integration tests for buildfix-fixer-clippy-suggestions
+//!
+//! These tests complement the inline tests in src/clippy_suggestions.rs
+
+use buildfix_fixer_api::{Fixer, PlanContext, PlannerConfig, ReceiptSet, RepoView};
+use buildfix_fixer_clippy_suggestions::ClippySuggestionsFixer;
+use buildfix_receipts::LoadedReceipt;
+use buildfix_types::ops::{OpKind, SafetyClass};
+use buildfix_types::receipt::{Finding, Location, ReceiptEnvelope, RunInfo, ToolInfo, Verdict};
+use camino::{Utf8Path, Utf8PathBuf};
+use std::collections::HashMap;
+
+const LIB_RS: &str = "fn main() {\n    foo(&bar);\n    let x = &baz.clone();\n}\n";
+
+/// Mock repository for testing
+struct MockRepo {
+    root: Utf8PathBuf,
+    files: HashMap<String, String>,
+}
+
+impl MockRepo {
+    fn new(files: &[(&str, &str)]) -> Self {
+        Self {
+            root: Utf8PathBuf::from("/repo"),
+            files: files
+                .iter()
+                .map(|(p, c)| (p.to_string(), c.to_string()))
+                .collect(),
+        }
+    }
+}
+
+impl RepoView for MockRepo {
+    fn root(&self) -> &Utf8Path {
+        &self.root
+    }
+
+    fn read_to_string(&self, rel: &Utf8Path) -> anyhow::Result<String> {
+        self.files
+            .get(rel.as_str())
+            .cloned()
+            .ok_or_else(|| anyhow::anyhow!("missing {}", rel))
+    }
+
+    fn exists(&self, rel: &Utf8Path) -> bool {
+        self.files.contains_key(rel.as_str())
+    }
+}
+
+fn finding(check_id: &str, line: u64, suggestions: serde_json::Value) -> Finding {
+    Finding {
+        severity: Default::default(),
+        check_id: Some(check_id.to_string()),
+        code: Some(check_id.replace('.', "::")),
+        message: None,
+        location: Some(Location {
+            path: Utf8PathBuf::from("src/lib.rs"),
+            line: Some(line),
+            column: None,
+        }),
+        fingerprint: None,
+        data: Some(serde_json::json!({ "suggestions": suggestions })),
+        ..Default::default()
+    }
+}
+
+fn suggestion(
+    line: u64,
+    start: u64,
+    end: u64,
+    replacement: &str,
+    line_text: &str,
+) -> serde_json::Value {
+    serde_json::json!({
+        "path": "src/lib.rs",
+        "line_start": line,
+        "line_end": line,
+        "column_start": start,
+        "column_end": end,
+        "replacement": replacement,
+        "line_text": line_text,
+    })
+}
+
+fn receipt_set(findings: Vec<Finding>) -> ReceiptSet {
+    let receipt = ReceiptEnvelope {
+        schema: "clippy.message.v1".to_string(),
+        tool: ToolInfo {
+            name: "clippy".to_string(),
+            version: None,
+            repo: None,
+            commit: None,
+        },
+        run: RunInfo::default(),
+        verdict: Verdict::default(),
+        findings,
+        capabilities: None,
+        data: None,
+    };
+
+    ReceiptSet::from_loaded(&[LoadedReceipt {
+        path: Utf8PathBuf::from("artifacts/clippy/clippy.json"),
+        sensor_id: "clippy".to_string(),
+        receipt: Ok(receipt),
+        signature: None,
+    }])
+}
+
+fn plan(repo: &MockRepo, receipts: &ReceiptSet) -> Vec<buildfix_types::plan::PlanOp> {
+    let ctx = PlanContext {
+        repo_root: repo.root.clone(),
+        artifacts_dir: Utf8PathBuf::from("/repo/artifacts"),
+        config: PlannerConfig::default(),
+        workspace_graph: None,
+    };
+    ClippySuggestionsFixer
+        .plan(&ctx, repo, receipts)
+        .expect("plan")
+}
+
+#[test]
+fn test_meta_is_guarded_and_consumes_clippy() {
+    let meta = ClippySuggestionsFixer.meta();
+    assert_eq!(meta.fix_key, "rust.apply_clippy_suggestions");
+    assert_eq!(meta.safety, SafetyClass::Guarded);
+    assert_eq!(meta.consumes_sensors, &["clippy"]);
+    assert!(!meta.consumes_check_ids.is_empty());
+}
+
+#[test]
+fn test_plans_anchored_replacement_per_line() {
+    let repo = MockRepo::new(&[("src/lib.rs", LIB_RS)]);
+    let receipts = receipt_set(vec![finding(
+        "clippy.needless_borrow",
+        2,
+        serde_json::json!([suggestion(2, 9, 13, "bar", "    foo(&bar);")]),
+    )]);
+
+    let ops = plan(&repo, &receipts);
+    assert_eq!(ops.len(), 1);
+    let op = &ops[0];
+    assert_eq!(op.target.path, "src/lib.rs");
+    assert_eq!(op.safety, SafetyClass::Guarded);
+    assert_eq!(
+        op.rationale.fix_key,
+        "clippy/clippy.needless_borrow/clippy::needless_borrow"
+    );
+
+    let OpKind::TextReplaceAnchored {
+        find,
+        replace,
+        anchor_before,
+        anchor_after,
+        ..
+    } = &op.kind
+    else {
+        panic!("expected text_replace_anchored, got {:?}", op.kind);
+    };
+    assert_eq!(find, "    foo(&bar);");
+    assert_eq!(replace, "    foo(bar);");
+    assert_eq!(anchor_before, &vec!["fn main() {".to_string()]);
+    assert_eq!(anchor_after, &vec!["    let x = &baz.clone();".to_string()]);
+
+    let applied = buildfix_edit::apply_op_to_content(LIB_RS, &op.kind).expect("apply");
+    assert_eq!(
+        applied,
+        "fn main() {\n    foo(bar);\n    let x = &baz.clone();\n}\n"
+    );
+}
+
+#[test]
+fn test_adjacent_lines_do_not_anchor_on_each_other() {
+    let repo = MockRepo::new(&[("src/lib.rs", LIB_RS)]);
+    let receipts = receipt_set(vec![
+        finding(
+            "clippy.needless_borrow",
+            2,
+            serde_json::json!([suggestion(2, 9, 13, "bar", "    foo(&bar);")]),
+        ),
+        finding(
+            "clippy.redundant_clone",
+            3,
+            serde_json::json!([suggestion(3, 17, 25, "", "    let x = &baz.clone();")]),
+        ),
+    ]);
+
+    let ops = plan(&repo, &receipts);
+    assert_eq!(ops.len(), 2);
+
+    let mut contents = LIB_RS.to_string();
+    for op in &ops {
+        contents = buildfix_edit::apply_op_to_content(&contents, &op.kind).expect("apply");
+    }
+    assert_eq!(
+        contents,
+        "fn main() {\n    foo(bar);\n    let x = &baz;\n}\n"
+    );
+}
+
+#[test]
+fn test_skips_stale_multiline_and_non_clippy_suggestions() {
+    let repo = MockRepo::new(&[("src/lib.rs", LIB_RS)]);
+    let mut multiline = suggestion(2, 5, 3, "x", "    foo(&bar);");
+    multiline["line_end"] = serde_json::json!(3);
+
+    let receipts = receipt_set(vec![
+        // The source line changed since clippy ran.
+        finding(
+            "clippy.needless_borrow",
+            2,
+            serde_json::json!([suggestion(2, 9, 13, "bar", "    foo(&qux);")]),
+        ),
+        finding("clippy.manual_map", 2, serde_json::json!([multiline])),
+        // rustc lints in the clippy stream are not covered by this fixer.
+        finding(
+            "unused_mut",
+            3,
+            serde_json::json!([suggestion(3, 13, 14, "", "    let x = &baz.clone();")]),
+        ),
+    ]);
+
+    assert!(plan(&repo, &receipts).is_empty());
+}
+
+#[test]
+fn test_missing_file_produces_no_ops() {
+    let repo = MockRepo::new(&[]);
+    let receipts = receipt_set(vec![finding(
+        "clippy.needless_borrow",
+        2,
+        serde_json::json!([suggestion(2, 9, 13, "bar", "    foo(&bar);")]),
+    )]);
+
+    assert!(plan(&repo, &receipts).is_empty());
+}
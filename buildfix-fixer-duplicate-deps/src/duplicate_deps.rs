@@ -1,4 +1,4 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::{MatchedFinding, ReceiptSet};
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -20,6 +20,58 @@ impl DuplicateDepsConsolidationFixer {
         "deps.duplicate_versions",
         "cargo.duplicate_versions",
     ];
+    const KEY: &'static str = "duplicate-deps";
+    const TITLE: &'static str = "Duplicate Dependency Consolidation";
+    const DETAILS: &'static str = r#"Consolidates duplicate dependency versions across member crates into
+[workspace.dependencies].
+
+When depguard reports that a dependency appears at multiple versions across the
+workspace, this fix:
+1. Ensures the selected version exists in [workspace.dependencies]
+2. Rewrites reported member dependencies to `{ workspace = true }`
+3. Preserves per-crate overrides such as features/optional/package
+
+This creates a single source of truth for the dependency version at workspace
+scope and removes per-crate drift."#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as SAFE because:
+- The target dependency and selected version come from the sensor receipt
+- Edits are deterministic and scoped to explicitly reported entries
+- The transform is mechanical (workspace.dependencies + workspace=true rewrite)
+- Per-crate non-version overrides are preserved"#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Pick the canonical version for the duplicated dependency
+2. Set it in root Cargo.toml:
+    [workspace.dependencies]
+    serde = "1.0.210"
+
+3. Update member manifests to inherit from the workspace:
+    [dependencies]
+    serde = { workspace = true }
+
+4. Re-run your sensor to confirm duplicate-version findings are cleared."#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "deps.duplicate_dependency_versions",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "cargo.duplicate_dependency_versions",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "deps.duplicate_versions",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "cargo.duplicate_versions",
+            code: None,
+        },
+    ];
 
     fn parse_receipt_candidate(matched: &MatchedFinding) -> Option<RawCandidate> {
         let path = matched.finding.path.as_ref()?;
@@ -59,8 +111,7 @@ impl DuplicateDepsConsolidationFixer {
     }
 
     fn enrich_candidate(repo: &dyn RepoView, raw: RawCandidate) -> Option<ConsolidationCandidate> {
-        let contents = repo.read_to_string(&raw.manifest).ok()?;
-        let doc = contents.parse::<DocumentMut>().ok()?;
+        let doc = repo.read_toml(&raw.manifest).ok()?;
         let dep_item = get_dep_item(&doc, &raw.toml_path)?;
         let preserved = dep_preserve_from_item(dep_item)?;
 
@@ -98,6 +149,7 @@ impl DuplicateDepsConsolidationFixer {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "ensure_workspace_dependency_version".to_string(),
@@ -110,6 +162,8 @@ impl DuplicateDepsConsolidationFixer {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }
     }
 
@@ -141,6 +195,7 @@ impl DuplicateDepsConsolidationFixer {
             blocked_reason_token: None,
             target: OpTarget {
                 path: cand.manifest.to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "use_workspace_dependency".to_string(),
@@ -153,6 +208,8 @@ impl DuplicateDepsConsolidationFixer {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }
     }
 }
@@ -161,10 +218,16 @@ impl Fixer for DuplicateDepsConsolidationFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Safe,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -518,6 +581,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/depguard/report.json"),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -553,6 +617,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         }
     }
 
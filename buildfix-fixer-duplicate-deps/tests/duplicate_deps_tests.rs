@@ -110,6 +110,7 @@ fn receipt_set_with_findings(findings: Vec<Finding>) -> ReceiptSet {
         path: Utf8PathBuf::from("artifacts/depguard/report.json"),
         sensor_id: "depguard".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -125,6 +126,7 @@ fn plan_context() -> PlanContext {
         repo_root: Utf8PathBuf::from("."),
         artifacts_dir: Utf8PathBuf::from("artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     }
 }
 
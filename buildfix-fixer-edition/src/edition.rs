@@ -1,4 +1,4 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::{MatchedFinding, ReceiptSet};
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -20,10 +20,70 @@ impl EditionUpgradeFixer {
         "cargo.edition_consistent",
         "edition.consistent",
     ];
+    const KEY: &'static str = "edition";
+    const TITLE: &'static str = "Edition Normalization";
+    const DETAILS: &'static str = r#"Normalizes per-crate Rust edition declarations to match the workspace
+canonical value.
+
+The Rust edition determines which language features are available and how certain
+syntax is interpreted. Having consistent editions across a workspace ensures:
+- Predictable behavior across all crates
+- Easier upgrades when moving to a new edition
+- Clear documentation of language version requirements
+
+The canonical edition is determined from (in order):
+1. [workspace.package].edition in root Cargo.toml
+2. [package].edition in root Cargo.toml"#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as GUARDED because:
+- Changing edition can affect code semantics and compilation
+- A higher edition may require code changes (e.g., 2021 keyword changes)
+- A lower edition might disable features your code depends on
+
+The fix requires --allow-guarded because:
+- Edition changes can break builds
+- The impact depends on which language features are used
+- Manual review is recommended before applying
+
+The fix is skipped entirely (not even planned) when:
+- No canonical workspace edition exists
+- The crate already has the correct edition"#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Decide on your workspace's canonical edition
+2. Set it in root Cargo.toml:
+    [workspace.package]
+    edition = "2021"
+
+3. Update each member crate:
+    [package]
+    edition = "2021"
+    # Or use workspace inheritance:
+    edition.workspace = true
+
+Before changing edition, verify your code compiles:
+    cargo +nightly fix --edition
+
+Consider using cargo fix to automatically migrate code between editions."#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "builddiag",
+            check_id: "rust.edition_consistent",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo",
+            check_id: "cargo.edition_consistent",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo",
+            check_id: "edition.consistent",
+            code: None,
+        },
+    ];
 
     fn canonical_edition(repo: &dyn RepoView) -> Option<String> {
-        let contents = repo.read_to_string(Utf8Path::new("Cargo.toml")).ok()?;
-        let doc = contents.parse::<DocumentMut>().ok()?;
+        let doc = repo.read_toml(Utf8Path::new("Cargo.toml")).ok()?;
 
         // Preferred: [workspace.package].edition
         if let Some(ws) = doc.get("workspace").and_then(|i| i.as_table())
@@ -81,10 +141,16 @@ impl Fixer for EditionUpgradeFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Guarded,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -165,6 +231,7 @@ impl Fixer for EditionUpgradeFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "set_package_edition".to_string(),
@@ -177,6 +244,8 @@ impl Fixer for EditionUpgradeFixer {
                 },
                 params_required,
                 preview: None,
+                impact: None,
+                workspace: None,
             });
         }
 
@@ -345,6 +414,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -414,6 +484,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/a/Cargo.toml");
@@ -447,6 +518,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/a/Cargo.toml");
@@ -502,6 +574,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -617,6 +690,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         // High confidence + full consensus → Safe
@@ -663,6 +737,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         // Low confidence → Guarded (not promoted to Safe)
@@ -9,7 +9,7 @@ mod ports {
 }
 
 mod fixers {
-    pub use buildfix_fixer_api::{Fixer, FixerMeta};
+    pub use buildfix_fixer_api::{Fixer, FixerMeta, TriggerPattern};
 }
 
 include!(concat!(env!("CARGO_MANIFEST_DIR"), "/src/edition.rs"));
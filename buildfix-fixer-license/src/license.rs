@@ -1,4 +1,4 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::{MatchedFinding, ReceiptSet};
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -25,10 +25,93 @@ impl LicenseNormalizeFixer {
         "cargo.licenses.unlicensed",
         "cargo.licenses.missing_license",
     ];
+    const KEY: &'static str = "license";
+    const TITLE: &'static str = "License Normalization";
+    const DETAILS: &'static str = r#"Normalizes per-crate `package.license` declarations to match the workspace
+canonical license.
+
+When cargo-deny reports missing or unlicensed crate manifests, this fix sets
+`package.license` on the affected crate's Cargo.toml using the workspace
+standard from the root manifest.
+
+Canonical license lookup order:
+1. [workspace.package].license in root Cargo.toml
+2. [package].license in root Cargo.toml"#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as GUARDED because:
+- License metadata has legal/compliance impact
+- A wrong license string can violate distribution policy
+- Manual review is recommended before applying to all crates
+
+If no workspace canonical license exists, the fix becomes UNSAFE and requires
+`--param license=<expression>` to provide an explicit value."#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Set the workspace canonical license in root Cargo.toml:
+    [workspace.package]
+    license = "MIT OR Apache-2.0"
+
+2. Set each affected crate:
+    [package]
+    license = "MIT OR Apache-2.0"
+
+If no canonical value is available and you still want buildfix to apply:
+    buildfix apply --apply --allow-unsafe --param license="MIT OR Apache-2.0""#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "cargo-deny",
+            check_id: "licenses.unlicensed",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo-deny",
+            check_id: "licenses.missing_license",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "deny",
+            check_id: "licenses.unlicensed",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "deny",
+            check_id: "licenses.missing_license",
+            code: None,
+        },
+        // Additional triggers from catalog
+        TriggerPattern {
+            sensor: "cargo-deny",
+            check_id: "licenses.missing",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "deny",
+            check_id: "license.unlicensed",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "deny",
+            check_id: "license.missing",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "deny",
+            check_id: "license.missing_license",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo-deny",
+            check_id: "cargo.licenses.unlicensed",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo-deny",
+            check_id: "cargo.licenses.missing_license",
+            code: None,
+        },
+    ];
 
     fn canonical_license(repo: &dyn RepoView) -> Option<String> {
-        let contents = repo.read_to_string(Utf8Path::new("Cargo.toml")).ok()?;
-        let doc = contents.parse::<DocumentMut>().ok()?;
+        let doc = repo.read_toml(Utf8Path::new("Cargo.toml")).ok()?;
 
         // Preferred: [workspace.package].license
         if let Some(ws) = doc.get("workspace").and_then(|i| i.as_table())
@@ -159,10 +242,16 @@ impl Fixer for LicenseNormalizeFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Guarded,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -263,6 +352,7 @@ impl Fixer for LicenseNormalizeFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "set_package_license".to_string(),
@@ -275,6 +365,8 @@ impl Fixer for LicenseNormalizeFixer {
                 },
                 params_required,
                 preview: None,
+                impact: None,
+                workspace: None,
             });
         }
 
@@ -448,6 +540,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo-deny/report.json"),
             sensor_id: "cargo-deny".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
 
         ReceiptSet::from_loaded(&loaded)
@@ -458,6 +551,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         }
     }
 
@@ -634,6 +728,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo-deny/report.json"),
             sensor_id: "cargo-deny".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
 
         ReceiptSet::from_loaded(&loaded)
@@ -113,6 +113,7 @@ fn receipt_set_with_license_finding(
         path: Utf8PathBuf::from("artifacts/cargo-deny/report.json"),
         sensor_id: sensor.to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -166,6 +167,7 @@ fn receipt_set_with_evidence(
         path: Utf8PathBuf::from("artifacts/cargo-deny/report.json"),
         sensor_id: sensor.to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -181,6 +183,7 @@ fn plan_context() -> PlanContext {
         repo_root: Utf8PathBuf::from("."),
         artifacts_dir: Utf8PathBuf::from("artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     }
 }
 
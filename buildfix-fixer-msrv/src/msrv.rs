@@ -1,4 +1,4 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::{MatchedFinding, ReceiptSet};
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -20,10 +20,68 @@ impl MsrvNormalizeFixer {
         "cargo.msrv_consistent",
         "msrv.consistent",
     ];
+    const KEY: &'static str = "msrv";
+    const TITLE: &'static str = "MSRV Normalization";
+    const DETAILS: &'static str = r#"Normalizes per-crate rust-version (MSRV) declarations to match the workspace
+canonical value.
+
+The Minimum Supported Rust Version should be consistent across a workspace to
+avoid confusion and ensure all crates can build with the same toolchain. This
+fix sets member crate `package.rust-version` to match the workspace standard.
+
+The canonical rust-version is determined from (in order):
+1. [workspace.package].rust-version in root Cargo.toml
+2. [package].rust-version in root Cargo.toml"#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as GUARDED because:
+- Changing MSRV can affect which Rust versions can compile the crate
+- A lower MSRV might hide newer Rust features being used
+- A higher MSRV might break builds for users on older toolchains
+
+The fix requires --allow-guarded because:
+- It changes a semantic version constraint
+- The impact depends on your support policy
+- Manual review is recommended before applying
+
+The fix is skipped entirely (not even planned) when:
+- No canonical workspace rust-version exists
+- The crate already has the correct rust-version"#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Decide on your workspace's canonical MSRV
+2. Set it in root Cargo.toml:
+    [workspace.package]
+    rust-version = "1.70"
+
+3. Update each member crate:
+    [package]
+    rust-version = "1.70"
+    # Or use workspace inheritance:
+    rust-version.workspace = true
+
+Before changing MSRV, verify your code compiles with the target version:
+    cargo +1.70 check --workspace
+
+Consider using cargo-msrv to verify actual minimum version."#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "builddiag",
+            check_id: "rust.msrv_consistent",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo",
+            check_id: "cargo.msrv_consistent",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo",
+            check_id: "msrv.consistent",
+            code: None,
+        },
+    ];
 
     fn canonical_rust_version(repo: &dyn RepoView) -> Option<String> {
-        let contents = repo.read_to_string(Utf8Path::new("Cargo.toml")).ok()?;
-        let doc = contents.parse::<DocumentMut>().ok()?;
+        let doc = repo.read_toml(Utf8Path::new("Cargo.toml")).ok()?;
 
         // Preferred: [workspace.package].rust-version
         if let Some(ws) = doc.get("workspace").and_then(|i| i.as_table())
@@ -81,10 +139,16 @@ impl Fixer for MsrvNormalizeFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Guarded,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -165,6 +229,7 @@ impl Fixer for MsrvNormalizeFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlTransform {
                     rule_id: "set_package_rust_version".to_string(),
@@ -177,6 +242,8 @@ impl Fixer for MsrvNormalizeFixer {
                 },
                 params_required,
                 preview: None,
+                impact: None,
+                workspace: None,
             });
         }
 
@@ -345,6 +412,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -414,6 +482,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/a/Cargo.toml");
@@ -447,6 +516,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/a/Cargo.toml");
@@ -502,6 +572,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
             sensor_id: "builddiag".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -617,6 +688,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         // High confidence + full consensus → Safe
@@ -663,6 +735,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         // Low confidence → Guarded (not promoted to Safe)
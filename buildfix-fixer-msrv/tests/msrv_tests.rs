@@ -100,6 +100,7 @@ fn receipt_set_with_msrv_finding(sensor: &str, check_id: &str, path: &str) -> Re
         path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
         sensor_id: sensor.to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -146,6 +147,7 @@ fn receipt_set_with_evidence(
         path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
         sensor_id: sensor.to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -161,6 +163,7 @@ fn plan_context() -> PlanContext {
         repo_root: Utf8PathBuf::from("."),
         artifacts_dir: Utf8PathBuf::from("artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     }
 }
 
@@ -1,4 +1,4 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::ReceiptSet;
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -15,6 +15,50 @@ impl PathDepVersionFixer {
     const SENSORS: &'static [&'static str] = &["depguard"];
     const CHECK_IDS: &'static [&'static str] =
         &["deps.path_requires_version", "cargo.path_requires_version"];
+    const KEY: &'static str = "path-dep-version";
+    const TITLE: &'static str = "Path Dependency Version";
+    const DETAILS: &'static str = r#"Adds a `version` field to path dependencies that are missing one.
+
+Path dependencies without a version field cannot be published to crates.io. This
+fix automatically determines the correct version by reading the target crate's
+Cargo.toml and adds the version field.
+
+Example transformation:
+    foo = { path = "../foo" }
+becomes:
+    foo = { path = "../foo", version = "1.0.0" }"#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as SAFE when the version can be determined because:
+- The version is read directly from the target crate's Cargo.toml
+- No guesswork or heuristics are involved
+- The edit is additive (only adds a field, doesn't change existing ones)
+- The change is required for publishing and is policy-compliant
+
+If the version cannot be determined (target crate has no version, or multiple
+possible targets exist), the fix is skipped to maintain safety."#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Find the target crate's Cargo.toml
+2. Read the `[package].version` field
+3. Add `version = "<version>"` to the path dependency
+
+Example:
+    [dependencies]
+    my-crate = { path = "../my-crate", version = "0.1.0" }
+
+If you need a different version constraint (e.g., ">=0.1"), manually edit
+the version field after buildfix applies the exact version."#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "deps.path_requires_version",
+            code: Some("missing_version"),
+        },
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "cargo.path_requires_version",
+            code: Some("missing_version"),
+        },
+    ];
 
     fn manifest_paths_from_triggers(
         triggers: &[buildfix_types::plan::FindingRef],
@@ -38,8 +82,7 @@ impl PathDepVersionFixer {
         let base = manifest.parent().unwrap_or_else(|| Utf8Path::new(""));
         let target_manifest: Utf8PathBuf = base.join(dep_path).join("Cargo.toml");
 
-        if let Ok(contents) = repo.read_to_string(&target_manifest)
-            && let Ok(doc) = contents.parse::<DocumentMut>()
+        if let Ok(doc) = repo.read_toml(&target_manifest)
             && let Some(pkg) = doc.get("package").and_then(|i| i.as_table())
             && let Some(v) = pkg
                 .get("version")
@@ -50,9 +93,7 @@ impl PathDepVersionFixer {
         }
 
         // 2) Workspace package version, if present.
-        if let Ok(contents) = repo.read_to_string(Utf8Path::new("Cargo.toml"))
-            && let Ok(doc) = contents.parse::<DocumentMut>()
-        {
+        if let Ok(doc) = repo.read_toml(Utf8Path::new("Cargo.toml")) {
             let ws = doc.get("workspace").and_then(|i| i.as_table());
             let ws_pkg = ws.and_then(|w| w.get("package")).and_then(|i| i.as_table());
             if let Some(v) = ws_pkg
@@ -198,10 +239,16 @@ impl Fixer for PathDepVersionFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Safe,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -230,12 +277,7 @@ impl Fixer for PathDepVersionFixer {
 
         let mut fixes = Vec::new();
         for manifest in Self::manifest_paths_from_triggers(&triggers) {
-            let contents = match repo.read_to_string(&manifest) {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
-
-            let doc = match contents.parse::<DocumentMut>() {
+            let doc = match repo.read_toml(&manifest) {
                 Ok(d) => d,
                 Err(_) => continue,
             };
@@ -291,6 +333,7 @@ impl Fixer for PathDepVersionFixer {
                     blocked_reason_token: None,
                     target: OpTarget {
                         path: manifest_path,
+                        sha256: None,
                     },
                     kind: OpKind::TomlTransform {
                         rule_id: "ensure_path_dep_has_version".to_string(),
@@ -307,6 +350,8 @@ impl Fixer for PathDepVersionFixer {
                         vec!["version".to_string()]
                     },
                     preview: None,
+                    impact: None,
+                    workspace: None,
                 });
             }
         }
@@ -409,6 +454,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/depguard/report.json"),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -489,6 +535,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/app/Cargo.toml");
@@ -516,6 +563,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/app/Cargo.toml");
@@ -568,6 +616,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let repo_missing = TestRepo::new(&[]);
@@ -610,6 +659,7 @@ mod tests {
             path: None,
             line: None,
             fingerprint: None,
+            severity: None,
         };
         assert_eq!(super::fix_key_for(&f), "depguard/-/X");
     }
@@ -99,6 +99,7 @@ fn receipt_set_with_finding(manifest_path: &str) -> ReceiptSet {
         path: Utf8PathBuf::from("artifacts/depguard/report.json"),
         sensor_id: "depguard".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -142,6 +143,7 @@ fn receipt_set_with_multiple_findings(manifest_paths: &[&str]) -> ReceiptSet {
         path: Utf8PathBuf::from("artifacts/depguard/report.json"),
         sensor_id: "depguard".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -157,6 +159,7 @@ fn plan_context() -> PlanContext {
         repo_root: Utf8PathBuf::from("."),
         artifacts_dir: Utf8PathBuf::from("artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     }
 }
 
@@ -10,7 +10,7 @@
 // The evidence-based safety promotion reduces false positive risk by requiring
 // multiple signals before allowing guarded operations.
 
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::{MatchedFinding, ReceiptSet};
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -34,6 +34,78 @@ impl RemoveUnusedDepsFixer {
         "udeps.unused_dependency",
         "machete.unused_dependency",
     ];
+    const KEY: &'static str = "remove-unused-deps";
+    const TITLE: &'static str = "Remove Unused Dependencies";
+    const DETAILS: &'static str = r#"Removes dependency entries that sensors report as unused.
+
+When tools such as cargo-udeps or cargo-machete identify dependencies that are
+not used by the crate, this fix removes the exact dependency entry from
+Cargo.toml (for example `dependencies.foo`).
+
+This is implemented as a direct TOML key removal (`toml_remove`) using
+sensor-provided location data (`toml_path`)."#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as UNSAFE because:
+- A dependency reported as unused can still be needed for optional/runtime flows
+- Build scripts, feature-gated code, and platform-specific paths can hide usage
+- Removing dependencies can change build behavior or break downstream workflows
+
+The edit is deterministic, but human confirmation is required before apply."#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Confirm the dependency is truly unused in all relevant build modes
+2. Remove it from the appropriate dependency table in Cargo.toml
+3. Run `cargo check --workspace --all-targets --all-features`
+
+To let buildfix apply this class of fix:
+    buildfix apply --apply --allow-unsafe"#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "cargo-udeps",
+            check_id: "deps.unused_dependency",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "udeps",
+            check_id: "deps.unused_dependency",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo-machete",
+            check_id: "deps.unused_dependency",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "machete",
+            check_id: "deps.unused_dependency",
+            code: None,
+        },
+        // Additional triggers from catalog not in main sensor outputs
+        TriggerPattern {
+            sensor: "cargo-udeps",
+            check_id: "deps.unused_dependencies",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo-udeps",
+            check_id: "cargo.unused_dependency",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo-udeps",
+            check_id: "cargo.unused_dependencies",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "udeps",
+            check_id: "udeps.unused_dependency",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "machete",
+            check_id: "machete.unused_dependency",
+            code: None,
+        },
+    ];
 
     fn parse_candidate(matched: &MatchedFinding) -> Option<RemoveCandidate> {
         let manifest_path = matched.finding.path.as_ref()?;
@@ -111,10 +183,7 @@ impl RemoveUnusedDepsFixer {
     }
 
     fn dep_item_exists(repo: &dyn RepoView, manifest: &Utf8Path, toml_path: &[String]) -> bool {
-        let Ok(contents) = repo.read_to_string(manifest) else {
-            return false;
-        };
-        let Ok(doc) = contents.parse::<DocumentMut>() else {
+        let Ok(doc) = repo.read_toml(manifest) else {
             return false;
         };
         get_dep_item(&doc, toml_path).is_some()
@@ -125,10 +194,16 @@ impl Fixer for RemoveUnusedDepsFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Unsafe,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -205,6 +280,7 @@ impl Fixer for RemoveUnusedDepsFixer {
                     path: None,
                     line: None,
                     fingerprint: None,
+                    severity: None,
                 }),
                 confidence: group.confidence,
                 tool_agreement: group.tool_agreement,
@@ -220,6 +296,7 @@ impl Fixer for RemoveUnusedDepsFixer {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: group.manifest.to_string(),
+                    sha256: None,
                 },
                 kind: OpKind::TomlRemove {
                     toml_path: group.toml_path,
@@ -231,6 +308,8 @@ impl Fixer for RemoveUnusedDepsFixer {
                 },
                 params_required: vec![],
                 preview: None,
+                impact: None,
+                workspace: None,
             });
         }
 
@@ -432,6 +511,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo-machete/report.json"),
             sensor_id: "cargo-machete".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -487,6 +567,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         }
     }
 
@@ -947,6 +1028,7 @@ mod tests {
                 path: Some("Cargo.toml".to_string()),
                 line: Some(1),
                 fingerprint: None,
+                severity: None,
             },
             confidence: Some(0.95),
             tool_agreement: true,
@@ -968,6 +1050,7 @@ mod tests {
                 path: Some("Cargo.toml".to_string()),
                 line: Some(1),
                 fingerprint: None,
+                severity: None,
             },
             confidence: None, // Missing
             tool_agreement: true,
@@ -990,6 +1073,7 @@ mod tests {
                 path: None,
                 line: None,
                 fingerprint: None,
+                severity: None,
             },
             confidence: Some(0.9),
             tool_agreement: true,
@@ -1008,6 +1092,7 @@ mod tests {
                 path: None,
                 line: None,
                 fingerprint: None,
+                severity: None,
             },
             confidence: Some(0.89),
             tool_agreement: true,
@@ -1028,6 +1113,7 @@ mod tests {
                 path: None,
                 line: None,
                 fingerprint: None,
+                severity: None,
             },
             confidence: Some(0.95),
             tool_agreement: true,
@@ -1045,6 +1131,7 @@ mod tests {
                 path: None,
                 line: None,
                 fingerprint: None,
+                severity: None,
             },
             confidence: Some(0.95),
             tool_agreement: true,
@@ -1062,6 +1149,7 @@ mod tests {
                 path: None,
                 line: None,
                 fingerprint: None,
+                severity: None,
             },
             confidence: Some(0.95),
             tool_agreement: true,
@@ -108,6 +108,7 @@ fn receipt_set_with_unused_dep(
         path: Utf8PathBuf::from(format!("artifacts/{}/report.json", sensor)),
         sensor_id: sensor.to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -159,6 +160,7 @@ fn receipt_set_with_evidence(
         path: Utf8PathBuf::from(format!("artifacts/{}/report.json", sensor)),
         sensor_id: sensor.to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -174,6 +176,7 @@ fn plan_context() -> PlanContext {
         repo_root: Utf8PathBuf::from("."),
         artifacts_dir: Utf8PathBuf::from("artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     }
 }
 
@@ -620,11 +623,13 @@ serde = "1.0""#,
             path: Utf8PathBuf::from("artifacts/cargo-machete/report.json"),
             sensor_id: "cargo-machete".to_string(),
             receipt: Ok(receipt1),
+            signature: None,
         },
         LoadedReceipt {
             path: Utf8PathBuf::from("artifacts/cargo-udeps/report.json"),
             sensor_id: "cargo-udeps".to_string(),
             receipt: Ok(receipt2),
+            signature: None,
         },
     ];
     let receipt_set = ReceiptSet::from_loaded(&loaded);
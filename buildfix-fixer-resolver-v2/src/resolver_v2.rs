@@ -1,10 +1,9 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::ReceiptSet;
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
 use buildfix_types::plan::{PlanOp, Rationale};
 use camino::Utf8PathBuf;
-use toml_edit::DocumentMut;
 
 pub struct ResolverV2Fixer;
 
@@ -15,14 +14,47 @@ impl ResolverV2Fixer {
     const SENSORS: &'static [&'static str] = &["builddiag", "cargo"];
     const CHECK_IDS: &'static [&'static str] =
         &["workspace.resolver_v2", "cargo.workspace.resolver_v2"];
+    const KEY: &'static str = "resolver-v2";
+    const TITLE: &'static str = "Workspace Resolver V2";
+    const DETAILS: &'static str = r#"Sets `[workspace].resolver = "2"` in the root Cargo.toml.
 
-    fn needs_fix(repo: &dyn RepoView, manifest: &Utf8PathBuf) -> bool {
-        let contents = match repo.read_to_string(manifest) {
-            Ok(c) => c,
-            Err(_) => return false,
-        };
+Cargo's resolver v2 is the modern feature resolver that provides correct feature
+unification across the dependency graph. It prevents surprising behavior where
+dev-dependencies can enable features in normal dependencies.
+
+This fix ensures your workspace uses the v2 resolver, which is required for:
+- Correct feature handling in workspaces
+- Avoiding feature leakage between different dependency kinds
+- Compatibility with modern Cargo practices"#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as SAFE because:
+- It only modifies the resolver field in the workspace table
+- The change is deterministic and predictable
+- Resolver v2 is backwards compatible for most projects
+- The edit is trivially reversible
+- It does not affect dependency versions or features directly"#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix, add or update your root Cargo.toml:
 
-        let doc = match contents.parse::<DocumentMut>() {
+    [workspace]
+    resolver = "2"
+
+If this fix is blocked because the file is not a workspace, you may need to
+convert your project to a workspace first or use `package.resolver = "2"`
+for single-crate projects."#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "builddiag",
+            check_id: "workspace.resolver_v2",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "cargo",
+            check_id: "cargo.workspace.resolver_v2",
+            code: None,
+        },
+    ];
+
+    fn needs_fix(repo: &dyn RepoView, manifest: &Utf8PathBuf) -> bool {
+        let doc = match repo.read_toml(manifest) {
             Ok(d) => d,
             Err(_) => return false,
         };
@@ -45,10 +77,16 @@ impl Fixer for ResolverV2Fixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Safe,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -81,6 +119,7 @@ impl Fixer for ResolverV2Fixer {
             blocked_reason_token: None,
             target: OpTarget {
                 path: manifest.to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: "ensure_workspace_resolver_v2".to_string(),
@@ -93,6 +132,8 @@ impl Fixer for ResolverV2Fixer {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }])
     }
 }
@@ -190,6 +231,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/cargo/report.json"),
             sensor_id: "cargo".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -240,6 +282,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
         let fixes = ResolverV2Fixer
             .plan(&ctx, &repo, &receipt_set())
@@ -302,6 +345,7 @@ mod tests {
             path: None,
             line: None,
             fingerprint: None,
+            severity: None,
         };
         assert_eq!(super::fix_key_for(&f), "builddiag/-/X");
     }
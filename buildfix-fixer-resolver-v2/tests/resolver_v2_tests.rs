@@ -98,6 +98,7 @@ fn receipt_set_with_resolver_finding(check_id: &str, code: &str) -> ReceiptSet {
         path: Utf8PathBuf::from("artifacts/cargo/report.json"),
         sensor_id: "cargo".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     ReceiptSet::from_loaded(&loaded)
 }
@@ -113,6 +114,7 @@ fn plan_context() -> PlanContext {
         repo_root: Utf8PathBuf::from("."),
         artifacts_dir: Utf8PathBuf::from("artifacts"),
         config: PlannerConfig::default(),
+        workspace_graph: None,
     }
 }
 
@@ -394,6 +396,7 @@ resolver = "1""#,
         path: Utf8PathBuf::from("artifacts/cargo/report.json"),
         sensor_id: "cargo".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let receipt_set = ReceiptSet::from_loaded(&loaded);
 
@@ -488,6 +491,7 @@ resolver = "1""#,
         path: Utf8PathBuf::from("artifacts/builddiag/report.json"),
         sensor_id: "builddiag".to_string(),
         receipt: Ok(receipt),
+        signature: None,
     }];
     let receipt_set = ReceiptSet::from_loaded(&loaded);
 
@@ -1,4 +1,4 @@
-use crate::fixers::{Fixer, FixerMeta};
+use crate::fixers::{Fixer, FixerMeta, TriggerPattern};
 use crate::planner::ReceiptSet;
 use crate::ports::RepoView;
 use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
@@ -15,12 +15,64 @@ impl WorkspaceInheritanceFixer {
     const SENSORS: &'static [&'static str] = &["depguard"];
     const CHECK_IDS: &'static [&'static str] =
         &["deps.workspace_inheritance", "cargo.workspace_inheritance"];
+    const KEY: &'static str = "workspace-inheritance";
+    const TITLE: &'static str = "Workspace Dependency Inheritance";
+    const DETAILS: &'static str = r#"Converts member crate dependencies to use workspace inheritance.
+
+When a dependency is defined in [workspace.dependencies], member crates should
+use `{ workspace = true }` instead of specifying the version directly. This
+ensures version consistency across the workspace.
+
+Example transformation:
+    serde = "1.0"
+becomes:
+    serde = { workspace = true }
+
+The fix preserves important per-crate overrides like:
+- `features` - additional features for this crate
+- `optional` - whether the dependency is optional
+- `default-features` - whether to include default features
+- `package` - renamed dependencies"#;
+    const SAFETY_RATIONALE: &'static str = r#"This fix is classified as SAFE because:
+- It only applies when the dependency exists in [workspace.dependencies]
+- The transformation is deterministic and preserves override keys
+- It enforces the single-source-of-truth pattern for versions
+- The edit is easily reversible
+
+The fix is skipped for:
+- Dependencies already using workspace = true
+- Path or git dependencies (these have different semantics)
+- Dependencies not defined in workspace.dependencies"#;
+    const REMEDIATION: &'static str = r#"To manually apply this fix:
+
+1. Ensure the dependency is defined in root Cargo.toml:
+    [workspace.dependencies]
+    serde = { version = "1.0", features = ["derive"] }
+
+2. Update member Cargo.toml to inherit:
+    [dependencies]
+    serde = { workspace = true }
+
+3. Add per-crate overrides if needed:
+    serde = { workspace = true, features = ["rc"] }
+
+Note: The workspace definition controls version and base features.
+Member crates can add features but cannot change the version."#;
+    const TRIGGERS: &'static [TriggerPattern] = &[
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "deps.workspace_inheritance",
+            code: None,
+        },
+        TriggerPattern {
+            sensor: "depguard",
+            check_id: "cargo.workspace_inheritance",
+            code: None,
+        },
+    ];
 
     fn workspace_deps(repo: &dyn RepoView) -> BTreeMap<String, WorkspaceDepSpec> {
-        let Ok(contents) = repo.read_to_string("Cargo.toml".as_ref()) else {
-            return BTreeMap::new();
-        };
-        let Ok(doc) = contents.parse::<DocumentMut>() else {
+        let Ok(doc) = repo.read_toml("Cargo.toml".as_ref()) else {
             return BTreeMap::new();
         };
 
@@ -310,10 +362,16 @@ impl Fixer for WorkspaceInheritanceFixer {
     fn meta(&self) -> FixerMeta {
         FixerMeta {
             fix_key: Self::FIX_ID,
+            key: Self::KEY,
+            title: Self::TITLE,
             description: Self::DESCRIPTION,
+            details: Self::DETAILS,
             safety: SafetyClass::Safe,
+            safety_rationale: Self::SAFETY_RATIONALE,
+            remediation: Self::REMEDIATION,
             consumes_sensors: Self::SENSORS,
             consumes_check_ids: Self::CHECK_IDS,
+            triggers: Self::TRIGGERS,
         }
     }
 
@@ -346,11 +404,7 @@ impl Fixer for WorkspaceInheritanceFixer {
 
         let mut fixes = Vec::new();
         for manifest in Self::manifest_paths_from_triggers(&triggers) {
-            let contents = match repo.read_to_string(&manifest) {
-                Ok(c) => c,
-                Err(_) => continue,
-            };
-            let doc = match contents.parse::<DocumentMut>() {
+            let doc = match repo.read_toml(&manifest) {
                 Ok(d) => d,
                 Err(_) => continue,
             };
@@ -434,6 +488,7 @@ impl Fixer for WorkspaceInheritanceFixer {
                     blocked_reason_token: None,
                     target: OpTarget {
                         path: manifest.to_string(),
+                        sha256: None,
                     },
                     kind: OpKind::TomlTransform {
                         rule_id: "use_workspace_dependency".to_string(),
@@ -446,6 +501,8 @@ impl Fixer for WorkspaceInheritanceFixer {
                     },
                     params_required: vec![],
                     preview: None,
+                    impact: None,
+                    workspace: None,
                 });
             }
         }
@@ -547,6 +604,7 @@ mod tests {
             path: Utf8PathBuf::from("artifacts/depguard/report.json"),
             sensor_id: "depguard".to_string(),
             receipt: Ok(receipt),
+            signature: None,
         }];
         ReceiptSet::from_loaded(&loaded)
     }
@@ -598,6 +656,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/member/Cargo.toml");
@@ -769,6 +828,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/member/Cargo.toml");
@@ -788,6 +848,7 @@ mod tests {
             repo_root: Utf8PathBuf::from("."),
             artifacts_dir: Utf8PathBuf::from("artifacts"),
             config: PlannerConfig::default(),
+            workspace_graph: None,
         };
 
         let receipt_set = receipt_set_for("crates/member/Cargo.toml");
@@ -815,6 +876,7 @@ mod tests {
             path: None,
             line: None,
             fingerprint: None,
+            severity: None,
         };
         assert_eq!(fix_key_for(&f), "depguard/-/X");
     }
@@ -1,5 +1,6 @@
 //! Minimal shared hashing helpers for buildfix crates.
 
+use sha1::Sha1;
 use sha2::{Digest, Sha256};
 
 /// Return the lowercase hexadecimal SHA-256 digest for the provided bytes.
@@ -9,6 +10,31 @@ pub fn sha256_hex(bytes: &[u8]) -> String {
     hex::encode(hasher.finalize())
 }
 
+/// SHA-256 of everything read from `reader`, hashed in chunks so large files
+/// are never held in memory.
+pub fn sha256_reader_hex(mut reader: impl std::io::Read) -> std::io::Result<String> {
+    let mut hasher = Sha256::new();
+    let mut buf = [0u8; 64 * 1024];
+    loop {
+        let n = reader.read(&mut buf)?;
+        if n == 0 {
+            break;
+        }
+        hasher.update(&buf[..n]);
+    }
+    Ok(hex::encode(hasher.finalize()))
+}
+
+/// Return the git blob object id (SHA-1 over `blob <len>\0<bytes>`) for the provided bytes.
+///
+/// This matches `git hash-object` and is what full-index patch headers carry.
+pub fn git_blob_sha1(bytes: &[u8]) -> String {
+    let mut hasher = Sha1::new();
+    hasher.update(format!("blob {}\0", bytes.len()).as_bytes());
+    hasher.update(bytes);
+    hex::encode(hasher.finalize())
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -19,4 +45,26 @@ mod tests {
         assert_ne!(sha256_hex(b"a"), sha256_hex(b"b"));
         assert_eq!(sha256_hex(b"workspace").len(), 64);
     }
+
+    #[test]
+    fn sha256_reader_hex_matches_slice_digest() {
+        let data = vec![7u8; 200 * 1024];
+        assert_eq!(
+            sha256_reader_hex(data.as_slice()).unwrap(),
+            sha256_hex(&data)
+        );
+    }
+
+    #[test]
+    fn git_blob_sha1_matches_git_hash_object() {
+        // `git hash-object /dev/null` and `printf 'hello\n' | git hash-object --stdin`
+        assert_eq!(
+            git_blob_sha1(b""),
+            "e69de29bb2d1d6434b8b29ae775ad8c2e48c5391"
+        );
+        assert_eq!(
+            git_blob_sha1(b"hello\n"),
+            "ce013625030ba8dba906f756967f9e9ca394464a"
+        );
+    }
 }
@@ -3,6 +3,7 @@ use buildfix_adapter_sdk::{Adapter, AdapterError, AdapterMetadata, ReceiptBuilde
 use buildfix_types::receipt::{Finding, Location, ReceiptEnvelope, Severity, VerdictStatus};
 use camino::Utf8PathBuf;
 use serde::Deserialize;
+use std::collections::BTreeMap;
 use std::path::Path;
 
 pub struct CargoAuditAdapter {
@@ -30,7 +31,11 @@ impl Adapter for CargoAuditAdapter {
 
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError> {
         let content = std::fs::read_to_string(path).map_err(AdapterError::Io)?;
-        convert_cargo_audit_json(&content, &self.sensor_id)
+        self.parse(&content)
+    }
+
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        convert_cargo_audit_json(content, &self.sensor_id)
     }
 }
 
@@ -52,7 +57,13 @@ fn convert_cargo_audit_json(
     content: &str,
     sensor_id: &str,
 ) -> Result<ReceiptEnvelope, AdapterError> {
-    let report: CargoAuditReport = serde_json::from_str(content).map_err(AdapterError::Json)?;
+    let value: serde_json::Value = serde_json::from_str(content).map_err(AdapterError::Json)?;
+    if value.get("cargo_audit").is_none() && value.get("vulnerabilities").is_some() {
+        let report: NativeAuditReport =
+            serde_json::from_value(value).map_err(AdapterError::Json)?;
+        return Ok(convert_native_report(report, sensor_id));
+    }
+    let report: CargoAuditReport = serde_json::from_value(value).map_err(AdapterError::Json)?;
 
     let mut findings = Vec::new();
     let mut error_count = 0u64;
@@ -85,6 +96,8 @@ fn convert_cargo_audit_json(
                 date: advisory.date.clone(),
                 categories: advisory.categories.clone(),
                 url: advisory.url.clone(),
+                patched_versions: Vec::new(),
+                kind: None,
             };
 
             findings.push(Finding {
@@ -121,6 +134,118 @@ fn convert_cargo_audit_json(
     Ok(builder.build())
 }
 
+/// Convert the native `cargo audit --json` output.
+///
+/// Vulnerabilities are errors; `warnings` entries (unmaintained, unsound,
+/// yanked, ...) are warnings. Findings carry the affected package and, when
+/// known, the patched version requirements a dependency bump must satisfy.
+fn convert_native_report(report: NativeAuditReport, sensor_id: &str) -> ReceiptEnvelope {
+    let mut findings = Vec::new();
+
+    for vuln in &report.vulnerabilities.list {
+        findings.push(native_finding(
+            Severity::Error,
+            &vuln.package,
+            Some(&vuln.advisory),
+            &vuln.versions.patched,
+            None,
+        ));
+    }
+
+    for (kind, warnings) in &report.warnings {
+        for warning in warnings {
+            let patched = warning
+                .versions
+                .as_ref()
+                .map(|v| v.patched.as_slice())
+                .unwrap_or_default();
+            findings.push(native_finding(
+                Severity::Warn,
+                &warning.package,
+                warning.advisory.as_ref(),
+                patched,
+                Some(warning.kind.clone().unwrap_or_else(|| kind.clone())),
+            ));
+        }
+    }
+
+    let error_count = findings
+        .iter()
+        .filter(|f| f.severity == Severity::Error)
+        .count() as u64;
+    let warning_count = findings.len() as u64 - error_count;
+
+    let status = if error_count > 0 {
+        VerdictStatus::Fail
+    } else if warning_count > 0 {
+        VerdictStatus::Warn
+    } else {
+        VerdictStatus::Pass
+    };
+
+    let mut builder = ReceiptBuilder::new(sensor_id)
+        .with_schema("cargo-audit.report.v1")
+        .with_status(status)
+        .with_counts(findings.len() as u64, error_count, warning_count);
+
+    for finding in findings {
+        builder = builder.with_finding(finding);
+    }
+
+    builder.build()
+}
+
+fn native_finding(
+    severity: Severity,
+    package: &Package,
+    advisory: Option<&NativeAdvisory>,
+    patched: &[String],
+    kind: Option<String>,
+) -> Finding {
+    let check_id = match (advisory, &kind) {
+        (Some(a), _) => format!("advisory.{}", a.id),
+        (None, Some(kind)) => format!("advisory.{}", kind),
+        (None, None) => "advisory.unknown".to_string(),
+    };
+
+    let message = match advisory {
+        Some(a) if a.description.is_empty() => a.title.clone(),
+        Some(a) => format!("{}\n{}", a.title, a.description),
+        None => format!(
+            "{} {} is {}",
+            package.name,
+            package.version,
+            kind.as_deref().unwrap_or("flagged")
+        ),
+    };
+
+    let data = CargoAuditAdvisoryData {
+        id: advisory.map(|a| a.id.clone()).unwrap_or_default(),
+        package_name: package.name.clone(),
+        package_version: package.version.clone(),
+        date: advisory.and_then(|a| a.date.clone()),
+        categories: advisory.map(|a| a.categories.clone()),
+        url: advisory.and_then(|a| a.url.clone()),
+        patched_versions: patched.to_vec(),
+        kind,
+    };
+
+    Finding {
+        severity,
+        check_id: Some(check_id),
+        code: None,
+        message: Some(message),
+        location: Some(Location {
+            path: Utf8PathBuf::from("Cargo.toml"),
+            line: None,
+            column: None,
+        }),
+        fingerprint: None,
+        data: Some(serde_json::to_value(data).unwrap_or_default()),
+        ..Default::default()
+    }
+}
+
 fn map_severity(severity: &Option<String>) -> Severity {
     match severity.as_deref() {
         Some("High") => Severity::Error,
@@ -175,6 +300,63 @@ struct CargoAuditAdvisoryData {
     date: Option<String>,
     categories: Option<Vec<String>>,
     url: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    patched_versions: Vec<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    kind: Option<String>,
+}
+
+/// Native `cargo audit --json` output (only the parts buildfix uses).
+#[derive(Debug, Deserialize)]
+struct NativeAuditReport {
+    vulnerabilities: NativeVulnerabilities,
+    #[serde(default)]
+    warnings: BTreeMap<String, Vec<NativeWarning>>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeVulnerabilities {
+    #[serde(default)]
+    list: Vec<NativeVulnerability>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeVulnerability {
+    advisory: NativeAdvisory,
+    #[serde(default)]
+    versions: NativeVersions,
+    package: Package,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeWarning {
+    #[serde(default)]
+    kind: Option<String>,
+    package: Package,
+    #[serde(default)]
+    advisory: Option<NativeAdvisory>,
+    #[serde(default)]
+    versions: Option<NativeVersions>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeAdvisory {
+    id: String,
+    title: String,
+    #[serde(default)]
+    description: String,
+    #[serde(default)]
+    date: Option<String>,
+    #[serde(default)]
+    url: Option<String>,
+    #[serde(default)]
+    categories: Vec<String>,
+}
+
+#[derive(Debug, Default, Deserialize)]
+struct NativeVersions {
+    #[serde(default)]
+    patched: Vec<String>,
 }
 
 #[cfg(test)]
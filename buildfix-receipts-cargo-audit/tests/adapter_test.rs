@@ -1,5 +1,6 @@
 use buildfix_adapter_sdk::{Adapter, AdapterTestHarness};
 use buildfix_receipts_cargo_audit::CargoAuditAdapter;
+use buildfix_types::receipt::Severity;
 use std::path::Path;
 
 #[test]
@@ -25,3 +26,38 @@ fn test_adapter_with_fixture_file() {
         buildfix_types::receipt::VerdictStatus::Fail
     );
 }
+
+#[test]
+fn test_adapter_loads_native_cargo_audit_json() {
+    let adapter = CargoAuditAdapter::new();
+    let receipt = adapter
+        .load(Path::new("tests/fixtures/native.json"))
+        .expect("should load native output");
+
+    assert_eq!(receipt.findings.len(), 3);
+    assert_eq!(receipt.verdict.counts.errors, 1);
+    assert_eq!(receipt.verdict.counts.warnings, 2);
+
+    let vuln = &receipt.findings[0];
+    assert_eq!(vuln.check_id.as_deref(), Some("advisory.RUSTSEC-2023-0071"));
+    assert_eq!(vuln.severity, Severity::Error);
+    let data = vuln.data.as_ref().expect("data");
+    assert_eq!(data["package_name"], "rsa");
+    assert_eq!(data["package_version"], "0.9.6");
+    assert_eq!(data["patched_versions"], serde_json::json!([">=0.10.0"]));
+
+    let unmaintained = &receipt.findings[1];
+    assert_eq!(
+        unmaintained.check_id.as_deref(),
+        Some("advisory.RUSTSEC-2024-0384")
+    );
+    assert_eq!(unmaintained.severity, Severity::Warn);
+    assert_eq!(unmaintained.data.as_ref().unwrap()["kind"], "unmaintained");
+
+    let yanked = &receipt.findings[2];
+    assert_eq!(yanked.check_id.as_deref(), Some("advisory.yanked"));
+    assert_eq!(
+        yanked.message.as_deref(),
+        Some("futures-util 0.3.30 is yanked")
+    );
+}
@@ -40,15 +40,20 @@ impl Adapter for CargoDenyAdapter {
 
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError> {
         let content = std::fs::read_to_string(path).map_err(AdapterError::Io)?;
-        let report: CargoDenyReport = serde_json::from_str(&content).map_err(AdapterError::Json)?;
+        self.parse(&content)
+    }
+
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        if is_native_output(content) {
+            return convert_native_output(content);
+        }
+        let report: CargoDenyReport = serde_json::from_str(content).map_err(AdapterError::Json)?;
         convert_report(report)
     }
 }
 
 fn convert_report(report: CargoDenyReport) -> Result<ReceiptEnvelope, AdapterError> {
     let mut findings = Vec::new();
-    let mut deny_count = 0u64;
-    let mut warn_count = 0u64;
 
     if let Some(licenses) = &report.licenses {
         process_license_section(licenses, &mut findings);
@@ -66,6 +71,13 @@ fn convert_report(report: CargoDenyReport) -> Result<ReceiptEnvelope, AdapterErr
         process_sources_section(sources, &mut findings);
     }
 
+    Ok(build_receipt(findings))
+}
+
+fn build_receipt(findings: Vec<Finding>) -> ReceiptEnvelope {
+    let mut deny_count = 0u64;
+    let mut warn_count = 0u64;
+
     for finding in &findings {
         match finding.severity {
             Severity::Error => deny_count += 1,
@@ -92,9 +104,126 @@ fn convert_report(report: CargoDenyReport) -> Result<ReceiptEnvelope, AdapterErr
         builder = builder.with_finding(finding);
     }
 
-    let receipt = builder.build();
+    builder.build()
+}
+
+/// `cargo deny --format json check` writes one JSON object per line, each
+/// tagged with a `type` (`diagnostic`, `summary`, `log`) and a `fields` payload.
+fn is_native_output(content: &str) -> bool {
+    content
+        .lines()
+        .map(str::trim)
+        .find(|l| !l.is_empty())
+        .and_then(|l| serde_json::from_str::<serde_json::Value>(l).ok())
+        .is_some_and(|v| v.get("type").is_some_and(|t| t.is_string()) && v.get("fields").is_some())
+}
+
+fn convert_native_output(content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+    let mut findings = Vec::new();
+    for line in content.lines().map(str::trim).filter(|l| !l.is_empty()) {
+        let entry: NativeEntry = serde_json::from_str(line).map_err(AdapterError::Json)?;
+        if entry.kind != "diagnostic" {
+            continue;
+        }
+        let diagnostic: NativeDiagnostic =
+            serde_json::from_value(entry.fields).map_err(AdapterError::Json)?;
+        findings.push(native_finding(diagnostic));
+    }
+    Ok(build_receipt(findings))
+}
+
+fn native_finding(diagnostic: NativeDiagnostic) -> Finding {
+    let severity = match diagnostic.severity.as_str() {
+        "error" => Severity::Error,
+        "warning" => Severity::Warn,
+        _ => Severity::Info,
+    };
+    let code = diagnostic.code.unwrap_or_else(|| "unknown".to_string());
+    let check_id = native_check_id(&code);
+    let is_license = check_id.starts_with("licenses.");
+
+    let data = FindingsData {
+        // For license diagnostics the primary label spans the license expression.
+        name: if is_license {
+            diagnostic.labels.first().and_then(|l| l.span.clone())
+        } else {
+            None
+        },
+        package: diagnostic.graphs.into_iter().find_map(|g| g.krate),
+        features: None,
+        source: None,
+        advisory_id: diagnostic.advisory.map(|a| a.id),
+    };
+
+    Finding {
+        severity,
+        check_id: Some(check_id),
+        code: Some(code),
+        message: Some(diagnostic.message),
+        location: None,
+        fingerprint: None,
+        data: Some(serde_json::to_value(data).unwrap_or_default()),
+        ..Default::default()
+    }
+}
 
-    Ok(receipt)
+const NATIVE_LICENSE_CODES: &[&str] = &[
+    "rejected",
+    "accepted",
+    "unlicensed",
+    "skipped-private-workspace-crate",
+    "license-not-encountered",
+    "license-exception-not-encountered",
+    "missing-clarification-file",
+    "parse-error",
+    "empty-license-field",
+    "no-license-field",
+    "gather-failure",
+];
+
+const NATIVE_ADVISORY_CODES: &[&str] = &[
+    "vulnerability",
+    "notice",
+    "unmaintained",
+    "unsound",
+    "yanked",
+    "index-failure",
+    "index-cache-load-failure",
+    "advisory-not-detected",
+    "unknown-advisory",
+];
+
+const NATIVE_SOURCE_CODES: &[&str] = &[
+    "git-source-underspecified",
+    "allowed-source",
+    "allowed-by-org",
+    "source-not-allowed",
+    "unmatched-source",
+    "unmatched-organization",
+];
+
+/// Map a native diagnostic code onto the check ids the report format uses,
+/// so fixers match either input the same way.
+fn native_check_id(code: &str) -> String {
+    if NATIVE_LICENSE_CODES.contains(&code) {
+        return match code {
+            "no-license-field" | "empty-license-field" => "licenses.missing".to_string(),
+            other => format!("licenses.{}", other),
+        };
+    }
+    if NATIVE_ADVISORY_CODES.contains(&code) {
+        return format!("advisories.{}", code);
+    }
+    if NATIVE_SOURCE_CODES.contains(&code) {
+        return match code {
+            "source-not-allowed" => "sources.untrusted".to_string(),
+            other => format!("sources.{}", other),
+        };
+    }
+    match code {
+        "duplicate" => "bans.multiple-versions".to_string(),
+        other => format!("bans.{}", other),
+    }
 }
 
 #[derive(Debug, Clone, Default, Serialize)]
@@ -465,6 +594,45 @@ struct SourcesWarn {
     source: Option<String>,
 }
 
+#[derive(Debug, Deserialize)]
+struct NativeEntry {
+    #[serde(rename = "type")]
+    kind: String,
+    #[serde(default)]
+    fields: serde_json::Value,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeDiagnostic {
+    severity: String,
+    #[serde(default)]
+    code: Option<String>,
+    message: String,
+    #[serde(default)]
+    labels: Vec<NativeLabel>,
+    #[serde(default)]
+    graphs: Vec<NativeGraph>,
+    #[serde(default)]
+    advisory: Option<NativeAdvisory>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeLabel {
+    #[serde(default)]
+    span: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeGraph {
+    #[serde(rename = "Krate", default)]
+    krate: Option<PackageInfo>,
+}
+
+#[derive(Debug, Deserialize)]
+struct NativeAdvisory {
+    id: String,
+}
+
 #[derive(Debug, Deserialize, Clone, Default, Serialize)]
 #[serde(rename_all = "kebab-case")]
 struct PackageInfo {
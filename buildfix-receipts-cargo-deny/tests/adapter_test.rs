@@ -1,4 +1,4 @@
-use buildfix_adapter_sdk::AdapterTestHarness;
+use buildfix_adapter_sdk::{Adapter, AdapterTestHarness};
 use buildfix_receipts_cargo_deny::CargoDenyAdapter;
 use pretty_assertions::assert_eq;
 
@@ -80,3 +80,35 @@ fn test_adapter_finding_severity_mapping() {
     assert_eq!(deny_findings.len(), 4, "should have 4 deny findings");
     assert_eq!(warn_findings.len(), 3, "should have 3 warn findings");
 }
+
+#[test]
+fn test_adapter_loads_native_json_lines() {
+    let adapter = CargoDenyAdapter::new();
+    let receipt = adapter
+        .load(std::path::Path::new("tests/fixtures/native.json"))
+        .expect("should load native output");
+
+    let check_ids: Vec<_> = receipt
+        .findings
+        .iter()
+        .map(|f| f.check_id.as_deref().unwrap_or_default())
+        .collect();
+    assert_eq!(
+        check_ids,
+        vec![
+            "licenses.unlicensed",
+            "licenses.rejected",
+            "advisories.vulnerability",
+            "bans.multiple-versions",
+        ]
+    );
+    assert_eq!(receipt.verdict.counts.errors, 3);
+    assert_eq!(receipt.verdict.counts.warnings, 1);
+
+    let rejected = receipt.findings[1].data.as_ref().expect("data");
+    assert_eq!(rejected["name"], "GPL-3.0");
+    assert_eq!(rejected["package"]["name"], "crate-b");
+
+    let vuln = receipt.findings[2].data.as_ref().expect("data");
+    assert_eq!(vuln["advisory_id"], "RUSTSEC-2023-0071");
+}
@@ -2,7 +2,8 @@ use anyhow::Result;
 use buildfix_adapter_sdk::{Adapter, AdapterError, AdapterMetadata, ReceiptBuilder};
 use buildfix_types::receipt::{Finding, Location, ReceiptEnvelope, Severity, VerdictStatus};
 use camino::Utf8PathBuf;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
+use std::io::{BufRead, BufReader};
 use std::path::Path;
 
 pub struct ClippyAdapter {
@@ -28,9 +29,15 @@ impl Adapter for ClippyAdapter {
         &self.sensor_id
     }
 
+    /// Reads the message stream line by line, so only compiler messages are
+    /// held in memory, not the whole (often very large) file.
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError> {
-        let content = std::fs::read_to_string(path).map_err(AdapterError::Io)?;
-        convert_clippy_json(&content, &self.sensor_id)
+        let file = std::fs::File::open(path).map_err(AdapterError::Io)?;
+        convert_clippy_lines(BufReader::new(file).lines(), &self.sensor_id)
+    }
+
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        convert_clippy_json(content, &self.sensor_id)
     }
 }
 
@@ -49,11 +56,19 @@ impl AdapterMetadata for ClippyAdapter {
 }
 
 fn convert_clippy_json(content: &str, sensor_id: &str) -> Result<ReceiptEnvelope, AdapterError> {
+    convert_clippy_lines(content.lines().map(|line| Ok(line.to_string())), sensor_id)
+}
+
+fn convert_clippy_lines(
+    lines: impl Iterator<Item = std::io::Result<String>>,
+    sensor_id: &str,
+) -> Result<ReceiptEnvelope, AdapterError> {
     let mut findings = Vec::new();
     let mut error_count = 0u64;
     let mut warning_count = 0u64;
 
-    for line in content.lines() {
+    for line in lines {
+        let line = line.map_err(AdapterError::Io)?;
         let trimmed = line.trim();
         if trimmed.is_empty() {
             continue;
@@ -83,7 +98,8 @@ fn convert_clippy_json(content: &str, sensor_id: &str) -> Result<ReceiptEnvelope
             _ => {}
         }
 
-        let check_id = msg.code.as_ref().map(|c| {
+        let code = msg.code.as_ref().map(|c| c.as_str().to_string());
+        let check_id = code.as_ref().map(|c| {
             if c.starts_with("clippy::") {
                 c.replace("::", ".")
             } else {
@@ -93,14 +109,22 @@ fn convert_clippy_json(content: &str, sensor_id: &str) -> Result<ReceiptEnvelope
 
         let location = extract_location(&msg);
 
+        let mut suggestions = Vec::new();
+        collect_suggestions(&msg, &mut suggestions);
+        let data = if suggestions.is_empty() {
+            None
+        } else {
+            Some(serde_json::json!({ "suggestions": suggestions }))
+        };
+
         findings.push(Finding {
             severity,
             check_id: check_id.clone(),
-            code: msg.code,
+            code,
             message: Some(msg.message),
             location,
             fingerprint: None,
-            data: None,
+            data,
             ..Default::default()
         });
     }
@@ -149,6 +173,68 @@ fn extract_location(msg: &ClippyMessageContent) -> Option<Location> {
     None
 }
 
+/// Collect machine-applicable suggestions from a diagnostic and its children.
+///
+/// Only `MachineApplicable` suggestions are kept: rustc guarantees those
+/// compile as-is, so a fixer may apply them without human judgement.
+fn collect_suggestions(msg: &ClippyMessageContent, out: &mut Vec<Suggestion>) {
+    for span in &msg.spans {
+        if span.suggestion_applicability.as_deref() != Some("MachineApplicable") {
+            continue;
+        }
+        let Some(replacement) = &span.suggested_replacement else {
+            continue;
+        };
+        let (Some(line_start), Some(line_end), Some(column_start), Some(column_end)) = (
+            span.line_start,
+            span.line_end,
+            span.column_start,
+            span.column_end,
+        ) else {
+            continue;
+        };
+        if span.file_name.is_empty() {
+            continue;
+        }
+
+        // A single-line span carries the full source line, which lets fixers
+        // confirm the file has not moved on since the diagnostic was emitted.
+        let line_text = match span.text.as_slice() {
+            [only] if line_start == line_end => Some(only.text.clone()),
+            _ => None,
+        };
+
+        out.push(Suggestion {
+            path: span.file_name.clone(),
+            line_start,
+            line_end,
+            column_start,
+            column_end,
+            replacement: replacement.clone(),
+            line_text,
+        });
+    }
+
+    for child in &msg.children {
+        collect_suggestions(child, out);
+    }
+}
+
+/// Machine-applicable suggestion recorded under `data.suggestions`.
+///
+/// Lines and columns are 1-based; `column_end` is exclusive.
+#[derive(Debug, Serialize)]
+struct Suggestion {
+    path: String,
+    line_start: u64,
+    line_end: u64,
+    column_start: u64,
+    column_end: u64,
+    replacement: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    line_text: Option<String>,
+}
+
 #[derive(Debug, Deserialize, Default)]
 #[allow(dead_code)]
 struct ClippyMessage {
@@ -177,13 +263,32 @@ struct ClippyTarget {
 #[derive(Debug, Deserialize, Default)]
 struct ClippyMessageContent {
     #[serde(default)]
-    code: Option<String>,
+    code: Option<ClippyCode>,
     #[serde(default)]
     level: Option<String>,
     #[serde(default)]
     message: String,
     #[serde(default)]
     spans: Vec<ClippySpan>,
+    #[serde(default)]
+    children: Vec<ClippyMessageContent>,
+}
+
+/// rustc emits `{"code": "...", "explanation": ...}`; a bare string is
+/// accepted for hand-written receipts.
+#[derive(Debug, Deserialize)]
+#[serde(untagged)]
+enum ClippyCode {
+    Plain(String),
+    Structured { code: String },
+}
+
+impl ClippyCode {
+    fn as_str(&self) -> &str {
+        match self {
+            ClippyCode::Plain(code) | ClippyCode::Structured { code } => code,
+        }
+    }
 }
 
 #[derive(Debug, Deserialize, Default)]
@@ -199,6 +304,18 @@ struct ClippySpan {
     column_start: Option<u64>,
     #[serde(default)]
     column_end: Option<u64>,
+    #[serde(default)]
+    text: Vec<ClippySpanText>,
+    #[serde(default)]
+    suggested_replacement: Option<String>,
+    #[serde(default)]
+    suggestion_applicability: Option<String>,
+}
+
+#[derive(Debug, Deserialize, Default)]
+struct ClippySpanText {
+    #[serde(default)]
+    text: String,
 }
 
 #[cfg(test)]
@@ -297,4 +414,46 @@ mod tests {
             Some("clippy.double_comparison".to_string())
         );
     }
+
+    #[test]
+    fn test_convert_clippy_json_structured_code() {
+        let json = r#"{"reason": "compiler-message", "message": {"code": {"code": "clippy::needless_return", "explanation": null}, "level": "warning", "message": "unneeded `return` statement", "spans": []}}
+"#;
+
+        let receipt = convert_clippy_json(json, "clippy").unwrap();
+
+        assert_eq!(receipt.findings.len(), 1);
+        assert_eq!(
+            receipt.findings[0].check_id,
+            Some("clippy.needless_return".to_string())
+        );
+        assert_eq!(
+            receipt.findings[0].code,
+            Some("clippy::needless_return".to_string())
+        );
+    }
+
+    #[test]
+    fn test_convert_clippy_json_keeps_machine_applicable_suggestions() {
+        let json = r#"{"reason": "compiler-message", "message": {"code": {"code": "clippy::needless_borrow", "explanation": null}, "level": "warning", "message": "this expression creates a reference which is immediately dereferenced by the compiler", "spans": [{"file_name": "src/lib.rs", "line_start": 3, "line_end": 3, "column_start": 9, "column_end": 13, "is_primary": true, "text": [{"text": "    foo(&bar);", "highlight_start": 9, "highlight_end": 13}], "suggested_replacement": null, "suggestion_applicability": null}], "children": [{"code": null, "level": "help", "message": "change this to", "spans": [{"file_name": "src/lib.rs", "line_start": 3, "line_end": 3, "column_start": 9, "column_end": 13, "is_primary": true, "text": [{"text": "    foo(&bar);", "highlight_start": 9, "highlight_end": 13}], "suggested_replacement": "bar", "suggestion_applicability": "MachineApplicable"}], "children": []}, {"code": null, "level": "help", "message": "maybe", "spans": [{"file_name": "src/lib.rs", "line_start": 3, "line_end": 3, "column_start": 5, "column_end": 8, "text": [], "suggested_replacement": "baz", "suggestion_applicability": "MaybeIncorrect"}], "children": []}]}}
+"#;
+
+        let receipt = convert_clippy_json(json, "clippy").unwrap();
+
+        let data = receipt.findings[0].data.as_ref().expect("suggestion data");
+        assert_eq!(
+            data,
+            &serde_json::json!({
+                "suggestions": [{
+                    "path": "src/lib.rs",
+                    "line_start": 3,
+                    "line_end": 3,
+                    "column_start": 9,
+                    "column_end": 13,
+                    "replacement": "bar",
+                    "line_text": "    foo(&bar);"
+                }]
+            })
+        );
+    }
 }
@@ -35,7 +35,11 @@ impl Adapter for SarifAdapter {
 
     fn load(&self, path: &Path) -> Result<ReceiptEnvelope, AdapterError> {
         let content = std::fs::read_to_string(path).map_err(AdapterError::Io)?;
-        let report: SarifLog = serde_json::from_str(&content).map_err(AdapterError::Json)?;
+        self.parse(&content)
+    }
+
+    fn parse(&self, content: &str) -> Result<ReceiptEnvelope, AdapterError> {
+        let report: SarifLog = serde_json::from_str(content).map_err(AdapterError::Json)?;
         convert_sarif(report, &self.sensor_id)
     }
 }
@@ -0,0 +1,92 @@
+//! Sensor outputs that are not receipt envelopes, loaded through adapter crates.
+//!
+//! Each entry pairs an artifacts-relative glob with the adapter that converts
+//! matching files into a [`ReceiptEnvelope`](buildfix_types::receipt::ReceiptEnvelope).
+
+use crate::ReceiptLoadError;
+use buildfix_adapter_sdk::{Adapter, AdapterError};
+use buildfix_receipts_cargo_audit::CargoAuditAdapter;
+use buildfix_receipts_cargo_deny::CargoDenyAdapter;
+use buildfix_receipts_clippy::ClippyAdapter;
+use buildfix_receipts_sarif::SarifAdapter;
+
+/// Artifacts-relative glob and the adapter used for matching files.
+pub(crate) struct AdapterSource {
+    pub pattern: &'static str,
+    pub adapter: fn() -> Box<dyn Adapter>,
+}
+
+pub(crate) const ADAPTER_SOURCES: &[AdapterSource] = &[
+    AdapterSource {
+        pattern: "*/*.sarif",
+        adapter: sarif,
+    },
+    AdapterSource {
+        pattern: "*/*.sarif.json",
+        adapter: sarif,
+    },
+    // `cargo audit --json > artifacts/<dir>/audit.json`
+    AdapterSource {
+        pattern: "*/audit.json",
+        adapter: cargo_audit,
+    },
+    AdapterSource {
+        pattern: "*/cargo-audit.json",
+        adapter: cargo_audit,
+    },
+    // `cargo deny --format json check 2> artifacts/<dir>/deny.json`
+    AdapterSource {
+        pattern: "*/deny.json",
+        adapter: cargo_deny,
+    },
+    AdapterSource {
+        pattern: "*/cargo-deny.json",
+        adapter: cargo_deny,
+    },
+    // `cargo clippy --message-format=json > artifacts/<dir>/clippy.json`
+    AdapterSource {
+        pattern: "*/clippy.json",
+        adapter: clippy,
+    },
+    AdapterSource {
+        pattern: "*/clippy.jsonl",
+        adapter: clippy,
+    },
+];
+
+/// The adapter whose pattern's file name part matches `file_name`.
+pub(crate) fn adapter_for_file_name(file_name: &str) -> Option<&'static AdapterSource> {
+    ADAPTER_SOURCES.iter().find(|source| {
+        let name_pattern = source.pattern.rsplit('/').next().unwrap_or(source.pattern);
+        glob::Pattern::new(name_pattern).is_ok_and(|p| p.matches(file_name))
+    })
+}
+
+fn sarif() -> Box<dyn Adapter> {
+    Box::new(SarifAdapter::new())
+}
+
+fn cargo_audit() -> Box<dyn Adapter> {
+    Box::new(CargoAuditAdapter::new())
+}
+
+fn cargo_deny() -> Box<dyn Adapter> {
+    Box::new(CargoDenyAdapter::new())
+}
+
+fn clippy() -> Box<dyn Adapter> {
+    Box::new(ClippyAdapter::new())
+}
+
+impl From<AdapterError> for ReceiptLoadError {
+    fn from(err: AdapterError) -> Self {
+        match err {
+            AdapterError::Io(e) => ReceiptLoadError::Io {
+                message: e.to_string(),
+            },
+            other => ReceiptLoadError::Json {
+                message: other.to_string(),
+            },
+        }
+    }
+}
@@ -0,0 +1,213 @@
+//! Receipts read straight out of `.zip`, `.tar` and `.tar.gz` bundles, which
+//! is how several CI systems hand over artifacts. Nothing is extracted to disk.
+//!
+//! Entries are matched against the default `<sensor>/<file>` layout, either at
+//! the top of the archive or under a single wrapping directory
+//! (`artifacts/<sensor>/report.json`).
+
+use camino::Utf8Path;
+
+const ARCHIVE_SUFFIXES: &[&str] = &[".zip", ".tar", ".tar.gz", ".tgz"];
+
+/// Whether `path` names a receipt bundle rather than an artifacts directory.
+pub fn is_archive(path: &Utf8Path) -> bool {
+    let name = path.as_str().to_ascii_lowercase();
+    ARCHIVE_SUFFIXES.iter().any(|suffix| name.ends_with(suffix)) && !path.is_dir()
+}
+
+#[cfg(feature = "archive")]
+pub(crate) use reader::load_archive;
+
+#[cfg(feature = "archive")]
+mod reader {
+    use crate::adapters::{ADAPTER_SOURCES, AdapterSource};
+    use crate::discovery::ReceiptDiscovery;
+    use crate::load::{LoadedReceipt, ReceiptLoadError, admit};
+    use crate::stream::{cap_findings, read_envelope};
+    use crate::trust::{SIGNATURE_SUFFIX, TrustPolicy};
+    use anyhow::Context;
+    use camino::Utf8Path;
+    use glob::{MatchOptions, Pattern};
+    use std::collections::BTreeMap;
+    use std::io::Read;
+
+    /// Where a matched entry is loaded from.
+    enum Layout {
+        Envelope,
+        Adapter(&'static AdapterSource),
+    }
+
+    /// Longest `.minisig` entry read; real signatures are a few hundred bytes.
+    const MAX_SIGNATURE_BYTES: u64 = 64 * 1024;
+
+    pub(crate) fn load_archive(
+        archive: &Utf8Path,
+        discovery: &ReceiptDiscovery,
+        trust: Option<&TrustPolicy>,
+    ) -> anyhow::Result<Vec<LoadedReceipt>> {
+        // Receipts are kept with their bytes until every signature entry has
+        // been seen; only matched receipts and signatures are ever read.
+        let mut pending: Vec<(String, LoadedReceipt, Vec<u8>)> = Vec::new();
+        let mut signatures = BTreeMap::new();
+        let mut out = Vec::new();
+
+        for_each_entry(archive, |name, entry| {
+            if let Some(receipt) = name.strip_suffix(SIGNATURE_SUFFIX) {
+                if trust.is_some() && match_layout(receipt).is_some() {
+                    let mut sig = String::new();
+                    entry
+                        .take(MAX_SIGNATURE_BYTES)
+                        .read_to_string(&mut sig)
+                        .with_context(|| format!("read {} in {}", name, archive))?;
+                    signatures.insert(receipt.to_string(), sig);
+                }
+                return Ok(());
+            }
+            let Some((rel, layout)) = match_layout(name) else {
+                return Ok(());
+            };
+            let sensor_id = discovery.sensor_id.sensor_id(&archive.join(rel));
+            if sensor_id == "buildfix" || sensor_id == "cockpit" {
+                return Ok(());
+            }
+
+            // Signed receipts are verified over their exact bytes, so those
+            // are buffered; otherwise envelopes stream from the entry.
+            let mut bytes = Vec::new();
+            let receipt = match layout {
+                Layout::Envelope if trust.is_none() => read_envelope(entry, discovery.max_findings)
+                    .map_err(|e| ReceiptLoadError::Json {
+                        message: e.to_string(),
+                    }),
+                Layout::Envelope => {
+                    entry
+                        .read_to_end(&mut bytes)
+                        .with_context(|| format!("read {} in {}", name, archive))?;
+                    read_envelope(bytes.as_slice(), discovery.max_findings).map_err(|e| {
+                        ReceiptLoadError::Json {
+                            message: e.to_string(),
+                        }
+                    })
+                }
+                Layout::Adapter(source) => {
+                    entry
+                        .read_to_end(&mut bytes)
+                        .with_context(|| format!("read {} in {}", name, archive))?;
+                    (source.adapter)()
+                        .parse(&String::from_utf8_lossy(&bytes))
+                        .map(|mut env| {
+                            cap_findings(&mut env, discovery.max_findings);
+                            env
+                        })
+                        .map_err(Into::into)
+                }
+            };
+            let loaded = LoadedReceipt {
+                path: archive.join(name),
+                sensor_id,
+                receipt,
+                signature: None,
+            };
+            if trust.is_some() {
+                pending.push((name.to_string(), loaded, bytes));
+            } else {
+                out.push(loaded);
+            }
+            Ok(())
+        })?;
+
+        if let Some(trust) = trust {
+            for (name, mut loaded, bytes) in pending {
+                let status =
+                    trust.verify_detached(&bytes, signatures.get(&name).map(String::as_str));
+                admit(trust, &mut loaded, status);
+                out.push(loaded);
+            }
+        }
+
+        out.sort_by(|a, b| a.path.cmp(&b.path));
+        Ok(out)
+    }
+
+    /// Match an entry name against the default layout, directly or below one
+    /// wrapping directory. Returns the matched tail of the name.
+    fn match_layout(name: &str) -> Option<(&str, Layout)> {
+        let options = MatchOptions {
+            require_literal_separator: true,
+            ..MatchOptions::default()
+        };
+        let candidates = [Some(name), name.split_once('/').map(|(_, rest)| rest)];
+        for rel in candidates.into_iter().flatten() {
+            if Pattern::new("*/report.json").is_ok_and(|p| p.matches_with(rel, options)) {
+                return Some((rel, Layout::Envelope));
+            }
+            for source in ADAPTER_SOURCES {
+                if Pattern::new(source.pattern).is_ok_and(|p| p.matches_with(rel, options)) {
+                    return Some((rel, Layout::Adapter(source)));
+                }
+            }
+        }
+        None
+    }
+
+    /// Call `visit` with the name and reader of each regular file in the
+    /// archive, in archive order. Unread entry bytes are skipped, not loaded.
+    fn for_each_entry(
+        archive: &Utf8Path,
+        mut visit: impl FnMut(&str, &mut dyn Read) -> anyhow::Result<()>,
+    ) -> anyhow::Result<()> {
+        let file = fs_err::File::open(archive)?;
+        let name = archive.as_str().to_ascii_lowercase();
+
+        if name.ends_with(".zip") {
+            let mut zip =
+                zip::ZipArchive::new(file).with_context(|| format!("open {}", archive))?;
+            for i in 0..zip.len() {
+                let mut entry = zip
+                    .by_index(i)
+                    .with_context(|| format!("read {}", archive))?;
+                if !entry.is_file() {
+                    continue;
+                }
+                let Some(path) = entry.enclosed_name().and_then(|p| entry_name(&p)) else {
+                    continue;
+                };
+                visit(&path, &mut entry)?;
+            }
+        } else {
+            let reader: Box<dyn Read> = if name.ends_with(".tar") {
+                Box::new(file)
+            } else {
+                Box::new(flate2::read::GzDecoder::new(file))
+            };
+            let mut tar = tar::Archive::new(reader);
+            for entry in tar.entries().with_context(|| format!("open {}", archive))? {
+                let mut entry = entry.with_context(|| format!("read {}", archive))?;
+                if !entry.header().entry_type().is_file() {
+                    continue;
+                }
+                let Some(path) = entry.path().ok().and_then(|p| entry_name(&p)) else {
+                    continue;
+                };
+                visit(&path, &mut entry)?;
+            }
+        }
+        Ok(())
+    }
+
+    /// Normalize an entry path to `/`-separated components without `./`.
+    /// Entries escaping the archive root are dropped.
+    fn entry_name(path: &std::path::Path) -> Option<String> {
+        use std::path::Component;
+
+        let mut parts = Vec::new();
+        for component in path.components() {
+            match component {
+                Component::Normal(part) => parts.push(part.to_str()?),
+                Component::CurDir => {}
+                _ => return None,
+            }
+        }
+        (!parts.is_empty()).then(|| parts.join("/"))
+    }
+}
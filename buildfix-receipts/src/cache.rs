@@ -0,0 +1,118 @@
+//! On-disk cache of parsed receipts, keyed by the sha256 of the receipt file.
+//!
+//! Repeated `plan` runs over unchanged artifacts then skip parsing (and
+//! adapter conversion) entirely. Entries record the loader, finding cap and
+//! buildfix version they were produced with; any mismatch is treated as a
+//! miss and the entry is rewritten.
+
+use crate::load::ReceiptLoadError;
+use buildfix_types::receipt::ReceiptEnvelope;
+use buildfix_types::telemetry;
+use camino::{Utf8Path, Utf8PathBuf};
+use serde::{Deserialize, Serialize};
+use std::sync::atomic::{AtomicU64, Ordering};
+use tracing::debug;
+
+/// Hit/miss counters for one loader run.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct CacheStats {
+    pub hits: u64,
+    pub misses: u64,
+}
+
+/// Parsed-receipt cache rooted at a directory.
+#[derive(Debug)]
+pub struct ReceiptCache {
+    dir: Utf8PathBuf,
+    hits: AtomicU64,
+    misses: AtomicU64,
+}
+
+#[derive(Serialize, Deserialize)]
+struct CacheEntry {
+    version: String,
+    loader: String,
+    max_findings: usize,
+    envelope: ReceiptEnvelope,
+}
+
+impl ReceiptCache {
+    pub fn new(dir: Utf8PathBuf) -> Self {
+        Self {
+            dir,
+            hits: AtomicU64::new(0),
+            misses: AtomicU64::new(0),
+        }
+    }
+
+    pub fn dir(&self) -> &Utf8Path {
+        &self.dir
+    }
+
+    pub fn stats(&self) -> CacheStats {
+        CacheStats {
+            hits: self.hits.load(Ordering::Relaxed),
+            misses: self.misses.load(Ordering::Relaxed),
+        }
+    }
+
+    /// Return the cached parse of `path`, or run `parse` and remember its
+    /// result. Failed parses are not cached, and cache I/O problems only
+    /// cost a re-parse.
+    pub(crate) fn get_or_parse(
+        &self,
+        path: &Utf8Path,
+        loader: &str,
+        max_findings: usize,
+        parse: impl FnOnce() -> Result<ReceiptEnvelope, ReceiptLoadError>,
+    ) -> Result<ReceiptEnvelope, ReceiptLoadError> {
+        let Some(sha) = fs_err::File::open(path)
+            .ok()
+            .and_then(|file| buildfix_hash::sha256_reader_hex(file).ok())
+        else {
+            return parse();
+        };
+        let entry_path = self.dir.join(format!("{}.json", sha));
+
+        if let Some(envelope) = self.read_entry(&entry_path, loader, max_findings) {
+            self.hits.fetch_add(1, Ordering::Relaxed);
+            return Ok(envelope);
+        }
+        self.misses.fetch_add(1, Ordering::Relaxed);
+
+        let envelope = parse()?;
+        let entry = CacheEntry {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            loader: loader.to_string(),
+            max_findings,
+            envelope,
+        };
+        let written = fs_err::create_dir_all(&self.dir)
+            .map_err(anyhow::Error::from)
+            .and_then(|()| Ok(serde_json::to_vec(&entry)?))
+            .and_then(|json| Ok(fs_err::write(&entry_path, json)?));
+        if let Err(e) = written {
+            debug!(
+                event = telemetry::RECEIPT_CACHE_WRITE_FAILED,
+                file = %entry_path,
+                error = %e,
+                "could not write receipt cache entry"
+            );
+        }
+        Ok(entry.envelope)
+    }
+
+    fn read_entry(
+        &self,
+        entry_path: &Utf8Path,
+        loader: &str,
+        max_findings: usize,
+    ) -> Option<ReceiptEnvelope> {
+        let file = fs_err::File::open(entry_path).ok()?;
+        let entry: CacheEntry = serde_json::from_reader(std::io::BufReader::new(file)).ok()?;
+        (entry.version == env!("CARGO_PKG_VERSION")
+            && entry.loader == loader
+            && entry.max_findings == max_findings)
+            .then_some(entry.envelope)
+    }
+}
@@ -0,0 +1,70 @@
+//! Where receipts are looked for and how their sensor id is inferred.
+//!
+//! The default layout is one directory per sensor under the artifacts dir
+//! (`<sensor>/report.json` plus the adapter formats). Custom patterns replace
+//! that layout entirely.
+
+use crate::stream::DEFAULT_MAX_FINDINGS;
+use serde::Deserialize;
+
+/// How a receipt's sensor id is derived from its path.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SensorIdRule {
+    /// Name of the directory holding the file: `ci-out/clippy/report.json` -> `clippy`.
+    #[default]
+    ParentDir,
+    /// File name up to its first `.`: `ci-out/clippy.report.json` -> `clippy`.
+    FileStem,
+}
+
+impl SensorIdRule {
+    pub fn sensor_id(&self, path: &camino::Utf8Path) -> String {
+        let id = match self {
+            SensorIdRule::ParentDir => path.parent().and_then(|p| p.file_name()),
+            SensorIdRule::FileStem => path
+                .file_name()
+                .and_then(|name| name.split('.').next())
+                .filter(|stem| !stem.is_empty()),
+        };
+        id.unwrap_or("unknown").to_string()
+    }
+}
+
+/// Receipt discovery settings.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReceiptDiscovery {
+    /// Globs relative to the artifacts dir (absolute globs are used as-is).
+    /// Empty means the built-in `<sensor>/<file>` layout.
+    pub patterns: Vec<String>,
+    pub sensor_id: SensorIdRule,
+    /// Findings kept per receipt; the rest are skipped while parsing and the
+    /// receipt is marked partial.
+    pub max_findings: usize,
+}
+
+impl Default for ReceiptDiscovery {
+    fn default() -> Self {
+        Self::new(Vec::new())
+    }
+}
+
+impl ReceiptDiscovery {
+    pub fn new(patterns: Vec<String>) -> Self {
+        Self {
+            patterns,
+            sensor_id: SensorIdRule::default(),
+            max_findings: DEFAULT_MAX_FINDINGS,
+        }
+    }
+
+    pub fn with_max_findings(mut self, max_findings: usize) -> Self {
+        self.max_findings = max_findings;
+        self
+    }
+
+    pub fn with_sensor_id(mut self, rule: SensorIdRule) -> Self {
+        self.sensor_id = rule;
+        self
+    }
+}
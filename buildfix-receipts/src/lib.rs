@@ -3,8 +3,39 @@
 //! buildfix consumes receipts produced by other tools. It intentionally does not enforce strict schema
 //! validation here; the director/conformance harness should do that. buildfix is tolerant so it can
 //! still plan fixes when a receipt contains extra fields or misses optional fields.
+//!
+//! Besides `report.json` envelopes, raw tool outputs are converted through their adapter crates so
+//! they can drive fixers directly: SARIF 2.1 logs (`*.sarif`, `*.sarif.json`), native
+//! `cargo audit` / `cargo deny` JSON (`audit.json`, `deny.json`), and clippy message streams
+//! (`clippy.json`).
+//!
+//! The `<sensor>/<file>` layout is the default; [`ReceiptDiscovery`] swaps it for custom globs and a
+//! [`SensorIdRule`] for naming the sensor of each match.
+//!
+//! The artifacts dir may also be a `.zip`, `.tar` or `.tar.gz` bundle; its entries are parsed without
+//! extracting anything to disk (`archive` feature, on by default).
+//!
+//! A [`ReceiptCache`] keeps parsed receipts on disk keyed by file sha256, so unchanged receipts are
+//! not re-parsed on the next run.
+//!
+//! With a [`TrustPolicy`], [`load_receipts_with_trust`] also checks detached minisign signatures
+//! (`report.json.minisig`). Receipts with a bad signature, or unsigned ones in strict mode, are
+//! kept as failed inputs so planning never sees their findings.
 
+mod adapters;
+mod archive;
+mod cache;
+mod discovery;
 mod load;
+mod stream;
+mod trust;
 
+pub use archive::is_archive;
 pub use buildfix_types::receipt::ReceiptEnvelope;
-pub use load::{LoadedReceipt, ReceiptLoadError, load_receipts};
+pub use cache::{CacheStats, ReceiptCache};
+pub use discovery::{ReceiptDiscovery, SensorIdRule};
+pub use load::{
+    LoadedReceipt, ReceiptLoadError, discover_receipts, load_receipts, load_receipts_with_trust,
+};
+pub use stream::DEFAULT_MAX_FINDINGS;
+pub use trust::{SIGNATURE_SUFFIX, SignatureStatus, TrustMode, TrustPolicy};
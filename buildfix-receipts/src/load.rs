@@ -1,17 +1,26 @@
+use crate::adapters::{ADAPTER_SOURCES, AdapterSource, adapter_for_file_name};
+use crate::cache::ReceiptCache;
+use crate::discovery::{ReceiptDiscovery, SensorIdRule};
+use crate::stream::{cap_findings, read_envelope};
+use crate::trust::{SignatureStatus, TrustPolicy};
 use anyhow::Context;
 use buildfix_types::receipt::ReceiptEnvelope;
+use buildfix_types::telemetry;
 use camino::{Utf8Path, Utf8PathBuf};
 use fs_err as fs;
 use glob::glob;
+use std::collections::BTreeSet;
 use thiserror::Error;
 use tracing::debug;
 
 #[derive(Debug, Clone)]
 pub struct LoadedReceipt {
     pub path: Utf8PathBuf,
-    /// Directory name under artifacts/... (best effort).
+    /// Inferred from the path; by default the directory name under artifacts/.
     pub sensor_id: String,
     pub receipt: Result<ReceiptEnvelope, ReceiptLoadError>,
+    /// Detached signature check; `None` when no trust policy was configured.
+    pub signature: Option<SignatureStatus>,
 }
 
 #[derive(Debug, Error, Clone)]
@@ -21,53 +30,209 @@ pub enum ReceiptLoadError {
 
     #[error("json parse error: {message}")]
     Json { message: String },
+
+    #[error("signature error: {message}")]
+    Signature { message: String },
 }
 
 pub fn load_receipts(artifacts_dir: &Utf8Path) -> anyhow::Result<Vec<LoadedReceipt>> {
-    let pattern = artifacts_dir.join("*/report.json");
+    load_receipts_with_trust(artifacts_dir, None)
+}
+
+/// Load receipts, checking detached signatures against `trust` when given.
+///
+/// Receipts the policy does not admit keep their place in the output with a
+/// [`ReceiptLoadError::Signature`] error, so they show up as failed inputs.
+pub fn load_receipts_with_trust(
+    artifacts_dir: &Utf8Path,
+    trust: Option<&TrustPolicy>,
+) -> anyhow::Result<Vec<LoadedReceipt>> {
+    discover_receipts(artifacts_dir, &ReceiptDiscovery::default(), trust, None)
+}
+
+/// Load receipts found by `discovery` under the artifacts dir.
+///
+/// When `artifacts_dir` is a `.zip`, `.tar` or `.tar.gz` bundle (and the
+/// `archive` feature is on), its entries are read in memory using the default
+/// layout; only the sensor id rule of `discovery` applies.
+///
+/// With custom patterns, each matched file goes through the adapter whose
+/// file name pattern it matches (e.g. `*.sarif`) and is otherwise parsed as a
+/// receipt envelope.
+///
+/// With a `cache`, files whose sha256 was parsed before are served from it.
+/// Archive entries are always parsed.
+pub fn discover_receipts(
+    artifacts_dir: &Utf8Path,
+    discovery: &ReceiptDiscovery,
+    trust: Option<&TrustPolicy>,
+    cache: Option<&ReceiptCache>,
+) -> anyhow::Result<Vec<LoadedReceipt>> {
+    let rule = discovery.sensor_id;
+    #[cfg(feature = "archive")]
+    if crate::archive::is_archive(artifacts_dir) {
+        return crate::archive::load_archive(artifacts_dir, discovery, trust);
+    }
+
+    let mut out = Vec::new();
+
+    if discovery.patterns.is_empty() {
+        for path in glob_sensor_files(artifacts_dir, "*/report.json", rule)? {
+            out.push(load_file(path, None, discovery, cache));
+        }
+        for source in ADAPTER_SOURCES {
+            for path in glob_sensor_files(artifacts_dir, source.pattern, rule)? {
+                out.push(load_file(path, Some(source), discovery, cache));
+            }
+        }
+    } else {
+        let mut paths = BTreeSet::new();
+        for pattern in &discovery.patterns {
+            // `**` globs also match directories; only files can be receipts.
+            paths.extend(
+                glob_sensor_files(artifacts_dir, pattern, rule)?
+                    .into_iter()
+                    .filter(|path| !path.is_dir()),
+            );
+        }
+        for path in paths {
+            let source = path.file_name().and_then(adapter_for_file_name);
+            out.push(load_file(path, source, discovery, cache));
+        }
+    }
+
+    if let Some(trust) = trust {
+        for loaded in &mut out {
+            check_signature(trust, loaded);
+        }
+    }
+
+    // Deterministic order matters.
+    out.sort_by(|a, b| a.path.cmp(&b.path));
+    Ok(out)
+}
+
+/// Parse one file, through `source`'s adapter or as a receipt envelope.
+fn load_file(
+    path: Utf8PathBuf,
+    source: Option<&AdapterSource>,
+    discovery: &ReceiptDiscovery,
+    cache: Option<&ReceiptCache>,
+) -> LoadedReceipt {
+    let sensor_id = discovery.sensor_id.sensor_id(&path);
+    let parse = || parse_file(&path, source, discovery.max_findings);
+    let receipt = match cache {
+        Some(cache) => {
+            let loader = source.map_or("report.json", |s| s.pattern);
+            cache.get_or_parse(&path, loader, discovery.max_findings, parse)
+        }
+        None => parse(),
+    };
+    LoadedReceipt {
+        path,
+        sensor_id,
+        receipt,
+        signature: None,
+    }
+}
+
+fn parse_file(
+    path: &Utf8Path,
+    source: Option<&AdapterSource>,
+    max_findings: usize,
+) -> Result<ReceiptEnvelope, ReceiptLoadError> {
+    match source {
+        // Adapters convert whole files; cap what they hand back.
+        Some(source) => (source.adapter)()
+            .load(path.as_std_path())
+            .map(|mut env| {
+                cap_findings(&mut env, max_findings);
+                env
+            })
+            .map_err(Into::into),
+        None => fs::File::open(path)
+            .map_err(|e| ReceiptLoadError::Io {
+                message: e.to_string(),
+            })
+            .and_then(|file| {
+                read_envelope(file, max_findings).map_err(|e| {
+                    if e.is_io() {
+                        ReceiptLoadError::Io {
+                            message: e.to_string(),
+                        }
+                    } else {
+                        ReceiptLoadError::Json {
+                            message: e.to_string(),
+                        }
+                    }
+                })
+            }),
+    }
+}
+
+fn check_signature(trust: &TrustPolicy, loaded: &mut LoadedReceipt) {
+    let status = match fs::read(&loaded.path) {
+        Ok(bytes) => trust.verify(&loaded.path, &bytes),
+        // Unreadable receipts already carry an io error.
+        Err(_) => return,
+    };
+    admit(trust, loaded, status);
+}
+
+/// Record `status` on the receipt, turning it into a signature error when the
+/// policy does not admit it.
+pub(crate) fn admit(trust: &TrustPolicy, loaded: &mut LoadedReceipt, status: SignatureStatus) {
+    if loaded.receipt.is_ok() && !trust.admits(&status) {
+        let message = match &status {
+            SignatureStatus::Invalid { reason } => format!("invalid signature: {}", reason),
+            _ => "unsigned receipt rejected by strict trust policy".to_string(),
+        };
+        debug!(
+            event = telemetry::RECEIPT_REJECTED,
+            file = %loaded.path,
+            error = %message,
+            "receipt not admitted by trust policy"
+        );
+        loaded.receipt = Err(ReceiptLoadError::Signature { message });
+    }
+    loaded.signature = Some(status);
+}
+
+/// Glob `pattern` under the artifacts dir, skipping reserved output
+/// directories that are not sensor receipts.
+fn glob_sensor_files(
+    artifacts_dir: &Utf8Path,
+    pattern: &str,
+    rule: SensorIdRule,
+) -> anyhow::Result<Vec<Utf8PathBuf>> {
+    let pattern = artifacts_dir.join(pattern);
     let pattern_str = pattern.as_str();
 
-    debug!(pattern = %pattern_str, "scanning artifacts for receipts");
+    debug!(
+        event = telemetry::RECEIPTS_SCAN,
+        pattern = %pattern_str,
+        "scanning artifacts for receipts"
+    );
 
     let mut out = Vec::new();
-    for entry in glob(pattern_str).context("glob artifacts/*/report.json")? {
+    for entry in glob(pattern_str).with_context(|| format!("glob {}", pattern_str))? {
         let path = entry
             .map_err(|e| anyhow::anyhow!("glob error: {e}"))?
             .to_string_lossy()
             .to_string();
 
         let utf8_path = Utf8PathBuf::from(path);
-        let sensor_id = utf8_path
-            .parent()
-            .and_then(|p| p.file_name())
-            .unwrap_or("unknown")
-            .to_string();
-
-        // Skip reserved output directories — not sensor receipts.
+        let sensor_id = rule.sensor_id(&utf8_path);
         if sensor_id == "buildfix" || sensor_id == "cockpit" {
-            debug!(path = %utf8_path, %sensor_id, "skipping non-sensor receipt");
+            debug!(
+                event = telemetry::RECEIPT_SKIPPED,
+                file = %utf8_path,
+                %sensor_id,
+                "skipping non-sensor receipt"
+            );
             continue;
         }
-
-        let receipt = match fs::read_to_string(&utf8_path) {
-            Ok(s) => {
-                serde_json::from_str::<ReceiptEnvelope>(&s).map_err(|e| ReceiptLoadError::Json {
-                    message: e.to_string(),
-                })
-            }
-            Err(e) => Err(ReceiptLoadError::Io {
-                message: e.to_string(),
-            }),
-        };
-
-        out.push(LoadedReceipt {
-            path: utf8_path,
-            sensor_id,
-            receipt,
-        });
+        out.push(utf8_path);
     }
-
-    // Deterministic order matters.
-    out.sort_by(|a, b| a.path.cmp(&b.path));
     Ok(out)
 }
@@ -0,0 +1,129 @@
+//! Incremental receipt parsing with a cap on kept findings.
+//!
+//! Envelopes are deserialized straight from a buffered reader, and findings
+//! past the cap are skipped without being materialized, so a huge sensor
+//! report costs memory proportional to the cap rather than the file size.
+
+use buildfix_types::receipt::{Finding, ReceiptCapabilities, ReceiptEnvelope};
+use serde::de::{DeserializeSeed, Deserializer, IgnoredAny, MapAccess, SeqAccess, Visitor};
+use std::fmt;
+use std::io::Read;
+
+/// Findings kept per receipt unless configured otherwise.
+pub const DEFAULT_MAX_FINDINGS: usize = 100_000;
+
+/// Parse an envelope from `reader`, keeping at most `max_findings` findings.
+pub(crate) fn read_envelope<R: Read>(
+    reader: R,
+    max_findings: usize,
+) -> serde_json::Result<ReceiptEnvelope> {
+    let mut de = serde_json::Deserializer::from_reader(std::io::BufReader::new(reader));
+    let (mut envelope, total) = EnvelopeSeed { max_findings }.deserialize(&mut de)?;
+    de.end()?;
+    note_truncation(&mut envelope, total);
+    Ok(envelope)
+}
+
+/// Drop findings past `max_findings` from an envelope that is already in
+/// memory (adapter output, archive entries).
+pub(crate) fn cap_findings(envelope: &mut ReceiptEnvelope, max_findings: usize) {
+    let total = envelope.findings.len() as u64;
+    envelope.findings.truncate(max_findings);
+    note_truncation(envelope, total);
+}
+
+/// Mark the receipt partial when findings were dropped.
+fn note_truncation(envelope: &mut ReceiptEnvelope, total: u64) {
+    let kept = envelope.findings.len() as u64;
+    if kept == total {
+        return;
+    }
+    let note = format!("findings truncated: kept {} of {}", kept, total);
+    let caps = envelope
+        .capabilities
+        .get_or_insert_with(ReceiptCapabilities::default);
+    caps.partial = true;
+    caps.reason = Some(match caps.reason.take() {
+        Some(reason) => format!("{}; {}", reason, note),
+        None => note,
+    });
+}
+
+/// Deserializes an envelope, routing `findings` through [`FindingsSeed`] and
+/// everything else through the regular `ReceiptEnvelope` impl.
+struct EnvelopeSeed {
+    max_findings: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for EnvelopeSeed {
+    type Value = (ReceiptEnvelope, u64);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_map(self)
+    }
+}
+
+impl<'de> Visitor<'de> for EnvelopeSeed {
+    type Value = (ReceiptEnvelope, u64);
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a receipt envelope object")
+    }
+
+    fn visit_map<A: MapAccess<'de>>(self, mut map: A) -> Result<Self::Value, A::Error> {
+        let mut rest = serde_json::Map::new();
+        let mut findings = Vec::new();
+        let mut total = 0;
+        while let Some(key) = map.next_key::<String>()? {
+            if key == "findings" {
+                (findings, total) = map.next_value_seed(FindingsSeed {
+                    max_findings: self.max_findings,
+                })?;
+            } else {
+                rest.insert(key, map.next_value()?);
+            }
+        }
+        let mut envelope: ReceiptEnvelope = serde_json::from_value(serde_json::Value::Object(rest))
+            .map_err(serde::de::Error::custom)?;
+        envelope.findings = findings;
+        Ok((envelope, total))
+    }
+}
+
+/// Keeps the first `max_findings` elements and counts the rest.
+struct FindingsSeed {
+    max_findings: usize,
+}
+
+impl<'de> DeserializeSeed<'de> for FindingsSeed {
+    type Value = (Vec<Finding>, u64);
+
+    fn deserialize<D: Deserializer<'de>>(self, deserializer: D) -> Result<Self::Value, D::Error> {
+        deserializer.deserialize_seq(self)
+    }
+}
+
+impl<'de> Visitor<'de> for FindingsSeed {
+    type Value = (Vec<Finding>, u64);
+
+    fn expecting(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        f.write_str("a list of findings")
+    }
+
+    fn visit_seq<A: SeqAccess<'de>>(self, mut seq: A) -> Result<Self::Value, A::Error> {
+        let mut findings = Vec::new();
+        let mut total = 0u64;
+        loop {
+            if findings.len() < self.max_findings {
+                match seq.next_element::<Finding>()? {
+                    Some(finding) => findings.push(finding),
+                    None => break,
+                }
+            } else if seq.next_element::<IgnoredAny>()?.is_none() {
+                break;
+            }
+            total += 1;
+        }
+        Ok((findings, total))
+    }
+}
@@ -0,0 +1,140 @@
+//! Detached minisign (ed25519) signatures next to receipts.
+//!
+//! A receipt at `artifacts/<sensor>/report.json` is signed by
+//! `artifacts/<sensor>/report.json.minisig`. Both prehashed and legacy
+//! minisign signatures are accepted.
+
+use anyhow::Context;
+use camino::Utf8Path;
+use minisign_verify::{PublicKey, Signature};
+use serde::{Deserialize, Serialize};
+
+/// Suffix of the detached signature file for a receipt.
+pub const SIGNATURE_SUFFIX: &str = ".minisig";
+
+/// How unsigned receipts are treated once a trust policy is configured.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum TrustMode {
+    /// Unsigned receipts are loaded and reported as `unsigned`.
+    #[default]
+    Permissive,
+    /// Only receipts with a valid signature are planned from.
+    Strict,
+}
+
+/// Public keys receipts must be signed with.
+///
+/// Receipts whose signature does not verify are never planned from, whatever
+/// the mode.
+#[derive(Debug, Clone, Default)]
+pub struct TrustPolicy {
+    pub mode: TrustMode,
+    keys: Vec<PublicKey>,
+}
+
+impl TrustPolicy {
+    pub fn new(mode: TrustMode) -> Self {
+        Self {
+            mode,
+            keys: Vec::new(),
+        }
+    }
+
+    /// Trust a minisign public key, given either as the bare base64 line or
+    /// as the contents of a `minisign.pub` file.
+    pub fn with_public_key(mut self, key: &str) -> anyhow::Result<Self> {
+        let key = key.trim();
+        let parsed = if key.lines().count() > 1 {
+            PublicKey::decode(key)
+        } else {
+            PublicKey::from_base64(key)
+        }
+        .map_err(|e| anyhow::anyhow!("invalid minisign public key: {}", e))?;
+        self.keys.push(parsed);
+        Ok(self)
+    }
+
+    /// Trust the minisign public key stored in `path`.
+    pub fn with_public_key_file(self, path: &Utf8Path) -> anyhow::Result<Self> {
+        let contents =
+            fs_err::read_to_string(path).with_context(|| format!("read public key {}", path))?;
+        self.with_public_key(&contents)
+            .with_context(|| format!("load public key {}", path))
+    }
+
+    pub fn key_count(&self) -> usize {
+        self.keys.len()
+    }
+
+    /// Check the detached signature of the receipt at `path` with contents `bytes`.
+    pub fn verify(&self, path: &Utf8Path, bytes: &[u8]) -> SignatureStatus {
+        let sig_path = format!("{}{}", path, SIGNATURE_SUFFIX);
+        match fs_err::read_to_string(&sig_path) {
+            Ok(text) => self.verify_detached(bytes, Some(&text)),
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => self.verify_detached(bytes, None),
+            Err(e) => SignatureStatus::Invalid {
+                reason: e.to_string(),
+            },
+        }
+    }
+
+    /// Check `bytes` against signature text already in memory; `None` means
+    /// the receipt has no signature.
+    pub fn verify_detached(&self, bytes: &[u8], sig_text: Option<&str>) -> SignatureStatus {
+        let Some(sig_text) = sig_text else {
+            return SignatureStatus::Unsigned;
+        };
+        let signature = match Signature::decode(sig_text) {
+            Ok(sig) => sig,
+            Err(e) => {
+                return SignatureStatus::Invalid {
+                    reason: format!("malformed signature: {}", e),
+                };
+            }
+        };
+
+        let mut last_err = "no trusted public keys configured".to_string();
+        for key in &self.keys {
+            match key.verify(bytes, &signature, true) {
+                Ok(()) => return SignatureStatus::Verified,
+                Err(e) => last_err = e.to_string(),
+            }
+        }
+        SignatureStatus::Invalid { reason: last_err }
+    }
+
+    /// Whether a receipt with `status` may be planned from.
+    pub fn admits(&self, status: &SignatureStatus) -> bool {
+        match status {
+            SignatureStatus::Verified => true,
+            SignatureStatus::Unsigned => self.mode == TrustMode::Permissive,
+            SignatureStatus::Invalid { .. } => false,
+        }
+    }
+}
+
+/// Outcome of checking a receipt's detached signature.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SignatureStatus {
+    Verified,
+    Unsigned,
+    Invalid { reason: String },
+}
+
+impl SignatureStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            SignatureStatus::Verified => "verified",
+            SignatureStatus::Unsigned => "unsigned",
+            SignatureStatus::Invalid { .. } => "invalid",
+        }
+    }
+
+    pub fn reason(&self) -> Option<&str> {
+        match self {
+            SignatureStatus::Invalid { reason } => Some(reason),
+            _ => None,
+        }
+    }
+}
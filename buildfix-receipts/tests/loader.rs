@@ -1,7 +1,14 @@
 //! Unit tests for receipt loader.
 
-use buildfix_receipts::{ReceiptLoadError, load_receipts};
-use camino::Utf8PathBuf;
+use base64::Engine;
+use base64::engine::general_purpose::STANDARD as BASE64;
+use buildfix_receipts::{
+    CacheStats, ReceiptCache, ReceiptDiscovery, ReceiptLoadError, SensorIdRule, SignatureStatus,
+    TrustMode, TrustPolicy, discover_receipts, load_receipts, load_receipts_with_trust,
+};
+use buildfix_types::receipt::Severity;
+use camino::{Utf8Path, Utf8PathBuf};
+use ed25519_dalek::{Signer, SigningKey};
 use std::fs;
 use tempfile::TempDir;
 
@@ -329,3 +336,650 @@ fn test_findings_with_optional_fields() {
     assert!(envelope.findings[0].check_id.is_none());
     assert!(envelope.findings[0].location.is_none());
 }
+
+fn sarif_log() -> &'static str {
+    r#"{
+        "version": "2.1.0",
+        "runs": [{
+            "tool": { "driver": { "name": "clippy", "version": "0.1.80" } },
+            "results": [
+                {
+                    "ruleId": "clippy::unwrap_used",
+                    "level": "error",
+                    "message": { "text": "used `unwrap()` on a `Result` value" },
+                    "locations": [{
+                        "physicalLocation": {
+                            "artifactLocation": { "uri": "src/lib.rs" },
+                            "region": { "startLine": 12, "startColumn": 5 }
+                        }
+                    }]
+                },
+                { "ruleId": "clippy::needless_return", "level": "warning", "message": {} }
+            ]
+        }]
+    }"#
+}
+
+#[test]
+fn test_sarif_results_map_to_findings() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    fs::create_dir_all(artifacts.join("clippy")).unwrap();
+    fs::write(artifacts.join("clippy").join("clippy.sarif"), sarif_log()).unwrap();
+
+    let receipts = load_receipts(&artifacts).unwrap();
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].sensor_id, "clippy");
+
+    let env = receipts[0].receipt.as_ref().expect("sarif parses");
+    assert_eq!(env.schema, "sarif.report.v1");
+    assert_eq!(env.findings.len(), 2);
+    assert_eq!(env.verdict.counts.errors, 1);
+    assert_eq!(env.verdict.counts.warnings, 1);
+
+    let first = &env.findings[0];
+    assert_eq!(first.check_id.as_deref(), Some("clippy::unwrap_used"));
+    assert_eq!(first.severity, Severity::Error);
+    let loc = first.location.as_ref().expect("location");
+    assert_eq!(loc.path.as_str(), "src/lib.rs");
+    assert_eq!(loc.line, Some(12));
+    assert_eq!(loc.column, Some(5));
+}
+
+#[test]
+fn test_sarif_alongside_report_json() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    create_receipt(&artifacts, "builddiag", valid_receipt());
+    fs::create_dir_all(artifacts.join("codeql")).unwrap();
+    fs::write(
+        artifacts.join("codeql").join("results.sarif.json"),
+        "{ not json",
+    )
+    .unwrap();
+    fs::create_dir_all(artifacts.join("buildfix")).unwrap();
+    fs::write(artifacts.join("buildfix").join("out.sarif"), sarif_log()).unwrap();
+
+    let receipts = load_receipts(&artifacts).unwrap();
+    assert_eq!(receipts.len(), 2);
+    assert_eq!(receipts[0].sensor_id, "builddiag");
+    assert_eq!(receipts[1].sensor_id, "codeql");
+    assert!(matches!(
+        receipts[1].receipt,
+        Err(ReceiptLoadError::Json { .. })
+    ));
+}
+
+#[test]
+fn test_native_cargo_audit_and_deny_outputs() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    fs::create_dir_all(artifacts.join("audit")).unwrap();
+    fs::write(
+        artifacts.join("audit").join("audit.json"),
+        r#"{
+            "vulnerabilities": {
+                "found": true,
+                "count": 1,
+                "list": [{
+                    "advisory": { "id": "RUSTSEC-2023-0071", "package": "rsa", "title": "Marvin Attack" },
+                    "versions": { "patched": [">=0.10.0"], "unaffected": [] },
+                    "package": { "name": "rsa", "version": "0.9.6" }
+                }]
+            },
+            "warnings": {}
+        }"#,
+    )
+    .unwrap();
+    fs::create_dir_all(artifacts.join("deny")).unwrap();
+    fs::write(
+        artifacts.join("deny").join("deny.json"),
+        concat!(
+            r#"{"type":"diagnostic","fields":{"code":"unlicensed","graphs":[{"Krate":{"name":"crate-a","version":"0.1.0"}}],"labels":[],"message":"crate-a = 0.1.0 is unlicensed","notes":[],"severity":"error"}}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let receipts = load_receipts(&artifacts).unwrap();
+    assert_eq!(receipts.len(), 2);
+
+    let audit = receipts[0].receipt.as_ref().expect("audit parses");
+    assert_eq!(receipts[0].sensor_id, "audit");
+    assert_eq!(audit.tool.name, "cargo-audit");
+    assert_eq!(
+        audit.findings[0].check_id.as_deref(),
+        Some("advisory.RUSTSEC-2023-0071")
+    );
+
+    let deny = receipts[1].receipt.as_ref().expect("deny parses");
+    assert_eq!(receipts[1].sensor_id, "deny");
+    assert_eq!(deny.tool.name, "cargo-deny");
+    assert_eq!(
+        deny.findings[0].check_id.as_deref(),
+        Some("licenses.unlicensed")
+    );
+}
+
+#[test]
+fn test_clippy_message_stream_keeps_suggestions() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    fs::create_dir_all(artifacts.join("clippy")).unwrap();
+    fs::write(
+        artifacts.join("clippy").join("clippy.json"),
+        concat!(
+            r#"{"reason":"compiler-artifact","package_id":"demo 0.1.0"}"#,
+            "\n",
+            r#"{"reason":"compiler-message","message":{"code":{"code":"clippy::needless_borrow","explanation":null},"level":"warning","message":"needless borrow","spans":[{"file_name":"src/lib.rs","line_start":2,"line_end":2,"column_start":9,"column_end":13,"text":[{"text":"    foo(&bar);","highlight_start":9,"highlight_end":13}]}],"children":[{"code":null,"level":"help","message":"change this to","spans":[{"file_name":"src/lib.rs","line_start":2,"line_end":2,"column_start":9,"column_end":13,"text":[{"text":"    foo(&bar);","highlight_start":9,"highlight_end":13}],"suggested_replacement":"bar","suggestion_applicability":"MachineApplicable"}],"children":[]}]}}"#,
+            "\n",
+        ),
+    )
+    .unwrap();
+
+    let receipts = load_receipts(&artifacts).unwrap();
+    assert_eq!(receipts.len(), 1);
+    let env = receipts[0].receipt.as_ref().expect("clippy parses");
+    assert_eq!(env.tool.name, "clippy");
+    assert_eq!(env.findings.len(), 1);
+    assert_eq!(
+        env.findings[0].check_id.as_deref(),
+        Some("clippy.needless_borrow")
+    );
+    let suggestions = &env.findings[0].data.as_ref().expect("data")["suggestions"];
+    assert_eq!(suggestions[0]["replacement"], "bar");
+}
+
+// ---------------------------------------------------------------------------
+// Finding caps
+// ---------------------------------------------------------------------------
+
+fn receipt_with_findings(count: usize, capabilities: &str) -> String {
+    let findings: Vec<String> = (0..count)
+        .map(|i| format!(r#"{{"severity":"warn","check_id":"check.{i}","message":"m{i}"}}"#))
+        .collect();
+    format!(
+        r#"{{"schema":"test.report.v1","findings":[{}],"tool":{{"name":"big"}}{}}}"#,
+        findings.join(","),
+        capabilities
+    )
+}
+
+#[test]
+fn test_findings_past_cap_are_dropped_and_noted() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    create_receipt(&artifacts, "big", &receipt_with_findings(5, ""));
+    create_receipt(
+        &artifacts,
+        "partial",
+        &receipt_with_findings(3, r#","capabilities":{"partial":true,"reason":"timeout"}"#),
+    );
+    create_receipt(&artifacts, "small", &receipt_with_findings(2, ""));
+
+    let discovery = ReceiptDiscovery::default().with_max_findings(2);
+    let receipts = discover_receipts(&artifacts, &discovery, None, None).unwrap();
+
+    let big = receipts[0].receipt.as_ref().expect("big parses");
+    assert_eq!(big.tool.name, "big");
+    let ids: Vec<_> = big
+        .findings
+        .iter()
+        .filter_map(|f| f.check_id.as_deref())
+        .collect();
+    assert_eq!(ids, vec!["check.0", "check.1"]);
+    let caps = big.capabilities.as_ref().expect("truncation noted");
+    assert!(caps.partial);
+    assert_eq!(
+        caps.reason.as_deref(),
+        Some("findings truncated: kept 2 of 5")
+    );
+
+    let partial = receipts[1].receipt.as_ref().expect("partial parses");
+    assert_eq!(
+        partial.capabilities.as_ref().unwrap().reason.as_deref(),
+        Some("timeout; findings truncated: kept 2 of 3")
+    );
+
+    let small = receipts[2].receipt.as_ref().expect("small parses");
+    assert_eq!(small.findings.len(), 2);
+    assert!(small.capabilities.is_none());
+}
+
+#[test]
+fn test_findings_cap_applies_to_adapter_output() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    fs::create_dir_all(artifacts.join("codeql")).unwrap();
+    fs::write(artifacts.join("codeql").join("results.sarif"), sarif_log()).unwrap();
+
+    let discovery = ReceiptDiscovery::default().with_max_findings(1);
+    let receipts = discover_receipts(&artifacts, &discovery, None, None).unwrap();
+
+    let env = receipts[0].receipt.as_ref().expect("sarif parses");
+    assert_eq!(env.findings.len(), 1);
+    assert!(env.capabilities.as_ref().unwrap().partial);
+}
+
+#[test]
+fn test_trailing_garbage_after_envelope_is_rejected() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    create_receipt(
+        &artifacts,
+        "sensor",
+        &format!("{} trailing", valid_receipt()),
+    );
+
+    let receipts = load_receipts(&artifacts).unwrap();
+    assert!(matches!(
+        receipts[0].receipt,
+        Err(ReceiptLoadError::Json { .. })
+    ));
+}
+
+// ---------------------------------------------------------------------------
+// Parsed-receipt cache
+// ---------------------------------------------------------------------------
+
+#[test]
+fn test_cache_reuses_parses_of_unchanged_receipts() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    let cache_dir = artifacts.join("buildfix").join("cache").join("receipts");
+    create_receipt(&artifacts, "a-sensor", valid_receipt());
+    create_receipt(&artifacts, "b-sensor", &receipt_with_findings(3, ""));
+    let discovery = ReceiptDiscovery::default();
+
+    let cache = ReceiptCache::new(cache_dir.clone());
+    let cold = discover_receipts(&artifacts, &discovery, None, Some(&cache)).unwrap();
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+    assert_eq!(fs::read_dir(&cache_dir).unwrap().count(), 2);
+
+    let cache = ReceiptCache::new(cache_dir.clone());
+    let warm = discover_receipts(&artifacts, &discovery, None, Some(&cache)).unwrap();
+    assert_eq!(cache.stats(), CacheStats { hits: 2, misses: 0 });
+    assert_eq!(
+        warm[1].receipt.as_ref().unwrap().findings.len(),
+        cold[1].receipt.as_ref().unwrap().findings.len()
+    );
+
+    // A changed receipt and a different finding cap both miss.
+    create_receipt(&artifacts, "a-sensor", &receipt_with_findings(1, ""));
+    let cache = ReceiptCache::new(cache_dir.clone());
+    let capped = ReceiptDiscovery::default().with_max_findings(2);
+    let receipts = discover_receipts(&artifacts, &capped, None, Some(&cache)).unwrap();
+    assert_eq!(cache.stats(), CacheStats { hits: 0, misses: 2 });
+    assert_eq!(receipts[0].receipt.as_ref().unwrap().findings.len(), 1);
+    assert_eq!(receipts[1].receipt.as_ref().unwrap().findings.len(), 2);
+}
+
+#[test]
+fn test_cache_skips_failed_parses() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    let cache_dir = temp.path().join("cache");
+    create_receipt(&artifacts, "broken", "{ not json");
+
+    let cache = ReceiptCache::new(Utf8PathBuf::from_path_buf(cache_dir.clone()).unwrap());
+    let receipts =
+        discover_receipts(&artifacts, &ReceiptDiscovery::default(), None, Some(&cache)).unwrap();
+
+    assert!(receipts[0].receipt.is_err());
+    assert!(!cache_dir.exists());
+}
+
+// ---------------------------------------------------------------------------
+// Signature verification
+// ---------------------------------------------------------------------------
+
+const KEY_ID: [u8; 8] = *b"buildfix";
+
+fn signing_key(seed: u8) -> SigningKey {
+    SigningKey::from_bytes(&[seed; 32])
+}
+
+/// Minisign public key line for `key`.
+fn minisign_public_key(key: &SigningKey) -> String {
+    let mut bin = b"Ed".to_vec();
+    bin.extend_from_slice(&KEY_ID);
+    bin.extend_from_slice(key.verifying_key().as_bytes());
+    BASE64.encode(bin)
+}
+
+/// Legacy (non-prehashed) minisign signature of `message`.
+fn minisign_signature(key: &SigningKey, message: &[u8]) -> String {
+    let sig = key.sign(message).to_bytes();
+    let mut bin = b"Ed".to_vec();
+    bin.extend_from_slice(&KEY_ID);
+    bin.extend_from_slice(&sig);
+
+    let trusted = "timestamp:0\tfile:report.json";
+    let mut global = sig.to_vec();
+    global.extend_from_slice(trusted.as_bytes());
+    let global_sig = key.sign(&global).to_bytes();
+
+    format!(
+        "untrusted comment: test\n{}\ntrusted comment: {}\n{}\n",
+        BASE64.encode(bin),
+        trusted,
+        BASE64.encode(global_sig)
+    )
+}
+
+fn sign_receipt(dir: &Utf8PathBuf, sensor: &str, key: &SigningKey) {
+    let path = dir.join(sensor).join("report.json");
+    let contents = fs::read(&path).unwrap();
+    fs::write(
+        format!("{}.minisig", path),
+        minisign_signature(key, &contents),
+    )
+    .unwrap();
+}
+
+fn trust(mode: TrustMode, key: &SigningKey) -> TrustPolicy {
+    TrustPolicy::new(mode)
+        .with_public_key(&minisign_public_key(key))
+        .unwrap()
+}
+
+#[test]
+fn test_signatures_unchecked_without_policy() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    create_receipt(&artifacts, "sensor", valid_receipt());
+
+    let receipts = load_receipts(&artifacts).unwrap();
+    assert!(receipts[0].signature.is_none());
+}
+
+#[test]
+fn test_permissive_policy_records_status_per_receipt() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    let key = signing_key(7);
+    create_receipt(&artifacts, "signed", valid_receipt());
+    create_receipt(&artifacts, "tampered", valid_receipt());
+    create_receipt(&artifacts, "unsigned", valid_receipt());
+    sign_receipt(&artifacts, "signed", &key);
+    sign_receipt(&artifacts, "tampered", &key);
+    fs::write(
+        artifacts.join("tampered/report.json"),
+        valid_receipt().replace("pass", "fail"),
+    )
+    .unwrap();
+
+    let policy = trust(TrustMode::Permissive, &key);
+    let receipts = load_receipts_with_trust(&artifacts, Some(&policy)).unwrap();
+
+    assert_eq!(receipts[0].sensor_id, "signed");
+    assert_eq!(receipts[0].signature, Some(SignatureStatus::Verified));
+    assert!(receipts[0].receipt.is_ok());
+
+    assert_eq!(receipts[1].sensor_id, "tampered");
+    assert_eq!(receipts[1].signature.as_ref().unwrap().as_str(), "invalid");
+    assert!(matches!(
+        receipts[1].receipt,
+        Err(ReceiptLoadError::Signature { .. })
+    ));
+
+    assert_eq!(receipts[2].sensor_id, "unsigned");
+    assert_eq!(receipts[2].signature, Some(SignatureStatus::Unsigned));
+    assert!(receipts[2].receipt.is_ok());
+}
+
+#[test]
+fn test_strict_policy_rejects_unsigned_and_foreign_keys() {
+    let temp = create_temp_dir();
+    let artifacts = artifacts_path(&temp);
+    let trusted = signing_key(7);
+    let foreign = signing_key(9);
+    create_receipt(&artifacts, "a-signed", valid_receipt());
+    create_receipt(&artifacts, "b-foreign", valid_receipt());
+    create_receipt(&artifacts, "c-unsigned", valid_receipt());
+    sign_receipt(&artifacts, "a-signed", &trusted);
+    sign_receipt(&artifacts, "b-foreign", &foreign);
+
+    let policy = trust(TrustMode::Strict, &trusted);
+    let receipts = load_receipts_with_trust(&artifacts, Some(&policy)).unwrap();
+
+    assert!(receipts[0].receipt.is_ok());
+    assert_eq!(receipts[1].signature.as_ref().unwrap().as_str(), "invalid");
+    assert!(receipts[1].receipt.is_err());
+    assert_eq!(receipts[2].signature, Some(SignatureStatus::Unsigned));
+    let err = receipts[2].receipt.as_ref().unwrap_err();
+    assert!(err.to_string().contains("strict trust policy"));
+}
+
+#[test]
+fn test_trust_policy_rejects_malformed_key() {
+    assert!(
+        TrustPolicy::new(TrustMode::Strict)
+            .with_public_key("not-a-key")
+            .is_err()
+    );
+}
+
+// =============================================================================
+// Configurable discovery
+// =============================================================================
+
+#[test]
+fn test_sensor_id_rules() {
+    let path = Utf8Path::new("ci-out/shard-1/clippy.report.json");
+    assert_eq!(SensorIdRule::ParentDir.sensor_id(path), "shard-1");
+    assert_eq!(SensorIdRule::FileStem.sensor_id(path), "clippy");
+    assert_eq!(
+        SensorIdRule::FileStem.sensor_id(Utf8Path::new(".hidden")),
+        "unknown"
+    );
+}
+
+#[test]
+fn test_discovery_patterns_replace_default_layout() {
+    let temp = create_temp_dir();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    create_receipt(&root.join("artifacts"), "ignored", valid_receipt());
+    fs::create_dir_all(root.join("ci-out/nested")).unwrap();
+    fs::write(root.join("ci-out/builddiag.report.json"), valid_receipt()).unwrap();
+    fs::write(
+        root.join("ci-out/nested/depguard.report.json"),
+        valid_receipt(),
+    )
+    .unwrap();
+    fs::write(root.join("ci-out/notes.json"), "{}").unwrap();
+
+    let discovery = ReceiptDiscovery::new(vec![
+        "ci-out/**/*.report.json".to_string(),
+        // Overlapping patterns do not load a file twice.
+        "ci-out/*.report.json".to_string(),
+    ])
+    .with_sensor_id(SensorIdRule::FileStem);
+    let receipts = discover_receipts(&root, &discovery, None, None).unwrap();
+
+    let ids: Vec<&str> = receipts.iter().map(|r| r.sensor_id.as_str()).collect();
+    assert_eq!(ids, vec!["builddiag", "depguard"]);
+    assert!(receipts.iter().all(|r| r.receipt.is_ok()));
+}
+
+#[test]
+fn test_discovery_patterns_route_adapter_formats() {
+    let temp = create_temp_dir();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    fs::create_dir_all(root.join("ci-out/codeql")).unwrap();
+    fs::write(root.join("ci-out/codeql/results.sarif"), sarif_log()).unwrap();
+
+    let discovery = ReceiptDiscovery::new(vec!["ci-out/**/*".to_string()]);
+    let receipts = discover_receipts(&root, &discovery, None, None).unwrap();
+
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].sensor_id, "codeql");
+    let env = receipts[0].receipt.as_ref().expect("sarif parses");
+    assert_eq!(env.schema, "sarif.report.v1");
+}
+
+#[test]
+fn test_discovery_patterns_skip_reserved_sensors() {
+    let temp = create_temp_dir();
+    let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+    fs::create_dir_all(root.join("ci-out")).unwrap();
+    fs::write(root.join("ci-out/buildfix.report.json"), valid_receipt()).unwrap();
+    fs::write(root.join("ci-out/clippy.report.json"), valid_receipt()).unwrap();
+
+    let discovery = ReceiptDiscovery::new(vec!["ci-out/*.report.json".to_string()])
+        .with_sensor_id(SensorIdRule::FileStem);
+    let receipts = discover_receipts(&root, &discovery, None, None).unwrap();
+
+    assert_eq!(receipts.len(), 1);
+    assert_eq!(receipts[0].sensor_id, "clippy");
+}
+
+// =============================================================================
+// Archive bundles
+// =============================================================================
+
+#[cfg(feature = "archive")]
+mod archive_bundles {
+    use super::*;
+    use std::io::Write;
+
+    fn write_zip(path: &Utf8Path, entries: &[(&str, &[u8])]) {
+        let file = fs::File::create(path).unwrap();
+        let mut zip = zip::ZipWriter::new(file);
+        let options = zip::write::SimpleFileOptions::default();
+        for (name, contents) in entries {
+            zip.start_file(*name, options).unwrap();
+            zip.write_all(contents).unwrap();
+        }
+        zip.finish().unwrap();
+    }
+
+    fn write_tar_gz(path: &Utf8Path, entries: &[(&str, &[u8])]) {
+        let file = fs::File::create(path).unwrap();
+        let encoder = flate2::write::GzEncoder::new(file, flate2::Compression::default());
+        let mut tar = tar::Builder::new(encoder);
+        for (name, contents) in entries {
+            let mut header = tar::Header::new_gnu();
+            header.set_size(contents.len() as u64);
+            header.set_mode(0o644);
+            header.set_cksum();
+            tar.append_data(&mut header, name, *contents).unwrap();
+        }
+        tar.into_inner().unwrap().finish().unwrap();
+    }
+
+    #[test]
+    fn test_zip_bundle_with_wrapping_directory() {
+        let temp = create_temp_dir();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let bundle = root.join("artifacts.zip");
+        write_zip(
+            &bundle,
+            &[
+                (
+                    "artifacts/builddiag/report.json",
+                    valid_receipt().as_bytes(),
+                ),
+                ("artifacts/codeql/results.sarif", sarif_log().as_bytes()),
+                ("artifacts/buildfix/report.json", valid_receipt().as_bytes()),
+                ("artifacts/depguard/report.json", b"{ not json"),
+                ("README.md", b"ignored"),
+            ],
+        );
+
+        assert!(buildfix_receipts::is_archive(&bundle));
+        let receipts = load_receipts(&bundle).unwrap();
+
+        let ids: Vec<&str> = receipts.iter().map(|r| r.sensor_id.as_str()).collect();
+        assert_eq!(ids, vec!["builddiag", "codeql", "depguard"]);
+        assert_eq!(
+            receipts[0].path,
+            bundle.join("artifacts/builddiag/report.json")
+        );
+        assert!(receipts[0].receipt.is_ok());
+        let sarif = receipts[1].receipt.as_ref().expect("sarif parses");
+        assert_eq!(sarif.findings.len(), 2);
+        assert!(matches!(
+            receipts[2].receipt,
+            Err(ReceiptLoadError::Json { .. })
+        ));
+        // Nothing was extracted next to the bundle.
+        assert_eq!(fs::read_dir(&root).unwrap().count(), 1);
+    }
+
+    #[test]
+    fn test_tar_gz_bundle_at_top_level() {
+        let temp = create_temp_dir();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let bundle = root.join("receipts.tar.gz");
+        write_tar_gz(
+            &bundle,
+            &[
+                ("./builddiag/report.json", valid_receipt().as_bytes()),
+                ("depguard/report.json", valid_receipt().as_bytes()),
+            ],
+        );
+
+        let receipts = load_receipts(&bundle).unwrap();
+        let ids: Vec<&str> = receipts.iter().map(|r| r.sensor_id.as_str()).collect();
+        assert_eq!(ids, vec!["builddiag", "depguard"]);
+        assert!(receipts.iter().all(|r| r.receipt.is_ok()));
+    }
+
+    #[test]
+    fn test_bundle_signatures_checked_in_memory() {
+        let temp = create_temp_dir();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let key = signing_key(7);
+        let sig = minisign_signature(&key, valid_receipt().as_bytes());
+        let bundle = root.join("signed.zip");
+        write_zip(
+            &bundle,
+            &[
+                ("a-signed/report.json", valid_receipt().as_bytes()),
+                ("a-signed/report.json.minisig", sig.as_bytes()),
+                ("b-unsigned/report.json", valid_receipt().as_bytes()),
+            ],
+        );
+
+        let policy = trust(TrustMode::Strict, &key);
+        let receipts = load_receipts_with_trust(&bundle, Some(&policy)).unwrap();
+
+        assert_eq!(receipts.len(), 2);
+        assert_eq!(receipts[0].signature, Some(SignatureStatus::Verified));
+        assert!(receipts[0].receipt.is_ok());
+        assert_eq!(receipts[1].signature, Some(SignatureStatus::Unsigned));
+        assert!(receipts[1].receipt.is_err());
+    }
+
+    #[test]
+    fn test_tar_bundle_signature_may_precede_its_receipt() {
+        let temp = create_temp_dir();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        let key = signing_key(7);
+        let sig = minisign_signature(&key, valid_receipt().as_bytes());
+        let bundle = root.join("signed.tar.gz");
+        write_tar_gz(
+            &bundle,
+            &[
+                ("builddiag/report.json.minisig", sig.as_bytes()),
+                ("target/debug/app", &[0u8; 4096]),
+                ("builddiag/report.json", valid_receipt().as_bytes()),
+            ],
+        );
+
+        let policy = trust(TrustMode::Strict, &key);
+        let receipts = load_receipts_with_trust(&bundle, Some(&policy)).unwrap();
+
+        assert_eq!(receipts.len(), 1);
+        assert_eq!(receipts[0].signature, Some(SignatureStatus::Verified));
+        assert!(receipts[0].receipt.is_ok());
+    }
+
+    #[test]
+    fn test_missing_bundle_is_an_error() {
+        let temp = create_temp_dir();
+        let root = Utf8PathBuf::from_path_buf(temp.path().to_path_buf()).unwrap();
+        assert!(load_receipts(&root.join("missing.zip")).is_err());
+    }
+}
@@ -0,0 +1,50 @@
+//! Checkstyle XML (`checkstyle.xml`) for Jenkins and other tools that read
+//! Checkstyle results.
+//!
+//! Ops are grouped into a `<file>` per target, each op an `<error>` whose
+//! `source` names the fixer.
+
+use crate::html::escape;
+use crate::{issue_line, issue_message};
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use std::collections::BTreeMap;
+
+/// Render plan ops as a Checkstyle report.
+pub fn render_plan_checkstyle(plan: &BuildfixPlan) -> String {
+    let mut files: BTreeMap<&str, Vec<&PlanOp>> = BTreeMap::new();
+    for op in &plan.ops {
+        files.entry(op.target.path.as_str()).or_default().push(op);
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str("<checkstyle version=\"4.3\">\n");
+    for (path, ops) in files {
+        out.push_str(&format!("  <file name=\"{}\">\n", escape(path)));
+        for op in ops {
+            out.push_str(&format!(
+                "    <error line=\"{}\" severity=\"{}\" message=\"{}\" source=\"buildfix.{}\"/>\n",
+                issue_line(op),
+                severity(op),
+                escape(&issue_message(op)),
+                escape(&op.rationale.fix_key)
+            ));
+        }
+        out.push_str("  </file>\n");
+    }
+    out.push_str("</checkstyle>\n");
+    out
+}
+
+/// Same ranking as the GitLab report, on Checkstyle's scale.
+fn severity(op: &PlanOp) -> &'static str {
+    if op.blocked {
+        return "info";
+    }
+    match op.safety {
+        SafetyClass::Safe => "info",
+        SafetyClass::Guarded => "warning",
+        SafetyClass::Unsafe => "error",
+    }
+}
@@ -0,0 +1,316 @@
+//! `comment.md`: the PR / cockpit comment.
+//!
+//! The comment opens with [`COMMENT_MARKER`] so bots can find and update
+//! their previous comment, then lists ops in a collapsed `<details>` block
+//! per fixer. When the whole comment would exceed the size limit, fixer
+//! sections are dropped (or cut short) from the end and a note points at
+//! `plan.md` instead.
+
+use crate::html::escape;
+use buildfix_types::messages::{MessageCatalog, codes};
+use buildfix_types::ops::OpKind;
+use buildfix_types::plan::{BuildfixPlan, ImpactSummary, PlanOp};
+use std::collections::{BTreeMap, BTreeSet};
+
+/// Hidden marker on the first line of every builtin comment.
+pub const COMMENT_MARKER: &str = "<!-- buildfix:comment -->";
+
+/// GitHub rejects issue and PR comments longer than 65536 characters.
+pub const DEFAULT_COMMENT_MAX_BYTES: usize = 65_536;
+
+/// Bytes held back for the truncation note.
+const TRUNCATION_NOTE_RESERVE: usize = 128;
+
+/// Longest value or text excerpt shown in an op line.
+const EXCERPT_CHARS: usize = 60;
+
+/// Options for [`render_comment_md_with`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CommentOptions {
+    /// Upper bound on the rendered comment size in bytes.
+    pub max_bytes: usize,
+}
+
+impl Default for CommentOptions {
+    fn default() -> Self {
+        Self {
+            max_bytes: DEFAULT_COMMENT_MAX_BYTES,
+        }
+    }
+}
+
+/// Render a short cockpit-friendly comment summary.
+pub fn render_comment_md(plan: &BuildfixPlan) -> String {
+    render_comment_md_with(plan, &CommentOptions::default())
+}
+
+/// Render the comment summary, truncating op listings to fit
+/// `options.max_bytes`.
+pub fn render_comment_md_with(plan: &BuildfixPlan, options: &CommentOptions) -> String {
+    comment_md(plan, options, &MessageCatalog::default())
+}
+
+pub(crate) fn comment_md(
+    plan: &BuildfixPlan,
+    options: &CommentOptions,
+    m: &MessageCatalog,
+) -> String {
+    let head = comment_head(plan, m);
+    let foot = format!(
+        "{}: [plan.md](plan.md) · [patch.diff](patch.diff)\n",
+        m.get(codes::MD_COMMENT_ARTIFACTS)
+    );
+
+    let mut groups: BTreeMap<&str, Vec<&PlanOp>> = BTreeMap::new();
+    for op in &plan.ops {
+        groups
+            .entry(op.rationale.fix_key.as_str())
+            .or_default()
+            .push(op);
+    }
+
+    let sections: Vec<String> = groups
+        .iter()
+        .map(|(fix_key, ops)| fixer_section(fix_key, ops, ops.len(), m))
+        .collect();
+    let full = sections.iter().map(String::len).sum::<usize>();
+    if head.len() + full + foot.len() <= options.max_bytes {
+        return format!("{}{}{}", head, sections.concat(), foot);
+    }
+
+    let budget = options
+        .max_bytes
+        .saturating_sub(head.len() + foot.len() + TRUNCATION_NOTE_RESERVE);
+    let mut body = String::new();
+    let mut shown = 0;
+    for ((fix_key, ops), section) in groups.iter().zip(&sections) {
+        if body.len() + section.len() <= budget {
+            body.push_str(section);
+            shown += ops.len();
+            continue;
+        }
+        // Keep as many of this fixer's ops as fit, then stop.
+        for n in (1..ops.len()).rev() {
+            let partial = fixer_section(fix_key, ops, n, m);
+            if body.len() + partial.len() <= budget {
+                body.push_str(&partial);
+                shown += n;
+                break;
+            }
+        }
+        break;
+    }
+
+    let note = format!(
+        "_{}_\n\n",
+        m.format(
+            codes::MD_COMMENT_TRUNCATED,
+            &[
+                ("hidden", &(plan.ops.len() - shown)),
+                ("total", &plan.ops.len())
+            ],
+        )
+    );
+    format!("{}{}{}{}", head, body, note, foot)
+}
+
+/// Marker, status line, safety table, impact totals and blocked reasons.
+fn comment_head(plan: &BuildfixPlan, m: &MessageCatalog) -> String {
+    let mut out = String::new();
+    out.push_str(COMMENT_MARKER);
+    out.push('\n');
+
+    let ops_applicable = plan
+        .summary
+        .ops_total
+        .saturating_sub(plan.summary.ops_blocked);
+    let fix_available = ops_applicable > 0;
+
+    let status = if fix_available {
+        codes::MD_COMMENT_FIX_AVAILABLE
+    } else if plan.ops.is_empty() {
+        codes::MD_COMMENT_NO_FIXES
+    } else {
+        codes::MD_COMMENT_ALL_BLOCKED
+    };
+    out.push_str(&format!("**buildfix**: {}\n\n", m.get(status)));
+
+    if let Some(sc) = &plan.summary.safety_counts {
+        out.push_str(&format!(
+            "| {} | {} |\n|--------|-------|\n",
+            m.get(codes::MD_COMMENT_SAFETY),
+            m.get(codes::MD_COMMENT_COUNT)
+        ));
+        if sc.safe > 0 {
+            out.push_str(&format!("| safe | {} |\n", sc.safe));
+        }
+        if sc.guarded > 0 {
+            out.push_str(&format!("| guarded | {} |\n", sc.guarded));
+        }
+        if sc.unsafe_count > 0 {
+            out.push_str(&format!("| unsafe | {} |\n", sc.unsafe_count));
+        }
+        out.push('\n');
+    }
+
+    if let Some(impact) = ImpactSummary::from_ops(&plan.ops) {
+        out.push_str(&format!(
+            "**{}**: {}\n\n",
+            m.get(codes::MD_COMMENT_IMPACT),
+            crate::impact_totals(&impact, m)
+        ));
+    }
+
+    let tokens: BTreeSet<&str> = plan
+        .ops
+        .iter()
+        .filter_map(|o| o.blocked_reason_token.map(|c| c.as_str()))
+        .collect();
+    if !tokens.is_empty() {
+        out.push_str(&format!(
+            "**{}**: ",
+            m.get(codes::MD_COMMENT_BLOCKED_REASONS)
+        ));
+        let top: Vec<&str> = tokens.into_iter().take(5).collect();
+        out.push_str(&top.join(", "));
+        out.push_str("\n\n");
+    }
+
+    out
+}
+
+/// A collapsed `<details>` block listing the first `shown` of a fixer's ops.
+fn fixer_section(fix_key: &str, ops: &[&PlanOp], shown: usize, m: &MessageCatalog) -> String {
+    let blocked = ops.iter().filter(|op| op.blocked).count();
+    let count_code = if ops.len() == 1 {
+        codes::MD_COMMENT_OPS_ONE
+    } else {
+        codes::MD_COMMENT_OPS_OTHER
+    };
+    let mut out = format!(
+        "<details>\n<summary><code>{}</code>: {}",
+        escape(fix_key),
+        m.format(count_code, &[("count", &ops.len())])
+    );
+    if blocked > 0 {
+        out.push_str(&format!(
+            " ({})",
+            m.format(codes::MD_COMMENT_OPS_BLOCKED, &[("count", &blocked)])
+        ));
+    }
+    out.push_str("</summary>\n\n");
+
+    for op in ops.iter().take(shown) {
+        out.push_str(&format!(
+            "- {}: {}",
+            code(&op.target.path),
+            code(&op_change(&op.kind))
+        ));
+        if op.blocked {
+            let label = m.get(codes::MD_COMMENT_OP_BLOCKED);
+            match &op.blocked_reason_token {
+                Some(token) => out.push_str(&format!(" ({}: {})", label, code(token.as_str()))),
+                None => out.push_str(&format!(" ({})", label)),
+            }
+        }
+        if let Some(impact) = &op.impact {
+            let mut flags = Vec::new();
+            if impact.lockfile_update {
+                flags.push(m.get(codes::MD_IMPACT_LOCKFILE));
+            }
+            if impact.semver_visible {
+                flags.push(m.get(codes::MD_IMPACT_SEMVER));
+            }
+            if !flags.is_empty() {
+                out.push_str(&format!(" · _{}_", flags.join(", ")));
+            }
+        }
+        out.push('\n');
+    }
+    if shown < ops.len() {
+        out.push_str(&format!(
+            "- _{}_\n",
+            m.format(codes::MD_COMMENT_MORE, &[("count", &(ops.len() - shown))])
+        ));
+    }
+
+    out.push_str("\n</details>\n\n");
+    out
+}
+
+/// One-line, diff-style summary of what an op changes.
+fn op_change(kind: &OpKind) -> String {
+    match kind {
+        OpKind::TomlSet {
+            toml_path: path,
+            value,
+            ..
+        }
+        | OpKind::JsonSet {
+            json_path: path,
+            value,
+        }
+        | OpKind::YamlSet {
+            yaml_path: path,
+            value,
+        } => format!("+{} = {}", path.join("."), excerpt(&value.to_string())),
+        OpKind::TomlRemove { toml_path: path }
+        | OpKind::JsonRemove { json_path: path }
+        | OpKind::YamlRemove { yaml_path: path } => format!("-{}", path.join(".")),
+        OpKind::TomlArrayAppend {
+            toml_path, values, ..
+        } => format!(
+            "+{} += {}",
+            toml_path.join("."),
+            excerpt(&serde_json::Value::from(values.clone()).to_string())
+        ),
+        OpKind::TomlMerge { toml_path, table } => format!(
+            "+{} += {}",
+            toml_path.join("."),
+            excerpt(&serde_json::Value::from(table.clone()).to_string())
+        ),
+        OpKind::TomlTransform { rule_id, args } => {
+            // Scalar args (dep name, version, ...) tell ops of one rule apart.
+            let mut line = rule_id.clone();
+            if let Some(args) = args.as_ref().and_then(|a| a.as_object()) {
+                for (key, value) in args {
+                    match value {
+                        serde_json::Value::String(s) => line.push_str(&format!(" {}={}", key, s)),
+                        serde_json::Value::Number(_) | serde_json::Value::Bool(_) => {
+                            line.push_str(&format!(" {}={}", key, value))
+                        }
+                        _ => {}
+                    }
+                }
+            }
+            excerpt_chars(&line, 2 * EXCERPT_CHARS)
+        }
+        OpKind::TextReplaceAnchored { find, replace, .. } => {
+            format!("{} → {}", excerpt(find), excerpt(replace))
+        }
+    }
+}
+
+/// First line of `s`, cut to [`EXCERPT_CHARS`].
+fn excerpt(s: &str) -> String {
+    excerpt_chars(s, EXCERPT_CHARS)
+}
+
+fn excerpt_chars(s: &str, max: usize) -> String {
+    let line = s.lines().next().unwrap_or_default();
+    if line.chars().count() > max || line.len() < s.trim_end().len() {
+        let cut: String = line.chars().take(max).collect();
+        format!("{}…", cut)
+    } else {
+        line.to_string()
+    }
+}
+
+/// Inline code span that survives backticks in `s`.
+fn code(s: &str) -> String {
+    if s.contains('`') {
+        format!("`` {} ``", s)
+    } else {
+        format!("`{}`", s)
+    }
+}
@@ -0,0 +1,70 @@
+//! Flat CSV/TSV export of plan ops for spreadsheet triage.
+//!
+//! One row per op. Finding fingerprints are joined with spaces, so an op
+//! backed by several findings still fits on one row.
+
+use crate::safety_label;
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+
+const COLUMNS: [&str; 6] = [
+    "op_id",
+    "fix_key",
+    "file",
+    "safety",
+    "blocked_reason",
+    "finding_fingerprint",
+];
+
+/// Render plan ops as CSV (RFC 4180 quoting) with a header row.
+pub fn render_plan_csv(plan: &BuildfixPlan) -> String {
+    render_rows(plan, ",", csv_field)
+}
+
+/// Render plan ops as tab-separated values with a header row. Tabs and
+/// line breaks inside fields become spaces.
+pub fn render_plan_tsv(plan: &BuildfixPlan) -> String {
+    render_rows(plan, "\t", |s| s.replace(['\t', '\n', '\r'], " "))
+}
+
+fn render_rows(plan: &BuildfixPlan, sep: &str, field: impl Fn(&str) -> String) -> String {
+    let mut out = COLUMNS.join(sep);
+    out.push('\n');
+    for op in &plan.ops {
+        let row: Vec<String> = row(op).iter().map(|s| field(s)).collect();
+        out.push_str(&row.join(sep));
+        out.push('\n');
+    }
+    out
+}
+
+fn row(op: &PlanOp) -> [String; 6] {
+    let blocked_reason = if op.blocked {
+        op.blocked_reason
+            .clone()
+            .unwrap_or_else(|| "blocked".to_string())
+    } else {
+        String::new()
+    };
+    let fingerprints: Vec<&str> = op
+        .rationale
+        .findings
+        .iter()
+        .filter_map(|f| f.fingerprint.as_deref())
+        .collect();
+    [
+        op.id.clone(),
+        op.rationale.fix_key.clone(),
+        op.target.path.clone(),
+        safety_label(op.safety).to_string(),
+        blocked_reason,
+        fingerprints.join(" "),
+    ]
+}
+
+fn csv_field(s: &str) -> String {
+    if s.contains([',', '"', '\n', '\r']) {
+        format!("\"{}\"", s.replace('"', "\"\""))
+    } else {
+        s.to_string()
+    }
+}
@@ -0,0 +1,70 @@
+//! GitLab Code Quality report (`gl-code-quality-report.json`).
+//!
+//! One issue per plan op, in the format GitLab reads from a job's
+//! `artifacts:reports:codequality`, so merge request widgets list buildfix
+//! fixes next to other linters.
+
+use crate::{issue_line, issue_message};
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use serde::Serialize;
+
+#[derive(Debug, Serialize)]
+struct Issue<'a> {
+    description: String,
+    check_name: &'a str,
+    fingerprint: &'a str,
+    severity: &'static str,
+    location: Location<'a>,
+}
+
+#[derive(Debug, Serialize)]
+struct Location<'a> {
+    path: &'a str,
+    lines: Lines,
+}
+
+#[derive(Debug, Serialize)]
+struct Lines {
+    begin: u64,
+}
+
+/// Render the plan as a GitLab Code Quality report (a JSON array).
+///
+/// Op ids are deterministic, so they double as issue fingerprints and GitLab
+/// can tell fixed issues from new ones across pipelines.
+pub fn render_gitlab_code_quality(plan: &BuildfixPlan) -> String {
+    let issues: Vec<Issue<'_>> = plan.ops.iter().map(issue).collect();
+    let mut out = serde_json::to_string_pretty(&issues).expect("code quality issues serialize");
+    out.push('\n');
+    out
+}
+
+fn issue(op: &PlanOp) -> Issue<'_> {
+    Issue {
+        description: issue_message(op),
+        check_name: &op.rationale.fix_key,
+        fingerprint: &op.id,
+        severity: severity(op),
+        location: Location {
+            path: &op.target.path,
+            // Point at the first finding on the target file, else the top of it.
+            lines: Lines {
+                begin: issue_line(op),
+            },
+        },
+    }
+}
+
+/// Riskier fixes rank higher; blocked ops are informational since buildfix
+/// will not apply them as planned.
+fn severity(op: &PlanOp) -> &'static str {
+    if op.blocked {
+        return "info";
+    }
+    match op.safety {
+        SafetyClass::Safe => "minor",
+        SafetyClass::Guarded => "major",
+        SafetyClass::Unsafe => "critical",
+    }
+}
@@ -0,0 +1,368 @@
+//! Standalone `report.html` for publishing plan artifacts to static hosting.
+//!
+//! The page embeds its own stylesheet and a few lines of script for table
+//! sorting, so it can be served or opened without any other files.
+
+use crate::{kind_label, safety_label};
+use buildfix_types::messages::{self, codes};
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use std::collections::BTreeMap;
+
+/// Diff bytes embedded in one rendered artifact (`report.html`, `apply.md`).
+/// Diffs past it are cut after a whole line and end in a note pointing at
+/// `patch.diff`, so a pathological plan cannot inflate its reports without
+/// bound.
+pub const DEFAULT_EMBEDDED_DIFF_MAX_BYTES: usize = 1_048_576;
+
+const STYLE: &str = r#"body{font-family:system-ui,sans-serif;margin:2rem auto;max-width:72rem;padding:0 1rem;color:#1f2328}
+h1,h2,h3{font-weight:600}
+table{border-collapse:collapse;width:100%;margin:1rem 0}
+th,td{border:1px solid #d0d7de;padding:.35rem .6rem;text-align:left;vertical-align:top}
+th{background:#f6f8fa}
+th[data-sort]{cursor:pointer;user-select:none}
+th[data-sort]::after{content:" \2195";color:#8c959f}
+code{font-size:.9em}
+.badge{display:inline-block;border-radius:1em;padding:0 .6em;font-size:.85em;font-weight:600;color:#fff}
+.safe{background:#1a7f37}.guarded{background:#9a6700}.unsafe{background:#cf222e}
+.blocked{background:#6e7781}.ready{background:#0969da}
+.summary li{margin:.2rem 0}
+details{border:1px solid #d0d7de;border-radius:6px;padding:.5rem 1rem;margin:.75rem 0}
+summary{cursor:pointer}
+pre.diff{background:#f6f8fa;padding:.75rem;overflow-x:auto;font-size:.85em;line-height:1.35}
+.diff .add{color:#116329;background:#dafbe1}
+.diff .del{color:#82071e;background:#ffebe9}
+.diff .hunk{color:#0550ae}
+.diff .meta{color:#6e7781}"#;
+
+const SCRIPT: &str = r#"document.querySelectorAll("table.sortable th[data-sort]").forEach(function(th){
+  th.addEventListener("click",function(){
+    var table=th.closest("table"),body=table.tBodies[0],col=th.cellIndex;
+    var asc=th.dataset.dir!=="asc";th.dataset.dir=asc?"asc":"desc";
+    var numeric=th.dataset.sort==="num";
+    Array.from(body.rows).sort(function(a,b){
+      var x=a.cells[col].dataset.value||a.cells[col].textContent;
+      var y=b.cells[col].dataset.value||b.cells[col].textContent;
+      var c=numeric?Number(x)-Number(y):x.localeCompare(y);
+      return asc?c:-c;
+    }).forEach(function(row){body.appendChild(row);});
+  });
+});"#;
+
+/// Render a plan and its patch as a self-contained HTML page.
+///
+/// Each op shows the patch section for its target file (or its own preview
+/// fragment when the planner attached one). Diffs share a budget of
+/// [`DEFAULT_EMBEDDED_DIFF_MAX_BYTES`].
+pub fn render_plan_html(plan: &BuildfixPlan, patch: &str) -> String {
+    let sections = patch_sections(patch);
+    let mut budget = DiffBudget::new(DEFAULT_EMBEDDED_DIFF_MAX_BYTES);
+
+    let mut out = String::new();
+    out.push_str("<!DOCTYPE html>\n<html lang=\"en\">\n<head>\n<meta charset=\"utf-8\">\n");
+    out.push_str("<meta name=\"viewport\" content=\"width=device-width, initial-scale=1\">\n");
+    out.push_str("<title>buildfix plan</title>\n");
+    out.push_str(&format!("<style>\n{}\n</style>\n", STYLE));
+    out.push_str("</head>\n<body>\n<h1>buildfix plan</h1>\n");
+
+    render_summary(&mut out, plan);
+    render_blocked_breakdown(&mut out, &plan.ops);
+
+    out.push_str("<h2>Ops</h2>\n");
+    if plan.ops.is_empty() {
+        out.push_str("<p><em>No ops planned.</em></p>\n");
+    } else {
+        render_ops_table(&mut out, &plan.ops);
+        for (i, op) in plan.ops.iter().enumerate() {
+            let diff = op
+                .preview
+                .as_ref()
+                .map(|p| p.patch_fragment.as_str())
+                .or_else(|| sections.get(op.target.path.as_str()).copied());
+            render_op_details(&mut out, i + 1, op, diff, &mut budget);
+        }
+    }
+
+    out.push_str(&format!("<script>\n{}\n</script>\n", SCRIPT));
+    out.push_str("</body>\n</html>\n");
+    out
+}
+
+fn render_summary(out: &mut String, plan: &BuildfixPlan) {
+    out.push_str("<ul class=\"summary\">\n");
+    out.push_str(&format!(
+        "<li>Ops: {} (blocked {})</li>\n",
+        plan.summary.ops_total, plan.summary.ops_blocked
+    ));
+    out.push_str(&format!(
+        "<li>Files touched: {}</li>\n",
+        plan.summary.files_touched
+    ));
+    if let Some(bytes) = plan.summary.patch_bytes {
+        out.push_str(&format!("<li>Patch bytes: {}</li>\n", bytes));
+    }
+    if let Some(sc) = &plan.summary.safety_counts {
+        out.push_str(&format!(
+            "<li>Safety: {} {}, {} {}, {} {}</li>\n",
+            sc.safe,
+            badge("safe"),
+            sc.guarded,
+            badge("guarded"),
+            sc.unsafe_count,
+            badge("unsafe")
+        ));
+    }
+    out.push_str(&format!("<li>Inputs: {}</li>\n", plan.inputs.len()));
+    out.push_str("</ul>\n");
+}
+
+fn render_blocked_breakdown(out: &mut String, ops: &[PlanOp]) {
+    let mut by_token: BTreeMap<&str, (u64, Option<&str>)> = BTreeMap::new();
+    for op in ops.iter().filter(|op| op.blocked) {
+        let token = op
+            .blocked_reason_token
+            .map(|c| c.as_str())
+            .unwrap_or("unspecified");
+        let entry = by_token.entry(token).or_insert((0, None));
+        entry.0 += 1;
+        if entry.1.is_none() {
+            entry.1 = op.blocked_reason.as_deref();
+        }
+    }
+    if by_token.is_empty() {
+        return;
+    }
+
+    out.push_str("<h2>Blocked reasons</h2>\n");
+    out.push_str("<table class=\"sortable\">\n<thead><tr>");
+    out.push_str("<th data-sort=\"str\">Reason</th><th data-sort=\"num\">Ops</th><th>Example</th>");
+    out.push_str("</tr></thead>\n<tbody>\n");
+    for (token, (count, example)) in by_token {
+        out.push_str(&format!(
+            "<tr><td><code>{}</code></td><td>{}</td><td>{}</td></tr>\n",
+            escape(token),
+            count,
+            escape(example.unwrap_or("-"))
+        ));
+    }
+    out.push_str("</tbody>\n</table>\n");
+}
+
+fn render_ops_table(out: &mut String, ops: &[PlanOp]) {
+    out.push_str("<table class=\"sortable\" id=\"ops\">\n<thead><tr>");
+    for (label, sort) in [
+        ("#", "num"),
+        ("Fix", "str"),
+        ("Target", "str"),
+        ("Kind", "str"),
+        ("Safety", "num"),
+        ("Status", "str"),
+    ] {
+        out.push_str(&format!("<th data-sort=\"{}\">{}</th>", sort, label));
+    }
+    out.push_str("</tr></thead>\n<tbody>\n");
+    for (i, op) in ops.iter().enumerate() {
+        let n = i + 1;
+        let status = match &op.blocked_reason_token {
+            Some(token) if op.blocked => {
+                format!(
+                    "{} <code>{}</code>",
+                    badge("blocked"),
+                    escape(token.as_str())
+                )
+            }
+            _ if op.blocked => badge("blocked"),
+            _ => badge("ready"),
+        };
+        out.push_str(&format!(
+            "<tr><td data-value=\"{n}\"><a href=\"#op-{n}\">{n}</a></td><td><code>{}</code></td>\
+             <td><code>{}</code></td><td><code>{}</code></td><td data-value=\"{}\">{}</td><td>{}</td></tr>\n",
+            escape(&op.rationale.fix_key),
+            escape(&op.target.path),
+            escape(kind_label(&op.kind)),
+            op.safety as u8,
+            badge(safety_label(op.safety)),
+            status,
+        ));
+    }
+    out.push_str("</tbody>\n</table>\n");
+}
+
+fn render_op_details(
+    out: &mut String,
+    n: usize,
+    op: &PlanOp,
+    diff: Option<&str>,
+    budget: &mut DiffBudget,
+) {
+    out.push_str(&format!(
+        "<details id=\"op-{}\"{}>\n<summary><strong>{}.</strong> <code>{}</code> {}{}</summary>\n",
+        n,
+        if op.blocked { "" } else { " open" },
+        n,
+        escape(&op.id),
+        badge(safety_label(op.safety)),
+        if op.blocked {
+            format!(" {}", badge("blocked"))
+        } else {
+            String::new()
+        }
+    ));
+
+    out.push_str("<ul>\n");
+    out.push_str(&format!(
+        "<li>Target: <code>{}</code></li>\n",
+        escape(&op.target.path)
+    ));
+    out.push_str(&format!(
+        "<li>Kind: <code>{}</code></li>\n",
+        escape(kind_label(&op.kind))
+    ));
+    if let Some(reason) = &op.blocked_reason {
+        out.push_str(&format!("<li>Blocked reason: {}</li>\n", escape(reason)));
+    }
+    if !op.params_required.is_empty() {
+        out.push_str(&format!(
+            "<li>Params required: {}</li>\n",
+            escape(&op.params_required.join(", "))
+        ));
+    }
+    out.push_str("</ul>\n");
+
+    if let Some(desc) = &op.rationale.description {
+        out.push_str(&format!("<p>{}</p>\n", escape(desc)));
+    }
+
+    if !op.rationale.findings.is_empty() {
+        out.push_str("<h3>Findings</h3>\n<ul>\n");
+        for f in &op.rationale.findings {
+            let check = f.check_id.as_deref().unwrap_or("-");
+            let loc = f
+                .path
+                .as_ref()
+                .map(|p| format!("{}:{}", p, f.line.unwrap_or(0)))
+                .unwrap_or_else(|| "-".to_string());
+            out.push_str(&format!(
+                "<li><code>{}/{}</code> <code>{}</code> at {}</li>\n",
+                escape(&f.source),
+                escape(check),
+                escape(&f.code),
+                escape(&loc)
+            ));
+        }
+        out.push_str("</ul>\n");
+    }
+
+    match diff {
+        Some(full) => {
+            let (diff, truncated) = budget.take(full);
+            out.push_str("<pre class=\"diff\">");
+            for line in diff.lines() {
+                let class = if line.starts_with("+++") || line.starts_with("---") {
+                    "meta"
+                } else if line.starts_with('+') {
+                    "add"
+                } else if line.starts_with('-') {
+                    "del"
+                } else if line.starts_with("@@") {
+                    "hunk"
+                } else if line.starts_with(' ') {
+                    ""
+                } else {
+                    "meta"
+                };
+                if class.is_empty() {
+                    out.push_str(&escape(line));
+                } else {
+                    out.push_str(&format!(
+                        "<span class=\"{}\">{}</span>",
+                        class,
+                        escape(line)
+                    ));
+                }
+                out.push('\n');
+            }
+            if truncated {
+                let note = messages::english(
+                    codes::MD_DIFF_TRUNCATED,
+                    &[("shown", &diff.len()), ("total", &full.len())],
+                );
+                out.push_str(&format!("<span class=\"meta\">{}</span>\n", escape(&note)));
+            }
+            out.push_str("</pre>\n");
+        }
+        None => out.push_str("<p><em>No diff for this op.</em></p>\n"),
+    }
+
+    out.push_str("</details>\n");
+}
+
+/// What is left of the diff bytes one artifact may embed.
+pub(crate) struct DiffBudget {
+    remaining: usize,
+}
+
+impl DiffBudget {
+    pub(crate) fn new(max_bytes: usize) -> Self {
+        Self {
+            remaining: max_bytes,
+        }
+    }
+
+    /// The head of `diff` that still fits, cut after a whole line, and
+    /// whether anything was cut. Once the budget is spent every diff comes
+    /// back empty and cut.
+    pub(crate) fn take<'a>(&mut self, diff: &'a str) -> (&'a str, bool) {
+        if diff.len() <= self.remaining {
+            self.remaining -= diff.len();
+            return (diff, false);
+        }
+        let cut = diff.as_bytes()[..self.remaining]
+            .iter()
+            .rposition(|&b| b == b'\n')
+            .map_or(0, |i| i + 1);
+        self.remaining = 0;
+        (&diff[..cut], true)
+    }
+}
+
+/// Split a unified patch into per-file sections keyed by the new path.
+pub fn patch_sections(patch: &str) -> BTreeMap<&str, &str> {
+    let mut starts: Vec<usize> = Vec::new();
+    let mut offset = 0;
+    for line in patch.split_inclusive('\n') {
+        if line.starts_with("diff --git ") {
+            starts.push(offset);
+        }
+        offset += line.len();
+    }
+
+    let mut sections = BTreeMap::new();
+    for (i, &start) in starts.iter().enumerate() {
+        let end = starts.get(i + 1).copied().unwrap_or(patch.len());
+        let section = &patch[start..end];
+        let header = section.lines().next().unwrap_or_default();
+        if let Some((_, path)) = header.rsplit_once(" b/") {
+            sections.insert(path, section);
+        }
+    }
+    sections
+}
+
+fn badge(label: &str) -> String {
+    format!("<span class=\"badge {0}\">{0}</span>", label)
+}
+
+pub(crate) fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '&' => out.push_str("&amp;"),
+            '<' => out.push_str("&lt;"),
+            '>' => out.push_str("&gt;"),
+            '"' => out.push_str("&quot;"),
+            '\'' => out.push_str("&#39;"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
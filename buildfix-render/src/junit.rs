@@ -0,0 +1,192 @@
+//! JUnit XML for CI dashboards that only understand test results.
+//!
+//! Each fixer is a `<testsuite>` and each of its ops a `<testcase>`. On a
+//! plan, blocked ops are skipped and the rest pass; on an apply, results map
+//! onto the same cases (blocked or skipped -> skipped, failed -> failure).
+
+use crate::html::escape;
+use buildfix_types::apply::{ApplyResult, ApplyStatus, BuildfixApply};
+use buildfix_types::plan::BuildfixPlan;
+use std::collections::{BTreeMap, HashMap};
+
+enum Outcome {
+    Passed,
+    Skipped(String),
+    Failed(String),
+}
+
+struct Case<'a> {
+    name: String,
+    outcome: Outcome,
+    files: Vec<&'a str>,
+}
+
+/// Render plan ops as JUnit test cases grouped by fixer.
+pub fn render_plan_junit(plan: &BuildfixPlan) -> String {
+    let mut suites: BTreeMap<&str, Vec<Case<'_>>> = BTreeMap::new();
+    for op in &plan.ops {
+        let outcome = if op.blocked {
+            Outcome::Skipped(
+                op.blocked_reason
+                    .clone()
+                    .unwrap_or_else(|| "blocked".into()),
+            )
+        } else {
+            Outcome::Passed
+        };
+        suites
+            .entry(op.rationale.fix_key.as_str())
+            .or_default()
+            .push(Case {
+                name: case_name(&op.target.path, &op.id),
+                outcome,
+                files: Vec::new(),
+            });
+    }
+    render("buildfix plan", suites)
+}
+
+/// Render apply results as JUnit test cases, grouped by the fixer of the
+/// matching op in `plan`. Planned ops without a result count as skipped.
+pub fn render_apply_junit(apply: &BuildfixApply, plan: &BuildfixPlan) -> String {
+    let results: HashMap<&str, &ApplyResult> = apply
+        .results
+        .iter()
+        .map(|r| (r.op_id.as_str(), r))
+        .collect();
+
+    let mut suites: BTreeMap<&str, Vec<Case<'_>>> = BTreeMap::new();
+    for op in &plan.ops {
+        let (outcome, files) = match results.get(op.id.as_str()) {
+            Some(r) => (
+                apply_outcome(r),
+                r.files.iter().map(|f| f.path.as_str()).collect(),
+            ),
+            None => (Outcome::Skipped("not attempted".into()), Vec::new()),
+        };
+        suites
+            .entry(op.rationale.fix_key.as_str())
+            .or_default()
+            .push(Case {
+                name: case_name(&op.target.path, &op.id),
+                outcome,
+                files,
+            });
+    }
+
+    // Results for ops the plan does not know about still get reported.
+    for r in &apply.results {
+        if !plan.ops.iter().any(|op| op.id == r.op_id) {
+            suites.entry("buildfix").or_default().push(Case {
+                name: r.op_id.clone(),
+                outcome: apply_outcome(r),
+                files: r.files.iter().map(|f| f.path.as_str()).collect(),
+            });
+        }
+    }
+
+    render("buildfix apply", suites)
+}
+
+fn apply_outcome(r: &ApplyResult) -> Outcome {
+    let detail = || {
+        r.blocked_reason
+            .clone()
+            .or_else(|| r.message.clone())
+            .unwrap_or_default()
+    };
+    match r.status {
+        ApplyStatus::Applied => Outcome::Passed,
+        ApplyStatus::Blocked => Outcome::Skipped(non_empty(detail(), "blocked")),
+        ApplyStatus::Skipped => Outcome::Skipped(non_empty(detail(), "skipped")),
+        ApplyStatus::Failed => Outcome::Failed(non_empty(detail(), "failed")),
+    }
+}
+
+fn non_empty(s: String, fallback: &str) -> String {
+    if s.is_empty() {
+        fallback.to_string()
+    } else {
+        s
+    }
+}
+
+fn case_name(target: &str, op_id: &str) -> String {
+    format!("{} [{}]", target, op_id)
+}
+
+fn render(name: &str, suites: BTreeMap<&str, Vec<Case<'_>>>) -> String {
+    let (mut tests, mut failures, mut skipped) = (0, 0, 0);
+    for cases in suites.values() {
+        let (f, s) = tally(cases);
+        tests += cases.len();
+        failures += f;
+        skipped += s;
+    }
+
+    let mut out = String::new();
+    out.push_str("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+    out.push_str(&format!(
+        "<testsuites name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">\n",
+        escape(name),
+        tests,
+        failures,
+        skipped,
+    ));
+
+    for (suite, cases) in &suites {
+        let (failures, skipped) = tally(cases);
+        out.push_str(&format!(
+            "  <testsuite name=\"{}\" tests=\"{}\" failures=\"{}\" errors=\"0\" skipped=\"{}\">\n",
+            escape(suite),
+            cases.len(),
+            failures,
+            skipped,
+        ));
+        for case in cases {
+            out.push_str(&format!(
+                "    <testcase name=\"{}\" classname=\"{}\"",
+                escape(&case.name),
+                escape(suite)
+            ));
+            let body = match &case.outcome {
+                Outcome::Passed => None,
+                Outcome::Skipped(msg) => Some(format!("<skipped message=\"{}\"/>", escape(msg))),
+                Outcome::Failed(msg) => Some(format!(
+                    "<failure message=\"{0}\">{0}</failure>",
+                    escape(msg)
+                )),
+            };
+            if body.is_none() && case.files.is_empty() {
+                out.push_str("/>\n");
+                continue;
+            }
+            out.push_str(">\n");
+            if let Some(body) = body {
+                out.push_str(&format!("      {}\n", body));
+            }
+            if !case.files.is_empty() {
+                out.push_str(&format!(
+                    "      <system-out>{}</system-out>\n",
+                    escape(&case.files.join("\n"))
+                ));
+            }
+            out.push_str("    </testcase>\n");
+        }
+        out.push_str("  </testsuite>\n");
+    }
+
+    out.push_str("</testsuites>\n");
+    out
+}
+
+/// Failed and skipped case counts.
+fn tally(cases: &[Case<'_>]) -> (usize, usize) {
+    cases
+        .iter()
+        .fold((0, 0), |(f, s), case| match case.outcome {
+            Outcome::Passed => (f, s),
+            Outcome::Skipped(_) => (f, s + 1),
+            Outcome::Failed(_) => (f + 1, s),
+        })
+}
@@ -1,57 +1,150 @@
-//! Rendering helpers (markdown) for human-readable artifacts.
+//! Rendering helpers (markdown, HTML, CI report formats) for human-readable
+//! artifacts.
 
 use buildfix_types::apply::{ApplyStatus, BuildfixApply};
-use buildfix_types::ops::SafetyClass;
-use buildfix_types::plan::BuildfixPlan;
+use buildfix_types::messages::codes;
+use buildfix_types::ops::{OpKind, SafetyClass};
+use buildfix_types::plan::{BuildfixPlan, ImpactSummary, OpImpact, PlanOp};
+use std::collections::BTreeMap;
+
+mod checkstyle;
+mod comment;
+mod export;
+mod gitlab;
+mod html;
+mod junit;
+mod plan_diff;
+mod teamcity;
+mod template;
+mod terminal;
+
+pub use buildfix_types::messages::MessageCatalog;
+pub use checkstyle::render_plan_checkstyle;
+pub use comment::{
+    COMMENT_MARKER, CommentOptions, DEFAULT_COMMENT_MAX_BYTES, render_comment_md,
+    render_comment_md_with,
+};
+pub use export::{render_plan_csv, render_plan_tsv};
+pub use gitlab::render_gitlab_code_quality;
+use html::DiffBudget;
+pub use html::{DEFAULT_EMBEDDED_DIFF_MAX_BYTES, patch_sections, render_plan_html};
+pub use junit::{render_apply_junit, render_plan_junit};
+pub use plan_diff::{
+    FieldChange, OpChange, OpSummary, PlanDiff, diff_plans, render_plan_diff, render_plan_diff_json,
+};
+pub use teamcity::render_plan_teamcity;
+pub use template::MarkdownTemplates;
+pub use terminal::{render_apply_pretty, render_plan_pretty};
 
 pub fn render_plan_md(plan: &BuildfixPlan) -> String {
+    plan_md(plan, &MessageCatalog::default())
+}
+
+pub(crate) fn plan_md(plan: &BuildfixPlan, m: &MessageCatalog) -> String {
+    let s = &plan.summary;
     let mut out = String::new();
-    out.push_str("# buildfix plan\n\n");
+    out.push_str(&format!("# {}\n\n", m.get(codes::MD_PLAN_TITLE)));
     out.push_str(&format!(
-        "- Ops: {} (blocked {})\n",
-        plan.summary.ops_total, plan.summary.ops_blocked
+        "- {}\n",
+        m.format(
+            codes::MD_PLAN_OPS_SUMMARY,
+            &[("total", &s.ops_total), ("blocked", &s.ops_blocked)],
+        )
     ));
     out.push_str(&format!(
-        "- Files touched: {}\n",
-        plan.summary.files_touched
+        "- {}\n",
+        m.format(codes::MD_PLAN_FILES_TOUCHED, &[("count", &s.files_touched)])
     ));
-    if let Some(bytes) = plan.summary.patch_bytes {
-        out.push_str(&format!("- Patch bytes: {}\n", bytes));
+    if let Some(bytes) = s.patch_bytes {
+        out.push_str(&format!(
+            "- {}\n",
+            m.format(codes::MD_PLAN_PATCH_BYTES, &[("count", &bytes)])
+        ));
     }
-    if let Some(sc) = &plan.summary.safety_counts {
+    if let Some(sc) = &s.safety_counts {
         out.push_str(&format!(
-            "- Safety: {} safe, {} guarded, {} unsafe\n",
-            sc.safe, sc.guarded, sc.unsafe_count
+            "- {}\n",
+            m.format(
+                codes::MD_PLAN_SAFETY_COUNTS,
+                &[
+                    ("safe", &sc.safe),
+                    ("guarded", &sc.guarded),
+                    ("unsafe", &sc.unsafe_count),
+                ],
+            )
         ));
     }
-    out.push_str(&format!("- Inputs: {}\n\n", plan.inputs.len()));
+    out.push_str(&format!(
+        "- {}\n",
+        m.format(codes::MD_PLAN_INPUTS, &[("count", &plan.inputs.len())])
+    ));
+    if let Some(impact) = ImpactSummary::from_ops(&plan.ops) {
+        out.push_str(&format!(
+            "- {}: {}\n",
+            m.get(codes::MD_PLAN_IMPACT),
+            impact_totals(&impact, m)
+        ));
+    }
+    out.push('\n');
 
-    out.push_str("## Ops\n\n");
+    if !plan.workspaces.is_empty() {
+        out.push_str(&format!("## {}\n\n", m.get(codes::MD_PLAN_WORKSPACES)));
+        for ws in &plan.workspaces {
+            let s = &ws.summary;
+            out.push_str(&format!(
+                "- `{}`: {}, {}\n",
+                ws.root,
+                m.format(
+                    codes::MD_PLAN_OPS_SUMMARY,
+                    &[("total", &s.ops_total), ("blocked", &s.ops_blocked)],
+                ),
+                m.format(codes::MD_PLAN_FILES_TOUCHED, &[("count", &s.files_touched)])
+            ));
+        }
+        out.push('\n');
+    }
+
+    out.push_str(&format!("## {}\n\n", m.get(codes::MD_PLAN_OPS)));
     if plan.ops.is_empty() {
-        out.push_str("_No ops planned._\n");
+        out.push_str(&format!("_{}_\n", m.get(codes::MD_PLAN_NO_OPS)));
         return out;
     }
 
     for (i, op) in plan.ops.iter().enumerate() {
         out.push_str(&format!("### {}. {}\n\n", i + 1, op.id));
-        out.push_str(&format!("- Safety: `{}`\n", safety_label(op.safety)));
-        out.push_str(&format!("- Blocked: `{}`\n", op.blocked));
-        out.push_str(&format!("- Target: `{}`\n", op.target.path));
         out.push_str(&format!(
-            "- Kind: `{}`\n",
-            match &op.kind {
-                buildfix_types::ops::OpKind::TomlSet { .. } => "toml_set",
-                buildfix_types::ops::OpKind::TomlRemove { .. } => "toml_remove",
-                buildfix_types::ops::OpKind::JsonSet { .. } => "json_set",
-                buildfix_types::ops::OpKind::JsonRemove { .. } => "json_remove",
-                buildfix_types::ops::OpKind::YamlSet { .. } => "yaml_set",
-                buildfix_types::ops::OpKind::YamlRemove { .. } => "yaml_remove",
-                buildfix_types::ops::OpKind::TomlTransform { rule_id, .. } => rule_id,
-                buildfix_types::ops::OpKind::TextReplaceAnchored { .. } => "text_replace_anchored",
-            }
+            "- {}: `{}`\n",
+            m.get(codes::MD_OP_SAFETY),
+            safety_label(op.safety)
+        ));
+        out.push_str(&format!(
+            "- {}: `{}`\n",
+            m.get(codes::MD_OP_BLOCKED),
+            op.blocked
+        ));
+        out.push_str(&format!(
+            "- {}: `{}`\n",
+            m.get(codes::MD_OP_TARGET),
+            op.target.path
+        ));
+        if let Some(workspace) = &op.workspace {
+            out.push_str(&format!(
+                "- {}: `{}`\n",
+                m.get(codes::MD_OP_WORKSPACE),
+                workspace
+            ));
+        }
+        out.push_str(&format!(
+            "- {}: `{}`\n",
+            m.get(codes::MD_OP_KIND),
+            kind_label(&op.kind)
         ));
         if let Some(reason) = &op.blocked_reason {
-            out.push_str(&format!("- Blocked reason: {}\n", reason));
+            out.push_str(&format!(
+                "- {}: {}\n",
+                m.get(codes::MD_OP_BLOCKED_REASON),
+                m.blocked_reason(op.blocked_reason_token, reason)
+            ));
         }
         if let Some(desc) = &op.rationale.description {
             out.push_str(&format!("\n{}\n", desc));
@@ -59,13 +152,21 @@ pub fn render_plan_md(plan: &BuildfixPlan) -> String {
 
         if !op.params_required.is_empty() {
             out.push_str(&format!(
-                "- Params required: {}\n",
+                "- {}: {}\n",
+                m.get(codes::MD_OP_PARAMS_REQUIRED),
                 op.params_required.join(", ")
             ));
         }
+        if let Some(impact) = &op.impact {
+            out.push_str(&format!(
+                "- {}: {}\n",
+                m.get(codes::MD_OP_IMPACT),
+                impact_label(impact, m, |c| format!("`{}`", c))
+            ));
+        }
 
         if !op.rationale.findings.is_empty() {
-            out.push_str("\n**Findings**\n\n");
+            out.push_str(&format!("\n**{}**\n\n", m.get(codes::MD_OP_FINDINGS)));
             for f in &op.rationale.findings {
                 let check = f.check_id.clone().unwrap_or_else(|| "-".to_string());
                 let loc = f
@@ -86,40 +187,98 @@ pub fn render_plan_md(plan: &BuildfixPlan) -> String {
     out
 }
 
-pub fn render_apply_md(apply: &BuildfixApply) -> String {
+/// Changed-line count above which an apply.md diff is folded into a
+/// `<details>` block.
+pub const APPLY_MD_DIFF_FOLD_LINES: usize = 40;
+
+/// Render `apply.md`. Each file a result changed gets its hunks from
+/// `patch`; a file touched by several ops shows the combined diff under the
+/// first of them. Diffs share a budget of [`DEFAULT_EMBEDDED_DIFF_MAX_BYTES`].
+pub fn render_apply_md(apply: &BuildfixApply, patch: &str) -> String {
+    apply_md(apply, patch, &MessageCatalog::default())
+}
+
+pub(crate) fn apply_md(apply: &BuildfixApply, patch: &str, m: &MessageCatalog) -> String {
+    let sections = patch_sections(patch);
+    let mut budget = DiffBudget::new(DEFAULT_EMBEDDED_DIFF_MAX_BYTES);
+    let mut shown: BTreeMap<&str, usize> = BTreeMap::new();
+    let s = &apply.summary;
     let mut out = String::new();
-    out.push_str("# buildfix apply\n\n");
-    out.push_str(&format!(
-        "- Attempted: {}\n- Applied: {}\n- Blocked: {}\n- Failed: {}\n- Files modified: {}\n\n",
-        apply.summary.attempted,
-        apply.summary.applied,
-        apply.summary.blocked,
-        apply.summary.failed,
-        apply.summary.files_modified
-    ));
+    out.push_str(&format!("# {}\n\n", m.get(codes::MD_APPLY_TITLE)));
+    for (code, count) in [
+        (codes::MD_APPLY_ATTEMPTED, s.attempted),
+        (codes::MD_APPLY_APPLIED, s.applied),
+        (codes::MD_APPLY_BLOCKED, s.blocked),
+        (codes::MD_APPLY_FAILED, s.failed),
+        (codes::MD_APPLY_FILES_MODIFIED, s.files_modified),
+    ] {
+        out.push_str(&format!("- {}: {}\n", m.get(code), count));
+    }
+    out.push('\n');
 
-    out.push_str("## Results\n\n");
+    out.push_str(&format!("## {}\n\n", m.get(codes::MD_APPLY_RESULTS)));
     if apply.results.is_empty() {
-        out.push_str("_No results._\n");
+        out.push_str(&format!("_{}_\n", m.get(codes::MD_APPLY_NO_RESULTS)));
         return out;
     }
 
     for (i, r) in apply.results.iter().enumerate() {
         out.push_str(&format!("### {}. {}\n\n", i + 1, r.op_id));
-        out.push_str(&format!("- Status: `{}`\n", status_label(&r.status)));
+        out.push_str(&format!(
+            "- {}: `{}`\n",
+            m.get(codes::MD_RESULT_STATUS),
+            status_label(&r.status)
+        ));
         if let Some(msg) = &r.message {
-            out.push_str(&format!("- Message: {}\n", msg));
+            out.push_str(&format!("- {}: {}\n", m.get(codes::MD_RESULT_MESSAGE), msg));
         }
         if let Some(reason) = &r.blocked_reason {
-            out.push_str(&format!("- Blocked reason: {}\n", reason));
+            out.push_str(&format!(
+                "- {}: {}\n",
+                m.get(codes::MD_OP_BLOCKED_REASON),
+                m.blocked_reason(r.blocked_reason_token, reason)
+            ));
+        }
+        // Only retries and failed read-backs; timings would make the file
+        // differ between identical runs.
+        if let Some(attempts) = r.attempts.filter(|&n| n > 1) {
+            out.push_str(&format!(
+                "- {}: {}\n",
+                m.get(codes::MD_RESULT_ATTEMPTS),
+                attempts
+            ));
+        }
+        if r.verified == Some(false) {
+            out.push_str(&format!("- **{}**\n", m.get(codes::MD_RESULT_UNVERIFIED)));
         }
         if !r.files.is_empty() {
-            out.push_str("\n**Files changed**\n\n");
+            out.push_str(&format!(
+                "\n**{}**\n\n",
+                m.get(codes::MD_RESULT_FILES_CHANGED)
+            ));
             for fc in &r.files {
                 let before = fc.sha256_before.as_deref().unwrap_or("-");
                 let after = fc.sha256_after.as_deref().unwrap_or("-");
                 out.push_str(&format!("- `{}` {} → {}\n", fc.path, before, after));
             }
+            for fc in &r.files {
+                let Some(diff) = sections.get(fc.path.as_str()) else {
+                    continue;
+                };
+                match shown.get(fc.path.as_str()) {
+                    Some(first) => out.push_str(&format!(
+                        "\n_{}_\n",
+                        m.format(
+                            codes::MD_RESULT_DIFF_SHOWN_UNDER,
+                            &[("path", &fc.path), ("index", first)],
+                        )
+                    )),
+                    None => {
+                        shown.insert(fc.path.as_str(), i + 1);
+                        apply_md_diff(&mut out, &fc.path, diff, &mut budget, m);
+                    }
+                }
+            }
         }
         out.push('\n');
     }
@@ -127,53 +286,120 @@ pub fn render_apply_md(apply: &BuildfixApply) -> String {
     out
 }
 
-/// Render a short cockpit-friendly comment summary.
-pub fn render_comment_md(plan: &BuildfixPlan) -> String {
-    let mut out = String::new();
-
-    let ops_applicable = plan
-        .summary
-        .ops_total
-        .saturating_sub(plan.summary.ops_blocked);
-    let fix_available = ops_applicable > 0;
-
-    if fix_available {
-        out.push_str("**buildfix**: fix available\n\n");
-    } else if plan.ops.is_empty() {
-        out.push_str("**buildfix**: no fixes needed\n\n");
-    } else {
-        out.push_str("**buildfix**: all ops blocked\n\n");
+fn apply_md_diff(
+    out: &mut String,
+    path: &str,
+    full: &str,
+    budget: &mut DiffBudget,
+    m: &MessageCatalog,
+) {
+    let (diff, truncated) = budget.take(full);
+    let changed = full
+        .lines()
+        .filter(|l| {
+            (l.starts_with('+') && !l.starts_with("+++"))
+                || (l.starts_with('-') && !l.starts_with("---"))
+        })
+        .count();
+    let fold = changed > APPLY_MD_DIFF_FOLD_LINES;
+    out.push('\n');
+    if fold {
+        out.push_str(&format!(
+            "<details>\n<summary><code>{}</code>: {}</summary>\n\n",
+            path,
+            m.format(codes::MD_RESULT_CHANGED_LINES, &[("count", &changed)])
+        ));
     }
-
-    if let Some(sc) = &plan.summary.safety_counts {
-        out.push_str("| Safety | Count |\n|--------|-------|\n");
-        if sc.safe > 0 {
-            out.push_str(&format!("| safe | {} |\n", sc.safe));
-        }
-        if sc.guarded > 0 {
-            out.push_str(&format!("| guarded | {} |\n", sc.guarded));
-        }
-        if sc.unsafe_count > 0 {
-            out.push_str(&format!("| unsafe | {} |\n", sc.unsafe_count));
-        }
+    out.push_str("```diff\n");
+    out.push_str(diff);
+    if !diff.is_empty() && !diff.ends_with('\n') {
         out.push('\n');
     }
+    out.push_str("```\n");
+    if truncated {
+        out.push_str(&format!(
+            "\n_{}_\n",
+            m.format(
+                codes::MD_DIFF_TRUNCATED,
+                &[("shown", &diff.len()), ("total", &full.len())],
+            )
+        ));
+    }
+    if fold {
+        out.push_str("\n</details>\n");
+    }
+}
 
-    let tokens: std::collections::BTreeSet<&str> = plan
-        .ops
-        .iter()
-        .filter_map(|o| o.blocked_reason_token.as_deref())
-        .collect();
-    if !tokens.is_empty() {
-        out.push_str("**Blocked reasons**: ");
-        let top: Vec<&str> = tokens.into_iter().take(5).collect();
-        out.push_str(&top.join(", "));
-        out.push_str("\n\n");
+fn kind_label(kind: &OpKind) -> &str {
+    match kind {
+        OpKind::TomlSet { .. } => "toml_set",
+        OpKind::TomlRemove { .. } => "toml_remove",
+        OpKind::TomlArrayAppend { .. } => "toml_array_append",
+        OpKind::TomlMerge { .. } => "toml_merge",
+        OpKind::JsonSet { .. } => "json_set",
+        OpKind::JsonRemove { .. } => "json_remove",
+        OpKind::YamlSet { .. } => "yaml_set",
+        OpKind::YamlRemove { .. } => "yaml_remove",
+        OpKind::TomlTransform { rule_id, .. } => rule_id,
+        OpKind::TextReplaceAnchored { .. } => "text_replace_anchored",
+    }
+}
+
+/// `~N lines; crates: a, b; lockfile update; semver-visible`, with each
+/// crate name passed through `quote`.
+pub(crate) fn impact_label(
+    impact: &OpImpact,
+    m: &MessageCatalog,
+    quote: impl Fn(&str) -> String,
+) -> String {
+    let mut parts = vec![m.format(codes::MD_IMPACT_LINES, &[("count", &impact.lines_changed)])];
+    if !impact.affected_crates.is_empty() {
+        let crates: Vec<String> = impact.affected_crates.iter().map(|c| quote(c)).collect();
+        parts.push(m.format(codes::MD_IMPACT_CRATES, &[("crates", &crates.join(", "))]));
     }
+    if impact.lockfile_update {
+        parts.push(m.get(codes::MD_IMPACT_LOCKFILE).to_string());
+    }
+    if impact.semver_visible {
+        parts.push(m.get(codes::MD_IMPACT_SEMVER).to_string());
+    }
+    parts.join("; ")
+}
 
-    out.push_str("Artifacts: [plan.md](plan.md) · [patch.diff](patch.diff)\n");
+pub(crate) fn impact_totals(impact: &ImpactSummary, m: &MessageCatalog) -> String {
+    m.format(
+        codes::MD_IMPACT_TOTALS,
+        &[
+            ("lines", &impact.lines_changed),
+            ("crates", &impact.affected_crates.len()),
+            ("lockfile", &impact.lockfile_update_ops),
+            ("semver", &impact.semver_visible_ops),
+        ],
+    )
+}
 
-    out
+/// One-line issue text for CI report formats: the fix description, plus the
+/// block reason when the op is blocked.
+fn issue_message(op: &PlanOp) -> String {
+    let mut message = op
+        .rationale
+        .description
+        .clone()
+        .unwrap_or_else(|| format!("{} can be fixed by buildfix", op.rationale.fix_key));
+    if let Some(reason) = &op.blocked_reason {
+        message.push_str(&format!(" (blocked: {})", reason));
+    }
+    message
+}
+
+/// Line of the first finding on the op's target file, else 1.
+fn issue_line(op: &PlanOp) -> u64 {
+    op.rationale
+        .findings
+        .iter()
+        .filter(|f| f.path.as_deref() == Some(op.target.path.as_str()))
+        .find_map(|f| f.line)
+        .unwrap_or(1)
 }
 
 fn safety_label(s: SafetyClass) -> &'static str {
@@ -199,6 +425,7 @@ mod tests {
     use buildfix_types::apply::{
         ApplyFile, ApplyRepoInfo, ApplyResult, ApplyStatus, ApplySummary, BuildfixApply, PlanRef,
     };
+    use buildfix_types::error_code::BuildfixErrorCode;
     use buildfix_types::ops::{OpKind, OpTarget};
     use buildfix_types::plan::{
         FindingRef, PlanInput, PlanOp, PlanPolicy, PlanSummary, Rationale, RepoInfo, SafetyCounts,
@@ -221,6 +448,8 @@ mod tests {
                 root: ".".into(),
                 head_sha: None,
                 dirty: None,
+                remote_url: None,
+                ci: None,
             },
             PlanPolicy::default(),
         );
@@ -237,7 +466,7 @@ mod tests {
         plan
     }
 
-    fn make_op(safety: SafetyClass, blocked: bool, token: Option<&str>) -> PlanOp {
+    fn make_op(safety: SafetyClass, blocked: bool, token: Option<BuildfixErrorCode>) -> PlanOp {
         PlanOp {
             id: "test-op".into(),
             safety,
@@ -247,13 +476,15 @@ mod tests {
             } else {
                 None
             },
-            blocked_reason_token: token.map(|s| s.to_string()),
+            blocked_reason_token: token,
             target: OpTarget {
                 path: "Cargo.toml".into(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["workspace".into(), "resolver".into()],
                 value: serde_json::json!("2"),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "test".into(),
@@ -262,6 +493,8 @@ mod tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         }
     }
 
@@ -292,7 +525,11 @@ mod tests {
     #[test]
     fn comment_md_all_blocked() {
         let plan = make_plan(
-            vec![make_op(SafetyClass::Safe, true, Some("denylist"))],
+            vec![make_op(
+                SafetyClass::Safe,
+                true,
+                Some(BuildfixErrorCode::Denylist),
+            )],
             Some(SafetyCounts {
                 safe: 1,
                 guarded: 0,
@@ -314,7 +551,11 @@ mod tests {
 
     #[test]
     fn plan_md_includes_details_and_findings() {
-        let mut op = make_op(SafetyClass::Guarded, true, Some("denylist"));
+        let mut op = make_op(
+            SafetyClass::Guarded,
+            true,
+            Some(BuildfixErrorCode::Denylist),
+        );
         op.blocked_reason = Some("denied by policy".to_string());
         op.rationale.description = Some("Normalize resolver".to_string());
         op.params_required = vec!["version".to_string()];
@@ -325,6 +566,7 @@ mod tests {
             path: Some("Cargo.toml".to_string()),
             line: Some(1),
             fingerprint: None,
+            severity: None,
         });
 
         let plan = make_plan(
@@ -365,10 +607,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary = ApplySummary {
@@ -390,9 +636,12 @@ mod tests {
                 sha256_after: Some("after".to_string()),
                 backup_path: None,
             }],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
 
-        let md = render_apply_md(&apply);
+        let md = render_apply_md(&apply, "");
         assert!(md.contains("# buildfix apply"));
         assert!(md.contains("Attempted: 1"));
         assert!(md.contains("Applied: 1"));
@@ -413,13 +662,17 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
-        let md = render_apply_md(&apply);
+        let md = render_apply_md(&apply, "");
         assert!(md.contains("_No results._"));
     }
 
@@ -472,10 +725,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary = ApplySummary {
@@ -492,6 +749,9 @@ mod tests {
             blocked_reason: None,
             blocked_reason_token: None,
             files: vec![],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
         apply.results.push(ApplyResult {
             op_id: "blocked".to_string(),
@@ -500,6 +760,9 @@ mod tests {
             blocked_reason: Some("reason".to_string()),
             blocked_reason_token: None,
             files: vec![],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
         apply.results.push(ApplyResult {
             op_id: "failed".to_string(),
@@ -508,6 +771,9 @@ mod tests {
             blocked_reason: None,
             blocked_reason_token: None,
             files: vec![],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
         apply.results.push(ApplyResult {
             op_id: "skipped".to_string(),
@@ -516,9 +782,12 @@ mod tests {
             blocked_reason: None,
             blocked_reason_token: None,
             files: vec![],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
 
-        let md = render_apply_md(&apply);
+        let md = render_apply_md(&apply, "");
         assert!(md.contains("Status: `applied`"));
         assert!(md.contains("Status: `blocked`"));
         assert!(md.contains("Status: `failed`"));
@@ -535,6 +804,9 @@ mod tests {
             path: "artifacts/builddiag/report.json".into(),
             schema: None,
             tool: None,
+            git_head_sha: None,
+            generated_at: None,
+            sha256: None,
         }];
 
         let md = render_plan_md(&plan);
@@ -581,7 +853,11 @@ mod tests {
             op
         };
         let unsafe_op = {
-            let mut op = make_op(SafetyClass::Unsafe, true, Some("needs_param"));
+            let mut op = make_op(
+                SafetyClass::Unsafe,
+                true,
+                Some(BuildfixErrorCode::MissingParams),
+            );
             op.id = "unsafe-op".to_string();
             op
         };
@@ -602,7 +878,7 @@ mod tests {
 
     #[test]
     fn plan_md_blocked_ops() {
-        let mut blocked_op = make_op(SafetyClass::Safe, true, Some("policy_deny"));
+        let mut blocked_op = make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::Denylist));
         blocked_op.blocked_reason = Some("Operation denied by policy".to_string());
         blocked_op.rationale.findings.push(FindingRef {
             source: "sensor".to_string(),
@@ -611,6 +887,7 @@ mod tests {
             path: Some("file.toml".to_string()),
             line: Some(10),
             fingerprint: None,
+            severity: None,
         });
 
         let plan = make_plan(vec![blocked_op], None);
@@ -621,15 +898,11 @@ mod tests {
 
     #[test]
     fn comment_md_multiple_blocked_reasons() {
-        let tokens = vec![
-            "reason1", "reason2", "reason3", "reason4", "reason5", "reason6",
-        ];
-        let mut ops = vec![];
-        for token in &tokens {
-            let mut op = make_op(SafetyClass::Safe, true, Some(*token));
-            op.blocked_reason_token = Some(token.to_string());
-            ops.push(op);
-        }
+        let ops = BuildfixErrorCode::ALL
+            .into_iter()
+            .take(6)
+            .map(|code| make_op(SafetyClass::Safe, true, Some(code)))
+            .collect();
 
         let mut plan = make_plan(
             ops,
@@ -644,8 +917,8 @@ mod tests {
         let md = render_comment_md(&plan);
         assert!(md.contains("all ops blocked"));
         assert!(md.contains("**Blocked reasons**"));
-        assert!(md.contains("reason1"));
-        assert!(md.contains("reason2"));
+        assert!(md.contains("missing_params"));
+        assert!(md.contains("denylist"));
     }
 
     #[test]
@@ -656,6 +929,7 @@ mod tests {
                 op.kind = OpKind::TomlSet {
                     toml_path: vec!["a".into(), "b".into()],
                     value: serde_json::json!("val"),
+                    insert: None,
                 };
                 op.id = "toml_set".to_string();
                 op
@@ -743,10 +1017,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary = ApplySummary {
@@ -776,9 +1054,12 @@ mod tests {
                     backup_path: None,
                 },
             ],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
 
-        let md = render_apply_md(&apply);
+        let md = render_apply_md(&apply, "");
         assert!(md.contains("Files modified: 2"));
         assert!(md.contains("Cargo.toml"));
         assert!(md.contains("src/main.rs"));
@@ -794,10 +1075,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary = ApplySummary {
@@ -819,9 +1104,12 @@ mod tests {
                 sha256_after: None,
                 backup_path: None,
             }],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
 
-        let md = render_apply_md(&apply);
+        let md = render_apply_md(&apply, "");
         assert!(md.contains("test.toml"));
         assert!(md.contains("- → -"));
     }
@@ -837,6 +1125,7 @@ mod tests {
                 path: Some("file1.toml".to_string()),
                 line: Some(1),
                 fingerprint: None,
+                severity: None,
             },
             FindingRef {
                 source: "sensor2".to_string(),
@@ -845,6 +1134,7 @@ mod tests {
                 path: Some("file2.rs".to_string()),
                 line: Some(42),
                 fingerprint: None,
+                severity: None,
             },
             FindingRef {
                 source: "sensor3".to_string(),
@@ -853,6 +1143,7 @@ mod tests {
                 path: None,
                 line: None,
                 fingerprint: None,
+                severity: None,
             },
         ];
 
@@ -899,10 +1190,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary = ApplySummary {
@@ -919,9 +1214,12 @@ mod tests {
             blocked_reason: None,
             blocked_reason_token: None,
             files: vec![],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
 
-        let md = render_apply_md(&apply);
+        let md = render_apply_md(&apply, "");
         assert!(md.contains("Failed: 1"));
         assert!(md.contains("Status: `failed`"));
         assert!(md.contains("Message: IO error"));
@@ -0,0 +1,247 @@
+//! Plan-to-plan comparison, for reviewing how a rebase or new receipts
+//! changed the fix set.
+//!
+//! Ops are matched by id. Ids are derived from the fix key, target and edit
+//! arguments, so an op whose edit changed shows up as removed plus added;
+//! "changed" covers the same edit with a different safety class, block
+//! state, required params, target hash or findings. Findings are compared by
+//! fingerprint, computed for findings whose receipt did not carry one, so the
+//! same count of different findings still shows as a change.
+
+use crate::{kind_label, safety_label};
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use serde::Serialize;
+use serde_json::{Value, json};
+use std::collections::{BTreeSet, HashMap};
+
+/// Differences between two plans.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct PlanDiff {
+    /// Ops only in the new plan, in new-plan order.
+    pub added: Vec<OpSummary>,
+    /// Ops only in the old plan, in old-plan order.
+    pub removed: Vec<OpSummary>,
+    /// Ops in both plans whose attributes differ, in new-plan order.
+    pub changed: Vec<OpChange>,
+    /// Ops identical in both plans.
+    pub unchanged: usize,
+}
+
+impl PlanDiff {
+    pub fn is_empty(&self) -> bool {
+        self.added.is_empty() && self.removed.is_empty() && self.changed.is_empty()
+    }
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpSummary {
+    pub id: String,
+    pub fix_key: String,
+    pub target: String,
+    pub kind: String,
+    pub safety: &'static str,
+    pub blocked: bool,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct OpChange {
+    pub id: String,
+    pub fix_key: String,
+    pub target: String,
+    pub changes: Vec<FieldChange>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct FieldChange {
+    pub field: &'static str,
+    pub old: Value,
+    pub new: Value,
+}
+
+/// Compare two plans op by op.
+pub fn diff_plans(old: &BuildfixPlan, new: &BuildfixPlan) -> PlanDiff {
+    let old_ops: HashMap<&str, &PlanOp> = old.ops.iter().map(|op| (op.id.as_str(), op)).collect();
+    let new_ops: HashMap<&str, &PlanOp> = new.ops.iter().map(|op| (op.id.as_str(), op)).collect();
+
+    let mut diff = PlanDiff {
+        added: Vec::new(),
+        removed: Vec::new(),
+        changed: Vec::new(),
+        unchanged: 0,
+    };
+    for op in &new.ops {
+        match old_ops.get(op.id.as_str()) {
+            None => diff.added.push(summary(op)),
+            Some(before) => {
+                let changes = field_changes(before, op);
+                if changes.is_empty() {
+                    diff.unchanged += 1;
+                } else {
+                    diff.changed.push(OpChange {
+                        id: op.id.clone(),
+                        fix_key: op.rationale.fix_key.clone(),
+                        target: op.target.path.clone(),
+                        changes,
+                    });
+                }
+            }
+        }
+    }
+    diff.removed = old
+        .ops
+        .iter()
+        .filter(|op| !new_ops.contains_key(op.id.as_str()))
+        .map(summary)
+        .collect();
+    diff
+}
+
+/// Render the differences between two plans as markdown.
+pub fn render_plan_diff(old: &BuildfixPlan, new: &BuildfixPlan) -> String {
+    let diff = diff_plans(old, new);
+    let mut out = String::new();
+    out.push_str("# buildfix plan diff\n\n");
+    out.push_str(&format!("- Added: {}\n", diff.added.len()));
+    out.push_str(&format!("- Removed: {}\n", diff.removed.len()));
+    out.push_str(&format!("- Changed: {}\n", diff.changed.len()));
+    out.push_str(&format!("- Unchanged: {}\n\n", diff.unchanged));
+
+    if diff.is_empty() {
+        out.push_str("No differences.\n");
+        return out;
+    }
+
+    for (title, ops) in [("Added", &diff.added), ("Removed", &diff.removed)] {
+        if ops.is_empty() {
+            continue;
+        }
+        out.push_str(&format!("## {}\n\n", title));
+        out.push_str("| Op | Fix | Target | Kind | Safety | Status |\n");
+        out.push_str("|----|-----|--------|------|--------|--------|\n");
+        for op in ops {
+            out.push_str(&format!(
+                "| `{}` | `{}` | `{}` | `{}` | {} | {} |\n",
+                short_id(&op.id),
+                op.fix_key,
+                op.target,
+                op.kind,
+                op.safety,
+                if op.blocked { "blocked" } else { "ready" }
+            ));
+        }
+        out.push('\n');
+    }
+
+    if !diff.changed.is_empty() {
+        out.push_str("## Changed\n\n");
+        out.push_str("| Op | Fix | Target | Changes |\n");
+        out.push_str("|----|-----|--------|---------|\n");
+        for op in &diff.changed {
+            let changes: Vec<String> = op
+                .changes
+                .iter()
+                .map(|c| format!("{}: {} → {}", c.field, display(&c.old), display(&c.new)))
+                .collect();
+            out.push_str(&format!(
+                "| `{}` | `{}` | `{}` | {} |\n",
+                short_id(&op.id),
+                op.fix_key,
+                op.target,
+                changes.join("; ")
+            ));
+        }
+        out.push('\n');
+    }
+
+    out
+}
+
+/// Render the differences between two plans as pretty-printed JSON.
+pub fn render_plan_diff_json(old: &BuildfixPlan, new: &BuildfixPlan) -> String {
+    let mut out =
+        serde_json::to_string_pretty(&diff_plans(old, new)).expect("plan diff serializes");
+    out.push('\n');
+    out
+}
+
+fn summary(op: &PlanOp) -> OpSummary {
+    OpSummary {
+        id: op.id.clone(),
+        fix_key: op.rationale.fix_key.clone(),
+        target: op.target.path.clone(),
+        kind: kind_label(&op.kind).to_string(),
+        safety: safety_label(op.safety),
+        blocked: op.blocked,
+    }
+}
+
+fn field_changes(old: &PlanOp, new: &PlanOp) -> Vec<FieldChange> {
+    let fields = [
+        (
+            "safety",
+            json!(safety_label(old.safety)),
+            json!(safety_label(new.safety)),
+        ),
+        ("blocked", json!(old.blocked), json!(new.blocked)),
+        (
+            "blocked_reason",
+            json!(old.blocked_reason_token),
+            json!(new.blocked_reason_token),
+        ),
+        (
+            "params_required",
+            json!(old.params_required),
+            json!(new.params_required),
+        ),
+        (
+            "target_sha256",
+            json!(old.target.sha256),
+            json!(new.target.sha256),
+        ),
+        (
+            "findings",
+            json!(old.rationale.findings.len()),
+            json!(new.rationale.findings.len()),
+        ),
+    ];
+    let mut changes: Vec<FieldChange> = fields
+        .into_iter()
+        .filter(|(_, old, new)| old != new)
+        .map(|(field, old, new)| FieldChange { field, old, new })
+        .collect();
+    // A different count already shows under `findings`.
+    if old.rationale.findings.len() == new.rationale.findings.len() {
+        let (old_fps, new_fps) = (fingerprints(old), fingerprints(new));
+        if old_fps != new_fps {
+            changes.push(FieldChange {
+                field: "finding_fingerprints",
+                old: json!(old_fps),
+                new: json!(new_fps),
+            });
+        }
+    }
+    changes
+}
+
+/// Sorted, shortened fingerprints of the op's findings.
+fn fingerprints(op: &PlanOp) -> BTreeSet<String> {
+    op.rationale
+        .findings
+        .iter()
+        .map(|f| short_id(&f.stable_fingerprint()).to_string())
+        .collect()
+}
+
+fn short_id(id: &str) -> &str {
+    id.get(..8).unwrap_or(id)
+}
+
+fn display(value: &Value) -> String {
+    match value {
+        Value::Null => "-".to_string(),
+        Value::String(s) => s.clone(),
+        Value::Array(items) if items.is_empty() => "[]".to_string(),
+        Value::Array(items) => items.iter().map(display).collect::<Vec<_>>().join(", "),
+        other => other.to_string(),
+    }
+}
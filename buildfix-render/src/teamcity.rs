@@ -0,0 +1,81 @@
+//! TeamCity service messages (`##teamcity[...]`).
+//!
+//! Printed to stdout, they register each fixer as an inspection type and
+//! each op as an inspection, and publish op counts as build statistics.
+
+use crate::{issue_line, issue_message};
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::{BuildfixPlan, PlanOp};
+use std::collections::BTreeSet;
+
+/// Render plan ops as TeamCity inspection service messages.
+pub fn render_plan_teamcity(plan: &BuildfixPlan) -> String {
+    let mut out = String::new();
+
+    let fix_keys: BTreeSet<&str> = plan
+        .ops
+        .iter()
+        .map(|op| op.rationale.fix_key.as_str())
+        .collect();
+    for key in fix_keys {
+        out.push_str(&format!(
+            "##teamcity[inspectionType id='{0}' name='{0}' description='buildfix fix {0}' category='buildfix']\n",
+            escape(key)
+        ));
+    }
+
+    for op in &plan.ops {
+        out.push_str(&format!(
+            "##teamcity[inspection typeId='{}' message='{}' file='{}' line='{}' SEVERITY='{}']\n",
+            escape(&op.rationale.fix_key),
+            escape(&issue_message(op)),
+            escape(&op.target.path),
+            issue_line(op),
+            severity(op)
+        ));
+    }
+
+    for (key, value) in [
+        ("buildfix.ops_total", plan.summary.ops_total),
+        ("buildfix.ops_blocked", plan.summary.ops_blocked),
+        ("buildfix.files_touched", plan.summary.files_touched),
+    ] {
+        out.push_str(&format!(
+            "##teamcity[buildStatisticValue key='{}' value='{}']\n",
+            key, value
+        ));
+    }
+
+    out
+}
+
+fn severity(op: &PlanOp) -> &'static str {
+    if op.blocked {
+        return "INFO";
+    }
+    match op.safety {
+        SafetyClass::Safe => "WEAK WARNING",
+        SafetyClass::Guarded => "WARNING",
+        SafetyClass::Unsafe => "ERROR",
+    }
+}
+
+/// Escape a value for a service message attribute.
+fn escape(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    for c in s.chars() {
+        match c {
+            '|' => out.push_str("||"),
+            '\'' => out.push_str("|'"),
+            '\n' => out.push_str("|n"),
+            '\r' => out.push_str("|r"),
+            '[' => out.push_str("|["),
+            ']' => out.push_str("|]"),
+            '\u{0085}' => out.push_str("|x"),
+            '\u{2028}' => out.push_str("|l"),
+            '\u{2029}' => out.push_str("|p"),
+            _ => out.push(c),
+        }
+    }
+    out
+}
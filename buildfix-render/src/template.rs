@@ -0,0 +1,79 @@
+//! User-supplied MiniJinja templates for the markdown artifacts.
+//!
+//! Each template sees the same data the builtin renderer uses (`plan`,
+//! `apply`) plus `default`, the builtin markdown itself, so a template can
+//! wrap or trim the stock output instead of rebuilding it.
+
+use crate::comment::comment_md;
+use crate::{CommentOptions, apply_md, plan_md};
+use anyhow::Context;
+use buildfix_types::apply::BuildfixApply;
+use buildfix_types::messages::MessageCatalog;
+use buildfix_types::plan::BuildfixPlan;
+use minijinja::{Environment, UndefinedBehavior, context};
+
+/// Template sources for `plan.md`, `apply.md` and `comment.md`. A missing
+/// template means the builtin markdown is written unchanged.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MarkdownTemplates {
+    pub plan: Option<String>,
+    pub apply: Option<String>,
+    pub comment: Option<String>,
+    /// Options for the builtin comment, which is also `default` in a
+    /// `comment.md` template.
+    pub comment_options: CommentOptions,
+    /// Messages for the builtin markdown; English by default.
+    pub messages: MessageCatalog,
+}
+
+impl MarkdownTemplates {
+    /// Whether every artifact uses its builtin markdown (possibly with
+    /// localized messages).
+    pub fn is_builtin(&self) -> bool {
+        self.plan.is_none() && self.apply.is_none() && self.comment.is_none()
+    }
+
+    /// Render `plan.md`. Context: `plan`, `default`.
+    pub fn render_plan_md(&self, plan: &BuildfixPlan) -> anyhow::Result<String> {
+        let default = plan_md(plan, &self.messages);
+        match &self.plan {
+            Some(source) => render("plan.md", source, context! { plan, default }),
+            None => Ok(default),
+        }
+    }
+
+    /// Render `apply.md`. Context: `apply`, `plan`, `patch`, `default`.
+    pub fn render_apply_md(
+        &self,
+        apply: &BuildfixApply,
+        plan: &BuildfixPlan,
+        patch: &str,
+    ) -> anyhow::Result<String> {
+        let default = apply_md(apply, patch, &self.messages);
+        match &self.apply {
+            Some(source) => render("apply.md", source, context! { apply, plan, patch, default }),
+            None => Ok(default),
+        }
+    }
+
+    /// Render `comment.md`. Context: `plan`, `default`.
+    pub fn render_comment_md(&self, plan: &BuildfixPlan) -> anyhow::Result<String> {
+        let default = comment_md(plan, &self.comment_options, &self.messages);
+        match &self.comment {
+            Some(source) => render("comment.md", source, context! { plan, default }),
+            None => Ok(default),
+        }
+    }
+}
+
+fn render(name: &str, source: &str, ctx: minijinja::Value) -> anyhow::Result<String> {
+    let mut env = Environment::new();
+    // A typo in a field name should fail the run, not silently drop text.
+    env.set_undefined_behavior(UndefinedBehavior::Strict);
+    env.set_keep_trailing_newline(true);
+    env.add_template(name, source)
+        .with_context(|| format!("parse {} template", name))?;
+    env.get_template(name)
+        .and_then(|t| t.render(ctx))
+        .with_context(|| format!("render {} template", name))
+}
@@ -0,0 +1,272 @@
+//! Human-facing terminal summaries for `--format pretty`.
+//!
+//! Output is plain text with optional ANSI colors; callers decide whether
+//! the stream supports them.
+
+use crate::html::patch_sections;
+use crate::{kind_label, safety_label, status_label};
+use buildfix_types::apply::{ApplyStatus, BuildfixApply};
+use buildfix_types::ops::SafetyClass;
+use buildfix_types::plan::BuildfixPlan;
+
+/// Changed lines shown per op before the diff is cut off.
+const MINI_DIFF_LINES: usize = 8;
+
+const RESET: &str = "\x1b[0m";
+const BOLD: &str = "\x1b[1m";
+const DIM: &str = "\x1b[2m";
+const RED: &str = "\x1b[31m";
+const GREEN: &str = "\x1b[32m";
+const YELLOW: &str = "\x1b[33m";
+const CYAN: &str = "\x1b[36m";
+
+struct Paint {
+    color: bool,
+}
+
+impl Paint {
+    fn paint(&self, style: &str, text: &str) -> String {
+        if self.color {
+            format!("{}{}{}", style, text, RESET)
+        } else {
+            text.to_string()
+        }
+    }
+
+    /// Pad `text` to `width` columns before coloring, so escapes do not
+    /// throw off alignment.
+    fn cell(&self, style: &str, text: &str, width: usize) -> String {
+        let pad = width.saturating_sub(text.chars().count());
+        format!("{}{}", self.paint(style, text), " ".repeat(pad))
+    }
+}
+
+fn safety_style(s: SafetyClass) -> &'static str {
+    match s {
+        SafetyClass::Safe => GREEN,
+        SafetyClass::Guarded => YELLOW,
+        SafetyClass::Unsafe => RED,
+    }
+}
+
+/// Render a plan as an aligned op table followed by a mini-diff per op.
+pub fn render_plan_pretty(plan: &BuildfixPlan, patch: &str, color: bool) -> String {
+    let p = Paint { color };
+    let mut out = String::new();
+
+    out.push_str(&format!(
+        "{} {} ops ({} blocked), {} files\n",
+        p.paint(BOLD, "buildfix plan:"),
+        plan.summary.ops_total,
+        plan.summary.ops_blocked,
+        plan.summary.files_touched
+    ));
+    if let Some(sc) = &plan.summary.safety_counts {
+        out.push_str(&format!(
+            "  {} {}, {} {}, {} {}\n",
+            sc.safe,
+            p.paint(GREEN, "safe"),
+            sc.guarded,
+            p.paint(YELLOW, "guarded"),
+            sc.unsafe_count,
+            p.paint(RED, "unsafe")
+        ));
+    }
+
+    if plan.ops.is_empty() {
+        out.push_str("\nNo ops planned.\n");
+        return out;
+    }
+
+    let rows: Vec<[String; 6]> = plan
+        .ops
+        .iter()
+        .enumerate()
+        .map(|(i, op)| {
+            [
+                (i + 1).to_string(),
+                safety_label(op.safety).to_string(),
+                if op.blocked { "blocked" } else { "ready" }.to_string(),
+                op.rationale.fix_key.clone(),
+                kind_label(&op.kind).to_string(),
+                op.target.path.clone(),
+            ]
+        })
+        .collect();
+    let header = ["#", "SAFETY", "STATUS", "FIX", "KIND", "TARGET"];
+    let widths: Vec<usize> = (0..header.len())
+        .map(|c| {
+            rows.iter()
+                .map(|r| r[c].chars().count())
+                .chain(std::iter::once(header[c].len()))
+                .max()
+                .unwrap_or(0)
+        })
+        .collect();
+
+    out.push('\n');
+    let head: Vec<String> = header
+        .iter()
+        .zip(&widths)
+        .map(|(h, w)| p.cell(BOLD, h, *w))
+        .collect();
+    out.push_str(&format!("  {}\n", head.join("  ").trim_end()));
+    for (op, row) in plan.ops.iter().zip(&rows) {
+        let styles = [
+            DIM,
+            safety_style(op.safety),
+            if op.blocked { RED } else { GREEN },
+            "",
+            DIM,
+            CYAN,
+        ];
+        let cells: Vec<String> = row
+            .iter()
+            .zip(&widths)
+            .zip(styles)
+            .map(|((text, w), style)| {
+                if style.is_empty() {
+                    format!("{:w$}", text, w = *w)
+                } else {
+                    p.cell(style, text, *w)
+                }
+            })
+            .collect();
+        let mut line = format!("  {}", cells.join("  ").trim_end());
+        if let Some(token) = op
+            .blocked_reason_token
+            .map(|c| c.as_str())
+            .filter(|_| op.blocked)
+        {
+            line.push_str(&format!("  {}", p.paint(DIM, &format!("({})", token))));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let sections = patch_sections(patch);
+    for (i, op) in plan.ops.iter().enumerate() {
+        let diff = op
+            .preview
+            .as_ref()
+            .map(|pv| pv.patch_fragment.as_str())
+            .or_else(|| sections.get(op.target.path.as_str()).copied());
+        out.push_str(&format!(
+            "\n{} {}\n",
+            p.paint(BOLD, &format!("{}.", i + 1)),
+            p.paint(CYAN, &op.target.path)
+        ));
+        if let Some(desc) = &op.rationale.description {
+            out.push_str(&format!("   {}\n", desc));
+        }
+        if let Some(reason) = op.blocked_reason.as_deref().filter(|_| op.blocked) {
+            out.push_str(&format!("   {} {}\n", p.paint(RED, "blocked:"), reason));
+        }
+        if let Some(diff) = diff {
+            mini_diff(&mut out, &p, diff);
+        }
+    }
+
+    out
+}
+
+/// Render apply results as an aligned status table with mini-diffs for the
+/// files that changed.
+pub fn render_apply_pretty(apply: &BuildfixApply, patch: &str, color: bool) -> String {
+    let p = Paint { color };
+    let s = &apply.summary;
+    let mut out = String::new();
+
+    out.push_str(&format!(
+        "{} {} {}, {} {}, {} {} ({} attempted), {} files modified\n",
+        p.paint(BOLD, "buildfix apply:"),
+        s.applied,
+        p.paint(GREEN, "applied"),
+        s.blocked,
+        p.paint(YELLOW, "blocked"),
+        s.failed,
+        p.paint(RED, "failed"),
+        s.attempted,
+        s.files_modified
+    ));
+
+    if apply.results.is_empty() {
+        out.push_str("\nNo results.\n");
+        return out;
+    }
+
+    let status_width = apply
+        .results
+        .iter()
+        .map(|r| status_label(&r.status).len())
+        .max()
+        .unwrap_or(0)
+        .max("STATUS".len());
+    out.push('\n');
+    out.push_str(&format!(
+        "  {}  {}\n",
+        p.cell(BOLD, "STATUS", status_width),
+        p.paint(BOLD, "OP")
+    ));
+    for r in &apply.results {
+        let style = match r.status {
+            ApplyStatus::Applied => GREEN,
+            ApplyStatus::Blocked | ApplyStatus::Skipped => YELLOW,
+            ApplyStatus::Failed => RED,
+        };
+        let mut line = format!(
+            "  {}  {}",
+            p.cell(style, status_label(&r.status), status_width),
+            r.op_id
+        );
+        if let Some(detail) = r.blocked_reason.as_deref().or(r.message.as_deref()) {
+            line.push_str(&format!("  {}", p.paint(DIM, &format!("({})", detail))));
+        }
+        out.push_str(&line);
+        out.push('\n');
+    }
+
+    let sections = patch_sections(patch);
+    for (path, diff) in &sections {
+        out.push_str(&format!("\n{}\n", p.paint(CYAN, path)));
+        mini_diff(&mut out, &p, diff);
+    }
+
+    out
+}
+
+/// Hunk headers and changed lines, capped at [`MINI_DIFF_LINES`] changes.
+fn mini_diff(out: &mut String, p: &Paint, diff: &str) {
+    let mut shown = 0;
+    let mut hidden = 0;
+    for line in diff.lines() {
+        if line.starts_with("+++") || line.starts_with("---") {
+            continue;
+        }
+        let style = if line.starts_with("@@") {
+            CYAN
+        } else if line.starts_with('+') {
+            GREEN
+        } else if line.starts_with('-') {
+            RED
+        } else {
+            continue;
+        };
+        if style != CYAN {
+            if shown == MINI_DIFF_LINES {
+                hidden += 1;
+                continue;
+            }
+            shown += 1;
+        } else if shown == MINI_DIFF_LINES {
+            continue;
+        }
+        out.push_str(&format!("   {}\n", p.paint(style, line)));
+    }
+    if hidden > 0 {
+        out.push_str(&format!(
+            "   {}\n",
+            p.paint(DIM, &format!("... {} more changed lines", hidden))
+        ));
+    }
+}
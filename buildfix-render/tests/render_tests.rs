@@ -3,14 +3,22 @@
 //! These tests complement the inline unit tests in src/lib.rs by covering
 //! additional edge cases and scenarios.
 
-use buildfix_render::{render_apply_md, render_comment_md, render_plan_md};
+use buildfix_render::{
+    APPLY_MD_DIFF_FOLD_LINES, COMMENT_MARKER, CommentOptions, DEFAULT_COMMENT_MAX_BYTES,
+    DEFAULT_EMBEDDED_DIFF_MAX_BYTES, MarkdownTemplates, MessageCatalog, diff_plans,
+    render_apply_junit, render_apply_md, render_apply_pretty, render_comment_md,
+    render_comment_md_with, render_gitlab_code_quality, render_plan_checkstyle, render_plan_csv,
+    render_plan_diff, render_plan_diff_json, render_plan_html, render_plan_junit, render_plan_md,
+    render_plan_pretty, render_plan_teamcity, render_plan_tsv,
+};
 use buildfix_types::apply::{
     ApplyFile, ApplyRepoInfo, ApplyResult, ApplyStatus, ApplySummary, BuildfixApply, PlanRef,
 };
-use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+use buildfix_types::error_code::BuildfixErrorCode;
+use buildfix_types::ops::{OpKind, OpPreview, OpTarget, SafetyClass};
 use buildfix_types::plan::{
-    BuildfixPlan, FindingRef, PlanInput, PlanOp, PlanPolicy, PlanSummary, Rationale, RepoInfo,
-    SafetyCounts,
+    BuildfixPlan, FindingRef, OpImpact, PlanInput, PlanOp, PlanPolicy, PlanSummary, PlanWorkspace,
+    Rationale, RepoInfo, SafetyCounts,
 };
 use buildfix_types::receipt::ToolInfo;
 
@@ -30,6 +38,8 @@ fn make_plan(ops: Vec<PlanOp>, safety_counts: Option<SafetyCounts>) -> BuildfixP
             root: ".".into(),
             head_sha: None,
             dirty: None,
+            remote_url: None,
+            ci: None,
         },
         PlanPolicy::default(),
     );
@@ -46,7 +56,7 @@ fn make_plan(ops: Vec<PlanOp>, safety_counts: Option<SafetyCounts>) -> BuildfixP
     plan
 }
 
-fn make_op(safety: SafetyClass, blocked: bool, token: Option<&str>) -> PlanOp {
+fn make_op(safety: SafetyClass, blocked: bool, token: Option<BuildfixErrorCode>) -> PlanOp {
     PlanOp {
         id: "test-op".into(),
         safety,
@@ -56,13 +66,15 @@ fn make_op(safety: SafetyClass, blocked: bool, token: Option<&str>) -> PlanOp {
         } else {
             None
         },
-        blocked_reason_token: token.map(|s| s.to_string()),
+        blocked_reason_token: token,
         target: OpTarget {
             path: "Cargo.toml".into(),
+            sha256: None,
         },
         kind: OpKind::TomlSet {
             toml_path: vec!["workspace".into(), "resolver".into()],
             value: serde_json::json!("2"),
+            insert: None,
         },
         rationale: Rationale {
             fix_key: "test".into(),
@@ -71,6 +83,8 @@ fn make_op(safety: SafetyClass, blocked: bool, token: Option<&str>) -> PlanOp {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     }
 }
 
@@ -83,10 +97,14 @@ fn make_apply() -> BuildfixApply {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     )
 }
@@ -144,12 +162,85 @@ fn plan_md_target_path_displayed() {
     let mut op = make_op(SafetyClass::Safe, false, None);
     op.target = OpTarget {
         path: "crates/my-crate/Cargo.toml".into(),
+        sha256: None,
     };
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
     assert!(md.contains("- Target: `crates/my-crate/Cargo.toml`"));
 }
 
+#[test]
+fn plan_md_and_comment_md_show_impact() {
+    let mut op = make_op(SafetyClass::Safe, false, None);
+    op.impact = Some(OpImpact {
+        lines_changed: 3,
+        affected_crates: vec!["core".into(), "cli".into()],
+        lockfile_update: true,
+        semver_visible: true,
+    });
+    let mut blocked = make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::Denylist));
+    blocked.impact = Some(OpImpact {
+        lines_changed: 10,
+        ..OpImpact::default()
+    });
+    let plan = make_plan(vec![op, blocked], None);
+
+    let md = render_plan_md(&plan);
+    assert!(md.contains("- Impact: ~3 lines, 2 crates, 1 lockfile updates, 1 semver-visible\n"));
+    assert!(
+        md.contains("- Impact: ~3 lines; crates: `core`, `cli`; lockfile update; semver-visible\n")
+    );
+    assert!(md.contains("- Impact: ~10 lines\n"));
+
+    let comment = render_comment_md(&plan);
+    assert!(
+        comment.contains("**Impact**: ~3 lines, 2 crates, 1 lockfile updates, 1 semver-visible\n")
+    );
+    assert!(comment.contains(" · _lockfile update, semver-visible_\n"));
+}
+
+#[test]
+fn plan_md_without_impact_has_no_impact_lines() {
+    let plan = make_plan(vec![make_op(SafetyClass::Safe, false, None)], None);
+    assert!(!render_plan_md(&plan).contains("Impact"));
+    assert!(!render_comment_md(&plan).contains("Impact"));
+}
+
+#[test]
+fn plan_md_lists_workspaces() {
+    let mut op = make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::MaxOps));
+    op.workspace = Some("tools/cli".into());
+    let mut plan = make_plan(vec![op], None);
+    plan.workspaces = vec![
+        PlanWorkspace {
+            root: ".".into(),
+            summary: PlanSummary::default(),
+        },
+        PlanWorkspace {
+            root: "tools/cli".into(),
+            summary: PlanSummary {
+                ops_total: 1,
+                ops_blocked: 1,
+                files_touched: 1,
+                ..PlanSummary::default()
+            },
+        },
+    ];
+
+    let md = render_plan_md(&plan);
+    assert!(md.contains(
+        "## Workspaces\n\n- `.`: Ops: 0 (blocked 0), Files touched: 0\n\
+         - `tools/cli`: Ops: 1 (blocked 1), Files touched: 1\n"
+    ));
+    assert!(md.contains("- Workspace: `tools/cli`\n"));
+
+    let single = render_plan_md(&make_plan(
+        vec![make_op(SafetyClass::Safe, false, None)],
+        None,
+    ));
+    assert!(!single.contains("Workspace"));
+}
+
 #[test]
 fn plan_md_inputs_count() {
     let mut plan = make_plan(vec![], None);
@@ -158,11 +249,17 @@ fn plan_md_inputs_count() {
             path: "artifacts/clippy/report.json".into(),
             schema: None,
             tool: None,
+            git_head_sha: None,
+            generated_at: None,
+            sha256: None,
         },
         PlanInput {
             path: "artifacts/machete/report.json".into(),
             schema: None,
             tool: None,
+            git_head_sha: None,
+            generated_at: None,
+            sha256: None,
         },
     ];
     let md = render_plan_md(&plan);
@@ -179,6 +276,7 @@ fn plan_md_finding_with_no_check_id_shows_dash() {
         path: Some("file.rs".to_string()),
         line: Some(10),
         fingerprint: None,
+        severity: None,
     });
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
@@ -195,6 +293,7 @@ fn plan_md_finding_with_no_path_shows_dash() {
         path: None,
         line: None,
         fingerprint: None,
+        severity: None,
     });
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
@@ -211,6 +310,7 @@ fn plan_md_finding_line_zero() {
         path: Some("file.rs".to_string()),
         line: Some(0),
         fingerprint: None,
+        severity: None,
     });
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
@@ -224,7 +324,7 @@ fn plan_md_finding_line_zero() {
 #[test]
 fn apply_md_header_format() {
     let apply = make_apply();
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.starts_with("# buildfix apply\n"));
 }
 
@@ -238,7 +338,7 @@ fn apply_md_summary_format() {
         failed: 1,
         files_modified: 5,
     };
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("- Attempted: 10"));
     assert!(md.contains("- Applied: 7"));
     assert!(md.contains("- Blocked: 2"));
@@ -249,7 +349,7 @@ fn apply_md_summary_format() {
 #[test]
 fn apply_md_results_section_header() {
     let apply = make_apply();
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("## Results\n"));
 }
 
@@ -263,6 +363,9 @@ fn apply_md_operation_numbering() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
     apply.results.push(ApplyResult {
         op_id: "second".to_string(),
@@ -271,13 +374,108 @@ fn apply_md_operation_numbering() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("### 1. first"));
     assert!(md.contains("### 2. second"));
 }
 
+fn changed_file(path: &str) -> ApplyFile {
+    ApplyFile {
+        path: path.to_string(),
+        sha256_before: Some("aaa".to_string()),
+        sha256_after: Some("bbb".to_string()),
+        backup_path: None,
+    }
+}
+
+#[test]
+fn apply_md_includes_diff_per_changed_file() {
+    let mut apply = make_apply();
+    let mut first = result("first", ApplyStatus::Applied, None);
+    first.files = vec![changed_file("Cargo.toml")];
+    let mut second = result("second", ApplyStatus::Applied, None);
+    second.files = vec![changed_file("Cargo.toml")];
+    apply.results = vec![first, second];
+    let patch = "diff --git a/Cargo.toml b/Cargo.toml\n--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,2 +1,2 @@\n [workspace]\n-resolver = \"1\"\n+resolver = \"2\"\n";
+
+    let md = render_apply_md(&apply, patch);
+    assert_eq!(md.matches("```diff\n").count(), 1);
+    assert!(md.contains(&format!("```diff\n{}```\n", patch)));
+    assert!(!md.contains("<details>"));
+    assert!(md.contains("_Diff for `Cargo.toml` is shown under result 1._"));
+    // The diff sits under the first result, before the second heading.
+    assert!(md.find("```diff").unwrap() < md.find("### 2. second").unwrap());
+}
+
+#[test]
+fn apply_md_folds_large_diffs() {
+    let mut apply = make_apply();
+    let mut r = result("big", ApplyStatus::Applied, None);
+    r.files = vec![changed_file("Cargo.toml"), changed_file("README.md")];
+    apply.results = vec![r];
+    let mut patch = String::from(
+        "diff --git a/Cargo.toml b/Cargo.toml\n--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,41 +1,0 @@\n",
+    );
+    for i in 0..=APPLY_MD_DIFF_FOLD_LINES {
+        patch.push_str(&format!("-line {}\n", i));
+    }
+
+    let md = render_apply_md(&apply, &patch);
+    assert!(md.contains(&format!(
+        "<details>\n<summary><code>Cargo.toml</code>: {} changed lines</summary>\n\n```diff\n",
+        APPLY_MD_DIFF_FOLD_LINES + 1
+    )));
+    assert!(md.contains("```\n\n</details>\n"));
+    // README.md has no hunks in the patch, so only its hashes are listed.
+    assert_eq!(md.matches("```diff").count(), 1);
+}
+
+/// A one-file patch of `lines` added lines, big enough to cross the embed budget.
+fn huge_patch(path: &str, lines: usize) -> String {
+    let mut patch = format!(
+        "diff --git a/{0} b/{0}\n--- a/{0}\n+++ b/{0}\n@@ -0,0 +1,{1} @@\n",
+        path, lines
+    );
+    for i in 0..lines {
+        patch.push_str(&format!("+key_{:08} = \"{}\"\n", i, "x".repeat(40)));
+    }
+    patch
+}
+
+#[test]
+fn apply_md_truncates_diffs_past_the_embed_budget() {
+    let mut apply = make_apply();
+    let mut r = result("big", ApplyStatus::Applied, None);
+    r.files = vec![
+        changed_file("Cargo.toml"),
+        changed_file("crates/a/Cargo.toml"),
+    ];
+    apply.results = vec![r];
+    let first = huge_patch("Cargo.toml", 20_000);
+    let second = huge_patch("crates/a/Cargo.toml", 10);
+    assert!(first.len() > DEFAULT_EMBEDDED_DIFF_MAX_BYTES);
+    let patch = format!("{first}{second}");
+
+    let md = render_apply_md(&apply, &patch);
+    assert!(md.len() < DEFAULT_EMBEDDED_DIFF_MAX_BYTES + 4096);
+    assert!(md.contains(&format!(
+        "of {} bytes shown. See patch.diff for the full patch._",
+        first.len()
+    )));
+    // The budget is spent, so the second file's diff is cut to nothing.
+    assert!(md.contains(&format!(
+        "diff truncated: 0 of {} bytes shown.",
+        second.len()
+    )));
+    // The cut lands on a line boundary: the last line shown is complete.
+    assert!(md.contains(&format!("\"{}\"\n```\n", "x".repeat(40))));
+}
+
 #[test]
 fn apply_md_message_display() {
     let mut apply = make_apply();
@@ -288,9 +486,12 @@ fn apply_md_message_display() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("- Message: Successfully applied"));
 }
 
@@ -304,12 +505,41 @@ fn apply_md_no_message_not_displayed() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(!md.contains("- Message:"));
 }
 
+#[test]
+fn apply_md_flags_retried_and_unverified_ops() {
+    let mut apply = make_apply();
+    for (op_id, attempts, verified) in [("steady", 1, true), ("flaky", 3, false)] {
+        apply.results.push(ApplyResult {
+            op_id: op_id.to_string(),
+            status: ApplyStatus::Applied,
+            message: None,
+            blocked_reason: None,
+            blocked_reason_token: None,
+            files: vec![],
+            duration_ms: Some(40),
+            attempts: Some(attempts),
+            verified: Some(verified),
+        });
+    }
+
+    let md = render_apply_md(&apply, "");
+    let (steady, flaky) = md.split_once("### 2. flaky").expect("two results");
+    assert!(!steady.contains("Write attempts"));
+    assert!(!steady.contains("did not read back"));
+    assert!(flaky.contains("- Write attempts: 3\n"));
+    assert!(flaky.contains("- **Files did not read back with the contents written**\n"));
+    assert!(!md.contains("40"), "timings stay out of apply.md");
+}
+
 #[test]
 fn apply_md_file_change_format() {
     let mut apply = make_apply();
@@ -325,9 +555,12 @@ fn apply_md_file_change_format() {
             sha256_after: Some("def456".to_string()),
             backup_path: None,
         }],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("**Files changed**"));
     assert!(md.contains("- `Cargo.toml` abc123 → def456"));
 }
@@ -377,7 +610,7 @@ fn comment_md_artifact_links_format() {
 #[test]
 fn comment_md_blocked_reasons_limited_to_five() {
     let ops: Vec<PlanOp> = (0..10)
-        .map(|i| make_op(SafetyClass::Safe, true, Some(&format!("reason{}", i))))
+        .map(|i| make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::ALL[i])))
         .collect();
 
     let mut plan = make_plan(
@@ -436,9 +669,12 @@ fn apply_status_applied_label() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("Status: `applied`"));
 }
 
@@ -452,9 +688,12 @@ fn apply_status_blocked_label() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("Status: `blocked`"));
 }
 
@@ -468,9 +707,12 @@ fn apply_status_failed_label() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("Status: `failed`"));
 }
 
@@ -484,9 +726,12 @@ fn apply_status_skipped_label() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("Status: `skipped`"));
 }
 
@@ -500,6 +745,7 @@ fn op_kind_toml_set() {
     op.kind = OpKind::TomlSet {
         toml_path: vec!["package".into(), "version".into()],
         value: serde_json::json!("1.0.0"),
+        insert: None,
     };
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
@@ -607,7 +853,7 @@ fn empty_plan_renders_correctly() {
 #[test]
 fn empty_apply_renders_correctly() {
     let apply = make_apply();
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
 
     assert!(md.contains("# buildfix apply"));
     assert!(md.contains("_No results._"));
@@ -641,7 +887,7 @@ fn apply_with_zero_values() {
         files_modified: 0,
     };
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("- Attempted: 0"));
     assert!(md.contains("- Applied: 0"));
     assert!(md.contains("- Blocked: 0"));
@@ -680,6 +926,7 @@ fn special_characters_in_path() {
     let mut op = make_op(SafetyClass::Safe, false, None);
     op.target = OpTarget {
         path: "crates/sub-crate/Cargo.toml".into(),
+        sha256: None,
     };
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
@@ -728,6 +975,7 @@ fn multiple_findings_in_single_op() {
             path: Some(format!("file{}.rs", i)),
             line: Some(i * 10),
             fingerprint: None,
+            severity: None,
         })
         .collect();
 
@@ -745,8 +993,12 @@ fn mixed_safety_classes_in_plan() {
     let ops = vec![
         make_op(SafetyClass::Safe, false, None),
         make_op(SafetyClass::Guarded, false, None),
-        make_op(SafetyClass::Safe, true, Some("blocked1")),
-        make_op(SafetyClass::Unsafe, true, Some("blocked2")),
+        make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::Denylist)),
+        make_op(
+            SafetyClass::Unsafe,
+            true,
+            Some(BuildfixErrorCode::MissingParams),
+        ),
     ];
 
     let plan = make_plan(
@@ -797,10 +1049,13 @@ fn apply_with_mixed_results() {
             },
             blocked_reason_token: None,
             files: vec![],
+            duration_ms: None,
+            attempts: None,
+            verified: None,
         });
     }
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("Status: `applied`"));
     assert!(md.contains("Status: `blocked`"));
     assert!(md.contains("Status: `failed`"));
@@ -831,7 +1086,11 @@ fn comment_md_no_fixes_needed_message() {
 #[test]
 fn comment_md_all_ops_blocked_message() {
     let mut plan = make_plan(
-        vec![make_op(SafetyClass::Safe, true, Some("deny"))],
+        vec![make_op(
+            SafetyClass::Safe,
+            true,
+            Some(BuildfixErrorCode::Denylist),
+        )],
         Some(SafetyCounts {
             safe: 1,
             guarded: 0,
@@ -874,9 +1133,12 @@ fn file_change_with_missing_sha256_before() {
             sha256_after: Some("after-hash".to_string()),
             backup_path: None,
         }],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("- `file.toml` - → after-hash"));
 }
 
@@ -895,9 +1157,12 @@ fn file_change_with_missing_sha256_after() {
             sha256_after: None,
             backup_path: None,
         }],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
     assert!(md.contains("- `file.toml` before-hash → -"));
 }
 
@@ -945,9 +1210,12 @@ fn apply_md_structure_order() {
         blocked_reason: None,
         blocked_reason_token: None,
         files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
     });
 
-    let md = render_apply_md(&apply);
+    let md = render_apply_md(&apply, "");
 
     let header_pos = md.find("# buildfix apply").unwrap();
     let summary_pos = md.find("- Attempted:").unwrap();
@@ -982,6 +1250,7 @@ fn unicode_in_path() {
     let mut op = make_op(SafetyClass::Safe, false, None);
     op.target = OpTarget {
         path: "crates/日本語/Cargo.toml".into(),
+        sha256: None,
     };
     let plan = make_plan(vec![op], None);
     let md = render_plan_md(&plan);
@@ -996,3 +1265,860 @@ fn newline_in_description_preserved() {
     let md = render_plan_md(&plan);
     assert!(md.contains("Line 1\nLine 2"));
 }
+
+// =============================================================================
+// render_plan_html
+// =============================================================================
+
+const TWO_FILE_PATCH: &str = "diff --git a/Cargo.toml b/Cargo.toml\n\
+--- a/Cargo.toml\n\
++++ b/Cargo.toml\n\
+@@ -1,2 +1,2 @@\n\
+ [workspace]\n\
+-resolver = \"1\"\n\
++resolver = \"2\"\n\
+diff --git a/crates/a/Cargo.toml b/crates/a/Cargo.toml\n\
+--- a/crates/a/Cargo.toml\n\
++++ b/crates/a/Cargo.toml\n\
+@@ -1 +1 @@\n\
+-edition = \"2018\"\n\
++edition = \"2021\"\n";
+
+#[test]
+fn plan_html_is_standalone_document() {
+    let html = render_plan_html(&make_plan(vec![], None), "");
+    assert!(html.starts_with("<!DOCTYPE html>"));
+    assert!(html.contains("<style>"));
+    assert!(html.contains("<script>"));
+    assert!(!html.contains("<link"));
+    assert!(!html.contains("src=\""));
+    assert!(html.contains("No ops planned."));
+    assert!(html.trim_end().ends_with("</html>"));
+}
+
+#[test]
+fn plan_html_shows_each_ops_own_diff() {
+    let mut root = make_op(SafetyClass::Safe, false, None);
+    root.id = "op-root".into();
+    let mut member = make_op(SafetyClass::Guarded, false, None);
+    member.id = "op-member".into();
+    member.target.path = "crates/a/Cargo.toml".into();
+    let plan = make_plan(vec![root, member], None);
+
+    let html = render_plan_html(&plan, TWO_FILE_PATCH);
+    let root_section = &html[html.find("id=\"op-1\"").unwrap()..html.find("id=\"op-2\"").unwrap()];
+    assert!(root_section.contains("<span class=\"add\">+resolver = &quot;2&quot;</span>"));
+    assert!(!root_section.contains("edition"));
+    let member_section = &html[html.find("id=\"op-2\"").unwrap()..];
+    assert!(member_section.contains("<span class=\"del\">-edition = &quot;2018&quot;</span>"));
+    assert!(member_section.contains("badge guarded"));
+}
+
+#[test]
+fn plan_html_bounds_repeated_diffs() {
+    // Every op on the same file would embed the whole section again.
+    let ops = (0..20)
+        .map(|i| {
+            let mut op = make_op(SafetyClass::Safe, false, None);
+            op.id = format!("op-{i}");
+            op
+        })
+        .collect();
+    let patch = huge_patch("Cargo.toml", 2_000);
+    let html = render_plan_html(&make_plan(ops, None), &patch);
+    assert!(html.len() < 2 * DEFAULT_EMBEDDED_DIFF_MAX_BYTES);
+    assert!(html.contains("<span class=\"meta\">… diff truncated: "));
+}
+
+#[test]
+fn plan_html_prefers_preview_fragment() {
+    let mut op = make_op(SafetyClass::Safe, false, None);
+    op.preview = Some(OpPreview {
+        patch_fragment: "@@ -1 +1 @@\n-a\n+b\n".into(),
+    });
+    let html = render_plan_html(&make_plan(vec![op], None), TWO_FILE_PATCH);
+    assert!(html.contains("<span class=\"add\">+b</span>"));
+    assert!(!html.contains("resolver = &quot;2&quot;"));
+}
+
+#[test]
+fn plan_html_breaks_down_blocked_reasons() {
+    let ops = vec![
+        make_op(
+            SafetyClass::Unsafe,
+            true,
+            Some(BuildfixErrorCode::MissingParams),
+        ),
+        make_op(
+            SafetyClass::Unsafe,
+            true,
+            Some(BuildfixErrorCode::MissingParams),
+        ),
+        make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::Denylist)),
+        make_op(SafetyClass::Safe, false, None),
+    ];
+    let html = render_plan_html(&make_plan(ops, None), "");
+    assert!(html.contains("<h2>Blocked reasons</h2>"));
+    assert!(html.contains("<tr><td><code>denylist</code></td><td>1</td>"));
+    assert!(html.contains("<tr><td><code>missing_params</code></td><td>2</td>"));
+    assert!(html.contains("No diff for this op."));
+}
+
+#[test]
+fn plan_html_ops_table_is_sortable() {
+    let html = render_plan_html(
+        &make_plan(vec![make_op(SafetyClass::Safe, false, None)], None),
+        "",
+    );
+    assert!(html.contains("<table class=\"sortable\" id=\"ops\">"));
+    assert!(html.contains("<th data-sort=\"num\">Safety</th>"));
+    assert!(html.contains("<a href=\"#op-1\">1</a>"));
+    assert!(html.contains("<span class=\"badge ready\">ready</span>"));
+    assert!(!html.contains("Blocked reasons"));
+}
+
+#[test]
+fn plan_html_escapes_user_content() {
+    let mut op = make_op(SafetyClass::Safe, true, Some(BuildfixErrorCode::Denylist));
+    op.rationale.description = Some("<script>alert(1)</script>".into());
+    op.blocked_reason = Some("a & b".into());
+    let html = render_plan_html(&make_plan(vec![op], None), "");
+    assert!(html.contains("&lt;script&gt;alert(1)&lt;/script&gt;"));
+    assert!(html.contains("<code>denylist</code>"));
+    assert!(html.contains("a &amp; b"));
+    assert_eq!(html.matches("<script>").count(), 1);
+}
+
+// =============================================================================
+// render_gitlab_code_quality
+// =============================================================================
+
+#[test]
+fn gitlab_code_quality_empty_plan_is_empty_array() {
+    let json = render_gitlab_code_quality(&make_plan(vec![], None));
+    assert_eq!(json.trim(), "[]");
+}
+
+#[test]
+fn gitlab_code_quality_issue_per_op() {
+    let mut safe = make_op(SafetyClass::Safe, false, None);
+    safe.id = "op-safe".into();
+    safe.rationale.fix_key = "cargo.workspace_resolver_v2".into();
+    safe.rationale.description = Some("Set workspace resolver to 2".into());
+    safe.rationale.findings = vec![
+        FindingRef {
+            source: "builddiag".into(),
+            check_id: Some("workspace.resolver_v2".into()),
+            code: "not_v2".into(),
+            path: Some("other/Cargo.toml".into()),
+            line: Some(9),
+            fingerprint: None,
+            severity: None,
+        },
+        FindingRef {
+            source: "builddiag".into(),
+            check_id: Some("workspace.resolver_v2".into()),
+            code: "not_v2".into(),
+            path: Some("Cargo.toml".into()),
+            line: Some(3),
+            fingerprint: None,
+            severity: None,
+        },
+    ];
+    let mut guarded = make_op(SafetyClass::Guarded, false, None);
+    guarded.id = "op-guarded".into();
+    let mut blocked = make_op(
+        SafetyClass::Unsafe,
+        true,
+        Some(BuildfixErrorCode::MissingParams),
+    );
+    blocked.id = "op-blocked".into();
+    let unsafe_op = make_op(SafetyClass::Unsafe, false, None);
+
+    let json =
+        render_gitlab_code_quality(&make_plan(vec![safe, guarded, blocked, unsafe_op], None));
+    let issues: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let issues = issues.as_array().unwrap();
+    assert_eq!(issues.len(), 4);
+
+    assert_eq!(
+        issues[0],
+        serde_json::json!({
+            "description": "Set workspace resolver to 2",
+            "check_name": "cargo.workspace_resolver_v2",
+            "fingerprint": "op-safe",
+            "severity": "minor",
+            "location": { "path": "Cargo.toml", "lines": { "begin": 3 } }
+        })
+    );
+    assert_eq!(issues[1]["severity"], "major");
+    assert_eq!(issues[1]["location"]["lines"]["begin"], 1);
+    assert_eq!(issues[1]["description"], "test can be fixed by buildfix");
+    assert_eq!(issues[2]["severity"], "info");
+    assert_eq!(
+        issues[2]["description"],
+        "test can be fixed by buildfix (blocked: blocked)"
+    );
+    assert_eq!(issues[3]["severity"], "critical");
+}
+
+// =============================================================================
+// render_plan_checkstyle / render_plan_teamcity
+// =============================================================================
+
+#[test]
+fn checkstyle_groups_errors_by_file() {
+    let mut guarded = make_op(SafetyClass::Guarded, false, None);
+    guarded.rationale.fix_key = "cargo.path_dep_version".into();
+    guarded.rationale.description = Some("Add \"version\" to <path> dep".into());
+    let mut other = make_op(SafetyClass::Unsafe, false, None);
+    other.target.path = "crates/a/Cargo.toml".into();
+    let blocked = make_op(
+        SafetyClass::Safe,
+        true,
+        Some(BuildfixErrorCode::MissingParams),
+    );
+
+    let xml = render_plan_checkstyle(&make_plan(vec![guarded, other, blocked], None));
+    assert!(
+        xml.starts_with(
+            "<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n<checkstyle version=\"4.3\">\n"
+        )
+    );
+    assert!(xml.ends_with("</checkstyle>\n"));
+    assert_eq!(xml.matches("<file ").count(), 2);
+    // Files are sorted by path.
+    let nested = xml.find("<file name=\"crates/a/Cargo.toml\">").unwrap();
+    let root = xml.find("<file name=\"Cargo.toml\">").unwrap();
+    assert!(root < nested);
+    assert!(xml.contains(
+        "<error line=\"1\" severity=\"warning\" message=\"Add &quot;version&quot; to &lt;path&gt; dep\" source=\"buildfix.cargo.path_dep_version\"/>"
+    ));
+    assert!(xml.contains("severity=\"error\""));
+    assert!(xml.contains(
+        "severity=\"info\" message=\"test can be fixed by buildfix (blocked: blocked)\""
+    ));
+}
+
+#[test]
+fn checkstyle_empty_plan_has_no_files() {
+    let xml = render_plan_checkstyle(&make_plan(vec![], None));
+    assert!(!xml.contains("<file"));
+    assert!(xml.contains("<checkstyle version=\"4.3\">"));
+}
+
+#[test]
+fn teamcity_emits_inspections_and_statistics() {
+    let mut safe = keyed_op("a", "cargo.workspace_resolver_v2", false);
+    safe.rationale.description = Some("Set [workspace] resolver to '2'".into());
+    let unsafe_op = {
+        let mut op = make_op(SafetyClass::Unsafe, false, None);
+        op.rationale.fix_key = "cargo.msrv".into();
+        op
+    };
+    let blocked = keyed_op("c", "cargo.workspace_resolver_v2", true);
+    let mut plan = make_plan(vec![safe, unsafe_op, blocked], None);
+    plan.summary.ops_total = 3;
+    plan.summary.ops_blocked = 1;
+    plan.summary.files_touched = 1;
+
+    let out = render_plan_teamcity(&plan);
+    let lines: Vec<&str> = out.lines().collect();
+    assert_eq!(lines.len(), 2 + 3 + 3);
+    assert_eq!(
+        lines[0],
+        "##teamcity[inspectionType id='cargo.msrv' name='cargo.msrv' description='buildfix fix cargo.msrv' category='buildfix']"
+    );
+    assert!(lines[1].contains("id='cargo.workspace_resolver_v2'"));
+    assert_eq!(
+        lines[2],
+        "##teamcity[inspection typeId='cargo.workspace_resolver_v2' message='Set |[workspace|] resolver to |'2|'' file='Cargo.toml' line='1' SEVERITY='WEAK WARNING']"
+    );
+    assert!(lines[3].ends_with("SEVERITY='ERROR']"));
+    assert!(lines[4].ends_with("SEVERITY='INFO']"));
+    assert_eq!(
+        lines[5],
+        "##teamcity[buildStatisticValue key='buildfix.ops_total' value='3']"
+    );
+    assert_eq!(
+        lines[6],
+        "##teamcity[buildStatisticValue key='buildfix.ops_blocked' value='1']"
+    );
+}
+
+// =============================================================================
+// render_plan_csv / render_plan_tsv
+// =============================================================================
+
+fn export_plan() -> BuildfixPlan {
+    let mut ready = keyed_op("op-1", "cargo.workspace_resolver_v2", false);
+    ready.rationale.findings = vec![
+        FindingRef {
+            source: "builddiag".into(),
+            check_id: None,
+            code: "not_v2".into(),
+            path: None,
+            line: None,
+            fingerprint: Some("fp-a".into()),
+            severity: None,
+        },
+        FindingRef {
+            source: "builddiag".into(),
+            check_id: None,
+            code: "not_v2".into(),
+            path: None,
+            line: None,
+            fingerprint: Some("fp-b".into()),
+            severity: None,
+        },
+    ];
+    let mut blocked = make_op(SafetyClass::Guarded, true, None);
+    blocked.id = "op-2".into();
+    blocked.blocked_reason = Some("needs \"version\", got\ttab".into());
+    make_plan(vec![ready, blocked], None)
+}
+
+#[test]
+fn csv_export_one_row_per_op() {
+    let csv = render_plan_csv(&export_plan());
+    let lines: Vec<&str> = csv.lines().collect();
+    assert_eq!(
+        lines,
+        vec![
+            "op_id,fix_key,file,safety,blocked_reason,finding_fingerprint",
+            "op-1,cargo.workspace_resolver_v2,Cargo.toml,safe,,fp-a fp-b",
+            "op-2,test,Cargo.toml,guarded,\"needs \"\"version\"\", got\ttab\",",
+        ]
+    );
+}
+
+#[test]
+fn tsv_export_flattens_tabs() {
+    let tsv = render_plan_tsv(&export_plan());
+    let lines: Vec<&str> = tsv.lines().collect();
+    assert_eq!(
+        lines[0],
+        "op_id\tfix_key\tfile\tsafety\tblocked_reason\tfinding_fingerprint"
+    );
+    assert_eq!(
+        lines[2],
+        "op-2\ttest\tCargo.toml\tguarded\tneeds \"version\", got tab\t"
+    );
+}
+
+#[test]
+fn csv_export_empty_plan_is_header_only() {
+    assert_eq!(
+        render_plan_csv(&make_plan(vec![], None)),
+        "op_id,fix_key,file,safety,blocked_reason,finding_fingerprint\n"
+    );
+}
+
+// =============================================================================
+// JUnit rendering
+// =============================================================================
+
+fn keyed_op(id: &str, fix_key: &str, blocked: bool) -> PlanOp {
+    let mut op = make_op(SafetyClass::Safe, blocked, None);
+    op.id = id.into();
+    op.rationale.fix_key = fix_key.into();
+    op
+}
+
+fn result(op_id: &str, status: ApplyStatus, message: Option<&str>) -> ApplyResult {
+    ApplyResult {
+        op_id: op_id.to_string(),
+        status,
+        message: message.map(str::to_string),
+        blocked_reason: None,
+        blocked_reason_token: None,
+        files: vec![],
+        duration_ms: None,
+        attempts: None,
+        verified: None,
+    }
+}
+
+#[test]
+fn plan_junit_groups_ops_by_fixer() {
+    let plan = make_plan(
+        vec![
+            keyed_op("op-1", "cargo.workspace_resolver_v2", false),
+            keyed_op("op-2", "cargo.edition", true),
+            keyed_op("op-3", "cargo.edition", false),
+        ],
+        None,
+    );
+    let xml = render_plan_junit(&plan);
+
+    assert!(xml.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n"));
+    assert!(xml.contains(
+        "<testsuites name=\"buildfix plan\" tests=\"3\" failures=\"0\" errors=\"0\" skipped=\"1\">"
+    ));
+    // Suites are sorted by fix key.
+    let edition = xml
+        .find("<testsuite name=\"cargo.edition\" tests=\"2\" failures=\"0\" errors=\"0\" skipped=\"1\">")
+        .unwrap();
+    let resolver = xml
+        .find("<testsuite name=\"cargo.workspace_resolver_v2\" tests=\"1\"")
+        .unwrap();
+    assert!(edition < resolver);
+    assert!(xml.contains(
+        "<testcase name=\"Cargo.toml [op-2]\" classname=\"cargo.edition\">\n      <skipped message=\"blocked\"/>"
+    ));
+    assert!(xml.contains("<testcase name=\"Cargo.toml [op-3]\" classname=\"cargo.edition\"/>"));
+    assert!(xml.trim_end().ends_with("</testsuites>"));
+}
+
+#[test]
+fn plan_junit_empty_plan() {
+    let xml = render_plan_junit(&make_plan(vec![], None));
+    assert!(xml.contains("tests=\"0\" failures=\"0\" errors=\"0\" skipped=\"0\">"));
+    assert!(!xml.contains("<testsuite "));
+}
+
+#[test]
+fn apply_junit_maps_statuses() {
+    let plan = make_plan(
+        vec![
+            keyed_op("applied", "fix.a", false),
+            keyed_op("blocked", "fix.a", false),
+            keyed_op("failed", "fix.b", false),
+            keyed_op("missing", "fix.b", false),
+        ],
+        None,
+    );
+    let mut apply = make_apply();
+    let mut applied = result("applied", ApplyStatus::Applied, None);
+    applied.files.push(ApplyFile {
+        path: "Cargo.toml".to_string(),
+        sha256_before: None,
+        sha256_after: None,
+        backup_path: None,
+    });
+    apply.results.push(applied);
+    let mut blocked = result("blocked", ApplyStatus::Blocked, None);
+    blocked.blocked_reason = Some("needs <param>".into());
+    apply.results.push(blocked);
+    apply
+        .results
+        .push(result("failed", ApplyStatus::Failed, Some("write error")));
+    apply
+        .results
+        .push(result("stray", ApplyStatus::Applied, None));
+
+    let xml = render_apply_junit(&apply, &plan);
+
+    assert!(xml.contains(
+        "<testsuites name=\"buildfix apply\" tests=\"5\" failures=\"1\" errors=\"0\" skipped=\"2\">"
+    ));
+    assert!(xml.contains("<system-out>Cargo.toml</system-out>"));
+    assert!(xml.contains("<skipped message=\"needs &lt;param&gt;\"/>"));
+    assert!(xml.contains("<failure message=\"write error\">write error</failure>"));
+    assert!(xml.contains("<skipped message=\"not attempted\"/>"));
+    assert!(xml.contains("<testcase name=\"stray\" classname=\"buildfix\"/>"));
+}
+
+// =============================================================================
+// Terminal rendering
+// =============================================================================
+
+#[test]
+fn plan_pretty_aligns_op_table() {
+    let mut long = keyed_op("op-1", "cargo.workspace_resolver_v2", false);
+    long.target.path = "crates/some-member/Cargo.toml".into();
+    let mut blocked = keyed_op("op-2", "cargo.edition", true);
+    blocked.blocked_reason_token = Some(BuildfixErrorCode::MissingParams);
+    let plan = make_plan(
+        vec![long, blocked],
+        Some(SafetyCounts {
+            safe: 2,
+            guarded: 0,
+            unsafe_count: 0,
+        }),
+    );
+
+    let out = render_plan_pretty(&plan, "", false);
+    assert!(
+        out.starts_with(
+            "buildfix plan: 2 ops (1 blocked), 1 files\n  2 safe, 0 guarded, 0 unsafe\n"
+        )
+    );
+    let lines: Vec<&str> = out.lines().collect();
+    let header = lines
+        .iter()
+        .position(|l| l.trim_start().starts_with("# "))
+        .unwrap();
+    let fix_col = lines[header].find("FIX").unwrap();
+    assert_eq!(
+        lines[header + 1].find("cargo.workspace_resolver_v2"),
+        Some(fix_col)
+    );
+    assert_eq!(lines[header + 2].find("cargo.edition"), Some(fix_col));
+    assert!(lines[header + 2].contains("blocked"));
+    assert!(lines[header + 2].ends_with("(missing_params)"));
+    assert!(out.contains("   blocked: blocked\n"));
+    assert!(!out.contains('\x1b'));
+}
+
+#[test]
+fn plan_pretty_colors_and_mini_diffs() {
+    let mut changes = String::from(
+        "diff --git a/Cargo.toml b/Cargo.toml\n--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1,12 +1,12 @@\n",
+    );
+    for i in 0..6 {
+        changes.push_str(&format!("-old{}\n+new{}\n", i, i));
+    }
+    let plan = make_plan(vec![make_op(SafetyClass::Unsafe, false, None)], None);
+
+    let out = render_plan_pretty(&plan, &changes, true);
+    assert!(out.contains("\x1b[31munsafe\x1b[0m"));
+    assert!(out.contains("\x1b[32m+new0\x1b[0m"));
+    assert!(out.contains("\x1b[32m+new3\x1b[0m"));
+    assert!(!out.contains("old4"));
+    assert!(out.contains("... 4 more changed lines"));
+    assert!(!out.contains("+++ b/Cargo.toml"));
+}
+
+#[test]
+fn plan_pretty_empty_plan() {
+    let out = render_plan_pretty(&make_plan(vec![], None), "", false);
+    assert!(out.ends_with("\nNo ops planned.\n"));
+}
+
+#[test]
+fn apply_pretty_lists_results() {
+    let mut apply = make_apply();
+    apply.summary.attempted = 2;
+    apply.summary.applied = 1;
+    apply.summary.failed = 1;
+    apply
+        .results
+        .push(result("op-ok", ApplyStatus::Applied, None));
+    apply
+        .results
+        .push(result("op-bad", ApplyStatus::Failed, Some("write error")));
+
+    let patch = "diff --git a/Cargo.toml b/Cargo.toml\n--- a/Cargo.toml\n+++ b/Cargo.toml\n@@ -1 +1 @@\n-a\n+b\n";
+    let out = render_apply_pretty(&apply, patch, false);
+    assert!(out.starts_with(
+        "buildfix apply: 1 applied, 0 blocked, 1 failed (2 attempted), 0 files modified\n"
+    ));
+    assert!(out.contains("  applied  op-ok\n"));
+    assert!(out.contains("  failed   op-bad  (write error)\n"));
+    assert!(out.contains("\nCargo.toml\n   @@ -1 +1 @@\n   -a\n   +b\n"));
+}
+
+// =============================================================================
+// Markdown templates
+// =============================================================================
+
+#[test]
+fn templates_default_to_builtin_markdown() {
+    let plan = make_plan(vec![make_op(SafetyClass::Safe, false, None)], None);
+    let templates = MarkdownTemplates::default();
+    assert!(templates.is_builtin());
+    assert_eq!(
+        templates.render_plan_md(&plan).unwrap(),
+        render_plan_md(&plan)
+    );
+    assert_eq!(
+        templates.render_comment_md(&plan).unwrap(),
+        render_comment_md(&plan)
+    );
+    assert_eq!(
+        templates.render_apply_md(&make_apply(), &plan, "").unwrap(),
+        render_apply_md(&make_apply(), "")
+    );
+}
+
+#[test]
+fn templates_override_markdown() {
+    let plan = make_plan(
+        vec![
+            keyed_op("op-1", "cargo.workspace_resolver_v2", false),
+            keyed_op("op-2", "cargo.edition", true),
+        ],
+        None,
+    );
+    let templates = MarkdownTemplates {
+        plan: Some(
+            "## Fixes ({{ plan.summary.ops_total }})\n{% for op in plan.ops %}- {{ op.rationale.fix_key }}{% if op.blocked %} (blocked){% endif %}\n{% endfor %}"
+                .into(),
+        ),
+        comment: Some("<!-- house style -->\n{{ default }}".into()),
+        apply: Some("{{ apply.summary.applied }}/{{ plan.summary.ops_total }} applied\n".into()),
+        ..Default::default()
+    };
+
+    assert_eq!(
+        templates.render_plan_md(&plan).unwrap(),
+        "## Fixes (2)\n- cargo.workspace_resolver_v2\n- cargo.edition (blocked)\n"
+    );
+    assert_eq!(
+        templates.render_comment_md(&plan).unwrap(),
+        format!("<!-- house style -->\n{}", render_comment_md(&plan))
+    );
+    assert_eq!(
+        templates.render_apply_md(&make_apply(), &plan, "").unwrap(),
+        "0/2 applied\n"
+    );
+}
+
+#[test]
+fn messages_localize_builtin_markdown() {
+    let plan = make_plan(
+        vec![make_op(
+            SafetyClass::Safe,
+            true,
+            Some(BuildfixErrorCode::Denylist),
+        )],
+        None,
+    );
+    let templates = MarkdownTemplates {
+        messages: MessageCatalog::with_overrides([
+            ("md.plan.title", "buildfix-Plan"),
+            ("md.op.blocked_reason", "Blockiert weil"),
+            ("blocked.denylist", "durch Richtlinie abgelehnt"),
+            ("md.comment.all_blocked", "alle Ops blockiert"),
+            ("md.comment.ops_one", "{count} Op"),
+            ("md.apply.no_results", "Keine Ergebnisse."),
+        ]),
+        ..Default::default()
+    };
+    assert!(templates.is_builtin());
+
+    let md = templates.render_plan_md(&plan).unwrap();
+    assert!(md.starts_with("# buildfix-Plan\n"));
+    assert!(md.contains("- Blockiert weil: durch Richtlinie abgelehnt\n"));
+    // Untranslated codes keep their English text.
+    assert!(md.contains("## Ops\n"));
+
+    let comment = templates.render_comment_md(&plan).unwrap();
+    assert!(comment.contains("**buildfix**: alle Ops blockiert"));
+    assert!(comment.contains(": 1 Op (1 blocked)</summary>"));
+    // Machine tokens are never translated.
+    assert!(comment.contains("(blocked: `denylist`)"));
+
+    let apply = templates.render_apply_md(&make_apply(), &plan, "").unwrap();
+    assert!(apply.contains("_Keine Ergebnisse._"));
+
+    // The default catalog reproduces the builtin renderers exactly.
+    let english = MarkdownTemplates::default();
+    assert_eq!(
+        english.render_plan_md(&plan).unwrap(),
+        render_plan_md(&plan)
+    );
+    assert_eq!(
+        english.render_comment_md(&plan).unwrap(),
+        render_comment_md(&plan)
+    );
+}
+
+#[test]
+fn templates_report_errors() {
+    let plan = make_plan(vec![], None);
+    let syntax = MarkdownTemplates {
+        plan: Some("{% if %}".into()),
+        ..Default::default()
+    };
+    let err = syntax.render_plan_md(&plan).unwrap_err();
+    assert!(format!("{:#}", err).contains("plan.md"));
+
+    let undefined = MarkdownTemplates {
+        comment: Some("{{ plan.no_such_field }}".into()),
+        ..Default::default()
+    };
+    assert!(undefined.render_comment_md(&plan).is_err());
+}
+
+// =============================================================================
+// Comment sections and truncation
+// =============================================================================
+
+#[test]
+fn comment_md_starts_with_marker() {
+    let md = render_comment_md(&make_plan(vec![], None));
+    assert!(md.starts_with(&format!("{}\n", COMMENT_MARKER)));
+}
+
+#[test]
+fn comment_md_groups_ops_by_fixer() {
+    let mut remove = keyed_op("op-2", "cargo.unused_deps", true);
+    remove.kind = OpKind::TomlRemove {
+        toml_path: vec!["dependencies".into(), "serde".into()],
+    };
+    remove.target.path = "crates/a/Cargo.toml".into();
+    remove.blocked_reason_token = Some(BuildfixErrorCode::Denylist);
+    let plan = make_plan(
+        vec![
+            keyed_op("op-1", "cargo.workspace_resolver_v2", false),
+            remove,
+        ],
+        None,
+    );
+
+    let md = render_comment_md(&plan);
+    assert!(md.contains(
+        "<details>\n<summary><code>cargo.unused_deps</code>: 1 op (1 blocked)</summary>\n\n- `crates/a/Cargo.toml`: `-dependencies.serde` (blocked: `denylist`)\n\n</details>\n"
+    ));
+    assert!(md.contains(
+        "<summary><code>cargo.workspace_resolver_v2</code>: 1 op</summary>\n\n- `Cargo.toml`: `+workspace.resolver = \"2\"`\n"
+    ));
+    // Sections are ordered by fix key.
+    assert!(
+        md.find("cargo.unused_deps").unwrap() < md.find("cargo.workspace_resolver_v2").unwrap()
+    );
+    assert!(md.ends_with("Artifacts: [plan.md](plan.md) · [patch.diff](patch.diff)\n"));
+    assert!(!md.contains("truncated"));
+}
+
+#[test]
+fn comment_md_truncates_to_max_bytes() {
+    let ops: Vec<PlanOp> = (0..400)
+        .map(|i| {
+            let mut op = keyed_op(&format!("op-{}", i), &format!("fix.{:03}", i / 100), false);
+            op.target.path = format!("crates/member-{:03}/Cargo.toml", i);
+            op
+        })
+        .collect();
+    let plan = make_plan(ops, None);
+
+    let full = render_comment_md(&plan);
+    assert!(full.len() < DEFAULT_COMMENT_MAX_BYTES);
+    assert!(!full.contains("truncated"));
+
+    let options = CommentOptions { max_bytes: 8_000 };
+    let md = render_comment_md_with(&plan, &options);
+    assert!(md.len() <= options.max_bytes);
+    assert!(md.starts_with(COMMENT_MARKER));
+    assert!(md.contains("<code>fix.000</code>: 100 ops"));
+    assert!(md.contains("more_\n"));
+    assert!(!md.contains("fix.003"));
+    assert!(md.contains("ops not shown. See [plan.md](plan.md) for the full plan._"));
+    assert!(md.ends_with("[patch.diff](patch.diff)\n"));
+
+    // Every <details> that is opened gets closed.
+    assert_eq!(
+        md.matches("<details>").count(),
+        md.matches("</details>").count()
+    );
+}
+
+#[test]
+fn comment_md_one_line_diffs() {
+    let mut text = keyed_op("op-1", "text", false);
+    text.kind = OpKind::TextReplaceAnchored {
+        find: "edition = \"2018\"".into(),
+        replace: "edition = \"2021\"".into(),
+        anchor_before: vec![],
+        anchor_after: vec![],
+        max_replacements: None,
+    };
+    let mut append = keyed_op("op-2", "append", false);
+    append.kind = OpKind::TomlArrayAppend {
+        toml_path: vec!["workspace".into(), "members".into()],
+        values: vec![serde_json::json!("crates/`odd`")],
+        unique: true,
+    };
+
+    let md = render_comment_md(&make_plan(vec![text, append], None));
+    assert!(md.contains("`edition = \"2018\" → edition = \"2021\"`"));
+    assert!(md.contains("`` +workspace.members += [\"crates/`odd`\"] ``"));
+}
+
+#[test]
+fn comment_md_transform_lines_show_scalar_args() {
+    let mut op = keyed_op("op-1", "path_dep", false);
+    op.kind = OpKind::TomlTransform {
+        rule_id: "ensure_path_dep_has_version".into(),
+        args: Some(serde_json::json!({
+            "dep": "acme-core",
+            "toml_path": ["dependencies", "acme-core"],
+            "version": "0.3.0"
+        })),
+    };
+
+    let md = render_comment_md(&make_plan(vec![op], None));
+    assert!(md.contains("`ensure_path_dep_has_version dep=acme-core version=0.3.0`"));
+}
+
+// =============================================================================
+// Plan diff
+// =============================================================================
+
+#[test]
+fn plan_diff_classifies_ops_by_id() {
+    let kept = keyed_op("11111111-aaaa", "cargo.workspace_resolver_v2", false);
+    let mut now_blocked = keyed_op("22222222-bbbb", "cargo.edition", false);
+    let removed = keyed_op("33333333-cccc", "cargo.msrv", false);
+    let old = make_plan(vec![kept.clone(), now_blocked.clone(), removed], None);
+
+    now_blocked.blocked = true;
+    now_blocked.blocked_reason_token = Some(BuildfixErrorCode::Denylist);
+    let mut added = keyed_op("44444444-dddd", "cargo.license", false);
+    added.safety = SafetyClass::Guarded;
+    let new = make_plan(vec![added, now_blocked, kept], None);
+
+    let diff = diff_plans(&old, &new);
+    assert_eq!(diff.unchanged, 1);
+    assert_eq!(diff.added.len(), 1);
+    assert_eq!(diff.added[0].id, "44444444-dddd");
+    assert_eq!(diff.added[0].safety, "guarded");
+    assert_eq!(diff.removed.len(), 1);
+    assert_eq!(diff.removed[0].fix_key, "cargo.msrv");
+    assert_eq!(diff.changed.len(), 1);
+    let fields: Vec<&str> = diff.changed[0].changes.iter().map(|c| c.field).collect();
+    assert_eq!(fields, vec!["blocked", "blocked_reason"]);
+
+    let md = render_plan_diff(&old, &new);
+    assert!(md.contains("- Added: 1\n- Removed: 1\n- Changed: 1\n- Unchanged: 1\n"));
+    assert!(md.contains(
+        "| `44444444` | `cargo.license` | `Cargo.toml` | `toml_set` | guarded | ready |"
+    ));
+    assert!(md.contains("## Removed\n"));
+    assert!(md.contains("| `22222222` | `cargo.edition` | `Cargo.toml` | blocked: false → true; blocked_reason: - → denylist |"));
+
+    let json: serde_json::Value = serde_json::from_str(&render_plan_diff_json(&old, &new)).unwrap();
+    assert_eq!(json["unchanged"], 1);
+    assert_eq!(json["changed"][0]["changes"][0]["old"], false);
+    assert_eq!(json["changed"][0]["changes"][1]["new"], "denylist");
+}
+
+#[test]
+fn plan_diff_reports_replaced_findings_by_fingerprint() {
+    let finding = |line: u64| FindingRef {
+        source: "builddiag".into(),
+        check_id: Some("workspace.resolver_v2".into()),
+        code: "not_v2".into(),
+        path: Some("Cargo.toml".into()),
+        line: Some(line),
+        fingerprint: None,
+        severity: None,
+    };
+    let mut before = keyed_op("op-1", "fix", false);
+    before.rationale.findings = vec![finding(1)];
+    let mut after = before.clone();
+    after.rationale.findings = vec![finding(2)];
+
+    let diff = diff_plans(
+        &make_plan(vec![before.clone()], None),
+        &make_plan(vec![after], None),
+    );
+    assert_eq!(diff.changed.len(), 1);
+    let change = &diff.changed[0].changes[0];
+    assert_eq!(change.field, "finding_fingerprints");
+    let short = |f: &FindingRef| serde_json::json!([f.stable_fingerprint()[..8].to_string()]);
+    assert_eq!(change.old, short(&finding(1)));
+    assert_eq!(change.new, short(&finding(2)));
+
+    let plan = make_plan(vec![before], None);
+    assert!(diff_plans(&plan, &plan).is_empty());
+}
+
+#[test]
+fn plan_diff_identical_plans() {
+    let plan = make_plan(vec![keyed_op("op-1", "fix", false)], None);
+    assert!(diff_plans(&plan, &plan).is_empty());
+    assert!(render_plan_diff(&plan, &plan).ends_with("- Unchanged: 1\n\nNo differences.\n"));
+}
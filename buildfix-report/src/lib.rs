@@ -1,15 +1,16 @@
 //! Reporting projections for buildfix outcomes.
 
-use chrono::Utc;
-use std::collections::BTreeSet;
+use chrono::{DateTime, Utc};
+use std::collections::{BTreeMap, BTreeSet};
 
 use buildfix_receipts::LoadedReceipt;
 use buildfix_types::apply::BuildfixApply;
-use buildfix_types::plan::BuildfixPlan;
+use buildfix_types::plan::{BuildfixPlan, ImpactSummary};
 use buildfix_types::receipt::ToolInfo;
 use buildfix_types::report::{
-    BuildfixReport, InputFailure, ReportArtifacts, ReportCapabilities, ReportCounts, ReportFinding,
-    ReportRunInfo, ReportSeverity, ReportStatus, ReportToolInfo, ReportVerdict,
+    BuildfixReport, InputFailure, InputSignature, ReportArtifacts, ReportCapabilities,
+    ReportCounts, ReportFinding, ReportRunInfo, ReportSeverity, ReportStatus, ReportToolInfo,
+    ReportVerdict,
 };
 
 pub fn build_report_capabilities(receipts: &[LoadedReceipt]) -> ReportCapabilities {
@@ -17,8 +18,16 @@ pub fn build_report_capabilities(receipts: &[LoadedReceipt]) -> ReportCapabiliti
     let mut inputs_failed = Vec::new();
     let mut check_ids = BTreeSet::new();
     let mut scopes = BTreeSet::new();
+    let mut input_signatures = Vec::new();
 
     for r in receipts {
+        if let Some(sig) = &r.signature {
+            input_signatures.push(InputSignature {
+                path: r.path.to_string(),
+                status: sig.as_str().to_string(),
+                reason: sig.reason().map(str::to_string),
+            });
+        }
         match &r.receipt {
             Ok(receipt) => {
                 inputs_available.push(r.path.to_string());
@@ -57,6 +66,7 @@ pub fn build_report_capabilities(receipts: &[LoadedReceipt]) -> ReportCapabiliti
         },
         inputs_available,
         inputs_failed,
+        input_signatures,
     }
 }
 
@@ -64,6 +74,16 @@ pub fn build_plan_report(
     plan: &BuildfixPlan,
     tool: ToolInfo,
     receipts: &[LoadedReceipt],
+) -> BuildfixReport {
+    build_plan_report_at(plan, tool, receipts, Utc::now())
+}
+
+/// [`build_plan_report`] with the run stamped at `now`.
+pub fn build_plan_report_at(
+    plan: &BuildfixPlan,
+    tool: ToolInfo,
+    receipts: &[LoadedReceipt],
+    now: DateTime<Utc>,
 ) -> BuildfixReport {
     let capabilities = build_report_capabilities(receipts);
     let has_failed_inputs = !capabilities.inputs_failed.is_empty();
@@ -79,7 +99,7 @@ pub fn build_plan_report(
         reasons.push("partial_inputs".to_string());
     }
 
-    let findings: Vec<ReportFinding> = capabilities
+    let mut findings: Vec<ReportFinding> = capabilities
         .inputs_failed
         .iter()
         .map(|failure| ReportFinding {
@@ -95,6 +115,7 @@ pub fn build_plan_report(
             data: None,
         })
         .collect();
+    findings.extend(plan.ops.iter().map(ReportFinding::from_plan_op));
 
     let warn_count = plan.ops.len() as u64 + capabilities.inputs_failed.len() as u64;
     let ops_applicable = plan
@@ -124,13 +145,17 @@ pub fn build_plan_report(
     let tokens: BTreeSet<&str> = plan
         .ops
         .iter()
-        .filter_map(|o| o.blocked_reason_token.as_deref())
+        .filter_map(|o| o.blocked_reason_token.map(|c| c.as_str()))
         .collect();
     let top: Vec<&str> = tokens.into_iter().take(5).collect();
     if !top.is_empty() {
         plan_data["blocked_reason_tokens_top"] = serde_json::json!(top);
     }
 
+    if let Some(impact) = ImpactSummary::from_ops(&plan.ops) {
+        plan_data["impact"] = serde_json::json!(impact);
+    }
+
     BuildfixReport {
         schema: buildfix_types::schema::SENSOR_REPORT_V1.to_string(),
         tool: ReportToolInfo {
@@ -139,8 +164,8 @@ pub fn build_plan_report(
             commit: tool.commit,
         },
         run: ReportRunInfo {
-            started_at: Utc::now().to_rfc3339(),
-            ended_at: Some(Utc::now().to_rfc3339()),
+            started_at: now.to_rfc3339(),
+            ended_at: Some(now.to_rfc3339()),
             duration_ms: Some(0),
             git_head_sha: plan.repo.head_sha.clone(),
         },
@@ -170,6 +195,15 @@ pub fn build_plan_report(
 }
 
 pub fn build_apply_report(apply: &BuildfixApply, tool: ToolInfo) -> BuildfixReport {
+    build_apply_report_at(apply, tool, Utc::now())
+}
+
+/// [`build_apply_report`] with the run stamped at `now`.
+pub fn build_apply_report_at(
+    apply: &BuildfixApply,
+    tool: ToolInfo,
+    now: DateTime<Utc>,
+) -> BuildfixReport {
     let status = if apply.summary.failed > 0 {
         ReportStatus::Fail
     } else if apply.summary.blocked > 0 {
@@ -189,6 +223,17 @@ pub fn build_apply_report(apply: &BuildfixApply, tool: ToolInfo) -> BuildfixRepo
         "apply_performed": apply.summary.applied > 0,
     });
 
+    let tokens: BTreeSet<&str> = apply
+        .results
+        .iter()
+        .filter_map(|r| r.blocked_reason_token.map(|c| c.as_str()))
+        .collect();
+    let top: Vec<&str> = tokens.into_iter().take(5).collect();
+    if !top.is_empty() {
+        apply_data["blocked_reason_tokens_top"] = serde_json::json!(top);
+    }
+    apply_data["blocked_tokens"] = serde_json::json!(blocked_token_counts(apply));
+
     if let Some(auto_commit) = &apply.auto_commit {
         apply_data["auto_commit"] = serde_json::json!({
             "enabled": auto_commit.enabled,
@@ -199,6 +244,15 @@ pub fn build_apply_report(apply: &BuildfixApply, tool: ToolInfo) -> BuildfixRepo
             "skip_reason": auto_commit.skip_reason,
         });
     }
+    if let Some(branch) = &apply.branch {
+        apply_data["branch"] = serde_json::json!({
+            "name": branch.name,
+            "created": branch.created,
+            "remote": branch.remote,
+            "pushed": branch.pushed,
+            "skip_reason": branch.skip_reason,
+        });
+    }
 
     BuildfixReport {
         schema: buildfix_types::schema::SENSOR_REPORT_V1.to_string(),
@@ -208,8 +262,8 @@ pub fn build_apply_report(apply: &BuildfixApply, tool: ToolInfo) -> BuildfixRepo
             commit: tool.commit,
         },
         run: ReportRunInfo {
-            started_at: Utc::now().to_rfc3339(),
-            ended_at: Some(Utc::now().to_rfc3339()),
+            started_at: now.to_rfc3339(),
+            ended_at: Some(now.to_rfc3339()),
             duration_ms: Some(0),
             git_head_sha: apply.repo.head_sha_after.clone(),
         },
@@ -238,14 +292,26 @@ pub fn build_apply_report(apply: &BuildfixApply, tool: ToolInfo) -> BuildfixRepo
     }
 }
 
+/// Results per `blocked_reason_token`, keyed by token.
+fn blocked_token_counts(apply: &BuildfixApply) -> BTreeMap<&'static str, u64> {
+    let mut counts = BTreeMap::new();
+    for token in apply.results.iter().filter_map(|r| r.blocked_reason_token) {
+        *counts.entry(token.as_str()).or_insert(0) += 1;
+    }
+    counts
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
     use buildfix_receipts::{LoadedReceipt, ReceiptLoadError};
+    use buildfix_types::error_code::BuildfixErrorCode;
     use buildfix_types::{
         apply::{ApplyRepoInfo, AutoCommitInfo, BuildfixApply, PlanRef},
         ops::{OpKind, OpTarget, SafetyClass},
-        plan::{BuildfixPlan, PlanOp, PlanPolicy, PlanSummary, Rationale, SafetyCounts},
+        plan::{
+            BuildfixPlan, FindingRef, PlanOp, PlanPolicy, PlanSummary, Rationale, SafetyCounts,
+        },
         receipt::{Finding, ReceiptCapabilities, ReceiptEnvelope, RunInfo, ToolInfo, Verdict},
     };
     use chrono::Utc;
@@ -265,6 +331,7 @@ mod tests {
             LoadedReceipt {
                 path: "artifacts/second/report.json".into(),
                 sensor_id: "second".to_string(),
+                signature: None,
                 receipt: Ok(ReceiptEnvelope {
                     schema: "sensor.report.v1".to_string(),
                     tool: fixture_tool(),
@@ -296,6 +363,7 @@ mod tests {
             LoadedReceipt {
                 path: "artifacts/first/report.json".into(),
                 sensor_id: "first".to_string(),
+                signature: None,
                 receipt: Ok(ReceiptEnvelope {
                     schema: "sensor.report.v1".to_string(),
                     tool: fixture_tool(),
@@ -325,6 +393,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "boom".to_string(),
                 }),
+                signature: None,
             },
         ];
 
@@ -372,6 +441,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "missing".to_string(),
                 }),
+                signature: None,
             }],
         );
 
@@ -392,10 +462,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
 
@@ -427,6 +501,8 @@ mod tests {
             root: ".".to_string(),
             head_sha: None,
             dirty: None,
+            remote_url: None,
+            ci: None,
         }
     }
 
@@ -450,6 +526,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "not found".to_string(),
                 }),
+                signature: None,
             },
             LoadedReceipt {
                 path: "artifacts/fail2/report.json".into(),
@@ -457,6 +534,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Json {
                     message: "invalid json".to_string(),
                 }),
+                signature: None,
             },
         ];
 
@@ -473,6 +551,7 @@ mod tests {
         let receipts = vec![LoadedReceipt {
             path: "artifacts/sensor/report.json".into(),
             sensor_id: "sensor".to_string(),
+            signature: None,
             receipt: Ok(ReceiptEnvelope {
                 schema: "sensor.report.v1".to_string(),
                 tool: fixture_tool(),
@@ -537,10 +616,12 @@ mod tests {
             blocked_reason_token: None,
             target: OpTarget {
                 path: "Cargo.toml".to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["workspace".to_string(), "members".to_string()],
                 value: serde_json::json!(["crate1"]),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "unused-dependency".to_string(),
@@ -549,6 +630,8 @@ mod tests {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         });
         plan.summary = PlanSummary {
             ops_total: 1,
@@ -581,13 +664,15 @@ mod tests {
             safety: SafetyClass::Unsafe,
             blocked: true,
             blocked_reason: Some("Missing parameters: version".to_string()),
-            blocked_reason_token: Some("missing_params".to_string()),
+            blocked_reason_token: Some(BuildfixErrorCode::MissingParams),
             target: OpTarget {
                 path: "Cargo.toml".to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["dependencies".to_string(), "foo".to_string()],
                 value: serde_json::json!({"version": "PARAM"}),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "add-dependency".to_string(),
@@ -596,6 +681,8 @@ mod tests {
             },
             params_required: vec!["version".to_string()],
             preview: None,
+            impact: None,
+            workspace: None,
         });
         plan.summary = PlanSummary {
             ops_total: 1,
@@ -634,6 +721,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "file missing".to_string(),
                 }),
+                signature: None,
             }],
         );
 
@@ -675,10 +763,12 @@ mod tests {
                 blocked_reason_token: None,
                 target: OpTarget {
                     path: format!("Cargo{}.toml", i),
+                    sha256: None,
                 },
                 kind: OpKind::TomlSet {
                     toml_path: vec!["package".to_string(), "version".to_string()],
                     value: serde_json::json!("0.1.0"),
+                    insert: None,
                 },
                 rationale: Rationale {
                     fix_key: "test".to_string(),
@@ -687,6 +777,8 @@ mod tests {
                 },
                 params_required: vec![],
                 preview: None,
+                impact: None,
+                workspace: None,
             });
         }
         plan.summary = PlanSummary {
@@ -721,10 +813,14 @@ mod tests {
                 head_sha_after: Some("abc123".to_string()),
                 dirty_before: Some(false),
                 dirty_after: Some(false),
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
 
@@ -746,10 +842,14 @@ mod tests {
                 head_sha_after: Some("def456".to_string()),
                 dirty_before: Some(false),
                 dirty_after: Some(true),
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: Some("hash".to_string()),
+                compat: None,
             },
         );
         apply.summary.attempted = 5;
@@ -774,10 +874,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary.attempted = 3;
@@ -802,10 +906,14 @@ mod tests {
                 head_sha_after: Some("def456".to_string()),
                 dirty_before: Some(false),
                 dirty_after: Some(true),
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".into(),
                 sha256: Some("hash".to_string()),
+                compat: None,
             },
         );
         apply.summary.attempted = 2;
@@ -830,10 +938,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".to_string(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary.applied = 1;
@@ -866,10 +978,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".to_string(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary.applied = 1;
@@ -903,10 +1019,14 @@ mod tests {
                 head_sha_after: Some("after_sha".to_string()),
                 dirty_before: Some(false),
                 dirty_after: Some(false),
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".to_string(),
                 sha256: None,
+                compat: None,
             },
         );
 
@@ -923,6 +1043,8 @@ mod tests {
                 root: ".".to_string(),
                 head_sha: Some("test_sha".to_string()),
                 dirty: Some(false),
+                remote_url: None,
+                ci: None,
             },
             PlanPolicy::default(),
         );
@@ -955,10 +1077,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".to_string(),
                 sha256: None,
+                compat: None,
             },
         );
         let report = build_apply_report(&apply, fixture_tool());
@@ -990,6 +1116,7 @@ mod tests {
                     capabilities: None,
                     data: None,
                 }),
+                signature: None,
             },
             LoadedReceipt {
                 path: "artifacts/fail/report.json".into(),
@@ -997,6 +1124,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "boom".to_string(),
                 }),
+                signature: None,
             },
         ];
 
@@ -1025,6 +1153,7 @@ mod tests {
                     capabilities: None,
                     data: None,
                 }),
+                signature: None,
             },
             LoadedReceipt {
                 path: "artifacts/a_report.json".into(),
@@ -1042,6 +1171,7 @@ mod tests {
                     capabilities: None,
                     data: None,
                 }),
+                signature: None,
             },
         ];
 
@@ -1064,6 +1194,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "error".to_string(),
                 }),
+                signature: None,
             },
             LoadedReceipt {
                 path: "artifacts/a_fail.json".into(),
@@ -1071,6 +1202,7 @@ mod tests {
                 receipt: Err(ReceiptLoadError::Io {
                     message: "error".to_string(),
                 }),
+                signature: None,
             },
         ];
 
@@ -1090,10 +1222,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".to_string(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary.attempted = 10;
@@ -1124,10 +1260,14 @@ mod tests {
                 head_sha_after: None,
                 dirty_before: None,
                 dirty_after: None,
+                dirty_changes: None,
+                remote_url: None,
+                ci: None,
             },
             PlanRef {
                 path: "plan.json".to_string(),
                 sha256: None,
+                compat: None,
             },
         );
         apply.summary.attempted = 0;
@@ -1148,6 +1288,7 @@ mod tests {
             receipt: Err(ReceiptLoadError::Io {
                 message: "file not found".to_string(),
             }),
+            signature: None,
         }];
 
         let report = build_plan_report(
@@ -1163,4 +1304,96 @@ mod tests {
         let fp = finding.fingerprint.as_ref().unwrap();
         assert!(fp.starts_with("inputs/receipt_load_failed/"));
     }
+
+    #[test]
+    fn test_plan_report_emits_a_finding_per_op() {
+        let op = |id: &str, safety: SafetyClass, blocked: bool| PlanOp {
+            id: id.to_string(),
+            safety,
+            blocked,
+            blocked_reason: blocked.then(|| "Missing parameters: version".to_string()),
+            blocked_reason_token: blocked.then_some(BuildfixErrorCode::MissingParams),
+            target: OpTarget {
+                path: "crates/a/Cargo.toml".to_string(),
+                sha256: None,
+            },
+            kind: OpKind::TomlTransform {
+                rule_id: "ensure_path_dep_has_version".to_string(),
+                args: None,
+            },
+            rationale: Rationale {
+                fix_key: "depguard/deps.path_requires_version/missing_version".to_string(),
+                description: None,
+                findings: vec![
+                    FindingRef {
+                        source: "depguard".to_string(),
+                        check_id: None,
+                        code: "missing_version".to_string(),
+                        path: Some("Cargo.toml".to_string()),
+                        line: Some(3),
+                        fingerprint: None,
+                        severity: None,
+                    },
+                    FindingRef {
+                        source: "depguard".to_string(),
+                        check_id: None,
+                        code: "missing_version".to_string(),
+                        path: Some("crates/a/Cargo.toml".to_string()),
+                        line: Some(9),
+                        fingerprint: None,
+                        severity: None,
+                    },
+                ],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: None,
+            workspace: None,
+        };
+        let mut plan = BuildfixPlan::new(fixture_tool(), default_repo(), PlanPolicy::default());
+        plan.ops.push(op("op-safe", SafetyClass::Safe, false));
+        plan.ops.push(op("op-unsafe", SafetyClass::Unsafe, true));
+        let receipts = vec![LoadedReceipt {
+            path: "artifacts/test/report.json".into(),
+            sensor_id: "test".to_string(),
+            receipt: Err(ReceiptLoadError::Io {
+                message: "file not found".to_string(),
+            }),
+            signature: None,
+        }];
+
+        let report = build_plan_report(&plan, fixture_tool(), &receipts);
+
+        assert_eq!(report.findings.len(), 3);
+        assert_eq!(report.findings[0].code, "receipt_load_failed");
+
+        let safe = &report.findings[1];
+        assert_eq!(safe.severity, ReportSeverity::Info);
+        assert_eq!(safe.check_id.as_deref(), Some("path_requires_version"));
+        assert_eq!(safe.code, "fix_available");
+        assert_eq!(
+            safe.message,
+            "depguard/deps.path_requires_version/missing_version can be fixed by buildfix"
+        );
+        let location = safe.location.as_ref().expect("location");
+        assert_eq!(location.path, "crates/a/Cargo.toml");
+        assert_eq!(location.line, Some(9));
+        assert_eq!(safe.fingerprint.as_deref(), Some("op-safe"));
+        assert_eq!(safe.data.as_ref().unwrap()["safety"], "safe");
+        assert_eq!(
+            safe.data.as_ref().unwrap()["fix_key"],
+            "depguard/deps.path_requires_version/missing_version"
+        );
+
+        let blocked = &report.findings[2];
+        assert_eq!(blocked.severity, ReportSeverity::Error);
+        assert_eq!(blocked.code, "missing_params");
+        assert!(
+            blocked
+                .message
+                .ends_with("(blocked: Missing parameters: version)")
+        );
+        assert_eq!(blocked.fingerprint.as_deref(), Some("op-unsafe"));
+        assert_eq!(blocked.data.as_ref().unwrap()["blocked"], true);
+    }
 }
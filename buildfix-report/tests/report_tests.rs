@@ -5,10 +5,13 @@
 
 use buildfix_receipts::{LoadedReceipt, ReceiptLoadError};
 use buildfix_report::{build_apply_report, build_plan_report, build_report_capabilities};
+use buildfix_types::error_code::BuildfixErrorCode;
 use buildfix_types::{
     apply::{ApplyRepoInfo, AutoCommitInfo, BuildfixApply, PlanRef},
     ops::{OpKind, OpTarget, SafetyClass},
-    plan::{BuildfixPlan, PlanOp, PlanPolicy, PlanSummary, Rationale, RepoInfo, SafetyCounts},
+    plan::{
+        BuildfixPlan, OpImpact, PlanOp, PlanPolicy, PlanSummary, Rationale, RepoInfo, SafetyCounts,
+    },
     receipt::{
         Finding, ReceiptCapabilities, ReceiptEnvelope, RunInfo, Severity, ToolInfo, Verdict,
     },
@@ -32,6 +35,8 @@ fn default_repo() -> RepoInfo {
         root: ".".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     }
 }
 
@@ -53,6 +58,7 @@ fn valid_receipt(path: &str, sensor_id: &str) -> LoadedReceipt {
             capabilities: None,
             data: None,
         }),
+        signature: None,
     }
 }
 
@@ -64,6 +70,7 @@ fn failed_receipt(path: &str, sensor_id: &str, message: &str) -> LoadedReceipt {
         receipt: Err(ReceiptLoadError::Io {
             message: message.to_string(),
         }),
+        signature: None,
     }
 }
 
@@ -90,10 +97,14 @@ fn test_apply_report_schema_version() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     let report = build_apply_report(&apply, fixture_tool());
@@ -134,10 +145,14 @@ fn test_apply_report_tool_info_no_version() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     let report = build_apply_report(&apply, tool);
@@ -230,10 +245,14 @@ fn test_apply_report_summary_statistics() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     apply.summary.attempted = 20;
@@ -286,6 +305,7 @@ fn test_plan_report_json_error_in_receipt() {
         receipt: Err(ReceiptLoadError::Json {
             message: "invalid JSON at position 42".to_string(),
         }),
+        signature: None,
     }];
 
     let plan = BuildfixPlan::new(fixture_tool(), default_repo(), PlanPolicy::default());
@@ -309,6 +329,7 @@ fn test_plan_report_schema_validation_error_in_receipt() {
         receipt: Err(ReceiptLoadError::Json {
             message: "schema validation failed: missing required field".to_string(),
         }),
+        signature: None,
     }];
 
     let plan = BuildfixPlan::new(fixture_tool(), default_repo(), PlanPolicy::default());
@@ -332,6 +353,7 @@ fn test_capabilities_io_vs_json_errors() {
             receipt: Err(ReceiptLoadError::Io {
                 message: "file not found".to_string(),
             }),
+            signature: None,
         },
         LoadedReceipt {
             path: "artifacts/json_error/report.json".into(),
@@ -339,6 +361,7 @@ fn test_capabilities_io_vs_json_errors() {
             receipt: Err(ReceiptLoadError::Json {
                 message: "parse error".to_string(),
             }),
+            signature: None,
         },
     ];
 
@@ -393,10 +416,14 @@ fn test_apply_report_empty_apply() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     let report = build_apply_report(&apply, fixture_tool());
@@ -422,10 +449,12 @@ fn test_plan_report_large_number_of_ops() {
             blocked_reason_token: None,
             target: OpTarget {
                 path: format!("crate{}/Cargo.toml", i % 10),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["package".to_string(), "version".to_string()],
                 value: serde_json::json!("0.1.0"),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "test".to_string(),
@@ -434,6 +463,8 @@ fn test_plan_report_large_number_of_ops() {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         });
     }
     plan.summary = PlanSummary {
@@ -464,29 +495,21 @@ fn test_plan_report_many_blocked_reason_tokens() {
     let mut plan = BuildfixPlan::new(fixture_tool(), default_repo(), PlanPolicy::default());
 
     // Create ops with many different blocked reason tokens
-    let tokens = [
-        "missing_param_a",
-        "missing_param_b",
-        "policy_denied",
-        "precondition_failed",
-        "unsafe_operation",
-        "requires_confirmation",
-        "guarded_needs_flag",
-    ];
-
-    for (i, token) in tokens.iter().enumerate() {
+    for (i, token) in BuildfixErrorCode::ALL.iter().take(7).enumerate() {
         plan.ops.push(PlanOp {
             id: format!("op-{}", i),
             safety: SafetyClass::Unsafe,
             blocked: true,
             blocked_reason: Some(format!("Blocked: {}", token)),
-            blocked_reason_token: Some(token.to_string()),
+            blocked_reason_token: Some(*token),
             target: OpTarget {
                 path: "Cargo.toml".to_string(),
+                sha256: None,
             },
             kind: OpKind::TomlSet {
                 toml_path: vec!["test".to_string()],
                 value: serde_json::json!(true),
+                insert: None,
             },
             rationale: Rationale {
                 fix_key: "test".to_string(),
@@ -495,6 +518,8 @@ fn test_plan_report_many_blocked_reason_tokens() {
             },
             params_required: vec![],
             preview: None,
+            impact: None,
+            workspace: None,
         });
     }
     plan.summary = PlanSummary {
@@ -520,6 +545,68 @@ fn test_plan_report_many_blocked_reason_tokens() {
     assert!(tokens_array.len() <= 5);
 }
 
+#[test]
+fn test_plan_report_totals_impact_of_unblocked_ops() {
+    let mut plan = BuildfixPlan::new(fixture_tool(), default_repo(), PlanPolicy::default());
+    let impacts = [
+        (false, 2, vec!["b", "a"], true, false),
+        (false, 1, vec!["a"], false, true),
+        (true, 50, vec!["c"], true, true),
+    ];
+    for (i, (blocked, lines, crates, lockfile, semver)) in impacts.into_iter().enumerate() {
+        plan.ops.push(PlanOp {
+            id: format!("op-{}", i),
+            safety: SafetyClass::Safe,
+            blocked,
+            blocked_reason: None,
+            blocked_reason_token: blocked.then_some(BuildfixErrorCode::Denylist),
+            target: OpTarget {
+                path: "Cargo.toml".to_string(),
+                sha256: None,
+            },
+            kind: OpKind::TomlSet {
+                toml_path: vec!["test".to_string()],
+                value: serde_json::json!(true),
+                insert: None,
+            },
+            rationale: Rationale {
+                fix_key: "test".to_string(),
+                description: None,
+                findings: vec![],
+            },
+            params_required: vec![],
+            preview: None,
+            impact: Some(OpImpact {
+                lines_changed: lines,
+                affected_crates: crates.into_iter().map(String::from).collect(),
+                lockfile_update: lockfile,
+                semver_visible: semver,
+            }),
+            workspace: None,
+        });
+    }
+
+    let report = build_plan_report(&plan, fixture_tool(), &[]);
+    let impact = &report.data.as_ref().unwrap()["buildfix"]["plan"]["impact"];
+    assert_eq!(
+        impact,
+        &serde_json::json!({
+            "lines_changed": 3,
+            "affected_crates": ["a", "b"],
+            "lockfile_update_ops": 1,
+            "semver_visible_ops": 1,
+        })
+    );
+
+    plan.ops.iter_mut().for_each(|op| op.impact = None);
+    let report = build_plan_report(&plan, fixture_tool(), &[]);
+    assert!(
+        report.data.as_ref().unwrap()["buildfix"]["plan"]
+            .get("impact")
+            .is_none()
+    );
+}
+
 #[test]
 fn test_receipt_with_empty_check_id() {
     let receipts = vec![LoadedReceipt {
@@ -549,6 +636,7 @@ fn test_receipt_with_empty_check_id() {
             capabilities: None,
             data: None,
         }),
+        signature: None,
     }];
 
     let caps = build_report_capabilities(&receipts);
@@ -586,6 +674,7 @@ fn test_receipt_with_none_check_id() {
             capabilities: None,
             data: None,
         }),
+        signature: None,
     }];
 
     let caps = build_report_capabilities(&receipts);
@@ -693,10 +782,14 @@ fn test_apply_report_verdict_reasons_empty_on_success() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     apply.summary.applied = 5;
@@ -737,6 +830,7 @@ fn test_capabilities_aggregates_scopes() {
             }),
             data: None,
         }),
+        signature: None,
     }];
 
     let caps = build_report_capabilities(&receipts);
@@ -771,6 +865,7 @@ fn test_capabilities_deduplicates_scopes() {
                 }),
                 data: None,
             }),
+            signature: None,
         },
         LoadedReceipt {
             path: "artifacts/b/report.json".into(),
@@ -793,6 +888,7 @@ fn test_capabilities_deduplicates_scopes() {
                 }),
                 data: None,
             }),
+            signature: None,
         },
     ];
 
@@ -816,10 +912,14 @@ fn test_apply_report_auto_commit_partial_info() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     apply.summary.applied = 1;
@@ -853,10 +953,14 @@ fn test_apply_report_no_auto_commit() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
 
@@ -891,10 +995,14 @@ fn test_apply_report_duration_is_zero() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     let report = build_apply_report(&apply, fixture_tool());
@@ -937,10 +1045,14 @@ fn test_apply_report_fail_takes_priority_over_warn() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     apply.summary.failed = 1;
@@ -962,10 +1074,14 @@ fn test_apply_report_warn_when_blocked_and_no_fail() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     apply.summary.failed = 0;
@@ -988,10 +1104,14 @@ fn test_apply_report_pass_only_when_applied_and_no_issues() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: None,
+            compat: None,
         },
     );
     apply.summary.failed = 0;
@@ -1024,10 +1144,12 @@ fn test_full_plan_report_workflow() {
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlSet {
             toml_path: vec!["workspace".to_string(), "members".to_string()],
             value: serde_json::json!(["crate1", "crate2"]),
+            insert: None,
         },
         rationale: Rationale {
             fix_key: "workspace-members".to_string(),
@@ -1036,6 +1158,8 @@ fn test_full_plan_report_workflow() {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     });
     plan.summary = PlanSummary {
         ops_total: 1,
@@ -1071,10 +1195,14 @@ fn test_full_apply_report_workflow() {
             head_sha_after: Some("def456".to_string()),
             dirty_before: Some(false),
             dirty_after: Some(false),
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".into(),
             sha256: Some("sha256:abc".to_string()),
+            compat: None,
         },
     );
     apply.summary.attempted = 5;
@@ -1,3 +1,6 @@
+use crate::error_code::BuildfixErrorCode;
+use crate::migrate::CompatReport;
+use crate::plan::CiRun;
 use crate::receipt::ToolInfo;
 use serde::{Deserialize, Serialize};
 
@@ -15,6 +18,9 @@ pub struct BuildfixApply {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auto_commit: Option<AutoCommitInfo>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<BranchInfo>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<String>,
 }
@@ -30,6 +36,7 @@ impl BuildfixApply {
             results: vec![],
             summary: ApplySummary::default(),
             auto_commit: None,
+            branch: None,
             errors: vec![],
         }
     }
@@ -51,6 +58,24 @@ pub struct AutoCommitInfo {
     pub skip_reason: Option<String>,
 }
 
+/// The branch apply switched to (`--branch`) and whether it was pushed.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BranchInfo {
+    /// Branch name rendered from the template.
+    pub name: String,
+    pub created: bool,
+
+    /// Remote to push to (`--push`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote: Option<String>,
+
+    #[serde(default)]
+    pub pushed: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub skip_reason: Option<String>,
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplyRepoInfo {
     pub root: String,
@@ -66,6 +91,47 @@ pub struct ApplyRepoInfo {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dirty_after: Option<bool>,
+
+    /// How the set of uncommitted paths changed across the apply; absent
+    /// when git could not list them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dirty_changes: Option<DirtyChanges>,
+
+    /// URL of the `origin` remote, without credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci: Option<CiRun>,
+}
+
+/// Paths whose uncommitted state differs between before and after an apply.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct DirtyChanges {
+    /// Clean before, dirty after (files the apply wrote and left uncommitted).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub dirtied: Vec<String>,
+
+    /// Dirty before, clean after (changes an auto-commit took along).
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub cleaned: Vec<String>,
+}
+
+impl DirtyChanges {
+    pub fn between(before: &[String], after: &[String]) -> Self {
+        Self {
+            dirtied: after
+                .iter()
+                .filter(|p| !before.contains(p))
+                .cloned()
+                .collect(),
+            cleaned: before
+                .iter()
+                .filter(|p| !after.contains(p))
+                .cloned()
+                .collect(),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,6 +140,10 @@ pub struct PlanRef {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub sha256: Option<String>,
+
+    /// How the plan was upgraded to be read, when it was not current.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub compat: Option<CompatReport>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -82,6 +152,11 @@ pub struct ApplyPreconditions {
 
     #[serde(default)]
     pub mismatches: Vec<PreconditionMismatch>,
+
+    /// Files whose sha256 changed but whose TOML structure did not, accepted
+    /// under the plan's `semantic` precondition mode.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub soft_matches: Vec<PreconditionMismatch>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -103,10 +178,22 @@ pub struct ApplyResult {
     pub blocked_reason: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub blocked_reason_token: Option<String>,
+    pub blocked_reason_token: Option<BuildfixErrorCode>,
 
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub files: Vec<ApplyFile>,
+
+    /// Time spent editing the op's target in memory, from the apply clock.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub duration_ms: Option<u64>,
+
+    /// Attempts the writes of the op's files took, when apply wrote them.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub attempts: Option<u32>,
+
+    /// Whether the op's files read back with the contents apply wrote.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub verified: Option<bool>,
 }
 
 #[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
@@ -118,6 +205,18 @@ pub enum ApplyStatus {
     Skipped,
 }
 
+impl ApplyStatus {
+    /// Snake-case name, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ApplyStatus::Applied => "applied",
+            ApplyStatus::Blocked => "blocked",
+            ApplyStatus::Failed => "failed",
+            ApplyStatus::Skipped => "skipped",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct ApplyFile {
     pub path: String,
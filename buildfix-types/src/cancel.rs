@@ -0,0 +1,28 @@
+//! Cooperative cancellation for long-running plans and applies.
+//!
+//! Not serialized to disk; hosts keep a clone of the token and cancel it
+//! from another thread (a signal handler, a request timeout).
+
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+/// Shared flag checked by the pipelines between fixers and between ops.
+/// Clones observe the same flag; the default token is never cancelled
+/// unless someone calls [`cancel`](Self::cancel).
+#[derive(Debug, Clone, Default)]
+pub struct CancellationToken(Arc<AtomicBool>);
+
+impl CancellationToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Ask every holder of this token to stop at its next check.
+    pub fn cancel(&self) {
+        self.0.store(true, Ordering::SeqCst);
+    }
+
+    pub fn is_cancelled(&self) -> bool {
+        self.0.load(Ordering::SeqCst)
+    }
+}
@@ -0,0 +1,125 @@
+//! Injectable time and id sources.
+//!
+//! Artifacts carry timestamps and backup run ids. Hosts that need
+//! byte-for-byte reproducible output (golden tests, conformance checks)
+//! swap in [`FixedClock`] and [`SequentialIds`].
+
+use chrono::{DateTime, Utc};
+use std::fmt;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicU64, Ordering};
+use uuid::Uuid;
+
+/// Source of the current time.
+pub trait ClockPort: Send + Sync {
+    fn now(&self) -> DateTime<Utc>;
+}
+
+/// Source of unique ids, used for backup run directories and temp file names.
+pub trait IdPort: Send + Sync {
+    fn new_id(&self) -> Uuid;
+}
+
+/// The system clock.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct SystemClock;
+
+impl ClockPort for SystemClock {
+    fn now(&self) -> DateTime<Utc> {
+        Utc::now()
+    }
+}
+
+/// A clock stopped at one instant.
+#[derive(Debug, Clone, Copy)]
+pub struct FixedClock(pub DateTime<Utc>);
+
+impl ClockPort for FixedClock {
+    fn now(&self) -> DateTime<Utc> {
+        self.0
+    }
+}
+
+/// Random (v4) UUIDs.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct RandomIds;
+
+impl IdPort for RandomIds {
+    fn new_id(&self) -> Uuid {
+        Uuid::new_v4()
+    }
+}
+
+/// UUIDs counting up from 1, the same sequence on every run.
+#[derive(Debug, Default)]
+pub struct SequentialIds(AtomicU64);
+
+impl IdPort for SequentialIds {
+    fn new_id(&self) -> Uuid {
+        Uuid::from_u128(u128::from(self.0.fetch_add(1, Ordering::Relaxed) + 1))
+    }
+}
+
+/// Shared handle to a [`ClockPort`], as carried in settings and options.
+/// Defaults to the [`SystemClock`].
+#[derive(Clone)]
+pub struct Clock(Arc<dyn ClockPort>);
+
+impl Clock {
+    pub fn new(port: impl ClockPort + 'static) -> Self {
+        Self(Arc::new(port))
+    }
+
+    /// A clock that always reads `at`.
+    pub fn fixed(at: DateTime<Utc>) -> Self {
+        Self::new(FixedClock(at))
+    }
+
+    pub fn now(&self) -> DateTime<Utc> {
+        self.0.now()
+    }
+}
+
+impl Default for Clock {
+    fn default() -> Self {
+        Self::new(SystemClock)
+    }
+}
+
+impl fmt::Debug for Clock {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Clock").finish_non_exhaustive()
+    }
+}
+
+/// Shared handle to an [`IdPort`], as carried in settings and options.
+/// Defaults to [`RandomIds`].
+#[derive(Clone)]
+pub struct Ids(Arc<dyn IdPort>);
+
+impl Ids {
+    pub fn new(port: impl IdPort + 'static) -> Self {
+        Self(Arc::new(port))
+    }
+
+    /// Ids counting up from 1; see [`SequentialIds`].
+    pub fn sequential() -> Self {
+        Self::new(SequentialIds::default())
+    }
+
+    pub fn new_id(&self) -> Uuid {
+        self.0.new_id()
+    }
+}
+
+impl Default for Ids {
+    fn default() -> Self {
+        Self::new(RandomIds)
+    }
+}
+
+impl fmt::Debug for Ids {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_tuple("Ids").finish_non_exhaustive()
+    }
+}
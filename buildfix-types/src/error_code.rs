@@ -0,0 +1,167 @@
+//! Machine-readable causes for blocked ops and refused runs.
+//!
+//! Each code serializes as the snake_case token recorded in
+//! `blocked_reason_token` on plan ops and apply results, so automation can
+//! branch on the cause instead of matching the English `blocked_reason`.
+
+use serde::{Deserialize, Serialize};
+use std::fmt;
+use std::str::FromStr;
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum BuildfixErrorCode {
+    /// An unsafe op needs parameters that were not provided.
+    MissingParams,
+    /// The fix matches a `deny` pattern.
+    Denylist,
+    /// An `allow` list is set and the fix is not on it.
+    AllowlistMissing,
+    MaxOps,
+    MaxFiles,
+    MaxPatchBytes,
+    DirtyWorkingTree,
+    SafetyGuardedNotAllowed,
+    SafetyUnsafeNotAllowed,
+    /// A file changed since the plan was made.
+    PreconditionMismatch,
+    /// The op's own target changed since the plan was made.
+    TargetDrifted,
+    StaleReceipt,
+    /// Excluded by `--only` / `--skip` style selection.
+    UserFiltered,
+    Cancelled,
+    PathDenylist,
+    PathAllowlistMissing,
+    PathSafetyNotAllowed,
+    ConflictingOps,
+    StalePlan,
+    /// The plan's `expires_at` has passed.
+    ExpiredPlan,
+}
+
+impl BuildfixErrorCode {
+    pub const ALL: [BuildfixErrorCode; 20] = [
+        BuildfixErrorCode::MissingParams,
+        BuildfixErrorCode::Denylist,
+        BuildfixErrorCode::AllowlistMissing,
+        BuildfixErrorCode::MaxOps,
+        BuildfixErrorCode::MaxFiles,
+        BuildfixErrorCode::MaxPatchBytes,
+        BuildfixErrorCode::DirtyWorkingTree,
+        BuildfixErrorCode::SafetyGuardedNotAllowed,
+        BuildfixErrorCode::SafetyUnsafeNotAllowed,
+        BuildfixErrorCode::PreconditionMismatch,
+        BuildfixErrorCode::TargetDrifted,
+        BuildfixErrorCode::StaleReceipt,
+        BuildfixErrorCode::UserFiltered,
+        BuildfixErrorCode::Cancelled,
+        BuildfixErrorCode::PathDenylist,
+        BuildfixErrorCode::PathAllowlistMissing,
+        BuildfixErrorCode::PathSafetyNotAllowed,
+        BuildfixErrorCode::ConflictingOps,
+        BuildfixErrorCode::StalePlan,
+        BuildfixErrorCode::ExpiredPlan,
+    ];
+
+    /// Snake-case token, as serialized.
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BuildfixErrorCode::MissingParams => "missing_params",
+            BuildfixErrorCode::Denylist => "denylist",
+            BuildfixErrorCode::AllowlistMissing => "allowlist_missing",
+            BuildfixErrorCode::MaxOps => "max_ops",
+            BuildfixErrorCode::MaxFiles => "max_files",
+            BuildfixErrorCode::MaxPatchBytes => "max_patch_bytes",
+            BuildfixErrorCode::DirtyWorkingTree => "dirty_working_tree",
+            BuildfixErrorCode::SafetyGuardedNotAllowed => "safety_guarded_not_allowed",
+            BuildfixErrorCode::SafetyUnsafeNotAllowed => "safety_unsafe_not_allowed",
+            BuildfixErrorCode::PreconditionMismatch => "precondition_mismatch",
+            BuildfixErrorCode::TargetDrifted => "target_drifted",
+            BuildfixErrorCode::StaleReceipt => "stale_receipt",
+            BuildfixErrorCode::UserFiltered => "user_filtered",
+            BuildfixErrorCode::Cancelled => "cancelled",
+            BuildfixErrorCode::PathDenylist => "path_denylist",
+            BuildfixErrorCode::PathAllowlistMissing => "path_allowlist_missing",
+            BuildfixErrorCode::PathSafetyNotAllowed => "path_safety_not_allowed",
+            BuildfixErrorCode::ConflictingOps => "conflicting_ops",
+            BuildfixErrorCode::StalePlan => "stale_plan",
+            BuildfixErrorCode::ExpiredPlan => "expired_plan",
+        }
+    }
+
+    /// Blocked because the op's safety class was not allowed.
+    pub fn is_safety_gate(&self) -> bool {
+        matches!(
+            self,
+            BuildfixErrorCode::SafetyGuardedNotAllowed
+                | BuildfixErrorCode::SafetyUnsafeNotAllowed
+                | BuildfixErrorCode::PathSafetyNotAllowed
+        )
+    }
+
+    /// Blocked by a `max_*` cap.
+    pub fn is_cap(&self) -> bool {
+        matches!(
+            self,
+            BuildfixErrorCode::MaxOps
+                | BuildfixErrorCode::MaxFiles
+                | BuildfixErrorCode::MaxPatchBytes
+        )
+    }
+
+    /// Blocked because the repo no longer matches what the plan saw.
+    pub fn is_drift(&self) -> bool {
+        matches!(
+            self,
+            BuildfixErrorCode::PreconditionMismatch
+                | BuildfixErrorCode::TargetDrifted
+                | BuildfixErrorCode::StaleReceipt
+                | BuildfixErrorCode::StalePlan
+        )
+    }
+}
+
+impl fmt::Display for BuildfixErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A token that names no [`BuildfixErrorCode`].
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct UnknownErrorCode(pub String);
+
+impl fmt::Display for UnknownErrorCode {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "unknown error code {}", self.0)
+    }
+}
+
+impl std::error::Error for UnknownErrorCode {}
+
+impl FromStr for BuildfixErrorCode {
+    type Err = UnknownErrorCode;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        BuildfixErrorCode::ALL
+            .into_iter()
+            .find(|code| code.as_str() == s)
+            .ok_or_else(|| UnknownErrorCode(s.to_string()))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::BuildfixErrorCode;
+
+    #[test]
+    fn tokens_match_serde_and_parse_back() {
+        for code in BuildfixErrorCode::ALL {
+            let json = serde_json::to_string(&code).expect("serialize");
+            assert_eq!(json, format!("\"{}\"", code.as_str()));
+            assert_eq!(code.as_str().parse::<BuildfixErrorCode>(), Ok(code));
+        }
+        assert!("safety".parse::<BuildfixErrorCode>().is_err());
+    }
+}
@@ -0,0 +1,84 @@
+//! Canonical fingerprints for findings that arrive without one.
+//!
+//! Sensors are asked to fingerprint their findings, but many do not. The
+//! fingerprint computed here gives such findings a stable identity across
+//! runs for dedup, fixers and plan diffs: a SHA-256 over the source tool,
+//! `check_id`, `code`, the normalized path and an anchor for the line.
+//!
+//! The anchor is the flagged line's text with whitespace collapsed when the
+//! caller can read it, so the fingerprint survives lines inserted above the
+//! finding; otherwise it is the line number.
+
+use buildfix_hash::sha256_hex;
+
+/// Version tag hashed into every fingerprint, bumped if the inputs change.
+const FINGERPRINT_SCHEME: &str = "buildfix.finding.v1";
+
+/// Where in its file a finding points.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LineAnchor<'a> {
+    /// No line, or a file-level finding.
+    None,
+    /// The 1-based line number.
+    Number(u64),
+    /// The text of the flagged line.
+    Text(&'a str),
+}
+
+impl<'a> LineAnchor<'a> {
+    /// `Number(line)` when a line is known, else `None`.
+    pub fn from_line(line: Option<u64>) -> Self {
+        line.map_or(LineAnchor::None, LineAnchor::Number)
+    }
+}
+
+/// Fingerprint of a finding. Empty or absent `check_id` and `code` (and the
+/// `-` placeholder plans use for a missing code) hash the same.
+pub fn finding_fingerprint(
+    source: &str,
+    check_id: Option<&str>,
+    code: Option<&str>,
+    path: Option<&str>,
+    line: LineAnchor<'_>,
+) -> String {
+    let code = code.filter(|c| *c != "-");
+    let path = path.map(normalize_path).unwrap_or_default();
+    let line = match line {
+        LineAnchor::None => String::new(),
+        LineAnchor::Number(n) => format!("#{}", n),
+        LineAnchor::Text(text) => format!("={}", normalize_line(text)),
+    };
+    let parts = [
+        FINGERPRINT_SCHEME,
+        source,
+        check_id.unwrap_or_default(),
+        code.unwrap_or_default(),
+        &path,
+        &line,
+    ];
+    sha256_hex(parts.join("\0").as_bytes())
+}
+
+/// Forward slashes, no leading `./`, no repeated separators.
+pub fn normalize_path(path: &str) -> String {
+    let path = path.replace('\\', "/");
+    let mut out = String::with_capacity(path.len());
+    for seg in path.split('/') {
+        if seg.is_empty() || seg == "." {
+            continue;
+        }
+        if !out.is_empty() {
+            out.push('/');
+        }
+        out.push_str(seg);
+    }
+    if path.starts_with('/') {
+        out.insert(0, '/');
+    }
+    out
+}
+
+/// Trimmed, with every whitespace run collapsed to one space.
+pub fn normalize_line(text: &str) -> String {
+    text.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -6,15 +6,24 @@
 //! - Prefer adding optional fields over changing semantics.
 
 pub mod apply;
+pub mod cancel;
+pub mod clock;
+pub mod error_code;
+pub mod fingerprint;
+pub mod messages;
+pub mod migrate;
 pub mod ops;
 pub mod plan;
+pub mod progress;
 pub mod receipt;
 pub mod report;
+pub mod telemetry;
 pub mod wire;
 
 /// Schema identifiers.
 pub mod schema {
     pub const BUILDFIX_PLAN_V1: &str = "buildfix.plan.v1";
+    pub const BUILDFIX_PLAN_V2: &str = "buildfix.plan.v2";
     pub const BUILDFIX_APPLY_V1: &str = "buildfix.apply.v1";
     pub const BUILDFIX_REPORT_V1: &str = "buildfix.report.v1";
 
@@ -0,0 +1,421 @@
+//! Human-readable message catalog.
+//!
+//! Every user-facing string (blocked reasons, markdown headings, `explain`
+//! output) is looked up by a stable code. [`MessageCatalog::default`] is the
+//! English catalog; embedders can override any subset of codes to localize
+//! output. Machine tokens such as `blocked_reason_token` are never
+//! translated.
+//!
+//! Messages may contain `{name}` placeholders, filled by
+//! [`MessageCatalog::format`]. Placeholders without a matching argument are
+//! left as-is.
+
+use crate::error_code::BuildfixErrorCode;
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fmt::Display;
+
+/// Stable message codes.
+pub mod codes {
+    // Blocked reasons: `blocked.<blocked_reason_token>`.
+    pub const BLOCKED_MISSING_PARAMS: &str = "blocked.missing_params";
+    pub const BLOCKED_DENYLIST: &str = "blocked.denylist";
+    pub const BLOCKED_ALLOWLIST_MISSING: &str = "blocked.allowlist_missing";
+    pub const BLOCKED_MAX_OPS: &str = "blocked.max_ops";
+    pub const BLOCKED_MAX_FILES: &str = "blocked.max_files";
+    pub const BLOCKED_MAX_PATCH_BYTES: &str = "blocked.max_patch_bytes";
+    pub const BLOCKED_DIRTY_WORKING_TREE: &str = "blocked.dirty_working_tree";
+    pub const BLOCKED_AUTO_COMMIT_DIRTY: &str = "blocked.auto_commit_dirty";
+    pub const BLOCKED_SAFETY_GUARDED_NOT_ALLOWED: &str = "blocked.safety_guarded_not_allowed";
+    pub const BLOCKED_SAFETY_UNSAFE_NOT_ALLOWED: &str = "blocked.safety_unsafe_not_allowed";
+    pub const BLOCKED_PRECONDITION_MISMATCH: &str = "blocked.precondition_mismatch";
+    pub const BLOCKED_TARGET_DRIFTED: &str = "blocked.target_drifted";
+    pub const BLOCKED_STALE_RECEIPT: &str = "blocked.stale_receipt";
+    pub const BLOCKED_USER_FILTERED: &str = "blocked.user_filtered";
+    pub const BLOCKED_CANCELLED: &str = "blocked.cancelled";
+    pub const BLOCKED_PATH_DENYLIST: &str = "blocked.path_denylist";
+    pub const BLOCKED_PATH_ALLOWLIST_MISSING: &str = "blocked.path_allowlist_missing";
+    pub const BLOCKED_PATH_SAFETY_NOT_ALLOWED: &str = "blocked.path_safety_not_allowed";
+    pub const BLOCKED_CONFLICTING_OPS: &str = "blocked.conflicting_ops";
+    pub const BLOCKED_STALE_PLAN: &str = "blocked.stale_plan";
+    pub const BLOCKED_EXPIRED_PLAN: &str = "blocked.expired_plan";
+
+    // Apply result messages.
+    pub const APPLY_SAFETY_CLASS_NOT_ALLOWED: &str = "apply.safety_class_not_allowed";
+    pub const APPLY_TARGET_CHANGED: &str = "apply.target_changed";
+    pub const APPLY_DRY_RUN: &str = "apply.dry_run";
+
+    // plan.md
+    pub const MD_PLAN_TITLE: &str = "md.plan.title";
+    pub const MD_PLAN_OPS_SUMMARY: &str = "md.plan.ops_summary";
+    pub const MD_PLAN_FILES_TOUCHED: &str = "md.plan.files_touched";
+    pub const MD_PLAN_PATCH_BYTES: &str = "md.plan.patch_bytes";
+    pub const MD_PLAN_SAFETY_COUNTS: &str = "md.plan.safety_counts";
+    pub const MD_PLAN_INPUTS: &str = "md.plan.inputs";
+    pub const MD_PLAN_IMPACT: &str = "md.plan.impact";
+    pub const MD_PLAN_WORKSPACES: &str = "md.plan.workspaces";
+    pub const MD_IMPACT_TOTALS: &str = "md.impact.totals";
+    pub const MD_PLAN_OPS: &str = "md.plan.ops";
+    pub const MD_PLAN_NO_OPS: &str = "md.plan.no_ops";
+    pub const MD_OP_SAFETY: &str = "md.op.safety";
+    pub const MD_OP_BLOCKED: &str = "md.op.blocked";
+    pub const MD_OP_TARGET: &str = "md.op.target";
+    pub const MD_OP_WORKSPACE: &str = "md.op.workspace";
+    pub const MD_OP_KIND: &str = "md.op.kind";
+    pub const MD_OP_BLOCKED_REASON: &str = "md.op.blocked_reason";
+    pub const MD_OP_PARAMS_REQUIRED: &str = "md.op.params_required";
+    pub const MD_OP_IMPACT: &str = "md.op.impact";
+    pub const MD_IMPACT_LINES: &str = "md.impact.lines";
+    pub const MD_IMPACT_CRATES: &str = "md.impact.crates";
+    pub const MD_IMPACT_LOCKFILE: &str = "md.impact.lockfile";
+    pub const MD_IMPACT_SEMVER: &str = "md.impact.semver";
+    pub const MD_OP_FINDINGS: &str = "md.op.findings";
+
+    // apply.md
+    pub const MD_APPLY_TITLE: &str = "md.apply.title";
+    pub const MD_APPLY_ATTEMPTED: &str = "md.apply.attempted";
+    pub const MD_APPLY_APPLIED: &str = "md.apply.applied";
+    pub const MD_APPLY_BLOCKED: &str = "md.apply.blocked";
+    pub const MD_APPLY_FAILED: &str = "md.apply.failed";
+    pub const MD_APPLY_FILES_MODIFIED: &str = "md.apply.files_modified";
+    pub const MD_APPLY_RESULTS: &str = "md.apply.results";
+    pub const MD_APPLY_NO_RESULTS: &str = "md.apply.no_results";
+    pub const MD_RESULT_STATUS: &str = "md.result.status";
+    pub const MD_RESULT_MESSAGE: &str = "md.result.message";
+    pub const MD_RESULT_ATTEMPTS: &str = "md.result.attempts";
+    pub const MD_RESULT_UNVERIFIED: &str = "md.result.unverified";
+    pub const MD_RESULT_FILES_CHANGED: &str = "md.result.files_changed";
+    pub const MD_RESULT_DIFF_SHOWN_UNDER: &str = "md.result.diff_shown_under";
+    pub const MD_RESULT_CHANGED_LINES: &str = "md.result.changed_lines";
+
+    // Diffs embedded in apply.md and report.html
+    pub const MD_DIFF_TRUNCATED: &str = "md.diff.truncated";
+
+    // comment.md
+    pub const MD_COMMENT_FIX_AVAILABLE: &str = "md.comment.fix_available";
+    pub const MD_COMMENT_NO_FIXES: &str = "md.comment.no_fixes";
+    pub const MD_COMMENT_ALL_BLOCKED: &str = "md.comment.all_blocked";
+    pub const MD_COMMENT_SAFETY: &str = "md.comment.safety";
+    pub const MD_COMMENT_COUNT: &str = "md.comment.count";
+    pub const MD_COMMENT_BLOCKED_REASONS: &str = "md.comment.blocked_reasons";
+    pub const MD_COMMENT_IMPACT: &str = "md.comment.impact";
+    pub const MD_COMMENT_OPS_ONE: &str = "md.comment.ops_one";
+    pub const MD_COMMENT_OPS_OTHER: &str = "md.comment.ops_other";
+    pub const MD_COMMENT_OPS_BLOCKED: &str = "md.comment.ops_blocked";
+    pub const MD_COMMENT_OP_BLOCKED: &str = "md.comment.op_blocked";
+    pub const MD_COMMENT_MORE: &str = "md.comment.more";
+    pub const MD_COMMENT_TRUNCATED: &str = "md.comment.truncated";
+    pub const MD_COMMENT_ARTIFACTS: &str = "md.comment.artifacts";
+
+    // `buildfix explain`
+    pub const EXPLAIN_FIX: &str = "explain.fix";
+    pub const EXPLAIN_KEY: &str = "explain.key";
+    pub const EXPLAIN_FIX_ID: &str = "explain.fix_id";
+    pub const EXPLAIN_POLICY: &str = "explain.policy";
+    pub const EXPLAIN_SAFETY: &str = "explain.safety";
+    pub const EXPLAIN_DESCRIPTION: &str = "explain.description";
+    pub const EXPLAIN_TRIGGERS: &str = "explain.triggers";
+    pub const EXPLAIN_TRIGGERS_INTRO: &str = "explain.triggers_intro";
+    pub const EXPLAIN_SAFETY_CLASS: &str = "explain.safety_class";
+    pub const EXPLAIN_SAFETY_RATIONALE: &str = "explain.safety_rationale";
+    pub const EXPLAIN_REMEDIATION: &str = "explain.remediation";
+    pub const EXPLAIN_OP: &str = "explain.op";
+    pub const EXPLAIN_OP_TARGET: &str = "explain.op.target";
+    pub const EXPLAIN_OP_STATUS: &str = "explain.op.status";
+    pub const EXPLAIN_OP_READY: &str = "explain.op.ready";
+    pub const EXPLAIN_OP_BLOCKED: &str = "explain.op.blocked";
+    pub const EXPLAIN_OP_PARAMS: &str = "explain.op.params";
+    pub const EXPLAIN_OP_KIND: &str = "explain.op.kind";
+    pub const EXPLAIN_OP_FINDINGS: &str = "explain.op.findings";
+    pub const EXPLAIN_OP_PREVIEW: &str = "explain.op.preview";
+    pub const EXPLAIN_OP_NO_CHANGES: &str = "explain.op.no_changes";
+    pub const EXPLAIN_OP_NO_PREVIEW: &str = "explain.op.no_preview";
+    pub const SAFETY_SAFE_MEANING: &str = "safety.safe.meaning";
+    pub const SAFETY_GUARDED_MEANING: &str = "safety.guarded.meaning";
+    pub const SAFETY_UNSAFE_MEANING: &str = "safety.unsafe.meaning";
+
+    /// Code for the blocked reason behind `token`.
+    pub fn blocked(token: crate::error_code::BuildfixErrorCode) -> String {
+        format!("blocked.{}", token.as_str())
+    }
+}
+
+/// English defaults for every code in [`codes`].
+const ENGLISH: &[(&str, &str)] = &[
+    (codes::BLOCKED_MISSING_PARAMS, "missing params: {params}"),
+    (codes::BLOCKED_DENYLIST, "denied by policy"),
+    (codes::BLOCKED_ALLOWLIST_MISSING, "not in allowlist"),
+    (
+        codes::BLOCKED_MAX_OPS,
+        "caps exceeded: max_ops {actual} > {limit} allowed",
+    ),
+    (
+        codes::BLOCKED_MAX_FILES,
+        "caps exceeded: max_files {actual} > {limit} allowed",
+    ),
+    (
+        codes::BLOCKED_MAX_PATCH_BYTES,
+        "caps exceeded: max_patch_bytes {actual} > {limit} allowed",
+    ),
+    (codes::BLOCKED_DIRTY_WORKING_TREE, "dirty working tree"),
+    (
+        codes::BLOCKED_AUTO_COMMIT_DIRTY,
+        "auto-commit requires clean git working tree",
+    ),
+    (codes::BLOCKED_SAFETY_GUARDED_NOT_ALLOWED, "safety gate"),
+    (codes::BLOCKED_SAFETY_UNSAFE_NOT_ALLOWED, "safety gate"),
+    (
+        codes::BLOCKED_PRECONDITION_MISMATCH,
+        "precondition mismatch",
+    ),
+    (codes::BLOCKED_TARGET_DRIFTED, "target file drifted"),
+    (
+        codes::BLOCKED_STALE_RECEIPT,
+        "stale receipt: {tool} ran at {sha}, HEAD is {head}",
+    ),
+    (
+        codes::BLOCKED_USER_FILTERED,
+        "not selected by --op/--only/--skip",
+    ),
+    (codes::BLOCKED_CANCELLED, "cancelled before this op ran"),
+    (
+        codes::BLOCKED_PATH_DENYLIST,
+        "denied by path policy for {path}",
+    ),
+    (
+        codes::BLOCKED_PATH_ALLOWLIST_MISSING,
+        "not in allowlist for {path}",
+    ),
+    (
+        codes::BLOCKED_PATH_SAFETY_NOT_ALLOWED,
+        "{safety} ops are not allowed under {path}",
+    ),
+    (
+        codes::BLOCKED_CONFLICTING_OPS,
+        "conflicts with {fix_key} at {toml_path} in {path}",
+    ),
+    (
+        codes::BLOCKED_STALE_PLAN,
+        "stale plan: receipts changed since planning: {paths}",
+    ),
+    (
+        codes::BLOCKED_EXPIRED_PLAN,
+        "expired plan: expired at {expires_at}; re-run buildfix plan",
+    ),
+    (
+        codes::APPLY_SAFETY_CLASS_NOT_ALLOWED,
+        "safety class not allowed",
+    ),
+    (
+        codes::APPLY_TARGET_CHANGED,
+        "{path} changed since planning (expected sha256 {expected}, got {actual})",
+    ),
+    (codes::APPLY_DRY_RUN, "dry-run: not written"),
+    (codes::MD_PLAN_TITLE, "buildfix plan"),
+    (
+        codes::MD_PLAN_OPS_SUMMARY,
+        "Ops: {total} (blocked {blocked})",
+    ),
+    (codes::MD_PLAN_FILES_TOUCHED, "Files touched: {count}"),
+    (codes::MD_PLAN_PATCH_BYTES, "Patch bytes: {count}"),
+    (
+        codes::MD_PLAN_SAFETY_COUNTS,
+        "Safety: {safe} safe, {guarded} guarded, {unsafe} unsafe",
+    ),
+    (codes::MD_PLAN_INPUTS, "Inputs: {count}"),
+    (codes::MD_PLAN_IMPACT, "Impact"),
+    (codes::MD_PLAN_WORKSPACES, "Workspaces"),
+    (
+        codes::MD_IMPACT_TOTALS,
+        "~{lines} lines, {crates} crates, {lockfile} lockfile updates, {semver} semver-visible",
+    ),
+    (codes::MD_PLAN_OPS, "Ops"),
+    (codes::MD_PLAN_NO_OPS, "No ops planned."),
+    (codes::MD_OP_SAFETY, "Safety"),
+    (codes::MD_OP_BLOCKED, "Blocked"),
+    (codes::MD_OP_TARGET, "Target"),
+    (codes::MD_OP_WORKSPACE, "Workspace"),
+    (codes::MD_OP_KIND, "Kind"),
+    (codes::MD_OP_BLOCKED_REASON, "Blocked reason"),
+    (codes::MD_OP_PARAMS_REQUIRED, "Params required"),
+    (codes::MD_OP_IMPACT, "Impact"),
+    (codes::MD_IMPACT_LINES, "~{count} lines"),
+    (codes::MD_IMPACT_CRATES, "crates: {crates}"),
+    (codes::MD_IMPACT_LOCKFILE, "lockfile update"),
+    (codes::MD_IMPACT_SEMVER, "semver-visible"),
+    (codes::MD_OP_FINDINGS, "Findings"),
+    (codes::MD_APPLY_TITLE, "buildfix apply"),
+    (codes::MD_APPLY_ATTEMPTED, "Attempted"),
+    (codes::MD_APPLY_APPLIED, "Applied"),
+    (codes::MD_APPLY_BLOCKED, "Blocked"),
+    (codes::MD_APPLY_FAILED, "Failed"),
+    (codes::MD_APPLY_FILES_MODIFIED, "Files modified"),
+    (codes::MD_APPLY_RESULTS, "Results"),
+    (codes::MD_APPLY_NO_RESULTS, "No results."),
+    (codes::MD_RESULT_STATUS, "Status"),
+    (codes::MD_RESULT_MESSAGE, "Message"),
+    (codes::MD_RESULT_ATTEMPTS, "Write attempts"),
+    (
+        codes::MD_RESULT_UNVERIFIED,
+        "Files did not read back with the contents written",
+    ),
+    (codes::MD_RESULT_FILES_CHANGED, "Files changed"),
+    (
+        codes::MD_RESULT_DIFF_SHOWN_UNDER,
+        "Diff for `{path}` is shown under result {index}.",
+    ),
+    (codes::MD_RESULT_CHANGED_LINES, "{count} changed lines"),
+    (
+        codes::MD_DIFF_TRUNCATED,
+        "… diff truncated: {shown} of {total} bytes shown. See patch.diff for the full patch.",
+    ),
+    (codes::MD_COMMENT_FIX_AVAILABLE, "fix available"),
+    (codes::MD_COMMENT_NO_FIXES, "no fixes needed"),
+    (codes::MD_COMMENT_ALL_BLOCKED, "all ops blocked"),
+    (codes::MD_COMMENT_SAFETY, "Safety"),
+    (codes::MD_COMMENT_COUNT, "Count"),
+    (codes::MD_COMMENT_BLOCKED_REASONS, "Blocked reasons"),
+    (codes::MD_COMMENT_IMPACT, "Impact"),
+    (codes::MD_COMMENT_OPS_ONE, "{count} op"),
+    (codes::MD_COMMENT_OPS_OTHER, "{count} ops"),
+    (codes::MD_COMMENT_OPS_BLOCKED, "{count} blocked"),
+    (codes::MD_COMMENT_OP_BLOCKED, "blocked"),
+    (codes::MD_COMMENT_MORE, "… and {count} more"),
+    (
+        codes::MD_COMMENT_TRUNCATED,
+        "Comment truncated: {hidden} of {total} ops not shown. See [plan.md](plan.md) for the full plan.",
+    ),
+    (codes::MD_COMMENT_ARTIFACTS, "Artifacts"),
+    (codes::EXPLAIN_FIX, "FIX"),
+    (codes::EXPLAIN_KEY, "Key"),
+    (codes::EXPLAIN_FIX_ID, "Fix ID"),
+    (codes::EXPLAIN_POLICY, "Policy"),
+    (codes::EXPLAIN_SAFETY, "Safety"),
+    (codes::EXPLAIN_DESCRIPTION, "DESCRIPTION"),
+    (codes::EXPLAIN_TRIGGERS, "TRIGGERING FINDINGS"),
+    (
+        codes::EXPLAIN_TRIGGERS_INTRO,
+        "This fix is triggered by sensor findings matching:",
+    ),
+    (codes::EXPLAIN_SAFETY_CLASS, "SAFETY CLASS"),
+    (codes::EXPLAIN_SAFETY_RATIONALE, "SAFETY RATIONALE"),
+    (codes::EXPLAIN_REMEDIATION, "REMEDIATION GUIDANCE"),
+    (codes::EXPLAIN_OP, "OP"),
+    (codes::EXPLAIN_OP_TARGET, "Target"),
+    (codes::EXPLAIN_OP_STATUS, "Status"),
+    (codes::EXPLAIN_OP_READY, "ready"),
+    (codes::EXPLAIN_OP_BLOCKED, "blocked ({reason})"),
+    (codes::EXPLAIN_OP_PARAMS, "Params"),
+    (codes::EXPLAIN_OP_KIND, "OPERATION"),
+    (codes::EXPLAIN_OP_FINDINGS, "FINDINGS"),
+    (codes::EXPLAIN_OP_PREVIEW, "DIFF PREVIEW"),
+    (
+        codes::EXPLAIN_OP_NO_CHANGES,
+        "No changes against the current tree.",
+    ),
+    (codes::EXPLAIN_OP_NO_PREVIEW, "No preview: {reason}"),
+    (
+        codes::SAFETY_SAFE_MEANING,
+        "SAFE fixes are fully determined from repo-local truth and have low impact.\n\
+         They are applied automatically with `buildfix apply --apply`.",
+    ),
+    (
+        codes::SAFETY_GUARDED_MEANING,
+        "GUARDED fixes are deterministic but have higher impact.\n\
+         They require explicit approval with `buildfix apply --apply --allow-guarded`.",
+    ),
+    (
+        codes::SAFETY_UNSAFE_MEANING,
+        "UNSAFE fixes are ambiguous without user-provided inputs.\n\
+         They require explicit `--allow-unsafe` and any required params to apply.",
+    ),
+];
+
+/// Messages keyed by code: the English defaults plus any overrides.
+///
+/// Serializes as a flat `{ code: message }` map of the overrides, so an
+/// alternate catalog can be loaded from TOML or JSON.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(transparent)]
+pub struct MessageCatalog {
+    overrides: BTreeMap<String, String>,
+}
+
+impl MessageCatalog {
+    /// A catalog that overrides the given codes and falls back to English.
+    pub fn with_overrides<I, K, V>(overrides: I) -> Self
+    where
+        I: IntoIterator<Item = (K, V)>,
+        K: Into<String>,
+        V: Into<String>,
+    {
+        Self {
+            overrides: overrides
+                .into_iter()
+                .map(|(k, v)| (k.into(), v.into()))
+                .collect(),
+        }
+    }
+
+    /// Override one code.
+    pub fn insert(&mut self, code: impl Into<String>, message: impl Into<String>) {
+        self.overrides.insert(code.into(), message.into());
+    }
+
+    /// Whether this is the plain English catalog.
+    pub fn is_english(&self) -> bool {
+        self.overrides.is_empty()
+    }
+
+    /// The override for `code`, if this catalog has one.
+    pub fn override_for(&self, code: &str) -> Option<&str> {
+        self.overrides.get(code).map(String::as_str)
+    }
+
+    /// The message for `code`: the override, else the English default.
+    pub fn lookup(&self, code: &str) -> Option<&str> {
+        self.override_for(code).or_else(|| english_default(code))
+    }
+
+    /// The message for `code`, or the code itself when unknown.
+    pub fn get<'a>(&'a self, code: &'a str) -> &'a str {
+        self.lookup(code).unwrap_or(code)
+    }
+
+    /// The message for `code` with `{name}` placeholders filled from `args`.
+    pub fn format(&self, code: &str, args: &[(&str, &dyn Display)]) -> String {
+        fill(self.get(code), args)
+    }
+
+    /// Localize a blocked reason recorded in an artifact.
+    ///
+    /// Reasons are stored in English. When this catalog overrides the code
+    /// for `token`, the override is used with `{reason}` bound to the stored
+    /// text; otherwise the stored text is returned unchanged.
+    pub fn blocked_reason(&self, token: Option<BuildfixErrorCode>, reason: &str) -> String {
+        match token.and_then(|t| self.override_for(&codes::blocked(t))) {
+            Some(message) => fill(message, &[("reason", &reason)]),
+            None => reason.to_string(),
+        }
+    }
+}
+
+/// Format `code` from the English catalog.
+pub fn english(code: &str, args: &[(&str, &dyn Display)]) -> String {
+    fill(english_default(code).unwrap_or(code), args)
+}
+
+fn english_default(code: &str) -> Option<&'static str> {
+    ENGLISH
+        .iter()
+        .find(|(c, _)| *c == code)
+        .map(|(_, message)| *message)
+}
+
+fn fill(message: &str, args: &[(&str, &dyn Display)]) -> String {
+    let mut out = message.to_string();
+    for (name, value) in args {
+        out = out.replace(&format!("{{{}}}", name), &value.to_string());
+    }
+    out
+}
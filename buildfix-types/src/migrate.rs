@@ -0,0 +1,240 @@
+//! Upgrades plan artifacts from other buildfix releases to the in-memory
+//! model, and downgrades the model for emission in a chosen schema version.
+//!
+//! Upgrading never fails on a field a release did not write yet: the gap is
+//! filled with its default and recorded in the [`CompatReport`], so callers
+//! can tell the user what the plan lacks (for example, no preconditions means
+//! no drift check before apply).
+
+use serde::{Deserialize, Serialize};
+
+use std::collections::BTreeSet;
+
+use crate::ops::{OpKind, TOML_TRANSFORMS};
+use crate::plan::BuildfixPlan;
+use crate::schema::{BUILDFIX_PLAN_V1, BUILDFIX_PLAN_V2};
+use crate::wire::{PlanV1, PlanV2, WireError};
+
+/// Plan schema versions buildfix reads and writes, oldest first.
+pub const PLAN_SCHEMAS: &[&str] = &[BUILDFIX_PLAN_V1, BUILDFIX_PLAN_V2];
+
+/// What an upgrade changed to read an artifact as the current model.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CompatReport {
+    /// Schema the artifact declared, if any.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub from: Option<String>,
+
+    /// Schema of the model it was read into.
+    pub to: String,
+
+    /// One line per gap filled or conversion made.
+    #[serde(default)]
+    pub notes: Vec<String>,
+}
+
+impl CompatReport {
+    /// Whether the artifact was already the current schema with nothing filled in.
+    pub fn is_current(&self) -> bool {
+        self.notes.is_empty() && self.from.as_deref() == Some(self.to.as_str())
+    }
+}
+
+/// An upgraded artifact with its [`CompatReport`].
+#[derive(Debug, Clone)]
+pub struct Migrated<T> {
+    pub value: T,
+    pub report: CompatReport,
+}
+
+#[derive(Debug)]
+pub enum MigrateError {
+    /// The `schema` field names a version this release cannot read or write.
+    UnsupportedSchema(String),
+    /// The plan uses op kinds or transform rules this release cannot apply,
+    /// one `op kind ...` / `transform ...` entry each.
+    UnsupportedOps(Vec<String>),
+    Json(serde_json::Error),
+    Wire(WireError),
+}
+
+impl std::fmt::Display for MigrateError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            MigrateError::UnsupportedSchema(schema) => write!(
+                f,
+                "unsupported plan schema {} (supported: {})",
+                schema,
+                PLAN_SCHEMAS.join(", ")
+            ),
+            MigrateError::UnsupportedOps(missing) => write!(
+                f,
+                "plan needs {}, which this release cannot apply",
+                missing.join(", ")
+            ),
+            MigrateError::Json(err) => write!(f, "invalid plan: {}", err),
+            MigrateError::Wire(err) => err.fmt(f),
+        }
+    }
+}
+
+impl std::error::Error for MigrateError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            MigrateError::UnsupportedSchema(_) | MigrateError::UnsupportedOps(_) => None,
+            MigrateError::Json(err) => Some(err),
+            MigrateError::Wire(err) => Some(err),
+        }
+    }
+}
+
+impl From<serde_json::Error> for MigrateError {
+    fn from(err: serde_json::Error) -> Self {
+        MigrateError::Json(err)
+    }
+}
+
+impl From<WireError> for MigrateError {
+    fn from(err: WireError) -> Self {
+        MigrateError::Wire(err)
+    }
+}
+
+/// Read a plan of any supported version, or one written before plans
+/// carried a `schema` field, as the current model.
+pub fn upgrade_plan(mut value: serde_json::Value) -> Result<Migrated<BuildfixPlan>, MigrateError> {
+    let from = value
+        .get("schema")
+        .and_then(|s| s.as_str())
+        .map(str::to_string);
+    let mut report = CompatReport {
+        from: from.clone(),
+        to: BUILDFIX_PLAN_V1.to_string(),
+        notes: Vec::new(),
+    };
+
+    let plan = match from.as_deref() {
+        Some(BUILDFIX_PLAN_V2) => {
+            check_supported_ops(&value)?;
+            report
+                .notes
+                .push(format!("converted from {}", BUILDFIX_PLAN_V2));
+            BuildfixPlan::from(serde_json::from_value::<PlanV2>(value)?)
+        }
+        Some(BUILDFIX_PLAN_V1) | None => {
+            check_supported_ops(&value)?;
+            legacy_gaps(&value, &mut report.notes);
+            if let Some(obj) = value.as_object_mut() {
+                obj.insert("schema".to_string(), BUILDFIX_PLAN_V1.into());
+            }
+            serde_json::from_value(value)?
+        }
+        Some(other) => return Err(MigrateError::UnsupportedSchema(other.to_string())),
+    };
+
+    Ok(Migrated {
+        value: plan,
+        report,
+    })
+}
+
+/// Serialize `plan` as the wire format of `schema`.
+pub fn downgrade_plan(
+    plan: &BuildfixPlan,
+    schema: &str,
+) -> Result<serde_json::Value, MigrateError> {
+    match schema {
+        BUILDFIX_PLAN_V1 => Ok(serde_json::to_value(PlanV1::try_from(plan)?)?),
+        BUILDFIX_PLAN_V2 => Ok(serde_json::to_value(PlanV2::try_from(plan)?)?),
+        other => Err(MigrateError::UnsupportedSchema(other.to_string())),
+    }
+}
+
+/// Refuse a plan whose declared `capabilities.op_kinds`, or whose ops, use an
+/// op kind or `toml_transform` rule this release does not know. Runs on the
+/// raw JSON, so an unknown kind is reported as such rather than as a parse
+/// error, and before any op is applied.
+fn check_supported_ops(value: &serde_json::Value) -> Result<(), MigrateError> {
+    let ops = value
+        .get("ops")
+        .and_then(|ops| ops.as_array())
+        .into_iter()
+        .flatten()
+        .chain(
+            value
+                .get("groups")
+                .and_then(|groups| groups.as_array())
+                .into_iter()
+                .flatten()
+                .filter_map(|group| group.get("ops").and_then(|ops| ops.as_array()))
+                .flatten(),
+        );
+
+    let mut kinds = BTreeSet::new();
+    let mut transforms = BTreeSet::new();
+    if let Some(declared) = value
+        .pointer("/capabilities/op_kinds")
+        .and_then(|k| k.as_array())
+    {
+        kinds.extend(declared.iter().filter_map(|k| k.as_str()));
+    }
+    for op in ops {
+        let Some(kind) = op.get("kind") else {
+            continue;
+        };
+        let ty = kind.get("type").and_then(|t| t.as_str());
+        kinds.extend(ty);
+        if ty == Some("toml_transform") {
+            transforms.extend(kind.get("rule_id").and_then(|r| r.as_str()));
+        }
+    }
+
+    let missing: Vec<String> = kinds
+        .into_iter()
+        .filter(|kind| !OpKind::TYPES.contains(kind))
+        .map(|kind| format!("op kind `{}`", kind))
+        .chain(
+            transforms
+                .into_iter()
+                .filter(|rule| !TOML_TRANSFORMS.contains(rule))
+                .map(|rule| format!("transform `{}`", rule)),
+        )
+        .collect();
+    if missing.is_empty() {
+        Ok(())
+    } else {
+        Err(MigrateError::UnsupportedOps(missing))
+    }
+}
+
+/// Fields older v1 writers left out, which read back as defaults.
+fn legacy_gaps(value: &serde_json::Value, notes: &mut Vec<String>) {
+    if value.get("schema").is_none() {
+        notes.push(format!("no `schema` field; read as {}", BUILDFIX_PLAN_V1));
+    }
+    if value.pointer("/tool/version").is_none() {
+        notes.push("no `tool.version`; the producing release is unknown".to_string());
+    }
+    if value.get("inputs").is_none() {
+        notes.push("no `inputs`; the receipts behind the plan are unknown".to_string());
+    }
+    if value.get("preconditions").is_none() {
+        notes.push("no `preconditions`; target files are not checked for drift".to_string());
+    }
+    let untokened = value
+        .get("ops")
+        .and_then(|ops| ops.as_array())
+        .map(|ops| {
+            ops.iter()
+                .filter(|op| op.get("blocked").and_then(|b| b.as_bool()) == Some(true))
+                .filter(|op| op.get("blocked_reason_token").is_none())
+                .count()
+        })
+        .unwrap_or(0);
+    if untokened > 0 {
+        notes.push(format!(
+            "{} blocked op(s) without `blocked_reason_token`",
+            untokened
+        ));
+    }
+}
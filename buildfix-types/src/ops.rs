@@ -7,7 +7,9 @@ use serde::{Deserialize, Serialize};
 /// - guarded: deterministic but higher impact (requires explicit allow)
 /// - unsafe: deterministic only when user-provided parameters are present and
 ///   `--allow-unsafe` is enabled
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+///
+/// Variants are ordered from least to most restrictive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum SafetyClass {
     Safe,
@@ -34,10 +36,28 @@ pub enum OpKind {
     TomlSet {
         toml_path: Vec<String>,
         value: serde_json::Value,
+        /// Placement and comment used when the key is newly inserted.
+        #[serde(default, skip_serializing_if = "Option::is_none")]
+        insert: Option<InsertHint>,
     },
     TomlRemove {
         toml_path: Vec<String>,
     },
+    /// Append values to the array at `toml_path`, creating it if missing.
+    /// With `unique`, values already present are skipped.
+    TomlArrayAppend {
+        toml_path: Vec<String>,
+        values: Vec<serde_json::Value>,
+        #[serde(default)]
+        unique: bool,
+    },
+    /// Shallow-merge the keys of `table` into the table or inline table at
+    /// `toml_path`, creating an inline table if missing. Existing keys are
+    /// overwritten; other keys and comments are left alone.
+    TomlMerge {
+        toml_path: Vec<String>,
+        table: serde_json::Map<String, serde_json::Value>,
+    },
     JsonSet {
         json_path: Vec<String>,
         value: serde_json::Value,
@@ -69,10 +89,128 @@ pub enum OpKind {
     },
 }
 
+/// `rule_id`s of [`OpKind::TomlTransform`] the edit engine applies. Any
+/// other rule would apply as a no-op, so plans using one are refused.
+pub const TOML_TRANSFORMS: &[&str] = &[
+    "ensure_workspace_resolver_v2",
+    "set_package_rust_version",
+    "set_package_edition",
+    "set_package_license",
+    "ensure_path_dep_has_version",
+    "ensure_workspace_dependency_version",
+    "use_workspace_dependency",
+];
+
+impl OpKind {
+    /// Every op `type` tag, as serialized.
+    pub const TYPES: &[&str] = &[
+        "toml_set",
+        "toml_remove",
+        "toml_array_append",
+        "toml_merge",
+        "json_set",
+        "json_remove",
+        "yaml_set",
+        "yaml_remove",
+        "toml_transform",
+        "text_replace_anchored",
+    ];
+
+    /// The op's `type` tag, as serialized.
+    pub fn type_name(&self) -> &'static str {
+        match self {
+            OpKind::TomlSet { .. } => "toml_set",
+            OpKind::TomlRemove { .. } => "toml_remove",
+            OpKind::TomlArrayAppend { .. } => "toml_array_append",
+            OpKind::TomlMerge { .. } => "toml_merge",
+            OpKind::JsonSet { .. } => "json_set",
+            OpKind::JsonRemove { .. } => "json_remove",
+            OpKind::YamlSet { .. } => "yaml_set",
+            OpKind::YamlRemove { .. } => "yaml_remove",
+            OpKind::TomlTransform { .. } => "toml_transform",
+            OpKind::TextReplaceAnchored { .. } => "text_replace_anchored",
+        }
+    }
+
+    /// Lines the op is expected to touch: one per set, remove or transform,
+    /// one per appended value or merged key, and the replaced lines times the
+    /// replacement cap for text replacements.
+    pub fn estimated_lines_changed(&self) -> u64 {
+        match self {
+            OpKind::TomlArrayAppend { values, .. } => values.len() as u64,
+            OpKind::TomlMerge { table, .. } => table.len() as u64,
+            OpKind::TextReplaceAnchored {
+                find,
+                replace,
+                max_replacements,
+                ..
+            } => {
+                let lines = find.lines().count().max(replace.lines().count()).max(1) as u64;
+                lines * max_replacements.unwrap_or(1)
+            }
+            OpKind::TomlSet { .. }
+            | OpKind::TomlRemove { .. }
+            | OpKind::JsonSet { .. }
+            | OpKind::JsonRemove { .. }
+            | OpKind::YamlSet { .. }
+            | OpKind::YamlRemove { .. }
+            | OpKind::TomlTransform { .. } => 1,
+        }
+    }
+
+    /// Key path of TOML ops that address a key directly.
+    pub fn toml_path(&self) -> Option<&[String]> {
+        match self {
+            OpKind::TomlSet { toml_path, .. }
+            | OpKind::TomlRemove { toml_path }
+            | OpKind::TomlArrayAppend { toml_path, .. }
+            | OpKind::TomlMerge { toml_path, .. } => Some(toml_path),
+            _ => None,
+        }
+    }
+}
+
+/// Placement and annotation for a key an op inserts.
+///
+/// Only applies when the key does not exist yet; an existing key is updated
+/// in place.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct InsertHint {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub position: Option<InsertPosition>,
+    /// Comment placed on the line above the new key, without the leading `#`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl InsertHint {
+    /// The conventional attribution comment, `added by buildfix (<fix_key>)`.
+    pub fn attributed(fix_key: &str) -> Self {
+        Self {
+            position: None,
+            comment: Some(format!("added by buildfix ({})", fix_key)),
+        }
+    }
+}
+
+/// Where a newly inserted key goes within its table.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum InsertPosition {
+    /// Before all other keys of the table.
+    Top,
+    /// Directly after the named sibling key, or at the end if it is absent.
+    After(String),
+}
+
 /// Target path for an operation.
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct OpTarget {
     pub path: String,
+    /// Expected sha256 of the target file. When set, the op is blocked on its
+    /// own if the file drifted, instead of failing the whole plan.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 /// Optional preview fragment for an operation.
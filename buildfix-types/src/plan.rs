@@ -1,5 +1,6 @@
-use crate::ops::{OpKind, OpPreview, OpTarget, SafetyClass};
-use crate::receipt::ToolInfo;
+use crate::error_code::BuildfixErrorCode;
+use crate::ops::{OpKind, OpPreview, OpTarget, SafetyClass, TOML_TRANSFORMS};
+use crate::receipt::{Severity, ToolInfo};
 use serde::{Deserialize, Serialize};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -20,6 +21,18 @@ pub struct BuildfixPlan {
     pub ops: Vec<PlanOp>,
 
     pub summary: PlanSummary,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<PlanCapabilities>,
+
+    /// RFC 3339 time after which apply blocks every op with `expired_plan`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+
+    /// One entry per Cargo workspace the plan covers, when any of them lies
+    /// below the repo root.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<PlanWorkspace>,
 }
 
 impl BuildfixPlan {
@@ -33,6 +46,9 @@ impl BuildfixPlan {
             preconditions: PlanPreconditions::default(),
             ops: vec![],
             summary: PlanSummary::default(),
+            capabilities: None,
+            expires_at: None,
+            workspaces: vec![],
         }
     }
 }
@@ -46,6 +62,33 @@ pub struct RepoInfo {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub dirty: Option<bool>,
+
+    /// URL of the `origin` remote, without credentials.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub remote_url: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub ci: Option<CiRun>,
+}
+
+/// The CI run that produced an artifact.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct CiRun {
+    /// `github`, `gitlab`, `bitbucket`, `jenkins`, ...
+    pub provider: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_id: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_attempt: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub job: Option<String>,
+
+    /// Link to the run (or job) page.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -57,6 +100,19 @@ pub struct PlanInput {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub tool: Option<String>,
+
+    /// Commit the receipt was produced for (`run.git_head_sha`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub git_head_sha: Option<String>,
+
+    /// When the receipt was produced (`run.ended_at`, else `run.started_at`).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub generated_at: Option<String>,
+
+    /// SHA-256 of the receipt file at planning time; apply re-hashes it to
+    /// detect a plan made from receipts that have since changed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sha256: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -86,11 +142,89 @@ pub struct PlanPolicy {
     pub max_patch_bytes: Option<u64>,
 }
 
+/// Policy for ops whose target path matches `path`, on top of the global
+/// allow/deny lists.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(deny_unknown_fields)]
+pub struct PathPolicy {
+    /// Glob over the repo-relative target path; `*` also matches `/`.
+    pub path: String,
+
+    /// If non-empty, only ops whose policy key matches one of these run here.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub allow: Vec<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub deny: Vec<String>,
+
+    /// Most permissive safety class allowed here.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub max_safety: Option<SafetyClass>,
+}
+
+/// Repo paths planning never targets, so a plan cannot edit build output or
+/// vendored copies of dependencies.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct ScanPolicy {
+    /// Directory names skipped wherever they appear in a path.
+    pub skip_dirs: Vec<String>,
+
+    /// Skip paths ignored by the repo's `.gitignore` files.
+    pub gitignore: bool,
+
+    /// Skip crates copied in by `cargo vendor` (any directory holding a
+    /// `.cargo-checksum.json`).
+    pub vendored: bool,
+}
+
+impl Default for ScanPolicy {
+    fn default() -> Self {
+        Self {
+            skip_dirs: vec!["target".to_string(), ".git".to_string()],
+            gitignore: true,
+            vendored: true,
+        }
+    }
+}
+
+/// Which uncommitted changes block a writing apply.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum DirtyScope {
+    /// Any change anywhere in the working tree.
+    #[default]
+    Repo,
+    /// Only changes to files the plan's ops target.
+    Plan,
+}
+
+/// How apply compares a file against its planned sha256.
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PreconditionMode {
+    /// Any byte change blocks.
+    #[default]
+    Exact,
+    /// A TOML file whose bytes changed but whose parsed structure did not
+    /// (formatting or comments only) is a soft match and does not block.
+    Semantic,
+}
+
+impl PreconditionMode {
+    pub fn is_exact(&self) -> bool {
+        *self == PreconditionMode::Exact
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PlanPreconditions {
     #[serde(default)]
     pub files: Vec<FilePrecondition>,
 
+    #[serde(default, skip_serializing_if = "PreconditionMode::is_exact")]
+    pub mode: PreconditionMode,
+
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub head_sha: Option<String>,
 
@@ -102,6 +236,11 @@ pub struct PlanPreconditions {
 pub struct FilePrecondition {
     pub path: String,
     pub sha256: String,
+
+    /// Hash of the parsed TOML structure, ignoring formatting and comments.
+    /// Only recorded in `semantic` mode, for files that parse as TOML.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub semantic_sha256: Option<String>,
 }
 
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
@@ -125,6 +264,59 @@ pub struct PlanSummary {
     pub safety_counts: Option<SafetyCounts>,
 }
 
+/// A Cargo workspace in a plan that covers several.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PlanWorkspace {
+    /// Repo-relative workspace root; `.` for the repo root.
+    pub root: String,
+
+    /// Counts over the ops tagged with `root`. `patch_bytes` is unset.
+    pub summary: PlanSummary,
+}
+
+/// What the planning build supports and what the plan needs, so a consumer
+/// running another build can refuse a plan it cannot apply before touching
+/// anything.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct PlanCapabilities {
+    /// Fix keys of the fixers the planner had.
+    #[serde(default)]
+    pub fixers: Vec<String>,
+
+    /// `toml_transform` rules the planning build applies.
+    #[serde(default)]
+    pub transforms: Vec<String>,
+
+    /// Op `type`s the plan's ops use, sorted.
+    #[serde(default)]
+    pub op_kinds: Vec<String>,
+
+    /// Plan schema versions the planning build reads.
+    #[serde(default)]
+    pub schemas: Vec<String>,
+}
+
+impl PlanCapabilities {
+    /// This build's capabilities, with `op_kinds` taken from `ops`.
+    pub fn new(fixers: impl IntoIterator<Item = String>, ops: &[PlanOp]) -> Self {
+        let mut op_kinds: Vec<String> = ops
+            .iter()
+            .map(|op| op.kind.type_name().to_string())
+            .collect();
+        op_kinds.sort();
+        op_kinds.dedup();
+        Self {
+            fixers: fixers.into_iter().collect(),
+            transforms: TOML_TRANSFORMS.iter().map(|t| t.to_string()).collect(),
+            op_kinds,
+            schemas: crate::migrate::PLAN_SCHEMAS
+                .iter()
+                .map(|s| s.to_string())
+                .collect(),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct PlanOp {
     pub id: String,
@@ -135,7 +327,7 @@ pub struct PlanOp {
     pub blocked_reason: Option<String>,
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub blocked_reason_token: Option<String>,
+    pub blocked_reason_token: Option<BuildfixErrorCode>,
 
     pub target: OpTarget,
     pub kind: OpKind,
@@ -146,6 +338,80 @@ pub struct PlanOp {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub preview: Option<OpPreview>,
+
+    /// Estimated effect of the op, filled in by the planner.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub impact: Option<OpImpact>,
+
+    /// Repo-relative root of the Cargo workspace the op belongs to (`.` for
+    /// the repo root). Set only in plans with [`BuildfixPlan::workspaces`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+}
+
+/// Estimated effect of an op, for review and gating policies.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpImpact {
+    /// Lines the op is expected to add, change or remove.
+    pub lines_changed: u64,
+
+    /// Workspace packages whose manifest or build the op changes, sorted.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub affected_crates: Vec<String>,
+
+    /// Whether `Cargo.lock` is expected to change on the next build.
+    #[serde(default)]
+    pub lockfile_update: bool,
+
+    /// Whether dependents of the affected crates can observe the change
+    /// (MSRV, features, public dependencies or the package version).
+    #[serde(default)]
+    pub semver_visible: bool,
+}
+
+impl OpImpact {
+    /// Impact known from the op kind alone: only `lines_changed` is set.
+    pub fn estimate(kind: &OpKind) -> Self {
+        Self {
+            lines_changed: kind.estimated_lines_changed(),
+            ..Self::default()
+        }
+    }
+}
+
+/// [`OpImpact`] totalled over the ops of a plan that are not blocked.
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct ImpactSummary {
+    pub lines_changed: u64,
+
+    /// Union of the ops' `affected_crates`, sorted.
+    #[serde(default)]
+    pub affected_crates: Vec<String>,
+
+    pub lockfile_update_ops: u64,
+    pub semver_visible_ops: u64,
+}
+
+impl ImpactSummary {
+    /// Totals over unblocked ops; `None` when none of them carry impact.
+    pub fn from_ops(ops: &[PlanOp]) -> Option<Self> {
+        let mut summary = Self::default();
+        let mut crates = std::collections::BTreeSet::new();
+        let mut any = false;
+        for impact in ops
+            .iter()
+            .filter(|op| !op.blocked)
+            .filter_map(|op| op.impact.as_ref())
+        {
+            any = true;
+            summary.lines_changed += impact.lines_changed;
+            crates.extend(impact.affected_crates.iter().cloned());
+            summary.lockfile_update_ops += u64::from(impact.lockfile_update);
+            summary.semver_visible_ops += u64::from(impact.semver_visible);
+        }
+        summary.affected_crates = crates.into_iter().collect();
+        any.then_some(summary)
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -176,17 +442,24 @@ pub struct FindingRef {
 
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub fingerprint: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
 }
 
-pub mod blocked_tokens {
-    pub const MISSING_PARAMS: &str = "missing_params";
-    pub const DENYLIST: &str = "denylist";
-    pub const ALLOWLIST_MISSING: &str = "allowlist_missing";
-    pub const MAX_OPS: &str = "max_ops";
-    pub const MAX_FILES: &str = "max_files";
-    pub const MAX_PATCH_BYTES: &str = "max_patch_bytes";
-    pub const DIRTY_WORKING_TREE: &str = "dirty_working_tree";
-    pub const SAFETY_GUARDED_NOT_ALLOWED: &str = "safety_guarded_not_allowed";
-    pub const SAFETY_UNSAFE_NOT_ALLOWED: &str = "safety_unsafe_not_allowed";
-    pub const PRECONDITION_MISMATCH: &str = "precondition_mismatch";
+impl FindingRef {
+    /// The recorded fingerprint, or the canonical one computed from the
+    /// finding's identity (see [`crate::fingerprint`]).
+    pub fn stable_fingerprint(&self) -> String {
+        if let Some(fp) = &self.fingerprint {
+            return fp.clone();
+        }
+        crate::fingerprint::finding_fingerprint(
+            &self.source,
+            self.check_id.as_deref(),
+            Some(&self.code),
+            self.path.as_deref(),
+            crate::fingerprint::LineAnchor::from_line(self.line),
+        )
+    }
 }
@@ -0,0 +1,62 @@
+//! Progress reported while a plan or apply runs.
+//!
+//! Not serialized to disk; hosts turn these into progress bars or event
+//! streams.
+
+/// A stage of `run_plan` or `run_apply`, in the order they are reached.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Phase {
+    /// Loading sensor receipts and the `cargo metadata` snapshot.
+    LoadReceipts,
+    /// Running the fixers; reports [`Progress::Fixer`].
+    Plan,
+    /// Hashing op targets for the plan's preconditions.
+    Preconditions,
+    /// Rendering the preview patch.
+    Preview,
+    /// Checking preconditions and running ops; reports [`Progress::Op`].
+    Apply,
+    /// Writing changed files (and backups) to disk.
+    Write,
+    /// Creating the branch, committing and pushing.
+    Git,
+    /// Building the report.
+    Report,
+}
+
+impl Phase {
+    /// Snake-case name, as used in event streams.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Phase::LoadReceipts => "load_receipts",
+            Phase::Plan => "plan",
+            Phase::Preconditions => "preconditions",
+            Phase::Preview => "preview",
+            Phase::Apply => "apply",
+            Phase::Write => "write",
+            Phase::Git => "git",
+            Phase::Report => "report",
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Progress<'a> {
+    /// `phase` has started.
+    Phase(Phase),
+    /// Fixer `fix_key` has run, planning `ops` ops; `done` of `total`
+    /// fixers have run so far. Disabled fixers count with no ops.
+    Fixer {
+        fix_key: &'a str,
+        ops: usize,
+        done: usize,
+        total: usize,
+    },
+    /// Op `op_id` was applied, blocked or skipped; `done` of `total` ops
+    /// have been handled so far.
+    Op {
+        op_id: &'a str,
+        done: usize,
+        total: usize,
+    },
+}
@@ -157,6 +157,21 @@ pub struct Finding {
 }
 
 impl Finding {
+    /// The sensor's fingerprint, or the canonical one computed from the
+    /// finding's identity (see [`crate::fingerprint`]).
+    pub fn stable_fingerprint(&self, source: &str) -> String {
+        if let Some(fp) = &self.fingerprint {
+            return fp.clone();
+        }
+        crate::fingerprint::finding_fingerprint(
+            source,
+            self.check_id.as_deref(),
+            self.code.as_deref(),
+            self.location.as_ref().map(|loc| loc.path.as_str()),
+            crate::fingerprint::LineAnchor::from_line(self.location.as_ref().and_then(|l| l.line)),
+        )
+    }
+
     /// Returns true if this finding has high confidence (>= 0.9).
     pub fn is_high_confidence(&self) -> bool {
         self.confidence.is_some_and(|c| c >= 0.9)
@@ -271,7 +286,8 @@ pub enum AnalysisDepth {
     Deep,
 }
 
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize, Deserialize)]
+/// Finding severity, ordered `Info < Warn < Error`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Default, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum Severity {
     #[default]
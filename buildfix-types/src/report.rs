@@ -1,5 +1,8 @@
 use serde::{Deserialize, Serialize};
 
+use crate::ops::SafetyClass;
+use crate::plan::{PlanInput, PlanOp};
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct BuildfixReport {
     pub schema: String,
@@ -63,6 +66,18 @@ pub enum ReportStatus {
     Skip,
 }
 
+impl ReportStatus {
+    /// Snake-case name, as serialized.
+    pub fn as_str(self) -> &'static str {
+        match self {
+            ReportStatus::Pass => "pass",
+            ReportStatus::Warn => "warn",
+            ReportStatus::Fail => "fail",
+            ReportStatus::Skip => "skip",
+        }
+    }
+}
+
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReportCounts {
     pub info: u64,
@@ -90,6 +105,89 @@ pub struct ReportFinding {
     pub data: Option<serde_json::Value>,
 }
 
+impl ReportFinding {
+    /// The finding a plan report emits for a planned op.
+    ///
+    /// Severity follows the op's safety class, the location is its target
+    /// (at the first finding on that file, when one has a line) and the
+    /// deterministic op id is the fingerprint. `code` is the op's
+    /// `blocked_reason_token` when blocked, else `fix_available`; `check_id`
+    /// is [`fix_key_check_id`] of the fix key, which `data` carries in full.
+    pub fn from_plan_op(op: &PlanOp) -> Self {
+        let severity = match op.safety {
+            SafetyClass::Safe => ReportSeverity::Info,
+            SafetyClass::Guarded => ReportSeverity::Warn,
+            SafetyClass::Unsafe => ReportSeverity::Error,
+        };
+        let code = match (op.blocked, op.blocked_reason_token) {
+            (false, _) => "fix_available",
+            (true, Some(token)) => token.as_str(),
+            (true, None) => "blocked",
+        };
+        let mut message = op
+            .rationale
+            .description
+            .clone()
+            .unwrap_or_else(|| format!("{} can be fixed by buildfix", op.rationale.fix_key));
+        if let Some(reason) = &op.blocked_reason {
+            message.push_str(&format!(" (blocked: {})", reason));
+        }
+        let line = op
+            .rationale
+            .findings
+            .iter()
+            .filter(|f| f.path.as_deref() == Some(op.target.path.as_str()))
+            .find_map(|f| f.line);
+        let safety = match op.safety {
+            SafetyClass::Safe => "safe",
+            SafetyClass::Guarded => "guarded",
+            SafetyClass::Unsafe => "unsafe",
+        };
+
+        Self {
+            severity,
+            check_id: Some(fix_key_check_id(&op.rationale.fix_key)),
+            code: code.to_string(),
+            message,
+            location: Some(ReportLocation {
+                path: op.target.path.clone(),
+                line,
+                col: None,
+            }),
+            fingerprint: Some(op.id.clone()),
+            data: Some(serde_json::json!({
+                "op_id": op.id,
+                "fix_key": op.rationale.fix_key,
+                "safety": safety,
+                "blocked": op.blocked,
+            })),
+        }
+    }
+}
+
+/// A report `check_id` (`^[a-z][a-z0-9_]*$`) for a `<sensor>/<check_id>/<code>`
+/// fix key: the last dotted part of its check, e.g. `resolver_v2` for
+/// `builddiag/workspace.resolver_v2/not_v2`. Keys of another shape, or with
+/// no check (`-`), are used whole. Other characters become `_`, and a token
+/// not starting with a letter is prefixed with `fix_`.
+pub fn fix_key_check_id(fix_key: &str) -> String {
+    let check = match fix_key.split('/').collect::<Vec<_>>().as_slice() {
+        [_, check, _] if *check != "-" => check.rsplit('.').next().unwrap_or(check),
+        _ => fix_key,
+    };
+    let mut token: String = check
+        .chars()
+        .map(|c| match c.to_ascii_lowercase() {
+            c @ ('a'..='z' | '0'..='9' | '_') => c,
+            _ => '_',
+        })
+        .collect();
+    if !token.starts_with(|c: char| c.is_ascii_lowercase()) {
+        token.insert_str(0, "fix_");
+    }
+    token
+}
+
 #[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
 #[serde(rename_all = "snake_case")]
 pub enum ReportSeverity {
@@ -135,6 +233,40 @@ pub struct ReportCapabilities {
     /// Input paths that failed to load.
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub inputs_failed: Vec<InputFailure>,
+
+    /// Signature verification status per input, when a trust policy is configured.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub input_signatures: Vec<InputSignature>,
+}
+
+impl ReportCapabilities {
+    /// Input capabilities as recorded in a plan's `inputs`, for reports made
+    /// from a plan after its receipts are gone. Inputs planned without a
+    /// schema had failed to load.
+    pub fn from_plan_inputs(inputs: &[PlanInput]) -> Self {
+        let mut inputs_available = Vec::new();
+        let mut inputs_failed = Vec::new();
+        for input in inputs {
+            if input.schema.is_some() {
+                inputs_available.push(input.path.clone());
+            } else {
+                inputs_failed.push(InputFailure {
+                    path: input.path.clone(),
+                    reason: "failed to load when the plan was made".to_string(),
+                });
+            }
+        }
+        inputs_available.sort();
+        inputs_failed.sort_by(|a, b| a.path.cmp(&b.path));
+
+        Self {
+            partial: !inputs_failed.is_empty(),
+            reason: (!inputs_failed.is_empty()).then(|| "some receipts failed to load".to_string()),
+            inputs_available,
+            inputs_failed,
+            ..Self::default()
+        }
+    }
 }
 
 /// Record of an input that failed to load.
@@ -144,6 +276,15 @@ pub struct InputFailure {
     pub reason: String,
 }
 
+/// Signature check of one input: `verified`, `unsigned` or `invalid`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InputSignature {
+    pub path: String,
+    pub status: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub reason: Option<String>,
+}
+
 /// Pointers to related artifact files.
 #[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct ReportArtifacts {
@@ -0,0 +1,114 @@
+//! Stable ids for the structured tracing events buildfix emits.
+//!
+//! Every event carries its id in an `event` field, so log pipelines can
+//! match on it instead of the message text, which may change. Events use
+//! these field names where they apply:
+//!
+//! - `fix_key`: the fixer's policy key, e.g. `cargo.workspace_resolver_v2`
+//! - `op_id`: the plan op id
+//! - `file`: the file or directory the event is about
+//! - `outcome`: a snake-case result, e.g. an apply status or blocked token
+//! - `error`: the error, formatted with its causes
+//!
+//! Ids are never reused or renamed; new events and fields may be added. The
+//! full list, with levels and fields, is in `docs/reference/telemetry.md`.
+
+// Receipts.
+pub const RECEIPTS_SCAN: &str = "receipts.scan";
+pub const RECEIPTS_LOADED: &str = "receipts.loaded";
+pub const RECEIPT_SKIPPED: &str = "receipts.skipped";
+pub const RECEIPT_REJECTED: &str = "receipts.rejected";
+pub const RECEIPT_CACHE_WRITE_FAILED: &str = "receipts.cache_write_failed";
+
+// Planning.
+pub const PLAN_STARTED: &str = "plan.started";
+pub const PLAN_SCOPE_CHANGED: &str = "plan.scope_changed";
+pub const PLAN_SCOPE_STAGED: &str = "plan.scope_staged";
+pub const PLAN_FINDINGS_FILTERED: &str = "plan.findings_filtered";
+pub const PLAN_WORKSPACES: &str = "plan.workspaces";
+pub const PLAN_FIXER: &str = "plan.fixer";
+pub const PLAN_OPS_FILTERED: &str = "plan.ops_filtered";
+pub const PLAN_CANCELLED: &str = "plan.cancelled";
+pub const PLAN_OP_BLOCKED: &str = "plan.op_blocked";
+pub const PLAN_FINISHED: &str = "plan.finished";
+
+// Applying.
+pub const APPLY_STARTED: &str = "apply.started";
+pub const APPLY_PLAN_FORMAT: &str = "apply.plan_format";
+pub const APPLY_LOCK_RECLAIMED: &str = "apply.lock_reclaimed";
+pub const APPLY_OP: &str = "apply.op";
+pub const APPLY_FINISHED: &str = "apply.finished";
+pub const APPLY_PATCH_NO_PLAN: &str = "apply_patch.no_plan";
+pub const APPLY_PATCH_MISMATCH: &str = "apply_patch.mismatch";
+pub const APPLY_PATCH_FILE: &str = "apply_patch.file";
+pub const APPLY_PATCH_BACKUPS: &str = "apply_patch.backups";
+pub const SANDBOX_CREATED: &str = "sandbox.created";
+pub const SANDBOX_CLEANUP_FAILED: &str = "sandbox.cleanup_failed";
+
+// Git.
+pub const GIT_GIX_FALLBACK: &str = "git.gix_fallback";
+
+// CLI.
+pub const CONFIG_FILE: &str = "config.file";
+pub const CONFIG_TEMPLATE: &str = "config.template";
+pub const CONFIG_MERGED: &str = "config.merged";
+pub const CONFIG_EXTENDS: &str = "config.extends";
+pub const CONFIG_EXTENDS_CACHED: &str = "config.extends_cached";
+pub const CONFIG_EXTENDS_CACHE_FAILED: &str = "config.extends_cache_failed";
+pub const ARTIFACTS_WRITTEN: &str = "artifacts.written";
+pub const VALIDATE_FAILED: &str = "validate.failed";
+pub const VALIDATE_PASSED: &str = "validate.passed";
+pub const EXPORT_SELECTION: &str = "export.selection";
+pub const EVENTS_WRITE_FAILED: &str = "events.write_failed";
+pub const METRICS_WRITE_FAILED: &str = "metrics.write_failed";
+pub const WATCH_PLAN_FAILED: &str = "watch.plan_failed";
+pub const SERVE_LISTENING: &str = "serve.listening";
+pub const SERVE_CONNECTION_FAILED: &str = "serve.connection_failed";
+pub const COMMAND_FAILED: &str = "command.failed";
+
+/// Every event id, in the order above.
+pub const ALL: &[&str] = &[
+    RECEIPTS_SCAN,
+    RECEIPTS_LOADED,
+    RECEIPT_SKIPPED,
+    RECEIPT_REJECTED,
+    RECEIPT_CACHE_WRITE_FAILED,
+    PLAN_STARTED,
+    PLAN_SCOPE_CHANGED,
+    PLAN_SCOPE_STAGED,
+    PLAN_FINDINGS_FILTERED,
+    PLAN_WORKSPACES,
+    PLAN_FIXER,
+    PLAN_OPS_FILTERED,
+    PLAN_CANCELLED,
+    PLAN_OP_BLOCKED,
+    PLAN_FINISHED,
+    APPLY_STARTED,
+    APPLY_PLAN_FORMAT,
+    APPLY_LOCK_RECLAIMED,
+    APPLY_OP,
+    APPLY_FINISHED,
+    APPLY_PATCH_NO_PLAN,
+    APPLY_PATCH_MISMATCH,
+    APPLY_PATCH_FILE,
+    APPLY_PATCH_BACKUPS,
+    SANDBOX_CREATED,
+    SANDBOX_CLEANUP_FAILED,
+    GIT_GIX_FALLBACK,
+    CONFIG_FILE,
+    CONFIG_TEMPLATE,
+    CONFIG_MERGED,
+    CONFIG_EXTENDS,
+    CONFIG_EXTENDS_CACHED,
+    CONFIG_EXTENDS_CACHE_FAILED,
+    ARTIFACTS_WRITTEN,
+    VALIDATE_FAILED,
+    VALIDATE_PASSED,
+    EXPORT_SELECTION,
+    EVENTS_WRITE_FAILED,
+    METRICS_WRITE_FAILED,
+    WATCH_PLAN_FAILED,
+    SERVE_LISTENING,
+    SERVE_CONNECTION_FAILED,
+    COMMAND_FAILED,
+];
@@ -1,8 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::apply::{
-    ApplyPreconditions, ApplyRepoInfo, ApplyResult, ApplySummary, AutoCommitInfo, BuildfixApply,
-    PlanRef,
+    ApplyPreconditions, ApplyRepoInfo, ApplyResult, ApplySummary, AutoCommitInfo, BranchInfo,
+    BuildfixApply, PlanRef,
 };
 use crate::receipt::ToolInfo;
 use crate::wire::{ToolInfoV1, WireError};
@@ -24,6 +24,9 @@ pub struct ApplyV1 {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub auto_commit: Option<AutoCommitInfo>,
 
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub branch: Option<BranchInfo>,
+
     #[serde(default, skip_serializing_if = "Vec::is_empty")]
     pub errors: Vec<String>,
 }
@@ -43,6 +46,7 @@ impl TryFrom<&BuildfixApply> for ApplyV1 {
             tool: ToolInfoV1 {
                 name: apply.tool.name.clone(),
                 version,
+                repo: apply.tool.repo.clone(),
                 commit: apply.tool.commit.clone(),
             },
             repo: apply.repo.clone(),
@@ -51,6 +55,7 @@ impl TryFrom<&BuildfixApply> for ApplyV1 {
             results: apply.results.clone(),
             summary: apply.summary.clone(),
             auto_commit: apply.auto_commit.clone(),
+            branch: apply.branch.clone(),
             errors: apply.errors.clone(),
         })
     }
@@ -63,7 +68,7 @@ impl From<ApplyV1> for BuildfixApply {
             tool: ToolInfo {
                 name: apply.tool.name,
                 version: Some(apply.tool.version),
-                repo: None,
+                repo: apply.tool.repo,
                 commit: apply.tool.commit,
             },
             repo: apply.repo,
@@ -72,6 +77,7 @@ impl From<ApplyV1> for BuildfixApply {
             results: apply.results,
             summary: apply.summary,
             auto_commit: apply.auto_commit,
+            branch: apply.branch,
             errors: apply.errors,
         }
     }
@@ -2,10 +2,14 @@ use serde::{Deserialize, Serialize};
 
 pub mod apply_v1;
 pub mod plan_v1;
+pub mod plan_v2;
+#[cfg(feature = "proto")]
+pub mod proto;
 pub mod report_v1;
 
 pub use apply_v1::ApplyV1;
 pub use plan_v1::PlanV1;
+pub use plan_v2::PlanV2;
 pub use report_v1::ReportV1;
 
 /// Tool information for wire-level schemas (schema-exact).
@@ -14,6 +18,8 @@ pub struct ToolInfoV1 {
     pub name: String,
     pub version: String,
     #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub repo: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
     pub commit: Option<String>,
 }
 
@@ -44,6 +50,7 @@ mod tests {
         let tool = ToolInfoV1 {
             name: "buildfix".to_string(),
             version: "1.2.3".to_string(),
+            repo: None,
             commit: None,
         };
 
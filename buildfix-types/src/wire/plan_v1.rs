@@ -1,7 +1,8 @@
 use serde::{Deserialize, Serialize};
 
 use crate::plan::{
-    BuildfixPlan, PlanInput, PlanOp, PlanPolicy, PlanPreconditions, PlanSummary, RepoInfo,
+    BuildfixPlan, PlanCapabilities, PlanInput, PlanOp, PlanPolicy, PlanPreconditions, PlanSummary,
+    PlanWorkspace, RepoInfo,
 };
 use crate::receipt::ToolInfo;
 use crate::wire::{ToolInfoV1, WireError};
@@ -25,6 +26,15 @@ pub struct PlanV1 {
     pub ops: Vec<PlanOp>,
 
     pub summary: PlanSummary,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<PlanCapabilities>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<PlanWorkspace>,
 }
 
 impl TryFrom<&BuildfixPlan> for PlanV1 {
@@ -42,6 +52,7 @@ impl TryFrom<&BuildfixPlan> for PlanV1 {
             tool: ToolInfoV1 {
                 name: plan.tool.name.clone(),
                 version,
+                repo: plan.tool.repo.clone(),
                 commit: plan.tool.commit.clone(),
             },
             repo: plan.repo.clone(),
@@ -50,6 +61,9 @@ impl TryFrom<&BuildfixPlan> for PlanV1 {
             preconditions: plan.preconditions.clone(),
             ops: plan.ops.clone(),
             summary: plan.summary.clone(),
+            capabilities: plan.capabilities.clone(),
+            expires_at: plan.expires_at.clone(),
+            workspaces: plan.workspaces.clone(),
         })
     }
 }
@@ -61,7 +75,7 @@ impl From<PlanV1> for BuildfixPlan {
             tool: ToolInfo {
                 name: plan.tool.name,
                 version: Some(plan.tool.version),
-                repo: None,
+                repo: plan.tool.repo,
                 commit: plan.tool.commit,
             },
             repo: plan.repo,
@@ -70,6 +84,9 @@ impl From<PlanV1> for BuildfixPlan {
             preconditions: plan.preconditions,
             ops: plan.ops,
             summary: plan.summary,
+            capabilities: plan.capabilities,
+            expires_at: plan.expires_at,
+            workspaces: plan.workspaces,
         }
     }
 }
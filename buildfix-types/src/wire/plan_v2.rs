@@ -0,0 +1,231 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+
+use crate::error_code::BuildfixErrorCode;
+use crate::ops::{OpKind, OpPreview, OpTarget, SafetyClass};
+use crate::plan::{
+    BuildfixPlan, OpImpact, PlanCapabilities, PlanInput, PlanOp, PlanPolicy, PlanPreconditions,
+    PlanSummary, PlanWorkspace, Rationale, RepoInfo,
+};
+use crate::receipt::Severity;
+use crate::wire::{PlanV1, ToolInfoV1, WireError};
+
+/// Schema-exact wire representation of buildfix.plan.v2.
+///
+/// Carries the same ops as v1, grouped by fixer, with per-op dependencies,
+/// severity, impact and parameter metadata derived from them. Converting to
+/// v1 and back is lossless for plans whose ops are contiguous by `fix_key`
+/// and carry `impact`, both of which the planner guarantees.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanV2 {
+    pub schema: String,
+    pub tool: ToolInfoV1,
+    pub repo: RepoInfo,
+
+    #[serde(default)]
+    pub inputs: Vec<PlanInput>,
+
+    pub policy: PlanPolicy,
+
+    #[serde(default)]
+    pub preconditions: PlanPreconditions,
+
+    #[serde(default)]
+    pub groups: Vec<OpGroupV2>,
+
+    pub summary: PlanSummary,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<PlanCapabilities>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub workspaces: Vec<PlanWorkspace>,
+}
+
+/// Ops produced by one fixer, in plan order.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct OpGroupV2 {
+    pub fix_key: String,
+
+    #[serde(default)]
+    pub ops: Vec<PlanOpV2>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PlanOpV2 {
+    pub id: String,
+    pub safety: SafetyClass,
+    pub blocked: bool,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_reason: Option<String>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub blocked_reason_token: Option<BuildfixErrorCode>,
+
+    pub target: OpTarget,
+    pub kind: OpKind,
+    pub rationale: Rationale,
+
+    /// Ids of ops that must apply first: the previous op on the same file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub depends_on: Vec<String>,
+
+    /// Highest severity among the op's findings, when any recorded one.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub severity: Option<Severity>,
+
+    /// The op's planned impact, or the estimate from its kind when the
+    /// plan predates impact metadata.
+    #[serde(default)]
+    pub impact: OpImpact,
+
+    /// Parameters the op still needs before it can apply.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub params: Vec<OpParamV2>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub preview: Option<OpPreview>,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub workspace: Option<String>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct OpParamV2 {
+    pub name: String,
+
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+}
+
+impl OpParamV2 {
+    pub fn new(name: impl Into<String>) -> Self {
+        let name = name.into();
+        let description = param_description(&name).map(str::to_string);
+        Self { name, description }
+    }
+}
+
+fn param_description(name: &str) -> Option<&'static str> {
+    match name {
+        "rust_version" => Some("Target rust-version when there is no workspace standard"),
+        "version" => Some("Version to add to a path dependency"),
+        "edition" => Some("Edition to set when there is no workspace standard"),
+        "license" => Some("SPDX license expression to set"),
+        _ => None,
+    }
+}
+
+impl From<PlanV1> for PlanV2 {
+    fn from(plan: PlanV1) -> Self {
+        let mut last_on_path: HashMap<String, String> = HashMap::new();
+        let mut groups: Vec<OpGroupV2> = Vec::new();
+
+        for op in plan.ops {
+            let depends_on = last_on_path
+                .insert(op.target.path.clone(), op.id.clone())
+                .into_iter()
+                .collect();
+            let severity = op
+                .rationale
+                .findings
+                .iter()
+                .filter_map(|f| f.severity)
+                .max();
+            let op = PlanOpV2 {
+                impact: op.impact.unwrap_or_else(|| OpImpact::estimate(&op.kind)),
+                params: op.params_required.into_iter().map(OpParamV2::new).collect(),
+                id: op.id,
+                safety: op.safety,
+                blocked: op.blocked,
+                blocked_reason: op.blocked_reason,
+                blocked_reason_token: op.blocked_reason_token,
+                target: op.target,
+                kind: op.kind,
+                rationale: op.rationale,
+                depends_on,
+                severity,
+                preview: op.preview,
+                workspace: op.workspace,
+            };
+
+            match groups.last_mut() {
+                Some(group) if group.fix_key == op.rationale.fix_key => group.ops.push(op),
+                _ => groups.push(OpGroupV2 {
+                    fix_key: op.rationale.fix_key.clone(),
+                    ops: vec![op],
+                }),
+            }
+        }
+
+        Self {
+            schema: crate::schema::BUILDFIX_PLAN_V2.to_string(),
+            tool: plan.tool,
+            repo: plan.repo,
+            inputs: plan.inputs,
+            policy: plan.policy,
+            preconditions: plan.preconditions,
+            groups,
+            summary: plan.summary,
+            capabilities: plan.capabilities,
+            expires_at: plan.expires_at,
+            workspaces: plan.workspaces,
+        }
+    }
+}
+
+impl From<PlanV2> for PlanV1 {
+    fn from(plan: PlanV2) -> Self {
+        let ops = plan
+            .groups
+            .into_iter()
+            .flat_map(|group| group.ops)
+            .map(|op| PlanOp {
+                id: op.id,
+                safety: op.safety,
+                blocked: op.blocked,
+                blocked_reason: op.blocked_reason,
+                blocked_reason_token: op.blocked_reason_token,
+                target: op.target,
+                kind: op.kind,
+                rationale: op.rationale,
+                params_required: op.params.into_iter().map(|p| p.name).collect(),
+                preview: op.preview,
+                impact: Some(op.impact),
+                workspace: op.workspace,
+            })
+            .collect();
+
+        Self {
+            schema: crate::schema::BUILDFIX_PLAN_V1.to_string(),
+            tool: plan.tool,
+            repo: plan.repo,
+            inputs: plan.inputs,
+            policy: plan.policy,
+            preconditions: plan.preconditions,
+            ops,
+            summary: plan.summary,
+            capabilities: plan.capabilities,
+            expires_at: plan.expires_at,
+            workspaces: plan.workspaces,
+        }
+    }
+}
+
+impl TryFrom<&BuildfixPlan> for PlanV2 {
+    type Error = WireError;
+
+    fn try_from(plan: &BuildfixPlan) -> Result<Self, Self::Error> {
+        PlanV1::try_from(plan).map(Self::from)
+    }
+}
+
+impl From<PlanV2> for BuildfixPlan {
+    fn from(plan: PlanV2) -> Self {
+        PlanV1::from(plan).into()
+    }
+}
@@ -0,0 +1,929 @@
+//! Compact protobuf encoding of [`PlanV1`], [`ApplyV1`] and [`ReportV1`].
+//!
+//! Meant for stores that keep and parse artifacts in bulk, where JSON's
+//! repeated keys dominate. Identity, status and count fields are typed
+//! protobuf fields; nested structures that evolve with the JSON schemas (op
+//! kinds, rationale, policy, preconditions, tool-specific `data`, ...) are
+//! carried as embedded JSON, so every field of the JSON artifact survives a
+//! round trip. `proto/buildfix.v1.proto` holds the same messages for
+//! non-Rust readers.
+
+use prost::Message;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+
+use crate::apply::{ApplyFile, ApplyResult, ApplyStatus, ApplySummary};
+use crate::error_code::BuildfixErrorCode;
+use crate::ops::{OpPreview, OpTarget, SafetyClass};
+use crate::plan::{PlanInput, PlanOp, PlanSummary, PlanWorkspace, SafetyCounts};
+use crate::report::{
+    ReportCounts, ReportFinding, ReportLocation, ReportRunInfo, ReportSeverity, ReportStatus,
+    ReportVerdict,
+};
+use crate::wire::{ApplyV1, PlanV1, ReportV1, ToolInfoV1};
+
+/// Errors decoding a protobuf artifact.
+#[derive(Debug)]
+pub enum ProtoError {
+    Decode(prost::DecodeError),
+    /// An embedded JSON field does not match its type.
+    Json {
+        field: &'static str,
+        source: serde_json::Error,
+    },
+    /// A field holds a value this release does not know.
+    Invalid {
+        field: &'static str,
+        value: String,
+    },
+}
+
+impl std::fmt::Display for ProtoError {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            ProtoError::Decode(err) => write!(f, "invalid protobuf: {}", err),
+            ProtoError::Json { field, source } => write!(f, "invalid {}: {}", field, source),
+            ProtoError::Invalid { field, value } => write!(f, "unknown {} {}", field, value),
+        }
+    }
+}
+
+impl std::error::Error for ProtoError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            ProtoError::Decode(err) => Some(err),
+            ProtoError::Json { source, .. } => Some(source),
+            ProtoError::Invalid { .. } => None,
+        }
+    }
+}
+
+impl From<prost::DecodeError> for ProtoError {
+    fn from(err: prost::DecodeError) -> Self {
+        ProtoError::Decode(err)
+    }
+}
+
+/// Encode a plan as protobuf bytes.
+pub fn encode_plan(plan: &PlanV1) -> Vec<u8> {
+    PlanProto::from(plan).encode_to_vec()
+}
+
+pub fn decode_plan(bytes: &[u8]) -> Result<PlanV1, ProtoError> {
+    PlanV1::try_from(PlanProto::decode(bytes)?)
+}
+
+/// Encode an apply result as protobuf bytes.
+pub fn encode_apply(apply: &ApplyV1) -> Vec<u8> {
+    ApplyProto::from(apply).encode_to_vec()
+}
+
+pub fn decode_apply(bytes: &[u8]) -> Result<ApplyV1, ProtoError> {
+    ApplyV1::try_from(ApplyProto::decode(bytes)?)
+}
+
+/// Encode a report as protobuf bytes.
+pub fn encode_report(report: &ReportV1) -> Vec<u8> {
+    ReportProto::from(report).encode_to_vec()
+}
+
+pub fn decode_report(bytes: &[u8]) -> Result<ReportV1, ProtoError> {
+    ReportV1::try_from(ReportProto::decode(bytes)?)
+}
+
+fn to_json<T: Serialize>(value: &T) -> Vec<u8> {
+    serde_json::to_vec(value).expect("artifact types serialize to JSON")
+}
+
+fn from_json<T: DeserializeOwned>(field: &'static str, bytes: &[u8]) -> Result<T, ProtoError> {
+    serde_json::from_slice(bytes).map_err(|source| ProtoError::Json { field, source })
+}
+
+fn opt_from_json<T: DeserializeOwned>(
+    field: &'static str,
+    bytes: Option<Vec<u8>>,
+) -> Result<Option<T>, ProtoError> {
+    bytes.map(|b| from_json(field, &b)).transpose()
+}
+
+fn token_from_str(value: Option<String>) -> Result<Option<BuildfixErrorCode>, ProtoError> {
+    value
+        .map(|token| {
+            token.parse().map_err(|_| ProtoError::Invalid {
+                field: "blocked_reason_token",
+                value: token,
+            })
+        })
+        .transpose()
+}
+
+fn enum_from_i32<E: TryFrom<i32>>(field: &'static str, value: i32) -> Result<E, ProtoError> {
+    E::try_from(value).map_err(|_| ProtoError::Invalid {
+        field,
+        value: value.to_string(),
+    })
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ToolProto {
+    #[prost(string, tag = "1")]
+    pub name: String,
+    #[prost(string, tag = "2")]
+    pub version: String,
+    #[prost(string, optional, tag = "3")]
+    pub repo: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub commit: Option<String>,
+}
+
+impl From<&ToolInfoV1> for ToolProto {
+    fn from(tool: &ToolInfoV1) -> Self {
+        Self {
+            name: tool.name.clone(),
+            version: tool.version.clone(),
+            repo: tool.repo.clone(),
+            commit: tool.commit.clone(),
+        }
+    }
+}
+
+impl From<ToolProto> for ToolInfoV1 {
+    fn from(tool: ToolProto) -> Self {
+        Self {
+            name: tool.name,
+            version: tool.version,
+            repo: tool.repo,
+            commit: tool.commit,
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Plan
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum SafetyProto {
+    Unspecified = 0,
+    Safe = 1,
+    Guarded = 2,
+    Unsafe = 3,
+}
+
+impl From<SafetyClass> for SafetyProto {
+    fn from(safety: SafetyClass) -> Self {
+        match safety {
+            SafetyClass::Safe => SafetyProto::Safe,
+            SafetyClass::Guarded => SafetyProto::Guarded,
+            SafetyClass::Unsafe => SafetyProto::Unsafe,
+        }
+    }
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PlanProto {
+    #[prost(string, tag = "1")]
+    pub schema: String,
+    #[prost(message, optional, tag = "2")]
+    pub tool: Option<ToolProto>,
+    /// JSON `repo` object.
+    #[prost(bytes = "vec", tag = "3")]
+    pub repo: Vec<u8>,
+    #[prost(message, repeated, tag = "4")]
+    pub inputs: Vec<PlanInputProto>,
+    /// JSON `policy` object.
+    #[prost(bytes = "vec", tag = "5")]
+    pub policy: Vec<u8>,
+    /// JSON `preconditions` object.
+    #[prost(bytes = "vec", tag = "6")]
+    pub preconditions: Vec<u8>,
+    #[prost(message, repeated, tag = "7")]
+    pub ops: Vec<PlanOpProto>,
+    #[prost(message, optional, tag = "8")]
+    pub summary: Option<PlanSummaryProto>,
+    /// JSON `capabilities` object.
+    #[prost(bytes = "vec", optional, tag = "9")]
+    pub capabilities: Option<Vec<u8>>,
+    #[prost(string, optional, tag = "10")]
+    pub expires_at: Option<String>,
+    #[prost(message, repeated, tag = "11")]
+    pub workspaces: Vec<PlanWorkspaceProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PlanInputProto {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(string, optional, tag = "2")]
+    pub schema: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub tool: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub git_head_sha: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub generated_at: Option<String>,
+    #[prost(string, optional, tag = "6")]
+    pub sha256: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PlanOpProto {
+    #[prost(string, tag = "1")]
+    pub id: String,
+    #[prost(enumeration = "SafetyProto", tag = "2")]
+    pub safety: i32,
+    #[prost(bool, tag = "3")]
+    pub blocked: bool,
+    #[prost(string, optional, tag = "4")]
+    pub blocked_reason: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub blocked_reason_token: Option<String>,
+    #[prost(string, tag = "6")]
+    pub target_path: String,
+    #[prost(string, optional, tag = "7")]
+    pub target_sha256: Option<String>,
+    /// JSON `kind` object.
+    #[prost(bytes = "vec", tag = "8")]
+    pub kind: Vec<u8>,
+    /// JSON `rationale` object.
+    #[prost(bytes = "vec", tag = "9")]
+    pub rationale: Vec<u8>,
+    #[prost(string, repeated, tag = "10")]
+    pub params_required: Vec<String>,
+    #[prost(string, optional, tag = "11")]
+    pub preview_patch_fragment: Option<String>,
+    /// JSON `impact` object.
+    #[prost(bytes = "vec", optional, tag = "12")]
+    pub impact: Option<Vec<u8>>,
+    #[prost(string, optional, tag = "13")]
+    pub workspace: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PlanSummaryProto {
+    #[prost(uint64, tag = "1")]
+    pub ops_total: u64,
+    #[prost(uint64, tag = "2")]
+    pub ops_blocked: u64,
+    #[prost(uint64, tag = "3")]
+    pub files_touched: u64,
+    #[prost(uint64, optional, tag = "4")]
+    pub patch_bytes: Option<u64>,
+    #[prost(message, optional, tag = "5")]
+    pub safety_counts: Option<SafetyCountsProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct PlanWorkspaceProto {
+    #[prost(string, tag = "1")]
+    pub root: String,
+    #[prost(message, optional, tag = "2")]
+    pub summary: Option<PlanSummaryProto>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct SafetyCountsProto {
+    #[prost(uint64, tag = "1")]
+    pub safe: u64,
+    #[prost(uint64, tag = "2")]
+    pub guarded: u64,
+    #[prost(uint64, tag = "3")]
+    pub unsafe_count: u64,
+}
+
+impl From<&PlanV1> for PlanProto {
+    fn from(plan: &PlanV1) -> Self {
+        Self {
+            schema: plan.schema.clone(),
+            tool: Some(ToolProto::from(&plan.tool)),
+            repo: to_json(&plan.repo),
+            inputs: plan.inputs.iter().map(PlanInputProto::from).collect(),
+            policy: to_json(&plan.policy),
+            preconditions: to_json(&plan.preconditions),
+            ops: plan.ops.iter().map(PlanOpProto::from).collect(),
+            summary: Some(PlanSummaryProto::from(&plan.summary)),
+            capabilities: plan.capabilities.as_ref().map(to_json),
+            expires_at: plan.expires_at.clone(),
+            workspaces: plan
+                .workspaces
+                .iter()
+                .map(PlanWorkspaceProto::from)
+                .collect(),
+        }
+    }
+}
+
+impl TryFrom<PlanProto> for PlanV1 {
+    type Error = ProtoError;
+
+    fn try_from(plan: PlanProto) -> Result<Self, Self::Error> {
+        Ok(Self {
+            schema: plan.schema,
+            tool: plan.tool.unwrap_or_default().into(),
+            repo: from_json("repo", &plan.repo)?,
+            inputs: plan.inputs.into_iter().map(PlanInput::from).collect(),
+            policy: from_json("policy", &plan.policy)?,
+            preconditions: from_json("preconditions", &plan.preconditions)?,
+            ops: plan
+                .ops
+                .into_iter()
+                .map(PlanOp::try_from)
+                .collect::<Result<_, _>>()?,
+            summary: plan.summary.unwrap_or_default().into(),
+            capabilities: opt_from_json("capabilities", plan.capabilities)?,
+            expires_at: plan.expires_at,
+            workspaces: plan
+                .workspaces
+                .into_iter()
+                .map(PlanWorkspace::from)
+                .collect(),
+        })
+    }
+}
+
+impl From<&PlanInput> for PlanInputProto {
+    fn from(input: &PlanInput) -> Self {
+        Self {
+            path: input.path.clone(),
+            schema: input.schema.clone(),
+            tool: input.tool.clone(),
+            git_head_sha: input.git_head_sha.clone(),
+            generated_at: input.generated_at.clone(),
+            sha256: input.sha256.clone(),
+        }
+    }
+}
+
+impl From<PlanInputProto> for PlanInput {
+    fn from(input: PlanInputProto) -> Self {
+        Self {
+            path: input.path,
+            schema: input.schema,
+            tool: input.tool,
+            git_head_sha: input.git_head_sha,
+            generated_at: input.generated_at,
+            sha256: input.sha256,
+        }
+    }
+}
+
+impl From<&PlanOp> for PlanOpProto {
+    fn from(op: &PlanOp) -> Self {
+        Self {
+            id: op.id.clone(),
+            safety: SafetyProto::from(op.safety) as i32,
+            blocked: op.blocked,
+            blocked_reason: op.blocked_reason.clone(),
+            blocked_reason_token: op.blocked_reason_token.map(|c| c.as_str().to_string()),
+            target_path: op.target.path.clone(),
+            target_sha256: op.target.sha256.clone(),
+            kind: to_json(&op.kind),
+            rationale: to_json(&op.rationale),
+            params_required: op.params_required.clone(),
+            preview_patch_fragment: op.preview.as_ref().map(|p| p.patch_fragment.clone()),
+            impact: op.impact.as_ref().map(to_json),
+            workspace: op.workspace.clone(),
+        }
+    }
+}
+
+impl TryFrom<PlanOpProto> for PlanOp {
+    type Error = ProtoError;
+
+    fn try_from(op: PlanOpProto) -> Result<Self, Self::Error> {
+        let safety = match enum_from_i32("safety", op.safety)? {
+            SafetyProto::Safe => SafetyClass::Safe,
+            SafetyProto::Guarded => SafetyClass::Guarded,
+            SafetyProto::Unsafe => SafetyClass::Unsafe,
+            SafetyProto::Unspecified => {
+                return Err(ProtoError::Invalid {
+                    field: "safety",
+                    value: op.safety.to_string(),
+                });
+            }
+        };
+        Ok(Self {
+            id: op.id,
+            safety,
+            blocked: op.blocked,
+            blocked_reason: op.blocked_reason,
+            blocked_reason_token: token_from_str(op.blocked_reason_token)?,
+            target: OpTarget {
+                path: op.target_path,
+                sha256: op.target_sha256,
+            },
+            kind: from_json("kind", &op.kind)?,
+            rationale: from_json("rationale", &op.rationale)?,
+            params_required: op.params_required,
+            preview: op
+                .preview_patch_fragment
+                .map(|patch_fragment| OpPreview { patch_fragment }),
+            impact: opt_from_json("impact", op.impact)?,
+            workspace: op.workspace,
+        })
+    }
+}
+
+impl From<&PlanSummary> for PlanSummaryProto {
+    fn from(summary: &PlanSummary) -> Self {
+        Self {
+            ops_total: summary.ops_total,
+            ops_blocked: summary.ops_blocked,
+            files_touched: summary.files_touched,
+            patch_bytes: summary.patch_bytes,
+            safety_counts: summary.safety_counts.as_ref().map(|sc| SafetyCountsProto {
+                safe: sc.safe,
+                guarded: sc.guarded,
+                unsafe_count: sc.unsafe_count,
+            }),
+        }
+    }
+}
+
+impl From<PlanSummaryProto> for PlanSummary {
+    fn from(summary: PlanSummaryProto) -> Self {
+        Self {
+            ops_total: summary.ops_total,
+            ops_blocked: summary.ops_blocked,
+            files_touched: summary.files_touched,
+            patch_bytes: summary.patch_bytes,
+            safety_counts: summary.safety_counts.map(|sc| SafetyCounts {
+                safe: sc.safe,
+                guarded: sc.guarded,
+                unsafe_count: sc.unsafe_count,
+            }),
+        }
+    }
+}
+
+impl From<&PlanWorkspace> for PlanWorkspaceProto {
+    fn from(workspace: &PlanWorkspace) -> Self {
+        Self {
+            root: workspace.root.clone(),
+            summary: Some(PlanSummaryProto::from(&workspace.summary)),
+        }
+    }
+}
+
+impl From<PlanWorkspaceProto> for PlanWorkspace {
+    fn from(workspace: PlanWorkspaceProto) -> Self {
+        Self {
+            root: workspace.root,
+            summary: workspace.summary.unwrap_or_default().into(),
+        }
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Apply
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum ApplyStatusProto {
+    Unspecified = 0,
+    Applied = 1,
+    Blocked = 2,
+    Failed = 3,
+    Skipped = 4,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ApplyProto {
+    #[prost(string, tag = "1")]
+    pub schema: String,
+    #[prost(message, optional, tag = "2")]
+    pub tool: Option<ToolProto>,
+    /// JSON `repo` object.
+    #[prost(bytes = "vec", tag = "3")]
+    pub repo: Vec<u8>,
+    /// JSON `plan_ref` object.
+    #[prost(bytes = "vec", tag = "4")]
+    pub plan_ref: Vec<u8>,
+    /// JSON `preconditions` object.
+    #[prost(bytes = "vec", tag = "5")]
+    pub preconditions: Vec<u8>,
+    #[prost(message, repeated, tag = "6")]
+    pub results: Vec<ApplyResultProto>,
+    #[prost(message, optional, tag = "7")]
+    pub summary: Option<ApplySummaryProto>,
+    /// JSON `auto_commit` object.
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub auto_commit: Option<Vec<u8>>,
+    /// JSON `branch` object.
+    #[prost(bytes = "vec", optional, tag = "9")]
+    pub branch: Option<Vec<u8>>,
+    #[prost(string, repeated, tag = "10")]
+    pub errors: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ApplyResultProto {
+    #[prost(string, tag = "1")]
+    pub op_id: String,
+    #[prost(enumeration = "ApplyStatusProto", tag = "2")]
+    pub status: i32,
+    #[prost(string, optional, tag = "3")]
+    pub message: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub blocked_reason: Option<String>,
+    #[prost(string, optional, tag = "5")]
+    pub blocked_reason_token: Option<String>,
+    #[prost(message, repeated, tag = "6")]
+    pub files: Vec<ApplyFileProto>,
+    #[prost(uint64, optional, tag = "7")]
+    pub duration_ms: Option<u64>,
+    #[prost(uint32, optional, tag = "8")]
+    pub attempts: Option<u32>,
+    #[prost(bool, optional, tag = "9")]
+    pub verified: Option<bool>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ApplyFileProto {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(string, optional, tag = "2")]
+    pub sha256_before: Option<String>,
+    #[prost(string, optional, tag = "3")]
+    pub sha256_after: Option<String>,
+    #[prost(string, optional, tag = "4")]
+    pub backup_path: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ApplySummaryProto {
+    #[prost(uint64, tag = "1")]
+    pub attempted: u64,
+    #[prost(uint64, tag = "2")]
+    pub applied: u64,
+    #[prost(uint64, tag = "3")]
+    pub blocked: u64,
+    #[prost(uint64, tag = "4")]
+    pub failed: u64,
+    #[prost(uint64, tag = "5")]
+    pub files_modified: u64,
+}
+
+impl From<&ApplyV1> for ApplyProto {
+    fn from(apply: &ApplyV1) -> Self {
+        Self {
+            schema: apply.schema.clone(),
+            tool: Some(ToolProto::from(&apply.tool)),
+            repo: to_json(&apply.repo),
+            plan_ref: to_json(&apply.plan_ref),
+            preconditions: to_json(&apply.preconditions),
+            results: apply.results.iter().map(ApplyResultProto::from).collect(),
+            summary: Some(ApplySummaryProto {
+                attempted: apply.summary.attempted,
+                applied: apply.summary.applied,
+                blocked: apply.summary.blocked,
+                failed: apply.summary.failed,
+                files_modified: apply.summary.files_modified,
+            }),
+            auto_commit: apply.auto_commit.as_ref().map(to_json),
+            branch: apply.branch.as_ref().map(to_json),
+            errors: apply.errors.clone(),
+        }
+    }
+}
+
+impl TryFrom<ApplyProto> for ApplyV1 {
+    type Error = ProtoError;
+
+    fn try_from(apply: ApplyProto) -> Result<Self, Self::Error> {
+        let summary = apply.summary.unwrap_or_default();
+        Ok(Self {
+            schema: apply.schema,
+            tool: apply.tool.unwrap_or_default().into(),
+            repo: from_json("repo", &apply.repo)?,
+            plan_ref: from_json("plan_ref", &apply.plan_ref)?,
+            preconditions: from_json("preconditions", &apply.preconditions)?,
+            results: apply
+                .results
+                .into_iter()
+                .map(ApplyResult::try_from)
+                .collect::<Result<_, _>>()?,
+            summary: ApplySummary {
+                attempted: summary.attempted,
+                applied: summary.applied,
+                blocked: summary.blocked,
+                failed: summary.failed,
+                files_modified: summary.files_modified,
+            },
+            auto_commit: opt_from_json("auto_commit", apply.auto_commit)?,
+            branch: opt_from_json("branch", apply.branch)?,
+            errors: apply.errors,
+        })
+    }
+}
+
+impl From<&ApplyResult> for ApplyResultProto {
+    fn from(result: &ApplyResult) -> Self {
+        let status = match result.status {
+            ApplyStatus::Applied => ApplyStatusProto::Applied,
+            ApplyStatus::Blocked => ApplyStatusProto::Blocked,
+            ApplyStatus::Failed => ApplyStatusProto::Failed,
+            ApplyStatus::Skipped => ApplyStatusProto::Skipped,
+        };
+        Self {
+            op_id: result.op_id.clone(),
+            status: status as i32,
+            message: result.message.clone(),
+            blocked_reason: result.blocked_reason.clone(),
+            blocked_reason_token: result.blocked_reason_token.map(|c| c.as_str().to_string()),
+            files: result
+                .files
+                .iter()
+                .map(|file| ApplyFileProto {
+                    path: file.path.clone(),
+                    sha256_before: file.sha256_before.clone(),
+                    sha256_after: file.sha256_after.clone(),
+                    backup_path: file.backup_path.clone(),
+                })
+                .collect(),
+            duration_ms: result.duration_ms,
+            attempts: result.attempts,
+            verified: result.verified,
+        }
+    }
+}
+
+impl TryFrom<ApplyResultProto> for ApplyResult {
+    type Error = ProtoError;
+
+    fn try_from(result: ApplyResultProto) -> Result<Self, Self::Error> {
+        let status = match enum_from_i32("status", result.status)? {
+            ApplyStatusProto::Applied => ApplyStatus::Applied,
+            ApplyStatusProto::Blocked => ApplyStatus::Blocked,
+            ApplyStatusProto::Failed => ApplyStatus::Failed,
+            ApplyStatusProto::Skipped => ApplyStatus::Skipped,
+            ApplyStatusProto::Unspecified => {
+                return Err(ProtoError::Invalid {
+                    field: "status",
+                    value: result.status.to_string(),
+                });
+            }
+        };
+        Ok(Self {
+            op_id: result.op_id,
+            status,
+            message: result.message,
+            blocked_reason: result.blocked_reason,
+            blocked_reason_token: token_from_str(result.blocked_reason_token)?,
+            files: result
+                .files
+                .into_iter()
+                .map(|file| ApplyFile {
+                    path: file.path,
+                    sha256_before: file.sha256_before,
+                    sha256_after: file.sha256_after,
+                    backup_path: file.backup_path,
+                })
+                .collect(),
+            duration_ms: result.duration_ms,
+            attempts: result.attempts,
+            verified: result.verified,
+        })
+    }
+}
+
+// ---------------------------------------------------------------------------
+// Report
+// ---------------------------------------------------------------------------
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum ReportStatusProto {
+    Unspecified = 0,
+    Pass = 1,
+    Warn = 2,
+    Fail = 3,
+    Skip = 4,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Hash, PartialOrd, Ord, prost::Enumeration)]
+#[repr(i32)]
+pub enum ReportSeverityProto {
+    Unspecified = 0,
+    Info = 1,
+    Warn = 2,
+    Error = 3,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ReportProto {
+    #[prost(string, tag = "1")]
+    pub schema: String,
+    #[prost(message, optional, tag = "2")]
+    pub tool: Option<ToolProto>,
+    #[prost(message, optional, tag = "3")]
+    pub run: Option<ReportRunProto>,
+    #[prost(message, optional, tag = "4")]
+    pub verdict: Option<ReportVerdictProto>,
+    #[prost(message, repeated, tag = "5")]
+    pub findings: Vec<ReportFindingProto>,
+    /// JSON `capabilities` object.
+    #[prost(bytes = "vec", optional, tag = "6")]
+    pub capabilities: Option<Vec<u8>>,
+    /// JSON `artifacts` object.
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub artifacts: Option<Vec<u8>>,
+    /// JSON `data` object.
+    #[prost(bytes = "vec", optional, tag = "8")]
+    pub data: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ReportRunProto {
+    #[prost(string, tag = "1")]
+    pub started_at: String,
+    #[prost(string, optional, tag = "2")]
+    pub ended_at: Option<String>,
+    #[prost(uint64, optional, tag = "3")]
+    pub duration_ms: Option<u64>,
+    #[prost(string, optional, tag = "4")]
+    pub git_head_sha: Option<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ReportVerdictProto {
+    #[prost(enumeration = "ReportStatusProto", tag = "1")]
+    pub status: i32,
+    #[prost(uint64, tag = "2")]
+    pub info: u64,
+    #[prost(uint64, tag = "3")]
+    pub warn: u64,
+    #[prost(uint64, tag = "4")]
+    pub error: u64,
+    #[prost(string, repeated, tag = "5")]
+    pub reasons: Vec<String>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ReportFindingProto {
+    #[prost(enumeration = "ReportSeverityProto", tag = "1")]
+    pub severity: i32,
+    #[prost(string, optional, tag = "2")]
+    pub check_id: Option<String>,
+    #[prost(string, tag = "3")]
+    pub code: String,
+    #[prost(string, tag = "4")]
+    pub message: String,
+    #[prost(message, optional, tag = "5")]
+    pub location: Option<ReportLocationProto>,
+    #[prost(string, optional, tag = "6")]
+    pub fingerprint: Option<String>,
+    /// JSON `data` object.
+    #[prost(bytes = "vec", optional, tag = "7")]
+    pub data: Option<Vec<u8>>,
+}
+
+#[derive(Clone, PartialEq, Message)]
+pub struct ReportLocationProto {
+    #[prost(string, tag = "1")]
+    pub path: String,
+    #[prost(uint64, optional, tag = "2")]
+    pub line: Option<u64>,
+    #[prost(uint64, optional, tag = "3")]
+    pub col: Option<u64>,
+}
+
+impl From<&ReportV1> for ReportProto {
+    fn from(report: &ReportV1) -> Self {
+        let status = match report.verdict.status {
+            ReportStatus::Pass => ReportStatusProto::Pass,
+            ReportStatus::Warn => ReportStatusProto::Warn,
+            ReportStatus::Fail => ReportStatusProto::Fail,
+            ReportStatus::Skip => ReportStatusProto::Skip,
+        };
+        Self {
+            schema: report.schema.clone(),
+            tool: Some(ToolProto::from(&report.tool)),
+            run: Some(ReportRunProto {
+                started_at: report.run.started_at.clone(),
+                ended_at: report.run.ended_at.clone(),
+                duration_ms: report.run.duration_ms,
+                git_head_sha: report.run.git_head_sha.clone(),
+            }),
+            verdict: Some(ReportVerdictProto {
+                status: status as i32,
+                info: report.verdict.counts.info,
+                warn: report.verdict.counts.warn,
+                error: report.verdict.counts.error,
+                reasons: report.verdict.reasons.clone(),
+            }),
+            findings: report
+                .findings
+                .iter()
+                .map(ReportFindingProto::from)
+                .collect(),
+            capabilities: report.capabilities.as_ref().map(to_json),
+            artifacts: report.artifacts.as_ref().map(to_json),
+            data: report.data.as_ref().map(to_json),
+        }
+    }
+}
+
+impl TryFrom<ReportProto> for ReportV1 {
+    type Error = ProtoError;
+
+    fn try_from(report: ReportProto) -> Result<Self, Self::Error> {
+        let run = report.run.unwrap_or_default();
+        let verdict = report.verdict.unwrap_or_default();
+        let status = match enum_from_i32("status", verdict.status)? {
+            ReportStatusProto::Pass => ReportStatus::Pass,
+            ReportStatusProto::Warn => ReportStatus::Warn,
+            ReportStatusProto::Fail => ReportStatus::Fail,
+            ReportStatusProto::Skip => ReportStatus::Skip,
+            ReportStatusProto::Unspecified => {
+                return Err(ProtoError::Invalid {
+                    field: "status",
+                    value: verdict.status.to_string(),
+                });
+            }
+        };
+        Ok(Self {
+            schema: report.schema,
+            tool: report.tool.unwrap_or_default().into(),
+            run: ReportRunInfo {
+                started_at: run.started_at,
+                ended_at: run.ended_at,
+                duration_ms: run.duration_ms,
+                git_head_sha: run.git_head_sha,
+            },
+            verdict: ReportVerdict {
+                status,
+                counts: ReportCounts {
+                    info: verdict.info,
+                    warn: verdict.warn,
+                    error: verdict.error,
+                },
+                reasons: verdict.reasons,
+            },
+            findings: report
+                .findings
+                .into_iter()
+                .map(ReportFinding::try_from)
+                .collect::<Result<_, _>>()?,
+            capabilities: opt_from_json("capabilities", report.capabilities)?,
+            artifacts: opt_from_json("artifacts", report.artifacts)?,
+            data: opt_from_json("data", report.data)?,
+        })
+    }
+}
+
+impl From<&ReportFinding> for ReportFindingProto {
+    fn from(finding: &ReportFinding) -> Self {
+        let severity = match finding.severity {
+            ReportSeverity::Info => ReportSeverityProto::Info,
+            ReportSeverity::Warn => ReportSeverityProto::Warn,
+            ReportSeverity::Error => ReportSeverityProto::Error,
+        };
+        Self {
+            severity: severity as i32,
+            check_id: finding.check_id.clone(),
+            code: finding.code.clone(),
+            message: finding.message.clone(),
+            location: finding.location.as_ref().map(|loc| ReportLocationProto {
+                path: loc.path.clone(),
+                line: loc.line,
+                col: loc.col,
+            }),
+            fingerprint: finding.fingerprint.clone(),
+            data: finding.data.as_ref().map(to_json),
+        }
+    }
+}
+
+impl TryFrom<ReportFindingProto> for ReportFinding {
+    type Error = ProtoError;
+
+    fn try_from(finding: ReportFindingProto) -> Result<Self, Self::Error> {
+        let severity = match enum_from_i32("severity", finding.severity)? {
+            ReportSeverityProto::Info => ReportSeverity::Info,
+            ReportSeverityProto::Warn => ReportSeverity::Warn,
+            ReportSeverityProto::Error => ReportSeverity::Error,
+            ReportSeverityProto::Unspecified => {
+                return Err(ProtoError::Invalid {
+                    field: "severity",
+                    value: finding.severity.to_string(),
+                });
+            }
+        };
+        Ok(Self {
+            severity,
+            check_id: finding.check_id,
+            code: finding.code,
+            message: finding.message,
+            location: finding.location.map(|loc| ReportLocation {
+                path: loc.path,
+                line: loc.line,
+                col: loc.col,
+            }),
+            fingerprint: finding.fingerprint,
+            data: opt_from_json("data", finding.data)?,
+        })
+    }
+}
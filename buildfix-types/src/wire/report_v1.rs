@@ -34,6 +34,7 @@ impl From<&BuildfixReport> for ReportV1 {
             tool: ToolInfoV1 {
                 name: report.tool.name.clone(),
                 version: report.tool.version.clone(),
+                repo: None,
                 commit: report.tool.commit.clone(),
             },
             run: report.run.clone(),
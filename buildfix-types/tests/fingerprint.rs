@@ -0,0 +1,83 @@
+use buildfix_types::fingerprint::{LineAnchor, finding_fingerprint, normalize_path};
+use buildfix_types::plan::FindingRef;
+use buildfix_types::receipt::{Finding, Location, Severity};
+
+fn fp(path: &str, line: LineAnchor<'_>) -> String {
+    finding_fingerprint(
+        "cargo-deny",
+        Some("bans.multiple"),
+        Some("dup"),
+        Some(path),
+        line,
+    )
+}
+
+#[test]
+fn fingerprint_is_a_stable_sha256() {
+    let a = fp("crates/a/Cargo.toml", LineAnchor::Number(3));
+    assert_eq!(a.len(), 64);
+    assert!(a.chars().all(|c| c.is_ascii_hexdigit()));
+    assert_eq!(a, fp("crates/a/Cargo.toml", LineAnchor::Number(3)));
+    assert_ne!(a, fp("crates/a/Cargo.toml", LineAnchor::Number(4)));
+    assert_ne!(a, fp("crates/b/Cargo.toml", LineAnchor::Number(3)));
+    assert_ne!(a, fp("crates/a/Cargo.toml", LineAnchor::None));
+}
+
+#[test]
+fn paths_and_line_text_are_normalized() {
+    assert_eq!(
+        normalize_path(".\\crates\\a//Cargo.toml"),
+        "crates/a/Cargo.toml"
+    );
+    assert_eq!(
+        fp("./crates/a/Cargo.toml", LineAnchor::None),
+        fp("crates\\a\\Cargo.toml", LineAnchor::None)
+    );
+    assert_eq!(
+        fp("Cargo.toml", LineAnchor::Text("  serde =   \"1\"\t")),
+        fp("Cargo.toml", LineAnchor::Text("serde = \"1\""))
+    );
+    assert_ne!(
+        fp("Cargo.toml", LineAnchor::Text("serde = \"1\"")),
+        fp("Cargo.toml", LineAnchor::Text("serde = \"2\""))
+    );
+}
+
+#[test]
+fn receipt_findings_and_finding_refs_agree() {
+    let finding = Finding {
+        severity: Severity::Warn,
+        check_id: Some("bans.multiple".to_string()),
+        code: None,
+        message: Some("ignored".to_string()),
+        location: Some(Location {
+            path: "Cargo.toml".into(),
+            line: Some(7),
+            column: Some(2),
+        }),
+        fingerprint: None,
+        data: None,
+        confidence: None,
+        provenance: None,
+        context: None,
+    };
+    let finding_ref = FindingRef {
+        source: "cargo-deny".to_string(),
+        check_id: Some("bans.multiple".to_string()),
+        code: "-".to_string(),
+        path: Some("Cargo.toml".to_string()),
+        line: Some(7),
+        fingerprint: None,
+        severity: None,
+    };
+    assert_eq!(
+        finding.stable_fingerprint("cargo-deny"),
+        finding_ref.stable_fingerprint()
+    );
+
+    let recorded = FindingRef {
+        fingerprint: Some("sensor-fp".to_string()),
+        ..finding_ref
+    };
+    assert_eq!(recorded.stable_fingerprint(), "sensor-fp");
+}
@@ -0,0 +1,80 @@
+use buildfix_types::error_code::BuildfixErrorCode;
+use buildfix_types::messages::{self, MessageCatalog, codes};
+
+#[test]
+fn english_catalog_formats_placeholders() {
+    let m = MessageCatalog::default();
+    assert!(m.is_english());
+    assert_eq!(
+        m.format(
+            codes::BLOCKED_MISSING_PARAMS,
+            &[("params", &"version, name")]
+        ),
+        "missing params: version, name"
+    );
+    assert_eq!(
+        messages::english(codes::BLOCKED_MAX_OPS, &[("actual", &3), ("limit", &2)]),
+        "caps exceeded: max_ops 3 > 2 allowed"
+    );
+}
+
+#[test]
+fn unknown_codes_fall_back_to_the_code() {
+    let m = MessageCatalog::default();
+    assert_eq!(m.lookup("no.such.code"), None);
+    assert_eq!(m.get("no.such.code"), "no.such.code");
+}
+
+#[test]
+fn every_blocked_token_has_an_english_reason() {
+    let m = MessageCatalog::default();
+    for token in [
+        BuildfixErrorCode::MissingParams,
+        BuildfixErrorCode::Denylist,
+        BuildfixErrorCode::AllowlistMissing,
+        BuildfixErrorCode::MaxOps,
+        BuildfixErrorCode::MaxFiles,
+        BuildfixErrorCode::MaxPatchBytes,
+        BuildfixErrorCode::DirtyWorkingTree,
+        BuildfixErrorCode::SafetyGuardedNotAllowed,
+        BuildfixErrorCode::SafetyUnsafeNotAllowed,
+        BuildfixErrorCode::PreconditionMismatch,
+        BuildfixErrorCode::TargetDrifted,
+        BuildfixErrorCode::StaleReceipt,
+        BuildfixErrorCode::UserFiltered,
+        BuildfixErrorCode::Cancelled,
+        BuildfixErrorCode::PathDenylist,
+        BuildfixErrorCode::PathAllowlistMissing,
+        BuildfixErrorCode::PathSafetyNotAllowed,
+        BuildfixErrorCode::ConflictingOps,
+        BuildfixErrorCode::StalePlan,
+        BuildfixErrorCode::ExpiredPlan,
+    ] {
+        assert!(
+            m.lookup(&codes::blocked(token)).is_some(),
+            "no message for {}",
+            token
+        );
+    }
+}
+
+#[test]
+fn overrides_replace_english_and_load_from_json() {
+    let m: MessageCatalog = serde_json::from_str(
+        r#"{ "md.plan.title": "buildfix-Plan", "blocked.denylist": "von Richtlinie abgelehnt ({reason})" }"#,
+    )
+    .unwrap();
+    assert!(!m.is_english());
+    assert_eq!(m.get(codes::MD_PLAN_TITLE), "buildfix-Plan");
+    assert_eq!(m.get(codes::MD_PLAN_OPS), "Ops");
+    assert_eq!(
+        m.blocked_reason(Some(BuildfixErrorCode::Denylist), "denied by policy"),
+        "von Richtlinie abgelehnt (denied by policy)"
+    );
+    // Reasons without an override, or without a token, stay as recorded.
+    assert_eq!(
+        m.blocked_reason(Some(BuildfixErrorCode::MaxOps), "caps exceeded"),
+        "caps exceeded"
+    );
+    assert_eq!(m.blocked_reason(None, "preblocked"), "preblocked");
+}
@@ -0,0 +1,131 @@
+use buildfix_types::migrate::{MigrateError, PLAN_SCHEMAS, downgrade_plan, upgrade_plan};
+use buildfix_types::plan::{BuildfixPlan, PlanPolicy, RepoInfo};
+use buildfix_types::receipt::ToolInfo;
+use buildfix_types::schema::{BUILDFIX_PLAN_V1, BUILDFIX_PLAN_V2};
+use serde_json::json;
+
+fn plan() -> BuildfixPlan {
+    let tool = ToolInfo {
+        name: "buildfix".to_string(),
+        version: Some("1.0.0".to_string()),
+        repo: None,
+        commit: None,
+    };
+    let repo = RepoInfo {
+        root: "/repo".to_string(),
+        head_sha: None,
+        dirty: None,
+        remote_url: None,
+        ci: None,
+    };
+    BuildfixPlan::new(tool, repo, PlanPolicy::default())
+}
+
+#[test]
+fn current_plans_upgrade_without_notes() {
+    let value = downgrade_plan(&plan(), BUILDFIX_PLAN_V1).expect("downgrade");
+    let migrated = upgrade_plan(value).expect("upgrade");
+    assert!(migrated.report.is_current(), "{:?}", migrated.report);
+    assert_eq!(migrated.value.tool.version.as_deref(), Some("1.0.0"));
+}
+
+#[test]
+fn v2_plans_upgrade_with_a_conversion_note() {
+    let value = downgrade_plan(&plan(), BUILDFIX_PLAN_V2).expect("downgrade");
+    assert_eq!(value["schema"], BUILDFIX_PLAN_V2);
+    assert!(value.get("groups").is_some());
+
+    let migrated = upgrade_plan(value).expect("upgrade");
+    assert_eq!(migrated.value.schema, BUILDFIX_PLAN_V1);
+    assert_eq!(migrated.report.from.as_deref(), Some(BUILDFIX_PLAN_V2));
+    assert_eq!(migrated.report.to, BUILDFIX_PLAN_V1);
+    assert!(!migrated.report.is_current());
+}
+
+#[test]
+fn legacy_plans_fill_defaults_and_report_each_gap() {
+    let legacy = json!({
+        "tool": { "name": "buildfix" },
+        "repo": { "root": "." },
+        "policy": {},
+        "ops": [{
+            "id": "op-1",
+            "safety": "safe",
+            "blocked": true,
+            "blocked_reason": "denied by policy",
+            "target": { "path": "Cargo.toml" },
+            "kind": { "type": "toml_remove", "toml_path": ["workspace", "resolver"] },
+            "rationale": { "fix_key": "resolver", "findings": [] }
+        }],
+        "summary": { "ops_total": 1, "ops_blocked": 1, "files_touched": 1 }
+    });
+
+    let migrated = upgrade_plan(legacy).expect("upgrade");
+    assert_eq!(migrated.value.schema, BUILDFIX_PLAN_V1);
+    assert!(migrated.value.preconditions.files.is_empty());
+    assert_eq!(migrated.value.ops.len(), 1);
+    assert_eq!(migrated.report.from, None);
+    assert_eq!(
+        migrated.report.notes.len(),
+        5,
+        "{:?}",
+        migrated.report.notes
+    );
+
+    // Without a tool version the plan cannot be written back as wire format.
+    assert!(matches!(
+        downgrade_plan(&migrated.value, BUILDFIX_PLAN_V1),
+        Err(MigrateError::Wire(_))
+    ));
+}
+
+#[test]
+fn unknown_schemas_are_rejected_with_the_supported_list() {
+    let err = upgrade_plan(json!({ "schema": "buildfix.plan.v9" })).expect_err("unsupported");
+    assert!(matches!(err, MigrateError::UnsupportedSchema(_)));
+    for schema in PLAN_SCHEMAS {
+        assert!(err.to_string().contains(schema));
+    }
+    assert!(matches!(
+        downgrade_plan(&plan(), "buildfix.apply.v1"),
+        Err(MigrateError::UnsupportedSchema(_))
+    ));
+}
+
+#[test]
+fn plans_needing_unknown_op_kinds_or_transforms_are_refused() {
+    let mut value = downgrade_plan(&plan(), BUILDFIX_PLAN_V1).expect("downgrade");
+    value["capabilities"] = json!({ "op_kinds": ["toml_set", "xml_set"] });
+    value["ops"] = json!([{
+        "id": "op-1",
+        "safety": "safe",
+        "blocked": false,
+        "target": { "path": "Cargo.toml" },
+        "kind": { "type": "toml_transform", "rule_id": "sort_dependencies" },
+        "rationale": { "fix_key": "sort", "findings": [] }
+    }]);
+
+    let err = upgrade_plan(value.clone()).expect_err("unsupported");
+    let MigrateError::UnsupportedOps(missing) = &err else {
+        panic!("unexpected error: {}", err);
+    };
+    assert_eq!(
+        missing,
+        &["op kind `xml_set`", "transform `sort_dependencies`"]
+    );
+
+    value["capabilities"] = json!({ "op_kinds": ["toml_transform"] });
+    value["ops"][0]["kind"]["rule_id"] = json!("set_package_license");
+    upgrade_plan(value).expect("supported");
+}
+
+#[test]
+fn v2_plans_are_checked_per_group() {
+    let mut value = downgrade_plan(&plan(), BUILDFIX_PLAN_V2).expect("downgrade");
+    value["groups"] = json!([{
+        "fix_key": "x",
+        "ops": [{ "kind": { "type": "ini_set" } }]
+    }]);
+    let err = upgrade_plan(value).expect_err("unsupported");
+    assert!(err.to_string().contains("op kind `ini_set`"), "{}", err);
+}
@@ -0,0 +1,199 @@
+#![cfg(feature = "proto")]
+
+use buildfix_types::wire::proto::{
+    self, ApplyResultProto, ProtoError, decode_apply, decode_plan, decode_report, encode_apply,
+    encode_plan, encode_report,
+};
+use buildfix_types::wire::{ApplyV1, PlanV1, ReportV1};
+use prost::Message;
+use serde_json::{Value, json};
+
+fn plan() -> PlanV1 {
+    let op = |id: &str, path: &str| {
+        json!({
+            "id": id,
+            "safety": "guarded",
+            "blocked": true,
+            "blocked_reason": "guarded ops not allowed",
+            "blocked_reason_token": "safety_guarded_not_allowed",
+            "target": { "path": path, "sha256": "abc" },
+            "kind": {
+                "type": "toml_transform",
+                "rule_id": "ensure_path_dep_has_version",
+                "args": { "dep": "b", "version": "0.2.0" }
+            },
+            "rationale": {
+                "fix_key": "depguard/deps.path_requires_version/missing_version",
+                "description": "Adds version field to path dependencies",
+                "findings": [{
+                    "source": "depguard",
+                    "check_id": "deps.path_requires_version",
+                    "code": "missing_version",
+                    "path": path,
+                    "line": 7,
+                    "severity": "error",
+                    "fingerprint": "99cf83ed063a1142c837a5ec9ecd0dd1723a19538a4cc54248a53d78e7b6f699"
+                }]
+            },
+            "params_required": ["version"],
+            "preview": { "patch_fragment": "+version = \"0.2.0\"" },
+            "impact": {
+                "lines_changed": 1,
+                "affected_crates": ["a"],
+                "lockfile_update": true,
+                "semver_visible": true
+            },
+            "workspace": "crates"
+        })
+    };
+    serde_json::from_value(json!({
+        "schema": "buildfix.plan.v1",
+        "tool": { "name": "buildfix", "version": "1.0.0", "commit": "abc" },
+        "repo": { "root": "/repo", "head_sha": "deadbeef", "dirty": false },
+        "inputs": [{
+            "path": "artifacts/depguard/report.json",
+            "schema": "depguard.report.v1",
+            "tool": "depguard",
+            "sha256": "0123"
+        }],
+        "policy": { "allow": ["depguard/*"], "max_ops": 50 },
+        "preconditions": {
+            "files": [{ "path": "crates/a/Cargo.toml", "sha256": "abc" }],
+            "head_sha": "deadbeef"
+        },
+        "ops": (0..20)
+            .map(|i| op(&format!("op-{}", i), &format!("crates/c{}/Cargo.toml", i)))
+            .collect::<Vec<_>>(),
+        "summary": {
+            "ops_total": 20,
+            "ops_blocked": 20,
+            "files_touched": 20,
+            "patch_bytes": 400,
+            "safety_counts": { "safe": 0, "guarded": 20, "unsafe": 0 }
+        },
+        "expires_at": "2025-01-02T00:00:00+00:00",
+        "workspaces": [{
+            "root": "crates",
+            "summary": { "ops_total": 20, "ops_blocked": 20, "files_touched": 20 }
+        }]
+    }))
+    .expect("plan")
+}
+
+fn apply() -> ApplyV1 {
+    serde_json::from_value(json!({
+        "schema": "buildfix.apply.v1",
+        "tool": { "name": "buildfix", "version": "1.0.0" },
+        "repo": { "root": "/repo", "head_sha_before": "deadbeef", "dirty_before": false },
+        "plan_ref": { "path": "artifacts/buildfix/plan.json", "sha256": "feed" },
+        "preconditions": {
+            "verified": false,
+            "mismatches": [{ "path": "Cargo.toml", "expected": "a", "actual": "b" }]
+        },
+        "results": [
+            {
+                "op_id": "op-0",
+                "status": "applied",
+                "files": [{
+                    "path": "Cargo.toml",
+                    "sha256_before": "a",
+                    "sha256_after": "b",
+                    "backup_path": "Cargo.toml.bak"
+                }]
+            },
+            {
+                "op_id": "op-1",
+                "status": "blocked",
+                "message": "precondition mismatch",
+                "blocked_reason": "file changed",
+                "blocked_reason_token": "target_drifted"
+            }
+        ],
+        "summary": { "attempted": 2, "applied": 1, "blocked": 1, "failed": 0, "files_modified": 1 },
+        "auto_commit": { "enabled": true, "attempted": false, "committed": false },
+        "errors": ["precondition mismatch"]
+    }))
+    .expect("apply")
+}
+
+fn report() -> ReportV1 {
+    serde_json::from_value(json!({
+        "schema": "sensor.report.v1",
+        "tool": { "name": "buildfix", "version": "1.0.0" },
+        "run": { "started_at": "2025-01-01T00:00:00Z", "duration_ms": 12 },
+        "verdict": {
+            "status": "warn",
+            "counts": { "info": 1, "warn": 1, "error": 0 },
+            "reasons": ["has_unsafe"]
+        },
+        "findings": [{
+            "severity": "warn",
+            "check_id": "buildfix.plan.blocked",
+            "code": "safety_unsafe_not_allowed",
+            "message": "1 op blocked",
+            "location": { "path": "Cargo.toml", "line": 3 },
+            "fingerprint": "f00d",
+            "data": { "op_id": "op-1" }
+        }],
+        "capabilities": { "inputs_available": ["artifacts/depguard/report.json"] },
+        "artifacts": { "plan": "plan.json" },
+        "data": { "buildfix": { "ops_total": 2 } }
+    }))
+    .expect("report")
+}
+
+fn to_value<T: serde::Serialize>(value: &T) -> Value {
+    serde_json::to_value(value).expect("serialize")
+}
+
+#[test]
+fn plan_roundtrips_through_protobuf() {
+    let plan = plan();
+    let bytes = encode_plan(&plan);
+    let decoded = decode_plan(&bytes).expect("decode");
+    assert_eq!(to_value(&decoded), to_value(&plan));
+
+    let json = serde_json::to_vec(&plan).expect("json");
+    assert!(
+        bytes.len() < json.len(),
+        "protobuf {} bytes, json {} bytes",
+        bytes.len(),
+        json.len()
+    );
+}
+
+#[test]
+fn apply_roundtrips_through_protobuf() {
+    let apply = apply();
+    let decoded = decode_apply(&encode_apply(&apply)).expect("decode");
+    assert_eq!(to_value(&decoded), to_value(&apply));
+}
+
+#[test]
+fn report_roundtrips_through_protobuf() {
+    let report = report();
+    let decoded = decode_report(&encode_report(&report)).expect("decode");
+    assert_eq!(to_value(&decoded), to_value(&report));
+}
+
+#[test]
+fn decode_rejects_unknown_tokens_and_garbage() {
+    let mut message = proto::ApplyProto::from(&apply());
+    message.results.push(ApplyResultProto {
+        op_id: "op-2".to_string(),
+        status: 2,
+        blocked_reason_token: Some("not_a_token".to_string()),
+        ..Default::default()
+    });
+    let err = decode_apply(&message.encode_to_vec()).expect_err("unknown token");
+    assert!(matches!(
+        err,
+        ProtoError::Invalid {
+            field: "blocked_reason_token",
+            ..
+        }
+    ));
+
+    let err = decode_plan(&[0xff, 0xff, 0xff]).expect_err("garbage");
+    assert!(matches!(err, ProtoError::Decode(_)));
+}
@@ -1,12 +1,12 @@
 use buildfix_types::apply::{ApplyRepoInfo, ApplyStatus, BuildfixApply, PlanRef};
-use buildfix_types::ops::{OpKind, OpTarget};
+use buildfix_types::ops::{InsertHint, InsertPosition, OpKind, OpTarget};
 use buildfix_types::plan::{BuildfixPlan, PlanPolicy, RepoInfo};
 use buildfix_types::receipt::{
     Finding, Location, ReceiptCapabilities, ReceiptEnvelope, RunInfo, ToolInfo,
 };
 use buildfix_types::report::{
     BuildfixReport, ReportCapabilities, ReportCounts, ReportFinding, ReportLocation, ReportRunInfo,
-    ReportSeverity, ReportStatus, ReportToolInfo, ReportVerdict,
+    ReportSeverity, ReportStatus, ReportToolInfo, ReportVerdict, fix_key_check_id,
 };
 use camino::Utf8PathBuf;
 
@@ -38,10 +38,14 @@ fn buildfix_apply_omits_empty_errors() {
             head_sha_after: None,
             dirty_before: None,
             dirty_after: None,
+            dirty_changes: None,
+            remote_url: None,
+            ci: None,
         },
         PlanRef {
             path: "plan.json".to_string(),
             sha256: None,
+            compat: None,
         },
     );
 
@@ -67,6 +71,22 @@ fn report_status_and_severity_serialize_snake_case() {
     assert_eq!(error, serde_json::json!("error"));
 }
 
+#[test]
+fn fix_key_check_ids_fit_the_report_pattern() {
+    assert_eq!(
+        fix_key_check_id("builddiag/workspace.resolver_v2/not_v2"),
+        "resolver_v2"
+    );
+    assert_eq!(fix_key_check_id("depguard/-/X"), "depguard___x");
+    assert_eq!(fix_key_check_id("edition"), "edition");
+    assert_eq!(
+        fix_key_check_id("clippy/clippy::Redundant-Clone/w"),
+        "clippy__redundant_clone"
+    );
+    assert_eq!(fix_key_check_id("rustc/2018.E0001/e"), "e0001");
+    assert_eq!(fix_key_check_id("x/y.1abc/z"), "fix_1abc");
+}
+
 #[test]
 fn report_omits_optional_sections_when_none() {
     let report = BuildfixReport {
@@ -258,6 +278,67 @@ fn opkind_serializes_remove_and_transform() {
         yaml_remove_value["yaml_path"],
         serde_json::json!(["tool", "name"])
     );
+
+    let append: OpKind = serde_json::from_value(serde_json::json!({
+        "type": "toml_array_append",
+        "toml_path": ["workspace", "members"],
+        "values": ["crates/c"]
+    }))
+    .expect("deserialize toml_array_append");
+    match &append {
+        OpKind::TomlArrayAppend { values, unique, .. } => {
+            assert_eq!(values, &vec![serde_json::json!("crates/c")]);
+            assert!(!unique, "unique defaults to false");
+        }
+        other => panic!("unexpected kind: {other:?}"),
+    }
+
+    let merge = OpKind::TomlMerge {
+        toml_path: vec!["dependencies".to_string(), "serde".to_string()],
+        table: serde_json::Map::from_iter([(
+            "features".to_string(),
+            serde_json::json!(["derive"]),
+        )]),
+    };
+    let merge_value = serde_json::to_value(&merge).expect("serialize toml_merge");
+    assert_eq!(merge_value["type"], "toml_merge");
+    assert_eq!(
+        merge_value["table"],
+        serde_json::json!({"features": ["derive"]})
+    );
+}
+
+#[test]
+fn toml_set_insert_hint_is_optional_and_roundtrips() {
+    let plain = OpKind::TomlSet {
+        toml_path: vec!["workspace".to_string(), "resolver".to_string()],
+        value: serde_json::json!("2"),
+        insert: None,
+    };
+    let plain_value = serde_json::to_value(&plain).expect("serialize toml_set");
+    assert!(plain_value.get("insert").is_none());
+
+    let hinted = OpKind::TomlSet {
+        toml_path: vec!["workspace".to_string(), "resolver".to_string()],
+        value: serde_json::json!("2"),
+        insert: Some(InsertHint {
+            position: Some(InsertPosition::After("members".to_string())),
+            ..InsertHint::attributed("cargo.workspace_resolver_v2")
+        }),
+    };
+    let hinted_value = serde_json::to_value(&hinted).expect("serialize hinted toml_set");
+    assert_eq!(
+        hinted_value["insert"],
+        serde_json::json!({
+            "position": {"after": "members"},
+            "comment": "added by buildfix (cargo.workspace_resolver_v2)"
+        })
+    );
+
+    let top: InsertHint =
+        serde_json::from_value(serde_json::json!({"position": "top"})).expect("top");
+    assert_eq!(top.position, Some(InsertPosition::Top));
+    assert_eq!(top.comment, None);
 }
 
 #[test]
@@ -273,6 +354,8 @@ fn plan_op_with_transform_roundtrip() {
             root: "/repo".to_string(),
             head_sha: None,
             dirty: None,
+            remote_url: None,
+            ci: None,
         },
         PlanPolicy::default(),
     );
@@ -285,6 +368,7 @@ fn plan_op_with_transform_roundtrip() {
         blocked_reason_token: None,
         target: OpTarget {
             path: "Cargo.toml".to_string(),
+            sha256: None,
         },
         kind: OpKind::TomlTransform {
             rule_id: "set_package_edition".to_string(),
@@ -297,6 +381,8 @@ fn plan_op_with_transform_roundtrip() {
         },
         params_required: vec![],
         preview: None,
+        impact: None,
+        workspace: None,
     };
 
     let mut plan = plan;
@@ -306,3 +392,35 @@ fn plan_op_with_transform_roundtrip() {
     let value = serde_json::to_value(&plan).expect("serialize plan");
     assert_eq!(value["ops"][0]["kind"]["rule_id"], "set_package_edition");
 }
+
+#[test]
+fn opkind_type_names_match_serialized_tags() {
+    let kinds: Vec<OpKind> = OpKind::TYPES
+        .iter()
+        .map(|ty| {
+            let value = match *ty {
+                "toml_set" | "json_set" | "yaml_set" => serde_json::json!({
+                    "type": ty, "toml_path": ["a"], "json_path": ["a"], "yaml_path": ["a"],
+                    "value": 1
+                }),
+                "toml_array_append" => {
+                    serde_json::json!({ "type": ty, "toml_path": ["a"], "values": [] })
+                }
+                "toml_merge" => serde_json::json!({ "type": ty, "toml_path": ["a"], "table": {} }),
+                "toml_transform" => serde_json::json!({ "type": ty, "rule_id": "r" }),
+                "text_replace_anchored" => {
+                    serde_json::json!({ "type": ty, "find": "a", "replace": "b" })
+                }
+                _ => serde_json::json!({
+                    "type": ty, "toml_path": ["a"], "json_path": ["a"], "yaml_path": ["a"]
+                }),
+            };
+            serde_json::from_value(value).expect(ty)
+        })
+        .collect();
+
+    for (kind, ty) in kinds.iter().zip(OpKind::TYPES) {
+        assert_eq!(kind.type_name(), *ty);
+        assert_eq!(serde_json::to_value(kind).expect("serialize")["type"], *ty);
+    }
+}
@@ -0,0 +1,35 @@
+use buildfix_types::telemetry;
+use std::collections::BTreeSet;
+
+#[test]
+fn event_ids_are_unique_and_dotted_snake_case() {
+    let mut seen = BTreeSet::new();
+    for id in telemetry::ALL {
+        assert!(seen.insert(id), "duplicate event id {id}");
+        let (area, name) = id.split_once('.').expect("area.name");
+        for part in [area, name] {
+            assert!(
+                !part.is_empty()
+                    && part
+                        .chars()
+                        .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '_'),
+                "{id} is not dotted snake case"
+            );
+        }
+    }
+}
+
+#[test]
+fn every_event_id_is_documented() {
+    let docs = std::fs::read_to_string(concat!(
+        env!("CARGO_MANIFEST_DIR"),
+        "/../docs/reference/telemetry.md"
+    ))
+    .expect("read docs/reference/telemetry.md");
+    for id in telemetry::ALL {
+        assert!(
+            docs.contains(&format!("| `{id}` |")),
+            "{id} missing from docs/reference/telemetry.md"
+        );
+    }
+}
@@ -15,6 +15,8 @@ fn buildfix_plan_new_sets_schema_and_defaults() {
         root: "/repo".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     };
     let policy = PlanPolicy {
         allow: vec!["cargo.*".to_string()],
@@ -57,10 +59,14 @@ fn buildfix_apply_new_sets_schema_and_defaults() {
         head_sha_after: None,
         dirty_before: None,
         dirty_after: None,
+        dirty_changes: None,
+        remote_url: None,
+        ci: None,
     };
     let plan_ref = PlanRef {
         path: "artifacts/buildfix/plan.json".to_string(),
         sha256: None,
+        compat: None,
     };
 
     let apply = BuildfixApply::new(tool, repo, plan_ref);
@@ -94,6 +100,7 @@ fn op_kind_serializes_with_type_tag() {
     let op = OpKind::TomlSet {
         toml_path: vec!["workspace".to_string(), "resolver".to_string()],
         value: serde_json::json!("2"),
+        insert: None,
     };
 
     let value = serde_json::to_value(&op).expect("serialize op");
@@ -1,13 +1,17 @@
 use buildfix_types::apply::{
     ApplyPreconditions, ApplyRepoInfo, ApplySummary, BuildfixApply, PlanRef,
 };
-use buildfix_types::plan::{BuildfixPlan, PlanPolicy, PlanPreconditions, PlanSummary, RepoInfo};
-use buildfix_types::receipt::ToolInfo;
+use buildfix_types::ops::{OpKind, OpTarget, SafetyClass};
+use buildfix_types::plan::{
+    BuildfixPlan, FindingRef, OpImpact, PlanOp, PlanPolicy, PlanPreconditions, PlanSummary,
+    PlanWorkspace, Rationale, RepoInfo,
+};
+use buildfix_types::receipt::{Severity, ToolInfo};
 use buildfix_types::report::{
     BuildfixReport, ReportCounts, ReportRunInfo, ReportSeverity, ReportStatus, ReportToolInfo,
     ReportVerdict,
 };
-use buildfix_types::wire::{ApplyV1, PlanV1, ReportV1, WireError};
+use buildfix_types::wire::{ApplyV1, PlanV1, PlanV2, ReportV1, WireError};
 
 #[test]
 fn plan_wire_requires_tool_version() {
@@ -21,6 +25,8 @@ fn plan_wire_requires_tool_version() {
         root: "/repo".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     };
     let policy = PlanPolicy::default();
     let plan = BuildfixPlan::new(tool, repo, policy);
@@ -44,6 +50,8 @@ fn plan_wire_roundtrip_preserves_tool_version() {
         root: "/repo".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     };
     let policy = PlanPolicy::default();
     let plan = BuildfixPlan {
@@ -55,6 +63,9 @@ fn plan_wire_roundtrip_preserves_tool_version() {
         preconditions: PlanPreconditions::default(),
         ops: vec![],
         summary: PlanSummary::default(),
+        capabilities: None,
+        expires_at: None,
+        workspaces: vec![],
     };
 
     let wire = PlanV1::try_from(&plan).expect("wire conversion");
@@ -79,10 +90,14 @@ fn apply_wire_requires_tool_version() {
         head_sha_after: None,
         dirty_before: None,
         dirty_after: None,
+        dirty_changes: None,
+        remote_url: None,
+        ci: None,
     };
     let plan_ref = PlanRef {
         path: "artifacts/buildfix/plan.json".to_string(),
         sha256: None,
+        compat: None,
     };
     let apply = BuildfixApply {
         schema: buildfix_types::schema::BUILDFIX_APPLY_V1.to_string(),
@@ -93,6 +108,7 @@ fn apply_wire_requires_tool_version() {
         results: vec![],
         summary: ApplySummary::default(),
         auto_commit: None,
+        branch: None,
         errors: vec![],
     };
 
@@ -117,10 +133,14 @@ fn apply_wire_roundtrip_preserves_tool_version() {
         head_sha_after: None,
         dirty_before: None,
         dirty_after: None,
+        dirty_changes: None,
+        remote_url: None,
+        ci: None,
     };
     let plan_ref = PlanRef {
         path: "artifacts/buildfix/plan.json".to_string(),
         sha256: Some("deadbeef".to_string()),
+        compat: None,
     };
     let apply = BuildfixApply {
         schema: buildfix_types::schema::BUILDFIX_APPLY_V1.to_string(),
@@ -131,6 +151,7 @@ fn apply_wire_roundtrip_preserves_tool_version() {
         results: vec![],
         summary: ApplySummary::default(),
         auto_commit: None,
+        branch: None,
         errors: vec![],
     };
 
@@ -182,3 +203,126 @@ fn report_wire_from_buildfix_report() {
     assert_eq!(wire.tool.commit.as_deref(), Some("abc"));
     assert_eq!(wire.findings.len(), 1);
 }
+
+fn plan_op(id: &str, fix_key: &str, path: &str, severity: Option<Severity>) -> PlanOp {
+    PlanOp {
+        id: id.to_string(),
+        safety: SafetyClass::Safe,
+        blocked: false,
+        blocked_reason: None,
+        blocked_reason_token: None,
+        target: OpTarget {
+            path: path.to_string(),
+            sha256: None,
+        },
+        kind: OpKind::TomlArrayAppend {
+            toml_path: vec!["workspace".to_string(), "members".to_string()],
+            values: vec![serde_json::json!("a"), serde_json::json!("b")],
+            unique: true,
+        },
+        rationale: Rationale {
+            fix_key: fix_key.to_string(),
+            description: None,
+            findings: vec![FindingRef {
+                source: "builddiag".to_string(),
+                check_id: None,
+                code: "x".to_string(),
+                path: None,
+                line: None,
+                fingerprint: None,
+                severity,
+            }],
+        },
+        params_required: vec![],
+        preview: None,
+        impact: Some(OpImpact {
+            lines_changed: 2,
+            affected_crates: path
+                .strip_suffix("/Cargo.toml")
+                .map(|dir| vec![dir.to_string()])
+                .unwrap_or_default(),
+            lockfile_update: false,
+            semver_visible: false,
+        }),
+        workspace: None,
+    }
+}
+
+#[test]
+fn plan_v2_groups_ops_and_roundtrips_through_v1() {
+    let tool = ToolInfo {
+        name: "buildfix".to_string(),
+        version: Some("1.0.0".to_string()),
+        repo: None,
+        commit: None,
+    };
+    let repo = RepoInfo {
+        root: "/repo".to_string(),
+        head_sha: None,
+        dirty: None,
+        remote_url: None,
+        ci: None,
+    };
+    let mut plan = BuildfixPlan::new(tool, repo, PlanPolicy::default());
+    let mut unsafe_op = plan_op("3", "msrv", "a/Cargo.toml", None);
+    unsafe_op.params_required = vec!["rust_version".to_string(), "custom".to_string()];
+    plan.ops = vec![
+        plan_op("1", "members", "Cargo.toml", Some(Severity::Warn)),
+        plan_op("2", "members", "a/Cargo.toml", Some(Severity::Error)),
+        unsafe_op,
+        plan_op("4", "resolver", "Cargo.toml", Some(Severity::Info)),
+    ];
+    plan.ops[1].workspace = Some("a".to_string());
+    plan.workspaces = vec![PlanWorkspace {
+        root: "a".to_string(),
+        summary: PlanSummary {
+            ops_total: 1,
+            ..PlanSummary::default()
+        },
+    }];
+
+    let v2 = PlanV2::try_from(&plan).expect("wire conversion");
+    assert_eq!(v2.schema, buildfix_types::schema::BUILDFIX_PLAN_V2);
+    let keys: Vec<&str> = v2.groups.iter().map(|g| g.fix_key.as_str()).collect();
+    assert_eq!(keys, ["members", "msrv", "resolver"]);
+
+    let ops: Vec<_> = v2.groups.iter().flat_map(|g| &g.ops).collect();
+    let depends_on: Vec<&[String]> = ops.iter().map(|o| o.depends_on.as_slice()).collect();
+    assert_eq!(
+        depends_on,
+        [
+            &[][..],
+            &[][..],
+            &["2".to_string()][..],
+            &["1".to_string()][..]
+        ]
+    );
+    let severities: Vec<_> = ops.iter().map(|o| o.severity).collect();
+    assert_eq!(
+        severities,
+        [
+            Some(Severity::Warn),
+            Some(Severity::Error),
+            None,
+            Some(Severity::Info)
+        ]
+    );
+    assert_eq!(ops[0].impact.lines_changed, 2);
+    assert_eq!(ops[1].impact.affected_crates, ["a"]);
+    assert_eq!(ops[2].params[0].name, "rust_version");
+    assert!(ops[2].params[0].description.is_some());
+    assert_eq!(ops[2].params[1].description, None);
+    assert_eq!(ops[1].workspace.as_deref(), Some("a"));
+    assert_eq!(v2.workspaces[0].root, "a");
+
+    let v1 = PlanV1::from(v2.clone());
+    assert_eq!(v1.schema, buildfix_types::schema::BUILDFIX_PLAN_V1);
+    assert_eq!(
+        serde_json::to_value(&v1).expect("v1 json"),
+        serde_json::to_value(PlanV1::try_from(&plan).expect("wire")).expect("v1 json")
+    );
+    assert_eq!(
+        serde_json::to_value(PlanV2::from(v1)).expect("v2 json"),
+        serde_json::to_value(&v2).expect("v2 json")
+    );
+}
@@ -189,6 +189,8 @@ fn build_plan(sp: &StructuredPlan, policy: &PolicyConfig) -> BuildfixPlan {
         root: ".".to_string(),
         head_sha: None,
         dirty: None,
+        remote_url: None,
+        ci: None,
     };
 
     let tool = ToolInfo {
@@ -216,6 +218,7 @@ fn build_plan(sp: &StructuredPlan, policy: &PolicyConfig) -> BuildfixPlan {
             blocked_reason_token: None,
             target: OpTarget {
                 path: sp.target_path.clone(),
+                sha256: None,
             },
             kind: OpKind::TomlTransform {
                 rule_id: sp.rule_id.clone(),
@@ -32,6 +32,10 @@ enum Command {
         /// Directory containing golden files for determinism check.
         #[arg(long)]
         golden_dir: Option<String>,
+        /// Compare against the golden files without stripping run
+        /// timestamps, for artifacts written with `SOURCE_DATE_EPOCH` set.
+        #[arg(long)]
+        exact: bool,
         /// Directory containing cockpit contract schemas.
         /// Falls back to the embedded constant when omitted.
         #[arg(long, env = "COCKPIT_CONTRACTS_DIR")]
@@ -80,11 +84,13 @@ fn run(cli: Cli) -> anyhow::Result<()> {
         Command::Conform {
             artifacts_dir,
             golden_dir,
+            exact,
             contracts_dir,
         } => {
             cmd_conform(
                 &artifacts_dir,
                 golden_dir.as_deref(),
+                exact,
                 contracts_dir.as_deref(),
             )?;
         }
@@ -104,6 +110,7 @@ const SENSOR_REPORT_V1_SCHEMA: &str =
 fn cmd_conform(
     artifacts_dir: &str,
     golden_dir: Option<&str>,
+    exact: bool,
     contracts_dir: Option<&str>,
 ) -> anyhow::Result<()> {
     let mut failures: Vec<String> = Vec::new();
@@ -160,7 +167,7 @@ fn cmd_conform(
 
     // 3. Determinism check - compare against golden files (if provided)
     if let Some(golden) = golden_dir {
-        match check_determinism(artifacts_dir, golden) {
+        match check_determinism(artifacts_dir, golden, exact) {
             Ok(()) => println!("[PASS] Determinism check"),
             Err(errors) => {
                 println!("[FAIL] Determinism check");
@@ -254,7 +261,11 @@ fn check_required_fields(file_path: &str) -> Result<(), Vec<String>> {
     }
 }
 
-fn check_determinism(artifacts_dir: &str, golden_dir: &str) -> Result<(), Vec<String>> {
+fn check_determinism(
+    artifacts_dir: &str,
+    golden_dir: &str,
+    exact: bool,
+) -> Result<(), Vec<String>> {
     let mut errors = Vec::new();
 
     for file in ["plan.json", "apply.json", "report.json"] {
@@ -273,8 +284,15 @@ fn check_determinism(artifacts_dir: &str, golden_dir: &str) -> Result<(), Vec<St
         let golden = fs::read_to_string(&golden_path).map_err(|e| vec![e.to_string()])?;
 
         // Parse and re-serialize to normalize, stripping volatile fields
-        let actual_normalized = normalize_for_determinism(&actual)?;
-        let golden_normalized = normalize_for_determinism(&golden)?;
+        // unless the run pinned them.
+        let (actual_normalized, golden_normalized) = if exact {
+            (actual, golden)
+        } else {
+            (
+                normalize_for_determinism(&actual)?,
+                normalize_for_determinism(&golden)?,
+            )
+        };
 
         if actual_normalized != golden_normalized {
             errors.push(format!("{} differs from golden", file));
@@ -300,6 +318,14 @@ fn normalize_for_determinism(json_str: &str) -> Result<String, Vec<String>> {
             run.remove("ended_at");
             run.remove("duration_ms");
         }
+        // Strip data.buildfix.phases_ms
+        if let Some(buildfix) = obj
+            .get_mut("data")
+            .and_then(|d| d.get_mut("buildfix"))
+            .and_then(|b| b.as_object_mut())
+        {
+            buildfix.remove("phases_ms");
+        }
     }
 
     serde_json::to_string_pretty(&json).map_err(|e| vec![e.to_string()])
@@ -346,7 +372,8 @@ mod tests {
             "schema": "buildfix.report.v1",
             "tool": { "name": "buildfix", "version": "1.0" },
             "run": { "started_at": "t0", "ended_at": "t1", "duration_ms": 5 },
-            "verdict": { "status": "pass", "counts": { "info": 0, "warn": 0, "error": 0 } }
+            "verdict": { "status": "pass", "counts": { "info": 0, "warn": 0, "error": 0 } },
+            "data": { "buildfix": { "phases_ms": { "plan": 3 }, "plan": { "ops_total": 1 } } }
         })
         .to_string();
 
@@ -356,6 +383,8 @@ mod tests {
         assert!(run.get("started_at").is_none());
         assert!(run.get("ended_at").is_none());
         assert!(run.get("duration_ms").is_none());
+        assert!(value["data"]["buildfix"].get("phases_ms").is_none());
+        assert_eq!(value["data"]["buildfix"]["plan"]["ops_total"], 1);
     }
 
     #[test]
@@ -514,10 +543,38 @@ mod tests {
         fs::write(artifacts.join("report.json"), actual).expect("write actual");
         fs::write(golden.join("report.json"), golden_content).expect("write golden");
 
-        check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap())
+        check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap(), false)
             .expect("determinism");
     }
 
+    #[test]
+    fn check_determinism_exact_keeps_run_fields() {
+        let temp = TempDir::new().expect("temp dir");
+        let artifacts = temp.path().join("artifacts");
+        let golden = temp.path().join("golden");
+        fs::create_dir_all(&artifacts).expect("artifacts");
+        fs::create_dir_all(&golden).expect("golden");
+
+        let report = |at: &str| {
+            format!(r#"{{ "schema": "buildfix.report.v1", "run": {{ "started_at": "{at}" }} }}"#)
+        };
+        fs::write(
+            artifacts.join("report.json"),
+            report("2020-01-01T00:00:00Z"),
+        )
+        .expect("write");
+        fs::write(golden.join("report.json"), report("2020-01-01T00:00:00Z")).expect("write");
+        check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap(), true)
+            .expect("identical");
+
+        fs::write(golden.join("report.json"), report("2021-01-01T00:00:00Z")).expect("write");
+        let errors = check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap(), true)
+            .expect_err("timestamps differ");
+        assert!(errors.iter().any(|e| e.contains("differs from golden")));
+        check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap(), false)
+            .expect("stripped");
+    }
+
     #[test]
     fn check_determinism_reports_missing_golden() {
         let temp = TempDir::new().expect("temp dir");
@@ -537,8 +594,9 @@ mod tests {
         )
         .expect("write actual");
 
-        let errors = check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap())
-            .expect_err("missing golden");
+        let errors =
+            check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap(), false)
+                .expect_err("missing golden");
         assert!(errors.iter().any(|e| e.contains("golden file missing")));
     }
 
@@ -572,8 +630,9 @@ mod tests {
         )
         .expect("write golden");
 
-        let errors = check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap())
-            .expect_err("mismatch");
+        let errors =
+            check_determinism(artifacts.to_str().unwrap(), golden.to_str().unwrap(), false)
+                .expect_err("mismatch");
         assert!(errors.iter().any(|e| e.contains("differs from golden")));
     }
 
@@ -594,7 +653,7 @@ mod tests {
         )
         .expect("write report");
 
-        cmd_conform(artifacts.to_str().unwrap(), None, None).expect("conform");
+        cmd_conform(artifacts.to_str().unwrap(), None, false, None).expect("conform");
     }
 
     #[test]
@@ -603,7 +662,7 @@ mod tests {
         let artifacts = temp.path().join("artifacts");
         fs::create_dir_all(&artifacts).expect("artifacts");
 
-        cmd_conform(artifacts.to_str().unwrap(), None, None).expect("conform");
+        cmd_conform(artifacts.to_str().unwrap(), None, false, None).expect("conform");
     }
 
     #[test]
@@ -639,6 +698,7 @@ mod tests {
         let err = cmd_conform(
             artifacts.to_str().unwrap(),
             Some(golden.to_str().unwrap()),
+            false,
             None,
         )
         .expect_err("determinism failure");
@@ -682,6 +742,7 @@ mod tests {
         cmd_conform(
             artifacts.to_str().unwrap(),
             None,
+            false,
             Some(contracts.to_str().unwrap()),
         )
         .expect("conform");
@@ -694,7 +755,7 @@ mod tests {
         fs::create_dir_all(&artifacts).expect("artifacts");
         fs::write(artifacts.join("report.json"), "{}").expect("write report");
 
-        let err = cmd_conform(artifacts.to_str().unwrap(), None, None).expect_err("fail");
+        let err = cmd_conform(artifacts.to_str().unwrap(), None, false, None).expect_err("fail");
         assert!(err.to_string().contains("conformance check failed"));
     }
 
@@ -743,6 +804,7 @@ mod tests {
             cmd: Command::Conform {
                 artifacts_dir: artifacts.to_string_lossy().to_string(),
                 golden_dir: None,
+                exact: false,
                 contracts_dir: None,
             },
         })